@@ -0,0 +1,149 @@
+/*
+ * Brute-Force Exact Maximum Matching - Test Oracle for Small Graphs
+ *
+ * Branch and bound over the edges at the lowest free vertex: match it to
+ * each available neighbor in turn, or leave it exposed. Vertex sets are
+ * u64 bitmasks. Bound: current size + (# available vertices that still
+ * have an available neighbor) / 2.
+ *
+ * Exponential time; intended for n <= ~30 (hard limit 64). Shares no code
+ * with the blossom-based solvers, which is the point of an oracle.
+ *
+ * Rust implementation -- fully deterministic, no hash containers.
+ */
+
+use std::env;
+use std::time::Instant;
+
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+
+pub(crate) const MAX_N: usize = 64;
+
+pub(crate) struct BruteForce {
+    n: usize,
+    adj: Vec<u64>,
+    cur: Vec<(usize, usize)>,
+    best: Vec<(usize, usize)>,
+    pub(crate) nodes: u64,
+}
+
+impl BruteForce {
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        assert!(n <= MAX_N, "brute force supports at most {} vertices", MAX_N);
+        let mut adj = vec![0u64; n];
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                adj[u] |= 1u64 << v;
+                adj[v] |= 1u64 << u;
+            }
+        }
+        BruteForce { n, adj, cur: Vec::new(), best: Vec::new(), nodes: 0 }
+    }
+
+    fn search(&mut self, avail: u64) {
+        self.nodes += 1;
+        if self.best.len() == self.n / 2 { return; }
+
+        let mut live = 0u64;
+        let mut rest = avail;
+        while rest != 0 {
+            let v = rest.trailing_zeros() as usize;
+            rest &= rest - 1;
+            if self.adj[v] & avail != 0 { live |= 1u64 << v; }
+        }
+        if live == 0 {
+            if self.cur.len() > self.best.len() { self.best = self.cur.clone(); }
+            return;
+        }
+        if self.cur.len() + (live.count_ones() as usize) / 2 <= self.best.len() { return; }
+
+        let v = live.trailing_zeros() as usize;
+        let without_v = live & !(1u64 << v);
+        let mut nbrs = self.adj[v] & without_v;
+        while nbrs != 0 {
+            let u = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            self.cur.push((v, u));
+            self.search(without_v & !(1u64 << u));
+            self.cur.pop();
+        }
+        self.search(without_v);
+    }
+
+    pub(crate) fn maximum_matching(&mut self) -> Vec<(usize, usize)> {
+        let all = if self.n == 64 { u64::MAX } else { (1u64 << self.n) - 1 };
+        self.search(all);
+        let mut result = self.best.clone();
+        result.sort_unstable();
+        result
+    }
+}
+
+fn main() {
+    println!("Brute-Force Exact Matching (Oracle) - Rust Implementation");
+    println!("=========================================================\n");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <filename> {} {} {} {}", args[0], matching_io::OUTPUT_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            println!("Graph: {} vertices, {} edges", n, edges.len());
+            if n > MAX_N {
+                eprintln!("Error: brute force supports at most {} vertices", MAX_N);
+                std::process::exit(1);
+            }
+            let start = Instant::now();
+            let mut bf = BruteForce::new(n, &edges);
+            let matching = bf.maximum_matching();
+            let duration = start.elapsed();
+
+            let report = verify::validate_general(n, &edges, &matching);
+            report.print();
+
+            println!("Matching size: {}", matching.len());
+            println!("Search nodes: {}", bf.nodes);
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!("Time: {} ms", duration.as_millis());
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -0,0 +1,186 @@
+/*
+ * Hard instances for the cardinality solvers, one family per weak spot,
+ * for stress tests and for measuring how running time grows with n.
+ *
+ * chains - against a search that restarts from vertex 0 after every
+ *   augmentation (the C++ and Python Edmonds simple): a broom, then
+ *   alternating paths. The broom is a handle q_0 - q_1 - ... - q_{2L-1}
+ *   with k bristles (free vertices on q_0) numbered after it. The root
+ *   loop pairs the handle (q_0, q_1), (q_2, q_3), ... first; from then on
+ *   every bristle is a root whose search walks the whole handle and fails,
+ *   and the bristles stay free to the end. Each path v_0 - ... - v_{2d+1}
+ *   is numbered interior first, so the root loop pairs (v_1, v_2),
+ *   (v_3, v_4), ... and the last augmentation of the path, from v_0, has
+ *   2d + 1 edges. Every one of those augmentations is paid for with k
+ *   failed searches of 2L vertices: about n^3 / 64 steps from the empty
+ *   matching, against one or two forest passes for the Rust solvers.
+ *
+ * nested-blossoms - against petal handling (Micali-Vazirani's DDFS and
+ *   petal walks, blossom lifting in the others): copies of a free vertex
+ *   in a triangle with the pair 1-2, then `depth` pairs, each joined to
+ *   the pair before by two crossing edges, and a free vertex at the end.
+ *   From the starting matching (the pairs) the one augmenting path of a
+ *   copy runs through `depth` blossoms, each nested in the next.
+ *
+ * even-collisions - against the blossom work of Gabow's phases: layered
+ *   copies of one free root, `levels` levels of `width` pairs (o, e) and a
+ *   free sink. The root sees every o of level 1, each e two random o's of
+ *   the next level (and its own column's), and the sink one e of the last
+ *   level. Each e also has about `degree` random neighbors among the e's
+ *   of its own level: from the starting matching (the pairs) those are all
+ *   EVEN at the same distance from the one root, so every such edge is a
+ *   collision that closes a blossom, about width * degree / 2 per level.
+ *
+ * Every instance has a known maximum matching size: the handle's L and
+ * d + 1 per path for chains (the bristles stay free), one pair more than
+ * the starting matching per copy for the other two.
+ *
+ * Included via #[path = "../../common/rust/adversarial.rs"] mod adversarial;
+ * next to rng.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::rng::Rng;
+
+pub const FAMILIES: [&str; 3] = ["chains", "nested-blossoms", "even-collisions"];
+
+/* Shape parameters; None derives them from n as documented on each. */
+#[derive(Default)]
+pub struct GenOptions {
+    pub depth: Option<usize>,  /* chains: d, path length 2d + 1; nested-blossoms: nesting depth */
+    pub levels: Option<usize>, /* even-collisions: levels per copy (default 4) */
+    pub width: Option<usize>,  /* even-collisions: pairs per level (default sqrt(n) / 2) */
+    pub degree: Option<usize>, /* even-collisions: same-level neighbors of an e (default width / 2) */
+}
+
+pub struct Instance {
+    pub n: usize,
+    pub edges: Vec<(usize, usize)>,
+    pub init: Vec<(usize, usize)>, /* starting matching, empty for chains */
+    pub maximum: usize,            /* size of a maximum matching */
+}
+
+fn sqrt(n: usize) -> usize {
+    (n as f64).sqrt() as usize
+}
+
+/* The broom on about 3n/8 vertices, then paths with d = sqrt(n) (by
+ * default) on the rest: k = n/8 bristles on a handle of n/4. */
+pub fn chains(n: usize, depth: Option<usize>) -> Instance {
+    let d = depth.unwrap_or(sqrt(n)).max(1);
+    let handle = (n / 8).max(1) * 2;
+    let bristles = (n / 8).max(1);
+    let mut edges = Vec::new();
+    for q in 0..handle - 1 {
+        edges.push((q, q + 1));
+    }
+    for b in handle..handle + bristles {
+        edges.push((0, b));
+    }
+    let mut next = handle + bristles;
+    let mut maximum = handle / 2;
+    while maximum == handle / 2 || next + 2 * d + 2 <= n {
+        /* v_1 .. v_2d at next .., then v_0 and v_{2d+1} */
+        let (first, v0, last) = (next, next + 2 * d, next + 2 * d + 1);
+        for v in first..v0 - 1 {
+            edges.push((v, v + 1));
+        }
+        edges.push((v0, first));
+        edges.push((v0 - 1, last));
+        next = last + 1;
+        maximum += d + 1;
+    }
+    Instance { n: next, edges, init: Vec::new(), maximum }
+}
+
+/* Copies of depth pairs each, as many as fit in n; depth sqrt(n) by
+ * default. Vertex 0 of a copy is the free root, pair i is (2i+1, 2i+2),
+ * 2 depth + 1 the free end. */
+pub fn nested_blossoms(n: usize, depth: Option<usize>) -> Instance {
+    let k = depth.unwrap_or(sqrt(n)).max(1);
+    let copies = (n / (2 * k + 2)).max(1);
+    let mut edges = Vec::new();
+    let mut init = Vec::new();
+    for c in 0..copies {
+        let o = c * (2 * k + 2);
+        let pairs: Vec<(usize, usize)> = (0..k).map(|i| (o + 2 * i + 1, o + 2 * i + 2)).collect();
+        edges.push((o, o + 1));
+        edges.push((o, o + 2));
+        edges.extend_from_slice(&pairs);
+        for i in 1..k {
+            edges.push((o + 2 * i, o + 2 * i + 1));
+            edges.push((o + 2 * i - 1, o + 2 * i + 2));
+        }
+        edges.push((o + 2 * k - 1, o + 2 * k + 1));
+        init.extend_from_slice(&pairs);
+    }
+    Instance { n: copies * (2 * k + 2), edges, maximum: init.len() + copies, init }
+}
+
+/* Copies of 2 + 2 levels width vertices, as many as fit in n. In a copy
+ * the root is 0, the sink 1, and pair j of level l is (o, o + 1) with
+ * o = 2 + 2 (l width + j). */
+pub fn even_collisions(n: usize, opts: &GenOptions, rng: &mut Rng) -> Instance {
+    let levels = opts.levels.unwrap_or(4).max(1);
+    let width = opts.width.unwrap_or(sqrt(n) / 2).max(2);
+    let degree = opts.degree.unwrap_or(width / 2).min(width - 1);
+    let size = 2 + 2 * levels * width;
+    let copies = (n / size).max(1);
+    let mut edges = Vec::new();
+    let mut init = Vec::new();
+    for c in 0..copies {
+        let base = c * size;
+        let odd = |l: usize, j: usize| base + 2 + 2 * (l * width + j);
+        let even = |l: usize, j: usize| odd(l, j) + 1;
+        for j in 0..width {
+            edges.push((base, odd(0, j)));
+        }
+        for l in 0..levels {
+            for j in 0..width {
+                init.push((odd(l, j), even(l, j)));
+                edges.push((odd(l, j), even(l, j)));
+                if l + 1 < levels {
+                    edges.push((even(l, j), odd(l + 1, j)));
+                    for _ in 0..2 {
+                        edges.push((even(l, j), odd(l + 1, rng.below(width as u64) as usize)));
+                    }
+                }
+                for _ in 0..degree.div_ceil(2) {
+                    /* about degree / 2 edges from each end */
+                    let i = rng.below(width as u64 - 1) as usize;
+                    let i = if i >= j { i + 1 } else { i };
+                    edges.push((even(l, j), even(l, i)));
+                }
+            }
+        }
+        edges.push((even(levels - 1, rng.below(width as u64) as usize), base + 1));
+    }
+    /* the random picks repeat pairs now and then */
+    for e in edges.iter_mut() {
+        *e = (e.0.min(e.1), e.0.max(e.1));
+    }
+    edges.sort_unstable();
+    edges.dedup();
+    Instance { n: copies * size, edges, maximum: init.len() + copies, init }
+}
+
+pub fn generate(family: &str, n: usize, opts: &GenOptions, rng: &mut Rng) -> Result<Instance, String> {
+    match family {
+        "chains" => Ok(chains(n, opts.depth)),
+        "nested-blossoms" => Ok(nested_blossoms(n, opts.depth)),
+        "even-collisions" => Ok(even_collisions(n, opts, rng)),
+        _ => Err(format!("unknown family '{}' (expected one of {})", family, FAMILIES.join(", "))),
+    }
+}
+
+/* Vertex ids through a random permutation, edges and starting pairs
+ * alike, and the edge order shuffled. */
+pub fn shuffle(inst: &mut Instance, rng: &mut Rng) {
+    let mut perm: Vec<usize> = (0..inst.n).collect();
+    rng.shuffle(&mut perm);
+    for e in inst.edges.iter_mut().chain(inst.init.iter_mut()) {
+        *e = (perm[e.0], perm[e.1]);
+    }
+    rng.shuffle(&mut inst.edges);
+}
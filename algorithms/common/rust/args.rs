@@ -0,0 +1,103 @@
+/*
+ * Command-line helpers shared by the Rust implementations.
+ *
+ * Each binary includes this file with
+ *     #[path = "../../common/rust/args.rs"] mod args;
+ * so the suite still builds with a plain `rustc -O <file>.rs`.
+ */
+
+#![allow(dead_code)]
+
+/* Value of `--name value` or `--name=value`, if present. */
+pub fn value_of<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let mut i = 0;
+    while i < args.len() {
+        let a = args[i].as_str();
+        if a == name {
+            return args.get(i + 1).map(|s| s.as_str());
+        }
+        if a.len() > name.len() && a.starts_with(name) && a.as_bytes()[name.len()] == b'=' {
+            return Some(&a[name.len() + 1..]);
+        }
+        i += 1;
+    }
+    None
+}
+
+/* Value of `--name` parsed as a T, `default` when absent; an invalid value
+ * is reported and exits with status 1. For the test and benchmark drivers,
+ * whose options all have defaults. */
+pub fn parse_or_exit<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> T {
+    match value_of(args, name) {
+        None => default,
+        Some(s) => s.parse().unwrap_or_else(|_| {
+            eprintln!("Error: invalid value for {}: {}", name, s);
+            std::process::exit(1);
+        }),
+    }
+}
+
+/* --seed S, for the seeded options such as --greedy-random; 1 when absent. */
+pub fn seed_of(args: &[String]) -> Result<u64, String> {
+    match value_of(args, "--seed") {
+        None => Ok(1),
+        Some(s) => s.parse().map_err(|_| format!("invalid value for --seed: {}", s)),
+    }
+}
+
+/* Passes of the length-3 augmenting initializer: --init-aug3, with
+ * --aug3-passes K (default AUG3_PASSES); 0 when --init-aug3 is absent. */
+pub const AUG3_PASSES: usize = 3;
+pub const AUG3_USAGE: &str = "[--init-aug3 [--aug3-passes K]]";
+
+pub fn aug3_of(args: &[String]) -> Result<usize, String> {
+    let passes = match value_of(args, "--aug3-passes") {
+        None => AUG3_PASSES,
+        Some(s) => match s.parse() {
+            Ok(k) if k > 0 => k,
+            _ => return Err(format!("invalid value for --aug3-passes: {} (expected a positive count)", s)),
+        },
+    };
+    if has_flag(args, "--init-aug3") {
+        Ok(passes)
+    } else if value_of(args, "--aug3-passes").is_some() {
+        Err("--aug3-passes needs --init-aug3".to_string())
+    } else {
+        Ok(0)
+    }
+}
+
+/* --epsilon E, the approximation mode of the phase-structured solvers (MV,
+ * Gabow optimized): augmenting only along paths of at most 2k - 1 edges,
+ * k = ceil(1/E), leaves no augmenting path of that length, and such a
+ * matching has at least k/(k+1) >= 1 - E of the maximum (Hopcroft and
+ * Karp). None when absent; E in (0, 1]. */
+pub const EPSILON_USAGE: &str = "[--epsilon E]";
+
+pub fn epsilon_of(args: &[String]) -> Result<Option<f64>, String> {
+    match value_of(args, "--epsilon") {
+        None => Ok(None),
+        Some(s) => match s.parse::<f64>() {
+            Ok(e) if e > 0.0 && e <= 1.0 => Ok(Some(e)),
+            _ => Err(format!("invalid value for --epsilon: {} (expected a number in (0, 1])", s)),
+        },
+    }
+}
+
+/* The longest augmenting path --epsilon E allows, 2 ceil(1/E) - 1 edges
+ * (1/E a hair low first, so that 1/0.05 is 20 and not 21). */
+pub fn max_path_length(epsilon: f64) -> usize {
+    2 * (1.0 / epsilon - 1e-9).ceil().max(1.0) as usize - 1
+}
+
+/* The fraction of the maximum a matching without augmenting paths of at
+ * most `length` edges is guaranteed, k/(k+1) for length 2k - 1. */
+pub fn approximation_ratio(length: usize) -> f64 {
+    let k = length.div_ceil(2);
+    k as f64 / (k + 1) as f64
+}
+
+/* True if the bare flag `name` is present. */
+pub fn has_flag(args: &[String], name: &str) -> bool {
+    args.iter().any(|a| a == name)
+}
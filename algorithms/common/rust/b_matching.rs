@@ -0,0 +1,184 @@
+/*
+ * Maximum simple b-matching via maximum cardinality matching.
+ *
+ * Given G = (V, E) and a capacity b(v) per vertex, find a largest edge
+ * subset F (each edge at most once) with deg_F(v) <= b(v) for every v.
+ *
+ * Reduction (Tutte 1954, Shiloach 1981): a vertex v whose capacity binds
+ * (b(v) < deg(v)) becomes b(v) copies; every edge e = uv gets a node e_w
+ * for each binding endpoint w, joined to all copies of w, and e_u e_v is
+ * an edge when both endpoints bind. Then
+ *     nu(G') = |E_2| + max |F|,
+ * where E_2 are the edges with two binding endpoints, and e is in F iff
+ * all of its e_w are matched to copies. Edges with no binding endpoint
+ * are always kept and stay out of G'.
+ *
+ * G' has sum(b(v)) + O(m) vertices and O(sum b(v) deg(v)) edges over the
+ * binding vertices.
+ *
+ * Included via #[path = "../../common/rust/b_matching.rs"] mod b_matching;
+ * together with matchers.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+pub const UNLIMITED: usize = usize::MAX;
+
+pub struct BMatching {
+    pub n: usize,
+    pub caps: Vec<usize>,
+    /* distinct edges (u < v), no loops, no out-of-range ids */
+    pub edges: Vec<(usize, usize)>,
+    /* reduced graph G' */
+    pub reduced_n: usize,
+    pub reduced: Vec<(usize, usize)>,
+    /* per edge: reduced ids of its e_u, e_v (None for a non-binding endpoint) */
+    ends: Vec<(Option<usize>, Option<usize>)>,
+    copies: usize,
+    both_binding: usize,
+    pub binding: usize, /* vertices with b(v) < deg(v) */
+}
+
+impl BMatching {
+    /* caps[v] for v < caps.len(); vertices past the end are unlimited. */
+    pub fn new(n: usize, edges: &[(usize, usize)], caps: &[usize]) -> Self {
+        let mut list: Vec<(usize, usize)> = edges.iter()
+            .filter(|&&(u, v)| u < n && v < n && u != v)
+            .map(|&(u, v)| if u < v { (u, v) } else { (v, u) })
+            .collect();
+        list.sort_unstable();
+        list.dedup();
+
+        let mut deg = vec![0usize; n];
+        for &(u, v) in &list {
+            deg[u] += 1;
+            deg[v] += 1;
+        }
+        let cap: Vec<usize> = (0..n).map(|v| caps.get(v).cloned().unwrap_or(UNLIMITED)).collect();
+
+        /* copies of binding vertex v: first[v] .. first[v] + cap[v] */
+        let mut first = vec![0usize; n];
+        let mut next = 0;
+        for v in 0..n {
+            first[v] = next;
+            if cap[v] < deg[v] { next += cap[v]; }
+        }
+        let copies = next;
+
+        let mut ends = Vec::with_capacity(list.len());
+        let mut reduced = Vec::new();
+        let mut both_binding = 0;
+        for &(u, v) in &list {
+            let node = |w: usize, next: &mut usize, reduced: &mut Vec<(usize, usize)>| {
+                if cap[w] >= deg[w] { return None; }
+                let e_w = *next;
+                *next += 1;
+                for c in first[w]..first[w] + cap[w] { reduced.push((e_w, c)); }
+                Some(e_w)
+            };
+            let e_u = node(u, &mut next, &mut reduced);
+            let e_v = node(v, &mut next, &mut reduced);
+            if let (Some(a), Some(b)) = (e_u, e_v) {
+                reduced.push((a, b));
+                both_binding += 1;
+            }
+            ends.push((e_u, e_v));
+        }
+
+        let binding = (0..n).filter(|&v| cap[v] < deg[v]).count();
+        BMatching {
+            n, caps: cap, edges: list, reduced_n: next, reduced, ends, copies, both_binding, binding,
+        }
+    }
+
+    /* Size of the b-matching a maximum matching of G' of this size encodes. */
+    pub fn expected_size(&self, matching_size: usize) -> usize {
+        let free = self.ends.iter().filter(|e| e.0.is_none() && e.1.is_none()).count();
+        matching_size - self.both_binding + free
+    }
+
+    /* Edges of F, sorted, read off a matching of G'. */
+    pub fn extract(&self, matching: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut to_copy = vec![false; self.reduced_n];
+        for &(a, b) in matching {
+            if a < self.copies && b >= self.copies { to_copy[b] = true; }
+            if b < self.copies && a >= self.copies { to_copy[a] = true; }
+        }
+        let kept = |e: Option<usize>| e.is_none_or(|x| to_copy[x]);
+        self.edges.iter().zip(&self.ends)
+            .filter(|&(_, &(a, b))| kept(a) && kept(b))
+            .map(|(&e, _)| e)
+            .collect()
+    }
+}
+
+/* Maximum simple b-matching of bm through the general matcher `algo`.
+ * Returns the kept edges and the size of the reduced matching. */
+pub fn max_b_matching(
+    algo: &str, bm: &BMatching,
+) -> Result<(Vec<(usize, usize)>, usize), String> {
+    let matching = super::matchers::maximum_matching(algo, bm.reduced_n, &bm.reduced)?;
+    Ok((bm.extract(&matching), matching.len()))
+}
+
+/* Problems with `selected` as a b-matching of bm (empty if none). */
+pub fn check_b_matching(bm: &BMatching, selected: &[(usize, usize)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut load = vec![0usize; bm.n];
+    let mut seen: Vec<(usize, usize)> = Vec::with_capacity(selected.len());
+    for &(u, v) in selected {
+        let e = if u < v { (u, v) } else { (v, u) };
+        if bm.edges.binary_search(&e).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+            continue;
+        }
+        seen.push(e);
+        load[u] += 1;
+        load[v] += 1;
+    }
+    seen.sort_unstable();
+    for w in seen.windows(2) {
+        if w[0] == w[1] {
+            errors.push(format!("Edge ({}, {}) selected twice!", w[0].0, w[0].1));
+        }
+    }
+    for (v, (&deg, &cap)) in load.iter().zip(&bm.caps).enumerate() {
+        if deg > cap {
+            errors.push(format!("Vertex {} has degree {} over its capacity {}!", v, deg, cap));
+        }
+    }
+    errors
+}
+
+/* Capacity file: one "v b" pair per line, '#' starts a comment. Vertices
+ * not listed get `default`. */
+pub fn load_capacities(
+    filename: &str, n: usize, default: usize,
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut caps = vec![default; n];
+    let mut listed = vec![false; n];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() { continue; }
+        if parts.len() != 2 {
+            return Err(format!("{}:{}: expected 'vertex capacity', got '{}'",
+                               filename, i + 1, raw.trim()).into());
+        }
+        let v: usize = parts[0].parse()?;
+        let b: usize = parts[1].parse()?;
+        if v >= n {
+            return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, i + 1, v, n).into());
+        }
+        if listed[v] {
+            return Err(format!("{}:{}: vertex {} listed twice", filename, i + 1, v).into());
+        }
+        listed[v] = true;
+        caps[v] = b;
+    }
+    Ok(caps)
+}
@@ -0,0 +1,421 @@
+/*
+ * Block triangular form of a sparse matrix, for `combi btf`: the maximum
+ * transversal and the Dulmage-Mendelsohn permutations, as SuiteSparse's
+ * btf computes them, from a Matrix Market file.
+ *
+ * The matrix is the bipartite graph of its rows and columns, an edge per
+ * stored entry (the values are not read; an explicit zero is an entry).
+ * Hopcroft-Karp matches rows to columns: the maximum transversal, whose
+ * size is the structural rank. Then, by alternating paths:
+ *   - H, horizontal: the columns an alternating path from an unmatched
+ *     column reaches, and the rows on the way. Every row of H is matched.
+ *   - V, vertical: the rows an alternating path from an unmatched row
+ *     reaches, and the columns on the way. Every column of V is matched.
+ *   - S, square: the rest, perfectly matched.
+ * No row outside H has an entry in a column of H (the path would reach it),
+ * and the rows of V have entries only in columns of V, so rows H, S, V
+ * against columns H, S, V is block upper triangular. S is split further:
+ * with row r -> r' when r has an entry in the column matched to r', the
+ * strongly connected components (Tarjan, iteratively) are the irreducible
+ * diagonal blocks, in topological order. A block's columns follow its rows'
+ * mates, so each diagonal block has a zero-free diagonal; in H the matched
+ * columns come first, in V the matched rows, and their diagonals are
+ * zero-free as far as they go. The blocks do not depend on which maximum
+ * transversal they start from (H and V are the Gallai-Edmonds D and A of
+ * the bipartite graph; see vertex_classes.rs).
+ *
+ * check_btf() verifies the permutations, that no entry lies below the
+ * block diagonal, and that the zero-free diagonal entries number the rows
+ * outside V plus the columns of V. Those rows and columns cover every
+ * entry, so by Konig the transversal is maximum.
+ *
+ * Included via #[path = "../../common/rust/block_triangular.rs"] mod block_triangular;
+ * next to hopcroft_karp.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+
+use super::hopcroft_karp::HopcroftKarp;
+
+const NIL: usize = usize::MAX;
+
+/* A sparse pattern: (row, column) entries, 0-based, as stored. */
+pub struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub entries: Vec<(usize, usize)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlockKind {
+    Horizontal, /* more columns than rows: underdetermined */
+    Square,     /* irreducible, zero-free diagonal */
+    Vertical,   /* more rows than columns: overdetermined */
+}
+
+impl BlockKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            BlockKind::Horizontal => "horizontal",
+            BlockKind::Square => "square",
+            BlockKind::Vertical => "vertical",
+        }
+    }
+}
+
+pub struct Btf {
+    pub row_perm: Vec<usize>,   /* row_perm[k]: the original row at position k */
+    pub col_perm: Vec<usize>,   /* col_perm[k]: the original column at position k */
+    pub row_start: Vec<usize>,  /* block b holds positions row_start[b]..row_start[b + 1] */
+    pub col_start: Vec<usize>,  /* the same for columns */
+    pub kind: Vec<BlockKind>,   /* one per block */
+    pub rank: usize,            /* structural rank: the maximum transversal's size */
+}
+
+impl Btf {
+    pub fn blocks(&self) -> usize {
+        self.kind.len()
+    }
+
+    /* The largest square block, 0 without one. */
+    pub fn largest_square(&self) -> usize {
+        (0..self.blocks()).filter(|&b| self.kind[b] == BlockKind::Square)
+            .map(|b| self.row_start[b + 1] - self.row_start[b]).max().unwrap_or(0)
+    }
+
+    /* Rows and columns of the horizontal block, and of the vertical one. */
+    pub fn coarse(&self) -> ((usize, usize), (usize, usize)) {
+        let size = |k| (0..self.blocks()).find(|&b| self.kind[b] == k)
+            .map_or((0, 0), |b| (self.row_start[b + 1] - self.row_start[b], self.col_start[b + 1] - self.col_start[b]));
+        (size(BlockKind::Horizontal), size(BlockKind::Vertical))
+    }
+}
+
+fn bad_line(filename: &str, line: usize, what: &str, got: &str) -> Box<dyn Error> {
+    format!("{}:{}: expected {}, got '{}'", filename, line, what, got).into()
+}
+
+/* Format: Matrix Market coordinate, "%%MatrixMarket matrix coordinate
+ * <real|integer|complex|pattern> <general|symmetric|skew-symmetric|hermitian>",
+ * '%' comments, a "rows cols nnz" line, then nnz lines "i j [value...]"
+ * with 1-based indices. A symmetric matrix stores one triangle; the other
+ * is added here. Repeated entries are kept. */
+pub fn load_matrix_market(filename: &str) -> Result<Matrix, Box<dyn Error>> {
+    let reader = BufReader::new(File::open(filename)?);
+    let mut lines = reader.lines().enumerate();
+    let (_, banner) = lines.next().ok_or("Empty file")?;
+    let banner = banner?;
+    let words: Vec<String> = banner.split_whitespace().map(|w| w.to_ascii_lowercase()).collect();
+    if words.len() != 5 || words[0] != "%%matrixmarket" || words[1] != "matrix" {
+        return Err(bad_line(filename, 1, "'%%MatrixMarket matrix coordinate <field> <symmetry>'", &banner));
+    }
+    if words[2] != "coordinate" {
+        return Err(format!("{}:1: only coordinate (sparse) matrices are read, not '{}'", filename, words[2]).into());
+    }
+    if !["real", "integer", "complex", "pattern"].contains(&words[3].as_str()) {
+        return Err(format!("{}:1: unknown field '{}'", filename, words[3]).into());
+    }
+    let mirrored = match words[4].as_str() {
+        "general" => false,
+        "symmetric" | "skew-symmetric" | "hermitian" => true,
+        s => return Err(format!("{}:1: unknown symmetry '{}'", filename, s).into()),
+    };
+
+    let mut size = None;
+    let mut entries = Vec::new();
+    for (i, l) in lines {
+        let l = l?;
+        let t = l.trim();
+        if t.is_empty() || t.starts_with('%') { continue; }
+        let line = i + 1;
+        let fields: Vec<&str> = t.split_whitespace().collect();
+        let (rows, cols, nnz) = match size {
+            None => {
+                let h: Vec<usize> = fields.iter().map(|f| f.parse()).collect::<Result<_, _>>()
+                    .map_err(|_| bad_line(filename, line, "'rows cols nnz'", t))?;
+                if h.len() != 3 {
+                    return Err(bad_line(filename, line, "'rows cols nnz'", t));
+                }
+                size = Some((h[0], h[1], h[2]));
+                entries.reserve(h[2].min(1 << 24) * if mirrored { 2 } else { 1 });
+                continue;
+            }
+            Some(s) => s,
+        };
+        if fields.len() < 2 {
+            return Err(bad_line(filename, line, "'i j [value]'", t));
+        }
+        let (r, c): (usize, usize) = match (fields[0].parse(), fields[1].parse()) {
+            (Ok(r), Ok(c)) => (r, c),
+            _ => return Err(bad_line(filename, line, "'i j [value]'", t)),
+        };
+        if r == 0 || r > rows || c == 0 || c > cols {
+            return Err(format!("{}:{}: entry ({}, {}) outside the {} x {} matrix", filename, line, r, c, rows, cols).into());
+        }
+        entries.push((r - 1, c - 1));
+        if mirrored && r != c {
+            entries.push((c - 1, r - 1));
+        }
+        if entries.len() > if mirrored { 2 * nnz } else { nnz } {
+            return Err(format!("Header declares {} entries but the file lists more", nnz).into());
+        }
+    }
+    let (rows, cols, nnz) = size.ok_or_else(|| format!("{}: no 'rows cols nnz' line", filename))?;
+    if mirrored && rows != cols {
+        return Err(format!("{}: a {} matrix must be square, not {} x {}", filename, words[4], rows, cols).into());
+    }
+    let stored = if mirrored { entries.iter().filter(|&&(r, c)| r <= c).count() } else { entries.len() };
+    if stored != nnz {
+        return Err(format!("Header declares {} entries but the file lists {}", nnz, stored).into());
+    }
+    Ok(Matrix { rows, cols, entries })
+}
+
+/* Compressed rows: the columns of row r are col[start[r]..start[r + 1]]. */
+fn by_rows(rows: usize, entries: &[(usize, usize)], transpose: bool) -> (Vec<usize>, Vec<usize>) {
+    let mut start = vec![0usize; rows + 1];
+    for &(r, c) in entries {
+        start[if transpose { c } else { r } + 1] += 1;
+    }
+    for r in 0..rows { start[r + 1] += start[r]; }
+    let mut fill = start.clone();
+    let mut col = vec![0usize; entries.len()];
+    for &(r, c) in entries {
+        let (r, c) = if transpose { (c, r) } else { (r, c) };
+        col[fill[r]] = c;
+        fill[r] += 1;
+    }
+    (start, col)
+}
+
+/* Marks what alternating paths from the unmatched vertices of one side
+ * reach: from a vertex to every neighbour on the other side, and from
+ * there along the matching back. */
+fn reach(start: &[usize], adj: &[usize], mate: &[usize], back: &[usize]) -> (Vec<bool>, Vec<bool>) {
+    let n = mate.len();
+    let mut near = vec![false; n];
+    let mut far = vec![false; back.len()];
+    let mut stack: Vec<usize> = (0..n).filter(|&v| mate[v] == NIL).collect();
+    for &v in &stack { near[v] = true; }
+    while let Some(v) = stack.pop() {
+        for &w in &adj[start[v]..start[v + 1]] {
+            if far[w] { continue; }
+            far[w] = true;
+            let x = back[w];
+            if x != NIL && !near[x] {
+                near[x] = true;
+                stack.push(x);
+            }
+        }
+    }
+    (near, far)
+}
+
+/* Strongly connected components of the rows of S, r -> mate of each
+ * column of S in row r, in topological order (Tarjan emits sinks first). */
+fn square_blocks(rows: &[usize], start: &[usize], col: &[usize], in_s: &[bool], row_of: &[usize]) -> Vec<Vec<usize>> {
+    let n = start.len() - 1;
+    let mut index = vec![NIL; n];
+    let mut low = vec![0usize; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut call: Vec<(usize, usize)> = Vec::new(); /* (row, next entry) */
+    let mut blocks = Vec::new();
+    let mut next = 0;
+    for &s in rows {
+        if index[s] != NIL { continue; }
+        index[s] = next;
+        low[s] = next;
+        next += 1;
+        stack.push(s);
+        on_stack[s] = true;
+        call.push((s, start[s]));
+        while let Some(&(v, e)) = call.last() {
+            if e < start[v + 1] {
+                call.last_mut().unwrap().1 += 1;
+                let c = col[e];
+                if !in_s[c] { continue; }
+                let w = row_of[c];
+                if index[w] == NIL {
+                    index[w] = next;
+                    low[w] = next;
+                    next += 1;
+                    stack.push(w);
+                    on_stack[w] = true;
+                    call.push((w, start[w]));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+            call.pop();
+            if let Some(&(u, _)) = call.last() {
+                low[u] = low[u].min(low[v]);
+            }
+            if low[v] == index[v] {
+                let mut block = Vec::new();
+                loop {
+                    let w = stack.pop().unwrap();
+                    on_stack[w] = false;
+                    block.push(w);
+                    if w == v { break; }
+                }
+                block.reverse();
+                blocks.push(block);
+            }
+        }
+    }
+    blocks.reverse();
+    blocks
+}
+
+/* The maximum transversal and block triangular permutations of `m`.
+ * Entries out of range are ignored. */
+pub fn btf_order(m: &Matrix) -> Btf {
+    let entries: Vec<(usize, usize)> = m.entries.iter().copied().filter(|&(r, c)| r < m.rows && c < m.cols).collect();
+    let mut hk = HopcroftKarp::new(m.rows, m.cols, &entries);
+    let matching = hk.maximum_matching(1);
+    let mut col_of = vec![NIL; m.rows];
+    let mut row_of = vec![NIL; m.cols];
+    for &(r, c) in &matching {
+        col_of[r] = c;
+        row_of[c] = r;
+    }
+    let (row_start, row_cols) = by_rows(m.rows, &entries, false);
+    let (col_start, col_rows) = by_rows(m.cols, &entries, true);
+    let (h_cols, h_rows) = reach(&col_start, &col_rows, &row_of, &col_of);
+    let (v_rows, v_cols) = reach(&row_start, &row_cols, &col_of, &row_of);
+
+    let mut btf = Btf {
+        row_perm: Vec::with_capacity(m.rows),
+        col_perm: Vec::with_capacity(m.cols),
+        row_start: vec![0],
+        col_start: vec![0],
+        kind: Vec::new(),
+        rank: matching.len(),
+    };
+    let close = |btf: &mut Btf, kind| {
+        if btf.row_perm.len() > *btf.row_start.last().unwrap() || btf.col_perm.len() > *btf.col_start.last().unwrap() {
+            btf.row_start.push(btf.row_perm.len());
+            btf.col_start.push(btf.col_perm.len());
+            btf.kind.push(kind);
+        }
+    };
+
+    /* H: matched pairs in row order, then the unmatched columns */
+    for r in (0..m.rows).filter(|&r| h_rows[r]) {
+        btf.row_perm.push(r);
+        btf.col_perm.push(col_of[r]);
+    }
+    btf.col_perm.extend((0..m.cols).filter(|&c| h_cols[c] && row_of[c] == NIL));
+    close(&mut btf, BlockKind::Horizontal);
+
+    let in_s: Vec<bool> = (0..m.cols).map(|c| !h_cols[c] && !v_cols[c]).collect();
+    let s_rows: Vec<usize> = (0..m.rows).filter(|&r| !h_rows[r] && !v_rows[r]).collect();
+    for block in square_blocks(&s_rows, &row_start, &row_cols, &in_s, &row_of) {
+        for r in block {
+            btf.row_perm.push(r);
+            btf.col_perm.push(col_of[r]);
+        }
+        close(&mut btf, BlockKind::Square);
+    }
+
+    /* V: matched pairs in column order, then the unmatched rows */
+    for c in (0..m.cols).filter(|&c| v_cols[c]) {
+        btf.row_perm.push(row_of[c]);
+        btf.col_perm.push(c);
+    }
+    btf.row_perm.extend((0..m.rows).filter(|&r| v_rows[r] && col_of[r] == NIL));
+    close(&mut btf, BlockKind::Vertical);
+    btf
+}
+
+/* Inverse of a permutation of 0..n, or what keeps it from being one. */
+fn inverse(perm: &[usize], n: usize, what: &str, errors: &mut Vec<String>) -> Option<Vec<usize>> {
+    if perm.len() != n {
+        errors.push(format!("{} permutation has {} entries for {} {}s", what, perm.len(), n, what));
+        return None;
+    }
+    let mut pos = vec![NIL; n];
+    for (k, &x) in perm.iter().enumerate() {
+        if x >= n || pos[x] != NIL {
+            errors.push(format!("{} permutation repeats or overruns at position {} ({})", what, k, x));
+            return None;
+        }
+        pos[x] = k;
+    }
+    Some(pos)
+}
+
+/* What breaks the block triangular form or the maximum transversal. */
+pub fn check_btf(m: &Matrix, btf: &Btf) -> Vec<String> {
+    let mut errors = Vec::new();
+    let rows = inverse(&btf.row_perm, m.rows, "row", &mut errors);
+    let cols = inverse(&btf.col_perm, m.cols, "column", &mut errors);
+    let blocks = btf.blocks();
+    let bounds_ok = btf.row_start.len() == blocks + 1 && btf.col_start.len() == blocks + 1
+        && btf.row_start[0] == 0 && btf.col_start[0] == 0
+        && btf.row_start[blocks] == m.rows && btf.col_start[blocks] == m.cols
+        && btf.row_start.windows(2).all(|w| w[0] <= w[1]) && btf.col_start.windows(2).all(|w| w[0] <= w[1]);
+    if !bounds_ok {
+        errors.push("block boundaries do not split the rows and columns in order".to_string());
+    }
+    let (rows, cols) = match (rows, cols) {
+        (Some(r), Some(c)) if bounds_ok => (r, c),
+        _ => return errors,
+    };
+    let block_of = |start: &[usize], n: usize| {
+        let mut b = vec![0usize; n];
+        for k in 0..blocks {
+            for x in &mut b[start[k]..start[k + 1]] { *x = k; }
+        }
+        b
+    };
+    let row_block = block_of(&btf.row_start, m.rows);
+    let col_block = block_of(&btf.col_start, m.cols);
+    let mut diagonal = vec![false; m.rows];
+    let mut below = 0;
+    for &(r, c) in &m.entries {
+        if r >= m.rows || c >= m.cols { continue; }
+        let (i, j) = (rows[r], cols[c]);
+        let b = row_block[i];
+        if b > col_block[j] {
+            if below == 0 {
+                errors.push(format!("entry ({}, {}) lies below the block diagonal", r, c));
+            }
+            below += 1;
+        } else if b == col_block[j] && i - btf.row_start[b] == j - btf.col_start[b] {
+            diagonal[i] = true;
+        }
+    }
+    for b in 0..blocks {
+        let (h, w) = (btf.row_start[b + 1] - btf.row_start[b], btf.col_start[b + 1] - btf.col_start[b]);
+        let ok = match btf.kind[b] {
+            BlockKind::Horizontal => h < w,
+            BlockKind::Square => h == w,
+            BlockKind::Vertical => h > w,
+        };
+        if !ok {
+            errors.push(format!("block {} is {} x {}, not {}", b, h, w, btf.kind[b].name()));
+        }
+        if btf.kind[b] == BlockKind::Square {
+            if let Some(k) = (btf.row_start[b]..btf.row_start[b + 1]).find(|&k| !diagonal[k]) {
+                errors.push(format!("square block {} has a zero on its diagonal at row {}", b, btf.row_perm[k]));
+            }
+        }
+    }
+    let zero_free = diagonal.iter().filter(|&&d| d).count();
+    if zero_free != btf.rank {
+        errors.push(format!("{} zero-free diagonal entries, but the rank is {}", zero_free, btf.rank));
+    }
+    /* rows outside V and the columns of V cover every entry (none below) */
+    let ((_, _), (v_rows, v_cols)) = btf.coarse();
+    if below == 0 && m.rows - v_rows + v_cols != zero_free {
+        errors.push(format!("{} diagonal entries, but a cover of {} rows and columns", zero_free, m.rows - v_rows + v_cols));
+    }
+    errors
+}
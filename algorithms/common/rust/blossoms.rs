@@ -0,0 +1,300 @@
+/*
+ * Explicit nested blossoms for the Gabow searches, kept next to their
+ * union-find bases: the structure a weighted (dual) search needs, where
+ * blossoms must be taken apart in the middle of a phase.
+ *
+ * Nodes 0..n are vertices, n..2n blossoms. A blossom is an odd cycle of
+ * top-level nodes (vertices or smaller blossoms), listed from the child
+ * holding its base, as in edmonds_blossom_simple.rs:
+ *   children[0], children[1], ..., children[k - 1]
+ *   edges[i] joins children[i] and children[(i + 1) % k], endpoint in
+ *   children[i] first; edges[1], edges[3], ... are matched, joining the
+ *   bases of their children.
+ * Every vertex knows its top-level node, so the base of a vertex is one
+ * lookup; a contraction or expansion rewrites that for the vertices
+ * involved, O(V) at most.
+ *
+ * contract_bridge builds a blossom from a search forest (mate and parent
+ * as Vertex arrays, NONE for none (types.rs); parent[v] is the EVEN vertex
+ * that labeled the ODD vertex v). expand takes a top-level blossom apart
+ * into its children. path_to_base walks the even alternating path from a
+ * vertex to the base of its blossom, through the nested cycles, and climb
+ * follows a search tree up to its root: together they lift an augmenting
+ * path without bridges. check lists the broken invariants, for tests.
+ *
+ * Included via #[path = "../../common/rust/blossoms.rs"] mod blossoms;
+ */
+
+#![allow(dead_code)]
+
+use super::types::{ix, vx, Vertex, NONE};
+
+const NIL: usize = usize::MAX;
+
+pub struct Blossoms {
+    n: usize,
+    parent: Vec<usize>,              /* enclosing blossom, NIL at top level */
+    top: Vec<usize>,                 /* per vertex: its top-level node */
+    base: Vec<usize>,                /* per node */
+    children: Vec<Vec<usize>>,       /* per blossom, cycle order from the base child */
+    edges: Vec<Vec<(usize, usize)>>, /* per blossom, edges[i] after children[i] */
+    unused: Vec<usize>,              /* free blossom ids, lowest last */
+}
+
+impl Blossoms {
+    pub fn new(n: usize) -> Self {
+        Blossoms {
+            n,
+            parent: vec![NIL; 2 * n],
+            top: (0..n).collect(),
+            base: (0..2 * n).map(|b| if b < n { b } else { NIL }).collect(),
+            children: vec![Vec::new(); 2 * n],
+            edges: vec![Vec::new(); 2 * n],
+            unused: (n..2 * n).rev().collect(),
+        }
+    }
+
+    /* Every vertex top-level again, all blossoms gone. */
+    pub fn reset(&mut self) {
+        for b in self.n..2 * self.n {
+            if !self.children[b].is_empty() {
+                self.children[b].clear();
+                self.edges[b].clear();
+            }
+        }
+        for v in 0..self.n {
+            self.top[v] = v;
+            self.parent[v] = NIL;
+        }
+        self.unused.clear();
+        self.unused.extend((self.n..2 * self.n).rev());
+    }
+
+    pub fn is_blossom(&self, node: usize) -> bool {
+        node >= self.n
+    }
+
+    pub fn top(&self, v: usize) -> usize {
+        self.top[v]
+    }
+
+    /* Base vertex of a node. */
+    pub fn base_of(&self, node: usize) -> usize {
+        self.base[node]
+    }
+
+    /* Base of the top-level node holding vertex v. */
+    pub fn base(&self, v: usize) -> usize {
+        self.base[self.top[v]]
+    }
+
+    pub fn children(&self, b: usize) -> &[usize] {
+        &self.children[b]
+    }
+
+    pub fn edges(&self, b: usize) -> &[(usize, usize)] {
+        &self.edges[b]
+    }
+
+    /* Vertices inside a node. */
+    pub fn leaves(&self, node: usize, out: &mut Vec<usize>) {
+        let mut stack = vec![node];
+        while let Some(x) = stack.pop() {
+            if x < self.n {
+                out.push(x);
+            } else {
+                stack.extend_from_slice(&self.children[x]);
+            }
+        }
+    }
+
+    /* The child of blossom b that holds vertex v. */
+    fn child_of(&self, b: usize, v: usize) -> usize {
+        let mut x = v;
+        while self.parent[x] != b {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn set_top(&mut self, node: usize, t: usize) {
+        let mut leaves = Vec::new();
+        self.leaves(node, &mut leaves);
+        for v in leaves {
+            self.top[v] = t;
+        }
+    }
+
+    /* New top-level blossom from an odd cycle of top-level nodes, base child
+     * first; edges as described above. */
+    pub fn contract(&mut self, children: Vec<usize>, edges: Vec<(usize, usize)>) -> usize {
+        let b = self.unused.pop().expect("more blossoms than vertices");
+        self.base[b] = self.base[children[0]];
+        for &c in &children {
+            self.parent[c] = b;
+        }
+        self.children[b] = children;
+        self.edges[b] = edges;
+        self.set_top(b, b);
+        b
+    }
+
+    /* Blossom for the EVEN-EVEN edge (x, y) of one search tree whose
+     * nearest common base is `lca`: the tree paths from x and from y up
+     * to lca's node, closed by the edge. */
+    pub fn contract_bridge(&mut self, lca: usize, x: usize, y: usize, mate: &[Vertex], parent: &[Vertex]) -> usize {
+        let l = self.top[lca];
+        let chain = |from: usize| {
+            let mut nodes = Vec::new();
+            let mut links = Vec::new();
+            let mut t = self.top[from];
+            while t != l {
+                let b = self.base[t];
+                let m = ix(mate[b]);
+                let p = ix(parent[m]);
+                nodes.push(t);
+                links.push((b, m));
+                nodes.push(m);
+                links.push((m, p));
+                t = self.top[p];
+            }
+            (nodes, links)
+        };
+        let (xs, x_links) = chain(x);
+        let (ys, y_links) = chain(y);
+        let mut children = vec![l];
+        let mut edges = Vec::with_capacity(1 + xs.len() + ys.len());
+        for i in (0..xs.len()).rev() {
+            children.push(xs[i]);
+            edges.push((x_links[i].1, x_links[i].0));
+        }
+        edges.push((x, y));
+        children.extend_from_slice(&ys);
+        edges.extend_from_slice(&y_links);
+        self.contract(children, edges)
+    }
+
+    /* Take top-level blossom b apart; its children become top-level and
+     * are returned in cycle order, base child first. */
+    pub fn expand(&mut self, b: usize) -> Vec<usize> {
+        debug_assert!(self.is_blossom(b) && self.parent[b] == NIL);
+        let children = std::mem::take(&mut self.children[b]);
+        for &c in &children {
+            self.parent[c] = NIL;
+            self.set_top(c, c);
+        }
+        self.edges[b].clear();
+        self.base[b] = NIL;
+        self.unused.push(b);
+        children
+    }
+
+    /* Unmatched edges of the even alternating path from vertex v to the
+     * base of `node` (which holds v), through the nested cycles: from the
+     * child holding v around the cycle in the direction that leaves by a
+     * matched edge. Vertex-disjoint, so their order does not matter. */
+    pub fn path_to_base(&self, node: usize, v: usize, out: &mut Vec<(usize, usize)>) {
+        let mut stack = vec![(node, v)];
+        while let Some((b, v)) = stack.pop() {
+            if b < self.n { continue; }
+            let ch = &self.children[b];
+            let ed = &self.edges[b];
+            let k = ch.len();
+            let mut i = ch.iter().position(|&c| c == self.child_of(b, v)).unwrap();
+            stack.push((ch[i], v));
+            let forward = i % 2 == 1;
+            while i != 0 {
+                /* matched edge to the next child, then an unmatched one out of it */
+                if forward {
+                    let (a, z) = ed[i + 1];
+                    stack.push((ch[i + 1], a));
+                    out.push((a, z));
+                    i = (i + 2) % k;
+                    stack.push((ch[i], z));
+                } else {
+                    let (z, a) = ed[i - 2];
+                    stack.push((ch[i - 1], a));
+                    out.push((z, a));
+                    i -= 2;
+                    stack.push((ch[i], z));
+                }
+            }
+        }
+    }
+
+    /* Unmatched edges of the alternating path from vertex x up its search
+     * tree to the free root. */
+    pub fn climb(&self, x: usize, mate: &[Vertex], parent: &[Vertex], out: &mut Vec<(usize, usize)>) {
+        let mut x = x;
+        loop {
+            let t = self.top[x];
+            self.path_to_base(t, x, out);
+            let b = self.base[t];
+            if mate[b] == NONE { return; }
+            let m = ix(mate[b]);
+            let p = ix(parent[m]);
+            out.push((m, p));
+            x = p;
+        }
+    }
+
+    fn inside(&self, v: usize, node: usize) -> bool {
+        let mut x = v;
+        while x != NIL && x != node {
+            x = self.parent[x];
+        }
+        x == node
+    }
+
+    /* Broken invariants under `mate`: cycle shape, edge ends, matched and
+     * unmatched edges alternating, bases, and the top-level map. */
+    pub fn check(&self, mate: &[Vertex]) -> Vec<String> {
+        let mut errors = Vec::new();
+        for b in self.n..2 * self.n {
+            let ch = &self.children[b];
+            if ch.is_empty() { continue; }
+            let ed = &self.edges[b];
+            let k = ch.len();
+            if k < 3 || k.is_multiple_of(2) || ed.len() != k {
+                errors.push(format!("blossom {}: {} children, {} edges", b, k, ed.len()));
+                continue;
+            }
+            if self.base[b] != self.base[ch[0]] {
+                errors.push(format!("blossom {}: base {} but its first child's is {}", b, self.base[b], self.base[ch[0]]));
+            }
+            let bb = self.base[b];
+            if mate[bb] != NONE && self.inside(ix(mate[bb]), b) {
+                errors.push(format!("blossom {}: base {} is matched inside it", b, bb));
+            }
+            for i in 0..k {
+                if self.parent[ch[i]] != b {
+                    errors.push(format!("blossom {}: child {} has parent {}", b, ch[i], self.parent[ch[i]]));
+                }
+                let (p, q) = ed[i];
+                let next = ch[(i + 1) % k];
+                if !self.inside(p, ch[i]) || !self.inside(q, next) {
+                    errors.push(format!("blossom {}: edge ({}, {}) does not join {} and {}", b, p, q, ch[i], next));
+                    continue;
+                }
+                let matched = mate[p] == vx(q);
+                if matched != (i % 2 == 1) {
+                    errors.push(format!("blossom {}: edge ({}, {}) at {} is {}matched", b, p, q, i,
+                                        if matched { "" } else { "un" }));
+                }
+                if i % 2 == 1 && (p != self.base[ch[i]] || q != self.base[next]) {
+                    errors.push(format!("blossom {}: matched edge ({}, {}) is not between bases", b, p, q));
+                }
+            }
+        }
+        for v in 0..self.n {
+            let mut t = v;
+            while self.parent[t] != NIL {
+                t = self.parent[t];
+            }
+            if self.top[v] != t {
+                errors.push(format!("vertex {}: top {} but its outermost node is {}", v, self.top[v], t));
+            }
+        }
+        errors
+    }
+}
@@ -0,0 +1,160 @@
+/*
+ * Pair constraints for `combi solve`: pairs that may not be matched
+ * (--forbid, roommates with conflicts, say) and pairs that must be
+ * (--force-edges, the part of an assignment already committed).
+ *
+ * Forbidden pairs: the solve runs on G minus every edge joining one,
+ * parallel copies included, so any matching it finds avoids them.
+ * Forced pairs: each must be an edge of what is left, and no vertex may be
+ * in two of them; their vertices leave the graph with every edge at them,
+ * the solver maximizes over the rest, and the forced pairs are added back.
+ * That is a maximum matching among those containing the forced pairs,
+ * since any such matching is the forced pairs plus a matching of G minus
+ * their vertices.
+ *
+ * The cost of the constraints is nu(G) against the constrained optimum,
+ * reported against an unconstrained solve. Forbidding F costs at most
+ * nu(F) (the forbidden edges as a graph), 0 whenever some maximum matching
+ * of G avoids F; forcing k pairs costs at most k, since each forced pair
+ * leaves out at most two pairs of a maximum matching and brings one.
+ *
+ * Pairs files: one pair "u v" per line, '#' starts a comment; either
+ * order, repeats allowed. After a --format snap load the ids are the
+ * file's original ones, and after a --format labels load the pairs are
+ * labels, as for matching files.
+ *
+ * Included via #[path = "../../common/rust/constraints.rs"] mod constraints;
+ * next to graph_io.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::fs;
+
+use super::graph_io;
+
+/* The pairs of a pairs file, sorted (u < v) and distinct, in the `names`
+ * of the graph's load; an id the graph does not have or a pair u u is an
+ * error. */
+pub fn load_pairs(filename: &str, n: usize, names: &graph_io::InputNames) -> Result<Vec<(usize, usize)>, String> {
+    let text = fs::read_to_string(filename).map_err(|e| format!("{}: {}", filename, e))?;
+    if names.labels.is_some() {
+        return load_labeled_pairs(filename, &text, n, names);
+    }
+    let mut pairs = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        let toks: Vec<&str> = line.split_whitespace().collect();
+        if toks.is_empty() { continue; }
+        if toks.len() != 2 {
+            return Err(format!("{}:{}: expected \"u v\"", filename, i + 1));
+        }
+        let num = |s: &str| s.parse::<usize>()
+            .map_err(|_| format!("{}:{}: expected a vertex id, got '{}'", filename, i + 1, s));
+        let (u, v) = (num(toks[0])?, num(toks[1])?);
+        if u == v {
+            return Err(format!("{}:{}: pair ({}, {}) is a self-loop", filename, i + 1, u, v));
+        }
+        pairs.push((u, v));
+    }
+    let pairs = names.remapped_pairs(pairs, false).map_err(|e| format!("{}: {}", filename, e))?;
+    canonical_pairs(filename, pairs, n)
+}
+
+/* load_pairs after a --format labels load: "a b" lines of labels. */
+fn load_labeled_pairs(
+    filename: &str, text: &str, n: usize, names: &graph_io::InputNames,
+) -> Result<Vec<(usize, usize)>, String> {
+    let mut pairs = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let mut labels = graph_io::label_fields(raw).map_err(|e| format!("{}:{}: {}", filename, i + 1, e))?;
+        if labels.is_empty() { continue; }
+        if labels.len() != 2 {
+            return Err(format!("{}:{}: expected two labels", filename, i + 1));
+        }
+        if labels[0] == labels[1] {
+            return Err(format!("{}:{}: pair ({}, {}) is a self-loop", filename, i + 1, labels[0], labels[1]));
+        }
+        let v = labels.pop().unwrap();
+        pairs.push((labels.pop().unwrap(), v));
+    }
+    let pairs = names.unlabeled_pairs(pairs, false).map_err(|e| format!("{}: {}", filename, e))?;
+    canonical_pairs(filename, pairs, n)
+}
+
+/* The pairs in range, sorted (u < v) and distinct. */
+fn canonical_pairs(filename: &str, mut pairs: Vec<(usize, usize)>, n: usize) -> Result<Vec<(usize, usize)>, String> {
+    if let Some(&(u, v)) = pairs.iter().find(|&&(u, v)| u >= n || v >= n) {
+        return Err(format!("{}: pair ({}, {}) out of range (n = {})", filename, u, v, n));
+    }
+    for p in &mut pairs {
+        *p = (p.0.min(p.1), p.0.max(p.1));
+    }
+    pairs.sort_unstable();
+    pairs.dedup();
+    Ok(pairs)
+}
+
+/* The edges of `edges` that join no pair of `forbidden` (sorted, u < v),
+ * in their order, and the number left out. */
+pub fn remove_forbidden(edges: &[(usize, usize)], forbidden: &[(usize, usize)]) -> (Vec<(usize, usize)>, usize) {
+    let kept: Vec<(usize, usize)> = edges.iter().cloned()
+        .filter(|&(u, v)| forbidden.binary_search(&(u.min(v), u.max(v))).is_err())
+        .collect();
+    let removed = edges.len() - kept.len();
+    (kept, removed)
+}
+
+/* Why `forced` (sorted, u < v) cannot all be in one matching of (n, edges),
+ * the graph after the forbidden pairs left: a pair that is forbidden or
+ * not an edge, or a vertex in two pairs. */
+pub fn check_forced(
+    n: usize, edges: &[(usize, usize)], forced: &[(usize, usize)], forbidden: &[(usize, usize)],
+) -> Result<(), String> {
+    let mut graph: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    graph.sort_unstable();
+    let mut owner = vec![None; n];
+    for &(u, v) in forced {
+        if forbidden.binary_search(&(u, v)).is_ok() {
+            return Err(format!("forced pair ({}, {}) is also forbidden", u, v));
+        }
+        if graph.binary_search(&(u, v)).is_err() {
+            return Err(format!("forced pair ({}, {}) is not an edge of the graph", u, v));
+        }
+        for &x in &[u, v] {
+            if let Some((a, b)) = owner[x] {
+                return Err(format!("forced pairs ({}, {}) and ({}, {}) conflict at vertex {}", a, b, u, v, x));
+            }
+            owner[x] = Some((u, v));
+        }
+    }
+    Ok(())
+}
+
+/* The edges of `edges` at no vertex of `forced`, in their order, and the
+ * number left out. */
+pub fn remove_forced(n: usize, edges: &[(usize, usize)], forced: &[(usize, usize)]) -> (Vec<(usize, usize)>, usize) {
+    let mut taken = vec![false; n];
+    for &(u, v) in forced {
+        taken[u] = true;
+        taken[v] = true;
+    }
+    let kept: Vec<(usize, usize)> = edges.iter().cloned()
+        .filter(|&(u, v)| (u >= n || !taken[u]) && (v >= n || !taken[v]))
+        .collect();
+    let removed = edges.len() - kept.len();
+    (kept, removed)
+}
+
+/* The graph a constrained solve runs on, and what it is checked against. */
+pub struct Constrained {
+    /* the graph as loaded: its maximum matching is the unconstrained one */
+    pub all: Vec<(usize, usize)>,
+    /* the graph minus the forbidden pairs: the matching must be one of it */
+    pub allowed: Vec<(usize, usize)>,
+    /* forced pairs (sorted, u < v), added to the solver's matching */
+    pub forced: Vec<(usize, usize)>,
+}
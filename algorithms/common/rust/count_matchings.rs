@@ -0,0 +1,244 @@
+/*
+ * The number of maximum matchings of a small or narrow graph, for
+ * `combi solve --count-matchings`: how many optima there are, next to the
+ * one the solver found.
+ *
+ * The count is a product over connected components. A component is solved
+ * by one of two exact methods:
+ *   - Ryser's permanent, for a bipartite component with sides of equal
+ *     size k <= MAX_RYSER: the permanent of its k x k biadjacency matrix is
+ *     the number of perfect matchings, by inclusion-exclusion over column
+ *     subsets in Gray code order, O(2^k k). A zero permanent means no
+ *     perfect matching, and the component goes to the DP instead;
+ *   - a frontier DP (transfer matrix) over the vertices in BFS order from a
+ *     pseudo-peripheral vertex, a path decomposition: the state is which
+ *     vertices of the frontier (introduced, with a neighbour still to come)
+ *     are matched, and each state holds the largest matching size reaching
+ *     it and how many matchings of that size do. An edge is taken or not
+ *     when its later end is introduced, and a vertex leaves the frontier
+ *     with its last neighbour. The width is the largest frontier, at most
+ *     MAX_WIDTH: 2^width states, O(2^width E) time. Paths, cycles, ladders
+ *     and grids of small side have small width; random graphs do not.
+ *
+ * Sizes and counts combine as (size, count) pairs: the larger size wins,
+ * and equal sizes add their counts; a product adds sizes and multiplies
+ * counts. Counts are exact u128; one that overflows is an error.
+ *
+ * Self-loops are dropped, and parallel edges count once: a matching is a
+ * set of vertex pairs.
+ *
+ * Included via #[path = "../../common/rust/count_matchings.rs"] mod count_matchings;
+ */
+
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+
+/* Largest side of a bipartite component counted by Ryser's formula. */
+pub const MAX_RYSER: usize = 22;
+/* Largest frontier of the DP: 2^MAX_WIDTH states at most. */
+pub const MAX_WIDTH: usize = 20;
+
+pub struct Count {
+    pub size: usize,    /* the matching number */
+    pub count: u128,    /* the number of maximum matchings */
+    pub components: usize,
+    pub ryser: usize,   /* components counted by the permanent */
+    pub width: usize,   /* the widest frontier of the DP, 0 if it did not run */
+}
+
+fn overflow() -> String {
+    "the number of maximum matchings exceeds 2^128".to_string()
+}
+
+/* The (size, count) sum: the larger size, or equal sizes with the counts
+ * added. */
+fn add(a: (usize, u128), b: (usize, u128)) -> Result<(usize, u128), String> {
+    if a.0 != b.0 {
+        return Ok(if a.0 > b.0 { a } else { b });
+    }
+    Ok((a.0, a.1.checked_add(b.1).ok_or_else(overflow)?))
+}
+
+/* The two sides of a connected component, or None if it has an odd cycle. */
+fn sides(adj: &[Vec<usize>], comp: &[usize], side: &mut [u8]) -> Option<(Vec<usize>, Vec<usize>)> {
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    for &v in comp {
+        side[v] = 2;
+    }
+    side[comp[0]] = 0;
+    let mut queue = VecDeque::new();
+    queue.push_back(comp[0]);
+    while let Some(v) = queue.pop_front() {
+        if side[v] == 0 { left.push(v); } else { right.push(v); }
+        for &w in &adj[v] {
+            if side[w] == 2 {
+                side[w] = 1 - side[v];
+                queue.push_back(w);
+            } else if side[w] == side[v] {
+                return None;
+            }
+        }
+    }
+    Some((left, right))
+}
+
+/* The permanent of the biadjacency matrix between `left` and `right`
+ * (equal sizes, at most MAX_RYSER). */
+fn ryser(adj: &[Vec<usize>], left: &[usize], right: &[usize], slot: &mut [usize]) -> u128 {
+    let k = left.len();
+    for (j, &w) in right.iter().enumerate() {
+        slot[w] = j;
+    }
+    let rows: Vec<u32> = left.iter()
+        .map(|&v| adj[v].iter().fold(0u32, |m, &w| m | 1 << slot[w]))
+        .collect();
+    /* sum over column sets S of (-1)^|S| prod_i |row_i & S|, sign fixed at the end;
+     * each product is at most k^k < 2^99 and there are 2^k of them */
+    let mut sums = vec![0i128; k];
+    let mut total: i128 = 0;
+    let mut set = 0u32;
+    for g in 1..(1u64 << k) {
+        let j = g.trailing_zeros() as usize;
+        let adding = set & 1 << j == 0;
+        set ^= 1 << j;
+        let mut prod: i128 = 1;
+        for (i, r) in rows.iter().enumerate() {
+            if r & 1 << j != 0 {
+                sums[i] += if adding { 1 } else { -1 };
+            }
+            prod *= sums[i];
+        }
+        if set.count_ones() % 2 == 1 { total -= prod; } else { total += prod; }
+    }
+    if k % 2 == 1 { total = -total; }
+    total as u128
+}
+
+/* BFS order of a component from the far end of a BFS from `start`. */
+fn bfs_order(adj: &[Vec<usize>], start: usize, seen: &mut [bool]) -> Vec<usize> {
+    let mut order = vec![start];
+    seen[start] = true;
+    let mut i = 0;
+    while i < order.len() {
+        let v = order[i];
+        i += 1;
+        for &w in &adj[v] {
+            if !seen[w] {
+                seen[w] = true;
+                order.push(w);
+            }
+        }
+    }
+    for &v in &order {
+        seen[v] = false;
+    }
+    order
+}
+
+/* The (size, count) of the maximum matchings of one component, by the
+ * frontier DP over `order`, and its width. */
+fn frontier_dp(adj: &[Vec<usize>], order: &[usize], pos: &mut [usize]) -> Result<((usize, u128), usize), String> {
+    for (i, &v) in order.iter().enumerate() {
+        pos[v] = i;
+    }
+    /* the frontier leaves after the last neighbour of a vertex (or the vertex itself) comes in */
+    let mut last = vec![Vec::new(); order.len()];
+    for (i, &v) in order.iter().enumerate() {
+        let end = adj[v].iter().map(|&w| pos[w]).max().unwrap_or(i).max(i);
+        last[end].push(v);
+    }
+    let mut slot_of = HashMap::new();
+    let mut free_slots: Vec<usize> = (0..64).rev().collect();
+    let mut states: HashMap<u64, (usize, u128)> = HashMap::new();
+    states.insert(0, (0, 1));
+    let mut width = 0;
+    for (i, &v) in order.iter().enumerate() {
+        let sv = free_slots.pop().unwrap_or(0);
+        slot_of.insert(v, sv);
+        width = width.max(slot_of.len());
+        if width > MAX_WIDTH {
+            return Err(format!("a component of {} vertices has frontier width over {}", order.len(), MAX_WIDTH));
+        }
+        for &w in &adj[v] {
+            if pos[w] >= i { continue; }
+            let sw = slot_of[&w];
+            let mut next = HashMap::with_capacity(states.len() * 2);
+            for (&mask, &value) in &states {
+                let e = next.entry(mask).or_insert((0, 0));
+                *e = add(*e, value)?;
+                if mask & (1 << sv | 1 << sw) == 0 {
+                    let e = next.entry(mask | 1 << sv | 1 << sw).or_insert((0, 0));
+                    *e = add(*e, (value.0 + 1, value.1))?;
+                }
+            }
+            states = next;
+        }
+        for &x in &last[i] {
+            let sx = slot_of.remove(&x).unwrap_or(0);
+            free_slots.push(sx);
+            let mut next = HashMap::with_capacity(states.len());
+            for (&mask, &value) in &states {
+                let e = next.entry(mask & !(1 << sx)).or_insert((0, 0));
+                *e = add(*e, value)?;
+            }
+            states = next;
+        }
+    }
+    let mut best = (0, 0);
+    for &value in states.values() {
+        best = add(best, value)?;
+    }
+    Ok((best, width))
+}
+
+/* The matching number of (n, edges) and the number of maximum matchings;
+ * an error if a component is too wide for the DP or the count overflows. */
+pub fn count_maximum_matchings(n: usize, edges: &[(usize, usize)]) -> Result<Count, String> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj {
+        a.sort_unstable();
+        a.dedup();
+    }
+    let mut result = Count { size: 0, count: 1, components: 0, ryser: 0, width: 0 };
+    let mut seen = vec![false; n];
+    let mut side = vec![2u8; n];
+    let mut scratch = vec![0usize; n];
+    for s in 0..n {
+        if seen[s] || adj[s].is_empty() { continue; }
+        /* the far end of a BFS from s starts the order */
+        let first = bfs_order(&adj, s, &mut seen);
+        let order = bfs_order(&adj, *first.last().unwrap_or(&s), &mut seen);
+        for &v in &order {
+            seen[v] = true;
+        }
+        result.components += 1;
+        let mut value = None;
+        if let Some((left, right)) = sides(&adj, &order, &mut side) {
+            if left.len() == right.len() && left.len() <= MAX_RYSER {
+                let perm = ryser(&adj, &left, &right, &mut scratch);
+                if perm > 0 {
+                    value = Some((left.len(), perm));
+                    result.ryser += 1;
+                }
+            }
+        }
+        let (size, count) = match value {
+            Some(v) => v,
+            None => {
+                let (v, width) = frontier_dp(&adj, &order, &mut scratch)?;
+                result.width = result.width.max(width);
+                v
+            }
+        };
+        result.size += size;
+        result.count = result.count.checked_mul(count).ok_or_else(overflow)?;
+    }
+    Ok(result)
+}
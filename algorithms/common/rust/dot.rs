@@ -0,0 +1,232 @@
+/*
+ * --dot FILE: the graph and its matching in GraphViz DOT, for teaching
+ * and for looking at the small counterexamples the oracles dump
+ * (`dot -Tsvg FILE -o out.svg`).
+ *
+ * Matched edges are bold and red, free vertices double circles, and the
+ * blossoms of the matching each a dashed cluster. The blossoms do not
+ * come from the solver (each contracts its own, and most are gone by the
+ * end): blossoms() runs one Edmonds search from every free vertex at
+ * once over the final matching and keeps its outermost blossoms. For a
+ * maximum matching these are the Gallai-Edmonds ones: the components of
+ * the vertices an even alternating path from a free vertex reaches
+ * (EVEN) are the blossoms and single vertices, each matched inside but
+ * for its base, and n - 2|M| = (such components) - (ODD vertices).
+ *
+ * Self-loops, out-of-range endpoints and parallel copies are left out, as
+ * by the solvers. The files are for small graphs; the search is
+ * O(n) per blossom.
+ *
+ * Included via #[path = "../../common/rust/dot.rs"] mod dot;
+ */
+
+#![allow(dead_code)]
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+pub const DOT_USAGE: &str = "[--dot FILE]";
+
+const NONE: usize = usize::MAX;
+
+/* A vertex's label in the search of blossoms(). */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Label {
+    Even,    /* an even alternating path from a free vertex ends here */
+    Odd,     /* only an odd one does */
+    Outside, /* the search never reaches it */
+}
+
+/* The search forest of blossoms(): e-maxx style, parent[] on the ODD
+ * vertices and base[] the base of each vertex's outermost blossom. */
+struct Search<'a> {
+    adj: &'a [Vec<usize>],
+    mate: &'a [usize],
+    label: Vec<Label>,
+    root: Vec<usize>,
+    parent: Vec<usize>,
+    base: Vec<usize>,
+    queue: VecDeque<usize>,
+}
+
+impl<'a> Search<'a> {
+    /* The base of the smallest blossom holding both v and w, one tree. */
+    fn lca(&self, mut v: usize, mut w: usize) -> usize {
+        let mut seen = vec![false; self.adj.len()];
+        loop {
+            v = self.base[v];
+            seen[v] = true;
+            if self.mate[v] == NONE {
+                break;
+            }
+            v = self.parent[self.mate[v]];
+        }
+        loop {
+            w = self.base[w];
+            if seen[w] {
+                return w;
+            }
+            w = self.parent[self.mate[w]];
+        }
+    }
+
+    /* Marks the bases on the path from v down to the blossom base b, and
+     * points the ODD vertices on it back across the bridge. */
+    fn mark_path(&mut self, mut v: usize, b: usize, mut child: usize, in_blossom: &mut [bool]) {
+        while self.base[v] != b {
+            in_blossom[self.base[v]] = true;
+            in_blossom[self.base[self.mate[v]]] = true;
+            self.parent[v] = child;
+            child = self.mate[v];
+            v = self.parent[self.mate[v]];
+        }
+    }
+
+    fn contract(&mut self, v: usize, w: usize) {
+        let b = self.lca(v, w);
+        let mut in_blossom = vec![false; self.adj.len()];
+        self.mark_path(v, b, w, &mut in_blossom);
+        self.mark_path(w, b, v, &mut in_blossom);
+        for x in 0..self.adj.len() {
+            if in_blossom[self.base[x]] {
+                self.base[x] = b;
+                if self.label[x] == Label::Odd {
+                    self.label[x] = Label::Even;
+                    self.queue.push_back(x);
+                }
+            }
+        }
+    }
+}
+
+/* (label, base of the outermost blossom) per vertex, for the matching
+ * `pairs` of the graph (n, edges). A vertex outside every blossom is its
+ * own base. Two trees meeting (an augmenting path, when the matching is
+ * not maximum) are left apart. */
+pub fn blossoms(n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)]) -> (Vec<Label>, Vec<usize>) {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    let mut mate = vec![NONE; n];
+    for &(u, v) in pairs {
+        mate[u] = v;
+        mate[v] = u;
+    }
+    let mut s = Search {
+        adj: &adj, mate: &mate, label: vec![Label::Outside; n], root: vec![NONE; n], parent: vec![NONE; n],
+        base: (0..n).collect(), queue: VecDeque::new(),
+    };
+    for (v, &m) in mate.iter().enumerate() {
+        if m == NONE {
+            s.label[v] = Label::Even;
+            s.root[v] = v;
+            s.queue.push_back(v);
+        }
+    }
+    while let Some(v) = s.queue.pop_front() {
+        for &w in &adj[v] {
+            if s.base[v] == s.base[w] || mate[v] == w {
+                continue;
+            }
+            match s.label[w] {
+                Label::Outside => {
+                    /* w is matched: a free vertex would be EVEN */
+                    s.label[w] = Label::Odd;
+                    s.parent[w] = v;
+                    s.root[w] = s.root[v];
+                    let x = mate[w];
+                    s.label[x] = Label::Even;
+                    s.root[x] = s.root[v];
+                    s.queue.push_back(x);
+                }
+                Label::Even if s.root[w] == s.root[v] => s.contract(v, w),
+                _ => {}
+            }
+        }
+    }
+    (s.label, s.base)
+}
+
+/* The outermost blossoms of blossoms(), more than one vertex each: the
+ * base and the vertices, in id order. */
+pub fn clusters(base: &[usize]) -> Vec<(usize, Vec<usize>)> {
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (v, &b) in base.iter().enumerate() {
+        groups.entry(b).or_default().push(v);
+    }
+    groups.into_iter().filter(|(_, vs)| vs.len() > 1).collect()
+}
+
+/* The graph in DOT. `left` numbers a bipartite graph as Hopcroft-Karp
+ * does (right vertex v is left + v) and names its vertices l0.., r0..
+ * in two ranks; a general graph gets the blossom clusters. */
+pub fn write_dot(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)], left: Option<usize>,
+) -> io::Result<()> {
+    let name = |v: usize| match left {
+        Some(l) if v < l => format!("l{}", v),
+        Some(l) => format!("r{}", v - l),
+        None => v.to_string(),
+    };
+    let mut matched = vec![false; n];
+    for &(u, v) in pairs {
+        matched[u] = true;
+        matched[v] = true;
+    }
+    writeln!(out, "graph matching {{")?;
+    writeln!(out, "  label=\"{} vertices, {} pairs\";", n, pairs.len())?;
+    writeln!(out, "  node [shape=circle];")?;
+    match left {
+        Some(l) => {
+            for (side, range) in [(0, 0..l), (1, l..n)].iter().cloned() {
+                write!(out, "  subgraph side{} {{ rank=same;", side)?;
+                for v in range {
+                    write!(out, " {};", name(v))?;
+                }
+                writeln!(out, " }}")?;
+            }
+        }
+        None => {
+            let (_, base) = blossoms(n, edges, pairs);
+            for (b, vs) in clusters(&base) {
+                write!(out, "  subgraph cluster_{} {{ label=\"blossom {}\"; style=dashed;", b, b)?;
+                for v in vs {
+                    write!(out, " {};", v)?;
+                }
+                writeln!(out, " }}")?;
+            }
+        }
+    }
+    for (v, &m) in matched.iter().enumerate() {
+        if !m {
+            writeln!(out, "  {} [shape=doublecircle];", name(v))?;
+        }
+    }
+    let mut seen: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    seen.sort_unstable();
+    seen.dedup();
+    let mut is_pair: Vec<(usize, usize)> = pairs.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    is_pair.sort_unstable();
+    for (u, v) in seen {
+        if is_pair.binary_search(&(u, v)).is_ok() {
+            writeln!(out, "  {} -- {} [color=red, style=bold, penwidth=3];", name(u), name(v))?;
+        } else {
+            writeln!(out, "  {} -- {};", name(u), name(v))?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/* write_dot to the file `path`. */
+pub fn save(path: &str, n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)], left: Option<usize>) -> Result<(), String> {
+    let mut w = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+    write_dot(&mut w, n, edges, pairs, left).and_then(|_| w.flush()).map_err(|e| format!("{}: {}", path, e))
+}
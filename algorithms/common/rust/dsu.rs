@@ -0,0 +1,188 @@
+/*
+ * Disjoint-set union (union-find) for blossom bases and other partitions
+ * of 0..n.
+ *
+ * Dsu: path-halving find. Three ways to join two sets:
+ *   - union(a, b): a's root goes under b's root (b's representative wins);
+ *   - union_into(a, r): the merged set is represented by r itself, as the
+ *     blossom code needs (the base of a contracted blossom is its name);
+ *   - union_by_size(a, b): the smaller set goes under the larger, so finds
+ *     stay O(log n) even without the halving.
+ * make_rep(v) moves the representative of v's set to v. Parents and sizes
+ * are kept as u32, like the solvers' Vertex ids (types.rs): 8 bytes a
+ * vertex instead of 16, and Gabow optimized keeps three of these.
+ *
+ * RollbackDsu: union by size and no path compression, so a union is one
+ * parent write that can be undone. snapshot() marks the current state and
+ * rollback(mark) undoes every union made since, newest first; finds are
+ * O(log n). For expanding a blossom in the middle of a phase without
+ * rebuilding the partition.
+ *
+ * Included via #[path = "../../common/rust/dsu.rs"] mod dsu;
+ */
+
+#![allow(dead_code)]
+
+pub struct Dsu {
+    parent: Vec<u32>,
+    size: Vec<u32>, /* valid at roots */
+}
+
+impl Dsu {
+    pub fn new(n: usize) -> Self {
+        debug_assert!(n <= u32::MAX as usize, "{} elements do not fit a u32 Dsu", n);
+        Dsu { parent: (0..n as u32).collect(), size: vec![1; n] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /* Every vertex on its own again. */
+    pub fn reset(&mut self) {
+        for (v, p) in self.parent.iter_mut().enumerate() {
+            *p = v as u32;
+        }
+        self.size.fill(1);
+    }
+
+    /* Make v a singleton without touching the rest of its set: only sound
+     * when every member of that set is detached too. */
+    pub fn detach(&mut self, v: usize) {
+        self.parent[v] = v as u32;
+        self.size[v] = 1;
+    }
+
+    pub fn find(&mut self, v: usize) -> usize {
+        let mut v = v as u32;
+        while self.parent[v as usize] != v {
+            let p = self.parent[v as usize];
+            self.parent[v as usize] = self.parent[p as usize];
+            v = self.parent[v as usize];
+        }
+        v as usize
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size_of(&mut self, v: usize) -> usize {
+        let r = self.find(v);
+        self.size[r] as usize
+    }
+
+    /* Join the sets of a and b under b's representative; returns it. */
+    pub fn union(&mut self, a: usize, b: usize) -> usize {
+        let fa = self.find(a);
+        let fb = self.find(b);
+        if fa != fb {
+            self.parent[fa] = fb as u32;
+            self.size[fb] += self.size[fa];
+        }
+        fb
+    }
+
+    /* Join the sets of a and r, represented by r. */
+    pub fn union_into(&mut self, a: usize, r: usize) {
+        let fa = self.find(a);
+        let fr = self.find(r);
+        if fr != r {
+            self.parent[fr] = r as u32;
+            self.parent[r] = r as u32;
+            self.size[r] = self.size[fr];
+        }
+        if fa != fr {
+            self.parent[fa] = r as u32;
+            self.size[r] += self.size[fa];
+        }
+    }
+
+    /* Join the sets of a and b, the smaller under the larger (b's root on
+     * a tie); returns the new representative. */
+    pub fn union_by_size(&mut self, a: usize, b: usize) -> usize {
+        let fa = self.find(a);
+        let fb = self.find(b);
+        if fa != fb && self.size[fa] > self.size[fb] {
+            self.union(fb, fa)
+        } else {
+            self.union(fa, fb)
+        }
+    }
+
+    pub fn make_rep(&mut self, v: usize) {
+        let r = self.find(v);
+        if r != v {
+            self.parent[r] = v as u32;
+            self.parent[v] = v as u32;
+            self.size[v] = self.size[r];
+        }
+    }
+}
+
+pub struct RollbackDsu {
+    parent: Vec<usize>,
+    size: Vec<usize>,    /* valid at roots */
+    history: Vec<usize>, /* roots linked under another, oldest first */
+}
+
+impl RollbackDsu {
+    pub fn new(n: usize) -> Self {
+        RollbackDsu { parent: (0..n).collect(), size: vec![1; n], history: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    /* v's parent in the forest, v itself at a root. */
+    pub fn parent(&self, v: usize) -> usize {
+        self.parent[v]
+    }
+
+    pub fn find(&self, mut v: usize) -> usize {
+        while self.parent[v] != v {
+            v = self.parent[v];
+        }
+        v
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size_of(&self, v: usize) -> usize {
+        self.size[self.find(v)]
+    }
+
+    /* Join the sets of a and b by size; false if they were one already
+     * (nothing is recorded then). */
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let mut fa = self.find(a);
+        let mut fb = self.find(b);
+        if fa == fb {
+            return false;
+        }
+        if self.size[fa] > self.size[fb] {
+            std::mem::swap(&mut fa, &mut fb);
+        }
+        self.parent[fa] = fb;
+        self.size[fb] += self.size[fa];
+        self.history.push(fa);
+        true
+    }
+
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /* Undo the unions made since snapshot() returned `mark`. */
+    pub fn rollback(&mut self, mark: usize) {
+        while self.history.len() > mark {
+            let c = self.history.pop().unwrap();
+            let p = self.parent[c];
+            self.size[p] -= self.size[c];
+            self.parent[c] = c;
+        }
+    }
+}
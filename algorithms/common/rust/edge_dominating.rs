@@ -0,0 +1,160 @@
+/*
+ * Minimum edge dominating set: a smallest edge set D such that every edge
+ * shares an endpoint with an edge of D. Equivalently, the endpoints V(D)
+ * form a vertex cover. NP-hard; this is the matching-based 2-approximation
+ * with a local improvement on top.
+ *
+ * Any maximal matching M dominates every edge (an undominated edge could be
+ * added to M), and |M| <= 2 OPT: an edge of D touches at most two edges of
+ * a matching, so nu <= 2 OPT. A maximum matching is maximal too, and gives
+ * the better lower bound ceil(nu / 2).
+ *
+ * Improvement. For a vertex cover C, a maximum matching of G[C] plus one
+ * edge per C vertex it leaves exposed is an edge dominating set of
+ * |C| - nu(G[C]) edges, and some minimum cover gives OPT that way. Starting
+ * from C = V(M) (|C| - nu(G[C]) = |M|), drop each cover vertex whose
+ * neighbours are all in C (lowest degree first): |C| falls by one and nu by
+ * at most one, so the set never grows. Repeat from the new endpoints while
+ * the set shrinks.
+ *
+ * Self-loops and out-of-range endpoints are ignored.
+ *
+ * Included via #[path = "../../common/rust/edge_dominating.rs"] mod edge_dominating;
+ * next to matchers.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::matchers;
+
+const NIL: usize = usize::MAX;
+
+pub struct EdgeDominating {
+    pub edges: Vec<(usize, usize)>, /* sorted, u < v */
+    pub matching_size: usize,       /* the starting matching, itself dominating */
+    pub lower_bound: usize,         /* ceil(matching_size / 2) <= OPT */
+    pub rounds: usize,              /* improvement rounds that shrank the set */
+}
+
+fn adjacency(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    adj
+}
+
+/* Greedy maximal matching, edges with the most neighbours first: an edge
+ * whose ends have high degree dominates many others. */
+pub fn maximal_matching(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let adj = adjacency(n, edges);
+    let mut order: Vec<(usize, usize)> = Vec::new();
+    for (u, nbrs) in adj.iter().enumerate() {
+        for &v in nbrs {
+            if u < v { order.push((u, v)); }
+        }
+    }
+    order.sort_by_key(|&(u, v)| (std::cmp::Reverse(adj[u].len() + adj[v].len()), u, v));
+    let mut matched = vec![false; n];
+    let mut matching = Vec::new();
+    for (u, v) in order {
+        if !matched[u] && !matched[v] {
+            matched[u] = true;
+            matched[v] = true;
+            matching.push((u, v));
+        }
+    }
+    matching.sort_unstable();
+    matching
+}
+
+/* One improvement round on the cover V(set): prune, rematch, extend. */
+fn improve_once(
+    algo: &str, n: usize, adj: &[Vec<usize>], set: &[(usize, usize)],
+) -> Result<Vec<(usize, usize)>, String> {
+    let mut in_cover = vec![false; n];
+    for &(u, v) in set {
+        in_cover[u] = true;
+        in_cover[v] = true;
+    }
+    let mut order: Vec<usize> = (0..n).filter(|&v| in_cover[v]).collect();
+    order.sort_by_key(|&v| (adj[v].len(), v));
+    for v in order {
+        if adj[v].iter().all(|&w| in_cover[w]) {
+            in_cover[v] = false;
+        }
+    }
+
+    let inner: Vec<(usize, usize)> = (0..n)
+        .filter(|&u| in_cover[u])
+        .flat_map(|u| adj[u].iter().filter(move |&&v| u < v).map(move |&v| (u, v)))
+        .filter(|&(_, v)| in_cover[v])
+        .collect();
+    let mut result = matchers::maximum_matching(algo, n, &inner)?;
+    let mut mate = vec![NIL; n];
+    for &(u, v) in &result {
+        mate[u] = v;
+        mate[v] = u;
+    }
+    /* An exposed cover vertex kept a neighbour outside the cover when it
+     * was checked; any neighbour will do. */
+    for v in 0..n {
+        if in_cover[v] && mate[v] == NIL {
+            let w = adj[v][0];
+            result.push((v.min(w), v.max(w)));
+        }
+    }
+    result.sort_unstable();
+    Ok(result)
+}
+
+/* Edge dominating set from a maximum matching (`maximum`, found by `algo`)
+ * or from the greedy maximal matching, optionally improved; `algo` also
+ * rematches the covers of the improvement rounds. */
+pub fn edge_dominating_set(
+    algo: &str, n: usize, edges: &[(usize, usize)], maximum: bool, improve: bool,
+) -> Result<EdgeDominating, String> {
+    let mut set = if maximum {
+        matchers::maximum_matching(algo, n, edges)?
+    } else {
+        maximal_matching(n, edges)
+    };
+    let matching_size = set.len();
+    let mut rounds = 0;
+    if improve {
+        let adj = adjacency(n, edges);
+        loop {
+            let next = improve_once(algo, n, &adj, &set)?;
+            if next.len() >= set.len() { break; }
+            set = next;
+            rounds += 1;
+        }
+    }
+    Ok(EdgeDominating { edges: set, matching_size, lower_bound: matching_size.div_ceil(2), rounds })
+}
+
+/* Edges of `set` that are not graph edges, and graph edges it leaves
+ * undominated. */
+pub fn check_edge_dominating(n: usize, edges: &[(usize, usize)], set: &[(usize, usize)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let adj = adjacency(n, edges);
+    let mut touched = vec![false; n];
+    for &(u, v) in set {
+        if u >= n || v >= n || adj[u].binary_search(&v).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+            continue;
+        }
+        touched[u] = true;
+        touched[v] = true;
+    }
+    let missed: Vec<&(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v && !touched[u] && !touched[v]).collect();
+    if let Some(&&(u, v)) = missed.first() {
+        errors.push(format!("{} edge(s) undominated, e.g. ({}, {})!", missed.len(), u, v));
+    }
+    errors
+}
@@ -0,0 +1,157 @@
+/*
+ * All maximum matchings of a graph, one at a time, for `combi enumerate`:
+ * which pairs every optimum of an assignment shares and which have
+ * alternatives, for sensitivity analysis.
+ *
+ * Binary partition, as in Uno's enumeration algorithms. A node of the
+ * search tree is the set of maximum matchings that hold some forced pairs
+ * and avoid some forbidden edges, with one member M known. A pair uv of M
+ * not forced splits it in two: the matchings holding uv, of which M is one,
+ * and those avoiding it. For the second, drop uv from M and forbid the
+ * edge. M - uv is one pair short, and a matching of the full size avoids uv
+ * iff an augmenting path exists; it ends at u or v, as M was maximum. So
+ * an Edmonds search from u, then from v, over the vertices not forced
+ * (tie_break.rs's Refiner) finds the new member or shows the half empty.
+ * Each matching is listed once, at the node where it first appears, and a
+ * node whose pairs are all forced holds M alone.
+ *
+ * The walk is depth first, on an explicit stack so that the iterator can
+ * stop between two matchings; the search's changes to M are logged and
+ * undone on the way back. A listed matching is the known member of at most
+ * nu + 1 nodes (its own and those holding it by forced pairs), each split
+ * by at most two searches: O(nu E) time per matching, and a stack of
+ * O(nu) steps.
+ *
+ * Included via #[path = "../../common/rust/enumerate_matchings.rs"] mod enumerate_matchings;
+ * next to tie_break.rs and dsu.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::mem;
+
+use super::tie_break::{Refiner, NIL};
+
+/* The number of matchings listed when no limit is given. */
+pub const DEFAULT_LIMIT: usize = 1000;
+
+enum Step {
+    /* split the current node on its first pair not forced */
+    Split,
+    /* back from the matchings avoiding uv: undo the search, then force uv */
+    Avoided(usize, usize, Vec<(usize, usize)>),
+    /* back from the matchings holding uv */
+    Held(usize, usize),
+}
+
+/* The maximum matchings of a graph as an iterator of sorted pair lists
+ * (u < v), the starting one first, at most `limit` of them. */
+pub struct MaximumMatchings {
+    r: Refiner,
+    stack: Vec<Step>,
+    started: bool,
+    left: usize,
+    pub searches: usize, /* augmenting path searches so far */
+}
+
+impl MaximumMatchings {
+    /* `matching` must be a maximum matching of (n, edges); with a smaller
+     * one the matchings listed are those of its size that it leads to.
+     * Self-loops, repeated edges and out-of-range endpoints are ignored. */
+    pub fn new(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)], limit: usize) -> MaximumMatchings {
+        let mut r = Refiner::new(n, edges, matching);
+        r.logging = true;
+        MaximumMatchings { r, stack: Vec::new(), started: false, left: limit, searches: 0 }
+    }
+
+    /* True once every maximum matching has been listed; false while the
+     * walk is on, or after the limit stopped it. */
+    pub fn exhausted(&self) -> bool {
+        self.started && self.stack.is_empty()
+    }
+
+    fn pairs(&self) -> Vec<(usize, usize)> {
+        let mate = &self.r.mate;
+        (0..mate.len()).filter(|&u| mate[u] != NIL && u < mate[u]).map(|u| (u, mate[u])).collect()
+    }
+
+    fn emit(&mut self) -> Vec<(usize, usize)> {
+        self.left -= 1;
+        self.pairs()
+    }
+
+    fn forbid(&mut self, u: usize, v: usize) {
+        for &(a, b) in &[(u, v), (v, u)] {
+            if let Ok(i) = self.r.adj[a].binary_search(&b) {
+                self.r.adj[a].remove(i);
+            }
+        }
+    }
+
+    fn allow(&mut self, u: usize, v: usize) {
+        for &(a, b) in &[(u, v), (v, u)] {
+            if let Err(i) = self.r.adj[a].binary_search(&b) {
+                self.r.adj[a].insert(i, b);
+            }
+        }
+    }
+
+    fn set_fixed(&mut self, u: usize, v: usize, fixed: bool) {
+        self.r.fixed[u] = fixed;
+        self.r.fixed[v] = fixed;
+    }
+}
+
+impl Iterator for MaximumMatchings {
+    type Item = Vec<(usize, usize)>;
+
+    fn next(&mut self) -> Option<Vec<(usize, usize)>> {
+        if self.left == 0 {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            self.stack.push(Step::Split);
+            return Some(self.emit());
+        }
+        while let Some(step) = self.stack.pop() {
+            match step {
+                Step::Split => {
+                    let n = self.r.mate.len();
+                    let u = match (0..n).find(|&u| !self.r.fixed[u] && self.r.mate[u] != NIL) {
+                        Some(u) => u,
+                        None => continue,
+                    };
+                    let v = self.r.mate[u];
+                    self.forbid(u, v);
+                    self.r.mate[u] = NIL;
+                    self.r.mate[v] = NIL;
+                    self.r.log.clear();
+                    self.searches += 1;
+                    let found = self.r.augment_from(u) || {
+                        self.searches += 1;
+                        self.r.augment_from(v)
+                    };
+                    let log = mem::take(&mut self.r.log);
+                    self.stack.push(Step::Avoided(u, v, log));
+                    if found {
+                        self.stack.push(Step::Split);
+                        return Some(self.emit());
+                    }
+                }
+                Step::Avoided(u, v, log) => {
+                    for &(x, m) in log.iter().rev() {
+                        self.r.mate[x] = m;
+                    }
+                    self.allow(u, v);
+                    self.r.pair(u, v);
+                    self.set_fixed(u, v, true);
+                    self.stack.push(Step::Held(u, v));
+                    self.stack.push(Step::Split);
+                }
+                Step::Held(u, v) => self.set_fixed(u, v, false),
+            }
+        }
+        None
+    }
+}
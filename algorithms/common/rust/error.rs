@@ -0,0 +1,152 @@
+/*
+ * The suite's error type: why a graph could not be read, or could not be
+ * solved by the algorithm asked for.
+ *
+ * The loaders (graph_io.rs) and the vertex-count check in front of the
+ * solvers (matchers::check_vertex_count) return a SuiteError; the
+ * binaries print it as "Error: ..." and exit with exit_code(), so a
+ * script can tell a missing file from a malformed one from a graph too
+ * large for the solver without reading stderr.
+ *
+ *   66  the input cannot be opened (missing, unreadable)     EX_NOINPUT
+ *   74  reading it failed (I/O, a failed decompressor)       EX_IOERR
+ *   65  it is not a valid graph file                         EX_DATAERR
+ *   69  the graph is too large for the algorithm             EX_UNAVAILABLE
+ *    1  anything else: a bad flag, a refused option, ...
+ *
+ * The codes are those of BSD's sysexits.h; 128 + the signal for an
+ * interrupted run stays with interrupt::exit_code. The messages are the
+ * ones the loaders have always printed (a bad number now names its line);
+ * Other carries any other refusal (a plain String converts to it), so a
+ * command's run() can return a SuiteError throughout.
+ *
+ * Included via #[path = "../../common/rust/error.rs"] mod error;
+ * wherever graph_io.rs or matchers.rs is.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+pub const EX_DATAERR: i32 = 65;
+pub const EX_NOINPUT: i32 = 66;
+pub const EX_UNAVAILABLE: i32 = 69;
+pub const EX_IOERR: i32 = 74;
+
+#[derive(Debug)]
+pub enum SuiteError {
+    /* Opening or reading the input. */
+    Io(io::Error),
+    /* The first data line is not the header the format needs. */
+    ParseHeader(String),
+    /* An edge line that does not read: a bad number, a missing weight, or
+     * a line --strict-format refuses. */
+    ParseEdge { line: usize, what: String },
+    /* An edge endpoint past the header's bound, under --strict. */
+    VertexOutOfRange {
+        line: usize,        /* 1-based line number in the file */
+        vertex: usize,
+        side: &'static str, /* "n", "left" or "right" */
+        bound: usize,
+    },
+    /* No header at all: the file is empty, or only comments. */
+    EmptyGraph,
+    /* The header's m against the edge lines read, under --on-mismatch
+     * error; `found` is None when the file has more. */
+    EdgeCount { declared: usize, found: Option<usize> },
+    /* Parallel edges between u and v under --merge-parallel error. */
+    ParallelEdge { u: usize, v: usize },
+    /* A binary, JSON, GraphML or SNAP input that does not hold together,
+     * or a graph the loader cannot represent. */
+    Format(String),
+    /* An odd cycle, first vertex repeated at the end, in a graph read as
+     * bipartite from the general format (graph_io::bipartition). */
+    NotBipartite { cycle: Vec<usize> },
+    /* More vertices than the algorithm's ids can number. */
+    TooManyVertices { n: usize, ids: String, algo: String },
+    /* Any other refusal. */
+    Other(String),
+}
+
+impl SuiteError {
+    pub fn exit_code(&self) -> i32 {
+        match *self {
+            SuiteError::Io(ref e) => match e.kind() {
+                io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => EX_NOINPUT,
+                _ => EX_IOERR,
+            },
+            SuiteError::ParseHeader(_)
+            | SuiteError::ParseEdge { .. }
+            | SuiteError::VertexOutOfRange { .. }
+            | SuiteError::EmptyGraph
+            | SuiteError::EdgeCount { .. }
+            | SuiteError::ParallelEdge { .. }
+            | SuiteError::NotBipartite { .. }
+            | SuiteError::Format(_) => EX_DATAERR,
+            SuiteError::TooManyVertices { .. } => EX_UNAVAILABLE,
+            SuiteError::Other(_) => 1,
+        }
+    }
+}
+
+impl fmt::Display for SuiteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SuiteError::Io(ref e) => write!(f, "{}", e),
+            SuiteError::ParseHeader(ref what) => write!(f, "{}", what),
+            SuiteError::ParseEdge { line, ref what } => write!(f, "line {}: {}", line, what),
+            SuiteError::VertexOutOfRange { line, vertex, side, bound } => {
+                write!(f, "line {}: vertex {} out of range ({} = {}); use --auto-grow to extend the graph",
+                       line, vertex, side, bound)
+            }
+            SuiteError::EmptyGraph => write!(f, "Empty file"),
+            SuiteError::EdgeCount { declared, found: None } => {
+                write!(f, "header declares {} edges but the file has more", declared)
+            }
+            SuiteError::EdgeCount { declared, found: Some(found) } => {
+                write!(f, "header declares {} edges but the file has only {} (truncated download?)", declared, found)
+            }
+            SuiteError::ParallelEdge { u, v } => {
+                write!(f, "parallel edges between {} and {}; use --merge-parallel keep|max|min|sum", u, v)
+            }
+            SuiteError::Format(ref what) => write!(f, "{}", what),
+            SuiteError::NotBipartite { ref cycle } => {
+                let ids: Vec<String> = cycle.iter().map(|v| v.to_string()).collect();
+                write!(f, "the graph is not bipartite: odd cycle {} (length {})", ids.join(" - "), cycle.len() - 1)
+            }
+            SuiteError::TooManyVertices { n, ref ids, ref algo } => {
+                write!(f, "{} vertices exceeds the {} vertex ids of {}", n, ids, algo)
+            }
+            SuiteError::Other(ref what) => write!(f, "{}", what),
+        }
+    }
+}
+
+impl Error for SuiteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            SuiteError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SuiteError {
+    fn from(e: io::Error) -> SuiteError {
+        SuiteError::Io(e)
+    }
+}
+
+impl From<String> for SuiteError {
+    fn from(what: String) -> SuiteError {
+        SuiteError::Other(what)
+    }
+}
+
+impl<'a> From<&'a str> for SuiteError {
+    fn from(what: &'a str) -> SuiteError {
+        SuiteError::Other(what.to_string())
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * f-factors via perfect matching (Tutte's gadget).
+ *
+ * Given G = (V, E) and a degree f(v) per vertex, find an edge subset F
+ * (each edge at most once) with deg_F(v) = f(v) for every v, or report
+ * that none exists. f = 1 is a perfect matching, f = 2 a 2-factor.
+ *
+ * Reduction (Tutte 1954): vertex v of degree d(v) becomes an outer node
+ * v_e for each incident edge e and d(v) - f(v) inner nodes, with every
+ * outer node joined to every inner one. Each edge e = uv becomes the edge
+ * u_e v_e. A perfect matching of G' matches all inner nodes of v, so
+ * exactly f(v) outer nodes of v are left to be matched across, and
+ *     F = { e : u_e v_e is matched }
+ * is an f-factor; any f-factor extends to a perfect matching the same
+ * way. Before building G', a vertex with f(v) > d(v) or an odd degree sum
+ * already rules a factor out.
+ *
+ * G' has sum(2 d(v) - f(v)) nodes and m + sum(d(v) (d(v) - f(v))) edges,
+ * so it suits sparse graphs or degrees close to f.
+ *
+ * Included via #[path = "../../common/rust/f_factor.rs"] mod f_factor;
+ * together with matchers.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+const NIL: usize = usize::MAX;
+
+pub struct FFactor {
+    pub n: usize,
+    pub f: Vec<usize>,
+    /* distinct edges (u < v), no loops, no out-of-range ids */
+    pub edges: Vec<(usize, usize)>,
+    /* gadget graph G'; empty when `obstacle` is set */
+    pub gadget_n: usize,
+    pub gadget: Vec<(usize, usize)>,
+    /* why no factor exists, found before matching */
+    pub obstacle: Option<String>,
+}
+
+pub enum Outcome {
+    Factor(Vec<(usize, usize)>),
+    /* the reason, e.g. the perfect matching G' lacks */
+    Infeasible(String),
+}
+
+impl FFactor {
+    /* f[v] for v < f.len(); vertices past the end get `default`. */
+    pub fn new(n: usize, edges: &[(usize, usize)], f: &[usize], default: usize) -> Self {
+        let mut list: Vec<(usize, usize)> = edges.iter()
+            .filter(|&&(u, v)| u < n && v < n && u != v)
+            .map(|&(u, v)| if u < v { (u, v) } else { (v, u) })
+            .collect();
+        list.sort_unstable();
+        list.dedup();
+        let f: Vec<usize> = (0..n).map(|v| f.get(v).cloned().unwrap_or(default)).collect();
+
+        let mut deg = vec![0usize; n];
+        for &(u, v) in &list {
+            deg[u] += 1;
+            deg[v] += 1;
+        }
+        let mut ff = FFactor { n, f, edges: list, gadget_n: 0, gadget: Vec::new(), obstacle: None };
+        if let Some(v) = (0..n).find(|&v| ff.f[v] > deg[v]) {
+            ff.obstacle = Some(format!("vertex {} needs degree {} but has only {} neighbours", v, ff.f[v], deg[v]));
+            return ff;
+        }
+        let total: usize = ff.f.iter().sum();
+        if total % 2 == 1 {
+            ff.obstacle = Some(format!("the degrees sum to {}, which is odd", total));
+            return ff;
+        }
+
+        /* outer nodes of v: outer[v] .., one per incident edge in list
+         * order; inner nodes: inner[v] .. inner[v] + d(v) - f(v) */
+        let mut outer = vec![0usize; n];
+        let mut inner = vec![0usize; n];
+        let mut next = 0;
+        for v in 0..n {
+            outer[v] = next;
+            inner[v] = next + deg[v];
+            next += 2 * deg[v] - ff.f[v];
+        }
+        let mut used = vec![0usize; n];
+        for &(u, v) in &ff.edges {
+            let (a, b) = (outer[u] + used[u], outer[v] + used[v]);
+            used[u] += 1;
+            used[v] += 1;
+            ff.gadget.push((a, b));
+        }
+        for v in 0..n {
+            for a in outer[v]..inner[v] {
+                for b in inner[v]..inner[v] + deg[v] - ff.f[v] {
+                    ff.gadget.push((a, b));
+                }
+            }
+        }
+        ff.gadget_n = next;
+        ff
+    }
+}
+
+/* The f-factor of ff through the general matcher `algo`, or why there is
+ * none. Also returns the size of the gadget matching. */
+pub fn f_factor(algo: &str, ff: &FFactor) -> Result<(Outcome, usize), String> {
+    if let Some(ref why) = ff.obstacle {
+        return Ok((Outcome::Infeasible(why.clone()), 0));
+    }
+    let matching = super::matchers::maximum_matching(algo, ff.gadget_n, &ff.gadget)?;
+    if 2 * matching.len() < ff.gadget_n {
+        return Ok((Outcome::Infeasible(format!("the gadget graph has no perfect matching ({} of {} nodes matched)",
+                                               2 * matching.len(), ff.gadget_n)), matching.len()));
+    }
+    /* the first m gadget edges are the u_e v_e links, in edge order; two
+     * outer nodes are adjacent only through their link */
+    let mut edge_of = vec![NIL; ff.gadget_n];
+    for (i, &(a, b)) in ff.gadget[..ff.edges.len()].iter().enumerate() {
+        edge_of[a] = i;
+        edge_of[b] = i;
+    }
+    let mut factor: Vec<(usize, usize)> = matching.iter()
+        .filter(|&&(a, b)| edge_of[a] != NIL && edge_of[b] != NIL)
+        .map(|&(a, _)| ff.edges[edge_of[a]])
+        .collect();
+    factor.sort_unstable();
+    Ok((Outcome::Factor(factor), matching.len()))
+}
+
+/* Problems with `selected` as an f-factor of ff (empty if none). */
+pub fn check_f_factor(ff: &FFactor, selected: &[(usize, usize)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut deg = vec![0usize; ff.n];
+    let mut seen: Vec<(usize, usize)> = Vec::with_capacity(selected.len());
+    for &(u, v) in selected {
+        let e = if u < v { (u, v) } else { (v, u) };
+        if ff.edges.binary_search(&e).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+            continue;
+        }
+        seen.push(e);
+        deg[u] += 1;
+        deg[v] += 1;
+    }
+    seen.sort_unstable();
+    for w in seen.windows(2) {
+        if w[0] == w[1] {
+            errors.push(format!("Edge ({}, {}) selected twice!", w[0].0, w[0].1));
+        }
+    }
+    for (v, (&d, &f)) in deg.iter().zip(&ff.f).enumerate() {
+        if d != f {
+            errors.push(format!("Vertex {} has degree {} instead of {}!", v, d, f));
+        }
+    }
+    errors
+}
+
+/* Degree file: one "v f" pair per line, '#' starts a comment. Vertices
+ * not listed get `default`. */
+pub fn load_degrees(
+    filename: &str, n: usize, default: usize,
+) -> Result<Vec<usize>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut f = vec![default; n];
+    let mut listed = vec![false; n];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() { continue; }
+        if parts.len() != 2 {
+            return Err(format!("{}:{}: expected 'vertex degree', got '{}'",
+                               filename, i + 1, raw.trim()).into());
+        }
+        let v: usize = parts[0].parse()?;
+        let d: usize = parts[1].parse()?;
+        if v >= n {
+            return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, i + 1, v, n).into());
+        }
+        if listed[v] {
+            return Err(format!("{}:{}: vertex {} listed twice", filename, i + 1, v).into());
+        }
+        listed[v] = true;
+        f[v] = d;
+    }
+    Ok(f)
+}
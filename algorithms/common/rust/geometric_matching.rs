@@ -0,0 +1,291 @@
+/*
+ * Minimum-weight perfect matching of points in the plane without building
+ * the complete graph.
+ *
+ * Weights are TSPLIB EUC_2D distances, nint(sqrt(dx^2 + dy^2)). Optimal
+ * matchings use short edges, so the solver starts from the k-nearest-
+ * neighbour graph (a uniform grid finds the neighbours) and certifies the
+ * result afterwards with the blossom duals:
+ *   - the matcher maximizes w' = W + 1 - d over perfect matchings and ends
+ *     with vertex duals y (scaled by 2) such that y_u + y_v >= 2 w'(uv) on
+ *     every candidate edge, blossom duals only adding to the left side;
+ *   - if that inequality also holds for every pair outside the candidate
+ *     set, the duals are feasible for the complete graph and the matching
+ *     is optimal there too;
+ *   - otherwise the violated pairs join the candidate set and the matching
+ *     is solved again.
+ * With rho_v = (W + 1 - y_v) / 2 a pair can only violate when
+ * d(u, v) < rho_u + rho_v, so the check is a radius query on the grid.
+ * A candidate graph without a perfect matching (say, clusters of odd size)
+ * gets nearest-neighbour edges among the points it leaves exposed.
+ *
+ * Included via #[path = "../../common/rust/geometric_matching.rs"]
+ * mod geometric_matching; next to weighted_blossom.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+use super::weighted_blossom::WeightedBlossom;
+
+pub const DEFAULT_K: usize = 8;
+
+pub struct GeometricMatching {
+    pub pairs: Vec<(usize, usize)>, /* sorted, u < v */
+    pub weight: i64,
+    pub candidates: usize, /* candidate edges in the final round */
+    pub rounds: usize,     /* blossom runs */
+    pub added: usize,      /* pairs added by the dual check */
+    pub linked: usize,     /* edges added among exposed points */
+}
+
+/* EUC_2D distance. */
+pub fn distance(a: (f64, f64), b: (f64, f64)) -> i64 {
+    let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+    ((dx * dx + dy * dy).sqrt() + 0.5).floor() as i64
+}
+
+/* Format: a line "n", then n lines "x y". Blank lines and '#' comments are
+ * skipped. */
+pub fn load_points(filename: &str) -> Result<Vec<(f64, f64)>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut lines = text.lines().enumerate()
+        .map(|(i, raw)| (i + 1, raw.split('#').next().unwrap_or("").trim()))
+        .filter(|&(_, l)| !l.is_empty());
+    let (_, first) = lines.next().ok_or("Empty file")?;
+    let n: usize = first.parse().map_err(|_| format!("{}:1: expected the point count, got '{}'", filename, first))?;
+    let mut points = Vec::with_capacity(n.min(1 << 20));
+    for (line, l) in lines {
+        let parts: Vec<&str> = l.split_whitespace().collect();
+        if parts.len() != 2 {
+            return Err(format!("{}:{}: expected 'x y', got '{}'", filename, line, l).into());
+        }
+        match (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
+            (Ok(x), Ok(y)) if x.is_finite() && y.is_finite() => points.push((x, y)),
+            _ => return Err(format!("{}:{}: bad coordinates '{}'", filename, line, l).into()),
+        }
+    }
+    if points.len() != n {
+        return Err(format!("Header declares {} points but the file lists {}", n, points.len()).into());
+    }
+    Ok(points)
+}
+
+/* Uniform grid, about two points per cell, cells stored CSR-style. */
+struct Grid {
+    x0: f64,
+    y0: f64,
+    cell: f64,
+    cols: usize,
+    rows: usize,
+    start: Vec<usize>,
+    items: Vec<usize>,
+}
+
+impl Grid {
+    fn new(points: &[(f64, f64)]) -> Self {
+        let (mut x0, mut y0, mut x1, mut y1) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+        for &(x, y) in points {
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x);
+            y1 = y1.max(y);
+        }
+        let side = (x1 - x0).max(y1 - y0).max(1e-9);
+        let per_side = ((points.len() as f64 / 2.0).sqrt().ceil() as usize).max(1);
+        let cell = side / per_side as f64;
+        let cols = (((x1 - x0) / cell) as usize + 1).min(per_side + 1);
+        let rows = (((y1 - y0) / cell) as usize + 1).min(per_side + 1);
+        let mut g = Grid { x0, y0, cell, cols, rows, start: vec![0; cols * rows + 1], items: vec![0; points.len()] };
+        for &p in points {
+            let c = g.cell_of(p);
+            g.start[c + 1] += 1;
+        }
+        for c in 0..cols * rows {
+            g.start[c + 1] += g.start[c];
+        }
+        let mut fill = g.start.clone();
+        for (i, &p) in points.iter().enumerate() {
+            let c = g.cell_of(p);
+            g.items[fill[c]] = i;
+            fill[c] += 1;
+        }
+        g
+    }
+
+    fn coords(&self, p: (f64, f64)) -> (usize, usize) {
+        let cx = (((p.0 - self.x0) / self.cell) as usize).min(self.cols - 1);
+        let cy = (((p.1 - self.y0) / self.cell) as usize).min(self.rows - 1);
+        (cx, cy)
+    }
+
+    fn cell_of(&self, p: (f64, f64)) -> usize {
+        let (cx, cy) = self.coords(p);
+        cy * self.cols + cx
+    }
+
+    /* Points in the cells at Chebyshev ring distance r from p's cell. */
+    fn ring(&self, p: (f64, f64), r: usize, out: &mut Vec<usize>) {
+        let (cx, cy) = self.coords(p);
+        let (cx, cy, r) = (cx as isize, cy as isize, r as isize);
+        for y in cy - r..=cy + r {
+            if y < 0 || y >= self.rows as isize { continue; }
+            let full = y == cy - r || y == cy + r;
+            let mut x = cx - r;
+            while x <= cx + r {
+                if x >= 0 && x < self.cols as isize {
+                    let c = y as usize * self.cols + x as usize;
+                    out.extend_from_slice(&self.items[self.start[c]..self.start[c + 1]]);
+                }
+                x += if full || r == 0 { 1 } else { 2 * r };
+            }
+        }
+    }
+
+    fn max_ring(&self) -> usize {
+        self.cols.max(self.rows)
+    }
+}
+
+/* The k nearest other points of every point (ties by index). */
+fn nearest(points: &[(f64, f64)], grid: &Grid, k: usize) -> Vec<Vec<usize>> {
+    let dist2 = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0) * (a.0 - b.0) + (a.1 - b.1) * (a.1 - b.1);
+    let mut out = Vec::with_capacity(points.len());
+    let mut found: Vec<(f64, usize)> = Vec::new();
+    let mut ring = Vec::new();
+    for (i, &p) in points.iter().enumerate() {
+        found.clear();
+        for r in 0..=grid.max_ring() {
+            ring.clear();
+            grid.ring(p, r, &mut ring);
+            found.extend(ring.iter().filter(|&&j| j != i).map(|&j| (dist2(p, points[j]), j)));
+            /* every point outside rings 0..=r is farther than r cells */
+            let reach = r as f64 * grid.cell;
+            if found.len() >= k {
+                found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                if found[k - 1].0 <= reach * reach { break; }
+            }
+        }
+        found.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        out.push(found.iter().take(k).map(|&(_, j)| j).collect());
+    }
+    out
+}
+
+/* Pairs outside `candidates` (sorted u < v) that violate the duals, most
+ * violated first, at most PER_VERTEX of them starting at each point (a
+ * round that adds every violated pair tends to add far more than needed). */
+const PER_VERTEX: usize = 2;
+
+fn violations(
+    points: &[(f64, f64)], grid: &Grid, dualvar: &[i64], top: i64, candidates: &[(usize, usize, i64)],
+) -> Vec<(usize, usize, i64)> {
+    let rho: Vec<f64> = dualvar.iter().map(|&y| (top + 1 - y) as f64 / 2.0).collect();
+    let rho_max = rho.iter().cloned().fold(f64::MIN, f64::max);
+    let mut found = Vec::new();
+    let mut ring = Vec::new();
+    for (u, &p) in points.iter().enumerate() {
+        /* d(u, v) < rho_u + rho_v and d >= euclid - 1/2 */
+        let radius = rho[u] + rho_max + 0.5;
+        if radius <= 0.0 { continue; }
+        let rings = ((radius / grid.cell).ceil() as usize + 1).min(grid.max_ring());
+        for r in 0..=rings {
+            ring.clear();
+            grid.ring(p, r, &mut ring);
+            for &v in &ring {
+                if v <= u { continue; }
+                let d = distance(p, points[v]);
+                let slack = dualvar[u] + dualvar[v] - 2 * (top + 1 - d);
+                if slack < 0 && candidates.binary_search_by(|e| (e.0, e.1).cmp(&(u, v))).is_err() {
+                    found.push((slack, u, v, d));
+                }
+            }
+        }
+    }
+    found.sort_unstable();
+    let mut count = vec![0usize; points.len()];
+    let mut out = Vec::new();
+    for (_, u, v, d) in found {
+        if count[u] < PER_VERTEX || count[v] < PER_VERTEX {
+            count[u] += 1;
+            count[v] += 1;
+            out.push((u, v, d));
+        }
+    }
+    out
+}
+
+/* Candidate edges (u < v) joining every point of `subset` to its k
+ * nearest others within `subset`. */
+fn knn_edges(points: &[(f64, f64)], subset: &[usize], k: usize) -> Vec<(usize, usize, i64)> {
+    let sub: Vec<(f64, f64)> = subset.iter().map(|&v| points[v]).collect();
+    let grid = Grid::new(&sub);
+    let mut edges = Vec::new();
+    for (i, near) in nearest(&sub, &grid, k.min(sub.len() - 1)).into_iter().enumerate() {
+        for j in near {
+            let (a, b) = (subset[i].min(subset[j]), subset[i].max(subset[j]));
+            edges.push((a, b, distance(points[a], points[b])));
+        }
+    }
+    edges
+}
+
+/* Minimum-weight perfect matching of the complete EUC_2D graph, starting
+ * from the k-nearest-neighbour graph. When the candidates have no perfect
+ * matching, the exposed points get k-nearest-neighbour edges among
+ * themselves (k doubling whenever that does not help). Errors on an odd
+ * point count. */
+pub fn min_weight_perfect_matching(points: &[(f64, f64)], k: usize) -> Result<GeometricMatching, String> {
+    let n = points.len();
+    if n % 2 == 1 {
+        return Err(format!("{} points cannot be paired up", n));
+    }
+    let mut result = GeometricMatching { pairs: Vec::new(), weight: 0, candidates: 0, rounds: 0, added: 0, linked: 0 };
+    if n == 0 { return Ok(result); }
+    let grid = Grid::new(points);
+    let all: Vec<usize> = (0..n).collect();
+    let mut candidates = knn_edges(points, &all, k.max(1));
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let mut link_k = k.max(1);
+    let mut best_size = 0;
+    loop {
+        let top = candidates.iter().map(|e| e.2).max().unwrap_or(0);
+        let flipped: Vec<(usize, usize, i64)> = candidates.iter().map(|&(u, v, d)| (u, v, top + 1 - d)).collect();
+        let mut wb = WeightedBlossom::new(n, &flipped);
+        let pairs = wb.maximum_weight_matching(true);
+        result.rounds += 1;
+        result.candidates = candidates.len();
+
+        let new_edges = if 2 * pairs.len() != n {
+            /* the exposed points plus the current matching can always be
+             * completed once they form a clique */
+            let mut matched = vec![false; n];
+            for &(u, v) in &pairs {
+                matched[u] = true;
+                matched[v] = true;
+            }
+            let exposed: Vec<usize> = (0..n).filter(|&v| !matched[v]).collect();
+            if pairs.len() <= best_size { link_k *= 2; }
+            best_size = best_size.max(pairs.len());
+            let edges = knn_edges(points, &exposed, link_k);
+            result.linked += edges.len();
+            edges
+        } else {
+            let missing = violations(points, &grid, &wb.dualvar[..n], top, &candidates);
+            if missing.is_empty() {
+                result.weight = pairs.iter().map(|&(u, v)| distance(points[u], points[v])).sum();
+                result.pairs = pairs;
+                return Ok(result);
+            }
+            result.added += missing.len();
+            missing
+        };
+        candidates.extend(new_edges);
+        candidates.sort_unstable();
+        candidates.dedup();
+    }
+}
@@ -0,0 +1,2452 @@
+/*
+ * Shared edge-list loader for the Rust implementations.
+ *
+ * Formats:
+ *   general:   "n m"            then m lines "u v"
+ *   bipartite: "left right m"   then m lines "u v"  (u left, v right)
+ *   weighted:  "n m"            then m lines "u v w"  (w an integer)
+ *   weighted bipartite: "left right m" then m lines "u v w"
+ * and the same graphs in the binary format, in JSON or in GraphML (see
+ * below), each told from text by its first bytes. `--format snap` reads
+ * headerless edge lists with arbitrary ids instead (see read_snap), and
+ * `--format labels` ones with named vertices (see read_labels).
+ *
+ * The header's m is checked against the number of edge lines actually
+ * read; what happens on a disagreement is set by MismatchPolicy
+ * (`--on-mismatch error|warn|truncate|accept`, default warn).
+ *
+ * Edges naming a vertex past the header's bound (id >= n, or >= left /
+ * right) are handled by RangePolicy: dropped with a warning (default),
+ * refused with SuiteError::VertexOutOfRange (`--strict`), or kept by growing the
+ * bound to the largest id + 1 (`--auto-grow`, with a warning).
+ *
+ * Parallel edges (the same pair on several lines; u v and v u are the same
+ * pair in a general graph) are kept as read by default. MergePolicy
+ * (`--merge-parallel keep|max|min|sum|error`) folds each group into one
+ * edge at its first line, weighted by the group's max, min or sum, or
+ * refuses the file; the number of lines folded is reported on stderr. For
+ * the unweighted formats max, min and sum all just deduplicate.
+ * `--edge-ids` keeps every parallel edge by its input line instead (see
+ * EdgeIds).
+ *
+ * Comments, blank lines and CRLF line endings are skipped (FormatPolicy):
+ * a comment runs from '#' or '%' to the end of the line, and a line whose
+ * first token is "c" (DIMACS) is one as well, before the header too. An
+ * edge line with a single field is skipped with a warning, extra fields
+ * are ignored. `--strict-format` refuses all of these instead, so that
+ * every line after the header is exactly one edge.
+ *
+ * A weighted graph is the same file with an integer weight as the third
+ * column of every edge line ("u v w"). The weighted loaders require it;
+ * the unweighted ones read past it, with one note on stderr, and
+ * `--strict-format` takes it as part of the line there. has_weights()
+ * tells a weighted file from the first edge line (or the binary header,
+ * or the document), for routing it to a weighted solver.
+ *
+ * Files ending in .gz or .zst are read through `gzip -dc` or `zstd -dcq`
+ * (open_input), streamed, so a 10 GB edge list never has to be unpacked
+ * on disk. The suite builds with plain rustc and has no crates to link a
+ * decompressor from; the tools are on every system that has such files.
+ *
+ * Reading is byte-level: a plain file is mapped (mmap) and lexed in place,
+ * a stream is read a block at a time; lines of the form "u v" / "u v w"
+ * take a one-pass integer scanner, anything else the general line lexer.
+ * Each block is cut at line ends into pieces lexed on up to
+ * `--load-threads` threads; the policies above then run over the pieces in
+ * file order, so results, warnings and errors (with their line numbers)
+ * are those of a line-at-a-time reader.
+ *
+ * Every failure is a SuiteError (error.rs), for the binaries' exit codes.
+ *
+ * Included via #[path = "../../common/rust/graph_io.rs"] mod graph_io;
+ * together with args.rs, error.rs and log.rs (its warnings).
+ */
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::process::{Child, ChildStdout, Command, Stdio};
+
+use super::error::SuiteError;
+use super::log;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MismatchPolicy {
+    Error,    /* refuse the file */
+    Warn,     /* report on stderr, keep every edge line */
+    Truncate, /* keep only the first m edge lines (warns if fewer) */
+    Accept,   /* keep every edge line silently */
+}
+
+impl MismatchPolicy {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "error" => Ok(MismatchPolicy::Error),
+            "warn" => Ok(MismatchPolicy::Warn),
+            "truncate" => Ok(MismatchPolicy::Truncate),
+            "accept" => Ok(MismatchPolicy::Accept),
+            _ => Err(format!(
+                "invalid --on-mismatch value '{}' (expected error, warn, truncate or accept)", s)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MismatchPolicy::Error => "error",
+            MismatchPolicy::Warn => "warn",
+            MismatchPolicy::Truncate => "truncate",
+            MismatchPolicy::Accept => "accept",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RangePolicy {
+    Drop,  /* skip the edge, one warning on stderr for the file */
+    Error, /* refuse the file (--strict) */
+    Grow,  /* raise n (or left / right) to fit, with a warning (--auto-grow) */
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MergePolicy {
+    Keep,  /* every line is an edge (default) */
+    Max,   /* one edge per pair, the heaviest weight */
+    Min,   /* one edge per pair, the lightest weight */
+    Sum,   /* one edge per pair, the weights added */
+    Error, /* refuse the file */
+}
+
+impl MergePolicy {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "keep" => Ok(MergePolicy::Keep),
+            "max" => Ok(MergePolicy::Max),
+            "min" => Ok(MergePolicy::Min),
+            "sum" => Ok(MergePolicy::Sum),
+            "error" => Ok(MergePolicy::Error),
+            _ => Err(format!(
+                "invalid --merge-parallel value '{}' (expected keep, max, min, sum or error)", s)),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            MergePolicy::Keep => "keep",
+            MergePolicy::Max => "max",
+            MergePolicy::Min => "min",
+            MergePolicy::Sum => "sum",
+            MergePolicy::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FormatPolicy {
+    Tolerant, /* skip comments, blank lines and short lines, accept CRLF */
+    Strict,   /* refuse them, and edge lines with extra fields (--strict-format) */
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputFormat {
+    Auto, /* text, or binary, JSON or GraphML by the first bytes */
+    Snap,   /* no header, any ids, remapped to 0.. (--format snap) */
+    Labels, /* no header, named vertices, interned to 0.. (--format labels) */
+}
+
+impl InputFormat {
+    /* Whether a text file starts with its "n m" header: not under
+     * --format snap or labels. */
+    pub fn has_header(self) -> bool {
+        self == InputFormat::Auto
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    pub input: InputFormat,
+    pub on_mismatch: MismatchPolicy,
+    pub out_of_range: RangePolicy,
+    pub on_parallel: MergePolicy,
+    pub format: FormatPolicy,
+    pub threads: usize,     /* lexing threads per block (--load-threads); 0 = one per core, up to 8 */
+    pub block_bytes: usize, /* BLOCK_BYTES; tests lower it to cut small inputs into many blocks */
+    pub edge_ids: bool,     /* keep each edge's input line (--edge-ids, see EdgeIds) */
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            input: InputFormat::Auto,
+            on_mismatch: MismatchPolicy::Warn,
+            out_of_range: RangePolicy::Drop,
+            on_parallel: MergePolicy::Keep,
+            format: FormatPolicy::Tolerant,
+            threads: 0,
+            block_bytes: BLOCK_BYTES,
+            edge_ids: false,
+        }
+    }
+}
+
+impl LoadOptions {
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut opts = LoadOptions::default();
+        match super::args::value_of(args, "--format") {
+            None | Some("auto") => {}
+            Some("snap") => opts.input = InputFormat::Snap,
+            Some("labels") => opts.input = InputFormat::Labels,
+            Some(v) => return Err(format!("invalid --format value '{}' (expected auto, snap or labels)", v)),
+        }
+        /* these draw and log the remapped ids, which the user never sees */
+        if !opts.input.has_header() && ["--trace", "--dot", "--svg"].iter().any(|f| super::args::has_flag(args, f)) {
+            return Err(format!("--format {} does not combine with --trace, --dot or --svg",
+                               if opts.input == InputFormat::Snap { "snap" } else { "labels" }));
+        }
+        if let Some(v) = super::args::value_of(args, "--on-mismatch") {
+            opts.on_mismatch = MismatchPolicy::parse(v)?;
+        }
+        match (super::args::has_flag(args, "--strict"), super::args::has_flag(args, "--auto-grow")) {
+            (true, true) => return Err("--strict and --auto-grow are mutually exclusive".to_string()),
+            (true, false) => opts.out_of_range = RangePolicy::Error,
+            (false, true) => opts.out_of_range = RangePolicy::Grow,
+            (false, false) => {}
+        }
+        if let Some(v) = super::args::value_of(args, "--merge-parallel") {
+            opts.on_parallel = MergePolicy::parse(v)?;
+        }
+        if super::args::has_flag(args, "--edge-ids") {
+            if opts.on_parallel != MergePolicy::Keep {
+                return Err("--edge-ids keeps every parallel edge; it does not combine with --merge-parallel".to_string());
+            }
+            opts.edge_ids = true;
+        }
+        if super::args::has_flag(args, "--strict-format") {
+            opts.format = FormatPolicy::Strict;
+        }
+        if let Some(v) = super::args::value_of(args, "--load-threads") {
+            opts.threads = v.parse().map_err(|_| format!("invalid value for --load-threads: {}", v))?;
+        }
+        Ok(opts)
+    }
+
+    /* The flags that give these options back through from_args, defaults
+     * left out, for a daemon that loads a file on a client's behalf (combi
+     * serve). --load-threads is not among them: it changes how fast a file
+     * loads, not what it loads to. */
+    pub fn to_args(&self) -> Vec<String> {
+        let mut out = Vec::new();
+        match self.input {
+            InputFormat::Auto => {}
+            InputFormat::Snap => out.push("--format=snap".to_string()),
+            InputFormat::Labels => out.push("--format=labels".to_string()),
+        }
+        if self.on_mismatch != MismatchPolicy::Warn {
+            out.push(format!("--on-mismatch={}", self.on_mismatch.name()));
+        }
+        match self.out_of_range {
+            RangePolicy::Drop => {}
+            RangePolicy::Error => out.push("--strict".to_string()),
+            RangePolicy::Grow => out.push("--auto-grow".to_string()),
+        }
+        if self.on_parallel != MergePolicy::Keep {
+            out.push(format!("--merge-parallel={}", self.on_parallel.name()));
+        }
+        if self.edge_ids {
+            out.push("--edge-ids".to_string());
+        }
+        if self.format == FormatPolicy::Strict {
+            out.push("--strict-format".to_string());
+        }
+        out
+    }
+}
+
+pub const LOAD_USAGE: &str =
+    "[--on-mismatch error|warn|truncate|accept] [--strict|--auto-grow] [--merge-parallel keep|max|min|sum|error] \
+     [--strict-format] [--load-threads N] [--format auto|snap|labels] [--edge-ids]";
+
+/* Compressed inputs by file extension: the tool and its flags to write
+ * the file decompressed to stdout. */
+const DECOMPRESSORS: [(&str, &str, &str); 2] = [(".gz", "gzip", "-dc"), (".zst", "zstd", "-dcq")];
+
+pub fn is_compressed(filename: &str) -> bool {
+    DECOMPRESSORS.iter().any(|&(ext, _, _)| filename.ends_with(ext))
+}
+
+/* A decompressor's stdout. At its end a failed exit (a truncated or
+ * corrupt file; the tool says why on stderr) is a read error, not a quiet
+ * end of file. Dropped early, e.g. after the header, the tool is killed. */
+struct Decompressed {
+    child: Child,
+    stdout: ChildStdout,
+    tool: &'static str,
+    done: bool,
+}
+
+impl Read for Decompressed {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let k = self.stdout.read(buf)?;
+        if k == 0 && !buf.is_empty() && !self.done {
+            self.done = true;
+            let status = self.child.wait()?;
+            if !status.success() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData,
+                                          format!("{} could not decompress the file ({})", self.tool, status)));
+            }
+        }
+        Ok(k)
+    }
+}
+
+impl Drop for Decompressed {
+    fn drop(&mut self) {
+        if !self.done {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+    }
+}
+
+/* `filename` for reading, decompressed on the fly when it ends in .gz or
+ * .zst. */
+pub fn open_input(filename: &str) -> Result<Box<dyn BufRead>, SuiteError> {
+    let file = File::open(filename)?;
+    let (tool, flags) = match DECOMPRESSORS.iter().find(|&&(ext, _, _)| filename.ends_with(ext)) {
+        Some(&(_, tool, flags)) => (tool, flags),
+        None => return Ok(Box::new(BufReader::new(file))),
+    };
+    let mut child = Command::new(tool).arg(flags).arg("--").arg(filename)
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| io::Error::other(format!("{}: cannot run {} to decompress it: {}", filename, tool, e)))?;
+    let stdout = child.stdout.take().ok_or_else(|| io::Error::other("no pipe from the decompressor"))?;
+    Ok(Box::new(BufReader::with_capacity(1 << 20, Decompressed { child, stdout, tool, done: false })))
+}
+
+/* A regular file mapped read-only, so the lexer reads the page cache in
+ * place instead of copying the file through a buffer. Unix only, through
+ * libc's mmap as interrupt.rs reaches signal(); elsewhere, and for pipes,
+ * empty files or a failed mmap, new() gives None and the file is streamed.
+ * The file must not shrink while it is mapped (that is a SIGBUS). */
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+#[cfg(unix)]
+extern "C" {
+    fn mmap(addr: *mut u8, len: usize, prot: i32, flags: i32, fd: i32, offset: i64) -> *mut u8;
+    fn munmap(addr: *mut u8, len: usize) -> i32;
+}
+
+#[cfg(unix)]
+const PROT_READ: i32 = 1;
+#[cfg(unix)]
+const MAP_PRIVATE: i32 = 2;
+
+impl Mapping {
+    #[cfg(unix)]
+    fn new(file: &File) -> Option<Mapping> {
+        use std::os::unix::io::AsRawFd;
+        let meta = file.metadata().ok()?;
+        if !meta.is_file() || meta.len() == 0 || meta.len() > isize::MAX as u64 {
+            return None;
+        }
+        let len = meta.len() as usize;
+        let ptr = unsafe { mmap(std::ptr::null_mut(), len, PROT_READ, MAP_PRIVATE, file.as_raw_fd(), 0) };
+        if ptr as usize == usize::MAX { None } else { Some(Mapping { ptr, len }) } /* MAP_FAILED */
+    }
+
+    #[cfg(not(unix))]
+    fn new(_file: &File) -> Option<Mapping> {
+        None
+    }
+
+    fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len) }
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            munmap(self.ptr, self.len);
+        }
+    }
+}
+
+/* Bytes a loader takes at a time (LoadOptions::block_bytes): a mapped
+ * file is cut into blocks of about this size at line ends, a stream is
+ * read into a buffer of it. Each block is lexed by up to `threads`
+ * threads, then its edges are checked in file order. */
+pub const BLOCK_BYTES: usize = 1 << 26;
+
+/* A thread gets at least this fraction of a block, so a small file is
+ * lexed on one thread. */
+const MIN_PIECES: usize = 64;
+
+/* --load-threads 0: one thread per core, up to this many. */
+const MAX_LOAD_THREADS: usize = 8;
+
+enum Source<R> {
+    Mapped(Mapping),
+    Stream(R),
+}
+
+/* The input cut into blocks of whole lines. The current block is
+ * bytes [pos, end) of the mapping, or of `buf` for a stream, which keeps
+ * the start of the next line after `end`. `line` counts the lines before
+ * `pos`. */
+struct Blocks<R> {
+    source: Source<R>,
+    size: usize,
+    buf: Vec<u8>,
+    pos: usize,
+    end: usize,
+    line: usize,
+}
+
+impl<R: BufRead> Blocks<R> {
+    fn new(source: Source<R>, size: usize) -> Self {
+        Blocks { source, size: size.max(1), buf: Vec::new(), pos: 0, end: 0, line: 0 }
+    }
+
+    fn stream(reader: R, size: usize) -> Self {
+        Blocks::new(Source::Stream(reader), size)
+    }
+
+    fn block(&self) -> &[u8] {
+        match self.source {
+            Source::Mapped(ref map) => &map.bytes()[self.pos..self.end],
+            Source::Stream(_) => &self.buf[self.pos..self.end],
+        }
+    }
+
+    /* The first `k` bytes of the input, or all of it if shorter; before
+     * the first advance() only. A stream keeps them in `buf` for the
+     * lexer. */
+    fn head(&mut self, k: usize) -> Result<&[u8], SuiteError> {
+        match self.source {
+            Source::Mapped(ref map) => Ok(&map.bytes()[..k.min(map.len)]),
+            Source::Stream(ref mut reader) => {
+                let have = self.buf.len();
+                if have < k {
+                    reader.by_ref().take((k - have) as u64).read_to_end(&mut self.buf)?;
+                }
+                Ok(&self.buf[..k.min(self.buf.len())])
+            }
+        }
+    }
+
+    fn is_binary(&mut self) -> Result<bool, SuiteError> {
+        Ok(self.head(BINARY_MAGIC.len())? == BINARY_MAGIC)
+    }
+
+    /* The structured format the input is in, if any, by its first
+     * non-blank byte (after a UTF-8 byte order mark). */
+    fn structured(&mut self) -> Result<Option<Structured>, SuiteError> {
+        let head = self.head(SNIFF_BYTES)?;
+        let head = head.strip_prefix(b"\xef\xbb\xbf").unwrap_or(head);
+        Ok(match head.iter().find(|&&b| !is_space(b)) {
+            Some(b'{') => Some(Structured::Json),
+            Some(b'<') => Some(Structured::GraphMl),
+            _ => None,
+        })
+    }
+
+    /* The whole input, a stream read to its end; before the first
+     * advance() only. */
+    fn rest(&mut self) -> Result<&[u8], SuiteError> {
+        match self.source {
+            Source::Mapped(ref map) => Ok(map.bytes()),
+            Source::Stream(ref mut reader) => {
+                reader.read_to_end(&mut self.buf)?;
+                Ok(&self.buf)
+            }
+        }
+    }
+
+    /* Bytes known to follow the current position: the rest of a mapped
+     * file, nothing for a stream. */
+    fn unread(&self) -> usize {
+        match self.source {
+            Source::Mapped(ref map) => map.len - self.pos,
+            Source::Stream(_) => 0,
+        }
+    }
+
+    /* Moves to the next block; false at the end of the input. */
+    fn advance(&mut self) -> Result<bool, SuiteError> {
+        let size = self.size;
+        match self.source {
+            Source::Mapped(ref map) => {
+                let rest = &map.bytes()[self.end..];
+                let cut = match rest.get(size..) {
+                    None => rest.len(),
+                    Some(tail) => tail.iter().position(|&b| b == b'\n').map_or(rest.len(), |i| size + i + 1),
+                };
+                self.pos = self.end;
+                self.end += cut;
+            }
+            Source::Stream(ref mut reader) => {
+                self.buf.drain(..self.end);
+                self.pos = 0;
+                self.end = 0;
+                while self.end == 0 {
+                    let want = (self.buf.len() + size) as u64;
+                    let have = self.buf.len();
+                    let read = reader.by_ref().take(want - have as u64).read_to_end(&mut self.buf)?;
+                    if have + read < want as usize {
+                        self.end = self.buf.len(); /* end of the input */
+                        break;
+                    }
+                    if let Some(i) = self.buf.iter().rposition(|&b| b == b'\n') {
+                        self.end = i + 1;
+                    } /* else a line longer than a block: read on */
+                }
+            }
+        }
+        Ok(self.pos < self.end)
+    }
+}
+
+/* Opens `filename` mapped when it is a plain regular file, else streamed
+ * through open_input. */
+fn open_blocks(filename: &str, size: usize) -> Result<Blocks<Box<dyn BufRead>>, SuiteError> {
+    if !is_compressed(filename) {
+        if let Some(map) = File::open(filename).ok().as_ref().and_then(Mapping::new) {
+            return Ok(Blocks::new(Source::Mapped(map), size));
+        }
+    }
+    Ok(Blocks::stream(open_input(filename)?, size))
+}
+
+/* One line split at ASCII whitespace, up to the first three fields kept
+ * along with the number of fields. Comments, a CR before the newline and
+ * blank lines are taken out under FormatPolicy::Tolerant (Skip when
+ * nothing is left) and refused under Strict. */
+enum Line<'a> {
+    Skip,
+    Fields([&'a [u8]; 3], usize),
+    Refused(&'static str),
+}
+
+fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c')
+}
+
+fn lex_line(line: &[u8], policy: FormatPolicy) -> Line<'_> {
+    let crlf = line.last() == Some(&b'\r');
+    let line = if crlf { &line[..line.len() - 1] } else { line };
+    let mut fields = [&line[..0]; 3];
+    let mut count = 0;
+    let mut comment = false;
+    let mut i = 0;
+    while i < line.len() {
+        match line[i] {
+            b'#' | b'%' => {
+                comment = true;
+                break;
+            }
+            b if is_space(b) => i += 1,
+            _ => {
+                let start = i;
+                while i < line.len() && !is_space(line[i]) && line[i] != b'#' && line[i] != b'%' {
+                    i += 1;
+                }
+                if count < 3 { fields[count] = &line[start..i]; }
+                count += 1;
+            }
+        }
+    }
+    if !comment && count > 0 && fields[0] == b"c" {
+        comment = true;
+        count = 0;
+    }
+    if policy == FormatPolicy::Strict {
+        if crlf { return Line::Refused("CRLF line ending"); }
+        if comment { return Line::Refused("comment"); }
+        if count == 0 { return Line::Refused("blank line"); }
+    }
+    if count == 0 { Line::Skip } else { Line::Fields(fields, count) }
+}
+
+/* Digits that surely fit a usize; longer fields take the slow path. */
+const FAST_DIGITS: usize = if usize::BITS >= 64 { 19 } else { 9 };
+
+/* A vertex id read straight from its decimal digits. Anything else (a
+ * sign, a stray byte, too many digits) goes through str::parse, for its
+ * checks and its error message. */
+fn scan_usize(field: &[u8]) -> Result<usize, Box<dyn Error + Send + Sync>> {
+    if !field.is_empty() && field.len() <= FAST_DIGITS && field.iter().all(u8::is_ascii_digit) {
+        return Ok(field.iter().fold(0, |x, &b| x * 10 + (b - b'0') as usize));
+    }
+    Ok(std::str::from_utf8(field)?.parse()?)
+}
+
+/* The same for a weight, with an optional minus sign. */
+fn scan_i64(field: &[u8]) -> Result<i64, Box<dyn Error + Send + Sync>> {
+    let digits = field.strip_prefix(b"-").unwrap_or(field);
+    if !digits.is_empty() && digits.len() <= 18 && digits.iter().all(u8::is_ascii_digit) {
+        let x = digits.iter().fold(0, |x, &b| x * 10 + (b - b'0') as i64);
+        return Ok(if digits.len() < field.len() { -x } else { x });
+    }
+    Ok(std::str::from_utf8(field)?.parse()?)
+}
+
+fn header_numbers(fields: &[&[u8]; 3], found: usize, count: usize, policy: FormatPolicy)
+    -> Result<Vec<usize>, SuiteError> {
+    if found < count {
+        return Err(SuiteError::ParseHeader(format!("First line must have {} numbers", count)));
+    }
+    if policy == FormatPolicy::Strict && found > count {
+        return Err(SuiteError::ParseHeader(format!("First line must have exactly {} numbers (--strict-format)", count)));
+    }
+    let mut out = Vec::with_capacity(count);
+    for f in &fields[..count] {
+        out.push(scan_usize(f).map_err(|e| SuiteError::ParseHeader(e.to_string()))?);
+    }
+    Ok(out)
+}
+
+/* The header is untrusted: reserve at most this many edges up front (a bogus
+ * m would otherwise abort the process on allocation), or one per 4 bytes
+ * left in a mapped file ("0 1\n" is the shortest edge line); past it the
+ * Vec grows as usual. */
+const MAX_PREALLOC_EDGES: usize = 1 << 20;
+
+/* The first data line: the header, `count` numbers; with `exact`, no
+ * more than that, so that a general "n m" file is not taken for a
+ * bipartite one. The blocks are left just past it. */
+fn read_header<R: BufRead>(blocks: &mut Blocks<R>, count: usize, exact: bool, policy: FormatPolicy)
+    -> Result<Vec<usize>, SuiteError> {
+    while blocks.advance()? {
+        let block = blocks.block();
+        let mut line = blocks.line;
+        let mut at = 0;
+        let mut header = None;
+        while at < block.len() && header.is_none() {
+            let next = block[at..].iter().position(|&b| b == b'\n').map_or(block.len(), |i| at + i + 1);
+            line += 1;
+            match lex_line(block[at..next].strip_suffix(b"\n").unwrap_or(&block[at..next]), policy) {
+                Line::Skip => {}
+                Line::Refused(what) => {
+                    return Err(SuiteError::ParseEdge { line, what: format!("{} (refused by --strict-format)", what) });
+                }
+                Line::Fields(_, found) if exact && found != count => {
+                    return Err(SuiteError::ParseHeader(format!("First line must have {} numbers", count)));
+                }
+                Line::Fields(fields, found) => header = Some(header_numbers(&fields, found, count, policy)),
+            }
+            at = next;
+        }
+        blocks.pos += at;
+        blocks.line = line;
+        if let Some(h) = header {
+            return h;
+        }
+    }
+    Err(SuiteError::EmptyGraph)
+}
+
+/* Why a piece of a block stopped short, at a line. Refused comes before
+ * the edge count is checked against the header, the other two after, as
+ * a line-at-a-time reader would meet them. */
+enum Stop {
+    Refused(String),
+    Number(Box<dyn Error + Send + Sync>),
+    NoWeight,
+}
+
+/* A piece of a block lexed on its own thread: its edges, the line of
+ * each (from 1 within the piece), the single-field lines met between
+ * them as (edges before, line), and where it stopped, if it did. */
+#[derive(Default)]
+struct Lexed {
+    edges: Vec<(usize, usize)>,
+    weights: Vec<i64>,
+    lines: Vec<u32>,
+    short: Vec<(usize, u32)>,
+    stop: Option<(u32, Stop)>,
+    count: usize,    /* lines in the piece */
+    weighted: usize, /* edge lines with a weight column, read past when unweighted */
+}
+
+/* The digits from `at`, if there are 1 to FAST_DIGITS of them: the value
+ * and the index after them. */
+fn quick_number(piece: &[u8], at: usize) -> Option<(usize, usize)> {
+    let mut x = 0usize;
+    let mut i = at;
+    while let Some(&b) = piece.get(i) {
+        if !b.is_ascii_digit() { break; }
+        x = x.wrapping_mul(10).wrapping_add((b - b'0') as usize);
+        i += 1;
+    }
+    if i == at || i - at > FAST_DIGITS { None } else { Some((x, i)) }
+}
+
+/* The common line, "u v" or "u v w" in plain digits with single spaces
+ * and a bare newline, read in one pass; anything else (None) goes through
+ * lex_line. Returns the edge, its weight and the start of the next line. */
+fn quick_line(piece: &[u8], at: usize, weighted: bool) -> Option<((usize, usize), i64, usize)> {
+    let (u, i) = quick_number(piece, at)?;
+    if piece.get(i) != Some(&b' ') { return None; }
+    let (v, mut i) = quick_number(piece, i + 1)?;
+    let mut w = 0;
+    if weighted {
+        if piece.get(i) != Some(&b' ') { return None; }
+        let start = i + 1 + (piece.get(i + 1) == Some(&b'-')) as usize;
+        let negative = start == i + 2;
+        let (x, j) = quick_number(piece, start)?;
+        if j - start > 18 { return None; }
+        w = if negative { -(x as i64) } else { x as i64 };
+        i = j;
+    }
+    match piece.get(i) {
+        None => Some(((u, v), w, i)),
+        Some(b'\n') => Some(((u, v), w, i + 1)),
+        Some(_) => None,
+    }
+}
+
+fn lex_piece(piece: &[u8], fields: usize, policy: FormatPolicy, out: &mut Lexed) {
+    out.edges.clear();
+    out.weights.clear();
+    out.lines.clear();
+    out.short.clear();
+    out.stop = None;
+    out.weighted = 0;
+    let mut at = 0;
+    let mut number = 0u32;
+    while at < piece.len() {
+        if let Some((edge, w, next)) = quick_line(piece, at, fields == 3) {
+            number += 1;
+            out.edges.push(edge);
+            if fields == 3 { out.weights.push(w); }
+            out.lines.push(number);
+            at = next;
+            continue;
+        }
+        let next = piece[at..].iter().position(|&b| b == b'\n').map_or(piece.len(), |i| at + i + 1);
+        let line = piece[at..next].strip_suffix(b"\n").unwrap_or(&piece[at..next]);
+        at = next;
+        number += 1;
+        let (f, found) = match lex_line(line, policy) {
+            Line::Skip => continue,
+            Line::Refused(what) => {
+                out.stop = Some((number, Stop::Refused(format!("{} (refused by --strict-format)", what))));
+                break;
+            }
+            Line::Fields(f, found) => (f, found),
+        };
+        let weight_column = fields == 2 && found == 3 && scan_i64(f[2]).is_ok();
+        if policy == FormatPolicy::Strict && found != fields && !weight_column {
+            let msg = format!("expected {} fields, got {} (refused by --strict-format)", fields, found);
+            out.stop = Some((number, Stop::Refused(msg)));
+            break;
+        }
+        if found < 2 {
+            out.short.push((out.edges.len(), number));
+            continue;
+        }
+        let edge = scan_usize(f[0]).and_then(|u| Ok((u, scan_usize(f[1])?)));
+        let weight = match (fields, found) {
+            (2, _) => Ok(0),
+            (_, 2) => Err(None),
+            _ => scan_i64(f[2]).map_err(Some),
+        };
+        match (edge, weight) {
+            (Err(e), _) | (Ok(_), Err(Some(e))) => out.stop = Some((number, Stop::Number(e))),
+            (Ok(_), Err(None)) => out.stop = Some((number, Stop::NoWeight)),
+            (Ok(e), Ok(w)) => {
+                out.edges.push(e);
+                if fields == 3 { out.weights.push(w); }
+                if weight_column { out.weighted += 1; }
+                out.lines.push(number);
+                continue;
+            }
+        }
+        break;
+    }
+    out.count = number as usize;
+}
+
+/* A block cut at line ends into `pieces` parts of about equal size, lexed
+ * in parallel into `lexed`, in block order. Its buffers are reused from
+ * block to block: fresh ones would cost a page fault per 4 KiB. */
+fn lex_block(block: &[u8], pieces: usize, fields: usize, policy: FormatPolicy, lexed: &mut Vec<Lexed>) {
+    let mut cuts = vec![0];
+    for k in 1..pieces {
+        let target = (block.len() / pieces * k).max(*cuts.last().unwrap());
+        let cut = block[target..].iter().position(|&b| b == b'\n').map_or(block.len(), |i| target + i + 1);
+        cuts.push(cut);
+    }
+    cuts.push(block.len());
+    cuts.dedup();
+    lexed.resize_with(cuts.len().max(2) - 1, Lexed::default);
+    if lexed.len() == 1 {
+        return lex_piece(block, fields, policy, &mut lexed[0]);
+    }
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = cuts.windows(2).zip(lexed.iter_mut())
+            .map(|(w, out)| scope.spawn(move || lex_piece(&block[w[0]..w[1]], fields, policy, out)))
+            .collect();
+        for h in handles {
+            h.join().unwrap_or_else(|p| std::panic::resume_unwind(p));
+        }
+    })
+}
+
+/* What the readers return: the edges, their weights (weighted loads
+ * only), their --edge-ids ids and the id bounds. */
+type Loaded = (Vec<(usize, usize)>, Vec<i64>, Vec<usize>, (usize, usize));
+
+/* Edge lines after the header, checked in file order: the mismatch and
+ * range policies, and the warnings they owe at the end. `bounds` are the
+ * exclusive id limits for the first and second endpoint (n and n, or left
+ * and right), raised in `grown` if the range policy grows them. */
+struct EdgeCheck<'a> {
+    opts: &'a LoadOptions,
+    m: usize,
+    bounds: (usize, usize),
+    sides: (&'static str, &'static str),
+    edges: Vec<(usize, usize)>,
+    weights: Vec<i64>,
+    ids: Vec<usize>,
+    found: usize,
+    grown: (usize, usize),
+    dropped: usize,
+    first_dropped: Option<(usize, usize, &'static str, usize)>,
+    short: usize,
+    first_short: usize,
+    ignored_weights: usize,
+    truncated: bool,
+}
+
+impl<'a> EdgeCheck<'a> {
+    fn new(
+        opts: &'a LoadOptions, m: usize, bounds: (usize, usize), sides: (&'static str, &'static str),
+        reserve: usize, weighted: bool,
+    ) -> Self {
+        EdgeCheck {
+            opts, m, bounds, sides,
+            edges: Vec::with_capacity(reserve),
+            weights: Vec::with_capacity(if weighted { reserve } else { 0 }),
+            ids: Vec::with_capacity(if opts.edge_ids { reserve } else { 0 }),
+            found: 0,
+            grown: bounds,
+            dropped: 0,
+            first_dropped: None,
+            short: 0,
+            first_short: 0,
+            ignored_weights: 0,
+            truncated: false,
+        }
+    }
+
+    /* Whether one more edge line may be read under the mismatch policy. */
+    fn room(&mut self) -> Result<bool, SuiteError> {
+        if self.found == self.m {
+            match self.opts.on_mismatch {
+                MismatchPolicy::Error => {
+                    return Err(SuiteError::EdgeCount { declared: self.m, found: None });
+                }
+                MismatchPolicy::Truncate => {
+                    log::warn(format_args!("WARNING: header declares {} edges, ignoring the remaining lines", self.m));
+                    self.truncated = true;
+                    return Ok(false);
+                }
+                MismatchPolicy::Warn | MismatchPolicy::Accept => {}
+            }
+        }
+        Ok(true)
+    }
+
+    fn note_short(&mut self, number: usize) {
+        self.short += 1;
+        if self.first_short == 0 { self.first_short = number; }
+    }
+
+    /* One edge line, after room(): counted, then kept, dropped or refused
+     * by the range policy. `id` is its --edge-ids id. */
+    fn edge(&mut self, u: usize, v: usize, w: Option<i64>, number: usize, id: usize) -> Result<(), SuiteError> {
+        self.found += 1;
+        let bounds = self.bounds;
+        if u >= bounds.0 || v >= bounds.1 {
+            let (vertex, side, bound) =
+                if u >= bounds.0 { (u, self.sides.0, bounds.0) } else { (v, self.sides.1, bounds.1) };
+            match self.opts.out_of_range {
+                RangePolicy::Error => {
+                    return Err(SuiteError::VertexOutOfRange { line: number, vertex, side, bound });
+                }
+                RangePolicy::Drop => {
+                    self.dropped += 1;
+                    if self.first_dropped.is_none() { self.first_dropped = Some((number, vertex, side, bound)); }
+                    return Ok(());
+                }
+                RangePolicy::Grow => {
+                    let fit = |x: usize| x.checked_add(1).ok_or_else(|| {
+                        SuiteError::ParseEdge { line: number, what: format!("vertex {} is too large to grow the graph to", x) }
+                    });
+                    if u >= self.grown.0 { self.grown.0 = fit(u)?; }
+                    if v >= self.grown.1 { self.grown.1 = fit(v)?; }
+                }
+            }
+        }
+        self.edges.push((u, v));
+        if let Some(w) = w { self.weights.push(w); }
+        if self.opts.edge_ids { self.ids.push(id); }
+        Ok(())
+    }
+
+    /* The lexed piece that starts after line `base`. */
+    fn apply(&mut self, lexed: &mut Lexed, base: usize) -> Result<(), SuiteError> {
+        self.ignored_weights += lexed.weighted;
+        let mut short = lexed.short.iter().peekable();
+        for (k, &(u, v)) in lexed.edges.iter().enumerate() {
+            while let Some(&&(_, line)) = short.peek().filter(|s| s.0 == k) {
+                self.note_short(base + line as usize);
+                short.next();
+            }
+            if !self.room()? {
+                return Ok(());
+            }
+            let number = base + lexed.lines[k] as usize;
+            self.edge(u, v, lexed.weights.get(k).copied(), number, number)?;
+        }
+        for &(_, line) in short {
+            self.note_short(base + line as usize);
+        }
+        if let Some((line, stop)) = lexed.stop.take() {
+            let number = base + line as usize;
+            return match stop {
+                Stop::Refused(what) => Err(SuiteError::ParseEdge { line: number, what }),
+                _ if !self.room()? => Ok(()),
+                Stop::Number(e) => Err(SuiteError::ParseEdge { line: number, what: e.to_string() }),
+                Stop::NoWeight => Err(SuiteError::ParseEdge { line: number, what: "missing edge weight".to_string() }),
+            };
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Loaded, SuiteError> {
+        let (m, found, bounds, grown, sides) = (self.m, self.found, self.bounds, self.grown, self.sides);
+        if self.short > 0 {
+            log::warn(format_args!("WARNING: skipped {} line(s) with a single field (first: line {})", self.short, self.first_short));
+        }
+        if self.ignored_weights > 0 {
+            log::warn(format_args!("Note: ignored the weights of {} edge(s); this load reads the graph unweighted", self.ignored_weights));
+        }
+        if let Some((line, vertex, side, bound)) = self.first_dropped {
+            log::warn(format_args!("WARNING: dropped {} edge(s) naming a vertex out of range (first: line {}, vertex {}, {} = {}); \
+                                    use --strict to refuse or --auto-grow to keep them", self.dropped, line, vertex, side, bound));
+        }
+        if grown != bounds {
+            if sides.0 == sides.1 {
+                log::warn(format_args!("WARNING: vertex ids past n = {}, growing n to {}", bounds.0, grown.0.max(grown.1)));
+            } else {
+                log::warn(format_args!("WARNING: vertex ids past {} = {} / {} = {}, growing to {} = {} / {} = {}",
+                                       sides.0, bounds.0, sides.1, bounds.1, sides.0, grown.0, sides.1, grown.1));
+            }
+        }
+
+        let policy = self.opts.on_mismatch;
+        if found < m {
+            let e = SuiteError::EdgeCount { declared: m, found: Some(found) };
+            match policy {
+                MismatchPolicy::Error => return Err(e),
+                MismatchPolicy::Warn | MismatchPolicy::Truncate => log::warn(format_args!("WARNING: {}", e)),
+                MismatchPolicy::Accept => {}
+            }
+        } else if found > m && policy == MismatchPolicy::Warn {
+            log::warn(format_args!("WARNING: header declares {} edges but the file has {}", m, found));
+        }
+        Ok((self.edges, self.weights, self.ids, grown))
+    }
+}
+
+/* Edge lines after the header, block by block. With `weighted`, every
+ * edge line needs a third column, returned in edge order. */
+fn read_edges<R: BufRead>(
+    mut blocks: Blocks<R>, m: usize, bounds: (usize, usize), sides: (&'static str, &'static str),
+    weighted: bool, opts: &LoadOptions,
+) -> Result<Loaded, SuiteError> {
+    let fields = if weighted { 3 } else { 2 };
+    let threads = match opts.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()).min(MAX_LOAD_THREADS),
+        t => t,
+    };
+    let min_piece = (blocks.size / MIN_PIECES).max(1);
+    let reserve = m.min(MAX_PREALLOC_EDGES.max(blocks.unread() / 4));
+    let mut check = EdgeCheck::new(opts, m, bounds, sides, reserve, weighted);
+    let mut lexed = Vec::new();
+    loop {
+        let block = blocks.block();
+        let pieces = threads.min(block.len() / min_piece).max(1);
+        lex_block(block, pieces, fields, opts.format, &mut lexed);
+        for piece in lexed.iter_mut() {
+            check.apply(piece, blocks.line)?;
+            if check.truncated {
+                return check.finish();
+            }
+            blocks.line += piece.count;
+        }
+        if !blocks.advance()? {
+            return check.finish();
+        }
+    }
+}
+
+/* The binary format (combi convert): a 40-byte header, then m edges as
+ * two little-endian u32 ids each, then, if weighted, m little-endian i64
+ * weights in edge order.
+ *
+ *   bytes 0-3    "CSBG"
+ *   bytes 4-7    version, BINARY_VERSION
+ *   bytes 8-11   flags: BINARY_BIPARTITE, BINARY_WEIGHTED
+ *   bytes 12-15  zero
+ *   bytes 16-39  u64 n (left for a bipartite graph), u64 right (0 for a
+ *                general one), u64 m
+ *
+ * The loaders tell it from text by the magic, so every load_* and parse_*
+ * reads both. The size must be exactly what the header implies (a short
+ * file is refused whatever --on-mismatch says); ids still go through the
+ * range policy, with the edge's position (from 1) as its line, and
+ * --merge-parallel applies as for text. */
+pub const BINARY_MAGIC: &[u8; 4] = b"CSBG";
+pub const BINARY_VERSION: u32 = 1;
+pub const BINARY_BIPARTITE: u32 = 1;
+pub const BINARY_WEIGHTED: u32 = 2;
+const BINARY_HEADER: usize = 40;
+
+/* A binary file's header. */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BinaryHeader {
+    pub bipartite: bool,
+    pub weighted: bool,
+    pub left: usize, /* n for a general graph */
+    pub right: usize,
+    pub m: usize,
+}
+
+impl BinaryHeader {
+    fn parse(bytes: &[u8]) -> Result<BinaryHeader, SuiteError> {
+        if bytes.len() < BINARY_HEADER {
+            return Err(SuiteError::Format(format!("binary graph: header cut short ({} of {} bytes)", bytes.len(), BINARY_HEADER)));
+        }
+        let word = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let size = |i: usize| -> Result<usize, SuiteError> {
+            let x = u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+            usize::try_from(x).map_err(|_| SuiteError::Format(format!("binary graph: {} does not fit in memory", x)))
+        };
+        if word(4) != BINARY_VERSION {
+            return Err(SuiteError::Format(format!("binary graph: version {}, this build reads {}", word(4), BINARY_VERSION)));
+        }
+        let flags = word(8);
+        if flags & !(BINARY_BIPARTITE | BINARY_WEIGHTED) != 0 || word(12) != 0 {
+            return Err(SuiteError::Format(format!("binary graph: unknown flags {:#x}", flags)));
+        }
+        let header = BinaryHeader {
+            bipartite: flags & BINARY_BIPARTITE != 0,
+            weighted: flags & BINARY_WEIGHTED != 0,
+            left: size(16)?,
+            right: size(24)?,
+            m: size(32)?,
+        };
+        if !header.bipartite && header.right != 0 {
+            return Err(SuiteError::Format("binary graph: a general graph with a right side".into()));
+        }
+        Ok(header)
+    }
+
+    /* File size the header implies, None past u64. */
+    fn file_size(&self) -> Option<u64> {
+        let per_edge = if self.weighted { 16 } else { 8 };
+        (self.m as u64).checked_mul(per_edge)?.checked_add(BINARY_HEADER as u64)
+    }
+}
+
+/* The form of `filename`: "binary", "JSON", "GraphML" or "text". */
+pub fn input_format(filename: &str) -> Result<&'static str, SuiteError> {
+    let mut blocks = open_blocks(filename, 1 << 16)?;
+    if blocks.is_binary()? {
+        return Ok("binary");
+    }
+    Ok(blocks.structured()?.map_or("text", Structured::name))
+}
+
+/* Whether `filename` holds a weighted graph: the binary header's flag,
+ * a weight on any edge of a JSON or GraphML document (read whole), or an
+ * integer third column on the first edge line of text, after the header
+ * unless the input is --format snap or labels. */
+pub fn has_weights(filename: &str, input: InputFormat) -> Result<bool, SuiteError> {
+    if input == InputFormat::Auto {
+        if let Some(h) = binary_header(filename)? {
+            return Ok(h.weighted);
+        }
+    }
+    let mut blocks = open_blocks(filename, 1 << 16)?;
+    if input == InputFormat::Auto {
+        if let Some(kind) = blocks.structured()? {
+            return Ok(structured_document(&mut blocks, kind, false, false)?.weighted > 0);
+        }
+    }
+    let head = blocks.head(1 << 16)?;
+    /* a line cut off by the end of the head is not looked at */
+    let whole = if head.len() < 1 << 16 { head } else { &head[..head.iter().rposition(|&b| b == b'\n').unwrap_or(0)] };
+    let mut header = input.has_header();
+    for line in whole.split(|&b| b == b'\n') {
+        if input == InputFormat::Labels {
+            match label_fields(&String::from_utf8_lossy(line)) {
+                Ok(f) if f.is_empty() => continue,
+                Ok(f) => return Ok(f.len() == 3 && scan_i64(f[2].as_bytes()).is_ok()),
+                Err(_) => return Ok(false),
+            }
+        }
+        if let Line::Fields(f, found) = lex_line(line, FormatPolicy::Tolerant) {
+            if header {
+                header = false;
+            } else {
+                return Ok(found == 3 && scan_i64(f[2]).is_ok());
+            }
+        }
+    }
+    Ok(false)
+}
+
+/* The header, if `filename` is a binary graph; None for text. */
+pub fn binary_header(filename: &str) -> Result<Option<BinaryHeader>, SuiteError> {
+    let mut blocks = open_blocks(filename, 1 << 16)?;
+    if !blocks.is_binary()? {
+        return Ok(None);
+    }
+    BinaryHeader::parse(blocks.head(BINARY_HEADER)?).map(Some)
+}
+
+/* Edges of a binary input, through the same checks as text edge lines.
+ * `bipartite` and `weighted` are what the caller loads: the file must
+ * agree on the first and have weights if the second is asked for (a
+ * weighted file read unweighted drops them). */
+fn read_binary<R: BufRead>(
+    mut blocks: Blocks<R>, bipartite: bool, weighted: bool, opts: &LoadOptions,
+) -> Result<Loaded, SuiteError> {
+    let bytes = blocks.rest()?;
+    let h = BinaryHeader::parse(bytes)?;
+    if h.bipartite != bipartite {
+        let (is, want) = if h.bipartite { ("bipartite", "general") } else { ("general", "bipartite") };
+        return Err(SuiteError::Format(format!("binary graph: the file holds a {} graph, a {} one was expected", is, want)));
+    }
+    if weighted && !h.weighted {
+        return Err(SuiteError::Format("binary graph: the file has no edge weights".into()));
+    }
+    if h.file_size() != Some(bytes.len() as u64) {
+        let need = h.file_size().map_or("more than 2^64".to_string(), |s| s.to_string());
+        return Err(SuiteError::Format(format!("binary graph: {} edges need {} bytes, the file has {} (truncated?)",
+                           h.m, need, bytes.len())));
+    }
+    let (bounds, sides) =
+        if bipartite { ((h.left, h.right), ("left", "right")) } else { ((h.left, h.left), ("n", "n")) };
+    let mut check = EdgeCheck::new(opts, h.m, bounds, sides, h.m, weighted);
+    if h.weighted && !weighted {
+        check.ignored_weights = h.m;
+    }
+    let (pairs, weights) = bytes[BINARY_HEADER..].split_at(8 * h.m);
+    for (k, pair) in pairs.chunks_exact(8).enumerate() {
+        let u = u32::from_le_bytes(pair[..4].try_into().unwrap()) as usize;
+        let v = u32::from_le_bytes(pair[4..].try_into().unwrap()) as usize;
+        let w = if weighted { Some(i64::from_le_bytes(weights[8 * k..8 * k + 8].try_into().unwrap())) } else { None };
+        check.edge(u, v, w, k + 1, k + 1)?;
+    }
+    check.finish()
+}
+
+/* The structured formats, for graphs exported by networkx, igraph and the
+ * like, told from text by their first non-blank byte:
+ *
+ *   JSON     '{'  {"n": 4, "edges": [[0, 1], [1, 2], [2, 3]]}
+ *                 bipartite: "left" and "right" in place of "n"; weighted:
+ *                 [u, v, w] edges; an optional "m" is checked as a text
+ *                 header's is; other keys are skipped
+ *   GraphML  '<'  <node id=...> and <edge source=... target=...> of one
+ *                 <graph>; the weight is the edge <data> whose <key> has
+ *                 attr.name="weight", a bipartite graph's side the node
+ *                 <data> whose <key> has attr.name="bipartite" (0 left,
+ *                 1 right, as networkx writes it)
+ *
+ * GraphML ids are strings: ids that are all numbers ("7", or igraph's
+ * "n7") are the vertex ids, any others number the nodes 0, 1, ... in
+ * document order, as does each side of a bipartite graph. Edge direction
+ * (edgedefault) is ignored. A weight may be written 3 or 3.0, but must be
+ * a whole number. Both formats are read whole into memory; the edges then
+ * go through the range and merge policies as text lines do, at the line
+ * they are written on. */
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Structured {
+    Json,
+    GraphMl,
+}
+
+impl Structured {
+    fn name(self) -> &'static str {
+        match self {
+            Structured::Json => "JSON",
+            Structured::GraphMl => "GraphML",
+        }
+    }
+}
+
+/* Leading bytes looked at for a structured format's first byte. */
+const SNIFF_BYTES: usize = 4096;
+
+/* A structured file's graph before the policies: sides (right is None for
+ * a general graph), the declared m if any, (u, v, weight, line) per edge,
+ * and whether GraphML ids were numbered in document order. */
+type DocumentEdge = (usize, usize, Option<i64>, usize);
+
+struct Document {
+    left: usize,
+    right: Option<usize>,
+    m: Option<usize>,
+    edges: Vec<DocumentEdge>,
+    weighted: usize, /* edges with a weight, read or not */
+    renumbered: bool,
+}
+
+/* A weight written as an integer, or as a float with no fraction. */
+fn whole_number(s: &str) -> Option<i64> {
+    if let Ok(w) = s.parse::<i64>() {
+        return Some(w);
+    }
+    let x: f64 = s.parse().ok()?;
+    if x.fract() == 0.0 && x.abs() < 9.0e18 { Some(x as i64) } else { None }
+}
+
+/* A JSON reader that keeps the line it is on, for the messages. */
+struct Json<'a> {
+    text: &'a [u8],
+    at: usize,
+    line: usize,
+}
+
+impl<'a> Json<'a> {
+    fn error(&self, what: String) -> SuiteError {
+        SuiteError::Format(format!("JSON graph: line {}: {}", self.line, what))
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        while let Some(&b) = self.text.get(self.at).filter(|&&b| is_space(b)) {
+            if b == b'\n' { self.line += 1; }
+            self.at += 1;
+        }
+        self.text.get(self.at).copied()
+    }
+
+    fn expect(&mut self, want: u8) -> Result<(), SuiteError> {
+        match self.peek() {
+            Some(b) if b == want => {
+                self.at += 1;
+                Ok(())
+            }
+            Some(b) => Err(self.error(format!("expected '{}', found '{}'", want as char, b as char))),
+            None => Err(self.error(format!("expected '{}', found the end of the file", want as char))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SuiteError> {
+        self.expect(b'"')?;
+        let mut s = Vec::new();
+        loop {
+            match self.text.get(self.at) {
+                None | Some(b'\n') => return Err(self.error("unterminated string".to_string())),
+                Some(b'"') => break,
+                Some(b'\\') => {
+                    let c = self.text.get(self.at + 1).copied().unwrap_or(b'"');
+                    self.at += 1;
+                    match c {
+                        b'n' => s.push(b'\n'),
+                        b't' => s.push(b'\t'),
+                        b'r' => s.push(b'\r'),
+                        b'b' => s.push(8),
+                        b'f' => s.push(12),
+                        b'u' => {
+                            let hex = self.text.get(self.at + 1..self.at + 5)
+                                .and_then(|h| u32::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok())
+                                .ok_or_else(|| self.error("bad \\u escape".to_string()))?;
+                            let c = std::char::from_u32(hex).unwrap_or('\u{fffd}');
+                            s.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                            self.at += 4;
+                        }
+                        c => s.push(c),
+                    }
+                }
+                Some(&b) => s.push(b),
+            }
+            self.at += 1;
+        }
+        self.at += 1;
+        Ok(String::from_utf8_lossy(&s).into_owned())
+    }
+
+    /* A number, literal or other bare token, as written. */
+    fn token(&mut self) -> Result<&'a str, SuiteError> {
+        self.peek();
+        let start = self.at;
+        while self.text.get(self.at).is_some_and(|&b| !is_space(b) && !b",:]}[{\"".contains(&b)) {
+            self.at += 1;
+        }
+        if start == self.at {
+            return Err(match self.text.get(self.at) {
+                Some(&b) => self.error(format!("unexpected '{}'", b as char)),
+                None => self.error("unexpected end of the file".to_string()),
+            });
+        }
+        std::str::from_utf8(&self.text[start..self.at]).map_err(|e| self.error(e.to_string()))
+    }
+
+    fn count(&mut self, key: &str) -> Result<usize, SuiteError> {
+        let t = self.token()?;
+        t.parse().map_err(|_| self.error(format!("\"{}\" must be a non-negative integer, not {}", key, t)))
+    }
+
+    /* Any value, unread. Nesting is counted rather than recursed into, so
+     * a deep document cannot overflow the stack. */
+    fn skip_value(&mut self) -> Result<(), SuiteError> {
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                Some(b'"') => { self.string()?; }
+                Some(b'{') | Some(b'[') => {
+                    self.at += 1;
+                    depth += 1;
+                    continue;
+                }
+                Some(b'}') | Some(b']') if depth > 0 => {
+                    self.at += 1;
+                    depth -= 1;
+                }
+                Some(b',') | Some(b':') if depth > 0 => {
+                    self.at += 1;
+                    continue;
+                }
+                _ => { self.token()?; }
+            }
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /* The "edges" array: [u, v] or [u, v, w] each, w read only if
+     * `weighted`; also how many have a w. */
+    fn edges(&mut self, weighted: bool) -> Result<(Vec<DocumentEdge>, usize), SuiteError> {
+        let mut edges = Vec::new();
+        let mut with_weight = 0;
+        self.expect(b'[')?;
+        if self.peek() == Some(b']') {
+            self.at += 1;
+            return Ok((edges, 0));
+        }
+        loop {
+            self.expect(b'[')?;
+            let line = self.line;
+            let mut fields = Vec::with_capacity(3);
+            if self.peek() != Some(b']') {
+                loop {
+                    fields.push(self.token()?);
+                    if self.peek() != Some(b',') { break; }
+                    self.at += 1;
+                }
+            }
+            self.expect(b']')?;
+            if fields.len() != 2 && fields.len() != 3 {
+                return Err(self.error("an edge is [u, v] or [u, v, w]".to_string()));
+            }
+            let id = |t: &str| t.parse::<usize>().map_err(|_| SuiteError::Format(format!("JSON graph: line {}: invalid vertex id {}", line, t)));
+            with_weight += (fields.len() == 3) as usize;
+            let w = match fields.get(2) {
+                Some(t) if weighted => Some(whole_number(t).ok_or_else(|| {
+                    SuiteError::Format(format!("JSON graph: line {}: weight {} is not a whole number", line, t))
+                })?),
+                _ => None,
+            };
+            edges.push((id(fields[0])?, id(fields[1])?, w, line));
+            match self.peek() {
+                Some(b',') => self.at += 1,
+                _ => break,
+            }
+        }
+        self.expect(b']')?;
+        Ok((edges, with_weight))
+    }
+
+    fn document(&mut self, weighted: bool) -> Result<Document, SuiteError> {
+        let (mut n, mut left, mut right, mut m, mut edges) = (None, None, None, None, None);
+        self.expect(b'{')?;
+        if self.peek() != Some(b'}') {
+            loop {
+                let key = self.string()?;
+                self.expect(b':')?;
+                match key.as_str() {
+                    "n" => n = Some(self.count("n")?),
+                    "left" => left = Some(self.count("left")?),
+                    "right" => right = Some(self.count("right")?),
+                    "m" => m = Some(self.count("m")?),
+                    "edges" => edges = Some(self.edges(weighted)?),
+                    _ => self.skip_value()?,
+                }
+                if self.peek() != Some(b',') { break; }
+                self.at += 1;
+            }
+        }
+        self.expect(b'}')?;
+        if let Some(b) = self.peek() {
+            return Err(self.error(format!("'{}' after the graph object", b as char)));
+        }
+        let edges = edges.ok_or_else(|| SuiteError::Format("JSON graph: no \"edges\" array".into()))?;
+        let (left, right) = match (n, left, right) {
+            (Some(n), None, None) => (n, None),
+            (None, Some(l), Some(r)) => (l, Some(r)),
+            (None, None, None) => return Err(SuiteError::Format("JSON graph: no \"n\" (or \"left\" and \"right\")".into())),
+            _ => return Err(SuiteError::Format("JSON graph: give either \"n\" or both \"left\" and \"right\"".into())),
+        };
+        let (edges, with_weight) = edges;
+        Ok(Document { left, right, m, edges, weighted: with_weight, renumbered: false })
+    }
+}
+
+/* A GraphML tag: its local name (no namespace prefix), attributes, line
+ * and whether it closes itself; or an end tag, or character data. */
+enum Xml<'a> {
+    Start(&'a str, Vec<(&'a str, String)>, usize, bool),
+    End(&'a str),
+    Text(String),
+}
+
+/* The five predefined entities and character references. */
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('&') {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        let end = match rest.find(';') {
+            Some(e) => e,
+            None => break,
+        };
+        let c = match &rest[1..end] {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            e if e.starts_with("#x") => u32::from_str_radix(&e[2..], 16).ok().and_then(std::char::from_u32),
+            e if e.starts_with('#') => e[1..].parse().ok().and_then(std::char::from_u32),
+            _ => None,
+        };
+        match c {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/* The tags of an XML document in order, skipping the declaration,
+ * comments, processing instructions and the doctype. */
+struct XmlReader<'a> {
+    text: &'a str,
+    at: usize,
+    line: usize,
+}
+
+impl<'a> XmlReader<'a> {
+    fn error(&self, what: String) -> SuiteError {
+        SuiteError::Format(format!("GraphML graph: line {}: {}", self.line, what))
+    }
+
+    /* Moves past the next `end`, counting lines. */
+    fn skip_past(&mut self, end: &str) -> Result<&'a str, SuiteError> {
+        let rest = &self.text[self.at..];
+        let i = rest.find(end).ok_or_else(|| self.error(format!("no closing {}", end)))?;
+        self.line += rest[..i + end.len()].bytes().filter(|&b| b == b'\n').count();
+        self.at += i + end.len();
+        Ok(&rest[..i])
+    }
+
+    fn next(&mut self) -> Result<Option<Xml<'a>>, SuiteError> {
+        loop {
+            let rest = &self.text[self.at..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+            if !rest.starts_with('<') {
+                let i = rest.find('<').unwrap_or(rest.len());
+                self.line += rest[..i].bytes().filter(|&b| b == b'\n').count();
+                self.at += i;
+                return Ok(Some(Xml::Text(unescape(&rest[..i]))));
+            }
+            if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<![CDATA[") {
+                self.at += "<![CDATA[".len();
+                return Ok(Some(Xml::Text(self.skip_past("]]>")?.to_string())));
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!") {
+                self.skip_past(">")?;
+            } else if rest.starts_with("</") {
+                let name = self.skip_past(">")?[2..].trim();
+                return Ok(Some(Xml::End(local_name(name))));
+            } else {
+                return self.start().map(Some);
+            }
+        }
+    }
+
+    fn start(&mut self) -> Result<Xml<'a>, SuiteError> {
+        let line = self.line;
+        let tag = self.skip_past(">")?;
+        let (tag, closed) = match tag.strip_suffix('/') {
+            Some(t) => (t, true),
+            None => (tag, false),
+        };
+        let tag = &tag[1..];
+        let name_end = tag.find(|c: char| c.is_ascii_whitespace()).unwrap_or(tag.len());
+        let mut attrs = Vec::new();
+        let mut rest = tag[name_end..].trim_start();
+        while !rest.is_empty() {
+            let eq = rest.find('=').ok_or_else(|| SuiteError::Format(format!("GraphML graph: line {}: bad attribute in <{}>", line, tag)))?;
+            let key = rest[..eq].trim();
+            let after = rest[eq + 1..].trim_start();
+            let quote = after.chars().next().filter(|&c| c == '"' || c == '\'')
+                .ok_or_else(|| SuiteError::Format(format!("GraphML graph: line {}: unquoted attribute {} in <{}>", line, key, tag)))?;
+            let close = after[1..].find(quote)
+                .ok_or_else(|| SuiteError::Format(format!("GraphML graph: line {}: unterminated attribute {}", line, key)))?;
+            attrs.push((local_name(key), unescape(&after[1..1 + close])));
+            rest = after[close + 2..].trim_start();
+        }
+        Ok(Xml::Start(local_name(&tag[..name_end]), attrs, line, closed))
+    }
+}
+
+/* Where the text of an open <data> or <default> goes. */
+enum Capture {
+    Side(usize),
+    Weight(usize),
+    Default(String),
+}
+
+/* A GraphML node id that is a number: "7", or igraph's "n7". */
+fn numeric_id(id: &str) -> Option<usize> {
+    let digits = id.strip_prefix('n').unwrap_or(id);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn graphml_document(text: &str, bipartite: bool, weighted: bool) -> Result<Document, SuiteError> {
+    let mut xml = XmlReader { text, at: 0, line: 1 };
+    let (mut weight_key, mut side_key) = (None, None);
+    let mut defaults: HashMap<String, String> = HashMap::new();
+    let mut nodes: Vec<(String, Option<String>, usize)> = Vec::new();
+    let mut edges: Vec<(String, String, Option<String>, usize)> = Vec::new();
+    let (mut graphs, mut owner, mut key, mut capture) = (0, None, None, None);
+    while let Some(tag) = xml.next()? {
+        match tag {
+            Xml::Start(name, attrs, line, closed) => {
+                let attr = |a: &str| attrs.iter().find(|(k, _)| *k == a).map(|(_, v)| v.clone());
+                let need = |a: &str| attr(a).ok_or_else(|| SuiteError::Format(format!("GraphML graph: line {}: <{}> without {}", line, name, a)));
+                match name {
+                    "key" => {
+                        let id = need("id")?;
+                        let domain = attr("for").unwrap_or_else(|| "all".to_string());
+                        match attr("attr.name").as_deref() {
+                            Some("weight") if domain == "edge" || domain == "all" => weight_key = Some(id.clone()),
+                            Some("bipartite") if domain == "node" || domain == "all" => side_key = Some(id.clone()),
+                            _ => {}
+                        }
+                        if !closed { key = Some(id); }
+                    }
+                    "default" => capture = key.clone().map(|k| (Capture::Default(k), String::new())),
+                    "graph" => {
+                        graphs += 1;
+                        if graphs > 1 {
+                            return Err(SuiteError::Format(format!("GraphML graph: line {}: a second <graph> (one graph per file, \
+                                                not nested)", line)));
+                        }
+                    }
+                    "node" => {
+                        nodes.push((need("id")?, None, line));
+                        if !closed { owner = Some(false); }
+                    }
+                    "edge" => {
+                        edges.push((need("source")?, need("target")?, None, line));
+                        if !closed { owner = Some(true); }
+                    }
+                    "hyperedge" => return Err(SuiteError::Format(format!("GraphML graph: line {}: hyperedges are not read", line))),
+                    "data" if !closed => {
+                        let k = attr("key");
+                        capture = match owner {
+                            Some(false) if k.is_some() && k == side_key => Some((Capture::Side(nodes.len() - 1), String::new())),
+                            Some(true) if k.is_some() && k == weight_key => Some((Capture::Weight(edges.len() - 1), String::new())),
+                            _ => None,
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            Xml::Text(t) => {
+                if let Some((_, ref mut s)) = capture {
+                    s.push_str(&t);
+                }
+            }
+            Xml::End(name) => match name {
+                "data" | "default" => match capture.take() {
+                    Some((Capture::Side(i), s)) => nodes[i].1 = Some(s.trim().to_string()),
+                    Some((Capture::Weight(i), s)) => edges[i].2 = Some(s.trim().to_string()),
+                    Some((Capture::Default(k), s)) => { defaults.insert(k, s.trim().to_string()); }
+                    None => {}
+                },
+                "key" => key = None,
+                "node" | "edge" => owner = None,
+                _ => {}
+            },
+        }
+    }
+    if graphs == 0 {
+        return Err(SuiteError::Format("GraphML graph: no <graph> element".into()));
+    }
+    let default_of = |k: &Option<String>| k.as_ref().and_then(|k| defaults.get(k)).cloned();
+    let (side_default, weight_default) = (default_of(&side_key), default_of(&weight_key));
+
+    /* The vertex of each node id, and the side of each vertex. */
+    let mut index: HashMap<&str, (usize, bool)> = HashMap::with_capacity(nodes.len());
+    let (mut left, mut right) = (0, 0);
+    for (id, side, line) in &nodes {
+        let vertex = if bipartite {
+            match side.as_ref().or(side_default.as_ref()).map(|s| s.as_str()) {
+                Some("0") => { left += 1; (left - 1, false) }
+                Some("1") => { right += 1; (right - 1, true) }
+                _ => return Err(SuiteError::Format(format!("GraphML graph: line {}: node '{}' has no bipartite side (a \"bipartite\" \
+                                         node attribute of 0 or 1)", line, id))),
+            }
+        } else {
+            (index.len(), false)
+        };
+        if index.insert(id.as_str(), vertex).is_some() {
+            return Err(SuiteError::Format(format!("GraphML graph: line {}: node '{}' declared twice", line, id)));
+        }
+    }
+    let mut renumbered = false;
+    if !bipartite {
+        let numbers: Option<Vec<usize>> = nodes.iter().map(|node| numeric_id(&node.0)).collect();
+        let distinct = numbers.as_ref().is_some_and(|ns| {
+            let mut sorted = ns.clone();
+            sorted.sort_unstable();
+            sorted.windows(2).all(|w| w[0] != w[1])
+        });
+        match numbers {
+            Some(ns) if distinct => {
+                left = ns.iter().max().map_or(0, |&x| x + 1);
+                for ((id, _, _), x) in nodes.iter().zip(ns) {
+                    index.insert(id.as_str(), (x, false));
+                }
+            }
+            _ => {
+                left = nodes.len();
+                renumbered = left > 0;
+            }
+        }
+    }
+
+    let mut out = Vec::with_capacity(edges.len());
+    let mut weighted_edges = 0;
+    for (source, target, weight, line) in &edges {
+        let at = |id: &String| index.get(id.as_str()).copied()
+            .ok_or_else(|| SuiteError::Format(format!("GraphML graph: line {}: edge end '{}' is not a node", line, id)));
+        let (mut u, mut v) = (at(source)?, at(target)?);
+        if bipartite {
+            if u.1 == v.1 {
+                return Err(SuiteError::Format(format!("GraphML graph: line {}: edge {}-{} joins two vertices of one side",
+                                   line, source, target)));
+            }
+            if u.1 { std::mem::swap(&mut u, &mut v); }
+        }
+        let weight = weight.as_ref().or(weight_default.as_ref());
+        weighted_edges += weight.is_some() as usize;
+        let w = match weight {
+            Some(w) if weighted => Some(whole_number(w).ok_or_else(|| {
+                SuiteError::Format(format!("GraphML graph: line {}: weight '{}' is not a whole number", line, w))
+            })?),
+            _ => None,
+        };
+        out.push((u.0, v.0, w, *line));
+    }
+    Ok(Document {
+        left, right: if bipartite { Some(right) } else { None }, m: None, edges: out, weighted: weighted_edges, renumbered,
+    })
+}
+
+/* The whole structured input, parsed. A JSON file says whether it is
+ * bipartite; a GraphML one is read as `bipartite` asks. */
+fn structured_document<R: BufRead>(
+    blocks: &mut Blocks<R>, kind: Structured, bipartite: bool, weighted: bool,
+) -> Result<Document, SuiteError> {
+    let bytes = blocks.rest()?;
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    match kind {
+        Structured::Json => Json { text: bytes, at: 0, line: 1 }.document(weighted),
+        Structured::GraphMl => {
+            let text = std::str::from_utf8(bytes).map_err(|e| SuiteError::Format(format!("GraphML graph: not UTF-8 ({})", e)))?;
+            graphml_document(text, bipartite, weighted)
+        }
+    }
+}
+
+/* Edges of a structured input, through the same checks as text edge
+ * lines; a JSON file must agree with `bipartite`. */
+fn read_structured<R: BufRead>(
+    mut blocks: Blocks<R>, kind: Structured, bipartite: bool, weighted: bool, opts: &LoadOptions,
+) -> Result<Loaded, SuiteError> {
+    let doc = structured_document(&mut blocks, kind, bipartite, weighted)?;
+    if doc.renumbered {
+        log::warn(format_args!("GraphML node ids are not all numbers: numbering the {} nodes in document order", doc.left));
+    }
+    if doc.right.is_some() != bipartite {
+        let (is, want) = if bipartite { ("general", "bipartite") } else { ("bipartite", "general") };
+        return Err(SuiteError::Format(format!("{} graph: the file holds a {} graph, a {} one was expected", kind.name(), is, want)));
+    }
+    let (bounds, sides) = match doc.right {
+        Some(right) => ((doc.left, right), ("left", "right")),
+        None => ((doc.left, doc.left), ("n", "n")),
+    };
+    let mut check = EdgeCheck::new(opts, doc.m.unwrap_or(doc.edges.len()), bounds, sides, doc.edges.len(), weighted);
+    if !weighted {
+        check.ignored_weights = doc.weighted;
+    }
+    /* several edges may share a line: the --edge-ids id is the position */
+    for (k, &(u, v, w, line)) in doc.edges.iter().enumerate() {
+        if !check.room()? {
+            break;
+        }
+        if weighted && w.is_none() {
+            return Err(SuiteError::Format(format!("{} graph: line {}: missing edge weight", kind.name(), line)));
+        }
+        check.edge(u, v, w, line, k + 1)?;
+    }
+    check.finish()
+}
+
+/* --format snap: the edge lists of SNAP (snap.stanford.edu/data) and
+ * similar collections, "u v" (or "u v w") lines with no header, `#`
+ * comments, and ids that are neither contiguous nor small. The ids of
+ * each side (both ends of every edge in a general graph) are sorted and
+ * deduplicated, and vertex v is the v-th smallest, so the order of the
+ * ids is kept. With no header there is no m to check and no bound to
+ * range-check against; --merge-parallel and --strict-format apply.
+ *
+ * The table comes back with the graph from the *_named loaders (see
+ * InputNames): matching_io writes --output-matching and reads
+ * --init-matching in the original ids through original_pairs() and
+ * remapped_pairs(). */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IdMap {
+    pub left: Vec<usize>,          /* original id of each vertex (left vertex) */
+    pub right: Option<Vec<usize>>, /* of each right vertex, bipartite only */
+}
+
+impl InputNames {
+    /* Pairs in the original ids (unchanged unless the load was --format
+     * snap). */
+    pub fn original_pairs(&self, pairs: &[(usize, usize)], bipartite: bool) -> Vec<(usize, usize)> {
+        match self.ids {
+            None => pairs.to_vec(),
+            Some(ref map) => {
+                let right = map.right.as_ref().filter(|_| bipartite).unwrap_or(&map.left);
+                pairs.iter().map(|&(u, v)| (map.left[u], right[v])).collect()
+            }
+        }
+    }
+
+    /* The reverse, for pairs read from a file; an id the graph does not
+     * have is an error. */
+    pub fn remapped_pairs(&self, pairs: Vec<(usize, usize)>, bipartite: bool) -> Result<Vec<(usize, usize)>, String> {
+        match self.ids {
+            None => Ok(pairs),
+            Some(ref map) => {
+                let right = map.right.as_ref().filter(|_| bipartite).unwrap_or(&map.left);
+                let at = |ids: &[usize], x: usize| ids.binary_search(&x)
+                    .map_err(|_| format!("vertex {} is not in the graph (--format snap)", x));
+                pairs.into_iter().map(|(u, v)| Ok((at(&map.left, u)?, at(right, v)?))).collect()
+            }
+        }
+    }
+}
+
+/* Sorted distinct ids. */
+fn distinct(ids: impl Iterator<Item = usize>) -> Vec<usize> {
+    let mut ids: Vec<usize> = ids.collect();
+    ids.sort_unstable();
+    ids.dedup();
+    ids
+}
+
+/* Edges of a --format snap input, remapped, and their id table. */
+fn read_snap<R: BufRead>(
+    blocks: Blocks<R>, bipartite: bool, weighted: bool, opts: &LoadOptions,
+) -> Result<(Loaded, IdMap), SuiteError> {
+    /* every line is an edge: nothing to count against, nothing out of range */
+    let lines = LoadOptions { on_mismatch: MismatchPolicy::Accept, out_of_range: RangePolicy::Drop, ..opts.clone() };
+    let (mut edges, weights, ids, _) = read_edges(blocks, 0, (usize::MAX, usize::MAX), ("n", "n"), weighted, &lines)?;
+    let (left, right) = if bipartite {
+        (distinct(edges.iter().map(|e| e.0)), Some(distinct(edges.iter().map(|e| e.1))))
+    } else {
+        (distinct(edges.iter().flat_map(|&(u, v)| [u, v])), None)
+    };
+    {
+        let other = right.as_ref().unwrap_or(&left);
+        for e in edges.iter_mut() {
+            *e = (left.binary_search(&e.0).unwrap(), other.binary_search(&e.1).unwrap());
+        }
+    }
+    let bounds = (left.len(), right.as_ref().map_or(left.len(), |r| r.len()));
+    if let Some(&largest) = left.last().max(right.as_ref().and_then(|r| r.last())) {
+        log::warn(format_args!("Remapped {} distinct ids (largest {}) to 0.. (--format snap)",
+                               left.len() + right.as_ref().map_or(0, |r| r.len()), largest));
+    }
+    Ok(((edges, weights, ids, bounds), IdMap { left, right }))
+}
+
+/* --format labels: edge lists whose vertices are named, not numbered.
+ * Each line is "a b" (or "a b w"), with no header; a label is a bare
+ * token (alice, P-17) or a double-quoted string that may hold spaces,
+ * '#' and '%', with \" and \\ for a quote and a backslash ("Smith, J.").
+ * Comments, blank lines, single-field lines and extra fields are taken as
+ * in the numeric formats, except that a line starting "c" is an edge of
+ * the vertex c, not a DIMACS comment. Each distinct label (of each side, in a
+ * bipartite graph) is interned to the next id in order of first
+ * appearance, so vertex 0 is the first label read.
+ *
+ * The labels come back with the graph in its InputNames, as the --format
+ * snap ids do: matching_io writes --output-matching and reads
+ * --init-matching in the labels through labeled_pairs() and
+ * unlabeled_pairs(). */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VertexLabels {
+    pub left: Vec<String>,          /* label of each vertex (left vertex) */
+    pub right: Option<Vec<String>>, /* of each right vertex, bipartite only */
+}
+
+/* The fields of one labeled line, quotes and escapes resolved, up to a
+ * comment; empty for a blank or comment line. */
+pub fn label_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.trim_end_matches('\r').chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            '#' | '%' => break,
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' => {
+                chars.next();
+                let mut label = String::new();
+                loop {
+                    match chars.next() {
+                        None => return Err("unterminated quoted label".to_string()),
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(e @ ('"' | '\\')) => label.push(e),
+                            _ => return Err("a backslash in a quoted label escapes only \" or \\".to_string()),
+                        },
+                        Some(c) => label.push(c),
+                    }
+                }
+                fields.push(label);
+            }
+            _ => {
+                let mut label = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '#' || c == '%' {
+                        break;
+                    }
+                    if c == '"' {
+                        return Err(format!("stray quote after '{}'; quote the whole label", label));
+                    }
+                    label.push(c);
+                    chars.next();
+                }
+                fields.push(label);
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/* `label` as written to a file: bare when it reads back as one token,
+ * quoted otherwise. */
+pub fn quote_label(label: &str) -> String {
+    let bare = !label.is_empty()
+        && !label.chars().any(|c| c.is_whitespace() || matches!(c, '"' | '\\' | '#' | '%'));
+    if bare {
+        label.to_string()
+    } else {
+        format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+/* Labels to ids in order of first appearance. */
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<String, usize>,
+    labels: Vec<String>,
+}
+
+impl Interner {
+    fn intern(&mut self, label: String) -> usize {
+        if let Some(&id) = self.ids.get(&label) {
+            return id;
+        }
+        let id = self.labels.len();
+        self.ids.insert(label.clone(), id);
+        self.labels.push(label);
+        id
+    }
+}
+
+/* Edges of a --format labels input, interned, and their labels. */
+fn read_labels<R: BufRead>(
+    mut blocks: Blocks<R>, bipartite: bool, weighted: bool, opts: &LoadOptions,
+) -> Result<(Loaded, VertexLabels), SuiteError> {
+    /* every line is an edge: nothing to count against, nothing out of range */
+    let lines = LoadOptions { on_mismatch: MismatchPolicy::Accept, out_of_range: RangePolicy::Drop, ..opts.clone() };
+    let strict = opts.format == FormatPolicy::Strict;
+    let bytes = blocks.rest()?;
+    let bytes = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    let text = std::str::from_utf8(bytes).map_err(|e| SuiteError::Format(format!("labeled graph: not UTF-8 ({})", e)))?;
+    let mut check = EdgeCheck::new(&lines, usize::MAX, (usize::MAX, usize::MAX), ("n", "n"), 0, weighted);
+    let (mut left, mut right) = (Interner::default(), Interner::default());
+    let body = text.strip_suffix('\n').unwrap_or(text);
+    let lines_of = if body.is_empty() { None } else { Some(body.split('\n')) };
+    for (i, line) in lines_of.into_iter().flatten().enumerate() {
+        let number = i + 1;
+        let refused = |what: &str| SuiteError::ParseEdge { line: number, what: format!("{} (refused by --strict-format)", what) };
+        let mut fields = label_fields(line).map_err(|what| SuiteError::ParseEdge { line: number, what })?;
+        if strict && line.ends_with('\r') {
+            return Err(refused("CRLF line ending"));
+        }
+        if fields.is_empty() {
+            if strict {
+                return Err(refused(if line.trim().is_empty() { "blank line" } else { "comment" }));
+            }
+            continue;
+        }
+        let weight = fields.get(2).map(|f| scan_i64(f.as_bytes()));
+        let weight_column = !weighted && fields.len() == 3 && weight.as_ref().is_some_and(|w| w.is_ok());
+        let expected = if weighted { 3 } else { 2 };
+        if strict && fields.len() != expected && !weight_column {
+            return Err(refused(&format!("expected {} fields, got {}", expected, fields.len())));
+        }
+        if fields.len() < 2 {
+            check.note_short(number);
+            continue;
+        }
+        let w = match (weighted, weight) {
+            (false, _) => None,
+            (true, None) => return Err(SuiteError::ParseEdge { line: number, what: "missing edge weight".to_string() }),
+            (true, Some(Err(e))) => return Err(SuiteError::ParseEdge { line: number, what: e.to_string() }),
+            (true, Some(Ok(w))) => Some(w),
+        };
+        if weight_column {
+            check.ignored_weights += 1;
+        }
+        fields.truncate(2);
+        let (b, a) = (fields.pop().unwrap(), fields.pop().unwrap());
+        let u = left.intern(a);
+        let v = if bipartite { right.intern(b) } else { left.intern(b) };
+        check.edge(u, v, w, number, number)?;
+    }
+    let (edges, weights, ids, _) = check.finish()?;
+    let bounds = (left.labels.len(), if bipartite { right.labels.len() } else { left.labels.len() });
+    if bounds.0 > 0 {
+        log::warn(format_args!("Interned {} distinct labels to 0.. (--format labels)",
+                               left.labels.len() + right.labels.len()));
+    }
+    let labels = VertexLabels { left: left.labels, right: if bipartite { Some(right.labels) } else { None } };
+    Ok(((edges, weights, ids, bounds), labels))
+}
+
+impl InputNames {
+    /* Pairs as the labels, None unless the load was --format labels. */
+    pub fn labeled_pairs(&self, pairs: &[(usize, usize)], bipartite: bool) -> Option<Vec<(String, String)>> {
+        let labels = self.labels.as_ref()?;
+        let right = labels.right.as_ref().filter(|_| bipartite).unwrap_or(&labels.left);
+        Some(pairs.iter().map(|&(u, v)| (labels.left[u].clone(), right[v].clone())).collect())
+    }
+
+    /* The reverse, for pairs read from a file; a label the graph does not
+     * have is an error. */
+    pub fn unlabeled_pairs(&self, pairs: Vec<(String, String)>, bipartite: bool) -> Result<Vec<(usize, usize)>, String> {
+        let labels = self.labels.as_ref().ok_or("the graph was not loaded with --format labels")?;
+        let right = labels.right.as_ref().filter(|_| bipartite).unwrap_or(&labels.left);
+        let index = |labels: &[String]| -> HashMap<String, usize> {
+            labels.iter().enumerate().map(|(k, l)| (l.clone(), k)).collect()
+        };
+        let (left_ids, right_ids) = (index(&labels.left), index(right));
+        let at = |ids: &HashMap<String, usize>, x: &str| ids.get(x).copied()
+            .ok_or_else(|| format!("vertex {} is not in the graph (--format labels)", quote_label(x)));
+        pairs.iter().map(|(u, v)| Ok((at(&left_ids, u)?, at(&right_ids, v)?))).collect()
+    }
+}
+
+/* --edge-ids: the edges of a multigraph by identity, for applications
+ * (edge coloring, T-joins) that must know which of several parallel
+ * edges was matched. An edge's id is its line in a text file, counted
+ * from 1 as in the loader's messages (the header and comments included),
+ * or its position from 1 in a binary, JSON or GraphML file, where lines
+ * do not tell edges apart. Every parallel edge is kept: --merge-parallel
+ * is refused with it.
+ *
+ * The solvers still match vertices. The table comes back with the graph
+ * in its InputNames, as the --format snap one does: matching_io writes
+ * --output-matching and reads --init-matching and combi check files as
+ * edge ids through ids_of() and pairs_of(). A pair joined by parallel edges is written as
+ * the heaviest of them (the lightest when minimizing), ties and
+ * unweighted graphs going to the smallest id. */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EdgeIds {
+    pub ids: Vec<usize>,            /* of each loaded edge, in edge order (so increasing) */
+    edges: Vec<(usize, usize, i64)>, /* the loaded edges, weight 0 when unweighted */
+    by_pair: Vec<usize>,            /* edge positions by pair, then id */
+    bipartite: bool,
+}
+
+impl EdgeIds {
+    fn new(edges: Vec<(usize, usize, i64)>, ids: Vec<usize>, bipartite: bool) -> Self {
+        let mut t = EdgeIds { ids, edges, by_pair: Vec::new(), bipartite };
+        let mut by_pair: Vec<usize> = (0..t.edges.len()).collect();
+        by_pair.sort_by_key(|&k| (t.key(t.edges[k].0, t.edges[k].1), k));
+        t.by_pair = by_pair;
+        t
+    }
+
+    fn key(&self, u: usize, v: usize) -> (usize, usize) {
+        if self.bipartite { (u, v) } else { (u.min(v), u.max(v)) }
+    }
+
+    /* The edge with this id, (u, v, weight). */
+    pub fn edge(&self, id: usize) -> Option<(usize, usize, i64)> {
+        self.ids.binary_search(&id).ok().map(|k| self.edges[k])
+    }
+
+    /* The id of each pair's edge; a pair that is no edge is an error. */
+    pub fn ids_of(&self, pairs: &[(usize, usize)], lightest: bool) -> Result<Vec<usize>, String> {
+        pairs.iter().map(|&(u, v)| {
+            let key = self.key(u, v);
+            let lo = self.by_pair.partition_point(|&k| self.key(self.edges[k].0, self.edges[k].1) < key);
+            let hi = self.by_pair.partition_point(|&k| self.key(self.edges[k].0, self.edges[k].1) <= key);
+            /* min_by_key keeps the first, smallest id, of equal weights */
+            let best = if lightest {
+                self.by_pair[lo..hi].iter().min_by_key(|&&k| self.edges[k].2)
+            } else {
+                self.by_pair[lo..hi].iter().min_by_key(|&&k| std::cmp::Reverse(self.edges[k].2))
+            };
+            best.map(|&k| self.ids[k]).ok_or_else(|| format!("pair ({}, {}) is not an edge of the graph", u, v))
+        }).collect()
+    }
+
+    /* The pairs of edge ids read from a file; an id the graph does not
+     * have is an error. */
+    pub fn pairs_of(&self, ids: &[usize]) -> Result<Vec<(usize, usize)>, String> {
+        ids.iter().map(|&id| {
+            self.edge(id).map(|(u, v, _)| (u, v)).ok_or_else(|| format!("edge id {} is not an edge of the graph (--edge-ids)", id))
+        }).collect()
+    }
+}
+
+/* The table of a finished load, if it asked for one. */
+fn edge_ids(opts: &LoadOptions, edges: &[(usize, usize)], weights: &[i64], ids: Vec<usize>, bipartite: bool)
+            -> Option<EdgeIds> {
+    if !opts.edge_ids {
+        return None;
+    }
+    let edges = edges.iter().enumerate().map(|(k, &(u, v))| (u, v, weights.get(k).copied().unwrap_or(0))).collect();
+    Some(EdgeIds::new(edges, ids, bipartite))
+}
+
+/* Writes a graph in the binary format: `right` is None for a general
+ * graph on `left` vertices. Ids must fit the format's u32. */
+pub fn write_binary<W: Write>(
+    out: W, left: usize, right: Option<usize>, edges: &[(usize, usize)], weights: Option<&[i64]>,
+) -> io::Result<()> {
+    let mut out = BufWriter::with_capacity(1 << 20, out);
+    let mut flags = 0;
+    if right.is_some() { flags |= BINARY_BIPARTITE; }
+    if weights.is_some() { flags |= BINARY_WEIGHTED; }
+    out.write_all(BINARY_MAGIC)?;
+    for word in [BINARY_VERSION, flags, 0] {
+        out.write_all(&word.to_le_bytes())?;
+    }
+    for size in [left, right.unwrap_or(0), edges.len()] {
+        out.write_all(&(size as u64).to_le_bytes())?;
+    }
+    for &(u, v) in edges {
+        for x in [u, v] {
+            let id = u32::try_from(x).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput,
+                format!("vertex {} does not fit the binary format's 32-bit ids", x)))?;
+            out.write_all(&id.to_le_bytes())?;
+        }
+    }
+    for &w in weights.unwrap_or(&[]) {
+        out.write_all(&w.to_le_bytes())?;
+    }
+    out.flush()
+}
+
+/* Writes a graph as text: "n m" or "left right m", then "u v" or "u v w"
+ * lines. */
+pub fn write_text<W: Write>(
+    out: W, left: usize, right: Option<usize>, edges: &[(usize, usize)], weights: Option<&[i64]>,
+) -> io::Result<()> {
+    let mut out = BufWriter::with_capacity(1 << 20, out);
+    match right {
+        Some(right) => writeln!(out, "{} {} {}", left, right, edges.len())?,
+        None => writeln!(out, "{} {}", left, edges.len())?,
+    }
+    for (k, &(u, v)) in edges.iter().enumerate() {
+        match weights {
+            Some(ws) => writeln!(out, "{} {} {}", u, v, ws[k])?,
+            None => writeln!(out, "{} {}", u, v)?,
+        }
+    }
+    out.flush()
+}
+
+/* Fold parallel edges by `policy`; `symmetric` makes (u, v) and (v, u) one
+ * pair. Each pair keeps the position and orientation of its first line.
+ * Returns the edges and the number of lines folded away. */
+pub type Merged = (Vec<(usize, usize, i64)>, usize);
+
+pub fn merge_parallel(
+    edges: Vec<(usize, usize, i64)>, symmetric: bool, policy: MergePolicy,
+) -> Result<Merged, SuiteError> {
+    if policy == MergePolicy::Keep {
+        return Ok((edges, 0));
+    }
+    let key = |&(u, v, _): &(usize, usize, i64)| if symmetric && v < u { (v, u) } else { (u, v) };
+    let mut order: Vec<usize> = (0..edges.len()).collect();
+    order.sort_by_key(|&i| (key(&edges[i]), i));
+    let mut merged: Vec<(usize, (usize, usize, i64))> = Vec::new(); /* (first line, edge) */
+    for (j, &i) in order.iter().enumerate() {
+        let e = edges[i];
+        if j == 0 || key(&edges[order[j - 1]]) != key(&e) {
+            merged.push((i, e));
+            continue;
+        }
+        let last = &mut merged.last_mut().unwrap().1;
+        last.2 = match policy {
+            MergePolicy::Max => last.2.max(e.2),
+            MergePolicy::Min => last.2.min(e.2),
+            MergePolicy::Sum => last.2.checked_add(e.2).ok_or_else(|| {
+                SuiteError::Format(format!("parallel edges between {} and {}: weight sum overflows", e.0, e.1))
+            })?,
+            MergePolicy::Error => {
+                return Err(SuiteError::ParallelEdge { u: last.0, v: last.1 });
+            }
+            MergePolicy::Keep => unreachable!(),
+        };
+    }
+    let folded = edges.len() - merged.len();
+    merged.sort_unstable_by_key(|&(i, _)| i);
+    Ok((merged.into_iter().map(|(_, e)| e).collect(), folded))
+}
+
+fn report_merged(folded: usize, left: usize, policy: MergePolicy) {
+    if folded > 0 {
+        log::warn(format_args!("Merged {} parallel edge line(s) (--merge-parallel {}), {} edges left",
+                               folded, policy.name(), left));
+    }
+}
+
+/* merge_parallel for the unweighted formats: a no-op under keep. */
+fn dedup_unweighted(
+    edges: Vec<(usize, usize)>, symmetric: bool, policy: MergePolicy,
+) -> Result<Vec<(usize, usize)>, SuiteError> {
+    if policy == MergePolicy::Keep {
+        return Ok(edges);
+    }
+    let (merged, folded) = merge_parallel(edges.into_iter().map(|(u, v)| (u, v, 0)).collect(), symmetric, policy)?;
+    report_merged(folded, merged.len(), policy);
+    Ok(merged.into_iter().map(|(u, v, _)| (u, v)).collect())
+}
+
+fn merge_weighted(
+    edges: Vec<(usize, usize, i64)>, symmetric: bool, policy: MergePolicy,
+) -> Result<Vec<(usize, usize, i64)>, SuiteError> {
+    let (merged, folded) = merge_parallel(edges, symmetric, policy)?;
+    report_merged(folded, merged.len(), policy);
+    Ok(merged)
+}
+
+/* The "n m" header of a general graph file, without reading the edges: for
+ * checks that should come before a long load. */
+pub fn read_graph_header(filename: &str) -> Result<(usize, usize), SuiteError> {
+    if let Some(h) = binary_header(filename)? {
+        if h.bipartite {
+            return Err(SuiteError::Format("binary graph: the file holds a bipartite graph, a general one was expected".into()));
+        }
+        return Ok((h.left, h.m));
+    }
+    let mut blocks = open_blocks(filename, 1 << 16)?;
+    if let Some(kind) = blocks.structured()? {
+        /* no header to read ahead: the whole file it is */
+        let doc = structured_document(&mut blocks, kind, false, false)?;
+        return Ok((doc.left, doc.m.unwrap_or(doc.edges.len())));
+    }
+    let h = read_header(&mut blocks, 2, false, FormatPolicy::Tolerant)?;
+    Ok((h[0], h[1]))
+}
+
+/* What the input calls the loaded graph's vertices and edges, where that
+ * is not the graph's own ids: the original ids of a --format snap load,
+ * the labels of a --format labels one, the edge table of an --edge-ids
+ * one. The *_named loaders return it with the graph, for matching_io to
+ * write and read matching files in those names. */
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InputNames {
+    pub ids: Option<IdMap>,           /* the original ids of a --format snap load */
+    pub labels: Option<VertexLabels>, /* of a --format labels load */
+    pub edges: Option<EdgeIds>,       /* --edge-ids */
+}
+
+/* The edges of any input, after its header, with their InputNames; the
+ * --edge-ids ids move to those. */
+fn read_named<R: BufRead>(
+    mut blocks: Blocks<R>, bipartite: bool, weighted: bool, opts: &LoadOptions,
+) -> Result<(Loaded, InputNames), SuiteError> {
+    let mut names = InputNames::default();
+    let mut loaded = if opts.input == InputFormat::Snap {
+        let (loaded, map) = read_snap(blocks, bipartite, weighted, opts)?;
+        names.ids = Some(map);
+        loaded
+    } else if opts.input == InputFormat::Labels {
+        let (loaded, labels) = read_labels(blocks, bipartite, weighted, opts)?;
+        names.labels = Some(labels);
+        loaded
+    } else if blocks.is_binary()? {
+        read_binary(blocks, bipartite, weighted, opts)?
+    } else if let Some(kind) = blocks.structured()? {
+        read_structured(blocks, kind, bipartite, weighted, opts)?
+    } else if bipartite {
+        let h = read_header(&mut blocks, 3, true, opts.format)?;
+        read_edges(blocks, h[2], (h[0], h[1]), ("left", "right"), weighted, opts)?
+    } else {
+        let h = read_header(&mut blocks, 2, false, opts.format)?;
+        read_edges(blocks, h[1], (h[0], h[0]), ("n", "n"), weighted, opts)?
+    };
+    names.edges = edge_ids(opts, &loaded.0, &loaded.1, std::mem::take(&mut loaded.2), bipartite);
+    Ok((loaded, names))
+}
+
+/* The loaders' results, and the same with their InputNames. */
+pub type WeightedGraph = (usize, Vec<(usize, usize, i64)>);
+pub type BipartiteGraph = (usize, usize, Vec<(usize, usize)>);
+pub type WeightedBipartiteGraph = (usize, usize, Vec<(usize, usize, i64)>);
+
+pub type NamedGraph = (usize, Vec<(usize, usize)>, InputNames);
+pub type NamedWeightedGraph = (usize, Vec<(usize, usize, i64)>, InputNames);
+pub type NamedBipartiteGraph = (usize, usize, Vec<(usize, usize)>, InputNames);
+pub type NamedWeightedBipartiteGraph = (usize, usize, Vec<(usize, usize, i64)>, InputNames);
+
+fn graph_from<R: BufRead>(blocks: Blocks<R>, opts: &LoadOptions) -> Result<NamedGraph, SuiteError> {
+    let ((edges, _, _, (a, b)), names) = read_named(blocks, false, false, opts)?;
+    Ok((a.max(b), dedup_unweighted(edges, true, opts.on_parallel)?, names))
+}
+
+fn weighted_graph_from<R: BufRead>(blocks: Blocks<R>, opts: &LoadOptions) -> Result<NamedWeightedGraph, SuiteError> {
+    let ((edges, weights, _, (a, b)), names) = read_named(blocks, false, true, opts)?;
+    let edges = edges.iter().zip(&weights).map(|(&(u, v), &w)| (u, v, w)).collect();
+    Ok((a.max(b), merge_weighted(edges, true, opts.on_parallel)?, names))
+}
+
+fn bipartite_graph_from<R: BufRead>(blocks: Blocks<R>, opts: &LoadOptions) -> Result<NamedBipartiteGraph, SuiteError> {
+    let ((edges, _, _, (left, right)), names) = read_named(blocks, true, false, opts)?;
+    Ok((left, right, dedup_unweighted(edges, false, opts.on_parallel)?, names))
+}
+
+fn weighted_bipartite_graph_from<R: BufRead>(
+    blocks: Blocks<R>, opts: &LoadOptions,
+) -> Result<NamedWeightedBipartiteGraph, SuiteError> {
+    let ((edges, weights, _, (left, right)), names) = read_named(blocks, true, true, opts)?;
+    let edges = edges.iter().zip(&weights).map(|(&(u, v), &w)| (u, v, w)).collect();
+    Ok((left, right, merge_weighted(edges, false, opts.on_parallel)?, names))
+}
+
+/* General graph: "n m" header, or a binary general graph. The load_*
+ * functions map a plain file; the parse_* ones read any stream a block at
+ * a time. The *_named ones return the graph's InputNames with it. */
+pub fn load_graph(
+    filename: &str, opts: &LoadOptions,
+) -> Result<(usize, Vec<(usize, usize)>), SuiteError> {
+    load_graph_named(filename, opts).map(|(n, edges, _)| (n, edges))
+}
+
+pub fn load_graph_named(filename: &str, opts: &LoadOptions) -> Result<NamedGraph, SuiteError> {
+    graph_from(open_blocks(filename, opts.block_bytes)?, opts)
+}
+
+pub fn parse_graph<R: BufRead>(
+    reader: R, opts: &LoadOptions,
+) -> Result<(usize, Vec<(usize, usize)>), SuiteError> {
+    parse_graph_named(reader, opts).map(|(n, edges, _)| (n, edges))
+}
+
+pub fn parse_graph_named<R: BufRead>(reader: R, opts: &LoadOptions) -> Result<NamedGraph, SuiteError> {
+    graph_from(Blocks::stream(reader, opts.block_bytes), opts)
+}
+
+/* Weighted general graph: "n m" header, "u v w" edge lines. */
+pub fn load_weighted_graph(
+    filename: &str, opts: &LoadOptions,
+) -> Result<WeightedGraph, SuiteError> {
+    load_weighted_graph_named(filename, opts).map(|(n, edges, _)| (n, edges))
+}
+
+pub fn load_weighted_graph_named(filename: &str, opts: &LoadOptions) -> Result<NamedWeightedGraph, SuiteError> {
+    weighted_graph_from(open_blocks(filename, opts.block_bytes)?, opts)
+}
+
+pub fn parse_weighted_graph<R: BufRead>(
+    reader: R, opts: &LoadOptions,
+) -> Result<WeightedGraph, SuiteError> {
+    parse_weighted_graph_named(reader, opts).map(|(n, edges, _)| (n, edges))
+}
+
+pub fn parse_weighted_graph_named<R: BufRead>(reader: R, opts: &LoadOptions) -> Result<NamedWeightedGraph, SuiteError> {
+    weighted_graph_from(Blocks::stream(reader, opts.block_bytes), opts)
+}
+
+/* Bipartite graph: "left right m" header. */
+pub fn load_bipartite_graph(
+    filename: &str, opts: &LoadOptions,
+) -> Result<BipartiteGraph, SuiteError> {
+    load_bipartite_graph_named(filename, opts).map(|(left, right, edges, _)| (left, right, edges))
+}
+
+pub fn load_bipartite_graph_named(filename: &str, opts: &LoadOptions) -> Result<NamedBipartiteGraph, SuiteError> {
+    bipartite_graph_from(open_blocks(filename, opts.block_bytes)?, opts)
+}
+
+pub fn parse_bipartite_graph<R: BufRead>(
+    reader: R, opts: &LoadOptions,
+) -> Result<BipartiteGraph, SuiteError> {
+    parse_bipartite_graph_named(reader, opts).map(|(left, right, edges, _)| (left, right, edges))
+}
+
+pub fn parse_bipartite_graph_named<R: BufRead>(reader: R, opts: &LoadOptions) -> Result<NamedBipartiteGraph, SuiteError> {
+    bipartite_graph_from(Blocks::stream(reader, opts.block_bytes), opts)
+}
+
+/* Weighted bipartite graph: "left right m" header, "u v w" edge lines. */
+pub fn load_weighted_bipartite_graph(
+    filename: &str, opts: &LoadOptions,
+) -> Result<WeightedBipartiteGraph, SuiteError> {
+    load_weighted_bipartite_graph_named(filename, opts).map(|(left, right, edges, _)| (left, right, edges))
+}
+
+pub fn load_weighted_bipartite_graph_named(filename: &str, opts: &LoadOptions) -> Result<NamedWeightedBipartiteGraph, SuiteError> {
+    weighted_bipartite_graph_from(open_blocks(filename, opts.block_bytes)?, opts)
+}
+
+pub fn parse_weighted_bipartite_graph<R: BufRead>(
+    reader: R, opts: &LoadOptions,
+) -> Result<WeightedBipartiteGraph, SuiteError> {
+    parse_weighted_bipartite_graph_named(reader, opts).map(|(left, right, edges, _)| (left, right, edges))
+}
+
+pub fn parse_weighted_bipartite_graph_named<R: BufRead>(reader: R, opts: &LoadOptions) -> Result<NamedWeightedBipartiteGraph, SuiteError> {
+    weighted_bipartite_graph_from(Blocks::stream(reader, opts.block_bytes), opts)
+}
+
+/* The classes of a 2-coloring of (n, edges), true for the second, for
+ * reading a general graph as a bipartite one (hopcroft_karp --general).
+ * Self-loops and out-of-range endpoints are ignored, as by the solvers;
+ * isolated vertices go to the first class. A graph with an odd cycle is
+ * refused with SuiteError::NotBipartite and one such cycle: the BFS that
+ * colors a component meets an edge inside one class between two vertices
+ * of one depth, and their tree paths up to the nearest common ancestor
+ * close an odd cycle with it. The cycle is in the original ids of a
+ * --format snap load (see IdMap). */
+pub fn bipartition(n: usize, edges: &[(usize, usize)], names: &InputNames) -> Result<Vec<bool>, SuiteError> {
+    let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    const NIL: usize = usize::MAX;
+    let mut parent = vec![NIL; n];
+    let mut depth = vec![NIL; n];
+    let mut queue = Vec::new();
+    for s in 0..n {
+        if depth[s] != NIL { continue; }
+        depth[s] = 0;
+        queue.clear();
+        queue.push(s);
+        let mut head = 0;
+        while head < queue.len() {
+            let u = queue[head];
+            head += 1;
+            for &w in &adj[u] {
+                if depth[w] == NIL {
+                    depth[w] = depth[u] + 1;
+                    parent[w] = u;
+                    queue.push(w);
+                } else if depth[w] % 2 == depth[u] % 2 {
+                    return Err(SuiteError::NotBipartite { cycle: odd_cycle(u, w, &parent, &depth, names) });
+                }
+            }
+        }
+    }
+    Ok(depth.into_iter().map(|d| d % 2 == 1).collect())
+}
+
+/* The cycle closed by the edge u-w, u and w at one BFS depth: from their
+ * nearest common ancestor down to u, across to w and back up. */
+fn odd_cycle(u: usize, w: usize, parent: &[usize], depth: &[usize], names: &InputNames) -> Vec<usize> {
+    let (mut a, mut b) = (u, w);
+    let (mut up_a, mut up_b) = (vec![a], vec![b]);
+    while a != b {
+        if depth[a] >= depth[b] {
+            a = parent[a];
+            up_a.push(a);
+        } else {
+            b = parent[b];
+            up_b.push(b);
+        }
+    }
+    up_a.reverse();
+    up_a.extend(up_b);
+    match names.ids {
+        None => up_a,
+        Some(ref map) => up_a.into_iter().map(|v| map.left[v]).collect(),
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * Matching in 3-uniform hypergraphs (3-set packing): a largest set of
+ * pairwise disjoint triples. NP-hard already for 3-dimensional matching,
+ * so this is the local search of k-set packing, with a bound on the gap.
+ *
+ * Start from the first-fit maximal packing in input order. A
+ * 2-improvement removes one chosen triple s and adds two disjoint triples
+ * that meet no chosen triple but s; triples that then meet nothing are
+ * added as well, so the packing stays maximal. Every improvement grows it,
+ * and the search stops when none is left.
+ *
+ * A packing A with no 2-improvement is within a factor 2 of an optimal B:
+ * each triple of B meets some triple of A (A is maximal). At most one
+ * meets only a given a in A, or two disjoint ones would improve a, so at
+ * most |A| meet one triple of A. The others meet at least two, and B uses
+ * each of the 3|A| covered vertices once, so |B| <= |A| + (3|A| - |A|) / 2.
+ * A maximal packing alone is within a factor 3: B uses each covered vertex
+ * once and every triple of B has one. The upper bound reported is that
+ * factor times |A|, or floor(non-isolated vertices / 3) if smaller.
+ *
+ * Included via #[path = "../../common/rust/hypergraph_matching.rs"] mod hypergraph_matching;
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+const NIL: usize = usize::MAX;
+
+pub struct Packing {
+    pub triples: Vec<[usize; 3]>, /* sorted, each sorted */
+    pub greedy_size: usize,       /* the first-fit start */
+    pub improvements: usize,      /* 2-improvements applied */
+}
+
+/* The vertex count and the triples. */
+pub type Hypergraph = (usize, Vec<[usize; 3]>);
+
+/* Format: a line "n m", then m lines "a b c" of distinct vertex ids below
+ * n. Blank lines and '#' comments are skipped. Triples come back sorted. */
+pub fn load_hypergraph(filename: &str) -> Result<Hypergraph, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut lines = text.lines().enumerate()
+        .map(|(i, raw)| (i + 1, raw.split('#').next().unwrap_or("").trim()))
+        .filter(|&(_, l)| !l.is_empty());
+    let (line, first) = lines.next().ok_or("Empty file")?;
+    let header: Vec<usize> = first.split_whitespace().map(|t| t.parse()).collect::<Result<_, _>>()
+        .map_err(|_| format!("{}:{}: expected 'n m', got '{}'", filename, line, first))?;
+    if header.len() != 2 {
+        return Err(format!("{}:{}: expected 'n m', got '{}'", filename, line, first).into());
+    }
+    let (n, m) = (header[0], header[1]);
+    let mut triples = Vec::with_capacity(m.min(1 << 20));
+    for (line, l) in lines {
+        let ids: Vec<usize> = l.split_whitespace().map(|t| t.parse()).collect::<Result<_, _>>()
+            .map_err(|_| format!("{}:{}: expected 'a b c', got '{}'", filename, line, l))?;
+        if ids.len() != 3 {
+            return Err(format!("{}:{}: expected 'a b c', got '{}'", filename, line, l).into());
+        }
+        let mut t = [ids[0], ids[1], ids[2]];
+        t.sort_unstable();
+        if t[2] >= n {
+            return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, line, t[2], n).into());
+        }
+        if t[0] == t[1] || t[1] == t[2] {
+            return Err(format!("{}:{}: repeated vertex in '{}'", filename, line, l).into());
+        }
+        triples.push(t);
+    }
+    if triples.len() != m {
+        return Err(format!("Header declares {} triples but the file lists {}", m, triples.len()).into());
+    }
+    Ok((n, triples))
+}
+
+/* Triples through each vertex, CSR-style. */
+fn incidence(n: usize, triples: &[[usize; 3]]) -> (Vec<usize>, Vec<usize>) {
+    let mut start = vec![0usize; n + 1];
+    for t in triples {
+        for &v in t { start[v + 1] += 1; }
+    }
+    for v in 0..n { start[v + 1] += start[v]; }
+    let mut fill = start.clone();
+    let mut inc = vec![0usize; start[n]];
+    for (i, t) in triples.iter().enumerate() {
+        for &v in t {
+            inc[fill[v]] = i;
+            fill[v] += 1;
+        }
+    }
+    (start, inc)
+}
+
+/* Local search from first fit; `improve` false stops at first fit.
+ * Triples must be sorted with distinct vertices below n, as loaded. */
+pub fn pack(n: usize, triples: &[[usize; 3]], improve: bool) -> Packing {
+    /* owner[v]: the chosen triple covering v */
+    let mut owner = vec![NIL; n];
+    let free = |owner: &[usize], t: &[usize; 3]| t.iter().all(|&v| owner[v] == NIL);
+    let mut chosen = Vec::new();
+    for (i, t) in triples.iter().enumerate() {
+        if free(&owner, t) {
+            for &v in t { owner[v] = i; }
+            chosen.push(i);
+        }
+    }
+    let greedy_size = chosen.len();
+
+    let mut improvements = 0;
+    if improve {
+        let (start, inc) = incidence(n, triples);
+        /* seen[e] == stamp: e already looked at for the current s */
+        let mut seen = vec![0usize; triples.len()];
+        let mut stamp = 0;
+        let mut candidates = Vec::new();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            let mut k = 0;
+            while k < chosen.len() {
+                let s = chosen[k];
+                /* unchosen triples that meet s and no other chosen triple */
+                candidates.clear();
+                stamp += 1;
+                for &v in &triples[s] {
+                    for &e in &inc[start[v]..start[v + 1]] {
+                        if seen[e] != stamp && e != s && owner[triples[e][0]] != e {
+                            seen[e] = stamp;
+                            if triples[e].iter().all(|&w| owner[w] == NIL || owner[w] == s) {
+                                candidates.push(e);
+                            }
+                        }
+                    }
+                }
+                let disjoint = |a: usize, b: usize| triples[a].iter().all(|v| !triples[b].contains(v));
+                let pair = (0..candidates.len())
+                    .flat_map(|i| (i + 1..candidates.len()).map(move |j| (i, j)))
+                    .find(|&(i, j)| disjoint(candidates[i], candidates[j]));
+                let (i, j) = match pair {
+                    Some(p) => p,
+                    None => {
+                        k += 1;
+                        continue;
+                    }
+                };
+                for &v in &triples[s] { owner[v] = NIL; }
+                chosen.swap_remove(k);
+                let (a, b) = (candidates[i], candidates[j]);
+                for &e in [a, b].iter().chain(candidates.iter()) {
+                    if free(&owner, &triples[e]) {
+                        for &v in &triples[e] { owner[v] = e; }
+                        chosen.push(e);
+                    }
+                }
+                improvements += 1;
+                changed = true;
+            }
+        }
+    }
+
+    let mut packed: Vec<[usize; 3]> = chosen.iter().map(|&i| triples[i]).collect();
+    packed.sort_unstable();
+    Packing { triples: packed, greedy_size, improvements }
+}
+
+/* min(f |packing|, floor(non-isolated vertices / 3)), f = 2 for a packing
+ * with no 2-improvement (`improved`), else 3 for a maximal one. */
+pub fn upper_bound(n: usize, triples: &[[usize; 3]], packing_size: usize, improved: bool) -> usize {
+    let mut touched = vec![false; n];
+    for t in triples {
+        for &v in t { touched[v] = true; }
+    }
+    (if improved { 2 } else { 3 } * packing_size).min(touched.iter().filter(|&&x| x).count() / 3)
+}
+
+/* Triples of `packing` that are not in `triples`, or reuse a vertex. */
+pub fn check_packing(n: usize, triples: &[[usize; 3]], packing: &[[usize; 3]]) -> Vec<String> {
+    let mut sorted = triples.to_vec();
+    sorted.sort_unstable();
+    let mut errors = Vec::new();
+    let mut used = vec![false; n];
+    for t in packing {
+        if t.iter().any(|&v| v >= n) || sorted.binary_search(t).is_err() {
+            errors.push(format!("Triple {:?} not in hypergraph!", t));
+            continue;
+        }
+        for &v in t {
+            if used[v] { errors.push(format!("Vertex {} in two triples!", v)); }
+            used[v] = true;
+        }
+    }
+    errors
+}
@@ -0,0 +1,65 @@
+/*
+ * SIGINT / SIGTERM as a stop request instead of an instant kill, so a long
+ * solve can still report the matching it has.
+ *
+ * install() replaces the default handlers; the handler only raises
+ * STOP, which the solvers reached through matchers.rs poll between phases
+ * (matchers::stop_on). A second signal ends the process at once, as the
+ * default would have. Unix only: elsewhere install() does nothing.
+ *
+ * Included via #[path = "../../common/rust/interrupt.rs"] mod interrupt;
+ */
+
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+pub static STOP: AtomicBool = AtomicBool::new(false);
+static SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+const SIGINT: i32 = 2;
+const SIGTERM: i32 = 15;
+
+#[cfg(unix)]
+extern "C" {
+    fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    fn _exit(status: i32) -> !;
+}
+
+/* Async-signal-safe: atomics and _exit only. */
+#[cfg(unix)]
+extern "C" fn on_signal(signum: i32) {
+    if SIGNAL.swap(signum, Ordering::SeqCst) != 0 {
+        unsafe { _exit(exit_code(signum)) }
+    }
+    STOP.store(true, Ordering::SeqCst);
+}
+
+pub fn install() {
+    #[cfg(unix)]
+    unsafe {
+        signal(SIGINT, on_signal);
+        signal(SIGTERM, on_signal);
+    }
+}
+
+/* The signal that raised STOP, if any. */
+pub fn caught() -> Option<i32> {
+    match SIGNAL.load(Ordering::SeqCst) {
+        0 => None,
+        s => Some(s),
+    }
+}
+
+pub fn name(signum: i32) -> String {
+    match signum {
+        SIGINT => "SIGINT".to_string(),
+        SIGTERM => "SIGTERM".to_string(),
+        s => format!("signal {}", s),
+    }
+}
+
+/* The shell's status for a process killed by `signum`. */
+pub fn exit_code(signum: i32) -> i32 {
+    128 + signum
+}
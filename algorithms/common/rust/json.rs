@@ -0,0 +1,297 @@
+/*
+ * Minimal JSON value: parser and serializer, no dependencies.
+ *
+ * Objects keep their keys in insertion order (a Vec of pairs), so output is
+ * deterministic. Numbers are f64; vertex ids and counts are exact below 2^53.
+ */
+
+#![allow(dead_code)]
+
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match *self {
+            Json::Obj(ref fields) => fields.iter().find(|f| f.0 == key).map(|f| &f.1),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            Json::Str(ref s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            Json::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /* Non-negative integers only. */
+    pub fn as_usize(&self) -> Option<usize> {
+        match *self {
+            Json::Num(x) if x >= 0.0 && x.fract() == 0.0 && x < 9007199254740992.0 => Some(x as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match *self {
+            Json::Arr(ref items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /* Builder for object literals: Json::obj().with("k", v).with(...) */
+    pub fn obj() -> Json {
+        Json::Obj(Vec::new())
+    }
+
+    pub fn with<V: Into<Json>>(mut self, key: &str, value: V) -> Json {
+        if let Json::Obj(ref mut fields) = self {
+            fields.push((key.to_string(), value.into()));
+        }
+        self
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Json { Json::Bool(b) }
+}
+impl From<usize> for Json {
+    fn from(x: usize) -> Json { Json::Num(x as f64) }
+}
+impl From<u64> for Json {
+    fn from(x: u64) -> Json { Json::Num(x as f64) }
+}
+impl From<i64> for Json {
+    fn from(x: i64) -> Json { Json::Num(x as f64) }
+}
+impl From<f64> for Json {
+    fn from(x: f64) -> Json { Json::Num(x) }
+}
+impl<'a> From<&'a str> for Json {
+    fn from(s: &'a str) -> Json { Json::Str(s.to_string()) }
+}
+impl From<String> for Json {
+    fn from(s: String) -> Json { Json::Str(s) }
+}
+impl From<Vec<Json>> for Json {
+    fn from(items: Vec<Json>) -> Json { Json::Arr(items) }
+}
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(x: Option<T>) -> Json { x.map_or(Json::Null, |v| v.into()) }
+}
+
+fn write_str(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+/* Compact, single-line output (safe for line-delimited protocols). */
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Num(x) if !x.is_finite() => f.write_str("null"),
+            Json::Num(x) if x.fract() == 0.0 && x.abs() < 1e15 => write!(f, "{}", x as i64),
+            Json::Num(x) => write!(f, "{}", x),
+            Json::Str(ref s) => write_str(f, s),
+            Json::Arr(ref items) => {
+                f.write_str("[")?;
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 { f.write_str(",")?; }
+                    write!(f, "{}", v)?;
+                }
+                f.write_str("]")
+            }
+            Json::Obj(ref fields) => {
+                f.write_str("{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 { f.write_str(",")?; }
+                    write_str(f, k)?;
+                    write!(f, ":{}", v)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn err<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("{} at byte {}", what, self.pos))
+    }
+
+    fn skip_ws(&mut self) {
+        while self.pos < self.s.len() && (self.s[self.pos] as char).is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, lit: &str) -> bool {
+        if self.s[self.pos..].starts_with(lit.as_bytes()) {
+            self.pos += lit.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, String> {
+        if depth > 128 { return self.err("nesting too deep"); }
+        self.skip_ws();
+        if self.pos >= self.s.len() { return self.err("unexpected end of input"); }
+        if self.eat("null") { return Ok(Json::Null); }
+        if self.eat("true") { return Ok(Json::Bool(true)); }
+        if self.eat("false") { return Ok(Json::Bool(false)); }
+        match self.s[self.pos] {
+            b'"' => self.string().map(Json::Str),
+            b'[' => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_ws();
+                if self.eat("]") { return Ok(Json::Arr(items)); }
+                loop {
+                    items.push(self.value(depth + 1)?);
+                    self.skip_ws();
+                    if self.eat(",") { continue; }
+                    if self.eat("]") { return Ok(Json::Arr(items)); }
+                    return self.err("expected ',' or ']'");
+                }
+            }
+            b'{' => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_ws();
+                if self.eat("}") { return Ok(Json::Obj(fields)); }
+                loop {
+                    self.skip_ws();
+                    if self.pos >= self.s.len() || self.s[self.pos] != b'"' {
+                        return self.err("expected object key");
+                    }
+                    let key = self.string()?;
+                    self.skip_ws();
+                    if !self.eat(":") { return self.err("expected ':'"); }
+                    let v = self.value(depth + 1)?;
+                    fields.push((key, v));
+                    self.skip_ws();
+                    if self.eat(",") { continue; }
+                    if self.eat("}") { return Ok(Json::Obj(fields)); }
+                    return self.err("expected ',' or '}'");
+                }
+            }
+            b'-' | b'0'..=b'9' => self.number(),
+            _ => self.err("unexpected character"),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while self.pos < self.s.len() && b"+-0123456789.eE".contains(&self.s[self.pos]) {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.s[start..self.pos]).unwrap_or("");
+        match text.parse::<f64>() {
+            Ok(x) => Ok(Json::Num(x)),
+            Err(_) => {
+                self.pos = start;
+                self.err("invalid number")
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        if self.pos + 4 > self.s.len() { return self.err("truncated \\u escape"); }
+        let text = std::str::from_utf8(&self.s[self.pos..self.pos + 4]).unwrap_or("");
+        match u32::from_str_radix(text, 16) {
+            Ok(x) => {
+                self.pos += 4;
+                Ok(x)
+            }
+            Err(_) => self.err("invalid \\u escape"),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1; /* opening quote */
+        let mut out: Vec<u8> = Vec::new();
+        loop {
+            if self.pos >= self.s.len() { return self.err("unterminated string"); }
+            let b = self.s[self.pos];
+            self.pos += 1;
+            match b {
+                b'"' => break,
+                b'\\' => {
+                    if self.pos >= self.s.len() { return self.err("unterminated string"); }
+                    let e = self.s[self.pos];
+                    self.pos += 1;
+                    let c = match e {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let mut cp = self.hex4()?;
+                            if (0xd800..0xdc00).contains(&cp) && self.eat("\\u") {
+                                let lo = self.hex4()?;
+                                cp = 0x10000 + ((cp - 0xd800) << 10) + (lo.wrapping_sub(0xdc00) & 0x3ff);
+                            }
+                            std::char::from_u32(cp).unwrap_or('\u{fffd}')
+                        }
+                        _ => return self.err("invalid escape"),
+                    };
+                    let mut buf = [0u8; 4];
+                    out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+                }
+                _ => out.push(b),
+            }
+        }
+        String::from_utf8(out).or_else(|_| self.err("invalid UTF-8 in string"))
+    }
+}
+
+pub fn parse(text: &str) -> Result<Json, String> {
+    let mut p = Parser { s: text.as_bytes(), pos: 0 };
+    let v = p.value(0)?;
+    p.skip_ws();
+    if p.pos != p.s.len() {
+        return p.err("trailing characters");
+    }
+    Ok(v)
+}
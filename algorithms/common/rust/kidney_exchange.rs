@@ -0,0 +1,340 @@
+/*
+ * Kidney exchange: cycles and chains in a directed compatibility graph.
+ *
+ * Vertex v is an incompatible patient-donor pair, or an altruistic donor
+ * with no patient. Arc u -> v means u's donor can give to v's patient.
+ * An exchange is a set of vertex-disjoint
+ *   - cycles of pairs, each donor giving to the next patient, and
+ *   - chains started by an altruist, a -> p1 -> p2 ..., whose last donor
+ *     gives to the waiting list,
+ * and its value is the number of transplants to pair patients (k for a
+ * k-cycle, one per pair on a chain). Cycles and chains have at most
+ * max_len vertices, since all operations of a cycle run at once.
+ *
+ * max_len = 2 is a matching problem. Mutual arcs u <-> v become an
+ * undirected edge, and so does every arc out of an altruist. Without
+ * altruists a maximum matching of that graph is optimal; with them, chains
+ * give one transplant rather than two, so pairs weigh 1 and altruists 0 in
+ * a maximum vertex-weight matching (vertex_weighted.rs).
+ *
+ * max_len = 3 is the cycle formulation solved by branch-and-bound, seeded
+ * with the matching solution. It is a stub for branch-and-price: columns
+ * (cycles and chains) are enumerated up front instead of priced, and the
+ * bound is the count of pairs some remaining column can still cover
+ * instead of an LP value. That proves optimality on sparse pools of about
+ * a hundred pairs; past node_limit the best exchange found is returned,
+ * marked not optimal.
+ *
+ * Included via #[path = "../../common/rust/kidney_exchange.rs"] mod kidney_exchange;
+ * together with matchers.rs and vertex_weighted.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+use super::matchers;
+use super::vertex_weighted;
+
+pub const MAX_CYCLE_LENGTHS: [usize; 2] = [2, 3];
+
+pub struct Pool {
+    pub n: usize,
+    pub altruist: Vec<bool>,
+    /* sorted, no loops, none into an altruist */
+    out: Vec<Vec<usize>>,
+}
+
+impl Pool {
+    /* Arcs with an endpoint >= n are dropped. */
+    pub fn new(n: usize, arcs: &[(usize, usize)], altruists: &[usize]) -> Self {
+        let mut altruist = vec![false; n];
+        for &a in altruists {
+            if a < n { altruist[a] = true; }
+        }
+        let mut out = vec![Vec::new(); n];
+        for &(u, v) in arcs {
+            if u < n && v < n && u != v && !altruist[v] {
+                out[u].push(v);
+            }
+        }
+        for o in &mut out { o.sort_unstable(); o.dedup(); }
+        Pool { n, altruist, out }
+    }
+
+    pub fn has_arc(&self, u: usize, v: usize) -> bool {
+        u < self.n && self.out[u].binary_search(&v).is_ok()
+    }
+
+    pub fn arcs(&self) -> usize {
+        self.out.iter().map(|o| o.len()).sum()
+    }
+
+    pub fn pairs(&self) -> usize {
+        self.altruist.iter().filter(|&&a| !a).count()
+    }
+}
+
+pub struct Exchange {
+    pub cycles: Vec<Vec<usize>>, /* in donation order */
+    pub chains: Vec<Vec<usize>>, /* altruist first */
+    pub transplants: usize,
+    pub nodes: usize,            /* branch-and-bound nodes, 0 for matching */
+    pub optimal: bool,
+}
+
+impl Exchange {
+    fn from_columns(pool: &Pool, columns: Vec<Vec<usize>>) -> Self {
+        let mut ex = Exchange { cycles: Vec::new(), chains: Vec::new(), transplants: 0, nodes: 0, optimal: true };
+        for c in columns {
+            if pool.altruist[c[0]] {
+                ex.transplants += c.len() - 1;
+                ex.chains.push(c);
+            } else {
+                ex.transplants += c.len();
+                ex.cycles.push(c);
+            }
+        }
+        ex.cycles.sort();
+        ex.chains.sort();
+        ex
+    }
+}
+
+/* Undirected graph of the max_len = 2 exchanges: mutual pairs and
+ * altruist -> pair arcs, as (u, v) with u < v. */
+pub fn two_cycle_graph(pool: &Pool) -> Vec<(usize, usize)> {
+    let mut edges = Vec::new();
+    for u in 0..pool.n {
+        for &v in &pool.out[u] {
+            if pool.altruist[u] || (u < v && pool.has_arc(v, u)) {
+                edges.push(if u < v { (u, v) } else { (v, u) });
+            }
+        }
+    }
+    edges.sort_unstable();
+    edges
+}
+
+/* Every cycle and chain of at most max_len vertices, one per vertex set,
+ * cycles starting at their smallest pair. */
+pub fn columns(pool: &Pool, max_len: usize) -> Vec<Vec<usize>> {
+    let mut cols = Vec::new();
+    for u in 0..pool.n {
+        if pool.altruist[u] {
+            for &v in &pool.out[u] {
+                cols.push(vec![u, v]);
+                if max_len >= 3 {
+                    for &w in &pool.out[v] {
+                        cols.push(vec![u, v, w]);
+                    }
+                }
+            }
+            continue;
+        }
+        for &v in &pool.out[u] {
+            if v < u { continue; }
+            if pool.has_arc(v, u) { cols.push(vec![u, v]); }
+            if max_len >= 3 {
+                for &w in &pool.out[v] {
+                    if w > u && pool.has_arc(w, u) { cols.push(vec![u, v, w]); }
+                }
+            }
+        }
+    }
+    /* u -> v -> w -> u and u -> w -> v -> u use the same pairs */
+    let mut keyed: Vec<(Vec<usize>, Vec<usize>)> = cols.into_iter()
+        .map(|c| { let mut k = c.clone(); k[1..].sort_unstable(); (k, c) })
+        .collect();
+    keyed.sort();
+    keyed.dedup_by(|a, b| a.0 == b.0);
+    keyed.into_iter().map(|(_, c)| c).collect()
+}
+
+/* max_len = 2: maximum matching, or vertex-weighted with altruists. */
+pub fn solve_two(algo: &str, pool: &Pool) -> Result<Exchange, String> {
+    let edges = two_cycle_graph(pool);
+    let matching = if pool.altruist.iter().any(|&a| a) {
+        let weights: Vec<u64> = pool.altruist.iter().map(|&a| if a { 0 } else { 1 }).collect();
+        vertex_weighted::max_vertex_weight_matching(pool.n, &edges, &weights).matching
+    } else {
+        matchers::maximum_matching(algo, pool.n, &edges)?
+    };
+    let columns = matching.into_iter()
+        .map(|(u, v)| if pool.altruist[v] { vec![v, u] } else { vec![u, v] })
+        .collect();
+    Ok(Exchange::from_columns(pool, columns))
+}
+
+struct Search<'a> {
+    pool: &'a Pool,
+    cols: Vec<Vec<usize>>,
+    by_vertex: Vec<Vec<usize>>,
+    used: Vec<bool>,
+    taken: Vec<usize>,
+    covered: usize,
+    best: usize,
+    best_taken: Vec<usize>,
+    nodes: usize,
+    node_limit: usize,
+}
+
+impl<'a> Search<'a> {
+    fn live(&self, c: usize) -> bool {
+        self.cols[c].iter().all(|&x| !self.used[x])
+    }
+
+    fn set(&mut self, c: usize, used: bool) {
+        for i in 0..self.cols[c].len() {
+            let x = self.cols[c][i];
+            self.used[x] = used;
+        }
+    }
+
+    fn value(&self, c: usize) -> usize {
+        let len = self.cols[c].len();
+        if self.pool.altruist[self.cols[c][0]] { len - 1 } else { len }
+    }
+
+    fn go(&mut self) {
+        if self.nodes >= self.node_limit { return; }
+        self.nodes += 1;
+        /* bound: free pairs that some live column still covers; branch on
+         * the one with the fewest live columns */
+        let mut bound = self.covered;
+        let mut pick = None;
+        let mut fewest = usize::MAX;
+        for v in 0..self.pool.n {
+            if self.used[v] || self.pool.altruist[v] { continue; }
+            let k = self.by_vertex[v].iter().filter(|&&c| self.live(c)).count();
+            if k == 0 { continue; }
+            bound += 1;
+            if k < fewest {
+                fewest = k;
+                pick = Some(v);
+            }
+        }
+        if self.covered > self.best {
+            self.best = self.covered;
+            self.best_taken = self.taken.clone();
+        }
+        let v = match pick {
+            Some(v) if bound > self.best => v,
+            _ => return,
+        };
+        let mut options: Vec<usize> = self.by_vertex[v].iter().cloned().filter(|&c| self.live(c)).collect();
+        options.sort_by(|&a, &b| self.value(b).cmp(&self.value(a)).then(a.cmp(&b)));
+        for c in options {
+            self.set(c, true);
+            self.taken.push(c);
+            self.covered += self.value(c);
+            self.go();
+            self.covered -= self.value(c);
+            self.taken.pop();
+            self.set(c, false);
+        }
+        /* v stays out of every exchange */
+        self.used[v] = true;
+        self.go();
+        self.used[v] = false;
+    }
+}
+
+/* max_len = 3: branch-and-bound over the columns, starting from `seed`. */
+pub fn cycle_packing(pool: &Pool, max_len: usize, seed: Exchange, node_limit: usize) -> Exchange {
+    let cols = columns(pool, max_len);
+    let mut by_vertex = vec![Vec::new(); pool.n];
+    for (i, c) in cols.iter().enumerate() {
+        for &x in c { by_vertex[x].push(i); }
+    }
+    let mut s = Search {
+        pool, cols, by_vertex,
+        used: vec![false; pool.n],
+        taken: Vec::new(),
+        covered: 0,
+        best: seed.transplants,
+        best_taken: Vec::new(),
+        nodes: 0,
+        node_limit,
+    };
+    s.go();
+    let optimal = s.nodes < node_limit;
+    let mut ex = if s.best_taken.is_empty() {
+        seed
+    } else {
+        let chosen = s.best_taken.iter().map(|&c| s.cols[c].clone()).collect();
+        Exchange::from_columns(pool, chosen)
+    };
+    ex.nodes = s.nodes;
+    ex.optimal = optimal;
+    ex
+}
+
+pub fn solve(algo: &str, pool: &Pool, max_len: usize, node_limit: usize) -> Result<Exchange, String> {
+    if !MAX_CYCLE_LENGTHS.contains(&max_len) {
+        return Err(format!("unsupported --max-cycle {} (expected 2 or 3)", max_len));
+    }
+    let two = solve_two(algo, pool)?;
+    if max_len == 2 {
+        return Ok(two);
+    }
+    Ok(cycle_packing(pool, max_len, two, node_limit))
+}
+
+/* Every cycle and chain is within max_len and uses existing arcs, no
+ * vertex is used twice, and the transplant count adds up. */
+pub fn check_exchange(pool: &Pool, ex: &Exchange, max_len: usize) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut used = vec![false; pool.n];
+    let mut transplants = 0;
+    let all = ex.cycles.iter().map(|c| (c, false)).chain(ex.chains.iter().map(|c| (c, true)));
+    for (c, chain) in all {
+        let kind = if chain { "Chain" } else { "Cycle" };
+        if c.len() < 2 || c.len() > max_len || c.iter().any(|&x| x >= pool.n) {
+            errors.push(format!("{} {:?} has a bad length or vertex!", kind, c));
+            continue;
+        }
+        for (i, &x) in c.iter().enumerate() {
+            if used[x] {
+                errors.push(format!("Vertex {} used twice!", x));
+            }
+            used[x] = true;
+            if pool.altruist[x] != (chain && i == 0) {
+                errors.push(format!("{} {:?}: vertex {} is in the wrong place!", kind, c, x));
+            }
+        }
+        let arcs = if chain { c.len() - 1 } else { c.len() };
+        for i in 0..arcs {
+            let (u, v) = (c[i], c[(i + 1) % c.len()]);
+            if !pool.has_arc(u, v) {
+                errors.push(format!("{} {:?}: arc {} -> {} not in graph!", kind, c, u, v));
+            }
+        }
+        transplants += arcs;
+    }
+    if transplants != ex.transplants {
+        errors.push(format!("Reported {} transplants, the exchange has {}!", ex.transplants, transplants));
+    }
+    errors
+}
+
+/* Altruist file: one vertex id per line, '#' starts a comment. */
+pub fn load_altruists(filename: &str, n: usize) -> Result<Vec<usize>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut ids = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        for tok in line.split_whitespace() {
+            let v: usize = tok.parse()
+                .map_err(|_| format!("{}:{}: expected a vertex id, got '{}'", filename, i + 1, tok))?;
+            if v >= n {
+                return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, i + 1, v, n).into());
+            }
+            ids.push(v);
+        }
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    Ok(ids)
+}
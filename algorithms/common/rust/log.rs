@@ -0,0 +1,129 @@
+/*
+ * Levelled diagnostics for the shared modules, in place of println! and
+ * eprintln!, so a program that includes them can quiet their output or
+ * capture it.
+ *
+ * Levels, most severe first:
+ *   error   a result that failed validation (verify.rs's ERROR lines)
+ *   warn    what a load dropped, merged or changed in the input (graph_io)
+ *   info    summaries: the validation report, the files written
+ *   debug   phase events of a solve (event(), below)
+ *   trace   its starting pairs, blossoms and augmenting paths
+ * Messages up to the level set (set_level, or --log-level in the
+ * binaries; info by default) reach the sink. Without set_sink, that is
+ * the console: info on stdout, the rest on stderr, each message as is,
+ * which is the output the binaries always had.
+ *
+ * event() is a tracer (the solvers' `trace` field, matchers::trace_on)
+ * that logs a solve's steps in trace.rs's event format, its phases at
+ * debug and the rest at trace. The binaries install it under --log-level
+ * debug or trace when no --trace file is given; it costs what --trace
+ * costs.
+ *
+ * Included via #[path = "../../common/rust/log.rs"] mod log;
+ * together with args.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use super::args;
+
+pub const LOG_USAGE: &str = "[--log-level error|warn|info|debug|trace]";
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [Level::Error, Level::Warn, Level::Info, Level::Debug, Level::Trace];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    pub fn from_name(s: &str) -> Result<Level, String> {
+        Level::ALL.iter().copied().find(|l| l.name() == s)
+            .ok_or_else(|| format!("--log-level: unknown level '{}' (error, warn, info, debug or trace)", s))
+    }
+}
+
+static MAX: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+/* Where set_sink sends the messages instead of the console. */
+pub type Sink = &'static (dyn Fn(Level, &str) + Sync);
+
+static SINK: OnceLock<Sink> = OnceLock::new();
+
+/* Messages above `level` are dropped from now on. */
+pub fn set_level(level: Level) {
+    MAX.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= MAX.load(Ordering::Relaxed)
+}
+
+/* From now on every message that passes the level goes to `sink` instead
+ * of the console. The first call wins, as with matchers::progress_on. */
+pub fn set_sink(sink: Sink) {
+    let _ = SINK.set(sink);
+}
+
+/* --log-level LEVEL, if given. */
+pub fn from_args(args: &[String]) -> Result<(), String> {
+    if let Some(name) = args::value_of(args, "--log-level") {
+        set_level(Level::from_name(name)?);
+    }
+    Ok(())
+}
+
+pub fn log(level: Level, message: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+    match SINK.get() {
+        Some(sink) => sink(level, &message.to_string()),
+        None if level == Level::Info => println!("{}", message),
+        None => eprintln!("{}", message),
+    }
+}
+
+pub fn error(message: fmt::Arguments) {
+    log(Level::Error, message);
+}
+
+pub fn warn(message: fmt::Arguments) {
+    log(Level::Warn, message);
+}
+
+pub fn info(message: fmt::Arguments) {
+    log(Level::Info, message);
+}
+
+pub fn debug(message: fmt::Arguments) {
+    log(Level::Debug, message);
+}
+
+/* The tracer behind --log-level debug and trace. */
+pub fn event(event: &str, vertices: &[usize]) {
+    let level = if event == "phase" { Level::Debug } else { Level::Trace };
+    if enabled(level) {
+        let ids: Vec<String> = vertices.iter().map(|v| v.to_string()).collect();
+        log(level, format_args!("{} {}", event, ids.join(" ")));
+    }
+}
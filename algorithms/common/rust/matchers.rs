@@ -0,0 +1,518 @@
+/*
+ * The general-graph matchers behind one entry point, for programs that
+ * use maximum cardinality matching as a subroutine (reductions,
+ * applications, cross-checks).
+ *
+ * Each solver source file is pulled in as a module; its own main() is
+ * compiled but never called. Names follow run_large_benchmarks.sh.
+ *
+ * "auto" is not a solver of its own: it 2-colors the graph and runs
+ * Hopcroft-Karp on the two color classes when that succeeds. Otherwise
+ * auto_choice picks one of AUTO_CANDIDATES from cheap statistics (size,
+ * density, degree spread, greedy gap). Solution::route says which and why.
+ *
+ * Included via #[path = "../../common/rust/matchers.rs"] mod matchers;
+ * together with error.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::sync::atomic::AtomicBool;
+use std::sync::{Mutex, OnceLock};
+
+#[path = "../../edmonds-blossom-simple/rust/edmonds_blossom_simple.rs"]
+mod edmonds_blossom_simple;
+#[path = "../../edmonds-blossom-optimized/rust/edmonds_blossom_optimized.rs"]
+mod edmonds_blossom_optimized;
+#[path = "../../gabow-simple/rust/gabow_simple.rs"]
+mod gabow_simple;
+#[path = "../../gabow-optimized/rust/gabow_optimized.rs"]
+mod gabow_optimized;
+#[path = "../../micali-vazirani-pure/rust/micali_vazirani_pure.rs"]
+mod micali_vazirani_pure;
+#[path = "../../hopcroft-karp/rust/hopcroft_karp.rs"]
+mod hopcroft_karp;
+#[path = "types.rs"]
+mod types;
+
+use self::types::Vertex;
+use super::error::SuiteError;
+
+pub const GENERAL_ALGORITHMS: [&str; 5] =
+    ["edmonds-simple", "edmonds-opt", "gabow-simple", "gabow-opt", "mv-pure"];
+
+pub const DEFAULT_ALGORITHM: &str = "mv-pure";
+
+/* Bipartite graphs to Hopcroft-Karp, the rest to one of AUTO_CANDIDATES. */
+pub const AUTO_ALGORITHM: &str = "auto";
+
+pub const AUTO_BIPARTITE: &str = "hopcroft-karp";
+
+pub const AUTO_CANDIDATES: [&str; 3] = ["edmonds-simple", "gabow-opt", "mv-pure"];
+
+/* combi solve on a graph with edge weights (weighted_blossom.rs); not a
+ * solver of this module. */
+pub const WEIGHTED_ALGORITHM: &str = "weighted-blossom";
+
+static STOP: OnceLock<&'static AtomicBool> = OnceLock::new();
+
+/* From now on every solve polls `flag` between phases and, once it is
+ * raised, returns the valid but possibly not maximum matching it has
+ * (combi solve passes interrupt::STOP). */
+pub fn stop_on(flag: &'static AtomicBool) {
+    let _ = STOP.set(flag);
+}
+
+static PROGRESS: OnceLock<&'static (dyn Fn(usize, usize, usize) + Sync)> = OnceLock::new();
+
+/* From now on every solve calls `report(phase, augmentations, free
+ * vertices)` after each phase (progress.rs; combi solve --progress passes
+ * progress::report). With stop_on, a UI can show progress and cancel. */
+pub fn progress_on(report: &'static (dyn Fn(usize, usize, usize) + Sync)) {
+    let _ = PROGRESS.set(report);
+}
+
+static TRACE: OnceLock<types::TraceSink> = OnceLock::new();
+
+/* From now on every solve calls `tracer(event, vertices)` for its starting
+ * pairs, phases, blossoms and augmenting paths (trace.rs; combi solve
+ * --trace passes trace::record). Vertex ids are the graph's, also when
+ * auto runs Hopcroft-Karp. */
+pub fn trace_on(tracer: types::TraceSink) {
+    let _ = TRACE.set(tracer);
+}
+
+/* The graph id of each Hopcroft-Karp id of the running bipartite_run. */
+static BIPARTITE_IDS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/* TRACE with Hopcroft-Karp's ids (right vertex b is left + b) mapped
+ * back; `phase` carries a count, not a vertex. */
+fn bipartite_trace(event: &str, vertices: &[usize]) {
+    if let Some(t) = TRACE.get() {
+        if event == "phase" {
+            t(event, vertices);
+        } else {
+            let ids = BIPARTITE_IDS.lock().unwrap();
+            let mapped: Vec<usize> = vertices.iter().map(|&v| ids[v]).collect();
+            t(event, &mapped);
+        }
+    }
+}
+
+/* Whether `algo` can number n vertices, before allocating: the Edmonds
+ * solvers keep i32 ids inside, the others use types::Vertex. */
+pub fn check_vertex_count(algo: &str, n: usize) -> Result<(), SuiteError> {
+    let (max, ids) = match algo {
+        "edmonds-simple" => (edmonds_blossom_simple::MAX_VERTICES, "i32".to_string()),
+        "edmonds-opt" => (edmonds_blossom_optimized::MAX_VERTICES, "i32".to_string()),
+        _ => (types::MAX_VERTICES, format!("{}-bit", 8 * std::mem::size_of::<Vertex>())),
+    };
+    if n > max {
+        Err(SuiteError::TooManyVertices { n, ids, algo: algo.to_string() })
+    } else {
+        Ok(())
+    }
+}
+
+pub struct Solution {
+    pub matching: Vec<(usize, usize)>,
+    pub greedy_size: usize,
+    /* Pairs of greedy_size added by the --init-aug3 passes. */
+    pub aug3_size: usize,
+    /* (path length, augmentations) per phase; empty unless phase-structured
+     * (gabow-opt, mv-pure). */
+    pub phases: Vec<(usize, usize)>,
+    /* For "auto": which solver ran, and why. */
+    pub route: Option<Route>,
+    /* mate[v] of `matching` (NONE for a free vertex), for the queries below. */
+    mate: Vec<Vertex>,
+}
+
+/* Per-vertex queries, the same on every solver struct. They describe
+ * `matching` as solved; edit it and they no longer agree. */
+impl Solution {
+    /* v's partner, or None if v is free or not a vertex of the graph. */
+    pub fn mate(&self, v: usize) -> Option<usize> {
+        self.mate.get(v).and_then(|&m| types::some(m))
+    }
+
+    pub fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    /* The free vertices, in increasing order (isolated ones included). */
+    pub fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.mate.len()).filter(move |&v| self.mate[v] == types::NONE)
+    }
+
+    /* Pairs in the matching. */
+    pub fn matching_size(&self) -> usize {
+        self.matching.len()
+    }
+}
+
+pub struct Route {
+    pub algo: &'static str,
+    /* one line for the report, e.g. "not bipartite (odd cycle), mv-pure" */
+    pub summary: String,
+    /* the statistics and the rule that decided, one line each (--explain) */
+    pub reasons: Vec<String>,
+}
+
+/* Adjacency of the valid edges (self-loops and out-of-range endpoints
+ * dropped) as CSR: the neighbours of v are adj[start[v]..start[v + 1]]. */
+fn csr(n: usize, edges: &[(usize, usize)]) -> (Vec<usize>, Vec<usize>) {
+    let mut start = vec![0usize; n + 1];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            start[u + 1] += 1;
+            start[v + 1] += 1;
+        }
+    }
+    for v in 0..n {
+        start[v + 1] += start[v];
+    }
+    let mut adj = vec![0usize; start[n]];
+    let mut fill = start.clone();
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[fill[u]] = v;
+            fill[u] += 1;
+            adj[fill[v]] = u;
+            fill[v] += 1;
+        }
+    }
+    (start, adj)
+}
+
+/* Color classes of a 2-coloring (true = second class), or None if the
+ * graph has an odd cycle. Self-loops and out-of-range endpoints are
+ * ignored, as by the solvers; isolated vertices get the first class. */
+pub fn two_coloring(n: usize, edges: &[(usize, usize)]) -> Option<Vec<bool>> {
+    let (start, adj) = csr(n, edges);
+    const UNCOLORED: u8 = 2;
+    let mut color = vec![UNCOLORED; n];
+    let mut queue = Vec::new();
+    for s in 0..n {
+        if color[s] != UNCOLORED { continue; }
+        color[s] = 0;
+        queue.clear();
+        queue.push(s);
+        let mut head = 0;
+        while head < queue.len() {
+            let u = queue[head];
+            head += 1;
+            for &w in &adj[start[u]..start[u + 1]] {
+                if color[w] == UNCOLORED {
+                    color[w] = 1 - color[u];
+                    queue.push(w);
+                } else if color[w] == color[u] {
+                    return None;
+                }
+            }
+        }
+    }
+    Some(color.into_iter().map(|c| c == 1).collect())
+}
+
+/* What auto_choice looks at, in one pass over the graph. Parallel copies
+ * count as loaded (--merge-parallel removes them). */
+pub struct AutoStats {
+    pub vertices: usize,  /* with at least one edge */
+    pub edges: usize,
+    pub density: f64,     /* edges over vertex pairs */
+    pub mean_degree: f64,
+    pub max_degree: usize,
+    pub degree_cv: f64,   /* standard deviation / mean */
+    pub greedy: usize,    /* maximal matching, edges in input order */
+    pub ceiling: usize,   /* half of each component, rounded down */
+}
+
+impl AutoStats {
+    /* Share of the ceiling the greedy matching misses. */
+    pub fn gap(&self) -> f64 {
+        if self.ceiling == 0 { 0.0 } else { (self.ceiling - self.greedy) as f64 / self.ceiling as f64 }
+    }
+}
+
+pub fn auto_stats(n: usize, edges: &[(usize, usize)]) -> AutoStats {
+    let (start, adj) = csr(n, edges);
+    let deg = |v: usize| start[v + 1] - start[v];
+    let vertices = (0..n).filter(|&v| deg(v) > 0).count();
+    let m = adj.len() / 2;
+    let mean_degree = if vertices > 0 { adj.len() as f64 / vertices as f64 } else { 0.0 };
+    let variance = (0..n).filter(|&v| deg(v) > 0)
+        .map(|v| (deg(v) as f64 - mean_degree).powi(2))
+        .sum::<f64>() / vertices.max(1) as f64;
+
+    let mut matched = vec![false; n];
+    let mut greedy = 0;
+    for &(u, v) in edges {
+        if u < n && v < n && u != v && !matched[u] && !matched[v] {
+            matched[u] = true;
+            matched[v] = true;
+            greedy += 1;
+        }
+    }
+
+    let mut seen = vec![false; n];
+    let mut stack = Vec::new();
+    let mut ceiling = 0;
+    for s in 0..n {
+        if seen[s] || deg(s) == 0 { continue; }
+        seen[s] = true;
+        stack.push(s);
+        let mut size = 0;
+        while let Some(u) = stack.pop() {
+            size += 1;
+            for &w in &adj[start[u]..start[u + 1]] {
+                if !seen[w] {
+                    seen[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        ceiling += size / 2;
+    }
+
+    let pairs = vertices as f64 * (vertices as f64 - 1.0) / 2.0;
+    AutoStats {
+        vertices,
+        edges: m,
+        density: if pairs > 0.0 { m as f64 / pairs } else { 0.0 },
+        mean_degree,
+        max_degree: (0..n).map(deg).max().unwrap_or(0),
+        degree_cv: if mean_degree > 0.0 { variance.sqrt() / mean_degree } else { 0.0 },
+        greedy,
+        ceiling,
+    }
+}
+
+/* Thresholds of auto_choice, from combi solve timings on Erdos-Renyi,
+ * random regular and triangular lattice graphs (see combi_README.md). */
+const SMALL_VERTICES: usize = 5000;
+const DENSE_DENSITY: f64 = 0.01;
+const REGULAR_CV: f64 = 0.4;
+const REGULAR_MEAN_DEGREE: f64 = 8.0;
+const NEAR_PERFECT_GAP: f64 = 0.03;
+
+/* Which of AUTO_CANDIDATES runs on a graph that is not bipartite, and the
+ * rule that decided. Edmonds simple augments many disjoint paths per
+ * forest pass and is the fastest on most graphs. On near-regular sparse
+ * ones, whose (near-)perfect matchings end in a few long augmenting paths,
+ * its passes stall for minutes where the O(E sqrt V) solvers take seconds;
+ * of those, gabow-opt is ahead when greedy leaves only a few free vertices. */
+pub fn auto_choice(s: &AutoStats) -> (&'static str, String) {
+    if s.vertices <= SMALL_VERTICES && s.density >= DENSE_DENSITY {
+        return ("edmonds-simple", format!("small and dense (at most {} vertices, density at least {}): \
+                                            a few cheap forest passes", SMALL_VERTICES, DENSE_DENSITY));
+    }
+    if s.degree_cv < REGULAR_CV && s.mean_degree <= REGULAR_MEAN_DEGREE {
+        let regular = format!("near-regular sparse (degree spread below {}, mean degree at most {})",
+                              REGULAR_CV, REGULAR_MEAN_DEGREE);
+        if s.gap() <= NEAR_PERFECT_GAP {
+            return ("gabow-opt", format!("{} and greedy within {}% of the ceiling: a few long augmenting paths, \
+                                          found phase by phase", regular, NEAR_PERFECT_GAP * 100.0));
+        }
+        return ("mv-pure", format!("{}: long augmenting paths stall Edmonds simple's passes, \
+                                    not mv-pure's O(E sqrt V) phases", regular));
+    }
+    ("edmonds-simple", format!("irregular or denser (degree spread at least {} or mean degree above {}): \
+                                short augmenting paths, found in a handful of forest passes",
+                               REGULAR_CV, REGULAR_MEAN_DEGREE))
+}
+
+/* What "auto" runs on (n, edges): Hopcroft-Karp on the 2-coloring when
+ * there is one (returned with the route), else auto_choice's pick. */
+pub fn auto_route(n: usize, edges: &[(usize, usize)]) -> (Option<Vec<bool>>, Route) {
+    if let Some(side) = two_coloring(n, edges) {
+        let second = side.iter().filter(|&&s| s).count();
+        let route = Route {
+            algo: AUTO_BIPARTITE,
+            summary: format!("bipartite ({} + {} vertices), {}", n - second, second, AUTO_BIPARTITE),
+            reasons: vec![
+                format!("2-coloring: classes of {} and {} vertices (isolated ones in the first)", n - second, second),
+                format!("choice: {}, O(E sqrt V) on the two classes", AUTO_BIPARTITE),
+            ],
+        };
+        return (Some(side), route);
+    }
+    let s = auto_stats(n, edges);
+    let (algo, rule) = auto_choice(&s);
+    let route = Route {
+        algo,
+        summary: format!("not bipartite (odd cycle), {}", algo),
+        reasons: vec![
+            "2-coloring: odd cycle, not bipartite".to_string(),
+            format!("size: {} vertices with edges, {} edges, density {:.2e}", s.vertices, s.edges, s.density),
+            format!("degrees: mean {:.2}, max {}, spread (std dev / mean) {:.2}",
+                    s.mean_degree, s.max_degree, s.degree_cv),
+            format!("greedy: {} pairs, {} below the ceiling of {} ({:.1}%)",
+                    s.greedy, s.ceiling - s.greedy, s.ceiling, 100.0 * s.gap()),
+            format!("choice: {}, {}", algo, rule),
+        ],
+    };
+    (None, route)
+}
+
+/* Hopcroft-Karp on the classes of `side`, ids mapped back. */
+fn bipartite_run(
+    n: usize, edges: &[(usize, usize)], side: &[bool], greedy_mode: i32, seed: u64, aug3_passes: usize,
+    init: Option<&[Vertex]>,
+) -> Solution {
+    let mut local = vec![0usize; n];
+    let mut ids: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+    for v in 0..n {
+        let k = side[v] as usize;
+        local[v] = ids[k].len();
+        ids[k].push(v);
+    }
+    let oriented = |u: usize, v: usize| if side[u] { (local[v], local[u]) } else { (local[u], local[v]) };
+    let pairs: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| oriented(u, v))
+        .collect();
+    let mut hk = hopcroft_karp::HopcroftKarp::new(ids[0].len(), ids[1].len(), &pairs);
+    hk.greedy_seed = seed;
+    hk.aug3_passes = aug3_passes;
+    hk.stop = STOP.get().copied();
+    hk.progress = PROGRESS.get().copied();
+    if TRACE.get().is_some() {
+        *BIPARTITE_IDS.lock().unwrap() = ids[0].iter().chain(ids[1].iter()).copied().collect();
+        hk.trace = Some(&bipartite_trace);
+    }
+    if let Some(mate) = init {
+        let start: Vec<(usize, usize)> = (0..n)
+            .filter(|&u| !side[u] && types::some(mate[u]).is_some())
+            .map(|u| (local[u], local[types::ix(mate[u])]))
+            .collect();
+        hk.seed(&start);
+    }
+    let mut matching: Vec<(usize, usize)> = hk.maximum_matching(greedy_mode).into_iter()
+        .map(|(a, b)| {
+            let (u, v) = (ids[0][a], ids[1][b]);
+            (u.min(v), u.max(v))
+        })
+        .collect();
+    matching.sort_unstable();
+    Solution { matching, greedy_size: hk.greedy_size(), aug3_size: hk.aug3_size(), phases: Vec::new(), route: None, mate: Vec::new() }
+}
+
+/* Maximum matching of the graph (n, edges) as sorted (u, v) pairs, u < v.
+ * Self-loops, duplicates and out-of-range endpoints are ignored, exactly
+ * as in the standalone binaries. */
+pub fn maximum_matching(
+    algo: &str, n: usize, edges: &[(usize, usize)],
+) -> Result<Vec<(usize, usize)>, String> {
+    solve(algo, n, edges, 0, 1, 0).map(|s| s.matching)
+}
+
+/* Same, with the binaries' greedy initializer: 0 none, 1 --greedy,
+ * 2 --greedy-md, 3 --greedy-ks, 4 --greedy-random seeded by `seed`;
+ * `aug3_passes` > 0 follows it with that many --init-aug3 passes. */
+pub fn solve(
+    algo: &str, n: usize, edges: &[(usize, usize)], greedy_mode: i32, seed: u64, aug3_passes: usize,
+) -> Result<Solution, String> {
+    run(algo, n, edges, greedy_mode, seed, aug3_passes, None)
+}
+
+/* Same, starting from the matching `init` (--init-matching), which must be
+ * one of the graph (matching_io::init_from_args checks that); greedy_size
+ * reports its size. */
+pub fn solve_from(
+    algo: &str, n: usize, edges: &[(usize, usize)], init: &[(usize, usize)],
+) -> Result<Solution, String> {
+    let mate = types::mate_of(n, init);
+    let mut solution = run(algo, n, edges, 0, 1, 0, Some(&mate))?;
+    solution.greedy_size = init.len();
+    Ok(solution)
+}
+
+fn run(
+    algo: &str, n: usize, edges: &[(usize, usize)], greedy_mode: i32, seed: u64, aug3_passes: usize,
+    init: Option<&[Vertex]>,
+) -> Result<Solution, String> {
+    check_vertex_count(algo, n).map_err(|e| e.to_string())?;
+    let stop = STOP.get().copied();
+    let progress = PROGRESS.get().copied();
+    let trace = TRACE.get().copied();
+    let mut solution = match algo {
+        AUTO_ALGORITHM => {
+            let (side, route) = auto_route(n, edges);
+            let mut s = match side {
+                Some(side) => bipartite_run(n, edges, &side, greedy_mode, seed, aug3_passes, init),
+                None => run(route.algo, n, edges, greedy_mode, seed, aug3_passes, init)?,
+            };
+            s.route = Some(route);
+            s
+        }
+        "edmonds-simple" => {
+            let mut s = edmonds_blossom_simple::Solver::new(n, edges);
+            s.greedy_seed = seed;
+            s.aug3_passes = aug3_passes;
+            s.stop = stop;
+            s.progress = progress;
+            s.trace = trace;
+            if let Some(mate) = init { s.set_mate(mate); }
+            let matching = s.solve(greedy_mode);
+            Solution { matching, greedy_size: s.greedy_size as usize, aug3_size: s.aug3_size as usize, phases: Vec::new(), route: None, mate: Vec::new() }
+        }
+        "edmonds-opt" => {
+            let mut s = edmonds_blossom_optimized::Solver::new(n, edges);
+            s.greedy_seed = seed;
+            s.aug3_passes = aug3_passes;
+            s.stop = stop;
+            s.progress = progress;
+            s.trace = trace;
+            if let Some(mate) = init { s.set_mate(mate); }
+            let matching = s.solve(greedy_mode);
+            Solution { matching, greedy_size: s.greedy_size as usize, aug3_size: s.aug3_size as usize, phases: Vec::new(), route: None, mate: Vec::new() }
+        }
+        "gabow-simple" => {
+            let mut g = gabow_simple::GabowSimple::new(n, edges);
+            g.greedy_seed = seed;
+            g.aug3_passes = aug3_passes;
+            g.stop = stop;
+            g.progress = progress;
+            g.trace = trace;
+            if let Some(mate) = init { g.set_mate(mate); }
+            let matching = g.maximum_matching(greedy_mode);
+            Solution { matching, greedy_size: g.greedy_size, aug3_size: g.aug3_size, phases: Vec::new(), route: None, mate: Vec::new() }
+        }
+        "gabow-opt" => {
+            let mut g = gabow_optimized::GabowOptimized::new(n, edges);
+            g.greedy_seed = seed;
+            g.aug3_passes = aug3_passes;
+            g.stop = stop;
+            g.progress = progress;
+            g.trace = trace;
+            if let Some(mate) = init { g.set_mate(mate); }
+            let matching = g.maximum_matching(greedy_mode);
+            Solution { matching, greedy_size: g.greedy_size, aug3_size: g.aug3_size, phases: g.phases.clone(), route: None, mate: Vec::new() }
+        }
+        "mv-pure" => {
+            let mut mv = micali_vazirani_pure::MVGraph::new();
+            mv.build(n, edges);
+            mv.stop = stop;
+            mv.progress = progress;
+            mv.trace = trace;
+            if let Some(mate) = init { mv.set_mate(mate); }
+            let greedy_size = match greedy_mode {
+                1 => mv.greedy_init(),
+                2 => mv.greedy_init_md(),
+                3 => mv.greedy_init_ks(),
+                4 => mv.greedy_init_random(seed),
+                _ => 0,
+            };
+            let aug3_size = if aug3_passes > 0 { mv.augment3(aug3_passes) } else { 0 };
+            mv.max_match();
+            Solution { matching: mv.get_matching(), greedy_size: greedy_size + aug3_size, aug3_size, phases: mv.phases.clone(), route: None, mate: Vec::new() }
+        }
+        _ => {
+            return Err(format!("unknown algorithm '{}' (expected one of: {}, {})",
+                               algo, GENERAL_ALGORITHMS.join(", "), AUTO_ALGORITHM));
+        }
+    };
+    solution.mate = types::mate_of(n, &solution.matching);
+    Ok(solution)
+}
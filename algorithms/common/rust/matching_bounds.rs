@@ -0,0 +1,356 @@
+/*
+ * Cheap bounds on the matching number nu(G), for deciding whether an exact
+ * run is worth it.
+ *
+ * Lower bounds are matchings:
+ *   - greedy: first fit in vertex order, as the solvers' --greedy;
+ *   - Karp-Sipser: match a degree-1 vertex with its neighbour while there
+ *     is one (always safe: some maximum matching uses that edge), else
+ *     match the lowest-id live vertex with its first live neighbour. Exact
+ *     on forests, and close to nu on sparse random graphs. The solvers'
+ *     --greedy-ks is the same rule on their own adjacency.
+ * Upper bounds:
+ *   - vertices: half the non-isolated vertices;
+ *   - components: floor(|C| / 2) summed over connected components;
+ *   - LP: the fractional matching number, per component. It equals half a
+ *     maximum matching of the bipartite double cover (v_L w_R and w_L v_R
+ *     for every edge vw), from Hopcroft-Karp, and nu(C) <= floor of it.
+ *     On bipartite graphs it is exact. A matching of G, doubled, seeds the
+ *     search, so only the gap to the optimum is augmented (Hopcroft-Karp
+ *     recurses along augmenting paths; a good seed keeps them short).
+ * All are linear time except the LP bound, O(E sqrt V).
+ *
+ * lp_relaxation reports the LP optimum itself, unrounded, next to a
+ * maximum matching (combi solve --lp-gap): the fractional matching LP
+ * (x(delta(v)) <= 1, x >= 0, no odd-set constraints) has a half-integral
+ * optimum, so twice it is an integer, and the integrality gap is its
+ * excess over the matching size. The matching seeds the double cover, so
+ * only the gap is augmented.
+ *
+ * half_integral_matching reads an optimal solution x off the same double
+ * cover matching: x_vw = 1 when it holds both v_L w_R and w_L v_R, 1/2
+ * when it holds one. The edges at 1/2 form directed paths and cycles;
+ * even cycles and paths are rounded alternately without loss (an odd path
+ * would round to more than the optimum), which leaves a basic optimum:
+ * a matching at 1 and vertex-disjoint odd cycles at 1/2. Dropping one
+ * vertex per odd cycle rounds it to a matching of at least LP - cycles/2
+ * pairs (more when a dropped vertex finds an exposed neighbour), so nu is
+ * at least that and the integrality gap at most cycles/2. The rounding is
+ * also a warm start whose vertices can be fixed as matched: augmenting
+ * never exposes a vertex, so some maximum matching covers every vertex it
+ * covers, and at most cycles/2 augmentations remain.
+ *
+ * Included via #[path = "../../common/rust/matching_bounds.rs"] mod matching_bounds;
+ * next to hopcroft_karp.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::hopcroft_karp::HopcroftKarp;
+
+const NIL: usize = usize::MAX;
+
+/* Simple graph as CSR: neighbours of v are adj[start[v]..start[v + 1]]. */
+struct Csr {
+    start: Vec<usize>,
+    adj: Vec<usize>,
+}
+
+impl Csr {
+    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut lists = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                lists[u].push(v);
+                lists[v].push(u);
+            }
+        }
+        let mut start = Vec::with_capacity(n + 1);
+        let mut adj = Vec::new();
+        for l in &mut lists {
+            l.sort_unstable();
+            l.dedup();
+            start.push(adj.len());
+            adj.extend_from_slice(l);
+        }
+        start.push(adj.len());
+        Csr { start, adj }
+    }
+
+    fn neighbors(&self, v: usize) -> &[usize] {
+        &self.adj[self.start[v]..self.start[v + 1]]
+    }
+
+    fn degree(&self, v: usize) -> usize {
+        self.start[v + 1] - self.start[v]
+    }
+}
+
+/* Component id per vertex, and the number of components. */
+fn components(g: &Csr, n: usize) -> (Vec<usize>, usize) {
+    let mut comp = vec![NIL; n];
+    let mut count = 0;
+    let mut stack = Vec::new();
+    for s in 0..n {
+        if comp[s] != NIL { continue; }
+        comp[s] = count;
+        stack.push(s);
+        while let Some(v) = stack.pop() {
+            for &w in g.neighbors(v) {
+                if comp[w] == NIL {
+                    comp[w] = count;
+                    stack.push(w);
+                }
+            }
+        }
+        count += 1;
+    }
+    (comp, count)
+}
+
+/* First-fit greedy matching, sorted pairs (u < v). */
+pub fn greedy_matching(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let g = Csr::new(n, edges);
+    let mut mate = vec![NIL; n];
+    let mut matching = Vec::new();
+    for u in 0..n {
+        if mate[u] != NIL { continue; }
+        if let Some(&v) = g.neighbors(u).iter().find(|&&v| mate[v] == NIL) {
+            mate[u] = v;
+            mate[v] = u;
+            matching.push((u, v));
+        }
+    }
+    matching
+}
+
+/* Karp-Sipser matching, sorted pairs (u < v). */
+pub fn karp_sipser(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let g = Csr::new(n, edges);
+    let mut deg: Vec<usize> = (0..n).map(|v| g.degree(v)).collect();
+    let mut alive = vec![true; n];
+    let mut leaves: Vec<usize> = (0..n).filter(|&v| deg[v] == 1).collect();
+    let mut matching = Vec::new();
+    let mut next = 0;
+    loop {
+        let (u, v) = if let Some(u) = leaves.pop() {
+            if !alive[u] || deg[u] != 1 { continue; }
+            (u, *g.neighbors(u).iter().find(|&&w| alive[w]).unwrap())
+        } else {
+            while next < n && (!alive[next] || deg[next] == 0) { next += 1; }
+            if next == n { break; }
+            (next, *g.neighbors(next).iter().find(|&&w| alive[w]).unwrap())
+        };
+        for &x in &[u, v] {
+            alive[x] = false;
+            for &w in g.neighbors(x) {
+                if !alive[w] { continue; }
+                deg[w] -= 1;
+                if deg[w] == 1 { leaves.push(w); }
+            }
+        }
+        matching.push((u.min(v), u.max(v)));
+    }
+    matching.sort_unstable();
+    matching
+}
+
+/* floor(non-isolated vertices / 2). */
+pub fn vertex_bound(n: usize, edges: &[(usize, usize)]) -> usize {
+    let g = Csr::new(n, edges);
+    (0..n).filter(|&v| g.degree(v) > 0).count() / 2
+}
+
+/* Sum of floor(|C| / 2) over the connected components C. */
+pub fn component_bound(n: usize, edges: &[(usize, usize)]) -> usize {
+    let g = Csr::new(n, edges);
+    let (comp, count) = components(&g, n);
+    let mut size = vec![0usize; count];
+    for v in 0..n { size[comp[v]] += 1; }
+    size.iter().map(|s| s / 2).sum()
+}
+
+/* Sum over the components of floor(fractional matching number); `warm` is
+ * a matching of (n, edges) to start from. */
+pub fn lp_bound(n: usize, edges: &[(usize, usize)], warm: &[(usize, usize)]) -> usize {
+    let g = Csr::new(n, edges);
+    let (comp, count) = components(&g, n);
+    let mut per_comp = vec![0usize; count];
+    for (u, _) in double_cover_matching(&g, n, warm) { per_comp[comp[u]] += 1; }
+    per_comp.iter().map(|m| m / 2).sum()
+}
+
+/* The fractional matching LP next to an integral matching. */
+pub struct Relaxation {
+    /* twice the LP optimum (the optimum is half-integral) */
+    pub twice_lp: usize,
+    /* components with an edge, and those whose LP optimum exceeds their
+     * share of the matching */
+    pub components: usize,
+    pub gap_components: usize,
+}
+
+impl Relaxation {
+    pub fn lp(&self) -> f64 {
+        self.twice_lp as f64 / 2.0
+    }
+}
+
+/* LP optimum of (n, edges), per component against `matching`, which should
+ * be maximum for the gap to mean the integrality gap; and the optimum
+ * itself. */
+pub fn lp_relaxation(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> (Relaxation, HalfIntegral) {
+    let g = Csr::new(n, edges);
+    let (comp, count) = components(&g, n);
+    let cover = double_cover_matching(&g, n, matching);
+    let mut twice_lp = vec![0usize; count];
+    for &(u, _) in &cover { twice_lp[comp[u]] += 1; }
+    let mut integral = vec![0usize; count];
+    for &(u, _) in matching { integral[comp[u]] += 1; }
+    let mut has_edge = vec![false; count];
+    for v in 0..n { if g.degree(v) > 0 { has_edge[comp[v]] = true; } }
+    let relaxation = Relaxation {
+        twice_lp: twice_lp.iter().sum(),
+        components: has_edge.iter().filter(|&&e| e).count(),
+        gap_components: (0..count).filter(|&c| twice_lp[c] > 2 * integral[c]).count(),
+    };
+    (relaxation, HalfIntegral::from_cover(n, &cover))
+}
+
+/* A basic optimum of the fractional matching LP: edges at 1 and
+ * vertex-disjoint odd cycles of edges at 1/2, every other edge at 0. */
+pub struct HalfIntegral {
+    /* sorted pairs (u < v) */
+    pub ones: Vec<(usize, usize)>,
+    /* each odd cycle as its vertices in cycle order */
+    pub cycles: Vec<Vec<usize>>,
+}
+
+impl HalfIntegral {
+    /* `cover` is a maximum matching of the double cover, (left, right). */
+    fn from_cover(n: usize, cover: &[(usize, usize)]) -> Self {
+        let mut out = vec![NIL; n];
+        let mut into = vec![NIL; n];
+        for &(u, w) in cover {
+            out[u] = w;
+            into[w] = u;
+        }
+        let mut ones = Vec::new();
+        let mut cycles = Vec::new();
+        let mut seen = vec![false; n];
+        for u in 0..n {
+            let w = out[u];
+            if w != NIL && out[w] == u {
+                seen[u] = true;
+                if u < w { ones.push((u, w)); }
+            }
+        }
+        /* the edges at 1/2: paths first, from their tails, then cycles */
+        let mut walk = Vec::new();
+        for pass in 0..2 {
+            for s in 0..n {
+                if seen[s] || out[s] == NIL || (pass == 0 && into[s] != NIL) { continue; }
+                walk.clear();
+                let mut v = s;
+                while v != NIL && !seen[v] {
+                    seen[v] = true;
+                    walk.push(v);
+                    v = out[v];
+                }
+                if pass == 1 && walk.len() % 2 == 1 {
+                    cycles.push(walk.clone());
+                    continue;
+                }
+                for pair in walk.chunks(2).filter(|p| p.len() == 2) {
+                    ones.push((pair[0].min(pair[1]), pair[0].max(pair[1])));
+                }
+            }
+        }
+        ones.sort_unstable();
+        HalfIntegral { ones, cycles }
+    }
+
+    /* Twice the objective: the LP optimum, doubled. */
+    pub fn twice_value(&self) -> usize {
+        2 * self.ones.len() + self.cycles.iter().map(|c| c.len()).sum::<usize>()
+    }
+
+    /* The ones and a near-perfect matching of every cycle, at least
+     * LP - cycles/2 pairs of (n, edges), sorted (u < v). A cycle leaves
+     * exposed a vertex with an exposed neighbour off the cycles when it
+     * has one, and is matched to it; first-fit then fills in. */
+    pub fn rounded(&self, n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let g = Csr::new(n, edges);
+        let mut mate = vec![NIL; n];
+        let mut on_cycle = vec![false; n];
+        for &(u, v) in &self.ones {
+            mate[u] = v;
+            mate[v] = u;
+        }
+        for c in &self.cycles {
+            for &v in c { on_cycle[v] = true; }
+        }
+        for c in &self.cycles {
+            let free = |v: usize| g.neighbors(v).iter().cloned().find(|&w| mate[w] == NIL && !on_cycle[w]);
+            let start = (0..c.len()).find(|&i| free(c[i]).is_some()).unwrap_or(0);
+            if let Some(w) = free(c[start]) {
+                mate[c[start]] = w;
+                mate[w] = c[start];
+            }
+            for j in (1..c.len()).step_by(2) {
+                let (u, v) = (c[(start + j) % c.len()], c[(start + j + 1) % c.len()]);
+                mate[u] = v;
+                mate[v] = u;
+            }
+            for &v in c { on_cycle[v] = false; }
+        }
+        for u in 0..n {
+            if mate[u] != NIL { continue; }
+            if let Some(&v) = g.neighbors(u).iter().find(|&&v| mate[v] == NIL) {
+                mate[u] = v;
+                mate[v] = u;
+            }
+        }
+        (0..n).filter(|&u| mate[u] != NIL && u < mate[u]).map(|u| (u, mate[u])).collect()
+    }
+}
+
+/* A basic optimum of the fractional matching LP of (n, edges); `warm` is a
+ * matching of (n, edges) to start from. */
+pub fn half_integral_matching(n: usize, edges: &[(usize, usize)], warm: &[(usize, usize)]) -> HalfIntegral {
+    let g = Csr::new(n, edges);
+    HalfIntegral::from_cover(n, &double_cover_matching(&g, n, warm))
+}
+
+/* Maximum matching of the bipartite double cover of `g`, seeded with
+ * `warm` doubled; pairs are (left, right) in original ids. */
+fn double_cover_matching(g: &Csr, n: usize, warm: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut h_edges = Vec::with_capacity(g.adj.len());
+    for v in 0..n {
+        for &w in g.neighbors(v) {
+            h_edges.push((v, w));
+        }
+    }
+    let mut hk = HopcroftKarp::new(n, n, &h_edges);
+    let doubled: Vec<(usize, usize)> = warm.iter().flat_map(|&(u, v)| vec![(u, v), (v, u)]).collect();
+    hk.seed(&doubled);
+    hk.maximum_matching(0)
+}
+
+/* Pairs that are not edges of (n, edges), or reuse a vertex. */
+pub fn check_matching(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Vec<String> {
+    let g = Csr::new(n, edges);
+    let mut errors = Vec::new();
+    let mut used = vec![false; n];
+    for &(u, v) in matching {
+        if u >= n || v >= n || g.neighbors(u).binary_search(&v).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+            continue;
+        }
+        for &x in &[u, v] {
+            if used[x] { errors.push(format!("Vertex {} matched twice!", x)); }
+            used[x] = true;
+        }
+    }
+    errors
+}
@@ -0,0 +1,291 @@
+/*
+ * Matching files: the solvers' --output-matching and --init-matching,
+ * and `combi check`.
+ *
+ * Format (canonical, so equal matchings give byte-equal files):
+ *   first line   the number of pairs k
+ *   then k lines "u v"
+ * General graphs write every pair with u < v; bipartite graphs write
+ * (left id, right id). Pairs are sorted by u, then v. Blank lines and
+ * lines starting with '#' are skipped on reading. A file that reads fine
+ * but is not in this order is accepted with a note (see check_pairs).
+ * The pairs are in the names the load returned (graph_io::InputNames):
+ * after a --format snap load the graph's original ids (original_pairs),
+ * which keep the order; after a --format labels load its labels
+ * (labeled_pairs), quoted where they have to be, in the order of the
+ * ids.
+ *
+ * After an --edge-ids load the files name edges instead of pairs (the
+ * graph_io::EdgeIds of its InputNames): the count k, then k lines "id",
+ * sorted.
+ *
+ * Included via #[path = "../../common/rust/matching_io.rs"] mod matching_io;
+ * together with args.rs, graph_io.rs, log.rs and verify.rs (see there).
+ */
+
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+
+use super::args;
+use super::graph_io;
+use super::log;
+use super::verify;
+
+pub const OUTPUT_USAGE: &str = "[--output-matching FILE]";
+pub const INIT_USAGE: &str = "[--init-matching FILE]";
+
+/* The pairs in file order: (min, max) for a general graph, as given for a
+ * bipartite one, then sorted. */
+pub fn canonical(matching: &[(usize, usize)], bipartite: bool) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = if bipartite {
+        matching.to_vec()
+    } else {
+        matching.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect()
+    };
+    pairs.sort_unstable();
+    pairs
+}
+
+pub fn write_matching(out: &mut dyn Write, matching: &[(usize, usize)], bipartite: bool) -> io::Result<()> {
+    let pairs = canonical(matching, bipartite);
+    writeln!(out, "{}", pairs.len())?;
+    for &(u, v) in &pairs {
+        writeln!(out, "{} {}", u, v)?;
+    }
+    out.flush()
+}
+
+/* The pairs of a --format labels graph, already canonical (in ids). */
+pub fn write_labeled_matching(out: &mut dyn Write, pairs: &[(String, String)]) -> io::Result<()> {
+    writeln!(out, "{}", pairs.len())?;
+    for (u, v) in pairs {
+        writeln!(out, "{} {}", graph_io::quote_label(u), graph_io::quote_label(v))?;
+    }
+    out.flush()
+}
+
+/* The edge ids of an --edge-ids matching file. */
+pub fn write_edge_ids(out: &mut dyn Write, ids: &[usize]) -> io::Result<()> {
+    let mut ids = ids.to_vec();
+    ids.sort_unstable();
+    writeln!(out, "{}", ids.len())?;
+    for id in &ids {
+        writeln!(out, "{}", id)?;
+    }
+    out.flush()
+}
+
+/* The matching as a file, in the `names` of the load the graph came from. */
+pub fn save_matching(
+    path: &str, matching: &[(usize, usize)], bipartite: bool, names: &graph_io::InputNames,
+) -> Result<(), String> {
+    save_weighted_matching(path, matching, bipartite, false, names)
+}
+
+/* save_matching for a weighted solve: after an --edge-ids load, a pair
+ * joined by parallel edges is written as the heaviest, or the lightest
+ * with `lightest`. */
+pub fn save_weighted_matching(
+    path: &str, matching: &[(usize, usize)], bipartite: bool, lightest: bool, names: &graph_io::InputNames,
+) -> Result<(), String> {
+    let ids = match names.edges {
+        Some(ref table) => Some(table.ids_of(matching, lightest).map_err(|e| format!("{}: {}", path, e))?),
+        None => None,
+    };
+    let mut out = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+    match ids {
+        Some(ids) => write_edge_ids(&mut out, &ids),
+        None => match names.labeled_pairs(&canonical(matching, bipartite), bipartite) {
+            Some(labeled) => write_labeled_matching(&mut out, &labeled),
+            None => write_matching(&mut out, &names.original_pairs(matching, bipartite), bipartite),
+        },
+    }.map_err(|e| format!("{}: {}", path, e))
+}
+
+/* --output-matching FILE, if given: save and say where. */
+pub fn save_from_args(
+    args: &[String], matching: &[(usize, usize)], bipartite: bool, names: &graph_io::InputNames,
+) -> Result<(), String> {
+    save_weighted_from_args(args, matching, bipartite, false, names)
+}
+
+pub fn save_weighted_from_args(
+    args: &[String], matching: &[(usize, usize)], bipartite: bool, lightest: bool, names: &graph_io::InputNames,
+) -> Result<(), String> {
+    if let Some(path) = args::value_of(args, "--output-matching") {
+        save_weighted_matching(path, matching, bipartite, lightest, names)?;
+        log::info(format_args!("Wrote {} pairs to {}", matching.len(), path));
+    }
+    Ok(())
+}
+
+/* The pairs as read, in file order. Errors name the line; the count on the
+ * first line must match the pairs that follow. */
+pub fn read_matching<R: BufRead>(reader: R) -> Result<Vec<(usize, usize)>, String> {
+    let mut declared = None;
+    let mut pairs = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let t = line.trim();
+        if t.is_empty() || t.starts_with('#') { continue; }
+        let parts: Vec<&str> = t.split_whitespace().collect();
+        let num = |s: &str| s.parse::<usize>().map_err(|_| format!("line {}: invalid number '{}'", i + 1, s));
+        match (declared, parts.len()) {
+            (None, 1) => declared = Some(num(parts[0])?),
+            (None, _) => return Err(format!("line {}: expected the pair count", i + 1)),
+            (Some(_), 2) => pairs.push((num(parts[0])?, num(parts[1])?)),
+            (Some(_), _) => return Err(format!("line {}: expected \"u v\"", i + 1)),
+        }
+    }
+    match declared {
+        None => Err("empty matching file".to_string()),
+        Some(k) if k != pairs.len() => Err(format!("count line says {} pairs but the file has {}", k, pairs.len())),
+        Some(_) => Ok(pairs),
+    }
+}
+
+pub fn load_matching(path: &str) -> Result<Vec<(usize, usize)>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    read_matching(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
+
+/* The labeled pairs of a --format labels matching file, as read_matching
+ * reads pairs. */
+pub fn read_labeled_matching<R: BufRead>(reader: R) -> Result<Vec<(String, String)>, String> {
+    let mut declared = None;
+    let mut pairs = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let mut parts = graph_io::label_fields(&line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        match (declared, parts.len()) {
+            (_, 0) => continue,
+            (None, 1) => declared = Some(parts[0].parse::<usize>()
+                .map_err(|_| format!("line {}: invalid number '{}'", i + 1, parts[0]))?),
+            (None, _) => return Err(format!("line {}: expected the pair count", i + 1)),
+            (Some(_), 2) => {
+                let v = parts.pop().unwrap();
+                pairs.push((parts.pop().unwrap(), v));
+            }
+            (Some(_), _) => return Err(format!("line {}: expected two labels (--format labels)", i + 1)),
+        }
+    }
+    match declared {
+        None => Err("empty matching file".to_string()),
+        Some(k) if k != pairs.len() => Err(format!("count line says {} pairs but the file has {}", k, pairs.len())),
+        Some(_) => Ok(pairs),
+    }
+}
+
+pub fn load_labeled_matching(path: &str) -> Result<Vec<(String, String)>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    read_labeled_matching(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
+
+/* The ids of an --edge-ids matching file, as read_matching reads pairs. */
+pub fn read_edge_ids<R: BufRead>(reader: R) -> Result<Vec<usize>, String> {
+    let mut declared = None;
+    let mut ids = Vec::new();
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let t = line.trim();
+        if t.is_empty() || t.starts_with('#') { continue; }
+        let parts: Vec<&str> = t.split_whitespace().collect();
+        if parts.len() != 1 {
+            return Err(format!("line {}: expected {} (--edge-ids)",
+                               i + 1, if declared.is_none() { "the edge count" } else { "one edge id" }));
+        }
+        let x = parts[0].parse::<usize>().map_err(|_| format!("line {}: invalid number '{}'", i + 1, parts[0]))?;
+        match declared {
+            None => declared = Some(x),
+            Some(_) => ids.push(x),
+        }
+    }
+    match declared {
+        None => Err("empty matching file".to_string()),
+        Some(k) if k != ids.len() => Err(format!("count line says {} edges but the file has {}", k, ids.len())),
+        Some(_) => Ok(ids),
+    }
+}
+
+pub fn load_edge_ids(path: &str) -> Result<Vec<usize>, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path, e))?;
+    read_edge_ids(BufReader::new(file)).map_err(|e| format!("{}: {}", path, e))
+}
+
+/* The pairs of the matching file `path` in the graph's ids, `names` those
+ * of its load: the edges named by an --edge-ids file, the labels of a
+ * --format labels one interned, or the pairs remapped from the original
+ * ids of a --format snap one. */
+pub fn load_pairs(path: &str, bipartite: bool, names: &graph_io::InputNames) -> Result<Vec<(usize, usize)>, String> {
+    let pairs = match names.edges {
+        Some(ref table) => table.pairs_of(&load_edge_ids(path)?),
+        None if names.labels.is_some() => names.unlabeled_pairs(load_labeled_matching(path)?, bipartite),
+        None => names.remapped_pairs(load_matching(path)?, bipartite),
+    };
+    pairs.map_err(|e| format!("{}: {}", path, e))
+}
+
+/* Problems with the pairs as a file of the format: ids within `bounds`
+ * (n and n, or left and right) and the canonical order. The first is an
+ * error, the second only a note, returned separately. Whether the pairs
+ * are edges and disjoint is verify::validate's check. */
+pub fn check_pairs(pairs: &[(usize, usize)], bounds: (usize, usize), bipartite: bool) -> (Vec<String>, Option<String>) {
+    let mut errors = Vec::new();
+    for &(u, v) in pairs {
+        if u >= bounds.0 || v >= bounds.1 {
+            errors.push(format!("Pair ({}, {}) out of range!", u, v));
+        } else if !bipartite && u == v {
+            errors.push(format!("Pair ({}, {}) is a self-loop!", u, v));
+        }
+    }
+    let note = if canonical(pairs, bipartite) != pairs {
+        Some(if bipartite { "pairs not sorted".to_string() } else { "pairs not sorted with u < v".to_string() })
+    } else {
+        None
+    };
+    (errors, note)
+}
+
+/* The matching in `path`, which must be one of the graph (bounds as in
+ * check_pairs): the --init-matching start. */
+pub fn load_init(path: &str, bounds: (usize, usize), edges: &[(usize, usize)], bipartite: bool,
+                 names: &graph_io::InputNames) -> Result<Vec<(usize, usize)>, String> {
+    let pairs = load_pairs(path, bipartite, names)?;
+    let (mut errors, _) = check_pairs(&pairs, bounds, bipartite);
+    if errors.is_empty() {
+        errors = verify::validate(bounds, edges, &pairs, bipartite).errors;
+    }
+    match errors.first() {
+        Some(e) => Err(format!("{}: not a matching of the graph: {} ({} problem(s))", path, e, errors.len())),
+        None => Ok(pairs),
+    }
+}
+
+/* --init-matching FILE, if given: load_init of it. The solver starts from
+ * that matching instead of a greedy one, so --greedy, --greedy-md,
+ * --greedy-ks and --greedy-random are refused alongside it. */
+pub fn init_from_args(args: &[String], bounds: (usize, usize), edges: &[(usize, usize)], bipartite: bool,
+                      names: &graph_io::InputNames) -> Result<Option<Vec<(usize, usize)>>, String> {
+    let path = match args::value_of(args, "--init-matching") {
+        Some(p) => p,
+        None => return Ok(None),
+    };
+    if ["--greedy", "--greedy-md", "--greedy-ks", "--greedy-random", "--init-aug3"].iter().any(|f| args::has_flag(args, f)) {
+        return Err("--init-matching replaces the greedy start; drop --greedy, --greedy-md, --greedy-ks, \
+                    --greedy-random or --init-aug3".to_string());
+    }
+    load_init(path, bounds, edges, bipartite, names).map(Some)
+}
+
+/* The lines reporting a --init-matching start, after the final size, as
+ * the greedy start has its own. */
+pub fn write_init(out: &mut dyn Write, init: usize, final_size: usize) -> io::Result<()> {
+    writeln!(out, "Init matching size: {}", init)?;
+    if final_size > 0 {
+        writeln!(out, "Init/Final: {:.2}%", 100.0 * init as f64 / final_size as f64)
+    } else {
+        writeln!(out, "Init/Final: NA")
+    }
+}
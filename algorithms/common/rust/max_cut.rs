@@ -0,0 +1,248 @@
+/*
+ * Maximum cut by local search with matching-chosen pair flips.
+ *
+ * Split V into two sides so that the edges across weigh as much as
+ * possible. NP-hard; this is local search from random sides.
+ *
+ * The gain g(v) of flipping v alone is (weight to v's own side) - (weight
+ * to the other side). Flipping u and v together gains
+ *     g(u) + g(v) - 2 w(uv)   if u and v are on the same side,
+ *     g(u) + g(v) + 2 w(uv)   if not,
+ * since uv changes state in each single flip but not in the double one.
+ * At a single-flip optimum every g(v) <= 0, yet an edge across the cut
+ * can still be worth flipping as a pair. Those pairs, weighted by their
+ * gain, form the gain graph; a maximum-weight matching of it picks
+ * disjoint pairs with the most total gain, which are flipped one by one
+ * (each rechecked, as flipping one pair changes its neighbours' gains).
+ * Single flips and matching rounds alternate until neither improves.
+ *
+ * Every flip strictly raises the cut, so the search ends. With w >= 0 the
+ * end cut is at least half the total weight, since each vertex then has
+ * at least half its weight across.
+ *
+ * Self-loops are never cut and are ignored; parallel edges add up.
+ *
+ * Included via #[path = "../../common/rust/max_cut.rs"] mod max_cut;
+ * next to rng.rs and weighted_blossom.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::rng::Rng;
+use super::weighted_blossom;
+
+const NIL: usize = usize::MAX;
+
+pub struct Cut {
+    pub side: Vec<bool>,
+    pub weight: i64,
+    pub start_weight: i64,  /* the random sides */
+    pub single_weight: i64, /* after the first single-flip descent */
+    pub single_flips: usize,
+    pub pair_flips: usize,
+    pub rounds: usize,      /* matching rounds that flipped a pair */
+}
+
+struct Search {
+    adj: Vec<Vec<(usize, i64)>>, /* parallel edges merged, no loops */
+    side: Vec<bool>,
+    gain: Vec<i64>,
+    weight: i64,
+}
+
+impl Search {
+    fn new(n: usize, edges: &[(usize, usize, i64)], side: Vec<bool>) -> Self {
+        let mut adj: Vec<Vec<(usize, i64)>> = vec![Vec::new(); n];
+        for &(u, v, w) in edges {
+            if u < n && v < n && u != v {
+                adj[u].push((v, w));
+                adj[v].push((u, w));
+            }
+        }
+        for a in &mut adj {
+            a.sort_unstable();
+            let mut merged: Vec<(usize, i64)> = Vec::with_capacity(a.len());
+            for &(v, w) in a.iter() {
+                match merged.last_mut() {
+                    Some(last) if last.0 == v => last.1 += w,
+                    _ => merged.push((v, w)),
+                }
+            }
+            *a = merged;
+        }
+        let mut gain = vec![0i64; n];
+        let mut weight = 0;
+        for u in 0..n {
+            for &(v, w) in &adj[u] {
+                if side[u] == side[v] {
+                    gain[u] += w;
+                } else {
+                    gain[u] -= w;
+                    if u < v { weight += w; }
+                }
+            }
+        }
+        Search { adj, side, gain, weight }
+    }
+
+    fn flip(&mut self, v: usize) {
+        self.weight += self.gain[v];
+        self.gain[v] = -self.gain[v];
+        self.side[v] = !self.side[v];
+        for &(u, w) in &self.adj[v] {
+            if self.side[u] == self.side[v] {
+                self.gain[u] += 2 * w;
+            } else {
+                self.gain[u] -= 2 * w;
+            }
+        }
+    }
+
+    fn edge_weight(&self, u: usize, v: usize) -> i64 {
+        match self.adj[u].binary_search_by_key(&v, |e| e.0) {
+            Ok(i) => self.adj[u][i].1,
+            Err(_) => 0,
+        }
+    }
+
+    fn pair_gain(&self, u: usize, v: usize) -> i64 {
+        let w = self.edge_weight(u, v);
+        self.gain[u] + self.gain[v] + if self.side[u] == self.side[v] { -2 * w } else { 2 * w }
+    }
+
+    /* Flip single vertices while one gains; returns the number of flips. */
+    fn descend(&mut self) -> usize {
+        let n = self.side.len();
+        let mut queued = vec![true; n];
+        let mut queue: Vec<usize> = (0..n).rev().collect();
+        let mut flips = 0;
+        while let Some(v) = queue.pop() {
+            queued[v] = false;
+            if self.gain[v] <= 0 { continue; }
+            self.flip(v);
+            flips += 1;
+            for i in 0..self.adj[v].len() {
+                let u = self.adj[v][i].0;
+                if !queued[u] && self.gain[u] > 0 {
+                    queued[u] = true;
+                    queue.push(u);
+                }
+            }
+        }
+        flips
+    }
+
+    /* One matching round on the gain graph; returns the pairs flipped. */
+    fn pair_round(&mut self) -> usize {
+        let n = self.side.len();
+        /* the gain graph on its own endpoints only: the blossom solver
+         * pays per vertex */
+        let mut local = vec![NIL; n];
+        let mut ids = Vec::new();
+        let mut gain_edges = Vec::new();
+        for u in 0..n {
+            for &(v, _) in &self.adj[u] {
+                if u >= v { continue; }
+                let g = self.pair_gain(u, v);
+                if g <= 0 { continue; }
+                for &x in &[u, v] {
+                    if local[x] == NIL {
+                        local[x] = ids.len();
+                        ids.push(x);
+                    }
+                }
+                gain_edges.push((local[u], local[v], g));
+            }
+        }
+        if gain_edges.is_empty() {
+            return 0;
+        }
+        let mut flipped = 0;
+        for (a, b) in weighted_blossom::max_weight_matching(ids.len(), &gain_edges, false) {
+            let (u, v) = (ids[a], ids[b]);
+            if self.pair_gain(u, v) > 0 {
+                self.flip(u);
+                self.flip(v);
+                flipped += 1;
+            }
+        }
+        flipped
+    }
+}
+
+/* Local search from `side`; `pairs` enables the matching rounds. */
+pub fn local_search(n: usize, edges: &[(usize, usize, i64)], side: Vec<bool>, pairs: bool) -> Cut {
+    let mut s = Search::new(n, edges, side);
+    let start_weight = s.weight;
+    let mut single_flips = s.descend();
+    let single_weight = s.weight;
+    let mut pair_flips = 0;
+    let mut rounds = 0;
+    if pairs {
+        loop {
+            let flipped = s.pair_round();
+            if flipped == 0 { break; }
+            pair_flips += flipped;
+            rounds += 1;
+            single_flips += s.descend();
+        }
+    }
+    Cut { side: s.side, weight: s.weight, start_weight, single_weight, single_flips, pair_flips, rounds }
+}
+
+/* The best of `restarts` local searches from seeded random sides, and the
+ * final weight of each. */
+pub fn max_cut(
+    n: usize, edges: &[(usize, usize, i64)], restarts: usize, seed: u64, pairs: bool,
+) -> (Cut, Vec<i64>) {
+    let mut rng = Rng::new(seed);
+    let mut best: Option<Cut> = None;
+    let mut weights = Vec::with_capacity(restarts);
+    for _ in 0..restarts.max(1) {
+        let side: Vec<bool> = (0..n).map(|_| rng.below(2) == 1).collect();
+        let cut = local_search(n, edges, side, pairs);
+        weights.push(cut.weight);
+        if best.as_ref().is_none_or(|b| cut.weight > b.weight) {
+            best = Some(cut);
+        }
+    }
+    (best.unwrap(), weights)
+}
+
+/* Weight of the edges across `side`. */
+pub fn cut_weight(edges: &[(usize, usize, i64)], side: &[bool]) -> i64 {
+    edges.iter()
+        .filter(|&&(u, v, _)| u < side.len() && v < side.len() && side[u] != side[v])
+        .map(|e| e.2)
+        .sum()
+}
+
+/* Total positive edge weight (self-loops excluded): no cut exceeds it. */
+pub fn upper_bound(n: usize, edges: &[(usize, usize, i64)]) -> i64 {
+    edges.iter().filter(|&&(u, v, w)| u < n && v < n && u != v && w > 0).map(|e| e.2).sum()
+}
+
+/* Problems with `cut` for (n, edges): side length, reported weight, and
+ * that no single flip gains (empty if none). */
+pub fn check_cut(n: usize, edges: &[(usize, usize, i64)], cut: &Cut) -> Vec<String> {
+    let mut errors = Vec::new();
+    if cut.side.len() != n {
+        errors.push(format!("Cut has {} sides for {} vertices!", cut.side.len(), n));
+        return errors;
+    }
+    let weight = cut_weight(edges, &cut.side);
+    if weight != cut.weight {
+        errors.push(format!("Cut edges weigh {}, reported {}!", weight, cut.weight));
+    }
+    let mut gain = vec![0i64; n];
+    for &(u, v, w) in edges {
+        if u >= n || v >= n || u == v { continue; }
+        let d = if cut.side[u] == cut.side[v] { w } else { -w };
+        gain[u] += d;
+        gain[v] += d;
+    }
+    if let Some(v) = (0..n).find(|&v| gain[v] > 0) {
+        errors.push(format!("Flipping vertex {} gains {}!", v, gain[v]));
+    }
+    errors
+}
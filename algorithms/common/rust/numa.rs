@@ -0,0 +1,220 @@
+/*
+ * NUMA placement for the parallel phases (--numa).
+ *
+ * On a multi-socket machine the threads of hopcroft_karp --threads and
+ * gabow_simple --threads run wherever the scheduler puts them, and a
+ * graph built by the main thread sits in its socket's memory: every other
+ * socket reads it remotely. With --numa the threads are pinned, spread
+ * block-wise over the nodes, and the data each thread works on is
+ * allocated by a thread on its node. Linux places a page on the node of
+ * the CPU that first writes it (first touch), so that is where it stays;
+ * no libnuma or hwloc is needed, and the suite links no crates for them.
+ *
+ * Topology comes from the cpulist of each /sys/devices/system/node/nodeN.
+ * Without them (not Linux, or a kernel without NUMA) there is one node
+ * holding every CPU; where sched_setaffinity is missing pinning is skipped,
+ * and --numa changes only the layout.
+ *
+ * Csr is a graph partitioned into one CSR block of consecutive vertices
+ * per node, the blocks balanced by edges, each built on its node. Items
+ * (frontier or root vertices) are split by the block that owns them; a
+ * thread takes chunks of its own node's items first, then helps the
+ * others (chunks_on).
+ *
+ * Included via #[path = "../../common/rust/numa.rs"] mod numa;
+ */
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+pub const NUMA_USAGE: &str = "[--numa]";
+
+/* CPUs per node, nodes by id. */
+pub struct Topology {
+    pub nodes: Vec<Vec<usize>>,
+}
+
+/* "0-3,8-11" to [0, 1, 2, 3, 8, 9, 10, 11]. */
+fn parse_cpulist(s: &str) -> Vec<usize> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+        let mut ends = part.splitn(2, '-').map(|x| x.trim().parse::<usize>());
+        match (ends.next(), ends.next()) {
+            (Some(Ok(a)), None) => cpus.push(a),
+            (Some(Ok(a)), Some(Ok(b))) if a <= b => cpus.extend(a..=b),
+            _ => {}
+        }
+    }
+    cpus
+}
+
+impl Topology {
+    pub fn detect() -> Topology {
+        let mut nodes: Vec<(usize, Vec<usize>)> = Vec::new();
+        if let Ok(dir) = fs::read_dir("/sys/devices/system/node") {
+            for entry in dir.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let id = match name.strip_prefix("node").and_then(|s| s.parse::<usize>().ok()) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                let cpus = fs::read_to_string(entry.path().join("cpulist")).map(|s| parse_cpulist(&s)).unwrap_or_default();
+                if !cpus.is_empty() {
+                    nodes.push((id, cpus));
+                }
+            }
+        }
+        nodes.sort();
+        if nodes.is_empty() {
+            let n = thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+            return Topology { nodes: vec![(0..n).collect()] };
+        }
+        Topology { nodes: nodes.into_iter().map(|(_, cpus)| cpus).collect() }
+    }
+
+    /* Node of thread t of `threads`: the threads in blocks, one per node,
+     * so that neighboring threads (and their chunks) share a socket. Nodes
+     * beyond the thread count get none. */
+    pub fn node_of(&self, t: usize, threads: usize) -> usize {
+        t * self.nodes.len().min(threads) / threads.max(1)
+    }
+
+    /* The nodes that get threads, as node_of deals them. */
+    pub fn used_nodes(&self, threads: usize) -> usize {
+        self.nodes.len().min(threads.max(1))
+    }
+
+    /* CPU of thread t: the node's CPUs in turn among its threads. */
+    pub fn cpu_of(&self, t: usize, threads: usize) -> usize {
+        let node = self.node_of(t, threads);
+        let first = (0..threads).position(|s| self.node_of(s, threads) == node).unwrap_or(0);
+        let cpus = &self.nodes[node];
+        cpus[(t - first) % cpus.len()]
+    }
+}
+
+#[cfg(target_os = "linux")]
+extern "C" {
+    fn sched_setaffinity(pid: i32, size: usize, mask: *const u64) -> i32;
+}
+
+/* Pins the calling thread to `cpu`; false where that is not possible. */
+pub fn pin(cpu: usize) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        let mut mask = [0u64; 16]; /* glibc's cpu_set_t: 1024 CPUs */
+        if cpu >= 64 * mask.len() { return false; }
+        mask[cpu / 64] |= 1 << (cpu % 64);
+        unsafe { sched_setaffinity(0, std::mem::size_of_val(&mask), mask.as_ptr()) == 0 }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = cpu;
+        false
+    }
+}
+
+/* An adjacency list split into one CSR block per node: block b holds the
+ * vertices first[b]..first[b + 1], start relative to its targets. */
+pub struct Csr {
+    first: Vec<usize>,
+    blocks: Vec<(Vec<usize>, Vec<usize>)>, /* (start, targets) */
+}
+
+impl Csr {
+    /* `adj` cut into `parts` blocks of about equal edge counts, block b
+     * copied by a thread pinned to a CPU of node b. */
+    pub fn build(topo: &Topology, adj: &[Vec<usize>], parts: usize) -> Csr {
+        let parts = parts.max(1);
+        let total: usize = adj.iter().map(|a| a.len() + 1).sum();
+        let mut first = vec![0];
+        let mut seen = 0;
+        for (u, a) in adj.iter().enumerate() {
+            seen += a.len() + 1;
+            if first.len() < parts && seen * parts >= total * first.len() {
+                first.push(u + 1);
+            }
+        }
+        while first.len() <= parts {
+            first.push(adj.len());
+        }
+        let blocks = thread::scope(|s| {
+            let handles: Vec<_> = (0..parts).map(|b| {
+                let (lo, hi) = (first[b], first[b + 1]);
+                let cpu = topo.nodes[b % topo.nodes.len()][0];
+                s.spawn(move || {
+                    pin(cpu);
+                    let mut start = Vec::with_capacity(hi - lo + 1);
+                    let mut targets = Vec::with_capacity(adj[lo..hi].iter().map(|a| a.len()).sum());
+                    start.push(0);
+                    for a in &adj[lo..hi] {
+                        targets.extend_from_slice(a);
+                        start.push(targets.len());
+                    }
+                    (start, targets)
+                })
+            }).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        Csr { first, blocks }
+    }
+
+    /* The block that holds vertex u. */
+    pub fn block_of(&self, u: usize) -> usize {
+        if self.blocks.len() == 1 { return 0; }
+        self.first.partition_point(|&f| f <= u) - 1
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn neighbors(&self, u: usize) -> &[usize] {
+        let b = self.block_of(u);
+        let (start, targets) = &self.blocks[b];
+        let i = u - self.first[b];
+        &targets[start[i]..start[i + 1]]
+    }
+
+    /* `items` split by the block of each; order kept within a block. */
+    pub fn split(&self, items: &[usize]) -> Vec<Vec<usize>> {
+        let mut out = vec![Vec::new(); self.blocks.len()];
+        for &u in items {
+            out[self.block_of(u)].push(u);
+        }
+        out
+    }
+}
+
+/* Runs `f` over the lists `by_node` (one per node, as Csr::split gives
+ * them) on `threads` threads pinned by `topo`, `chunk` items at a time: a
+ * thread drains its own node's list first, then the others in turn. */
+pub fn chunks_on<R: Send>(topo: &Topology, threads: usize, chunk: usize, by_node: &[Vec<usize>],
+                          f: &(dyn Fn(&[usize], &mut Vec<R>) + Sync)) -> Vec<R> {
+    let next: Vec<AtomicUsize> = by_node.iter().map(|_| AtomicUsize::new(0)).collect();
+    let next = &next;
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads).map(|t| {
+            let node = topo.node_of(t, threads) % by_node.len().max(1);
+            let cpu = topo.cpu_of(t, threads);
+            s.spawn(move || {
+                pin(cpu);
+                let mut out = Vec::new();
+                for k in 0..by_node.len() {
+                    let q = (node + k) % by_node.len();
+                    let items = &by_node[q];
+                    loop {
+                        let i = next[q].fetch_add(chunk, Ordering::Relaxed);
+                        if i >= items.len() { break; }
+                        f(&items[i..(i + chunk).min(items.len())], &mut out);
+                    }
+                }
+                out
+            })
+        }).collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    })
+}
@@ -0,0 +1,254 @@
+/*
+ * Vertex orderings applied as a relabeling before solving.
+ *
+ * The solvers scan vertices in id order (greedy initialization, forest
+ * roots, tie-breaking), so the labeling of an input steers how the
+ * matching grows. `peripheral` relabels each connected component in BFS
+ * layers started from a low-degree pseudo-peripheral vertex (George-Liu:
+ * restart from a minimum-degree vertex of the last layer while the
+ * eccentricity grows), lower degree first within each layer. The matching
+ * then grows inward from the periphery, the way a leaf-first greedy would,
+ * leaving fewer long augmenting paths for the late phases. Compare with
+ * --phase-stats.
+ *
+ * The other orderings are for memory locality: the solvers build their
+ * adjacency from the relabeled edges, so neighbors with nearby ids sit in
+ * nearby cache lines of the per-vertex arrays (mate, labels, levels).
+ * `bfs` numbers each component in plain BFS order from its lowest id.
+ * `rcm` is reverse Cuthill-McKee: BFS from a pseudo-peripheral vertex,
+ * each vertex's new neighbors by increasing degree, the component's order
+ * then reversed; it keeps the bandwidth (largest id gap on an edge) small.
+ * `degree` puts the vertices by decreasing degree, so that the hubs, which
+ * most edges touch, share a few cache lines.
+ *
+ * Usage: solve on relabeling.apply(edges), map back with restore(matching).
+ */
+
+#![allow(dead_code)]
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Reorder {
+    Natural,    /* input ids as given */
+    Peripheral, /* BFS layers from a low-degree pseudo-peripheral vertex */
+    Bfs,        /* BFS order per component from its lowest id */
+    Rcm,        /* reverse Cuthill-McKee */
+    Degree,     /* decreasing degree, ties by id */
+}
+
+impl Reorder {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "natural" => Ok(Reorder::Natural),
+            "peripheral" => Ok(Reorder::Peripheral),
+            "bfs" => Ok(Reorder::Bfs),
+            "rcm" => Ok(Reorder::Rcm),
+            "degree" => Ok(Reorder::Degree),
+            _ => Err(format!("unknown --reorder '{}' (expected natural, peripheral, bfs, rcm or degree)", s)),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Reorder::Natural => "natural",
+            Reorder::Peripheral => "peripheral",
+            Reorder::Bfs => "bfs",
+            Reorder::Rcm => "rcm",
+            Reorder::Degree => "degree",
+        }
+    }
+
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        match super::args::value_of(args, "--reorder") {
+            Some(v) => Reorder::parse(v),
+            None => Ok(Reorder::Natural),
+        }
+    }
+}
+
+pub const REORDER_USAGE: &str = "[--reorder natural|peripheral|bfs|rcm|degree]";
+
+pub struct Relabeling {
+    pub new_of: Vec<usize>, /* original id -> solver id */
+    pub old_of: Vec<usize>, /* solver id -> original id */
+}
+
+impl Relabeling {
+    /* Out-of-range endpoints stay out of range, so solvers still drop them. */
+    pub fn apply(&self, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let n = self.new_of.len();
+        let id = |x: usize| if x < n { self.new_of[x] } else { x };
+        edges.iter().map(|&(u, v)| (id(u), id(v))).collect()
+    }
+
+    /* Matching back in original ids, as sorted (u, v) pairs with u < v. */
+    pub fn restore(&self, matching: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut out: Vec<(usize, usize)> = matching.iter()
+            .map(|&(u, v)| {
+                let (a, b) = (self.old_of[u], self.old_of[v]);
+                if a < b { (a, b) } else { (b, a) }
+            })
+            .collect();
+        out.sort_unstable();
+        out
+    }
+}
+
+/* None for the natural order (nothing to relabel). */
+pub fn relabeling(kind: Reorder, n: usize, edges: &[(usize, usize)]) -> Option<Relabeling> {
+    let old_of = match kind {
+        Reorder::Natural => return None,
+        Reorder::Peripheral => peripheral_order(n, edges),
+        Reorder::Bfs => bfs_order(n, edges),
+        Reorder::Rcm => rcm_order(n, edges),
+        Reorder::Degree => degree_order(n, edges),
+    };
+    let mut new_of = vec![0; n];
+    for (new, &old) in old_of.iter().enumerate() {
+        new_of[old] = new;
+    }
+    Some(Relabeling { new_of, old_of })
+}
+
+fn adjacency(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    adj
+}
+
+/* BFS from root; returns the visit order and sets dist for those vertices.
+ * The caller resets dist[v] = usize::MAX for the returned vertices. */
+fn bfs(adj: &[Vec<usize>], root: usize, dist: &mut [usize]) -> Vec<usize> {
+    let mut order = vec![root];
+    dist[root] = 0;
+    let mut qi = 0;
+    while qi < order.len() {
+        let v = order[qi];
+        qi += 1;
+        for &w in &adj[v] {
+            if dist[w] == usize::MAX {
+                dist[w] = dist[v] + 1;
+                order.push(w);
+            }
+        }
+    }
+    order
+}
+
+/* Minimum-degree vertex of the last BFS layer (smallest id on ties). */
+fn last_layer_min_degree(adj: &[Vec<usize>], order: &[usize], dist: &[usize]) -> (usize, usize) {
+    let ecc = dist[*order.last().unwrap()];
+    let x = order.iter().cloned()
+        .filter(|&v| dist[v] == ecc)
+        .min_by_key(|&v| (adj[v].len(), v))
+        .unwrap();
+    (x, ecc)
+}
+
+/* Pseudo-peripheral vertex of the component of s (George-Liu): from the
+ * component's minimum-degree vertex, restart at a minimum-degree vertex of
+ * the last BFS layer while the eccentricity grows. */
+fn pseudo_peripheral(adj: &[Vec<usize>], s: usize, dist: &mut [usize]) -> usize {
+    let comp = bfs(adj, s, dist);
+    let mut root = comp.iter().cloned().min_by_key(|&v| (adj[v].len(), v)).unwrap();
+    for &v in &comp { dist[v] = usize::MAX; }
+
+    let order = bfs(adj, root, dist);
+    let (mut cand, mut ecc) = last_layer_min_degree(adj, &order, dist);
+    for &v in &order { dist[v] = usize::MAX; }
+    loop {
+        let order = bfs(adj, cand, dist);
+        let (next, cand_ecc) = last_layer_min_degree(adj, &order, dist);
+        for &v in &order { dist[v] = usize::MAX; }
+        if cand_ecc <= ecc { break; }
+        root = cand;
+        ecc = cand_ecc;
+        cand = next;
+    }
+    root
+}
+
+fn peripheral_order(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let adj = adjacency(n, edges);
+    let mut dist = vec![usize::MAX; n];
+    let mut placed = vec![false; n];
+    let mut result = Vec::with_capacity(n);
+
+    for s in 0..n {
+        if placed[s] { continue; }
+        let root = pseudo_peripheral(&adj, s, &mut dist);
+
+        /* Layered BFS from root, lower degree first within each layer. */
+        let mut layer = vec![root];
+        placed[root] = true;
+        while !layer.is_empty() {
+            layer.sort_unstable_by_key(|&v| (adj[v].len(), v));
+            let mut next = Vec::new();
+            for &v in &layer {
+                for &w in &adj[v] {
+                    if !placed[w] {
+                        placed[w] = true;
+                        next.push(w);
+                    }
+                }
+            }
+            result.extend_from_slice(&layer);
+            layer = next;
+        }
+    }
+    result
+}
+
+fn bfs_order(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let adj = adjacency(n, edges);
+    let mut dist = vec![usize::MAX; n];
+    let mut result = Vec::with_capacity(n);
+    for s in 0..n {
+        if dist[s] == usize::MAX {
+            result.extend(bfs(&adj, s, &mut dist));
+        }
+    }
+    result
+}
+
+fn rcm_order(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let adj = adjacency(n, edges);
+    let mut dist = vec![usize::MAX; n];
+    let mut placed = vec![false; n];
+    let mut result = Vec::with_capacity(n);
+    let mut fresh = Vec::new();
+
+    for s in 0..n {
+        if placed[s] { continue; }
+        let root = pseudo_peripheral(&adj, s, &mut dist);
+
+        /* Cuthill-McKee: the queue is the order itself. */
+        let first = result.len();
+        result.push(root);
+        placed[root] = true;
+        let mut qi = first;
+        while qi < result.len() {
+            let v = result[qi];
+            qi += 1;
+            fresh.clear();
+            fresh.extend(adj[v].iter().cloned().filter(|&w| !placed[w]));
+            fresh.sort_unstable_by_key(|&w| (adj[w].len(), w));
+            for &w in &fresh { placed[w] = true; }
+            result.extend_from_slice(&fresh);
+        }
+        result[first..].reverse();
+    }
+    result
+}
+
+fn degree_order(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let adj = adjacency(n, edges);
+    let mut result: Vec<usize> = (0..n).collect();
+    result.sort_by_key(|&v| (std::cmp::Reverse(adj[v].len()), v));
+    result
+}
@@ -0,0 +1,604 @@
+/*
+ * Phase 0: exact matching of tree and unicyclic components before a
+ * general solver runs.
+ *
+ * A component with at most as many (distinct, non-loop) edges as vertices
+ * has at most one cycle and is solved in linear time:
+ *   - leaf rule: a degree-1 vertex is matched to its neighbour, both are
+ *     removed (some maximum matching uses that edge, in any graph);
+ *   - what survives in a unicyclic component is its cycle, or nothing;
+ *     pairs are taken around it, floor(k/2) for a cycle of k vertices.
+ * Only the other components, with two or more independent cycles, are
+ * handed to the general solver, relabeled 0..rest_n. Blossoms can occur
+ * only there.
+ *
+ * --crown reduces further before the components are split:
+ *   - the leaf rule is applied everywhere, which peels every component
+ *     down to its 2-core;
+ *   - crowns are matched and removed. A crown is an independent set I with
+ *     H = N(I) matched into I. Every edge at I u H has an end in H, so some
+ *     maximum matching is that H-I matching plus one of G - I - H.
+ * Crowns are searched as in Abu-Khzam et al. (2007): O, the vertices a
+ * maximal matching leaves exposed, is independent; a maximum matching of
+ * O against N(O) (Hopcroft-Karp) either covers N(O), and (O, N(O)) is a
+ * crown, or the alternating reach of its exposed O vertices is one. Peeling
+ * and crown search repeat until no crown is found.
+ *
+ * --fold instead applies the linear-time degree rules until none is left
+ * (Karp-Sipser 1981; Mertzios, Nichterlein and Niedermeier 2020):
+ *   - an isolated vertex is dropped, and the leaf rule matches a degree-1
+ *     vertex to its neighbour;
+ *   - a degree-2 vertex v with neighbours u, w is folded: v goes, u and w
+ *     merge into one vertex x adjacent to N(u) u N(w) - {v}, and
+ *     nu(G) = nu(G') + 1. If x is matched to y, y is a neighbour of u (or
+ *     w) and the pairs are uy and vw (or wy and uv); if x is exposed, uv.
+ * Folding repeatedly contracts degree-2 paths. Every vertex left has degree
+ * 3 or more. A folded vertex stands for an odd set of original vertices,
+ * and each edge keeps an original edge as its witness, so merge unfolds
+ * the solver's matching into one of the original graph.
+ *
+ * Included via #[path = "../../common/rust/phase0.rs"] mod phase0;
+ * next to args.rs and hopcroft_karp.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use super::args;
+use super::hopcroft_karp::HopcroftKarp;
+
+const NIL: usize = usize::MAX;
+
+pub const PHASE0_USAGE: &str = "[--phase0|--crown|--fold]";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Off,
+    Components, /* --phase0: tree and unicyclic components */
+    Crown,      /* --crown: 2-core peeling and crowns, then components */
+    Fold,       /* --fold: degree-0, degree-1 and degree-2 rules */
+}
+
+impl Mode {
+    pub fn from_args(args: &[String]) -> Mode {
+        if args::has_flag(args, "--fold") {
+            Mode::Fold
+        } else if args::has_flag(args, "--crown") {
+            Mode::Crown
+        } else if args::has_flag(args, "--phase0") {
+            Mode::Components
+        } else {
+            Mode::Off
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Stats {
+    pub trees: usize,        /* tree components with at least one edge */
+    pub unicyclic: usize,
+    pub solved_vertices: usize,
+    pub solved_pairs: usize,
+    pub rest_components: usize,
+    pub peeled_pairs: usize, /* --crown, --fold: leaf rule pairs */
+    pub crowns: usize,
+    pub crown_pairs: usize,
+    pub folds: usize,        /* --fold: degree-2 folds, one pair each */
+    pub input_edges: usize,  /* simple edges of the input */
+}
+
+pub struct Split {
+    pub matching: Vec<(usize, usize)>, /* exact part, original ids */
+    pub rest_n: usize,
+    pub rest_edges: Vec<(usize, usize)>, /* simple, in rest ids */
+    pub rest_ids: Vec<usize>,            /* rest id -> original id */
+    pub stats: Stats,
+    pub mode: Mode,
+    pub folds: Option<Folds>,            /* --fold: how to unfold the rest */
+}
+
+/* The fold forest of --fold. Nodes 0..n are the original vertices, node
+ * n + k is fold k, with children (u, v, w) and witness edges uv and vw
+ * (original ids, first end in the first child). Each subtree is a range
+ * [tin, tout) of a DFS order. */
+pub struct Folds {
+    n: usize,
+    children: Vec<[usize; 3]>,
+    witness: Vec<[(usize, usize); 2]>,
+    tin: Vec<usize>,
+    tout: Vec<usize>,
+    rest_node: Vec<usize>,                          /* rest id -> forest node */
+    rest_witness: HashMap<(usize, usize), (usize, usize)>, /* (a < b) -> ends in a, b */
+}
+
+impl Folds {
+    fn contains(&self, node: usize, orig: usize) -> bool {
+        self.tin[node] <= self.tin[orig] && self.tin[orig] < self.tout[node]
+    }
+
+    /* Pairs matching every original vertex of `node` except `port` (all
+     * but one if port is NIL). */
+    fn unfold(&self, node: usize, port: usize, out: &mut Vec<(usize, usize)>) {
+        let mut stack = vec![(node, port)];
+        while let Some((z, a)) = stack.pop() {
+            if z < self.n { continue; }
+            let [u, v, w] = self.children[z - self.n];
+            let [(p, q), (r, s)] = self.witness[z - self.n];
+            if a != NIL && self.contains(w, a) {
+                out.push((p.min(q), p.max(q)));
+                stack.extend_from_slice(&[(u, p), (v, q), (w, a)]);
+            } else {
+                out.push((r.min(s), r.max(s)));
+                stack.extend_from_slice(&[(u, a), (v, r), (w, s)]);
+            }
+        }
+    }
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { 100.0 * part as f64 / whole as f64 }
+}
+
+impl Split {
+    /* One-line report for the binaries and combi. */
+    pub fn summary(&self) -> String {
+        let st = &self.stats;
+        let n = st.solved_vertices + self.rest_n;
+        let exact = if self.mode == Mode::Fold {
+            format!("leaf rule {} pairs, {} degree-2 folds, {} pairs fixed in all",
+                    st.peeled_pairs, st.folds, st.solved_pairs)
+        } else if self.mode == Mode::Crown {
+            format!("2-core peeling {} pairs, {} crowns {} pairs, {} cycles solved exactly ({} vertices, {} pairs)",
+                    st.peeled_pairs, st.crowns, st.crown_pairs, st.unicyclic, st.solved_vertices, st.solved_pairs)
+        } else {
+            format!("{} trees, {} unicyclic components solved exactly ({} vertices, {} pairs)",
+                    st.trees, st.unicyclic, st.solved_vertices, st.solved_pairs)
+        };
+        format!("{}; {} components ({} vertices, {} edges) left to the solver; \
+                 {:.1}% of vertices and {:.1}% of edges removed",
+                exact, st.rest_components, self.rest_n, self.rest_edges.len(),
+                percent(st.solved_vertices, n), percent(st.input_edges - self.rest_edges.len(), st.input_edges))
+    }
+
+    /* The exact pairs plus a matching of the rest graph, original ids,
+     * sorted with u < v. */
+    pub fn merge(&self, rest_matching: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut m = self.matching.clone();
+        if let Some(ref f) = self.folds {
+            let mut matched = vec![false; self.rest_n];
+            for &(a, b) in rest_matching {
+                let (p, q) = if a < b { f.rest_witness[&(a, b)] } else { let (q, p) = f.rest_witness[&(b, a)]; (p, q) };
+                m.push((p.min(q), p.max(q)));
+                f.unfold(f.rest_node[a], p, &mut m);
+                f.unfold(f.rest_node[b], q, &mut m);
+                matched[a] = true;
+                matched[b] = true;
+            }
+            for r in (0..self.rest_n).filter(|&r| !matched[r]) {
+                f.unfold(f.rest_node[r], NIL, &mut m);
+            }
+            m.sort_unstable();
+            return m;
+        }
+        for &(u, v) in rest_matching {
+            let (a, b) = (self.rest_ids[u], self.rest_ids[v]);
+            m.push((a.min(b), a.max(b)));
+        }
+        m.sort_unstable();
+        m
+    }
+}
+
+/* Sorted simple adjacency, loops and out-of-range ends dropped; also what
+ * the binaries validate a merged matching against. */
+pub fn simple_adjacency(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    adj
+}
+
+/* None for Mode::Off. */
+pub fn run(mode: Mode, n: usize, edges: &[(usize, usize)]) -> Option<Split> {
+    match mode {
+        Mode::Off => None,
+        Mode::Components => Some(split(n, edges)),
+        Mode::Crown => Some(reduce(n, edges)),
+        Mode::Fold => Some(fold(n, edges)),
+    }
+}
+
+pub fn split(n: usize, edges: &[(usize, usize)]) -> Split {
+    split_adjacency(simple_adjacency(n, edges))
+}
+
+fn split_adjacency(adj: Vec<Vec<usize>>) -> Split {
+    let n = adj.len();
+
+    /* Components by BFS; component ids in order of their lowest vertex. */
+    let mut comp = vec![NIL; n];
+    let mut sizes: Vec<(usize, usize)> = Vec::new(); /* (vertices, edge ends) */
+    let mut queue = Vec::new();
+    for s in 0..n {
+        if comp[s] != NIL { continue; }
+        let c = sizes.len();
+        comp[s] = c;
+        queue.clear();
+        queue.push(s);
+        let mut i = 0;
+        let (mut nv, mut ends) = (0, 0);
+        while i < queue.len() {
+            let v = queue[i];
+            i += 1;
+            nv += 1;
+            ends += adj[v].len();
+            for &w in &adj[v] {
+                if comp[w] == NIL {
+                    comp[w] = c;
+                    queue.push(w);
+                }
+            }
+        }
+        sizes.push((nv, ends / 2));
+    }
+    let exact: Vec<bool> = sizes.iter().map(|&(nv, m)| m <= nv).collect();
+
+    let mut stats = Stats { input_edges: sizes.iter().map(|&(_, m)| m).sum(), ..Default::default() };
+    for &(nv, m) in &sizes {
+        if m > nv {
+            stats.rest_components += 1;
+        } else {
+            stats.solved_vertices += nv;
+            if m == nv { stats.unicyclic += 1; } else if m > 0 { stats.trees += 1; }
+        }
+    }
+
+    /* Leaf rule on the exact components. */
+    let mut removed: Vec<bool> = (0..n).map(|v| !exact[comp[v]]).collect();
+    let mut deg: Vec<usize> = adj.iter().map(|a| a.len()).collect();
+    let mut matching = Vec::new();
+    let mut leaves: Vec<usize> = (0..n).filter(|&v| !removed[v] && deg[v] == 1).collect();
+    let remove = |v: usize, removed: &mut Vec<bool>, deg: &mut Vec<usize>, leaves: &mut Vec<usize>| {
+        removed[v] = true;
+        for &w in &adj[v] {
+            if !removed[w] {
+                deg[w] -= 1;
+                if deg[w] == 1 { leaves.push(w); }
+            }
+        }
+    };
+    while let Some(v) = leaves.pop() {
+        if removed[v] || deg[v] != 1 { continue; }
+        let u = adj[v].iter().cloned().find(|&w| !removed[w]).unwrap();
+        matching.push((v.min(u), v.max(u)));
+        removed[v] = true;
+        remove(u, &mut removed, &mut deg, &mut leaves);
+    }
+    /* Survivors of degree 2 are the cycles; the rest have degree 0. */
+    for s in 0..n {
+        if removed[s] || deg[s] != 2 { continue; }
+        let mut cycle = vec![s];
+        removed[s] = true;
+        let mut v = s;
+        while let Some(w) = adj[v].iter().cloned().find(|&w| !removed[w] && deg[w] == 2) {
+            removed[w] = true;
+            cycle.push(w);
+            v = w;
+        }
+        for p in cycle.chunks(2) {
+            if p.len() == 2 { matching.push((p[0].min(p[1]), p[0].max(p[1]))); }
+        }
+    }
+    matching.sort_unstable();
+    stats.solved_pairs = matching.len();
+
+    /* The rest graph, relabeled in vertex order. */
+    let mut rest_id = vec![NIL; n];
+    let mut rest_ids = Vec::new();
+    for v in 0..n {
+        if !exact[comp[v]] {
+            rest_id[v] = rest_ids.len();
+            rest_ids.push(v);
+        }
+    }
+    let mut rest_edges = Vec::new();
+    for &v in &rest_ids {
+        for &w in &adj[v] {
+            if v < w { rest_edges.push((rest_id[v], rest_id[w])); }
+        }
+    }
+    Split { matching, rest_n: rest_ids.len(), rest_edges, rest_ids, stats, mode: Mode::Components, folds: None }
+}
+
+fn kill(v: usize, adj: &[Vec<usize>], alive: &mut [bool], deg: &mut [usize], leaves: &mut Vec<usize>) {
+    alive[v] = false;
+    for &w in &adj[v] {
+        if alive[w] {
+            deg[w] -= 1;
+            if deg[w] == 1 { leaves.push(w); }
+        }
+    }
+}
+
+/* A crown, as the pairs matching H into I and the vertices of I and H. */
+type Crown = (Vec<(usize, usize)>, Vec<usize>);
+
+/* A crown of the alive subgraph; None if the search finds none. */
+fn find_crown(adj: &[Vec<usize>], alive: &[bool], deg: &[usize]) -> Option<Crown> {
+    let n = adj.len();
+    let mut mate = vec![NIL; n];
+    for v in 0..n {
+        if !alive[v] || mate[v] != NIL { continue; }
+        if let Some(w) = adj[v].iter().cloned().find(|&w| alive[w] && mate[w] == NIL) {
+            mate[v] = w;
+            mate[w] = v;
+        }
+    }
+    let outside: Vec<usize> = (0..n).filter(|&v| alive[v] && mate[v] == NIL && deg[v] > 0).collect();
+    if outside.is_empty() { return None; }
+    let mut right_id = vec![NIL; n];
+    let mut right = Vec::new();
+    let mut badj = vec![Vec::new(); outside.len()];
+    let mut bedges = Vec::new();
+    for (i, &v) in outside.iter().enumerate() {
+        for &w in &adj[v] {
+            if !alive[w] { continue; }
+            if right_id[w] == NIL {
+                right_id[w] = right.len();
+                right.push(w);
+            }
+            badj[i].push(right_id[w]);
+            bedges.push((i, right_id[w]));
+        }
+    }
+    let m2 = HopcroftKarp::new(outside.len(), right.len(), &bedges).maximum_matching(0);
+    let mut mate_l = vec![NIL; outside.len()];
+    let mut mate_r = vec![NIL; right.len()];
+    for &(i, j) in &m2 {
+        mate_l[i] = j;
+        mate_r[j] = i;
+    }
+    let (mut in_i, mut in_h) = (vec![false; outside.len()], vec![false; right.len()]);
+    if m2.len() == right.len() {
+        /* N(O) is covered: (O, N(O)) itself */
+        in_i.fill(true);
+        in_h.fill(true);
+    } else {
+        /* Alternating reach of the exposed O vertices. Each H vertex it
+         * meets is matched, or there would be an augmenting path. */
+        let mut queue: Vec<usize> = (0..outside.len()).filter(|&i| mate_l[i] == NIL).collect();
+        if queue.is_empty() { return None; }
+        for &i in &queue { in_i[i] = true; }
+        let mut k = 0;
+        while k < queue.len() {
+            let i = queue[k];
+            k += 1;
+            for &j in &badj[i] {
+                if in_h[j] { continue; }
+                in_h[j] = true;
+                let l = mate_r[j];
+                if !in_i[l] {
+                    in_i[l] = true;
+                    queue.push(l);
+                }
+            }
+        }
+    }
+    let mut pairs = Vec::new();
+    let mut vertices = Vec::new();
+    for j in 0..right.len() {
+        if in_h[j] {
+            let (h, i) = (right[j], outside[mate_r[j]]);
+            pairs.push((h.min(i), h.max(i)));
+            vertices.push(h);
+        }
+    }
+    for i in 0..outside.len() {
+        if in_i[i] { vertices.push(outside[i]); }
+    }
+    Some((pairs, vertices))
+}
+
+/* --crown: leaf rule and crowns until neither applies, then the component
+ * split on what is left. */
+pub fn reduce(n: usize, edges: &[(usize, usize)]) -> Split {
+    let adj = simple_adjacency(n, edges);
+    let input_edges = adj.iter().map(|a| a.len()).sum::<usize>() / 2;
+    let mut alive = vec![true; n];
+    let mut deg: Vec<usize> = adj.iter().map(|a| a.len()).collect();
+    let mut leaves: Vec<usize> = (0..n).filter(|&v| deg[v] == 1).collect();
+    let mut matching = Vec::new();
+    let (mut peeled_pairs, mut crowns, mut crown_pairs) = (0, 0, 0);
+    loop {
+        while let Some(v) = leaves.pop() {
+            if !alive[v] || deg[v] != 1 { continue; }
+            let u = adj[v].iter().cloned().find(|&w| alive[w]).unwrap();
+            matching.push((v.min(u), v.max(u)));
+            peeled_pairs += 1;
+            kill(v, &adj, &mut alive, &mut deg, &mut leaves);
+            kill(u, &adj, &mut alive, &mut deg, &mut leaves);
+        }
+        let (pairs, vertices) = match find_crown(&adj, &alive, &deg) {
+            Some(c) => c,
+            None => break,
+        };
+        crowns += 1;
+        crown_pairs += pairs.len();
+        matching.extend(pairs);
+        for v in vertices {
+            kill(v, &adj, &mut alive, &mut deg, &mut leaves);
+        }
+    }
+
+    /* Components of what is left: cycles are solved, the rest handed on. */
+    let kept: Vec<usize> = (0..n).filter(|&v| alive[v] && deg[v] > 0).collect();
+    let mut kept_id = vec![NIL; n];
+    for (i, &v) in kept.iter().enumerate() { kept_id[v] = i; }
+    let sub: Vec<Vec<usize>> = kept.iter()
+        .map(|&v| adj[v].iter().filter(|&&w| alive[w]).map(|&w| kept_id[w]).collect())
+        .collect();
+    let inner = split_adjacency(sub);
+    for &(a, b) in &inner.matching {
+        let (u, v) = (kept[a], kept[b]);
+        matching.push((u.min(v), u.max(v)));
+    }
+    matching.sort_unstable();
+    let rest_ids: Vec<usize> = inner.rest_ids.iter().map(|&r| kept[r]).collect();
+    let stats = Stats {
+        trees: 0,
+        unicyclic: inner.stats.unicyclic,
+        solved_vertices: n - rest_ids.len(),
+        solved_pairs: matching.len(),
+        rest_components: inner.stats.rest_components,
+        peeled_pairs,
+        crowns,
+        crown_pairs,
+        folds: 0,
+        input_edges,
+    };
+    Split { matching, rest_n: rest_ids.len(), rest_edges: inner.rest_edges, rest_ids, stats, mode: Mode::Crown, folds: None }
+}
+
+/* Drop v from the graph; neighbours that fall to degree 2 or less are
+ * queued. */
+fn drop_vertex(v: usize, adj: &mut [HashMap<usize, (usize, usize)>], alive: &mut [bool], queue: &mut Vec<usize>) {
+    alive[v] = false;
+    let mut nbrs: Vec<usize> = adj[v].drain().map(|(y, _)| y).collect();
+    nbrs.sort_unstable();
+    for y in nbrs {
+        adj[y].remove(&v);
+        if adj[y].len() <= 2 { queue.push(y); }
+    }
+}
+
+/* --fold: isolated vertices, the leaf rule and degree-2 folds until none
+ * applies; what is left goes to the solver with its folds recorded. */
+pub fn fold(n: usize, edges: &[(usize, usize)]) -> Split {
+    let simple = simple_adjacency(n, edges);
+    let input_edges = simple.iter().map(|a| a.len()).sum::<usize>() / 2;
+    /* adj[a][b] = witness edge (end in a's set, end in b's set) */
+    let mut adj: Vec<HashMap<usize, (usize, usize)>> = simple.iter().enumerate()
+        .map(|(a, list)| list.iter().map(|&b| (b, (a, b))).collect())
+        .collect();
+    drop(simple);
+    let mut alive = vec![true; n];
+    let mut node: Vec<usize> = (0..n).collect(); /* vertex -> forest node */
+    let mut children: Vec<[usize; 3]> = Vec::new();
+    let mut witness: Vec<[(usize, usize); 2]> = Vec::new();
+    let mut pending: Vec<(usize, usize)> = Vec::new(); /* (forest node, port) to unfold */
+    let mut matching = Vec::new();
+    let mut peeled_pairs = 0;
+    let mut queue: Vec<usize> = (0..n).rev().filter(|&v| adj[v].len() <= 2).collect();
+    while let Some(v) = queue.pop() {
+        if !alive[v] { continue; }
+        match adj[v].len() {
+            0 => {
+                alive[v] = false;
+                pending.push((node[v], NIL));
+            }
+            1 => {
+                let (&u, &(p, q)) = adj[v].iter().next().unwrap();
+                matching.push((p.min(q), p.max(q)));
+                pending.push((node[v], p));
+                pending.push((node[u], q));
+                peeled_pairs += 1;
+                drop_vertex(v, &mut adj, &mut alive, &mut queue);
+                drop_vertex(u, &mut adj, &mut alive, &mut queue);
+            }
+            2 => {
+                let mut nbrs: Vec<usize> = adj[v].keys().cloned().collect();
+                nbrs.sort_unstable();
+                let (u, w) = (nbrs[0], nbrs[1]);
+                let (vu, vw) = (adj[v][&u], adj[v][&w]);
+                children.push([node[u], node[v], node[w]]);
+                witness.push([(vu.1, vu.0), vw]);
+                adj[v].clear();
+                alive[v] = false;
+                adj[u].remove(&v);
+                adj[w].remove(&v);
+                /* merge the smaller side into the larger */
+                let (keep, gone) = if adj[u].len() >= adj[w].len() { (u, w) } else { (w, u) };
+                let mut moved: Vec<(usize, (usize, usize))> = adj[gone].drain().collect();
+                moved.sort_unstable();
+                alive[gone] = false;
+                for (y, wit) in moved {
+                    adj[y].remove(&gone);
+                    if y != keep && !adj[keep].contains_key(&y) {
+                        adj[keep].insert(y, wit);
+                        adj[y].insert(keep, (wit.1, wit.0));
+                    }
+                    if y != keep && adj[y].len() <= 2 { queue.push(y); }
+                }
+                node[keep] = n + children.len() - 1;
+                if adj[keep].len() <= 2 { queue.push(keep); }
+            }
+            _ => {}
+        }
+    }
+
+    /* DFS ranges over the fold forest; roots are the nodes nobody folded. */
+    let total = n + children.len();
+    let mut has_parent = vec![false; total];
+    for c in &children {
+        for &x in c { has_parent[x] = true; }
+    }
+    let (mut tin, mut tout) = (vec![0usize; total], vec![0usize; total]);
+    let mut clock = 0;
+    let mut stack: Vec<(usize, bool)> = Vec::new();
+    for root in (0..total).filter(|&x| !has_parent[x]) {
+        stack.push((root, false));
+        while let Some((x, done)) = stack.pop() {
+            if done {
+                tout[x] = clock;
+                continue;
+            }
+            tin[x] = clock;
+            clock += 1;
+            stack.push((x, true));
+            if x >= n {
+                for &c in children[x - n].iter().rev() { stack.push((c, false)); }
+            }
+        }
+    }
+    let folds_done = children.len();
+    let mut f = Folds { n, children, witness, tin, tout, rest_node: Vec::new(), rest_witness: HashMap::new() };
+    for (z, port) in pending {
+        f.unfold(z, port, &mut matching);
+    }
+    matching.sort_unstable();
+
+    /* The rest: every vertex has degree 3 or more. */
+    let kept: Vec<usize> = (0..n).filter(|&v| alive[v]).collect();
+    let mut kept_id = vec![NIL; n];
+    for (i, &v) in kept.iter().enumerate() { kept_id[v] = i; }
+    let sub: Vec<Vec<usize>> = kept.iter().map(|&v| {
+        let mut list: Vec<usize> = adj[v].keys().map(|&y| kept_id[y]).collect();
+        list.sort_unstable();
+        list
+    }).collect();
+    let inner = split_adjacency(sub);
+    f.rest_node = kept.iter().map(|&v| node[v]).collect();
+    for (a, &v) in kept.iter().enumerate() {
+        for (&y, &wit) in &adj[v] {
+            if a < kept_id[y] { f.rest_witness.insert((a, kept_id[y]), wit); }
+        }
+    }
+    /* a rest vertex standing for 2k + 1 originals gives k pairs on unfolding */
+    let rest_fold_pairs: usize = f.rest_node.iter().map(|&z| (f.tout[z] - f.tin[z] - 1) / 3).sum();
+    let stats = Stats {
+        solved_vertices: n - kept.len(),
+        solved_pairs: matching.len() + rest_fold_pairs,
+        rest_components: inner.stats.rest_components,
+        peeled_pairs,
+        folds: folds_done,
+        input_edges,
+        ..Stats::default()
+    };
+    Split { matching, rest_n: kept.len(), rest_edges: inner.rest_edges, rest_ids: kept, stats, mode: Mode::Fold, folds: Some(f) }
+}
@@ -0,0 +1,30 @@
+/*
+ * Per-phase statistics of the phase-structured solvers (MV, Gabow
+ * optimized): each phase augments along a maximal set of shortest
+ * augmenting paths, all of the same length.
+ *
+ * A phase is recorded as (path length in edges, augmentations).
+ */
+
+#![allow(dead_code)]
+
+use std::io::{self, Write};
+
+pub const PHASE_STATS_USAGE: &str = "[--phase-stats]";
+
+pub fn write_phase_stats(out: &mut dyn Write, phases: &[(usize, usize)]) -> io::Result<()> {
+    let total: usize = phases.iter().map(|p| p.1).sum();
+    let weighted: usize = phases.iter().map(|p| p.0 * p.1).sum();
+    writeln!(out, "=== Phase Statistics ===")?;
+    writeln!(out, "Phases: {}", phases.len())?;
+    writeln!(out, "  {:>5}  {:>11}  {:>13}", "phase", "path length", "augmentations")?;
+    for (i, &(len, aug)) in phases.iter().enumerate() {
+        writeln!(out, "  {:>5}  {:>11}  {:>13}", i + 1, len, aug)?;
+    }
+    writeln!(out, "Augmentations: {}", total)?;
+    if total > 0 {
+        writeln!(out, "Mean path length: {:.2}", weighted as f64 / total as f64)?;
+        writeln!(out, "Longest path: {}", phases.iter().map(|p| p.0).max().unwrap_or(0))?;
+    }
+    writeln!(out, "========================\n")
+}
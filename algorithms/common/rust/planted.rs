@@ -0,0 +1,166 @@
+/*
+ * Weighted instances with a planted optimal matching, for checking
+ * weighted solvers against a known answer at any size.
+ *
+ * A random perfect matching M is planted on n vertices (or n/2 + n/2 for
+ * bipartite instances), together with noise edges: random non-M pairs,
+ * about `degree` per vertex. Weights come from vertex potentials y_v,
+ * drawn from 1..=max_potential:
+ *   w(uv) = y_u + y_v            for uv in M,
+ *   w(uv) = y_u + y_v - s_uv     for noise, slack s_uv in gap..=gap+spread,
+ * plus one offset c >= 0 on every edge that keeps all weights >= 1.
+ *
+ * Why M wins: for any matching N, w(N) <= sum of y over the vertices N
+ * covers, minus the slack of its noise edges, plus c|N|; M reaches
+ * sum(y) + c n/2. So a matching other than M is lighter by at least its
+ * noise slack (>= gap) or, if it is a proper part of M, by the two
+ * potentials and offset of an edge it leaves out (>= 2 + c). With gap >= 1,
+ * M is the unique maximum-weight matching (and the unique heaviest
+ * perfect one); `margin` is the smaller of the two amounts. With gap 0 it
+ * is a maximum-weight matching, not necessarily the only one.
+ *
+ * Vertex labels are shuffled and so is the edge order, so M is not visible
+ * in the ids.
+ *
+ * Included via #[path = "../../common/rust/planted.rs"] mod planted;
+ * next to rng.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+
+use super::rng::Rng;
+
+/* Potentials, slacks and their sums stay far from i64 overflow. */
+const LIMIT: i64 = 1 << 40;
+
+pub struct PlantOptions {
+    pub n: usize,           /* vertices, even; bipartite: n/2 per side */
+    pub bipartite: bool,
+    pub degree: f64,        /* noise edges per vertex, on average */
+    pub max_potential: i64, /* y_v in 1..=max_potential */
+    pub gap: i64,           /* least slack of a noise edge */
+    pub spread: i64,        /* slacks in gap..=gap+spread */
+}
+
+impl Default for PlantOptions {
+    fn default() -> Self {
+        PlantOptions { n: 1000, bipartite: false, degree: 4.0, max_potential: 1000, gap: 1, spread: 100 }
+    }
+}
+
+pub struct Planted {
+    pub left: usize,  /* bipartite: left side size; general: n */
+    pub right: usize, /* bipartite: right side size; general: 0 */
+    /* general: (u, v, w) over 0..n; bipartite: left id, right id, w */
+    pub edges: Vec<(usize, usize, i64)>,
+    pub matching: Vec<(usize, usize)>, /* sorted; u < v when general */
+    pub weight: i64,
+    pub margin: i64, /* any other matching is at least this much lighter */
+}
+
+fn validate(opts: &PlantOptions) -> Result<(), String> {
+    if opts.n % 2 == 1 {
+        return Err(format!("n must be even for a perfect matching, got {}", opts.n));
+    }
+    if opts.degree.is_nan() || opts.degree < 0.0 {
+        return Err(format!("degree must be non-negative, got {}", opts.degree));
+    }
+    if opts.max_potential < 1 || opts.max_potential > LIMIT {
+        return Err(format!("max potential must be in 1..={}, got {}", LIMIT, opts.max_potential));
+    }
+    if opts.gap < 0 || opts.spread < 0 || opts.gap > LIMIT || opts.spread > LIMIT {
+        return Err(format!("gap and spread must be in 0..={}, got {} and {}", LIMIT, opts.gap, opts.spread));
+    }
+    Ok(())
+}
+
+pub fn plant(opts: &PlantOptions, rng: &mut Rng) -> Result<Planted, String> {
+    validate(opts)?;
+    let n = opts.n;
+    let k = n / 2;
+    /* Potentials of the left/general ids, then of the right ids. */
+    let (left, right) = if opts.bipartite { (k, k) } else { (n, 0) };
+    let potentials = |rng: &mut Rng, count: usize| -> Vec<i64> {
+        (0..count).map(|_| 1 + rng.below(opts.max_potential as u64) as i64).collect()
+    };
+    let y_left = potentials(rng, left);
+    let y_right = potentials(rng, right);
+
+    let mut matching: Vec<(usize, usize)> = Vec::with_capacity(k);
+    if opts.bipartite {
+        let mut sigma: Vec<usize> = (0..k).collect();
+        rng.shuffle(&mut sigma);
+        matching.extend((0..k).map(|u| (u, sigma[u])));
+    } else {
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+        for p in order.chunks(2) {
+            matching.push((p[0].min(p[1]), p[0].max(p[1])));
+        }
+        matching.sort_unstable();
+    }
+    let planted: BTreeSet<(usize, usize)> = matching.iter().cloned().collect();
+
+    /* Noise pairs: distinct, outside M. Sampled when sparse, taken from
+     * the full list when more than half of it is wanted. */
+    let available = if opts.bipartite { k * k - k } else { n * n.saturating_sub(1) / 2 - k };
+    let wanted = ((opts.degree * n as f64 / 2.0).round() as usize).min(available);
+    let pair = |rng: &mut Rng| -> (usize, usize) {
+        if opts.bipartite {
+            (rng.below(k as u64) as usize, rng.below(k as u64) as usize)
+        } else {
+            let u = rng.below(n as u64) as usize;
+            let v = rng.below(n as u64) as usize;
+            (u.min(v), u.max(v))
+        }
+    };
+    let mut noise: Vec<(usize, usize)> = Vec::with_capacity(wanted);
+    if 2 * wanted > available {
+        for u in 0..left {
+            let range = if opts.bipartite { 0..right } else { u + 1..n };
+            for v in range {
+                if !planted.contains(&(u, v)) { noise.push((u, v)); }
+            }
+        }
+        rng.shuffle(&mut noise);
+        noise.truncate(wanted);
+    } else {
+        let mut seen: BTreeSet<(usize, usize)> = BTreeSet::new();
+        while noise.len() < wanted {
+            let (u, v) = pair(rng);
+            if (!opts.bipartite && u == v) || planted.contains(&(u, v)) || !seen.insert((u, v)) {
+                continue;
+            }
+            noise.push((u, v));
+        }
+    }
+
+    let y = |u: usize, v: usize| y_left[u] + if opts.bipartite { y_right[v] } else { y_left[v] };
+    let mut edges: Vec<(usize, usize, i64)> = Vec::with_capacity(k + noise.len());
+    for &(u, v) in &matching {
+        edges.push((u, v, y(u, v)));
+    }
+    for &(u, v) in &noise {
+        let slack = opts.gap + rng.below(opts.spread as u64 + 1) as i64;
+        edges.push((u, v, y(u, v) - slack));
+    }
+    let offset = edges.iter().map(|e| e.2).min().map_or(0, |w| (1 - w).max(0));
+    for e in &mut edges {
+        e.2 += offset;
+    }
+    /* Shuffle the lines, and the endpoints within a general-graph line. */
+    if !opts.bipartite {
+        for e in &mut edges {
+            if rng.below(2) == 1 { std::mem::swap(&mut e.0, &mut e.1); }
+        }
+    }
+    rng.shuffle(&mut edges);
+
+    let weight = matching.iter().map(|&(u, v)| y(u, v) + offset).sum();
+    let y_min = y_left.iter().chain(y_right.iter()).cloned().min().unwrap_or(0);
+    let cut = 2 * y_min + offset;
+    let margin = if noise.is_empty() { cut } else { cut.min(opts.gap) };
+    Ok(Planted { left, right, edges, matching, weight, margin })
+}
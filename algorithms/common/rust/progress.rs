@@ -0,0 +1,50 @@
+/*
+ * --progress: a line on stderr while a solver runs, for large instances
+ * that are otherwise silent for minutes.
+ *
+ * The solvers take a reporter (their `progress` field, or
+ * matchers::progress_on) and call it after every phase with
+ * (phase, augmentations so far, free vertices). A phase is a solver's
+ * outer round: one blossom search stage (Edmonds), one search round
+ * (Gabow simple), one set of shortest paths (Gabow optimized, MV,
+ * Hopcroft-Karp). Augmentations count from the start of the search, after
+ * the greedy start; free vertices include isolated ones.
+ *
+ * report() is the reporter behind --progress. It prints the first call,
+ * then at most one line a second, so a solver with a phase per
+ * augmentation is not slowed down by its output.
+ *
+ * Included via #[path = "../../common/rust/progress.rs"] mod progress;
+ */
+
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+pub const PROGRESS_USAGE: &str = "[--progress]";
+
+/* Milliseconds between printed lines. */
+const INTERVAL_MS: u64 = 1000;
+
+static START: OnceLock<Instant> = OnceLock::new();
+/* 1 + the time of the last printed line in ms; 0 before the first. */
+static LAST: AtomicU64 = AtomicU64::new(0);
+
+/* Times the lines from now instead of from the first report. */
+pub fn start() {
+    let _ = START.set(Instant::now());
+}
+
+pub fn report(phase: usize, augmentations: usize, free: usize) {
+    let elapsed = START.get_or_init(Instant::now).elapsed();
+    let now = elapsed.as_millis() as u64 + 1;
+    let last = LAST.load(Ordering::Relaxed);
+    if last != 0 && now < last + INTERVAL_MS {
+        return;
+    }
+    LAST.store(now, Ordering::Relaxed);
+    eprintln!("Progress: phase {}, {} augmentations, {} free vertices ({:.1} s)",
+              phase, augmentations, free, elapsed.as_secs_f64());
+}
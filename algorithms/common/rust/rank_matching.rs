@@ -0,0 +1,345 @@
+/*
+ * Rank-maximal and popular matchings: one-sided preferences, as in house
+ * allocation or student-project allocation.
+ *
+ * Applicants rank posts, possibly with ties; posts have capacities and no
+ * preferences. The rank of an edge is the position of its tie group in the
+ * applicant's list (1 = first choice).
+ *
+ * Rank-maximal (Irving, Kavitha, Mehlhorn, Michail, Paluch 2006): the
+ * signature (s1, s2, ...), s_i = pairs of rank i, is lexicographically
+ * maximum. Staged Hopcroft-Karp: M_1 is a maximum matching of the rank-1
+ * edges; before rank i+1 edges are added, the Gallai-Edmonds labels of
+ * G_i (even / odd / unreachable w.r.t. M_i) fix what every later stage
+ * must keep:
+ *   - odd and unreachable vertices are matched in every maximum matching
+ *     of G_i, so their edges of higher rank are never added;
+ *   - odd-odd and odd-unreachable edges are in no maximum matching, so they
+ *     are dropped.
+ * M_{i+1} is M_i augmented in G_{i+1}. A post with capacity c is c slots,
+ * which the applicant ranks alike.
+ *
+ * Popular (Abraham, Irving, Kavitha, Mehlhorn 2007), unit capacities: no
+ * other matching is preferred by more applicants than prefer this one.
+ * Each applicant a gets a last-resort post l(a), ranked below its list.
+ * f(a) are a's first choices, s(a) its best posts that are even in G_1. A
+ * matching is popular iff it matches every applicant into f(a) or s(a)
+ * and restricts to a maximum matching of G_1. So: drop the odd-odd and
+ * odd-unreachable edges of G_1, add the s(a) edges, and augment a maximum
+ * matching of G_1; a popular matching exists iff every applicant ends up
+ * matched. Applicants on their last resort are left unmatched.
+ *
+ * Cost: r Hopcroft-Karp runs on at most sum(capacities) slots for
+ * rank-maximal (r = largest rank), two for popular.
+ *
+ * Input format ('#' comments, blank lines ignored):
+ *     <applicants> <posts>
+ *     one line per applicant: its preference list, most preferred first;
+ *         "(3 5)" is a tie, "-" an empty list
+ *     optional last line: "capacities:" and one capacity per post
+ *
+ * Included via #[path = "../../common/rust/rank_matching.rs"] mod rank_matching;
+ * next to hopcroft_karp.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+use super::hopcroft_karp::HopcroftKarp;
+
+const NIL: usize = usize::MAX;
+
+pub struct Instance {
+    pub posts: usize,
+    pub capacity: Vec<usize>,        /* per post */
+    pub prefs: Vec<Vec<Vec<usize>>>, /* per applicant: tie groups, best first */
+}
+
+impl Instance {
+    pub fn applicants(&self) -> usize { self.prefs.len() }
+
+    /* Largest rank in any list, 0 if all lists are empty. */
+    pub fn max_rank(&self) -> usize {
+        self.prefs.iter().map(|g| g.len()).max().unwrap_or(0)
+    }
+
+    /* (applicant, post, rank), rank 1-based, sorted. */
+    pub fn ranked_pairs(&self) -> Vec<(usize, usize, usize)> {
+        let mut pairs = Vec::new();
+        for (a, groups) in self.prefs.iter().enumerate() {
+            for (i, group) in groups.iter().enumerate() {
+                for &p in group { pairs.push((a, p, i + 1)); }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /* Rank of p in a's list, None if unlisted. */
+    pub fn rank_of(&self, a: usize, p: usize) -> Option<usize> {
+        self.prefs[a].iter().position(|g| g.contains(&p)).map(|i| i + 1)
+    }
+
+    /* Post of each slot, slots of a post consecutive. */
+    fn slots(&self) -> Vec<usize> {
+        let mut owner = Vec::new();
+        for (p, &c) in self.capacity.iter().enumerate() {
+            for _ in 0..c { owner.push(p); }
+        }
+        owner
+    }
+}
+
+pub fn load_instance(filename: &str) -> Result<Instance, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    parse_instance(&text).map_err(|e| format!("{}:{}", filename, e).into())
+}
+
+/* Errors are "line: message". */
+pub fn parse_instance(text: &str) -> Result<Instance, String> {
+    let mut lines = text.lines().enumerate()
+        .map(|(i, raw)| (i + 1, raw.split('#').next().unwrap_or("").trim()))
+        .filter(|&(_, l)| !l.is_empty());
+    let (hl, header) = lines.next().ok_or("1: empty file")?;
+    let sizes: Vec<usize> = header.split_whitespace().map(|t| t.parse::<usize>())
+        .collect::<Result<_, _>>().map_err(|_| format!("{}: expected '<applicants> <posts>', got '{}'", hl, header))?;
+    if sizes.len() != 2 {
+        return Err(format!("{}: expected '<applicants> <posts>', got '{}'", hl, header));
+    }
+    let (count, posts) = (sizes[0], sizes[1]);
+    let mut prefs = Vec::with_capacity(count);
+    for a in 0..count {
+        let (line, l) = lines.next()
+            .ok_or_else(|| format!("{}: file ends before the list of applicant {} of {}", hl, a, count))?;
+        let spaced = l.replace('(', " ( ").replace(')', " ) ");
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut open: Option<Vec<usize>> = None;
+        let mut seen = vec![false; posts];
+        for t in spaced.split_whitespace() {
+            match t {
+                "-" => continue,
+                "(" if open.is_none() => open = Some(Vec::new()),
+                ")" => match open.take() {
+                    Some(g) if !g.is_empty() => groups.push(g),
+                    Some(_) => return Err(format!("{}: empty tie '()'", line)),
+                    None => return Err(format!("{}: ')' without '('", line)),
+                },
+                "(" => return Err(format!("{}: nested '('", line)),
+                _ => match t.parse::<usize>() {
+                    Ok(p) if p < posts && !seen[p] => {
+                        seen[p] = true;
+                        match open {
+                            Some(ref mut g) => g.push(p),
+                            None => groups.push(vec![p]),
+                        }
+                    }
+                    Ok(p) if p < posts => return Err(format!("{}: {} listed twice", line, p)),
+                    _ => return Err(format!("{}: '{}' is not a post id below {}", line, t, posts)),
+                },
+            }
+        }
+        if open.is_some() {
+            return Err(format!("{}: '(' is never closed", line));
+        }
+        prefs.push(groups);
+    }
+    let mut capacity = vec![1; posts];
+    if let Some((line, l)) = lines.next() {
+        let rest = match l.strip_prefix("capacities:") {
+            Some(r) => r,
+            None => return Err(format!("{}: expected 'capacities:' after {} preference lists, got '{}'",
+                                       line, count, l)),
+        };
+        let caps: Vec<usize> = rest.split_whitespace().map(|t| t.parse::<usize>())
+            .collect::<Result<_, _>>().map_err(|_| format!("{}: capacities must be non-negative integers", line))?;
+        if caps.len() != posts {
+            return Err(format!("{}: {} capacities for {} posts", line, caps.len(), posts));
+        }
+        capacity = caps;
+    }
+    if let Some((line, l)) = lines.next() {
+        return Err(format!("{}: unexpected line '{}' after the capacities", line, l));
+    }
+    Ok(Instance { posts, capacity, prefs })
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Label { Even, Odd, Unreachable }
+
+/* Gallai-Edmonds labels of a bipartite graph for a maximum matching:
+ * even vertices are reached from an exposed vertex by an even-length
+ * alternating path, odd ones by an odd-length path. */
+fn labels(left: usize, right: usize, edges: &[(usize, usize)], matching: &[(usize, usize)])
+          -> (Vec<Label>, Vec<Label>) {
+    let mut adj_l = vec![Vec::new(); left];
+    let mut adj_r = vec![Vec::new(); right];
+    for &(u, v) in edges {
+        adj_l[u].push(v);
+        adj_r[v].push(u);
+    }
+    let mut mate_l = vec![NIL; left];
+    let mut mate_r = vec![NIL; right];
+    for &(u, v) in matching {
+        mate_l[u] = v;
+        mate_r[v] = u;
+    }
+    let mut lab_l = vec![Label::Unreachable; left];
+    let mut lab_r = vec![Label::Unreachable; right];
+    /* queue of even vertices, (is_left, id) */
+    let mut queue = Vec::new();
+    for u in 0..left {
+        if mate_l[u] == NIL { lab_l[u] = Label::Even; queue.push((true, u)); }
+    }
+    for v in 0..right {
+        if mate_r[v] == NIL { lab_r[v] = Label::Even; queue.push((false, v)); }
+    }
+    let mut i = 0;
+    while i < queue.len() {
+        let (is_left, x) = queue[i];
+        i += 1;
+        if is_left {
+            for &v in &adj_l[x] {
+                if lab_r[v] != Label::Unreachable { continue; }
+                lab_r[v] = Label::Odd;
+                /* v is matched, or it would be even already */
+                let u = mate_r[v];
+                if lab_l[u] == Label::Unreachable {
+                    lab_l[u] = Label::Even;
+                    queue.push((true, u));
+                }
+            }
+        } else {
+            for &u in &adj_r[x] {
+                if lab_l[u] != Label::Unreachable { continue; }
+                lab_l[u] = Label::Odd;
+                let v = mate_l[u];
+                if lab_r[v] == Label::Unreachable {
+                    lab_r[v] = Label::Even;
+                    queue.push((false, v));
+                }
+            }
+        }
+    }
+    (lab_l, lab_r)
+}
+
+fn augment(left: usize, right: usize, edges: &[(usize, usize)], seed: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut hk = HopcroftKarp::new(left, right, edges);
+    hk.seed(seed);
+    hk.maximum_matching(0)
+}
+
+/* Odd-odd and odd-unreachable edges are in no maximum matching. */
+fn removable(a: Label, b: Label) -> bool {
+    (a == Label::Odd && b != Label::Even) || (b == Label::Odd && a != Label::Even)
+}
+
+pub struct RankMatching {
+    pub pairs: Vec<(usize, usize)>, /* (applicant, post), sorted */
+    pub signature: Vec<usize>,     /* pairs of rank 1, 2, ... */
+}
+
+/* Pairs of each rank, up to the largest rank in the instance. */
+pub fn signature(inst: &Instance, pairs: &[(usize, usize)]) -> Vec<usize> {
+    let mut sig = vec![0; inst.max_rank()];
+    for &(a, p) in pairs {
+        if let Some(r) = inst.rank_of(a, p) { sig[r - 1] += 1; }
+    }
+    sig
+}
+
+pub fn rank_maximal(inst: &Instance) -> RankMatching {
+    let owner = inst.slots();
+    let (na, ns) = (inst.applicants(), owner.len());
+    let mut first_slot = vec![0; inst.posts + 1];
+    for p in 0..inst.posts { first_slot[p + 1] = first_slot[p] + inst.capacity[p]; }
+    /* vertices fixed at an earlier stage take no edges of a higher rank */
+    let mut fixed_a = vec![false; na];
+    let mut fixed_s = vec![false; ns];
+    let mut edges: Vec<(usize, usize)> = Vec::new();
+    let mut matching = Vec::new();
+    let r = inst.max_rank();
+    for i in 0..r {
+        for (a, &fixed) in fixed_a.iter().enumerate() {
+            if fixed || i >= inst.prefs[a].len() { continue; }
+            for &p in &inst.prefs[a][i] {
+                edges.extend((first_slot[p]..first_slot[p + 1]).filter(|&s| !fixed_s[s]).map(|s| (a, s)));
+            }
+        }
+        matching = augment(na, ns, &edges, &matching);
+        if i + 1 == r { break; }
+        let (lab_a, lab_s) = labels(na, ns, &edges, &matching);
+        for a in 0..na { if lab_a[a] != Label::Even { fixed_a[a] = true; } }
+        for s in 0..ns { if lab_s[s] != Label::Even { fixed_s[s] = true; } }
+        edges.retain(|&(a, s)| !removable(lab_a[a], lab_s[s]));
+    }
+    let mut pairs: Vec<(usize, usize)> = matching.iter().map(|&(a, s)| (a, owner[s])).collect();
+    pairs.sort_unstable();
+    let signature = signature(inst, &pairs);
+    RankMatching { pairs, signature }
+}
+
+/* None when no popular matching exists. Needs unit capacities. */
+pub fn popular(inst: &Instance) -> Result<Option<Vec<(usize, usize)>>, String> {
+    if let Some(p) = inst.capacity.iter().position(|&c| c != 1) {
+        return Err(format!("popular matching needs unit capacities (post {} has {})", p, inst.capacity[p]));
+    }
+    let (na, np) = (inst.applicants(), inst.posts);
+    let last_resort = |a: usize| np + a;
+    let first = |a: usize| -> Vec<usize> {
+        match inst.prefs[a].first() {
+            Some(g) => g.clone(),
+            None => vec![last_resort(a)],
+        }
+    };
+    let mut g1: Vec<(usize, usize)> = Vec::new();
+    for a in 0..na {
+        for p in first(a) { g1.push((a, p)); }
+    }
+    let m1 = augment(na, np + na, &g1, &[]);
+    let (lab_a, lab_p) = labels(na, np + na, &g1, &m1);
+
+    let mut edges: Vec<(usize, usize)> = g1.iter().cloned().filter(|&(a, p)| !removable(lab_a[a], lab_p[p])).collect();
+    for a in 0..na {
+        /* s(a): the best tie group with an even post; l(a) is always even */
+        let s = inst.prefs[a].iter()
+            .map(|g| g.iter().cloned().filter(|&p| lab_p[p] == Label::Even).collect::<Vec<_>>())
+            .find(|g| !g.is_empty())
+            .unwrap_or_else(|| vec![last_resort(a)]);
+        for p in s { edges.push((a, p)); }
+    }
+    let m = augment(na, np + na, &edges, &m1);
+    if m.len() < na {
+        return Ok(None);
+    }
+    Ok(Some(m.into_iter().filter(|&(_, p)| p < np).collect()))
+}
+
+/* Listed pairs only, one post per applicant, capacities respected. */
+pub fn check_matching(inst: &Instance, pairs: &[(usize, usize)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut used_a = vec![false; inst.applicants()];
+    let mut load = vec![0usize; inst.posts];
+    for &(a, p) in pairs {
+        if a >= inst.applicants() || p >= inst.posts {
+            errors.push(format!("Pair ({}, {}) is out of range!", a, p));
+            continue;
+        }
+        if inst.rank_of(a, p).is_none() {
+            errors.push(format!("Applicant {} does not list post {}!", a, p));
+        }
+        if used_a[a] {
+            errors.push(format!("Applicant {} is matched twice!", a));
+        }
+        used_a[a] = true;
+        load[p] += 1;
+    }
+    for (p, (&l, &cap)) in load.iter().zip(&inst.capacity).enumerate() {
+        if l > cap {
+            errors.push(format!("Post {} has {} applicants, capacity {}!", p, l, cap));
+        }
+    }
+    errors
+}
@@ -0,0 +1,223 @@
+/*
+ * Planted-matching recovery experiments: how often, and how nearly, each
+ * algorithm returns the matching planted by planted.rs as the noise
+ * around it grows.
+ *
+ * An experiment is a grid of noise degrees x gaps. Each cell plants
+ * `trials` instances and runs every requested algorithm on each:
+ *   - "weighted": the weighted blossom solver (maximum weight),
+ *   - "greedy": heaviest edge first, a weight-aware baseline,
+ *   - "hk-tie-break": Hopcroft-Karp then --tie-break heavier (bipartite
+ *     instances only),
+ *   - any name of matchers::GENERAL_ALGORITHMS: a maximum-cardinality
+ *     matcher that ignores the weights, which finds the planted pairs
+ *     only by chance.
+ * Per cell and algorithm it reports how many results are exactly the
+ * planted matching, the mean fraction of planted pairs found, the mean
+ * and least ratio of result weight to planted weight, and the mean time.
+ *
+ * Trial t of every cell uses seed `seed + t`, so it is the instance
+ * `combi plant <n> --degree D --gap G --seed <seed + t>` with the same
+ * potentials and planted matching across cells: cells differ only in
+ * their noise.
+ *
+ * Included via #[path = "../../common/rust/recovery.rs"] mod recovery;
+ * next to hopcroft_karp.rs, matchers.rs, planted.rs, rng.rs and
+ * weighted_blossom.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use super::hopcroft_karp::HopcroftKarp;
+use super::matchers;
+use super::planted::{self, PlantOptions, Planted};
+use super::rng::Rng;
+use super::weighted_blossom;
+
+pub const WEIGHTED_ALGORITHMS: [&str; 3] = ["weighted", "greedy", "hk-tie-break"];
+
+pub struct RecoveryOptions {
+    pub plant: PlantOptions, /* n, bipartite, max_potential, spread; degree and gap per cell */
+    pub degrees: Vec<f64>,
+    pub gaps: Vec<i64>,
+    pub trials: usize,
+    pub seed: u64,
+    pub algos: Vec<String>,
+}
+
+pub struct Row {
+    pub degree: f64,
+    pub gap: i64,
+    pub algo: String,
+    pub trials: usize,
+    pub exact: usize,          /* results equal to the planted matching */
+    pub pair_fraction: f64,    /* mean share of planted pairs found */
+    pub weight_ratio: f64,     /* mean result weight / planted weight */
+    pub min_weight_ratio: f64,
+    pub mean_ms: f64,
+}
+
+/* Algorithm names for --algos: weighted ones first, then the cardinality
+ * matchers. */
+pub fn algorithm_names() -> Vec<&'static str> {
+    WEIGHTED_ALGORITHMS.iter().chain(matchers::GENERAL_ALGORITHMS.iter()).cloned().collect()
+}
+
+/* The options' problems, before any instance is planted. */
+pub fn validate(opts: &RecoveryOptions) -> Result<(), String> {
+    if opts.plant.n % 2 == 1 {
+        return Err(format!("n must be even for a perfect matching, got {}", opts.plant.n));
+    }
+    if opts.degrees.is_empty() || opts.gaps.is_empty() || opts.algos.is_empty() {
+        return Err("need at least one degree, one gap and one algorithm".to_string());
+    }
+    if opts.trials == 0 {
+        return Err("need at least one trial".to_string());
+    }
+    for a in &opts.algos {
+        if !algorithm_names().contains(&a.as_str()) {
+            return Err(format!("unknown algorithm '{}' (expected one of {})", a, algorithm_names().join(", ")));
+        }
+        if a == "hk-tie-break" && !opts.plant.bipartite {
+            return Err("hk-tie-break needs bipartite instances (--bipartite)".to_string());
+        }
+    }
+    Ok(())
+}
+
+/* The instance as a general graph (n, edges, planted matching): bipartite
+ * right ids follow the left; planted pairs sorted, u < v. */
+pub type General = (usize, Vec<(usize, usize, i64)>, Vec<(usize, usize)>);
+
+pub fn general(p: &Planted) -> General {
+    let shift = if p.right > 0 { p.left } else { 0 };
+    let edges = p.edges.iter().map(|&(u, v, w)| (u, shift + v, w)).collect();
+    let mut matching: Vec<(usize, usize)> =
+        p.matching.iter().map(|&(u, v)| (u.min(shift + v), u.max(shift + v))).collect();
+    matching.sort_unstable();
+    (p.left + p.right, edges, matching)
+}
+
+/* Heaviest edge first, ties by endpoints; a maximal matching. */
+pub fn greedy_weighted(n: usize, edges: &[(usize, usize, i64)]) -> Vec<(usize, usize)> {
+    let mut order: Vec<&(usize, usize, i64)> = edges.iter().collect();
+    order.sort_by(|a, b| b.2.cmp(&a.2).then((a.0, a.1).cmp(&(b.0, b.1))));
+    let mut used = vec![false; n];
+    let mut matching = Vec::new();
+    for &&(u, v, _) in &order {
+        if u != v && !used[u] && !used[v] {
+            used[u] = true;
+            used[v] = true;
+            matching.push((u.min(v), u.max(v)));
+        }
+    }
+    matching.sort_unstable();
+    matching
+}
+
+/* `algo` on the planted instance, as sorted general-id pairs, u < v. */
+pub fn solve(algo: &str, p: &Planted) -> Result<Vec<(usize, usize)>, String> {
+    let (n, edges, _) = general(p);
+    let mut matching = match algo {
+        "weighted" => weighted_blossom::max_weight_matching(n, &edges, false),
+        "greedy" => greedy_weighted(n, &edges),
+        "hk-tie-break" => {
+            let plain: Vec<(usize, usize)> = p.edges.iter().map(|&(u, v, _)| (u, v)).collect();
+            let mut hk = HopcroftKarp::new(p.left, p.right, &plain);
+            hk.maximum_matching(0);
+            hk.tie_break(&p.edges, true).0.into_iter().map(|(u, v)| (u, p.left + v)).collect()
+        }
+        _ => {
+            let plain: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+            matchers::maximum_matching(algo, n, &plain)?
+        }
+    };
+    matching.sort_unstable();
+    Ok(matching)
+}
+
+/* Weight of `matching` on the instance's edges, or why it is not a
+ * matching of them. */
+fn weigh(n: usize, edges: &[(usize, usize, i64)], matching: &[(usize, usize)]) -> Result<i64, String> {
+    let mut weight_of = BTreeSet::new();
+    for &(u, v, w) in edges {
+        weight_of.insert((u.min(v), u.max(v), w));
+    }
+    let mut used = vec![false; n];
+    let mut total = 0;
+    for &(u, v) in matching {
+        let w = weight_of.range((u, v, i64::MIN)..=(u, v, i64::MAX)).next().map(|e| e.2)
+            .ok_or_else(|| format!("pair ({}, {}) is not an edge", u, v))?;
+        if used[u] || used[v] {
+            return Err(format!("pair ({}, {}) reuses a vertex", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+        total += w;
+    }
+    Ok(total)
+}
+
+/* Runs the grid, cells in (degree, gap) order and algorithms in the
+ * order given. `progress` is called after each cell. */
+pub fn run(opts: &RecoveryOptions, progress: &mut dyn FnMut(&[Row])) -> Result<Vec<Row>, String> {
+    validate(opts)?;
+    let mut rows = Vec::new();
+    for &degree in &opts.degrees {
+        for &gap in &opts.gaps {
+            let cell = PlantOptions { degree, gap, ..opts.plant };
+            let mut stats: Vec<Row> = opts.algos.iter().map(|a| Row {
+                degree, gap, algo: a.clone(), trials: opts.trials, exact: 0, pair_fraction: 0.0,
+                weight_ratio: 0.0, min_weight_ratio: f64::INFINITY, mean_ms: 0.0,
+            }).collect();
+            for t in 0..opts.trials {
+                let p = planted::plant(&cell, &mut Rng::new(opts.seed.wrapping_add(t as u64)))?;
+                let (n, edges, planted_pairs) = general(&p);
+                let wanted: BTreeSet<(usize, usize)> = planted_pairs.iter().cloned().collect();
+                for row in &mut stats {
+                    let start = Instant::now();
+                    let matching = solve(&row.algo, &p)?;
+                    row.mean_ms += start.elapsed().as_secs_f64() * 1000.0;
+                    let weight = weigh(n, &edges, &matching).map_err(|e| {
+                        format!("{} on degree {}, gap {}, seed {}: {}", row.algo, degree, gap,
+                                opts.seed.wrapping_add(t as u64), e)
+                    })?;
+                    let found = matching.iter().filter(|e| wanted.contains(e)).count();
+                    let fraction = if wanted.is_empty() { 1.0 } else { found as f64 / wanted.len() as f64 };
+                    let ratio = if p.weight == 0 { 1.0 } else { weight as f64 / p.weight as f64 };
+                    if matching == planted_pairs { row.exact += 1; }
+                    row.pair_fraction += fraction;
+                    row.weight_ratio += ratio;
+                    row.min_weight_ratio = row.min_weight_ratio.min(ratio);
+                }
+            }
+            for row in &mut stats {
+                let trials = opts.trials as f64;
+                row.pair_fraction /= trials;
+                row.weight_ratio /= trials;
+                row.mean_ms /= trials;
+            }
+            progress(&stats);
+            rows.extend(stats);
+        }
+    }
+    Ok(rows)
+}
+
+pub const CSV_HEADER: &str = "degree,gap,algorithm,trials,exact,pair_fraction,weight_ratio,min_weight_ratio,mean_ms";
+
+pub fn write_csv_row(out: &mut dyn Write, r: &Row) -> io::Result<()> {
+    writeln!(out, "{},{},{},{},{},{:.6},{:.6},{:.6},{:.3}", r.degree, r.gap, r.algo, r.trials, r.exact,
+             r.pair_fraction, r.weight_ratio, r.min_weight_ratio, r.mean_ms)
+}
+
+pub const TABLE_HEADER: &str = "  degree      gap  algorithm          exact     pairs    weight  min weight        ms";
+
+pub fn write_table_row(out: &mut dyn Write, r: &Row) -> io::Result<()> {
+    writeln!(out, "  {:>6} {:>8}  {:<16} {:>7}  {:>8.4}  {:>8.6}  {:>10.6}  {:>8.2}", r.degree, r.gap, r.algo,
+             format!("{}/{}", r.exact, r.trials), r.pair_fraction, r.weight_ratio, r.min_weight_ratio, r.mean_ms)
+}
@@ -0,0 +1,164 @@
+/*
+ * Matching sparsifier: a subgraph H of G, the union of k edge-disjoint
+ * matchings, with (nearly) the matching number of G on far fewer edges.
+ *
+ * Round i takes a matching M_i of G minus M_1 .. M_{i-1}: a first-fit
+ * maximal one, or a maximum one from the matchers. Every M_i is maximal in
+ * what was left, so an edge uv of G that H leaves out had an end matched
+ * in every round: deg_H(u) + deg_H(v) >= k. H thus keeps at most k n / 2
+ * edges and every dropped edge sits next to a dense part of H, which is
+ * what lets a maximum matching of H reach nu(G) or near it.
+ *
+ *   - maximum rounds: M_1 alone is a maximum matching of G, so nu(H) =
+ *     nu(G) from the first round; more rounds add alternatives (a
+ *     certificate that survives edge deletions, say);
+ *   - maximal rounds: nu(H) >= |M_1| >= nu(G) / 2 from the first round,
+ *     and in practice close to nu(G) after a few; each round is linear,
+ *     one pass over the edges left, as a streaming algorithm would do.
+ *
+ * With a target (nu(G), from a solve), the rounds stop as soon as
+ * nu(H) reaches it: a small certificate subgraph with the full matching
+ * number. Every round then also solves H, which is small.
+ *
+ * Maximum rounds with no bound on k are also a decomposition of G into
+ * matchings, largest first (decompose_into_matchings): at least Delta(G)
+ * of them, and rounds of a schedule where each holds as many pairs as the
+ * pairs left allow.
+ *
+ * Self-loops, repeated edges and out-of-range endpoints are ignored.
+ *
+ * Included via #[path = "../../common/rust/sparsifier.rs"] mod sparsifier;
+ * next to matchers.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::matchers;
+
+const NIL: usize = usize::MAX;
+
+pub struct Sparsifier {
+    /* edge-disjoint matchings, in the order taken; sorted pairs, u < v */
+    pub rounds: Vec<Vec<(usize, usize)>>,
+    /* nu(H) after each round, when the rounds ran against a target */
+    pub nu_after: Vec<usize>,
+}
+
+impl Sparsifier {
+    /* The edges of H, sorted. */
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut all: Vec<(usize, usize)> = self.rounds.iter().flat_map(|r| r.iter().cloned()).collect();
+        all.sort_unstable();
+        all
+    }
+}
+
+/* Distinct edges of (n, edges) as sorted pairs (u < v). */
+fn distinct(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut out: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/* First-fit maximal matching of `edges` (sorted pairs), in their order. */
+fn first_fit(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut matched = vec![false; n];
+    let mut matching = Vec::new();
+    for &(u, v) in edges {
+        if !matched[u] && !matched[v] {
+            matched[u] = true;
+            matched[v] = true;
+            matching.push((u, v));
+        }
+    }
+    matching
+}
+
+/* Up to `max_rounds` rounds of maximal (first-fit) or maximum (`algo`)
+ * matchings of (n, edges), stopping early when no edge is left or, given
+ * a target, when nu(H) reaches it. */
+pub fn sparsify(
+    algo: &str, n: usize, edges: &[(usize, usize)], maximum: bool, max_rounds: usize, target: Option<usize>,
+) -> Result<Sparsifier, String> {
+    let mut left = distinct(n, edges);
+    let mut s = Sparsifier { rounds: Vec::new(), nu_after: Vec::new() };
+    let mut kept: Vec<(usize, usize)> = Vec::new();
+    while s.rounds.len() < max_rounds && !left.is_empty() {
+        let mut round = if maximum {
+            matchers::maximum_matching(algo, n, &left)?
+        } else {
+            first_fit(n, &left)
+        };
+        round.sort_unstable();
+        left.retain(|e| round.binary_search(e).is_err());
+        kept.extend_from_slice(&round);
+        s.rounds.push(round);
+        if let Some(nu) = target {
+            let reached = if maximum && s.rounds.len() == 1 {
+                s.rounds[0].len()
+            } else {
+                matchers::maximum_matching(algo, n, &kept)?.len()
+            };
+            s.nu_after.push(reached);
+            if reached >= nu {
+                break;
+            }
+        }
+    }
+    Ok(s)
+}
+
+/* Decomposition of (n, edges) into up to `k` edge-disjoint matchings: a
+ * maximum matching (`algo`), removed, and again, until k are taken or no
+ * edge is left (usize::MAX for all of them). Each is maximum in the edges
+ * the earlier ones left, so they shrink; the first is a maximum matching
+ * of the graph. Rounds of pairwise meetings, say: nobody is in two
+ * meetings of a round, and each round holds as many as it can. */
+pub fn decompose_into_matchings(
+    algo: &str, n: usize, edges: &[(usize, usize)], k: usize,
+) -> Result<Vec<Vec<(usize, usize)>>, String> {
+    Ok(sparsify(algo, n, edges, true, k, None)?.rounds)
+}
+
+/* Problems with `s` as rounds of (n, edges): a round that is not a
+ * matching of graph edges, an edge taken twice, or a round that is not
+ * maximal in the edges left before it. */
+pub fn check_sparsifier(n: usize, edges: &[(usize, usize)], s: &Sparsifier) -> Vec<String> {
+    let graph = distinct(n, edges);
+    let mut errors = Vec::new();
+    let mut taken = vec![false; graph.len()];
+    for (i, round) in s.rounds.iter().enumerate() {
+        let mut mate = vec![NIL; n];
+        for &(u, v) in round {
+            let k = match graph.binary_search(&(u.min(v), u.max(v))) {
+                Ok(k) => k,
+                Err(_) => {
+                    errors.push(format!("Round {}: edge ({}, {}) not in graph!", i + 1, u, v));
+                    continue;
+                }
+            };
+            if taken[k] {
+                errors.push(format!("Round {}: edge ({}, {}) taken twice!", i + 1, u, v));
+            }
+            taken[k] = true;
+            for &(x, y) in &[(u, v), (v, u)] {
+                if mate[x] != NIL {
+                    errors.push(format!("Round {}: vertex {} matched twice!", i + 1, x));
+                }
+                mate[x] = y;
+            }
+        }
+        /* maximal among the edges no round up to this one took */
+        if let Some(&(u, v)) = graph.iter().enumerate()
+            .find(|&(k, &(u, v))| !taken[k] && mate[u] == NIL && mate[v] == NIL)
+            .map(|(_, e)| e)
+        {
+            errors.push(format!("Round {}: not maximal, ({}, {}) joins two exposed vertices", i + 1, u, v));
+        }
+    }
+    errors
+}
@@ -0,0 +1,278 @@
+/*
+ * Stable matching by deferred acceptance (Gale-Shapley 1962).
+ *
+ * Two sides with strict, possibly incomplete preference lists; a pair is
+ * acceptable when each lists the other. Capacities give the
+ * hospitals/residents problem: one side may take several partners (a
+ * hospital ranks residents individually, so its preferences are
+ * responsive). Both sides capacitated (many-to-many) is rejected.
+ *
+ * Deferred acceptance: every proposer with a free slot proposes down its
+ * list; a receiver holds its best proposals up to its capacity and rejects
+ * the rest, so a rejection is final. The result is stable and
+ * proposer-optimal: no stable matching gives any unit-capacity proposer a
+ * better partner, and every unit-capacity receiver gets its worst stable
+ * partner. By the rural hospitals theorem each agent has the same number
+ * of partners in every stable matching, whichever side proposes.
+ *
+ * Cost: O(sum of list lengths * log) (rank lookups by binary search, a
+ * heap of held proposals per receiver).
+ *
+ * Input format ('#' comments, blank lines ignored):
+ *     <left> <right>
+ *     one line per left agent, then one per right agent:
+ *     [<capacity>:] <preference list, most preferred first>
+ * ids refer to the other side; "-" is an empty list; capacity defaults to 1.
+ *
+ * Included via #[path = "../../common/rust/stable_matching.rs"] mod stable_matching;
+ */
+
+#![allow(dead_code)]
+
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fs;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Side { Left, Right }
+
+impl Side {
+    pub fn parse(s: &str) -> Option<Side> {
+        match s {
+            "left" => Some(Side::Left),
+            "right" => Some(Side::Right),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self { Side::Left => "left", Side::Right => "right" }
+    }
+}
+
+/* One side's agents: capacities and preference lists over the other side. */
+pub struct Agents {
+    pub capacity: Vec<usize>,
+    pub prefs: Vec<Vec<usize>>,
+}
+
+impl Agents {
+    pub fn len(&self) -> usize { self.prefs.len() }
+
+    /* Position of each listed agent, sorted by id, for rank lookups. */
+    fn rank_tables(&self) -> Vec<Vec<(usize, usize)>> {
+        self.prefs.iter().map(|list| {
+            let mut t: Vec<(usize, usize)> = list.iter().enumerate().map(|(r, &x)| (x, r)).collect();
+            t.sort_unstable();
+            t
+        }).collect()
+    }
+}
+
+pub struct Instance {
+    pub left: Agents,
+    pub right: Agents,
+}
+
+/* Rank of x in an agent's list (0 = first choice), None if unlisted. */
+fn rank_in(table: &[(usize, usize)], x: usize) -> Option<usize> {
+    table.binary_search_by_key(&x, |&(y, _)| y).ok().map(|i| table[i].1)
+}
+
+impl Instance {
+    /* Mutually acceptable pairs, (left, right), sorted. */
+    pub fn acceptable_pairs(&self) -> Vec<(usize, usize)> {
+        let rr = self.right.rank_tables();
+        let mut pairs = Vec::new();
+        for (l, list) in self.left.prefs.iter().enumerate() {
+            for &r in list {
+                if rank_in(&rr[r], l).is_some() { pairs.push((l, r)); }
+            }
+        }
+        pairs.sort_unstable();
+        pairs
+    }
+}
+
+pub fn load_instance(filename: &str) -> Result<Instance, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    parse_instance(&text).map_err(|e| format!("{}:{}", filename, e).into())
+}
+
+/* Errors are "line: message". */
+pub fn parse_instance(text: &str) -> Result<Instance, String> {
+    let mut lines = text.lines().enumerate()
+        .map(|(i, raw)| (i + 1, raw.split('#').next().unwrap_or("").trim()))
+        .filter(|&(_, l)| !l.is_empty());
+    let (hl, header) = lines.next().ok_or("1: empty file")?;
+    let sizes: Vec<usize> = header.split_whitespace().map(|t| t.parse::<usize>())
+        .collect::<Result<_, _>>().map_err(|_| format!("{}: expected '<left> <right>', got '{}'", hl, header))?;
+    if sizes.len() != 2 {
+        return Err(format!("{}: expected '<left> <right>', got '{}'", hl, header));
+    }
+    let mut sides = Vec::with_capacity(2);
+    for (count, other) in [(sizes[0], sizes[1]), (sizes[1], sizes[0])].iter().cloned() {
+        let mut agents = Agents { capacity: Vec::with_capacity(count), prefs: Vec::with_capacity(count) };
+        for a in 0..count {
+            let (line, l) = lines.next()
+                .ok_or_else(|| format!("{}: file ends before the list of agent {} of {}", hl, a, count))?;
+            let (cap, list) = match l.find(':') {
+                Some(i) => {
+                    let c = l[..i].trim();
+                    match c.parse::<usize>() {
+                        Ok(c) if c > 0 => (c, &l[i + 1..]),
+                        _ => return Err(format!("{}: capacity must be a positive integer, got '{}'", line, c)),
+                    }
+                }
+                None => (1, l),
+            };
+            let mut prefs = Vec::new();
+            let mut seen = vec![false; other];
+            for t in list.split_whitespace() {
+                if t == "-" { continue; }
+                match t.parse::<usize>() {
+                    Ok(x) if x < other && !seen[x] => {
+                        seen[x] = true;
+                        prefs.push(x);
+                    }
+                    Ok(x) if x < other => return Err(format!("{}: {} listed twice", line, x)),
+                    _ => return Err(format!("{}: '{}' is not an id below {}", line, t, other)),
+                }
+            }
+            agents.capacity.push(cap);
+            agents.prefs.push(prefs);
+        }
+        sides.push(agents);
+    }
+    if let Some((line, l)) = lines.next() {
+        return Err(format!("{}: unexpected line '{}' after {} preference lists", line, l, sizes[0] + sizes[1]));
+    }
+    let right = sides.pop().unwrap();
+    let left = sides.pop().unwrap();
+    if left.capacity.iter().any(|&c| c > 1) && right.capacity.iter().any(|&c| c > 1) {
+        return Err(format!("{}: capacities above 1 on both sides (many-to-many) are not supported", hl));
+    }
+    Ok(Instance { left, right })
+}
+
+pub struct StableMatching {
+    pub pairs: Vec<(usize, usize)>, /* (left, right), sorted */
+    pub proposals: u64,
+}
+
+pub fn gale_shapley(inst: &Instance, proposer: Side) -> StableMatching {
+    let (props, recv) = match proposer {
+        Side::Left => (&inst.left, &inst.right),
+        Side::Right => (&inst.right, &inst.left),
+    };
+    let ranks = recv.rank_tables();
+    let mut next = vec![0usize; props.len()];
+    let mut free = props.capacity.clone();
+    /* held[r]: max-heap on rank, the worst held proposal on top */
+    let mut held: Vec<BinaryHeap<(usize, usize)>> = (0..recv.len()).map(|_| BinaryHeap::new()).collect();
+    let mut stack: Vec<usize> = (0..props.len()).rev().collect();
+    let mut proposals = 0u64;
+
+    while let Some(p) = stack.pop() {
+        while free[p] > 0 && next[p] < props.prefs[p].len() {
+            let r = props.prefs[p][next[p]];
+            next[p] += 1;
+            proposals += 1;
+            let rank = match rank_in(&ranks[r], p) {
+                Some(k) => k,
+                None => continue, /* p is unacceptable to r */
+            };
+            if held[r].len() < recv.capacity[r] {
+                held[r].push((rank, p));
+                free[p] -= 1;
+            } else if held[r].peek().is_some_and(|&(worst, _)| rank < worst) {
+                let (_, q) = held[r].pop().unwrap();
+                held[r].push((rank, p));
+                free[p] -= 1;
+                free[q] += 1;
+                stack.push(q);
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (r, h) in held.iter().enumerate() {
+        for &(_, p) in h.iter() {
+            pairs.push(match proposer { Side::Left => (p, r), Side::Right => (r, p) });
+        }
+    }
+    pairs.sort_unstable();
+    StableMatching { pairs, proposals }
+}
+
+/* Capacities, mutual acceptability and blocking pairs. A pair (l, r)
+ * blocks when both find each other acceptable, are not matched together,
+ * and each has a free slot or prefers the other to its worst partner. */
+pub fn check_stable(inst: &Instance, pairs: &[(usize, usize)]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let (lr, rr) = (inst.left.rank_tables(), inst.right.rank_tables());
+    let (nl, nr) = (inst.left.len(), inst.right.len());
+    /* worst partner rank, usize::MAX while a slot is free */
+    let mut count_l = vec![0usize; nl];
+    let mut count_r = vec![0usize; nr];
+    let mut worst_l = vec![0usize; nl];
+    let mut worst_r = vec![0usize; nr];
+    let mut sorted = pairs.to_vec();
+    sorted.sort_unstable();
+    for (i, &(l, r)) in sorted.iter().enumerate() {
+        if l >= nl || r >= nr {
+            errors.push(format!("Pair ({}, {}) is out of range!", l, r));
+            continue;
+        }
+        if i > 0 && sorted[i - 1] == (l, r) {
+            errors.push(format!("Pair ({}, {}) appears twice!", l, r));
+            continue;
+        }
+        match (rank_in(&lr[l], r), rank_in(&rr[r], l)) {
+            (Some(a), Some(b)) => {
+                worst_l[l] = worst_l[l].max(a);
+                worst_r[r] = worst_r[r].max(b);
+            }
+            _ => errors.push(format!("Pair ({}, {}) is not mutually acceptable!", l, r)),
+        }
+        count_l[l] += 1;
+        count_r[r] += 1;
+    }
+    for l in 0..nl {
+        if count_l[l] > inst.left.capacity[l] {
+            errors.push(format!("Left {} has {} partners, capacity {}!", l, count_l[l], inst.left.capacity[l]));
+        } else if count_l[l] < inst.left.capacity[l] {
+            worst_l[l] = usize::MAX;
+        }
+    }
+    for r in 0..nr {
+        if count_r[r] > inst.right.capacity[r] {
+            errors.push(format!("Right {} has {} partners, capacity {}!", r, count_r[r], inst.right.capacity[r]));
+        } else if count_r[r] < inst.right.capacity[r] {
+            worst_r[r] = usize::MAX;
+        }
+    }
+    if !errors.is_empty() { return errors; }
+    for (l, &worst) in worst_l.iter().enumerate() {
+        for (a, &r) in inst.left.prefs[l].iter().enumerate() {
+            if a >= worst { break; }
+            if let Some(b) = rank_in(&rr[r], l) {
+                if b < worst_r[r] && sorted.binary_search(&(l, r)).is_err() {
+                    errors.push(format!("Pair ({}, {}) is blocking!", l, r));
+                }
+            }
+        }
+    }
+    errors
+}
+
+/* Sum of 1-based partner ranks on each side; lower is better. */
+pub fn rank_sums(inst: &Instance, pairs: &[(usize, usize)]) -> (usize, usize) {
+    let (lr, rr) = (inst.left.rank_tables(), inst.right.rank_tables());
+    let mut sums = (0, 0);
+    for &(l, r) in pairs {
+        sums.0 += rank_in(&lr[l], r).map_or(0, |k| k + 1);
+        sums.1 += rank_in(&rr[r], l).map_or(0, |k| k + 1);
+    }
+    sums
+}
@@ -0,0 +1,248 @@
+/*
+ * --svg FILE: a picture of the graph and its matching, for slides, drawn
+ * here with no layout tool needed (--dot leaves the layout to GraphViz).
+ *
+ * Matched edges are thick and red, free vertices white, matched ones
+ * grey; ids are printed up to LABELED_VERTICES vertices. Layouts:
+ *   force   Fruchterman-Reingold from seeded random positions, all pairs
+ *           repelling, edges and the centre pulling, cooling over
+ *           FORCE_ROUNDS rounds (O(n^2) each, hence the cap; about a
+ *           second at 2000 vertices)
+ *   circle  the vertices in id order on a circle
+ * A Hopcroft-Karp graph is drawn in two columns instead, each right
+ * vertex on its mate's row, so that the matching is the horizontal edges.
+ *
+ * Self-loops, out-of-range endpoints and parallel copies are left out, as
+ * by the solvers. Graphs over MAX_VERTICES vertices are refused.
+ *
+ * Included via #[path = "../../common/rust/svg.rs"] mod svg; next to
+ * args.rs and rng.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use super::args;
+use super::rng::Rng;
+
+pub const SVG_USAGE: &str = "[--svg FILE [--layout force|circle]]";
+
+/* The most vertices a picture is drawn for. */
+pub const MAX_VERTICES: usize = 2000;
+/* Vertex ids are printed up to this many vertices. */
+const LABELED_VERTICES: usize = 100;
+const FORCE_ROUNDS: usize = 200;
+/* The pull towards the centre that keeps components from drifting off. */
+const GRAVITY: f64 = 5.0;
+/* The drawing area in pixels, inside the margin. */
+const SIZE: f64 = 800.0;
+const MARGIN: f64 = 30.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Layout {
+    Force,
+    Circle,
+}
+
+impl Layout {
+    /* --layout, force by default. */
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        match args::value_of(args, "--layout") {
+            None | Some("force") => Ok(Layout::Force),
+            Some("circle") => Ok(Layout::Circle),
+            Some(s) => Err(format!("invalid value for --layout: {} (expected force or circle)", s)),
+        }
+    }
+}
+
+/* The cap, checked before solving. */
+pub fn check_size(n: usize) -> Result<(), String> {
+    if n > MAX_VERTICES {
+        return Err(format!("--svg draws at most {} vertices, the graph has {} (--dot has no cap)", MAX_VERTICES, n));
+    }
+    Ok(())
+}
+
+/* The valid edges once each, (u, v) with u < v. */
+fn simple_edges(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut simple: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    simple.sort_unstable();
+    simple.dedup();
+    simple
+}
+
+fn circle(n: usize) -> Vec<(f64, f64)> {
+    (0..n).map(|v| {
+        let a = 2.0 * std::f64::consts::PI * v as f64 / n.max(1) as f64;
+        (0.5 + 0.5 * a.cos(), 0.5 + 0.5 * a.sin())
+    }).collect()
+}
+
+/* Fruchterman-Reingold around the unit square: repulsion k^2/d between
+ * all pairs, attraction d^2/k along edges and GRAVITY towards the centre
+ * instead of walls (which pile vertices up in the corners), steps capped
+ * by a temperature falling linearly to 0. */
+fn force(n: usize, edges: &[(usize, usize)]) -> Vec<(f64, f64)> {
+    let mut rng = Rng::new(1);
+    let mut pos: Vec<(f64, f64)> = (0..n).map(|_| (rng.next_f64(), rng.next_f64())).collect();
+    let k = (1.0 / n.max(1) as f64).sqrt();
+    let mut shift = vec![(0.0, 0.0); n];
+    for round in 0..FORCE_ROUNDS {
+        let t = 0.1 * (1.0 - round as f64 / FORCE_ROUNDS as f64);
+        for s in shift.iter_mut() {
+            *s = (0.0, 0.0);
+        }
+        for u in 0..n {
+            for v in u + 1..n {
+                let (dx, dy) = (pos[u].0 - pos[v].0, pos[u].1 - pos[v].1);
+                let d2 = (dx * dx + dy * dy).max(1e-9);
+                let f = k * k / d2;
+                shift[u].0 += dx * f;
+                shift[u].1 += dy * f;
+                shift[v].0 -= dx * f;
+                shift[v].1 -= dy * f;
+            }
+        }
+        for &(u, v) in edges {
+            let (dx, dy) = (pos[u].0 - pos[v].0, pos[u].1 - pos[v].1);
+            let f = (dx * dx + dy * dy).sqrt() / k;
+            shift[u].0 -= dx * f;
+            shift[u].1 -= dy * f;
+            shift[v].0 += dx * f;
+            shift[v].1 += dy * f;
+        }
+        for v in 0..n {
+            let (sx, sy) = (shift[v].0 - GRAVITY * (pos[v].0 - 0.5), shift[v].1 - GRAVITY * (pos[v].1 - 0.5));
+            let len = (sx * sx + sy * sy).sqrt().max(1e-9);
+            let step = len.min(t);
+            pos[v].0 += sx / len * step;
+            pos[v].1 += sy / len * step;
+        }
+    }
+    pos
+}
+
+/* Left vertices down the left column in id order, each right vertex on
+ * its mate's row and the free ones below. */
+fn columns(left: usize, n: usize, mate: &[Option<usize>]) -> Vec<(f64, f64)> {
+    let mut pos = vec![(0.0, 0.0); n];
+    let mut row = vec![0; n];
+    for u in 0..left {
+        row[u] = u;
+        if let Some(v) = mate[u] {
+            row[v] = u;
+        }
+    }
+    let mut next = left;
+    for v in left..n {
+        if mate[v].is_none() {
+            row[v] = next;
+            next += 1;
+        }
+    }
+    let rows = next.max(n - left).max(2);
+    for v in 0..n {
+        let x = if v < left { 0.2 } else { 0.8 };
+        pos[v] = (x, row[v] as f64 / (rows - 1) as f64);
+    }
+    pos
+}
+
+/* Unit-square positions, stretched to fill it. */
+pub fn positions(layout: Layout, n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)], left: Option<usize>) -> Vec<(f64, f64)> {
+    let mut mate = vec![None; n];
+    for &(u, v) in pairs {
+        mate[u] = Some(v);
+        mate[v] = Some(u);
+    }
+    let mut pos = match (left, layout) {
+        (Some(l), _) => columns(l, n, &mate),
+        (None, Layout::Circle) => circle(n),
+        (None, Layout::Force) => force(n, &simple_edges(n, edges)),
+    };
+    if n > 1 {
+        let (x0, x1) = pos.iter().fold((f64::MAX, f64::MIN), |(a, b), p| (a.min(p.0), b.max(p.0)));
+        let (y0, y1) = pos.iter().fold((f64::MAX, f64::MIN), |(a, b), p| (a.min(p.1), b.max(p.1)));
+        for p in pos.iter_mut() {
+            p.0 = if x1 > x0 { (p.0 - x0) / (x1 - x0) } else { 0.5 };
+            p.1 = if y1 > y0 { (p.1 - y0) / (y1 - y0) } else { 0.5 };
+        }
+    } else if n == 1 {
+        pos[0] = (0.5, 0.5);
+    }
+    pos
+}
+
+/* The picture as an SVG document. `left` numbers a bipartite graph as
+ * Hopcroft-Karp does (right vertex v is left + v), labeled l0.., r0... */
+pub fn write_svg(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)], left: Option<usize>,
+    layout: Layout,
+) -> io::Result<()> {
+    let pos = positions(layout, n, edges, pairs, left);
+    let at = |v: usize| (MARGIN + pos[v].0 * SIZE, MARGIN + pos[v].1 * SIZE);
+    let radius = (SIZE / (n.max(1) as f64).sqrt() * 0.08).clamp(2.0, 12.0);
+    let mut matched = vec![false; n];
+    for &(u, v) in pairs {
+        matched[u] = true;
+        matched[v] = true;
+    }
+    let mut is_pair: Vec<(usize, usize)> = pairs.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    is_pair.sort_unstable();
+
+    let side = SIZE + 2.0 * MARGIN;
+    writeln!(out, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+             side, side + 20.0, side, side + 20.0)?;
+    writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"white\"/>")?;
+    writeln!(out, "<text x=\"{}\" y=\"{}\" font-family=\"sans-serif\" font-size=\"14\">{} vertices, {} pairs</text>",
+             MARGIN, side + 12.0, n, pairs.len())?;
+    writeln!(out, "<g stroke=\"#999\" stroke-width=\"1\">")?;
+    let simple = simple_edges(n, edges);
+    for &(u, v) in simple.iter().filter(|e| is_pair.binary_search(e).is_err()) {
+        let ((x1, y1), (x2, y2)) = (at(u), at(v));
+        writeln!(out, "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>", x1, y1, x2, y2)?;
+    }
+    writeln!(out, "</g>")?;
+    writeln!(out, "<g stroke=\"red\" stroke-width=\"4\">")?;
+    for &(u, v) in &is_pair {
+        let ((x1, y1), (x2, y2)) = (at(u), at(v));
+        writeln!(out, "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>", x1, y1, x2, y2)?;
+    }
+    writeln!(out, "</g>")?;
+    writeln!(out, "<g stroke=\"black\" stroke-width=\"1\">")?;
+    for (v, &m) in matched.iter().enumerate() {
+        let (x, y) = at(v);
+        let fill = if m { "#ccc" } else { "white" };
+        writeln!(out, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"{}\"/>", x, y, radius, fill)?;
+    }
+    writeln!(out, "</g>")?;
+    if n <= LABELED_VERTICES {
+        writeln!(out, "<g font-family=\"sans-serif\" font-size=\"{:.0}\" text-anchor=\"middle\" dominant-baseline=\"central\">",
+                 radius.max(8.0))?;
+        for v in 0..n {
+            let (x, y) = at(v);
+            let name = match left {
+                Some(l) if v < l => format!("l{}", v),
+                Some(l) => format!("r{}", v - l),
+                None => v.to_string(),
+            };
+            writeln!(out, "<text x=\"{:.1}\" y=\"{:.1}\">{}</text>", x, y - radius - 6.0, name)?;
+        }
+        writeln!(out, "</g>")?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/* write_svg to the file `path`. */
+pub fn save(
+    path: &str, n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)], left: Option<usize>, layout: Layout,
+) -> Result<(), String> {
+    check_size(n)?;
+    let mut w = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+    write_svg(&mut w, n, edges, pairs, left, layout).and_then(|_| w.flush()).map_err(|e| format!("{}: {}", path, e))
+}
@@ -0,0 +1,180 @@
+/*
+ * Minimum-weight T-join for non-negative edge weights.
+ *
+ * A T-join is an edge set whose odd-degree vertices are exactly T (|T|
+ * even in every component). With w >= 0 a minimum one is the symmetric
+ * difference of shortest paths between the pairs of a minimum-weight
+ * perfect matching of T under shortest-path distances (Edmonds-Johnson
+ * 1973):
+ *   1. Dijkstra from every vertex of T,
+ *   2. minimum-weight perfect matching on the complete graph over T,
+ *   3. one shortest path per matched pair, edges kept with odd multiplicity.
+ *
+ * Cost: |T| Dijkstra runs plus O(|T|^3) for the matching.
+ *
+ * check_t_join verifies a join against T; load_vertex_set reads T from a
+ * file for `combi postman --t-set`.
+ *
+ * Included via #[path = "../../common/rust/t_join.rs"] mod t_join;
+ * next to weighted_blossom.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::error::Error;
+use std::fs;
+
+use super::weighted_blossom;
+
+const NIL: usize = usize::MAX;
+const UNREACHED: i64 = i64::MAX;
+
+pub struct TJoin {
+    pub edges: Vec<usize>, /* indices into the input edge list, sorted */
+    pub weight: i64,
+    pub pairs: Vec<(usize, usize)>, /* the matched pairs of T */
+}
+
+/* Distances from src and the edge each vertex was reached by. */
+fn dijkstra(adj: &[Vec<(usize, usize)>], edges: &[(usize, usize, i64)], src: usize) -> (Vec<i64>, Vec<usize>) {
+    let n = adj.len();
+    let mut dist = vec![UNREACHED; n];
+    let mut via = vec![NIL; n];
+    let mut heap = BinaryHeap::new();
+    dist[src] = 0;
+    heap.push(Reverse((0i64, src)));
+    while let Some(Reverse((d, v))) = heap.pop() {
+        if d > dist[v] { continue; }
+        for &(w, k) in &adj[v] {
+            let nd = d.saturating_add(edges[k].2);
+            if nd < dist[w] {
+                dist[w] = nd;
+                via[w] = k;
+                heap.push(Reverse((nd, w)));
+            }
+        }
+    }
+    (dist, via)
+}
+
+/* Minimum-weight T-join of (n, edges). Self-loops never help and are
+ * skipped; out-of-range endpoints are ignored. Errors on a negative weight
+ * or when some component holds an odd number of T vertices. */
+pub fn min_t_join(n: usize, edges: &[(usize, usize, i64)], t: &[usize]) -> Result<TJoin, String> {
+    let mut adj = vec![Vec::new(); n];
+    for (k, &(u, v, w)) in edges.iter().enumerate() {
+        if u >= n || v >= n || u == v { continue; }
+        if w < 0 {
+            return Err(format!("edge ({}, {}) has negative weight {}", u, v, w));
+        }
+        adj[u].push((v, k));
+        adj[v].push((u, k));
+    }
+    let mut t = t.to_vec();
+    t.sort_unstable();
+    t.dedup();
+    if let Some(&bad) = t.iter().find(|&&x| x >= n) {
+        return Err(format!("T vertex {} out of range (n = {})", bad, n));
+    }
+
+    /* Complete graph on T, weighted by distance (unreachable pairs left out). */
+    let mut complete = Vec::new();
+    for (i, &s) in t.iter().enumerate() {
+        let (dist, _) = dijkstra(&adj, edges, s);
+        for (j, &x) in t.iter().enumerate().skip(i + 1) {
+            if dist[x] != UNREACHED {
+                complete.push((i, j, dist[x]));
+            }
+        }
+    }
+    let matching = weighted_blossom::min_weight_perfect_matching(t.len(), &complete)
+        .ok_or("T cannot be paired up: some component has an odd number of T vertices")?;
+
+    let mut parity = vec![false; edges.len()];
+    let mut pairs = Vec::with_capacity(matching.len());
+    for &(i, j) in &matching {
+        let (s, x) = (t[i], t[j]);
+        pairs.push((s, x));
+        let (_, via) = dijkstra(&adj, edges, s);
+        let mut v = x;
+        while v != s {
+            let k = via[v];
+            parity[k] = !parity[k];
+            v = if edges[k].0 == v { edges[k].1 } else { edges[k].0 };
+        }
+    }
+    let join: Vec<usize> = (0..edges.len()).filter(|&k| parity[k]).collect();
+    let weight = join.iter().map(|&k| edges[k].2).sum();
+    Ok(TJoin { edges: join, weight, pairs })
+}
+
+/* Problems with `join` as a minimum-T-join candidate for (n, edges, t):
+ * its odd-degree vertices must be exactly T, its weight the sum of its
+ * edges, and its pairs must cover T once each (empty if none). */
+pub fn check_t_join(n: usize, edges: &[(usize, usize, i64)], t: &[usize], join: &TJoin) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut odd = vec![false; n];
+    let mut weight = 0i64;
+    for &k in &join.edges {
+        if k >= edges.len() {
+            errors.push(format!("Edge index {} out of range!", k));
+            continue;
+        }
+        let (u, v, w) = edges[k];
+        odd[u] = !odd[u];
+        odd[v] = !odd[v];
+        weight += w;
+    }
+    if join.edges.windows(2).any(|w| w[0] >= w[1]) {
+        errors.push("Join edges are not sorted and distinct!".to_string());
+    }
+    let mut in_t = vec![false; n];
+    for &x in t {
+        if x < n { in_t[x] = true; }
+    }
+    if let Some(v) = (0..n).find(|&v| odd[v] != in_t[v]) {
+        errors.push(format!("Vertex {} has {} join degree but is {} T!", v,
+                            if odd[v] { "odd" } else { "even" }, if in_t[v] { "in" } else { "not in" }));
+    }
+    if weight != join.weight {
+        errors.push(format!("Join edges weigh {}, reported {}!", weight, join.weight));
+    }
+    let mut paired = vec![false; n];
+    for &(s, x) in &join.pairs {
+        for v in [s, x] {
+            if v >= n || !in_t[v] || paired[v] {
+                errors.push(format!("Pair ({}, {}) does not split T!", s, x));
+                break;
+            }
+            paired[v] = true;
+        }
+    }
+    if let Some(v) = (0..n).find(|&v| in_t[v] && !paired[v]) {
+        errors.push(format!("T vertex {} is not paired!", v));
+    }
+    errors
+}
+
+/* T-set file: vertex ids separated by whitespace, '#' starts a comment.
+ * Returned sorted; a repeated or out-of-range id is an error. */
+pub fn load_vertex_set(filename: &str, n: usize) -> Result<Vec<usize>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut listed = vec![false; n];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        for tok in line.split_whitespace() {
+            let v: usize = tok.parse()
+                .map_err(|_| format!("{}:{}: expected a vertex id, got '{}'", filename, i + 1, tok))?;
+            if v >= n {
+                return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, i + 1, v, n).into());
+            }
+            if listed[v] {
+                return Err(format!("{}:{}: vertex {} listed twice", filename, i + 1, v).into());
+            }
+            listed[v] = true;
+        }
+    }
+    Ok((0..n).filter(|&v| listed[v]).collect())
+}
@@ -0,0 +1,250 @@
+/*
+ * Tie-breaking among maximum matchings: the lexicographically smallest,
+ * for `combi solve --tie-break lex`. (A secondary weight is the weighted
+ * blossom's: maximum or minimum weight among the maximum-cardinality
+ * matchings, --tie-break heavier|lighter.)
+ *
+ * Matchings are compared as sorted lists of pairs (u < v). Between two of
+ * the same size the smaller is the one holding the smallest pair of their
+ * symmetric difference, so a greedy pass over the pairs in order finds the
+ * smallest: an edge is fixed when some maximum matching contains it and
+ * every edge fixed before it. An edge rejected once stays rejected, as the
+ * fixed set only grows, so one pass suffices.
+ *
+ * The pass refines a maximum matching M in place. Invariant: M holds the
+ * fixed pairs and is maximum on the vertices not fixed. For the next edge
+ * uv with u, v not fixed and not matched to each other, take uv into M and
+ * drop the pairs at u and v; with one of them free this keeps the size,
+ * with both matched (to a and b) one pair is lost, and it comes back iff an
+ * augmenting path exists among the vertices not fixed. Such a path ends at
+ * a or b, as M was maximum there, so an Edmonds search from a, then from b
+ * (blossom bases in a union-find, reset only where the search went)
+ * decides; a failed search leaves M as it was, and uv is rejected.
+ *
+ * Cost: one search per rejected edge with both ends matched, O(E) each,
+ * so O(V E) at worst; matched edges and edges at a free vertex need none.
+ *
+ * Included via #[path = "../../common/rust/tie_break.rs"] mod tie_break;
+ * next to dsu.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+
+use super::dsu::Dsu;
+
+pub const NIL: usize = usize::MAX;
+const UNSEEN: u8 = 0;
+const EVEN: u8 = 1;
+const ODD: u8 = 2;
+
+/* A maximum matching with some vertices fixed, and the Edmonds search over
+ * the rest; enumerate_matchings.rs drives it too. */
+pub struct Refiner {
+    pub adj: Vec<Vec<usize>>, /* distinct neighbours, sorted */
+    pub mate: Vec<usize>,
+    pub fixed: Vec<bool>,
+    pub log: Vec<(usize, usize)>, /* (vertex, old mate) of each augmentation, if logging */
+    pub logging: bool,
+    /* search state, reset on the touched vertices only */
+    label: Vec<u8>,
+    parent: Vec<usize>,
+    base: Dsu,
+    mark: Vec<usize>,
+    stamp: usize,
+    touched: Vec<usize>,
+    queue: VecDeque<usize>,
+    path: Vec<usize>, /* the two tree paths of a blossom being contracted */
+}
+
+impl Refiner {
+    /* Self-loops, repeated edges and out-of-range endpoints are ignored. */
+    pub fn new(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Refiner {
+        let mut adj = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                adj[u].push(v);
+                adj[v].push(u);
+            }
+        }
+        for a in &mut adj {
+            a.sort_unstable();
+            a.dedup();
+        }
+        let mut r = Refiner {
+            adj,
+            mate: vec![NIL; n],
+            fixed: vec![false; n],
+            log: Vec::new(),
+            logging: false,
+            label: vec![UNSEEN; n],
+            parent: vec![NIL; n],
+            base: Dsu::new(n),
+            mark: vec![0; n],
+            stamp: 0,
+            touched: Vec::new(),
+            queue: VecDeque::new(),
+            path: Vec::new(),
+        };
+        for &(u, v) in matching {
+            r.pair(u, v);
+        }
+        r
+    }
+
+    fn touch(&mut self, v: usize, label: u8) {
+        if self.label[v] == UNSEEN { self.touched.push(v); }
+        self.label[v] = label;
+    }
+
+    /* The base of the blossom holding the bases of x and y in the tree. */
+    fn lca(&mut self, x: usize, y: usize) -> usize {
+        self.stamp += 1;
+        let mut a = self.base.find(x);
+        loop {
+            self.mark[a] = self.stamp;
+            if self.mate[a] == NIL { break; }
+            a = self.base.find(self.parent[self.mate[a]]);
+        }
+        let mut b = self.base.find(y);
+        while self.mark[b] != self.stamp {
+            b = self.base.find(self.parent[self.mate[b]]);
+        }
+        b
+    }
+
+    /* Walk the tree path from v up to the base b, linking it the other way
+     * round for the augmentation and collecting it for the contraction; its
+     * odd vertices become even and join the queue. The bases merge only
+     * after both paths: merged early, a sub-blossom on the path would stop
+     * the walk at its base. */
+    fn shrink(&mut self, mut v: usize, b: usize, mut child: usize) {
+        while self.base.find(v) != b {
+            let m = self.mate[v];
+            self.parent[v] = child;
+            child = m;
+            self.path.push(v);
+            self.path.push(m);
+            if self.label[m] == ODD {
+                self.label[m] = EVEN;
+                self.queue.push_back(m);
+            }
+            v = self.parent[m];
+        }
+    }
+
+    /* Edmonds search from the free vertex `root` over the vertices not
+     * fixed; augments M along the path found, if any. */
+    pub fn augment_from(&mut self, root: usize) -> bool {
+        for &v in &self.touched {
+            self.label[v] = UNSEEN;
+            self.parent[v] = NIL;
+            self.base.detach(v);
+        }
+        self.touched.clear();
+        self.queue.clear();
+        self.touch(root, EVEN);
+        self.queue.push_back(root);
+        while let Some(x) = self.queue.pop_front() {
+            for i in 0..self.adj[x].len() {
+                let y = self.adj[x][i];
+                if self.fixed[y] || self.label[y] == ODD || self.base.find(x) == self.base.find(y) {
+                    continue;
+                }
+                if self.label[y] == EVEN {
+                    let b = self.lca(x, y);
+                    self.shrink(x, b, y);
+                    self.shrink(y, b, x);
+                    while let Some(w) = self.path.pop() {
+                        self.base.union_into(w, b);
+                    }
+                } else if self.mate[y] == NIL {
+                    self.parent[y] = x;
+                    let mut v = y;
+                    while v != NIL {
+                        let pv = self.parent[v];
+                        let next = self.mate[pv];
+                        if self.logging {
+                            self.log.push((v, self.mate[v]));
+                            self.log.push((pv, next));
+                        }
+                        self.mate[v] = pv;
+                        self.mate[pv] = v;
+                        v = next;
+                    }
+                    return true;
+                } else {
+                    self.touch(y, ODD);
+                    self.parent[y] = x;
+                    let z = self.mate[y];
+                    self.touch(z, EVEN);
+                    self.queue.push_back(z);
+                }
+            }
+        }
+        false
+    }
+
+    pub fn pair(&mut self, u: usize, v: usize) {
+        self.mate[u] = v;
+        self.mate[v] = u;
+    }
+
+    /* Fix uv if some maximum matching holds it and the fixed pairs. */
+    fn try_fix(&mut self, u: usize, v: usize) -> bool {
+        let (a, b) = (self.mate[u], self.mate[v]);
+        if a == v {
+            self.fixed[u] = true;
+            self.fixed[v] = true;
+            return true;
+        }
+        if a != NIL && b != NIL {
+            self.mate[a] = NIL;
+            self.mate[b] = NIL;
+            self.pair(u, v);
+            self.fixed[u] = true;
+            self.fixed[v] = true;
+            if self.adj[a].binary_search(&b).is_ok() {
+                self.pair(a, b);
+                return true;
+            }
+            if self.augment_from(a) || self.augment_from(b) {
+                return true;
+            }
+            self.fixed[u] = false;
+            self.fixed[v] = false;
+            self.pair(u, a);
+            self.pair(v, b);
+            return false;
+        }
+        /* one end free: the pair at the other goes, the size stays */
+        for &x in &[a, b] {
+            if x != NIL { self.mate[x] = NIL; }
+        }
+        self.pair(u, v);
+        self.fixed[u] = true;
+        self.fixed[v] = true;
+        true
+    }
+}
+
+/* The lexicographically smallest maximum matching of (n, edges), from the
+ * maximum matching `matching`; pairs sorted, u < v. Self-loops, repeated
+ * edges and out-of-range endpoints are ignored. */
+pub fn lexicographic(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut r = Refiner::new(n, edges, matching);
+    for u in 0..n {
+        for i in 0..r.adj[u].len() {
+            let v = r.adj[u][i];
+            if r.fixed[u] { break; }
+            if v < u || r.fixed[v] { continue; }
+            r.try_fix(u, v);
+        }
+    }
+    let mut pairs: Vec<(usize, usize)> = (0..n).filter(|&u| r.mate[u] != NIL && u < r.mate[u])
+        .map(|u| (u, r.mate[u]))
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
@@ -0,0 +1,109 @@
+/*
+ * --time-limit SECONDS: anytime runs. Once the limit has passed a timer
+ * thread raises the solver's stop flag, the same one SIGINT raises in
+ * combi (interrupt.rs); the solver returns the matching of its last
+ * finished phase, which is valid but may not be maximum, and the report
+ * says so with a bound on how far from the maximum it can be.
+ *
+ * The bound: an augmenting path joins two free vertices of one connected
+ * component and matches both, so component C gains at most
+ * floor(F_C / 2) pairs, F_C its free vertices. Isolated vertices and
+ * components with a single free vertex add nothing, and a bound of 0
+ * proves the matching maximum after all.
+ *
+ * Included via #[path = "../../common/rust/time_limit.rs"] mod time_limit;
+ * next to args.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use super::args;
+
+pub const TIME_LIMIT_USAGE: &str = "[--time-limit SECONDS]";
+
+/* The stop flag for programs without one of their own (the standalone
+ * solvers); combi passes interrupt::STOP instead. */
+pub static EXPIRED: AtomicBool = AtomicBool::new(false);
+/* Whether the timer went off, whichever flag it raised. */
+static FIRED: AtomicBool = AtomicBool::new(false);
+
+/* --time-limit as a duration: a positive number of seconds, fractions
+ * allowed. */
+pub fn time_limit_of(args: &[String]) -> Result<Option<Duration>, String> {
+    let s = match args::value_of(args, "--time-limit") {
+        None => return Ok(None),
+        Some(s) => s,
+    };
+    match s.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() && secs < 1e9 => Ok(Some(Duration::from_secs_f64(secs))),
+        _ => Err(format!("invalid value for --time-limit: {} (expected a positive number of seconds)", s)),
+    }
+}
+
+/* Raises `flag` once `limit` has passed from now. */
+pub fn start(limit: Duration, flag: &'static AtomicBool) {
+    thread::spawn(move || {
+        thread::sleep(limit);
+        FIRED.store(true, Ordering::SeqCst);
+        flag.store(true, Ordering::SeqCst);
+    });
+}
+
+pub fn expired() -> bool {
+    FIRED.load(Ordering::SeqCst)
+}
+
+fn find(parent: &mut [usize], mut v: usize) -> usize {
+    while parent[v] != v {
+        parent[v] = parent[parent[v]];
+        v = parent[v];
+    }
+    v
+}
+
+/* (pairs the matching can still be short of the maximum, free vertices
+ * in components with another free vertex): floor(F_C / 2) summed over the
+ * components C of the valid edges (self-loops and out-of-range endpoints
+ * ignored, as by the solvers). */
+pub fn gap_bound(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> (usize, usize) {
+    let mut parent: Vec<usize> = (0..n).collect();
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            let (a, b) = (find(&mut parent, u), find(&mut parent, v));
+            if a != b { parent[a] = b; }
+        }
+    }
+    let mut matched = vec![false; n];
+    for &(u, v) in matching {
+        matched[u] = true;
+        matched[v] = true;
+    }
+    let mut free = vec![0usize; n];
+    for (v, &m) in matched.iter().enumerate() {
+        if !m {
+            let r = find(&mut parent, v);
+            free[r] += 1;
+        }
+    }
+    let bound = free.iter().map(|&f| f / 2).sum();
+    let open = free.iter().filter(|&&f| f >= 2).sum();
+    (bound, open)
+}
+
+/* The report line of a run the timer stopped, with its gap_bound. */
+pub fn write_report(out: &mut dyn Write, limit: Duration, gap: (usize, usize)) -> io::Result<()> {
+    let (bound, open) = gap;
+    if bound == 0 {
+        writeln!(out, "Time limit: {:.3} s reached; the matching is still maximum (no component has two free vertices)",
+                 limit.as_secs_f64())
+    } else {
+        writeln!(out, "Time limit: {:.3} s reached; the matching is valid but may not be maximum \
+                       (at most {} pairs short: {} free vertices share a component with another)",
+                 limit.as_secs_f64(), bound, open)
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * --trace FILE: every step a solver takes, one line each, for replaying
+ * (combi replay) and for diffing two solvers' runs on one graph.
+ *
+ * The solvers take a tracer (their `trace` field, or matchers::trace_on)
+ * and call it with an event name and its vertices:
+ *   matched u v          a pair of the matching the search starts from
+ *                        (greedy, --init-aug3 or --init-matching), before
+ *                        any other event
+ *   phase p              phase p is over (as counted by --progress)
+ *   blossom b v w        the edge v-w closed a blossom with base b (an
+ *                        even-even edge; in MV a bridge, b the petal's bud)
+ *   augment v0 v1 ... vk the matching was flipped along this path, k odd:
+ *                        v0 and vk free before, v1-v2, v3-v4, ...
+ *                        matched before, v0-v1, v2-v3, ... after
+ * and the run ends with `done s`, s the final matching size. Vertex ids
+ * are the solver's; Hopcroft-Karp numbers right vertex v as left + v.
+ * Lines starting with `#` are comments.
+ *
+ * The solvers do not build the paths themselves: begin() keeps a copy of
+ * the starting mates, and augmented(), given any vertex of a flipped path,
+ * walks it along the new mates and the copy's old ones and brings the
+ * copy up to date, in time linear in the path.
+ *
+ * replay() re-applies a trace to its graph and checks every event against
+ * the matching so far, so each intermediate matching is a legal one.
+ *
+ * Included via #[path = "../../common/rust/trace.rs"] mod trace;
+ */
+
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::sync::Mutex;
+
+pub const TRACE_USAGE: &str = "[--trace FILE]";
+
+/* An old mate of nobody. */
+const FREE: usize = usize::MAX;
+
+/* The trace file behind record(), and the first write error on it. */
+static WRITER: Mutex<Option<BufWriter<File>>> = Mutex::new(None);
+static ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+/* Starts writing record()'s events to `path`, after a `#` header line. */
+pub fn open(path: &str, header: &str) -> Result<(), String> {
+    let mut w = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+    writeln!(w, "# {}", header).map_err(|e| format!("{}: {}", path, e))?;
+    *WRITER.lock().unwrap() = Some(w);
+    Ok(())
+}
+
+/* The tracer behind --trace. */
+pub fn record(event: &str, vertices: &[usize]) {
+    let mut guard = WRITER.lock().unwrap();
+    if let Some(w) = guard.as_mut() {
+        let mut written = write!(w, "{}", event);
+        for v in vertices {
+            written = written.and_then(|_| write!(w, " {}", v));
+        }
+        if let Err(e) = written.and_then(|_| writeln!(w)) {
+            ERROR.lock().unwrap().get_or_insert(e.to_string());
+        }
+    }
+}
+
+/* Ends the trace with `done size` and closes the file. */
+pub fn finish(size: usize) -> Result<(), String> {
+    record("done", &[size]);
+    if let Some(mut w) = WRITER.lock().unwrap().take() {
+        w.flush().map_err(|e| e.to_string())?;
+    }
+    match ERROR.lock().unwrap().take() {
+        Some(e) => Err(format!("trace not fully written: {}", e)),
+        None => Ok(()),
+    }
+}
+
+/* The `matched` events of the starting matching; returns the copy of its
+ * mates that augmented() keeps. */
+pub fn begin(tracer: &dyn Fn(&str, &[usize]), n: usize, mate: &dyn Fn(usize) -> Option<usize>) -> Vec<usize> {
+    let before: Vec<usize> = (0..n).map(|v| mate(v).unwrap_or(FREE)).collect();
+    for (u, &v) in before.iter().enumerate() {
+        if v != FREE && u < v {
+            tracer("matched", &[u, v]);
+        }
+    }
+    before
+}
+
+/* From x, alternately along a new and an old mate (new_first) or the
+ * other way round, until an old free vertex is reached. */
+fn walk(x: usize, new_first: bool, mate: &dyn Fn(usize) -> Option<usize>, before: &[usize]) -> Vec<usize> {
+    let mut path = Vec::new();
+    let (mut v, mut new) = (x, new_first);
+    while path.len() <= before.len() {
+        let next = if new { mate(v) } else if before[v] == FREE { None } else { Some(before[v]) };
+        match next {
+            Some(w) => path.push(w),
+            None => break,
+        }
+        v = *path.last().unwrap();
+        new = !new;
+    }
+    path
+}
+
+/* The `augment` event of the path through x that was just flipped. */
+pub fn augmented(tracer: &dyn Fn(&str, &[usize]), x: usize, mate: &dyn Fn(usize) -> Option<usize>, before: &mut [usize]) {
+    let mut path = walk(x, false, mate, before);
+    path.reverse();
+    path.push(x);
+    path.extend(walk(x, true, mate, before));
+    for pair in path.chunks(2) {
+        if let [u, v] = *pair {
+            before[u] = v;
+            before[v] = u;
+        }
+    }
+    tracer("augment", &path);
+}
+
+/* What a replayed trace did. */
+pub struct Replay {
+    pub start: usize,         /* pairs of the starting matching */
+    pub phases: usize,
+    pub blossoms: usize,
+    pub augmentations: usize,
+    pub longest: usize,       /* edges of the longest augmenting path */
+    pub size: usize,          /* of the final matching */
+}
+
+/* Re-applies the trace in `text` to the graph (n, edges). The first event
+ * that does not fit the matching so far is an error, with its line. */
+pub fn replay(n: usize, edges: &[(usize, usize)], text: &str) -> Result<Replay, String> {
+    let edge_set: HashSet<(usize, usize)> = edges.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    let is_edge = |u: usize, v: usize| edge_set.contains(&(u.min(v), u.max(v)));
+    let mut mate = vec![FREE; n];
+    let mut r = Replay { start: 0, phases: 0, blossoms: 0, augmentations: 0, longest: 0, size: 0 };
+    let mut done = None;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let at = |e: String| format!("line {}: {}", i + 1, e);
+        let mut words = line.split_whitespace();
+        let event = words.next().unwrap();
+        let ids: Vec<usize> = words.map(|w| w.parse().map_err(|_| at(format!("invalid vertex id '{}'", w))))
+            .collect::<Result<_, _>>()?;
+        if done.is_some() {
+            return Err(at("event after `done`".to_string()));
+        }
+        if event != "phase" && event != "done" {
+            if let Some(&v) = ids.iter().find(|&&v| v >= n) {
+                return Err(at(format!("vertex {} out of range (n = {})", v, n)));
+            }
+        }
+        match (event, ids.len()) {
+            ("matched", 2) => {
+                let (u, v) = (ids[0], ids[1]);
+                if r.phases + r.blossoms + r.augmentations > 0 {
+                    return Err(at("`matched` after the search started".to_string()));
+                }
+                if u == v || !is_edge(u, v) {
+                    return Err(at(format!("{}-{} is not an edge", u, v)));
+                }
+                if mate[u] != FREE || mate[v] != FREE {
+                    return Err(at(format!("{}-{} reuses a matched vertex", u, v)));
+                }
+                mate[u] = v;
+                mate[v] = u;
+                r.start += 1;
+            }
+            ("phase", 1) => {
+                if ids[0] != r.phases + 1 {
+                    return Err(at(format!("phase {} after phase {}", ids[0], r.phases)));
+                }
+                r.phases += 1;
+            }
+            ("blossom", 3) => {
+                let (v, w) = (ids[1], ids[2]);
+                if v == w || !is_edge(v, w) {
+                    return Err(at(format!("blossom edge {}-{} is not an edge", v, w)));
+                }
+                r.blossoms += 1;
+            }
+            ("augment", k) if k >= 2 && k % 2 == 0 => {
+                let mut seen = HashSet::new();
+                if let Some(&v) = ids.iter().find(|&&v| !seen.insert(v)) {
+                    return Err(at(format!("vertex {} twice on the path", v)));
+                }
+                if mate[ids[0]] != FREE || mate[ids[k - 1]] != FREE {
+                    return Err(at(format!("path ends {} and {} are not both free", ids[0], ids[k - 1])));
+                }
+                for j in 0..k - 1 {
+                    let (u, v) = (ids[j], ids[j + 1]);
+                    if !is_edge(u, v) {
+                        return Err(at(format!("{}-{} is not an edge", u, v)));
+                    }
+                    if (j % 2 == 1) != (mate[u] == v) {
+                        return Err(at(format!("{}-{} should {}be matched", u, v, if j % 2 == 1 { "" } else { "not " })));
+                    }
+                }
+                for pair in ids.chunks(2) {
+                    mate[pair[0]] = pair[1];
+                    mate[pair[1]] = pair[0];
+                }
+                r.augmentations += 1;
+                r.longest = r.longest.max(k - 1);
+            }
+            ("done", 1) => done = Some(ids[0]),
+            _ => return Err(at(format!("malformed event '{}'", line))),
+        }
+    }
+    r.size = r.start + r.augmentations;
+    match done {
+        None => Err("no `done` line: the trace is cut short".to_string()),
+        Some(s) if s != r.size => Err(format!("`done {}`, but the replayed matching has {} pairs", s, r.size)),
+        Some(_) => Ok(r),
+    }
+}
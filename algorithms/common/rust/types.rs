@@ -0,0 +1,93 @@
+/*
+ * Vertex and edge ids for the solvers, the one "none" sentinel, and the
+ * type of their tracer.
+ *
+ * The solvers grew with three conventions: i32 ids with -1 for "no mate /
+ * no parent" (Edmonds, Gabow, Micali-Vazirani, Hopcroft-Karp), usize ids
+ * with usize::MAX (most common modules), and i32::MAX for unreached BFS
+ * distances. Code moved between them needed casts at every use, and a
+ * missed one turned -1 into a huge index. Vertex and EdgeId fix the width
+ * in one place and NONE is the only sentinel.
+ *
+ * Vertex is u32: the per-vertex arrays stay the size they were as i32,
+ * and ids reach 2^32 - 2. For larger graphs set both aliases to u64 (or
+ * usize), and AtomicVertex, for the parallel Hopcroft-Karp phases, to
+ * AtomicU64; MAX_VERTICES and matchers::check_vertex_count follow.
+ *
+ * Indexing stays usize: ix(v) to index with a Vertex, vx(i) to store an
+ * index. The i32 helpers are for the Edmonds solvers, which keep i32
+ * inside (blossoms are numbered from n and their cycle arithmetic is
+ * signed) and convert at set_mate and solve; a negative value means "none".
+ *
+ * Included via #[path = "../../common/rust/types.rs"] mod types;
+ */
+
+#![allow(dead_code)]
+
+use std::sync::atomic::AtomicU32;
+
+pub type Vertex = u32;
+pub type EdgeId = u32;
+pub type AtomicVertex = AtomicU32;
+
+/* No vertex: a free vertex's mate, a root's parent, "not found". */
+pub const NONE: Vertex = Vertex::MAX;
+/* No edge. */
+pub const NO_EDGE: EdgeId = EdgeId::MAX;
+/* An unreached distance (BFS layers). */
+pub const UNREACHED: Vertex = Vertex::MAX;
+
+/* Vertex ids are 0..MAX_VERTICES; the last value is NONE. */
+pub const MAX_VERTICES: usize = NONE as usize;
+
+/* A solver's tracer, called with an event and its vertices (trace.rs). */
+pub type TraceSink = &'static (dyn Fn(&str, &[usize]) + Sync);
+
+#[inline(always)]
+pub fn ix(v: Vertex) -> usize {
+    v as usize
+}
+
+#[inline(always)]
+pub fn vx(i: usize) -> Vertex {
+    debug_assert!(i < MAX_VERTICES, "vertex id {} does not fit a Vertex", i);
+    i as Vertex
+}
+
+#[inline(always)]
+pub fn eid(i: usize) -> EdgeId {
+    debug_assert!(i < NO_EDGE as usize, "edge id {} does not fit an EdgeId", i);
+    i as EdgeId
+}
+
+/* The vertex, or None for NONE. */
+#[inline(always)]
+pub fn some(v: Vertex) -> Option<usize> {
+    if v == NONE { None } else { Some(v as usize) }
+}
+
+pub fn from_i32(x: i32) -> Vertex {
+    if x < 0 { NONE } else { x as Vertex }
+}
+
+pub fn to_i32(v: Vertex) -> i32 {
+    if v == NONE { -1 } else { v as i32 }
+}
+
+/* Edges for the i32 solvers (Edmonds); ids >= n become -1, which they
+ * drop, instead of wrapping into range. */
+pub fn edges_i32(n: usize, edges: &[(usize, usize)]) -> Vec<(i32, i32)> {
+    let id = |x: usize| if x < n { x as i32 } else { -1 };
+    edges.iter().map(|&(u, v)| (id(u), id(v))).collect()
+}
+
+/* mate[v] of the pairs, NONE for a free vertex: what the solvers'
+ * set_mate takes (--init-matching). */
+pub fn mate_of(n: usize, pairs: &[(usize, usize)]) -> Vec<Vertex> {
+    let mut mate = vec![NONE; n];
+    for &(u, v) in pairs {
+        mate[u] = vx(v);
+        mate[v] = vx(u);
+    }
+    mate
+}
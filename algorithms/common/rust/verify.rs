@@ -0,0 +1,222 @@
+/*
+ * Matching validation, shared by the solvers, combi and the tests.
+ *
+ * validate() checks a list of pairs against its graph and returns a
+ * ValidationReport: the problems found (ids out of range, pairs that are
+ * not edges, vertices in more than one pair), the matched vertices, and
+ * the edges left with both ends free. A matching with no such edge is
+ * maximal: nothing can be added to it without an augmenting path.
+ * Maximality is a status of its own, beside validity: a valid matching
+ * need not be maximal. Whether it is maximum is a third, set by whoever
+ * checked it independently (optimal): `--certify` on the cardinality
+ * solvers and `combi solve` builds the Tutte-Berge certificate
+ * (vertex_classes::certify), and `combi check --maximum` compares with a
+ * second solver.
+ *
+ * The report prints as the "=== Validation Report ===" block every binary
+ * ends with (print, through log.rs: the errors at error, the block at
+ * info; or write, to one stream), and serializes to one JSON
+ * object (to_json) for scripts: `--report-json FILE` on the solvers and
+ * on `combi check` writes it.
+ *
+ * Included via #[path = "../../common/rust/verify.rs"] mod verify;
+ * together with args.rs, json.rs and log.rs.
+ */
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{self, Write};
+
+use super::args;
+use super::json::Json;
+use super::log;
+
+pub const REPORT_USAGE: &str = "[--report-json FILE]";
+pub const CERTIFY_USAGE: &str = "[--certify]";
+
+pub struct ValidationReport {
+    pub size: usize,                   /* pairs in the matching */
+    pub matched: usize,                /* vertices in a pair, both sides */
+    pub sides: Option<(usize, usize)>, /* matched left and right vertices, bipartite only */
+    pub exposed_edges: usize,          /* distinct edges with both ends unmatched */
+    pub weight: Option<i64>,           /* set by the weighted solvers */
+    pub errors: Vec<String>,
+    pub extra: Vec<String>,            /* the caller's lines for the block, before the verdict */
+    pub optimal: Option<Result<String, String>>, /* an optimality check: how it passed, or why not */
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    pub fn is_maximal(&self) -> bool {
+        self.exposed_edges == 0
+    }
+
+    /* False only when an optimality check ran and failed. */
+    pub fn is_optimal(&self) -> bool {
+        !matches!(self.optimal, Some(Err(_)))
+    }
+
+    /* The errors, one "ERROR:" line each, and the report block, all to `out`. */
+    pub fn write(&self, out: &mut dyn Write) -> io::Result<()> {
+        for e in &self.errors {
+            writeln!(out, "ERROR: {}", e)?;
+        }
+        self.write_block(out)
+    }
+
+    /* Same, through log.rs: the standalone binaries' report, by default
+     * with the errors on stderr and the block on stdout. */
+    pub fn print(&self) {
+        for e in &self.errors {
+            log::error(format_args!("ERROR: {}", e));
+        }
+        let mut block = Vec::new();
+        let _ = self.write_block(&mut block);
+        let block = String::from_utf8_lossy(&block);
+        /* the sink ends the message with its own newline */
+        log::info(format_args!("{}", block.strip_suffix('\n').unwrap_or(&block)));
+    }
+
+    fn write_block(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "\n=== Validation Report ===")?;
+        writeln!(out, "Matching size: {}", self.size)?;
+        match self.sides {
+            Some((left, right)) => writeln!(out, "Matched vertices: {} left, {} right", left, right)?,
+            None => writeln!(out, "Matched vertices: {}", self.matched)?,
+        }
+        if let Some(w) = self.weight {
+            writeln!(out, "Matching weight: {}", w)?;
+        }
+        for line in &self.extra {
+            writeln!(out, "{}", line)?;
+        }
+        if self.is_maximal() {
+            writeln!(out, "Maximal: yes")?;
+        } else {
+            writeln!(out, "Maximal: NO ({} edge(s) with both ends unmatched)", self.exposed_edges)?;
+        }
+        match &self.optimal {
+            Some(Ok(how)) => writeln!(out, "Optimal: yes ({})", how)?,
+            Some(Err(why)) => writeln!(out, "Optimal: NO ({})", why)?,
+            None => {}
+        }
+        writeln!(out, "{}", if self.is_valid() { "VALIDATION PASSED" } else { "VALIDATION FAILED" })?;
+        writeln!(out, "=========================\n")
+    }
+
+    pub fn to_json(&self) -> Json {
+        let mut report = Json::obj()
+            .with("valid", self.is_valid())
+            .with("size", self.size)
+            .with("matched", self.matched);
+        if let Some((left, right)) = self.sides {
+            report = report.with("matched_left", left).with("matched_right", right);
+        }
+        report
+            .with("maximal", self.is_maximal())
+            .with("exposed_edges", self.exposed_edges)
+            .with("optimal", self.optimal.as_ref().map(|o| o.is_ok()))
+            .with("weight", self.weight)
+            .with("errors", self.errors.iter().map(|e| Json::from(e.as_str())).collect::<Vec<Json>>())
+    }
+}
+
+/* `pairs` as a matching of the graph: (n, edges) with bounds (n, n), or
+ * a bipartite one with bounds (left, right) and (left, right) pairs. */
+pub fn validate(bounds: (usize, usize), edges: &[(usize, usize)], pairs: &[(usize, usize)],
+                bipartite: bool) -> ValidationReport {
+    let key = |u: usize, v: usize| if bipartite { (u, v) } else { (u.min(v), u.max(v)) };
+    let mut sorted: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < bounds.0 && v < bounds.1 && (bipartite || u != v))
+        .map(|&(u, v)| key(u, v))
+        .collect();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut deg_left = vec![0usize; bounds.0];
+    let mut deg_right = vec![0usize; if bipartite { bounds.1 } else { 0 }];
+    let mut errors = Vec::new();
+    for &(u, v) in pairs {
+        if u >= bounds.0 || v >= bounds.1 {
+            errors.push(format!("Pair ({}, {}) out of range!", u, v));
+            continue;
+        }
+        if sorted.binary_search(&key(u, v)).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+        }
+        deg_left[u] += 1;
+        if bipartite { deg_right[v] += 1; } else { deg_left[v] += 1; }
+    }
+    let names = if bipartite { ["Left vertex", "Right vertex"] } else { ["Vertex", ""] };
+    for (name, deg) in names.iter().zip([&deg_left, &deg_right].iter()) {
+        for (x, &d) in deg.iter().enumerate() {
+            if d > 1 {
+                errors.push(format!("{} {} in {} edges!", name, x, d));
+            }
+        }
+    }
+
+    let free_right = |v: usize| if bipartite { deg_right[v] == 0 } else { deg_left[v] == 0 };
+    let exposed_edges = sorted.iter().filter(|&&(u, v)| deg_left[u] == 0 && free_right(v)).count();
+    let matched_left = deg_left.iter().filter(|&&d| d > 0).count();
+    let matched_right = deg_right.iter().filter(|&&d| d > 0).count();
+    ValidationReport {
+        size: pairs.len(),
+        matched: matched_left + matched_right,
+        sides: if bipartite { Some((matched_left, matched_right)) } else { None },
+        exposed_edges,
+        weight: None,
+        errors,
+        extra: Vec::new(),
+        optimal: None,
+    }
+}
+
+/* validate() for a general graph on n vertices. */
+pub fn validate_general(n: usize, edges: &[(usize, usize)], pairs: &[(usize, usize)]) -> ValidationReport {
+    validate((n, n), edges, pairs, false)
+}
+
+/* validate() for a weighted graph, with the matching's weight (weight_of)
+ * in the report. */
+pub fn validate_weighted(bounds: (usize, usize), edges: &[(usize, usize, i64)], pairs: &[(usize, usize)],
+                         bipartite: bool, heaviest: bool) -> ValidationReport {
+    let plain: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+    let mut report = validate(bounds, &plain, pairs, bipartite);
+    report.weight = Some(weight_of(edges, pairs, bipartite, heaviest));
+    report
+}
+
+/* Total weight of the pairs, each at its heaviest parallel edge, or its
+ * lightest when `heaviest` is false. A pair that is not an edge adds
+ * nothing; validate() reports it. */
+pub fn weight_of(edges: &[(usize, usize, i64)], pairs: &[(usize, usize)], bipartite: bool, heaviest: bool) -> i64 {
+    let key = |u: usize, v: usize| if bipartite { (u, v) } else { (u.min(v), u.max(v)) };
+    let mut sorted: Vec<(usize, usize, i64)> = edges.iter()
+        .map(|&(u, v, w)| { let (a, b) = key(u, v); (a, b, w) })
+        .collect();
+    sorted.sort_unstable();
+    let mut total = 0i64;
+    for &(u, v) in pairs {
+        let k = key(u, v);
+        let lo = sorted.partition_point(|e| (e.0, e.1) < k);
+        let hi = sorted.partition_point(|e| (e.0, e.1) <= k);
+        let weights = sorted[lo..hi].iter().map(|e| e.2);
+        let w = if heaviest { weights.max() } else { weights.min() };
+        total = total.saturating_add(w.unwrap_or(0));
+    }
+    total
+}
+
+/* --report-json FILE, if given: write the report there as one JSON line. */
+pub fn save_from_args(args: &[String], report: &Json) -> Result<(), String> {
+    if let Some(path) = args::value_of(args, "--report-json") {
+        fs::write(path, format!("{}\n", report)).map_err(|e| format!("{}: {}", path, e))?;
+        log::info(format_args!("Wrote the validation report to {}", path));
+    }
+    Ok(())
+}
@@ -0,0 +1,163 @@
+/*
+ * Which vertices every maximum matching covers, for `combi classify`: the
+ * Gallai-Edmonds decomposition (Dulmage-Mendelsohn, for a bipartite graph)
+ * read as one class per vertex.
+ *
+ * From a maximum matching M, dot.rs's blossoms() searches from every free
+ * vertex at once. D, its EVEN vertices, are those an even alternating path
+ * from a free vertex reaches, and each is missed by some maximum matching
+ * (swap the path). A, the ODD ones, and C, those never reached, are covered
+ * by every one. So a vertex is
+ *   - Always matched: in A or C, an assignment that is always served;
+ *   - Sometimes: in D, with a neighbour. Covered in M, it is missed after
+ *     the swap; free in M, its neighbour x is matched to some y, and
+ *     M - xy + vx is maximum and covers it;
+ *   - Never: isolated (self-loops do not count), in D alone.
+ * The decomposition does not depend on which maximum matching it starts
+ * from, and check_classification() verifies its identities, which also
+ * certify M maximum: the components of G[D] are odd, no edge joins D to C,
+ * M matches A into D and C within C, and the free vertices number the
+ * components of G[D] less |A| (the Tutte-Berge bound, met).
+ *
+ * Included via #[path = "../../common/rust/vertex_classes.rs"] mod vertex_classes;
+ * next to dot.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::dot::{self, Label};
+
+const NONE: usize = usize::MAX;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VertexClass {
+    Always,    /* matched in every maximum matching */
+    Sometimes, /* matched in some, missed by others */
+    Never,     /* matched in none */
+}
+
+impl VertexClass {
+    pub fn name(self) -> &'static str {
+        match self {
+            VertexClass::Always => "always",
+            VertexClass::Sometimes => "sometimes",
+            VertexClass::Never => "never",
+        }
+    }
+}
+
+pub struct Classification {
+    pub class: Vec<VertexClass>,
+    pub label: Vec<Label>, /* Even: D, Odd: A, Outside: C */
+}
+
+impl Classification {
+    /* The sizes of D, A and C. */
+    pub fn sizes(&self) -> (usize, usize, usize) {
+        let count = |l| self.label.iter().filter(|&&x| x == l).count();
+        (count(Label::Even), count(Label::Odd), count(Label::Outside))
+    }
+
+    pub fn count(&self, c: VertexClass) -> usize {
+        self.class.iter().filter(|&&x| x == c).count()
+    }
+}
+
+fn neighbours(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    adj
+}
+
+/* The class of every vertex of (n, edges), from its maximum matching
+ * `matching`. Self-loops, repeated edges and out-of-range endpoints are
+ * ignored. */
+pub fn vertex_classification(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Classification {
+    let (label, _) = dot::blossoms(n, edges, matching);
+    let adj = neighbours(n, edges);
+    let class = (0..n).map(|v| match label[v] {
+        Label::Odd | Label::Outside => VertexClass::Always,
+        Label::Even if adj[v].is_empty() => VertexClass::Never,
+        Label::Even => VertexClass::Sometimes,
+    }).collect();
+    Classification { class, label }
+}
+
+/* The number of components of G[D], or what breaks the Gallai-Edmonds
+ * identities for `matching`. */
+pub fn check_classification(
+    n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)], c: &Classification,
+) -> Result<usize, Vec<String>> {
+    let adj = neighbours(n, edges);
+    let mut mate = vec![NONE; n];
+    for &(u, v) in matching {
+        mate[u] = v;
+        mate[v] = u;
+    }
+    let mut errors = Vec::new();
+    let mut comp = vec![NONE; n];
+    let mut components = 0;
+    for s in (0..n).filter(|&v| c.label[v] == Label::Even) {
+        if comp[s] != NONE { continue; }
+        let mut stack = vec![s];
+        let mut size = 0;
+        comp[s] = components;
+        while let Some(v) = stack.pop() {
+            size += 1;
+            for &w in &adj[v] {
+                if c.label[w] == Label::Even && comp[w] == NONE {
+                    comp[w] = components;
+                    stack.push(w);
+                }
+            }
+        }
+        if size % 2 == 0 {
+            errors.push(format!("the component of D at {} has {} vertices, an even number", s, size));
+        }
+        components += 1;
+    }
+    for v in 0..n {
+        let m = mate[v];
+        match c.label[v] {
+            Label::Even => {
+                if let Some(&w) = adj[v].iter().find(|&&w| c.label[w] == Label::Outside) {
+                    errors.push(format!("edge ({}, {}) joins D to C", v, w));
+                }
+            }
+            Label::Odd if m == NONE || c.label[m] != Label::Even => {
+                errors.push(format!("vertex {} of A is not matched into D", v));
+            }
+            Label::Outside if m == NONE || c.label[m] != Label::Outside => {
+                errors.push(format!("vertex {} of C is not matched within C", v));
+            }
+            _ => {}
+        }
+    }
+    let free = mate.iter().filter(|&&m| m == NONE).count();
+    let (_, a, _) = c.sizes();
+    if free + a != components {
+        errors.push(format!("{} free vertices, but {} components of D less {} of A", free, components, a));
+    }
+    if errors.is_empty() { Ok(components) } else { Err(errors) }
+}
+
+/* The Tutte-Berge certificate that the valid matching `matching` is
+ * maximum, for the "Optimal:" status of verify.rs: Ok with the odd set
+ * and the odd components it leaves, or the first identity that fails.
+ * It is built from the matching alone, so it does not trust the solver
+ * that found it. */
+pub fn certify(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Result<String, String> {
+    let c = vertex_classification(n, edges, matching);
+    match check_classification(n, edges, matching, &c) {
+        Ok(components) => {
+            let (_, a, _) = c.sizes();
+            Ok(format!("Tutte-Berge certificate: |A| = {}, {} odd components of G - A", a, components))
+        }
+        Err(errors) => Err(format!("no Tutte-Berge certificate: {}", errors[0])),
+    }
+}
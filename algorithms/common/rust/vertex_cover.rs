@@ -0,0 +1,422 @@
+/*
+ * Minimum vertex cover (equivalently maximum independent set) for general
+ * graphs: matching-based kernelization, then exact branching.
+ *
+ * Kernel (Nemhauser-Trotter 1975). The LP relaxation of vertex cover has a
+ * half-integral optimum that is read off a minimum vertex cover C of the
+ * bipartite double cover H (v_L v'_R and v'_L v_R for every edge vv'):
+ *     x_v = (|{v_L, v_R} & C|) / 2,      |C| = nu(H) by Konig.
+ * Some minimum cover of G contains V1 = {x_v = 1} and avoids V0 = {x_v = 0}
+ * (V0 with its neighbourhood V1 is a crown), so only G[V1/2] is left, with
+ * |V1/2| <= 2 OPT. nu(H) comes from Hopcroft-Karp.
+ *
+ * Branching on the kernel, one component at a time:
+ *   - degree 0: drop; degree 1: take the neighbour;
+ *   - all degrees 2: cycles, take every other vertex;
+ *   - otherwise branch on a maximum-degree v: v, or all of N(v).
+ * A maximal matching of what is left bounds the rest from below.
+ *
+ * Bipartite graphs need none of this: konig_cover reads a minimum cover
+ * off a maximum matching. For graphs too large to branch on, approx_cover
+ * takes the kernel's forced vertices plus both ends of a maximal matching
+ * of the kernel, at most 2 OPT, and then drops vertices whose neighbours
+ * are all in the cover.
+ *
+ * Included via #[path = "../../common/rust/vertex_cover.rs"] mod vertex_cover;
+ * next to hopcroft_karp.rs.
+ */
+
+#![allow(dead_code)]
+
+use super::hopcroft_karp::HopcroftKarp;
+
+const NIL: usize = usize::MAX;
+
+/* Nemhauser-Trotter partition of the vertices. */
+pub struct Kernel {
+    pub forced: Vec<usize>,   /* x = 1 (and self-loop vertices): in the cover */
+    pub excluded: Vec<usize>, /* x = 0: left out */
+    pub kernel: Vec<usize>,   /* x = 1/2: still to decide */
+    pub lp_bound: usize,      /* ceil of the LP optimum, a lower bound on OPT */
+}
+
+/* Minimum cover of a bipartite graph, by side. */
+pub struct BipartiteCover {
+    pub left: Vec<usize>,
+    pub right: Vec<usize>,
+    pub matching_size: usize, /* equals left.len() + right.len() (Konig) */
+}
+
+pub struct Approximation {
+    pub cover: Vec<usize>,  /* sorted */
+    pub lower_bound: usize, /* max(LP bound, forced + maximal matching) */
+}
+
+pub struct Branching {
+    pub cover: Option<Vec<usize>>, /* sorted; None when no cover fits the budget */
+    pub nodes: u64,                /* branching nodes explored */
+}
+
+/* Simple adjacency without self-loops or out-of-range ends, plus the
+ * vertices that carry a self-loop. */
+fn adjacency(n: usize, edges: &[(usize, usize)]) -> (Vec<Vec<usize>>, Vec<bool>) {
+    let mut adj = vec![Vec::new(); n];
+    let mut looped = vec![false; n];
+    for &(u, v) in edges {
+        if u >= n || v >= n { continue; }
+        if u == v {
+            looped[u] = true;
+        } else {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    (adj, looped)
+}
+
+/* Konig: with Z the vertices reachable from free left vertices by
+ * alternating paths, (L \ Z) + (R & Z) is a minimum cover. Returns the
+ * cover as flags per side, and the matching size. */
+fn konig(left: usize, right: usize, edges: &[(usize, usize)]) -> (Vec<bool>, Vec<bool>, usize) {
+    let mut hk = HopcroftKarp::new(left, right, edges);
+    let matching = hk.maximum_matching(1);
+    let mut adj = vec![Vec::new(); left];
+    for &(u, v) in edges {
+        if u < left && v < right { adj[u].push(v); }
+    }
+    let mut pair_left = vec![NIL; left];
+    let mut pair_right = vec![NIL; right];
+    for &(u, v) in &matching {
+        pair_left[u] = v;
+        pair_right[v] = u;
+    }
+    let mut z_left = vec![false; left];
+    let mut z_right = vec![false; right];
+    let mut stack: Vec<usize> = (0..left).filter(|&u| pair_left[u] == NIL).collect();
+    for &u in &stack { z_left[u] = true; }
+    while let Some(u) = stack.pop() {
+        for &v in &adj[u] {
+            if z_right[v] { continue; }
+            z_right[v] = true;
+            let w = pair_right[v];
+            if w != NIL && !z_left[w] {
+                z_left[w] = true;
+                stack.push(w);
+            }
+        }
+    }
+    let in_left = z_left.iter().map(|&z| !z).collect();
+    (in_left, z_right, matching.len())
+}
+
+/* Minimum vertex cover of a bipartite graph (ids per side). */
+pub fn konig_cover(left: usize, right: usize, edges: &[(usize, usize)]) -> BipartiteCover {
+    let (in_left, in_right, matching_size) = konig(left, right, edges);
+    BipartiteCover {
+        left: (0..left).filter(|&u| in_left[u]).collect(),
+        right: (0..right).filter(|&v| in_right[v]).collect(),
+        matching_size,
+    }
+}
+
+/* Edges of a bipartite graph that the cover misses. */
+pub fn check_bipartite_cover(left: usize, right: usize, edges: &[(usize, usize)], cover: &BipartiteCover) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut in_left = vec![false; left];
+    let mut in_right = vec![false; right];
+    for &u in &cover.left {
+        if u < left { in_left[u] = true; } else { errors.push(format!("Left cover vertex {} out of range!", u)); }
+    }
+    for &v in &cover.right {
+        if v < right { in_right[v] = true; } else { errors.push(format!("Right cover vertex {} out of range!", v)); }
+    }
+    let missed: Vec<&(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < left && v < right && !in_left[u] && !in_right[v]).collect();
+    if let Some(&&(u, v)) = missed.first() {
+        errors.push(format!("{} edge(s) uncovered, e.g. ({}, {})!", missed.len(), u, v));
+    }
+    if cover.left.len() + cover.right.len() != cover.matching_size {
+        errors.push(format!("Cover of {} vertices but the matching has {} edges!",
+                            cover.left.len() + cover.right.len(), cover.matching_size));
+    }
+    errors
+}
+
+/* LP kernel. A vertex with a self-loop must be in every cover; it is forced
+ * before the LP sees the rest of the graph. */
+pub fn lp_kernel(n: usize, edges: &[(usize, usize)]) -> Kernel {
+    let (adj, looped) = adjacency(n, edges);
+    let mut h_edges = Vec::new();
+    for u in 0..n {
+        if looped[u] { continue; }
+        for &v in &adj[u] {
+            if !looped[v] { h_edges.push((u, v)); }
+        }
+    }
+    let (in_left, in_right, _) = konig(n, n, &h_edges);
+
+    let mut k = Kernel { forced: Vec::new(), excluded: Vec::new(), kernel: Vec::new(), lp_bound: 0 };
+    for v in 0..n {
+        if looped[v] {
+            k.forced.push(v);
+            continue;
+        }
+        match in_left[v] as usize + in_right[v] as usize {
+            0 => k.excluded.push(v),
+            1 => k.kernel.push(v),
+            _ => k.forced.push(v),
+        }
+    }
+    k.lp_bound = k.forced.len() + k.kernel.len().div_ceil(2);
+    k
+}
+
+/* Exact search on an induced subgraph (local ids), best-first pruning. */
+struct Search<'a> {
+    adj: &'a [Vec<usize>],
+    alive: Vec<bool>,
+    deg: Vec<usize>,
+    trail: Vec<(usize, bool)>, /* removed vertex, whether it joined the cover */
+    cover: Vec<usize>,
+    best: Option<Vec<usize>>,
+    limit: usize, /* only covers smaller than this are still wanted */
+    nodes: u64,
+}
+
+impl<'a> Search<'a> {
+    fn remove(&mut self, v: usize, take: bool) {
+        self.alive[v] = false;
+        for &w in &self.adj[v] {
+            if self.alive[w] { self.deg[w] -= 1; }
+        }
+        if take { self.cover.push(v); }
+        self.trail.push((v, take));
+    }
+
+    fn undo(&mut self, mark: usize) {
+        while self.trail.len() > mark {
+            let (v, take) = self.trail.pop().unwrap();
+            if take { self.cover.pop(); }
+            for &w in &self.adj[v] {
+                if self.alive[w] { self.deg[w] += 1; }
+            }
+            self.alive[v] = true;
+        }
+    }
+
+    /* Degree 0 and degree 1 rules to a fixpoint. */
+    fn reduce(&mut self, verts: &[usize]) {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &v in verts {
+                if !self.alive[v] || self.deg[v] > 1 { continue; }
+                if self.deg[v] == 1 {
+                    let w = *self.adj[v].iter().find(|&&w| self.alive[w]).unwrap();
+                    self.remove(w, true);
+                }
+                self.remove(v, false);
+                changed = true;
+            }
+        }
+    }
+
+    /* Greedy maximal matching size of the alive part. */
+    fn matching_bound(&self, verts: &[usize]) -> usize {
+        let mut used = vec![false; self.adj.len()];
+        let mut size = 0;
+        for &v in verts {
+            if !self.alive[v] || used[v] { continue; }
+            if let Some(&w) = self.adj[v].iter().find(|&&w| self.alive[w] && !used[w]) {
+                used[v] = true;
+                used[w] = true;
+                size += 1;
+            }
+        }
+        size
+    }
+
+    /* Every alive vertex has degree 2: walk each cycle, take every other
+     * vertex plus the first one when the length is odd. */
+    fn take_cycles(&mut self, verts: &[usize]) {
+        for &s in verts {
+            if !self.alive[s] { continue; }
+            let mut cycle = vec![s];
+            let (mut prev, mut cur) = (s, self.adj[s].iter().cloned().find(|&w| self.alive[w]).unwrap());
+            while cur != s {
+                cycle.push(cur);
+                let next = self.adj[cur].iter().cloned().find(|&w| self.alive[w] && w != prev).unwrap();
+                prev = cur;
+                cur = next;
+            }
+            let odd = cycle.len() % 2 == 1;
+            for (i, &v) in cycle.iter().enumerate() {
+                self.remove(v, i % 2 == 1 || (i == 0 && odd));
+            }
+        }
+    }
+
+    fn go(&mut self, verts: &[usize]) {
+        self.nodes += 1;
+        let mark = self.trail.len();
+        self.reduce(verts);
+        if self.cover.len() + self.matching_bound(verts) >= self.limit {
+            self.undo(mark);
+            return;
+        }
+        let mut pick = NIL;
+        for &v in verts {
+            if self.alive[v] && (pick == NIL || self.deg[v] > self.deg[pick]) { pick = v; }
+        }
+        if pick == NIL || self.deg[pick] == 2 {
+            if pick != NIL { self.take_cycles(verts); }
+            if self.cover.len() < self.limit {
+                self.limit = self.cover.len();
+                self.best = Some(self.cover.clone());
+            }
+            self.undo(mark);
+            return;
+        }
+
+        let branch = self.trail.len();
+        self.remove(pick, true);
+        self.go(verts);
+        self.undo(branch);
+
+        let nbrs: Vec<usize> = self.adj[pick].iter().cloned().filter(|&w| self.alive[w]).collect();
+        for w in nbrs {
+            self.remove(w, true);
+        }
+        self.remove(pick, false);
+        self.go(verts);
+        self.undo(mark);
+    }
+}
+
+/* Minimum cover of G[kernel.kernel] within `budget` vertices (None: no
+ * limit), solved one connected component at a time. */
+pub fn solve_kernel(n: usize, edges: &[(usize, usize)], kernel: &Kernel, budget: Option<usize>) -> Branching {
+    let mut local = vec![NIL; n];
+    for (i, &v) in kernel.kernel.iter().enumerate() {
+        local[v] = i;
+    }
+    let kn = kernel.kernel.len();
+    let mut adj = vec![Vec::new(); kn];
+    for &(u, v) in edges {
+        if u >= n || v >= n || u == v || local[u] == NIL || local[v] == NIL { continue; }
+        adj[local[u]].push(local[v]);
+        adj[local[v]].push(local[u]);
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+
+    let deg: Vec<usize> = adj.iter().map(|a| a.len()).collect();
+    let mut s = Search {
+        adj: &adj, alive: vec![true; kn], deg, trail: Vec::new(), cover: Vec::new(),
+        best: None, limit: 0, nodes: 0,
+    };
+    let mut left = budget.unwrap_or(kn);
+    let mut cover = Vec::new();
+    let mut seen = vec![false; kn];
+    for root in 0..kn {
+        if seen[root] { continue; }
+        let mut comp = vec![root];
+        seen[root] = true;
+        let mut i = 0;
+        while i < comp.len() {
+            for &w in &adj[comp[i]] {
+                if !seen[w] {
+                    seen[w] = true;
+                    comp.push(w);
+                }
+            }
+            i += 1;
+        }
+        comp.sort_unstable();
+        s.best = None;
+        s.limit = left + 1;
+        s.go(&comp);
+        match s.best.take() {
+            Some(c) => {
+                left -= c.len();
+                cover.extend(c.iter().map(|&v| kernel.kernel[v]));
+            }
+            None => return Branching { cover: None, nodes: s.nodes },
+        }
+    }
+    cover.sort_unstable();
+    Branching { cover: Some(cover), nodes: s.nodes }
+}
+
+/* 2-approximation: the forced vertices plus both ends of a greedy maximal
+ * matching of G[kernel], then each cover vertex (lowest degree first) is
+ * dropped if all its neighbours are covered. OPT = |forced| + OPT(kernel)
+ * and OPT(kernel) >= the matching, so the result is at most 2 OPT. */
+pub fn approx_cover(n: usize, edges: &[(usize, usize)], kernel: &Kernel) -> Approximation {
+    let (adj, looped) = adjacency(n, edges);
+    let mut in_kernel = vec![false; n];
+    for &v in &kernel.kernel { in_kernel[v] = true; }
+    let mut in_cover = vec![false; n];
+    for &v in &kernel.forced { in_cover[v] = true; }
+    let mut matched = 0;
+    for &v in &kernel.kernel {
+        if in_cover[v] { continue; }
+        if let Some(&w) = adj[v].iter().find(|&&w| in_kernel[w] && !in_cover[w]) {
+            in_cover[v] = true;
+            in_cover[w] = true;
+            matched += 1;
+        }
+    }
+    let mut order: Vec<usize> = (0..n).filter(|&v| in_cover[v] && !looped[v]).collect();
+    order.sort_by_key(|&v| (adj[v].len(), v));
+    for v in order {
+        if adj[v].iter().all(|&w| in_cover[w]) {
+            in_cover[v] = false;
+        }
+    }
+    Approximation {
+        cover: (0..n).filter(|&v| in_cover[v]).collect(),
+        lower_bound: kernel.lp_bound.max(kernel.forced.len() + matched),
+    }
+}
+
+/* A minimum vertex cover of (n, edges), or None when it has more than k
+ * vertices. */
+pub fn min_vertex_cover(n: usize, edges: &[(usize, usize)], k: Option<usize>) -> Option<Vec<usize>> {
+    let kernel = lp_kernel(n, edges);
+    if k.is_some_and(|k| kernel.lp_bound > k) { return None; }
+    let budget = k.map(|k| k - kernel.forced.len());
+    let mut cover = solve_kernel(n, edges, &kernel, budget).cover?;
+    cover.extend_from_slice(&kernel.forced);
+    cover.sort_unstable();
+    Some(cover)
+}
+
+/* The complement of a minimum vertex cover. */
+pub fn max_independent_set(n: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let cover = min_vertex_cover(n, edges, None).unwrap_or_default();
+    let mut in_cover = vec![false; n];
+    for &v in &cover { in_cover[v] = true; }
+    (0..n).filter(|&v| !in_cover[v]).collect()
+}
+
+/* Edges of (n, edges) that `cover` misses. */
+pub fn check_cover(n: usize, edges: &[(usize, usize)], cover: &[usize]) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut in_cover = vec![false; n];
+    for &v in cover {
+        if v >= n {
+            errors.push(format!("Cover vertex {} out of range!", v));
+        } else if in_cover[v] {
+            errors.push(format!("Vertex {} listed twice!", v));
+        } else {
+            in_cover[v] = true;
+        }
+    }
+    let missed: Vec<&(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && !in_cover[u] && !in_cover[v]).collect();
+    if let Some(&&(u, v)) = missed.first() {
+        errors.push(format!("{} edge(s) uncovered, e.g. ({}, {})!", missed.len(), u, v));
+    }
+    errors
+}
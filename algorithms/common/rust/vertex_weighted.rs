@@ -0,0 +1,293 @@
+/*
+ * Maximum vertex-weight matching in general graphs: maximize the total
+ * weight of the matched vertices (non-negative weights), not the number of
+ * pairs. Kidney exchange is the usual example, where some patients are
+ * more urgent to match than others.
+ *
+ * The vertex sets a matching can cover form a matroid (the matching
+ * matroid), so a greedy pass is exact: take vertices by decreasing weight,
+ * and keep v if some matching covers the vertices kept so far and v. The
+ * current matching M covers the kept set S; for an exposed v, M xor M'
+ * (M' covering S + v) holds an alternating path from v that ends at an
+ * exposed vertex, or at a matched vertex outside S after an M edge. So
+ * the augmenting search of Edmonds' algorithm is rooted at v and accepts
+ * both ends: an exposed vertex (augment) or an even ("outer") vertex that
+ * is matched but not kept (flip the even path, which exposes it). Vertices
+ * stay matched once kept, and a rejected vertex is never covered later.
+ *
+ * The search is the classic single-tree Edmonds search with blossom bases
+ * (parent links, LCA by walking to the root); only the vertices it
+ * touched are reset between searches. A weight-ordered greedy matching
+ * seeds M. Cost: one search per exposed weighted vertex, each
+ * O(E + V * blossoms); O(V^3) in the worst case.
+ *
+ * Included via #[path = "../../common/rust/vertex_weighted.rs"] mod vertex_weighted;
+ */
+
+#![allow(dead_code)]
+
+use std::error::Error;
+use std::fs;
+
+const NIL: usize = usize::MAX;
+
+pub struct VertexWeighted {
+    pub matching: Vec<(usize, usize)>, /* sorted, u < v */
+    pub weight: u64,                   /* total weight of matched vertices */
+    pub rejected: usize,               /* weighted vertices left exposed */
+    pub searches: usize,
+    pub flips: usize,                  /* searches that exposed a lighter vertex */
+}
+
+struct Search {
+    adj: Vec<Vec<usize>>,
+    mate: Vec<usize>,
+    parent: Vec<usize>,
+    base: Vec<usize>,
+    outer: Vec<bool>,
+    in_blossom: Vec<bool>,
+    lca_mark: Vec<usize>,
+    stamp: usize,
+    touched: Vec<usize>,
+    queue: Vec<usize>,
+}
+
+impl Search {
+    fn touch(&mut self, v: usize) {
+        if self.parent[v] == NIL && !self.outer[v] && self.base[v] == v {
+            self.touched.push(v);
+        }
+    }
+
+    fn reset(&mut self) {
+        for &v in &self.touched {
+            self.parent[v] = NIL;
+            self.base[v] = v;
+            self.outer[v] = false;
+            self.in_blossom[v] = false;
+        }
+        self.touched.clear();
+        self.queue.clear();
+    }
+
+    fn lca(&mut self, mut a: usize, mut b: usize) -> usize {
+        self.stamp += 1;
+        loop {
+            a = self.base[a];
+            self.lca_mark[a] = self.stamp;
+            if self.mate[a] == NIL { break; }
+            a = self.parent[self.mate[a]];
+        }
+        loop {
+            b = self.base[b];
+            if self.lca_mark[b] == self.stamp { return b; }
+            b = self.parent[self.mate[b]];
+        }
+    }
+
+    fn mark_path(&mut self, mut v: usize, b: usize, mut child: usize) {
+        while self.base[v] != b {
+            let m = self.mate[v];
+            self.in_blossom[self.base[v]] = true;
+            self.in_blossom[self.base[m]] = true;
+            self.parent[v] = child;
+            child = m;
+            v = self.parent[m];
+        }
+    }
+
+    /* Flip the alternating path that ends at odd vertex x (parent[x] set):
+     * x and the root end up matched. */
+    fn flip_from(&mut self, mut x: usize) {
+        while x != NIL {
+            let px = self.parent[x];
+            let next = self.mate[px];
+            self.mate[x] = px;
+            self.mate[px] = x;
+            x = next;
+        }
+    }
+
+    /* Outer vertex u is reached: true if it ends the search. */
+    fn accept(&mut self, u: usize, kept: &[bool]) -> bool {
+        if kept[u] || self.mate[u] == NIL { return false; }
+        let m = self.mate[u];
+        self.mate[u] = NIL;
+        self.flip_from(m);
+        true
+    }
+
+    /* Search from exposed root r; (found, flipped). */
+    fn search(&mut self, r: usize, kept: &[bool]) -> (bool, bool) {
+        self.reset();
+        self.touch(r);
+        self.outer[r] = true;
+        self.queue.push(r);
+        let mut qi = 0;
+        while qi < self.queue.len() {
+            let v = self.queue[qi];
+            qi += 1;
+            for k in 0..self.adj[v].len() {
+                let to = self.adj[v][k];
+                if self.base[v] == self.base[to] || self.mate[v] == to { continue; }
+                if to == r || (self.mate[to] != NIL && self.parent[self.mate[to]] != NIL) {
+                    /* odd cycle: contract it into a blossom on their LCA */
+                    let cb = self.lca(v, to);
+                    self.mark_path(v, cb, to);
+                    self.mark_path(to, cb, v);
+                    for i in 0..self.touched.len() {
+                        let x = self.touched[i];
+                        if !self.in_blossom[self.base[x]] { continue; }
+                        self.base[x] = cb;
+                        if !self.outer[x] {
+                            self.outer[x] = true;
+                            self.queue.push(x);
+                            if self.accept(x, kept) { return (true, true); }
+                        }
+                    }
+                    for i in 0..self.touched.len() {
+                        let x = self.touched[i];
+                        self.in_blossom[x] = false;
+                    }
+                } else if self.parent[to] == NIL {
+                    self.touch(to);
+                    self.parent[to] = v;
+                    if self.mate[to] == NIL {
+                        self.flip_from(to);
+                        return (true, false);
+                    }
+                    let m = self.mate[to];
+                    self.touch(m);
+                    self.outer[m] = true;
+                    self.queue.push(m);
+                    if self.accept(m, kept) { return (true, true); }
+                }
+            }
+        }
+        (false, false)
+    }
+}
+
+pub fn max_vertex_weight_matching(n: usize, edges: &[(usize, usize)], weights: &[u64]) -> VertexWeighted {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    let mut order: Vec<usize> = (0..n).filter(|&v| weights[v] > 0).collect();
+    order.sort_by(|&a, &b| weights[b].cmp(&weights[a]).then(a.cmp(&b)));
+
+    let mut mate = vec![NIL; n];
+    /* seed: heaviest exposed neighbour, heaviest vertices first */
+    for &v in &order {
+        if mate[v] != NIL { continue; }
+        let best = adj[v].iter().cloned().filter(|&w| mate[w] == NIL)
+            .max_by(|&a, &b| weights[a].cmp(&weights[b]).then(b.cmp(&a)));
+        if let Some(w) = best {
+            mate[v] = w;
+            mate[w] = v;
+        }
+    }
+
+    let mut s = Search {
+        adj, mate,
+        parent: vec![NIL; n],
+        base: (0..n).collect(),
+        outer: vec![false; n],
+        in_blossom: vec![false; n],
+        lca_mark: vec![0; n],
+        stamp: 0,
+        touched: Vec::new(),
+        queue: Vec::new(),
+    };
+    let mut kept = vec![false; n];
+    let (mut rejected, mut searches, mut flips) = (0, 0, 0);
+    for &v in &order {
+        if s.mate[v] == NIL {
+            searches += 1;
+            let (found, flipped) = s.search(v, &kept);
+            if flipped { flips += 1; }
+            if !found {
+                rejected += 1;
+                continue;
+            }
+        }
+        kept[v] = true;
+    }
+
+    let mut matching = Vec::new();
+    let mut weight = 0;
+    for (v, &m) in s.mate.iter().enumerate() {
+        if m != NIL {
+            weight += weights[v];
+            if v < m { matching.push((v, m)); }
+        }
+    }
+    VertexWeighted { matching, weight, rejected, searches, flips }
+}
+
+/* Pairs are edges and disjoint; returns the matched weight too. */
+pub fn check_matching(
+    n: usize, edges: &[(usize, usize)], weights: &[u64], matching: &[(usize, usize)],
+) -> (Vec<String>, u64) {
+    let mut errors = Vec::new();
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); }
+    let mut used = vec![false; n];
+    let mut weight = 0;
+    for &(u, v) in matching {
+        if u >= n || v >= n || u == v {
+            errors.push(format!("Pair ({}, {}) is not a pair of distinct vertices!", u, v));
+            continue;
+        }
+        if adj[u].binary_search(&v).is_err() {
+            errors.push(format!("Edge ({}, {}) not in graph!", u, v));
+        }
+        for &x in &[u, v] {
+            if used[x] {
+                errors.push(format!("Vertex {} matched twice!", x));
+            } else {
+                used[x] = true;
+                weight += weights[x];
+            }
+        }
+    }
+    (errors, weight)
+}
+
+/* Weight file: one "v w" pair per line, '#' starts a comment. Vertices
+ * not listed get `default`. */
+pub fn load_weights(filename: &str, n: usize, default: u64) -> Result<Vec<u64>, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut weights = vec![default; n];
+    let mut listed = vec![false; n];
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.split('#').next().unwrap_or("");
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.is_empty() { continue; }
+        if parts.len() != 2 {
+            return Err(format!("{}:{}: expected 'vertex weight', got '{}'", filename, i + 1, raw.trim()).into());
+        }
+        let v: usize = parts[0].parse()?;
+        let w: u64 = parts[1].parse()
+            .map_err(|_| format!("{}:{}: weight must be a non-negative integer, got '{}'", filename, i + 1, parts[1]))?;
+        if v >= n {
+            return Err(format!("{}:{}: vertex {} out of range (n = {})", filename, i + 1, v, n).into());
+        }
+        if listed[v] {
+            return Err(format!("{}:{}: vertex {} listed twice", filename, i + 1, v).into());
+        }
+        listed[v] = true;
+        weights[v] = w;
+    }
+    Ok(weights)
+}
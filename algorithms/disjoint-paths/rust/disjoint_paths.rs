@@ -0,0 +1,254 @@
+/*
+ * Vertex-Disjoint Terminal Path Packing via Maximum Matching
+ *
+ * Given G = (V, E) and a terminal set A, find the maximum number of
+ * vertex-disjoint A-paths: paths joining two distinct terminals whose
+ * inner vertices are all outside A (Gallai 1961).
+ *
+ * Reduction: G~ has every vertex of G plus a copy v' of each non-terminal
+ * v, with edges
+ *     v v'                  for v not in A
+ *     u v  and  u' v'       for uv in E, u, v not in A
+ *     a v  and  a v'        for av in E, a in A, v not in A
+ *     a b                   for ab in E, a, b in A
+ * Then  max #paths = nu(G~) - |V \ A|, and the paths are the augmenting
+ * components of M xor {v v'} for any maximum matching M of G~.
+ *
+ * Any general matcher of the suite can be the backend (--algo). Packing
+ * paths between prescribed pairs (s_i, t_i) is NP-hard; this is the
+ * polynomial case where any two terminals may be joined.
+ *
+ * Rust implementation -- fully deterministic, no hash containers.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::time::Instant;
+
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/matchers.rs"]
+mod matchers;
+
+const NIL: i32 = -1;
+
+pub(crate) struct PathPacking {
+    n: usize,
+    terminal: Vec<bool>,
+    graph: Vec<Vec<usize>>,
+}
+
+impl PathPacking {
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)], terminals: &[usize]) -> Self {
+        let mut graph = vec![Vec::new(); n];
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                graph[u].push(v);
+                graph[v].push(u);
+            }
+        }
+        for adj in &mut graph { adj.sort_unstable(); adj.dedup(); }
+        let mut terminal = vec![false; n];
+        for &a in terminals {
+            if a < n { terminal[a] = true; }
+        }
+        PathPacking { n, terminal, graph }
+    }
+
+    pub(crate) fn non_terminals(&self) -> usize {
+        self.terminal.iter().filter(|&&t| !t).count()
+    }
+
+    /* G~ on 2n vertices: v' = v + n (copies of terminals stay isolated). */
+    pub(crate) fn reduced_edges(&self) -> Vec<(usize, usize)> {
+        let n = self.n;
+        let mut out = Vec::new();
+        for u in 0..n {
+            if !self.terminal[u] { out.push((u, u + n)); }
+            for &v in &self.graph[u] {
+                if v < u { continue; }
+                out.push((u, v));
+                match (self.terminal[u], self.terminal[v]) {
+                    (false, false) => out.push((u + n, v + n)),
+                    (true, false) => out.push((u, v + n)),
+                    (false, true) => out.push((v, u + n)),
+                    (true, true) => {}
+                }
+            }
+        }
+        out
+    }
+
+    /* Walk M xor {v v'} from every matched terminal. Components that end
+     * in an M-exposed copy are even alternating paths, not A-paths. */
+    pub(crate) fn extract_paths(&self, matching: &[(usize, usize)]) -> Vec<Vec<usize>> {
+        let n = self.n;
+        let mut mate = vec![NIL; 2 * n];
+        for &(u, v) in matching {
+            mate[u] = v as i32;
+            mate[v] = u as i32;
+        }
+        let mut paths = Vec::new();
+        for a in 0..n {
+            if !self.terminal[a] || mate[a] == NIL { continue; }
+            let mut path = vec![a];
+            let mut cur = mate[a] as usize;
+            loop {
+                let p = cur % n;
+                path.push(p);
+                if self.terminal[p] { break; }
+                /* leave the pair {p, p'} through the other side */
+                let other = if cur == p { p + n } else { p };
+                if mate[other] == NIL { path.clear(); break; }
+                cur = mate[other] as usize;
+            }
+            /* every A-path is reached from both ends; keep one */
+            if path.len() >= 2 && path[0] < path[path.len() - 1] {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+}
+
+fn validate_paths(pp: &PathPacking, paths: &[Vec<usize>], expected: usize) {
+    let mut used = vec![false; pp.n];
+    let mut errors = 0;
+    for path in paths {
+        let k = path.len();
+        if !pp.terminal[path[0]] || !pp.terminal[path[k - 1]] || path[0] == path[k - 1] {
+            eprintln!("ERROR: Path {:?} does not join two distinct terminals!", path);
+            errors += 1;
+        }
+        for i in 0..k {
+            let v = path[i];
+            if used[v] {
+                eprintln!("ERROR: Vertex {} on more than one path!", v);
+                errors += 1;
+            }
+            used[v] = true;
+            if i > 0 && i + 1 < k && pp.terminal[v] {
+                eprintln!("ERROR: Terminal {} is an inner vertex!", v);
+                errors += 1;
+            }
+            if i + 1 < k && pp.graph[v].binary_search(&path[i + 1]).is_err() {
+                eprintln!("ERROR: Edge ({}, {}) not in graph!", v, path[i + 1]);
+                errors += 1;
+            }
+        }
+    }
+    if paths.len() != expected {
+        eprintln!("ERROR: Extracted {} paths, matching bound says {}!", paths.len(), expected);
+        errors += 1;
+    }
+    println!("\n=== Validation Report ===");
+    println!("Disjoint paths: {}", paths.len());
+    println!("Vertices used: {}", used.iter().filter(|&&u| u).count());
+    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
+    println!("=========================\n");
+}
+
+/* Format:
+ *   n m t
+ *   u v        (m lines)
+ *   a          (t terminals, any whitespace layout)
+ * read as (n, edges, terminals). */
+type Instance = (usize, Vec<(usize, usize)>, Vec<usize>);
+
+fn load_instance(filename: &str) -> Result<Instance, Box<dyn Error>> {
+    let text = fs::read_to_string(filename)?;
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+
+    let first = lines.next().ok_or("Empty file")?;
+    let parts: Vec<&str> = first.split_whitespace().collect();
+    if parts.len() != 3 {
+        return Err("First line must have 3 numbers: n m t".into());
+    }
+    let n: usize = parts[0].parse()?;
+    let m: usize = parts[1].parse()?;
+    let t: usize = parts[2].parse()?;
+
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let line = lines.next().ok_or("Fewer edge lines than declared")?;
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() < 2 {
+            return Err(format!("Bad edge line '{}'", line).into());
+        }
+        edges.push((parts[0].parse()?, parts[1].parse()?));
+    }
+
+    let mut terminals = Vec::with_capacity(t);
+    for line in lines {
+        for tok in line.split_whitespace() {
+            let a: usize = tok.parse()?;
+            if a >= n {
+                return Err(format!("Terminal {} out of range (n = {})", a, n).into());
+            }
+            terminals.push(a);
+        }
+    }
+    if terminals.len() != t {
+        return Err(format!("Header declares {} terminals but the file lists {}",
+                           t, terminals.len()).into());
+    }
+    Ok((n, edges, terminals))
+}
+
+fn main() {
+    println!("Vertex-Disjoint Terminal Paths (via Matching) - Rust Implementation");
+    println!("====================================================================\n");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <filename> [--algo {}] [--show-paths]",
+                  args[0], matchers::GENERAL_ALGORITHMS.join("|"));
+        std::process::exit(1);
+    }
+    let algo = args::value_of(&args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let show = args::has_flag(&args, "--show-paths");
+
+    match load_instance(&args[1]) {
+        Ok((n, edges, terminals)) => {
+            let start = Instant::now();
+            let pp = PathPacking::new(n, &edges, &terminals);
+            let reduced = pp.reduced_edges();
+            println!("Graph: {} vertices, {} edges, {} terminals", n, edges.len(), terminals.len());
+            println!("Reduced graph: {} vertices, {} edges", 2 * n, reduced.len());
+            println!("Backend: {}", algo);
+
+            let matching = match matchers::maximum_matching(algo, 2 * n, &reduced) {
+                Ok(m) => m,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let expected = matching.len() - pp.non_terminals();
+            let paths = pp.extract_paths(&matching);
+            let duration = start.elapsed();
+
+            validate_paths(&pp, &paths, expected);
+
+            if show {
+                for path in &paths {
+                    let s: Vec<String> = path.iter().map(|v| v.to_string()).collect();
+                    println!("{}", s.join(" -> "));
+                }
+                println!();
+            }
+            println!("Reduced matching size: {}", matching.len());
+            println!("Disjoint paths: {}", paths.len());
+            println!("Time: {} ms", duration.as_millis());
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
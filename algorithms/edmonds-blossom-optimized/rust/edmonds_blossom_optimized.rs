@@ -3,16 +3,63 @@
  *
  * Rust implementation. Forest BFS: each stage labels ALL free vertices as
  * S-roots and grows a search forest. Augmenting path found when two trees meet.
- * Blossom IDs reset to n each stage. All indices are i32.
+ * The stage goes on after it: both trees are retired (their vertices are
+ * neither scanned nor grown into again), and the rest of the forest keeps
+ * growing, so a stage augments along vertex-disjoint paths until its
+ * queue runs dry. Blossom IDs reset to n each stage. All indices are i32; new, set_mate
+ * and solve take and return the suite's ids (types.rs).
  *
- * Complexity: O(V * E) worst case.
+ * Dense graphs (n <= MATRIX_MAX_N, density >= MATRIX_MIN_DENSITY) are stored
+ * as an n x n adjacency matrix instead of sorted adjacency lists: no sorting,
+ * no deduplication, and the search scans one contiguous row. Rows are
+ * bit-packed (64 neighbours per word, skipped with trailing_zeros); a byte
+ * per entry (--adjacency matrix) measured slower at every density. Rows are
+ * scanned in index order, so all layouts return the same matching.
+ *
+ * Complexity: O(V * E) worst case; O(V^3 / 64) scanning with the bitset.
  */
 
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use self::types::Vertex;
+
+/* Internal ids are i32 (types.rs), so the vertex count is capped below
+ * types::MAX_VERTICES. */
+pub(crate) const MAX_VERTICES: usize = i32::MAX as usize;
+
 // â”€â”€ Blossom data â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
 
 #[derive(Clone)]
@@ -25,11 +72,142 @@ impl Blos {
     fn new() -> Self { Blos { childs: Vec::new(), edges: Vec::new() } }
 }
 
+// â”€â”€ Adjacency â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
+
+/* Auto-selection of the bit-packed matrix, measured with
+ * run_large_benchmarks.sh on general_dense_* graphs: at n = 5000 the lists
+ * win below ~0.5% density, the bitset from ~1% on (2-3x with --greedy). */
+pub(crate) const MATRIX_MAX_N: i32 = 5000;
+pub(crate) const MATRIX_MIN_DENSITY: f64 = 0.01;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum Layout { Auto, Lists, Matrix, Bitset }
+
+impl Layout {
+    pub(crate) fn parse(s: &str) -> Option<Layout> {
+        match s {
+            "auto" => Some(Layout::Auto),
+            "lists" => Some(Layout::Lists),
+            "matrix" => Some(Layout::Matrix),
+            "bitset" => Some(Layout::Bitset),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Layout::Auto => "auto",
+            Layout::Lists => "lists",
+            Layout::Matrix => "matrix",
+            Layout::Bitset => "bitset",
+        }
+    }
+
+    /* Edge count before deduplication; parallel edges only make the choice
+     * lean towards the bitset. */
+    fn choose(n: i32, m: usize) -> Layout {
+        if !(2..=MATRIX_MAX_N).contains(&n) { return Layout::Lists; }
+        let pairs = n as f64 * (n - 1) as f64 / 2.0;
+        if m as f64 >= MATRIX_MIN_DENSITY * pairs { Layout::Bitset } else { Layout::Lists }
+    }
+}
+
+enum Adjacency {
+    Lists(Vec<Vec<i32>>),          // sorted, deduplicated
+    Matrix(Vec<u8>),               // row-major n x n, 1 = edge
+    Bitset(Vec<u64>, usize),       // row-major, words per row
+}
+
+impl Adjacency {
+    fn build(n: i32, edges: &[(i32, i32)], layout: Layout) -> Adjacency {
+        let nu = n as usize;
+        let valid = |u: i32, v: i32| u != v && u >= 0 && u < n && v >= 0 && v < n;
+        match layout {
+            Layout::Matrix => {
+                let mut m = vec![0u8; nu * nu];
+                for &(u, v) in edges {
+                    if valid(u, v) {
+                        m[u as usize * nu + v as usize] = 1;
+                        m[v as usize * nu + u as usize] = 1;
+                    }
+                }
+                Adjacency::Matrix(m)
+            }
+            Layout::Bitset => {
+                let words = nu.div_ceil(64);
+                let mut m = vec![0u64; nu * words];
+                for &(u, v) in edges {
+                    if valid(u, v) {
+                        let (u, v) = (u as usize, v as usize);
+                        m[u * words + v / 64] |= 1 << (v % 64);
+                        m[v * words + u / 64] |= 1 << (u % 64);
+                    }
+                }
+                Adjacency::Bitset(m, words)
+            }
+            _ => {
+                let mut adj = vec![Vec::new(); nu];
+                for &(u, v) in edges {
+                    if valid(u, v) {
+                        adj[u as usize].push(v);
+                        adj[v as usize].push(u);
+                    }
+                }
+                for a in &mut adj {
+                    a.sort_unstable();
+                    a.dedup();
+                }
+                Adjacency::Lists(adj)
+            }
+        }
+    }
+
+    /* First neighbour of v at cursor `pos` or later, with the cursor to
+     * resume from. Neighbours come out in increasing order for every layout;
+     * the cursor lets the search mutate the solver between calls. */
+    #[inline]
+    fn next(&self, n: usize, v: usize, pos: usize) -> Option<(i32, usize)> {
+        match *self {
+            Adjacency::Lists(ref adj) => adj[v].get(pos).map(|&w| (w, pos + 1)),
+            Adjacency::Matrix(ref m) => {
+                let row = &m[v * n..v * n + n];
+                row[pos..].iter().position(|&b| b != 0).map(|i| ((pos + i) as i32, pos + i + 1))
+            }
+            Adjacency::Bitset(ref m, words) => {
+                if pos >= n { return None; }
+                let row = &m[v * words..v * words + words];
+                let mut wi = pos / 64;
+                let mut bits = row[wi] & (!0u64 << (pos % 64));
+                loop {
+                    if bits != 0 {
+                        let w = wi * 64 + bits.trailing_zeros() as usize;
+                        return Some((w as i32, w + 1));
+                    }
+                    wi += 1;
+                    if wi == words { return None; }
+                    bits = row[wi];
+                }
+            }
+        }
+    }
+
+    fn degree(&self, n: usize, v: usize) -> i32 {
+        match *self {
+            Adjacency::Lists(ref adj) => adj[v].len() as i32,
+            Adjacency::Matrix(ref m) => m[v * n..v * n + n].iter().filter(|&&b| b != 0).count() as i32,
+            Adjacency::Bitset(ref m, words) => {
+                m[v * words..v * words + words].iter().map(|w| w.count_ones() as i32).sum()
+            }
+        }
+    }
+}
+
 // â”€â”€ Solver â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
 
-struct Solver {
+pub(crate) struct Solver {
     n: i32,
-    adj: Vec<Vec<i32>>,
+    adj: Adjacency,
+    pub(crate) layout: Layout,    // resolved, never Auto
     mate: Vec<i32>,
 
     blos: Vec<Blos>,
@@ -42,24 +220,30 @@ struct Solver {
     label: Vec<i32>,              // 0=none, 1=S, 2=T, 5=breadcrumb
     labeledge: Vec<(i32, i32)>,
     queue: Vec<i32>,
-
-    greedy_size: i32,
+    tree: Vec<i32>,               // root of each labeled vertex's tree, -1 if unlabeled
+    retired: Vec<bool>,           // by root: the tree was augmented through this stage
+
+    pub(crate) greedy_size: i32,
+    pub(crate) greedy_seed: u64, /* for greedy_mode 4 (--greedy-random) */
+    pub(crate) aug3_passes: usize, /* --init-aug3 passes after the greedy start; 0 = off */
+    pub(crate) aug3_size: i32,     /* pairs those passes added, counted in greedy_size */
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path and blossom (trace.rs) */
+    trace_mate: Vec<usize>, /* for the tracer: the mates before the last augmentation */
 }
 
 impl Solver {
-    fn new(n: i32, edges: &[(i32, i32)]) -> Self {
-        let nu = n as usize;
-        let mut adj = vec![Vec::new(); nu];
-        for &(u, v) in edges {
-            if u != v && u >= 0 && u < n && v >= 0 && v < n {
-                adj[u as usize].push(v);
-                adj[v as usize].push(u);
-            }
-        }
-        for a in &mut adj {
-            a.sort_unstable();
-            a.dedup();
-        }
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        Solver::with_layout(n, edges, Layout::Auto)
+    }
+
+    pub(crate) fn with_layout(n: usize, edges: &[(usize, usize)], layout: Layout) -> Self {
+        let nu = n;
+        let edges = &types::edges_i32(nu, edges)[..];
+        let n = n as i32;
+        let layout = if layout == Layout::Auto { Layout::choose(n, edges.len()) } else { layout };
+        let adj = Adjacency::build(n, edges, layout);
 
         let mut inblossom = vec![0i32; nu];
         let mut blossombase = vec![0i32; nu];
@@ -70,12 +254,20 @@ impl Solver {
         }
 
         Solver {
-            n, adj, mate: vec![-1; nu],
+            n, adj, layout, mate: vec![-1; nu],
             blos: vec![Blos::new(); nu],
             nblos: n,
             inblossom, blossomparent, blossombase,
             label: Vec::new(), labeledge: Vec::new(), queue: Vec::new(),
+            tree: vec![-1; nu], retired: vec![false; nu],
             greedy_size: 0,
+            greedy_seed: 1,
+            aug3_passes: 0,
+            aug3_size: 0,
+            stop: None,
+            progress: None,
+            trace: None,
+            trace_mate: Vec::new(),
         }
     }
 
@@ -118,6 +310,13 @@ impl Solver {
         self.label = vec![0; nu];
         self.labeledge = vec![(-1, -1); nu];
         self.queue.clear();
+        for x in self.tree.iter_mut() { *x = -1; }
+        for x in self.retired.iter_mut() { *x = false; }
+    }
+
+    /* Whether the labeled vertex v is in a tree augmented through this stage. */
+    fn is_retired(&self, v: i32) -> bool {
+        self.retired[self.tree[v as usize] as usize]
     }
 
     // â”€â”€ Tree building â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
@@ -134,10 +333,12 @@ impl Solver {
             self.labeledge[w as usize] = (-1, -1);
             self.labeledge[b as usize] = (-1, -1);
         }
+        let root = if v == -1 { w } else { self.tree[v as usize] };
+        let mut lv = Vec::new();
+        self.leaves(b, &mut lv);
+        for &u in &lv { self.tree[u as usize] = root; }
         if t == 1 {
-            let mut lv = Vec::new();
-            self.leaves(b, &mut lv);
-            for u in lv { self.queue.push(u); }
+            self.queue.extend(lv);
         } else if t == 2 {
             let base = self.blossombase[b as usize];
             let mb = self.mate[base as usize];
@@ -509,7 +710,9 @@ impl Solver {
         let mut cnt = 0;
         for u in 0..self.n {
             if self.mate[u as usize] != -1 { continue; }
-            for &v in &self.adj[u as usize].clone() {
+            let mut pos = 0;
+            while let Some((v, next)) = self.adj.next(self.n as usize, u as usize, pos) {
+                pos = next;
                 if self.mate[v as usize] == -1 {
                     self.mate[u as usize] = v;
                     self.mate[v as usize] = u;
@@ -523,19 +726,17 @@ impl Solver {
 
     fn greedy_init_md(&mut self) -> i32 {
         let mut cnt = 0;
-        let mut deg = vec![0i32; self.n as usize];
-        for u in 0..self.n as usize {
-            for &v in &self.adj[u] {
-                deg[v as usize] += 1;
-            }
-        }
+        let nu = self.n as usize;
+        let deg: Vec<i32> = (0..nu).map(|v| self.adj.degree(nu, v)).collect();
         let mut order: Vec<i32> = (0..self.n).collect();
         order.sort_unstable_by(|&a, &b| deg[a as usize].cmp(&deg[b as usize]).then(a.cmp(&b)));
         for u in order {
             if self.mate[u as usize] != -1 { continue; }
             let mut best = -1i32;
             let mut bd = i32::MAX;
-            for &v in &self.adj[u as usize] {
+            let mut pos = 0;
+            while let Some((v, next)) = self.adj.next(nu, u as usize, pos) {
+                pos = next;
                 if self.mate[v as usize] == -1 && deg[v as usize] < bd {
                     best = v;
                     bd = deg[v as usize];
@@ -550,13 +751,203 @@ impl Solver {
         cnt
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor,
+     * match the two; otherwise match the lowest-id exposed vertex that has
+     * one with its first unmatched neighbor. deg counts unmatched neighbors. */
+    fn greedy_init_ks(&mut self) -> i32 {
+        let nu = self.n as usize;
+        let first_free = |s: &Self, u: usize| {
+            let mut pos = 0;
+            while let Some((v, next)) = s.adj.next(nu, u, pos) {
+                pos = next;
+                if s.mate[v as usize] == -1 { return Some(v); }
+            }
+            None
+        };
+        let mut deg = vec![0i32; nu];
+        for (u, d) in deg.iter_mut().enumerate() {
+            if self.mate[u] != -1 { continue; }
+            let mut pos = 0;
+            while let Some((v, next)) = self.adj.next(nu, u, pos) {
+                pos = next;
+                if self.mate[v as usize] == -1 { *d += 1; }
+            }
+        }
+        let mut leaves: Vec<usize> = (0..nu).filter(|&v| deg[v] == 1).collect();
+        let mut next_root = 0;
+        let mut cnt = 0;
+        loop {
+            let u = match leaves.pop() {
+                Some(u) if self.mate[u] != -1 || deg[u] != 1 => continue,
+                Some(u) => u,
+                None => {
+                    while next_root < nu && (self.mate[next_root] != -1 || deg[next_root] == 0) { next_root += 1; }
+                    if next_root == nu { break; }
+                    next_root
+                }
+            };
+            let v = first_free(self, u).unwrap() as usize;
+            self.mate[u] = v as i32;
+            self.mate[v] = u as i32;
+            cnt += 1;
+            for &x in &[u, v] {
+                let mut pos = 0;
+                while let Some((w, next)) = self.adj.next(nu, x, pos) {
+                    pos = next;
+                    let w = w as usize;
+                    if self.mate[w] != -1 { continue; }
+                    deg[w] -= 1;
+                    if deg[w] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched neighbor. The
+     * same seed gives the same matching. */
+    fn greedy_init_random(&mut self, seed: u64) -> i32 {
+        let nu = self.n as usize;
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<usize> = (0..nu).collect();
+        rng.shuffle(&mut order);
+        let mut free = Vec::new();
+        let mut cnt = 0;
+        for u in order {
+            if self.mate[u] != -1 { continue; }
+            free.clear();
+            let mut pos = 0;
+            while let Some((v, next)) = self.adj.next(nu, u, pos) {
+                pos = next;
+                if self.mate[v as usize] == -1 { free.push(v); }
+            }
+            if free.is_empty() { continue; }
+            let v = free[rng.below(free.len() as u64) as usize];
+            self.mate[u] = v;
+            self.mate[v as usize] = u as i32;
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+     * free vertices, each taking the first path u - v = w - x from u (x
+     * free, x != u) or a free neighbor outright. A pass without one ends
+     * it; the cursor next[w] skips neighbors of w that are matched for good. */
+    fn augment3(&mut self, passes: usize) -> i32 {
+        let nu = self.n as usize;
+        let mut next = vec![0usize; nu];
+        let mut cnt = 0;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..nu {
+                if self.mate[u] != -1 { continue; }
+                let mut pos = 0;
+                while let Some((v, after)) = self.adj.next(nu, u, pos) {
+                    pos = after;
+                    let v = v as usize;
+                    if self.mate[v] == -1 {
+                        self.mate[u] = v as i32;
+                        self.mate[v] = u as i32;
+                        cnt += 1;
+                        break;
+                    }
+                    let w = self.mate[v] as usize;
+                    while let Some((x, after)) = self.adj.next(nu, w, next[w]) {
+                        if self.mate[x as usize] == -1 { break; }
+                        next[w] = after;
+                    }
+                    let mut j = next[w];
+                    let mut found = -1;
+                    while let Some((x, after)) = self.adj.next(nu, w, j) {
+                        if x as usize != u && self.mate[x as usize] == -1 { found = x; break; }
+                        j = after;
+                    }
+                    if found >= 0 {
+                        self.mate[u] = v as i32;
+                        self.mate[v] = u as i32;
+                        self.mate[w] = found;
+                        self.mate[found as usize] = w as i32;
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
     // â”€â”€ Main solver â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
 
-    fn solve(&mut self, greedy_mode: i32) -> Vec<(i32, i32)> {
+    /* A stop was requested (SIGINT/SIGTERM in combi): the caller gets the
+     * matching of the last finished phase. */
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+    }
+
+    fn trace_blossom(&self, base: usize, v: usize, w: usize) {
+        if let Some(t) = self.trace {
+            t("blossom", &[base, v, w]);
+        }
+    }
+
+    /* x is on the path just flipped. */
+    fn trace_augment(&mut self, x: usize) {
+        if let Some(t) = self.trace {
+            let mut before = std::mem::take(&mut self.trace_mate);
+            trace::augmented(t, x, &|v| self.mate(v), &mut before);
+            self.trace_mate = before;
+        }
+    }
+
+    /* Start from a matching found elsewhere (mate[v] = NONE for free v),
+     * e.g. --init-matching; solve(0) then only augments it. */
+    pub(crate) fn set_mate(&mut self, mate: &[Vertex]) {
+        for (m, &v) in self.mate.iter_mut().zip(mate) {
+            *m = types::to_i32(v);
+        }
+    }
+
+    /* Per-vertex queries on the current matching, as on matchers::Solution. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        self.mate.get(v).filter(|&&m| m >= 0).map(|&m| m as usize)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n as usize).filter(move |&v| self.mate[v] < 0)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.mate.iter().filter(|&&m| m >= 0).count() / 2
+    }
+
+    pub(crate) fn solve(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
         if greedy_mode == 1 { self.greedy_size = self.greedy_init(); }
         else if greedy_mode == 2 { self.greedy_size = self.greedy_init_md(); }
+        else if greedy_mode == 3 { self.greedy_size = self.greedy_init_ks(); }
+        else if greedy_mode == 4 { self.greedy_size = self.greedy_init_random(self.greedy_seed); }
+        if self.aug3_passes > 0 {
+            self.aug3_size = self.augment3(self.aug3_passes);
+            self.greedy_size += self.aug3_size;
+        }
 
-        loop {
+        /* for the progress reports: pairs before the stages, and found since */
+        let start = if self.progress.is_some() { self.matching_size() } else { 0 };
+        if let Some(t) = self.trace {
+            self.trace_mate = trace::begin(t, self.n as usize, &|v| self.mate(v));
+        }
+        let (mut stage, mut found) = (0, 0);
+        while !self.stopped() {
             // New stage: reset all blossom state
             self.reset_blossoms();
 
@@ -567,22 +958,24 @@ impl Solver {
                 }
             }
 
-            // BFS: grow forest
-            let mut augmented = false;
+            // BFS: grow forest, augmenting whenever two live trees meet
+            let mut augmented = 0;
             let mut qi = 0usize;
-            while qi < self.queue.len() && !augmented {
+            while qi < self.queue.len() {
                 let v = self.queue[qi];
                 qi += 1;
-                if self.label[self.inblossom[v as usize] as usize] != 1 { continue; }
+                if self.label[self.inblossom[v as usize] as usize] != 1 || self.is_retired(v) { continue; }
 
-                let neighbors = self.adj[v as usize].clone();
-                for &w in &neighbors {
+                let mut pos = 0;
+                while let Some((w, next)) = self.adj.next(self.n as usize, v as usize, pos) {
+                    pos = next;
                     let bv = self.inblossom[v as usize];
                     let bw = self.inblossom[w as usize];
                     if bv == bw { continue; }
                     self.ensure(bw);
 
                     let lbw = self.label[bw as usize];
+                    if lbw != 0 && self.is_retired(w) { continue; }
                     if lbw == 0 {
                         // Grow
                         self.assign_label(w, 2, v);
@@ -590,11 +983,17 @@ impl Solver {
                         // S-S edge: blossom or augmenting path
                         let base = self.scan_blossom(v, w);
                         if base >= 0 {
+                            self.trace_blossom(base as usize, v as usize, w as usize);
                             self.add_blossom(base, v, w);
                         } else {
-                            // Two different trees met â†’ augmenting path
+                            // Two different trees met â†’ augmenting path;
+                            // both trees are spent for this stage
+                            let (rv, rw) = (self.tree[v as usize], self.tree[w as usize]);
                             self.augment_matching(v, w);
-                            augmented = true;
+                            self.trace_augment(v as usize);
+                            self.retired[rv as usize] = true;
+                            self.retired[rw as usize] = true;
+                            augmented += 1;
                             break;
                         }
                     }
@@ -610,13 +1009,21 @@ impl Solver {
                 }
             }
 
-            if !augmented { break; }
+            stage += 1;
+            found += augmented;
+            if let Some(report) = self.progress {
+                report(stage, found, self.n as usize - 2 * (start + found));
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[stage]);
+            }
+            if augmented == 0 { break; }
         }
 
         let mut result = Vec::new();
         for u in 0..self.n {
             let m = self.mate[u as usize];
-            if m != -1 && m > u { result.push((u, m)); }
+            if m != -1 && m > u { result.push((u as usize, m as usize)); }
         }
         result.sort_unstable();
         result
@@ -625,96 +1032,191 @@ impl Solver {
 
 // â”€â”€ Validation and main â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
 
-fn validate_matching(n: i32, graph: &[Vec<i32>], matching: &[(i32, i32)]) {
-    let mut deg = vec![0i32; n as usize];
-    let mut errors = 0;
-    for &(u, v) in matching {
-        if graph[u as usize].binary_search(&v).is_err() {
-            eprintln!("ERROR: Edge ({},{}) not in graph!", u, v);
-            errors += 1;
-        }
-        deg[u as usize] += 1;
-        deg[v as usize] += 1;
-    }
-    for i in 0..n as usize {
-        if deg[i] > 1 {
-            eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]);
-            errors += 1;
-        }
-    }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
-}
-
-fn load_graph(filename: &str) -> Result<(i32, Vec<(i32, i32)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    let n: i32 = parts[0].parse()?;
-    let _m: i32 = parts[1].parse()?;
-
-    let mut edges = Vec::new();
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: i32 = parts[0].parse()?;
-            let v: i32 = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-    Ok((n, edges))
-}
-
 fn main() {
     println!("Edmonds' Blossom Algorithm (Optimized) - Rust Implementation");
     println!("==============================================================\n");
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} [--adjacency auto|lists|matrix|bitset] {} {} {} {} {} {} {} {}",
+                  args[0], args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
         std::process::exit(1);
     }
 
+    let layout = match args::value_of(&args, "--adjacency") {
+        None => Layout::Auto,
+        Some(s) => match Layout::parse(s) {
+            Some(l) => l,
+            None => {
+                eprintln!("Error: --adjacency must be auto, lists, matrix or bitset (got: {})", s);
+                std::process::exit(1);
+            }
+        },
+    };
+
     let mut gm = 0;
     for a in &args[2..] {
         match a.as_str() {
             "--greedy" => gm = 1,
             "--greedy-md" => gm = 2,
+            "--greedy-ks" => gm = 3,
+            "--greedy-random" => gm = 4,
             _ => {}
         }
     }
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    if gm == 0 && aug3 > 0 { gm = 1; }
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            if n > MAX_VERTICES {
+                let e = error::SuiteError::TooManyVertices {
+                    n, ids: "i32".to_string(), algo: "this implementation".to_string(),
+                };
+                eprintln!("Error: {}", e);
+                std::process::exit(e.exit_code());
+            }
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(n) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
 
             let start = Instant::now();
-            let mut sol = Solver::new(n, &edges);
+            let mut sol = match layout {
+                Layout::Auto => Solver::new(n, &edges),
+                l => Solver::with_layout(n, &edges, l),
+            };
+            sol.greedy_seed = seed;
+            sol.aug3_passes = aug3;
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                sol.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                sol.stop = Some(&time_limit::EXPIRED);
+            }
+            if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("edmonds-opt {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                sol.trace = Some(&trace::record);
+            } else if log::enabled(log::Level::Debug) {
+                sol.trace = Some(&log::event);
+            }
+            if let Some(ref pairs) = init {
+                sol.set_mate(&types::mate_of(n, pairs));
+            }
             let matching = sol.solve(gm);
             let duration = start.elapsed();
+            let gap = time_limit.filter(|_| time_limit::expired())
+                .map(|limit| (limit, time_limit::gap_bound(n, &edges, &matching)));
 
-            validate_matching(n, &sol.adj, &matching);
+            println!("Adjacency: {}", sol.layout.name());
+            let mut report = verify::validate_general(n, &edges, &matching);
+            if args::has_flag(&args, "--certify") && report.is_valid() {
+                report.optimal = Some(vertex_classes::certify(n, &edges, &matching));
+            }
+            report.print();
 
             println!("Matching size: {}", matching.len());
             if gm > 0 {
                 println!("Greedy init size: {}", sol.greedy_size);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", sol.aug3_size);
+                }
                 if !matching.is_empty() {
                     println!("Greedy/Final: {:.2}%", 100.0 * sol.greedy_size as f64 / matching.len() as f64);
                 }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            if let Some(path) = args::value_of(&args, "--dot") {
+                if let Err(e) = dot::save(path, n, &edges, &matching, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = args::value_of(&args, "--svg") {
+                if let Err(e) = svg::save(path, n, &edges, &matching, None, svg_layout) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
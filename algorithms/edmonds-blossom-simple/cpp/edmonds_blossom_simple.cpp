@@ -16,6 +16,7 @@
  */
 #include <cstdio>
 #include <cstdlib>
+#include <cstdint>
 #include <vector>
 #include <algorithm>
 #include <string>
@@ -23,6 +24,25 @@
 #include <chrono>
 #include <cassert>
 
+/* SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+   stream, so --greedy-random --seed S picks the same matching there */
+struct SplitMix64 {
+    uint64_t state;
+    explicit SplitMix64(uint64_t seed) : state(seed) {}
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+    /* uniform in [0, bound) */
+    uint64_t below(uint64_t bound) { return (uint64_t)(((unsigned __int128)next() * bound) >> 64); }
+    template <typename T> void shuffle(std::vector<T>& v) {
+        for (size_t i = v.size(); i > 1; i--) std::swap(v[i - 1], v[below(i)]);
+    }
+};
+
 struct Solver {
     int n;
     std::vector<std::vector<int>> adj;
@@ -448,6 +468,9 @@ struct Solver {
     // ---- Greedy initialization ----
 
     int greedy_size = 0;
+    uint64_t greedy_seed = 1; /* for greedy_mode 4 (--greedy-random) */
+    int aug3_passes = 0; /* --init-aug3 passes after the greedy start; 0 = off */
+    int aug3_size = 0;   /* pairs those passes added, counted in greedy_size */
 
     int greedy_init() {
         int cnt = 0;
@@ -478,11 +501,95 @@ struct Solver {
         return cnt;
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor, match the two;
+       otherwise match the lowest-id exposed vertex that has one with its first unmatched neighbor */
+    int greedy_init_ks() {
+        int cnt = 0;
+        std::vector<int> deg(n, 0), leaves;
+        for (int u = 0; u < n; u++) {
+            if (mate[u] != -1) continue;
+            for (int v : adj[u]) if (mate[v] == -1) deg[u]++;
+            if (deg[u] == 1) leaves.push_back(u);
+        }
+        int next = 0;
+        while (true) {
+            int u;
+            if (!leaves.empty()) {
+                u = leaves.back(); leaves.pop_back();
+                if (mate[u] != -1 || deg[u] != 1) continue;
+            } else {
+                while (next < n && (mate[next] != -1 || deg[next] == 0)) next++;
+                if (next == n) break;
+                u = next;
+            }
+            int v = -1;
+            for (int w : adj[u]) if (mate[w] == -1) { v = w; break; }
+            mate[u] = v; mate[v] = u; cnt++;
+            for (int x : {u, v})
+                for (int w : adj[x])
+                    if (mate[w] == -1 && --deg[w] == 1) leaves.push_back(w);
+        }
+        return cnt;
+    }
+
+    /* Random greedy: vertices in a seeded random order, each exposed one
+       matched with a uniformly random unmatched neighbor */
+    int greedy_init_random(uint64_t seed) {
+        SplitMix64 rng(seed);
+        std::vector<int> order(n);
+        for (int u = 0; u < n; u++) order[u] = u;
+        rng.shuffle(order);
+        int cnt = 0;
+        std::vector<int> free;
+        for (int u : order) {
+            if (mate[u] != -1) continue;
+            free.clear();
+            for (int v : adj[u]) if (mate[v] == -1) free.push_back(v);
+            if (free.empty()) continue;
+            int v = free[rng.below(free.size())];
+            mate[u] = v; mate[v] = u; cnt++;
+        }
+        return cnt;
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+       free vertices, each taking the first path u - v = w - x from u (x
+       free, x != u) or a free neighbor outright. A pass without one ends
+       it; next[w] skips neighbors of w that are matched for good. */
+    int augment3(int passes) {
+        std::vector<size_t> next(n, 0);
+        int cnt = 0;
+        for (int p = 0; p < passes; p++) {
+            int before = cnt;
+            for (int u = 0; u < n; u++) {
+                if (mate[u] != -1) continue;
+                for (int v : adj[u]) {
+                    if (mate[v] == -1) { mate[u] = v; mate[v] = u; cnt++; break; }
+                    int w = mate[v];
+                    const std::vector<int>& nw = adj[w];
+                    while (next[w] < nw.size() && mate[nw[next[w]]] != -1) next[w]++;
+                    size_t j = next[w];
+                    while (j < nw.size() && (nw[j] == u || mate[nw[j]] != -1)) j++;
+                    if (j < nw.size()) {
+                        int x = nw[j];
+                        mate[u] = v; mate[v] = u; mate[w] = x; mate[x] = w; cnt++;
+                        break;
+                    }
+                }
+            }
+            if (cnt == before) break;
+        }
+        return cnt;
+    }
+
     // ---- Main solver ----
 
     std::vector<std::pair<int,int>> solve(int greedy_mode = 0) {
         if (greedy_mode == 1) greedy_size = greedy_init();
         else if (greedy_mode == 2) greedy_size = greedy_init_md();
+        else if (greedy_mode == 3) greedy_size = greedy_init_ks();
+        else if (greedy_mode == 4) greedy_size = greedy_init_random(greedy_seed);
+        if (aug3_passes > 0) { aug3_size = augment3(aug3_passes); greedy_size += aug3_size; }
 
         bool improved = true;
         while (improved) {
@@ -571,14 +678,24 @@ int main(int argc, char* argv[]) {
     printf("Edmonds' Blossom Algorithm (Simple) - C++ Implementation\n"
            "=========================================================\n\n");
     if (argc < 2) {
-        printf("Usage: %s <filename> [--greedy|--greedy-md]\n", argv[0]);
+        printf("Usage: %s <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]\n", argv[0]);
         return 1;
     }
     int gm = 0;
+    uint64_t seed = 1;
+    int aug3 = 0, aug3_passes = 3;
     for (int i = 2; i < argc; i++) {
         if (std::string(argv[i]) == "--greedy") gm = 1;
         else if (std::string(argv[i]) == "--greedy-md") gm = 2;
+        else if (std::string(argv[i]) == "--greedy-ks") gm = 3;
+        else if (std::string(argv[i]) == "--greedy-random") gm = 4;
+        else if (std::string(argv[i]) == "--seed" && i + 1 < argc) seed = strtoull(argv[++i], nullptr, 10);
+        else if (std::string(argv[i]) == "--init-aug3") aug3 = 1;
+        else if (std::string(argv[i]) == "--aug3-passes" && i + 1 < argc) aug3_passes = atoi(argv[++i]);
     }
+    if (aug3_passes < 1) { fprintf(stderr, "Error: --aug3-passes must be a positive count\n"); return 1; }
+    /* --init-aug3 alone runs after the plain greedy start */
+    if (aug3) { aug3 = aug3_passes; if (gm == 0) gm = 1; }
     FILE* f = fopen(argv[1], "r");
     if (!f) { fprintf(stderr, "Cannot open: %s\n", argv[1]); return 1; }
     int nn, m;
@@ -594,12 +711,15 @@ int main(int argc, char* argv[]) {
     printf("Graph: %d vertices, %d edges\n", nn, (int)edges.size());
     auto t0 = std::chrono::high_resolution_clock::now();
     Solver sol(nn, edges);
+    sol.greedy_seed = seed;
+    sol.aug3_passes = aug3;
     auto matching = sol.solve(gm);
     auto t1 = std::chrono::high_resolution_clock::now();
     validate_matching(nn, sol.adj, matching);
     printf("Matching size: %d\n", (int)matching.size());
     if (gm > 0) {
         printf("Greedy init size: %d\n", sol.greedy_size);
+        if (aug3 > 0) printf("Aug3 augmentations: %d\n", sol.aug3_size);
         if (!matching.empty())
             printf("Greedy/Final: %.2f%%\n", 100.0 * sol.greedy_size / matching.size());
     }
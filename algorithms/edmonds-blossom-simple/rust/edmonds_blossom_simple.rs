@@ -1,17 +1,60 @@
 /*
  * Edmonds' Blossom Algorithm (Simple) — Unweighted Maximum Cardinality Matching
  *
- * Rust implementation. Single-source BFS (tree, not forest). Blossom IDs
- * reset to n each BFS. All indices are i32 (same type as vertex indices).
+ * Rust implementation. Each pass grows a forest: every free vertex is an
+ * S-root, and an S-S edge between two trees is an augmenting path. After
+ * an augmentation the two trees are dead for the rest of the pass (their
+ * labels no longer fit the matching) and the search goes on in the
+ * others, so one pass augments many vertex-disjoint paths. A pass without
+ * an augmentation is a complete search and ends the algorithm. Blossom IDs
+ * reset to n each pass. All indices are i32 (same type as vertex indices);
+ * new, set_mate and solve take and return the suite's ids (types.rs).
  *
- * Complexity: O(V^2 * E) worst case.
+ * Complexity: O(V^2 * E) worst case, as before; at most V/2 passes, and
+ * in practice a handful.
  */
 
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use self::types::Vertex;
+
+/* Internal ids are i32 (types.rs), so the vertex count is capped below
+ * types::MAX_VERTICES. */
+pub(crate) const MAX_VERTICES: usize = i32::MAX as usize;
+
 // ── Blossom data ─────────────────────────────────────────────────────
 
 #[derive(Clone)]
@@ -26,9 +69,11 @@ impl Blos {
 
 // ── Solver ───────────────────────────────────────────────────────────
 
-struct Solver {
+pub(crate) struct Solver {
     n: i32,
-    adj: Vec<Vec<i32>>,
+    /* CSR adjacency: neighbors of v are adj[adj_start[v]..adj_start[v + 1]] */
+    adj_start: Vec<usize>,
+    adj: Vec<i32>,
     mate: Vec<i32>,
 
     blos: Vec<Blos>,
@@ -42,23 +87,39 @@ struct Solver {
     labeledge: Vec<(i32, i32)>,
     queue: Vec<i32>,
 
-    greedy_size: i32,
+    tree: Vec<i32>,               // root of the tree a labeled vertex is in
+    dead: Vec<bool>,              // per root: the tree augmented this pass
+
+    pub(crate) greedy_size: i32,
+    pub(crate) greedy_seed: u64, /* for greedy_mode 4 (--greedy-random) */
+    pub(crate) aug3_passes: usize, /* --init-aug3 passes after the greedy start; 0 = off */
+    pub(crate) aug3_size: i32,     /* pairs those passes added, counted in greedy_size */
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path and blossom (trace.rs) */
+    trace_mate: Vec<usize>, /* for the tracer: the mates before the last augmentation */
 }
 
 impl Solver {
-    fn new(n: i32, edges: &[(i32, i32)]) -> Self {
-        let nu = n as usize;
-        let mut adj = vec![Vec::new(); nu];
-        for &(u, v) in edges {
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let nu = n;
+        let n = n as i32;
+        let mut lists = vec![Vec::new(); nu];
+        for &(u, v) in &types::edges_i32(nu, edges) {
             if u != v && u >= 0 && u < n && v >= 0 && v < n {
-                adj[u as usize].push(v);
-                adj[v as usize].push(u);
+                lists[u as usize].push(v);
+                lists[v as usize].push(u);
             }
         }
-        for a in &mut adj {
+        let mut adj_start = Vec::with_capacity(nu + 1);
+        let mut adj = Vec::new();
+        for a in &mut lists {
             a.sort_unstable();
             a.dedup();
+            adj_start.push(adj.len());
+            adj.extend_from_slice(a);
         }
+        adj_start.push(adj.len());
 
         let mut inblossom = vec![0i32; nu];
         let mut blossombase = vec![0i32; nu];
@@ -69,17 +130,29 @@ impl Solver {
         }
 
         Solver {
-            n, adj, mate: vec![-1; nu],
+            n, adj_start, adj, mate: vec![-1; nu],
             blos: vec![Blos::new(); nu],
             nblos: n,
             inblossom, blossomparent, blossombase,
             label: Vec::new(), labeledge: Vec::new(), queue: Vec::new(),
+            tree: vec![-1; nu], dead: vec![false; nu],
             greedy_size: 0,
+            greedy_seed: 1,
+            aug3_passes: 0,
+            aug3_size: 0,
+            stop: None,
+            progress: None,
+            trace: None,
+            trace_mate: Vec::new(),
         }
     }
 
     fn is_blossom(&self, b: i32) -> bool { b >= self.n }
 
+    fn neighbors(&self, v: i32) -> &[i32] {
+        &self.adj[self.adj_start[v as usize]..self.adj_start[v as usize + 1]]
+    }
+
     fn ensure(&mut self, b: i32) {
         let needed = (b + 1) as usize;
         if self.label.len() < needed {
@@ -100,7 +173,7 @@ impl Solver {
         }
     }
 
-    // ── Reset for new BFS ────────────────────────────────────────────
+    // ── Reset for new pass ───────────────────────────────────────────
 
     fn reset_blossoms(&mut self) {
         self.nblos = self.n;
@@ -117,8 +190,11 @@ impl Solver {
         self.label = vec![0; nu];
         self.labeledge = vec![(-1, -1); nu];
         self.queue.clear();
+        for d in &mut self.dead { *d = false; }
     }
 
+    fn alive(&self, v: i32) -> bool { !self.dead[self.tree[v as usize] as usize] }
+
     // ── Tree building ────────────────────────────────────────────────
 
     fn assign_label(&mut self, w: i32, t: i32, v: i32) {
@@ -126,18 +202,27 @@ impl Solver {
         self.ensure(b);
         self.label[b as usize] = t;
         self.label[w as usize] = t;
-        if v != -1 {
+        let root = if v != -1 {
             self.labeledge[w as usize] = (v, w);
             self.labeledge[b as usize] = (v, w);
+            self.tree[v as usize]
         } else {
             self.labeledge[w as usize] = (-1, -1);
             self.labeledge[b as usize] = (-1, -1);
-        }
-        if t == 1 {
+            w
+        };
+        if b < self.n {
+            self.tree[b as usize] = root;
+            if t == 1 { self.queue.push(b); }
+        } else {
             let mut lv = Vec::new();
             self.leaves(b, &mut lv);
-            for u in lv { self.queue.push(u); }
-        } else if t == 2 {
+            for &u in &lv { self.tree[u as usize] = root; }
+            if t == 1 {
+                for u in lv { self.queue.push(u); }
+            }
+        }
+        if t == 2 {
             let base = self.blossombase[b as usize];
             let mb = self.mate[base as usize];
             self.assign_label(mb, 1, base);
@@ -286,8 +371,9 @@ impl Solver {
 
     fn relabel_expanded_t_blossom(&mut self, b: i32) {
         let entrychild = self.inblossom[self.labeledge[b as usize].1 as usize];
-        let childs = self.blos[b as usize].childs.clone();
-        let edges = self.blos[b as usize].edges.clone();
+        /* The caller clears b's lists right after; nothing below reads them. */
+        let childs = std::mem::take(&mut self.blos[b as usize].childs);
+        let edges = std::mem::take(&mut self.blos[b as usize].edges);
         let k = childs.len() as i32;
 
         let mut j: i32 = 0;
@@ -482,24 +568,27 @@ impl Solver {
 
     // ── Augmenting path ──────────────────────────────────────────────
 
+    /* S-S edge (v, w) between two trees: flip both tree paths, from v and
+     * from w up to their roots, and the edge itself. */
     fn augment_path(&mut self, v: i32, w: i32) {
-        let mut s = v;
-        let mut j = w;
-        loop {
-            let bs = self.inblossom[s as usize];
-            if self.is_blossom(bs) { self.augment_blossom(bs, s); }
-            self.mate[s as usize] = j;
-            let le = self.labeledge[bs as usize];
-            if le.0 == -1 { break; }
-            let t = le.0;
-            let bt = self.inblossom[t as usize];
-            let le2 = self.labeledge[bt as usize];
-            s = le2.0;
-            j = le2.1;
-            if self.is_blossom(bt) { self.augment_blossom(bt, j); }
-            self.mate[j as usize] = s;
-        }
-        self.mate[w as usize] = v;
+        for &(start, end) in &[(v, w), (w, v)] {
+            let mut s = start;
+            let mut j = end;
+            loop {
+                let bs = self.inblossom[s as usize];
+                if self.is_blossom(bs) { self.augment_blossom(bs, s); }
+                self.mate[s as usize] = j;
+                let le = self.labeledge[bs as usize];
+                if le.0 == -1 { break; }
+                let t = le.0;
+                let bt = self.inblossom[t as usize];
+                let le2 = self.labeledge[bt as usize];
+                s = le2.0;
+                j = le2.1;
+                if self.is_blossom(bt) { self.augment_blossom(bt, j); }
+                self.mate[j as usize] = s;
+            }
+        }
     }
 
     // ── Greedy initialization ────────────────────────────────────────
@@ -508,7 +597,8 @@ impl Solver {
         let mut cnt = 0;
         for u in 0..self.n {
             if self.mate[u as usize] != -1 { continue; }
-            for &v in &self.adj[u as usize].clone() {
+            for k in self.adj_start[u as usize]..self.adj_start[u as usize + 1] {
+                let v = self.adj[k];
                 if self.mate[v as usize] == -1 {
                     self.mate[u as usize] = v;
                     self.mate[v as usize] = u;
@@ -524,9 +614,7 @@ impl Solver {
         let mut cnt = 0;
         let mut deg = vec![0i32; self.n as usize];
         for u in 0..self.n as usize {
-            for &v in &self.adj[u] {
-                deg[v as usize] += 1;
-            }
+            deg[u] = (self.adj_start[u + 1] - self.adj_start[u]) as i32;
         }
         let mut order: Vec<i32> = (0..self.n).collect();
         order.sort_unstable_by(|&a, &b| deg[a as usize].cmp(&deg[b as usize]).then(a.cmp(&b)));
@@ -534,7 +622,7 @@ impl Solver {
             if self.mate[u as usize] != -1 { continue; }
             let mut best = -1i32;
             let mut bd = i32::MAX;
-            for &v in &self.adj[u as usize] {
+            for &v in self.neighbors(u) {
                 if self.mate[v as usize] == -1 && deg[v as usize] < bd {
                     best = v;
                     bd = deg[v as usize];
@@ -549,69 +637,238 @@ impl Solver {
         cnt
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor,
+     * match the two; otherwise match the lowest-id exposed vertex that has
+     * one with its first unmatched neighbor. deg counts unmatched neighbors. */
+    fn greedy_init_ks(&mut self) -> i32 {
+        let mut deg = vec![0i32; self.n as usize];
+        for u in 0..self.n {
+            if self.mate[u as usize] == -1 {
+                deg[u as usize] = self.neighbors(u).iter().filter(|&&v| self.mate[v as usize] == -1).count() as i32;
+            }
+        }
+        let mut leaves: Vec<i32> = (0..self.n).filter(|&v| deg[v as usize] == 1).collect();
+        let mut next = 0;
+        let mut cnt = 0;
+        loop {
+            let u = match leaves.pop() {
+                Some(u) if self.mate[u as usize] != -1 || deg[u as usize] != 1 => continue,
+                Some(u) => u,
+                None => {
+                    while next < self.n && (self.mate[next as usize] != -1 || deg[next as usize] == 0) { next += 1; }
+                    if next == self.n { break; }
+                    next
+                }
+            };
+            let v = *self.neighbors(u).iter().find(|&&v| self.mate[v as usize] == -1).unwrap();
+            self.mate[u as usize] = v;
+            self.mate[v as usize] = u;
+            cnt += 1;
+            for &x in &[u, v] {
+                for &w in self.neighbors(x) {
+                    if self.mate[w as usize] != -1 { continue; }
+                    deg[w as usize] -= 1;
+                    if deg[w as usize] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched neighbor. The
+     * same seed gives the same matching. */
+    fn greedy_init_random(&mut self, seed: u64) -> i32 {
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<i32> = (0..self.n).collect();
+        rng.shuffle(&mut order);
+        let mut cnt = 0;
+        for u in order {
+            if self.mate[u as usize] != -1 { continue; }
+            let free = self.neighbors(u).iter().filter(|&&v| self.mate[v as usize] == -1).count();
+            if free == 0 { continue; }
+            let k = rng.below(free as u64) as usize;
+            let v = *self.neighbors(u).iter().filter(|&&v| self.mate[v as usize] == -1).nth(k).unwrap();
+            self.mate[u as usize] = v;
+            self.mate[v as usize] = u;
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+     * free vertices, each taking the first path u - v = w - x from u (x
+     * free, x != u) or a free neighbor outright. A pass without one ends
+     * it; next[w] skips neighbors of w that are matched for good. */
+    fn augment3(&mut self, passes: usize) -> i32 {
+        let mut next: Vec<usize> = self.adj_start[..self.n as usize].to_vec();
+        let mut cnt = 0;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..self.n {
+                if self.mate[u as usize] != -1 { continue; }
+                for k in self.adj_start[u as usize]..self.adj_start[u as usize + 1] {
+                    let v = self.adj[k];
+                    if self.mate[v as usize] == -1 {
+                        self.mate[u as usize] = v;
+                        self.mate[v as usize] = u;
+                        cnt += 1;
+                        break;
+                    }
+                    let w = self.mate[v as usize] as usize;
+                    let end = self.adj_start[w + 1];
+                    while next[w] < end && self.mate[self.adj[next[w]] as usize] != -1 { next[w] += 1; }
+                    let mut j = next[w];
+                    while j < end && (self.adj[j] == u || self.mate[self.adj[j] as usize] != -1) { j += 1; }
+                    if j < end {
+                        let x = self.adj[j];
+                        self.mate[u as usize] = v;
+                        self.mate[v as usize] = u;
+                        self.mate[w] = x;
+                        self.mate[x as usize] = w as i32;
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
     // ── Main solver ──────────────────────────────────────────────────
 
-    fn solve(&mut self, greedy_mode: i32) -> Vec<(i32, i32)> {
+    /* A stop was requested (SIGINT/SIGTERM in combi): the caller gets the
+     * matching of the last finished phase. */
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+    }
+
+    fn trace_blossom(&self, base: usize, v: usize, w: usize) {
+        if let Some(t) = self.trace {
+            t("blossom", &[base, v, w]);
+        }
+    }
+
+    /* x is on the path just flipped. */
+    fn trace_augment(&mut self, x: usize) {
+        if let Some(t) = self.trace {
+            let mut before = std::mem::take(&mut self.trace_mate);
+            trace::augmented(t, x, &|v| self.mate(v), &mut before);
+            self.trace_mate = before;
+        }
+    }
+
+    /* Start from a matching found elsewhere (mate[v] = NONE for free v),
+     * e.g. --init-matching; solve(0) then only augments it. */
+    pub(crate) fn set_mate(&mut self, mate: &[Vertex]) {
+        for (m, &v) in self.mate.iter_mut().zip(mate) {
+            *m = types::to_i32(v);
+        }
+    }
+
+    /* Per-vertex queries on the current matching, as on matchers::Solution. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        self.mate.get(v).filter(|&&m| m >= 0).map(|&m| m as usize)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n as usize).filter(move |&v| self.mate[v] < 0)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.mate.iter().filter(|&&m| m >= 0).count() / 2
+    }
+
+    pub(crate) fn solve(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
         if greedy_mode == 1 { self.greedy_size = self.greedy_init(); }
         else if greedy_mode == 2 { self.greedy_size = self.greedy_init_md(); }
+        else if greedy_mode == 3 { self.greedy_size = self.greedy_init_ks(); }
+        else if greedy_mode == 4 { self.greedy_size = self.greedy_init_random(self.greedy_seed); }
+        if self.aug3_passes > 0 {
+            self.aug3_size = self.augment3(self.aug3_passes);
+            self.greedy_size += self.aug3_size;
+        }
 
+        /* for the progress reports: pairs before the stages, and found since */
+        let start = if self.progress.is_some() { self.matching_size() } else { 0 };
+        if let Some(t) = self.trace {
+            self.trace_mate = trace::begin(t, self.n as usize, &|v| self.mate(v));
+        }
+        let (mut stage, mut found) = (0, 0);
         let mut improved = true;
-        while improved {
+        while improved && !self.stopped() {
             improved = false;
+            self.reset_blossoms();
             for root in 0..self.n {
-                if self.mate[root as usize] != -1 { continue; }
-
-                self.reset_blossoms();
-                self.assign_label(root, 1, -1);
-
-                let mut augmented = false;
-                let mut qi = 0usize;
-                while qi < self.queue.len() && !augmented {
-                    let v = self.queue[qi];
-                    qi += 1;
-                    if self.label[self.inblossom[v as usize] as usize] != 1 { continue; }
-
-                    let neighbors = self.adj[v as usize].clone();
-                    for &w in &neighbors {
-                        let bv = self.inblossom[v as usize];
-                        let bw = self.inblossom[w as usize];
-                        if bv == bw { continue; }
-                        self.ensure(bw);
-
-                        let lbw = self.label[bw as usize];
-                        if lbw == 0 {
-                            if self.mate[w as usize] == -1 {
-                                self.augment_path(v, w);
-                                augmented = true;
-                                break;
-                            }
-                            self.assign_label(w, 2, v);
-                        } else if lbw == 1 {
-                            let base = self.scan_blossom(v, w);
-                            if base >= 0 {
-                                self.add_blossom(base, v, w);
-                            }
+                if self.mate[root as usize] == -1 { self.assign_label(root, 1, -1); }
+            }
+
+            let mut qi = 0usize;
+            while qi < self.queue.len() {
+                let v = self.queue[qi];
+                qi += 1;
+                if self.label[self.inblossom[v as usize] as usize] != 1 || !self.alive(v) { continue; }
+
+                for k in self.adj_start[v as usize]..self.adj_start[v as usize + 1] {
+                    let w = self.adj[k];
+                    let bv = self.inblossom[v as usize];
+                    let bw = self.inblossom[w as usize];
+                    if bv == bw { continue; }
+                    self.ensure(bw);
+
+                    let lbw = self.label[bw as usize];
+                    if lbw == 0 {
+                        /* unlabeled vertices are matched: every free one is a root */
+                        self.assign_label(w, 2, v);
+                    } else if lbw == 1 && self.alive(w) {
+                        let base = self.scan_blossom(v, w);
+                        if base >= 0 {
+                            self.trace_blossom(base as usize, v as usize, w as usize);
+                            self.add_blossom(base, v, w);
+                        } else {
+                            /* Different trees -> augmenting path */
+                            self.augment_path(v, w);
+                            self.trace_augment(v as usize);
+                            self.dead[self.tree[v as usize] as usize] = true;
+                            self.dead[self.tree[w as usize] as usize] = true;
+                            improved = true;
+                            found += 1;
+                            break;
                         }
                     }
                 }
+            }
 
-                // Expand all remaining blossoms
-                for b in self.n..self.nblos {
-                    if !self.blos[b as usize].childs.is_empty()
-                        && self.blossomparent[b as usize] == -1
-                    {
-                        self.expand_blossom(b, true);
-                    }
+            // Expand all remaining blossoms
+            for b in self.n..self.nblos {
+                if !self.blos[b as usize].childs.is_empty()
+                    && self.blossomparent[b as usize] == -1
+                {
+                    self.expand_blossom(b, true);
                 }
-
-                if augmented { improved = true; break; }
+            }
+            stage += 1;
+            if let Some(report) = self.progress {
+                report(stage, found, self.n as usize - 2 * (start + found));
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[stage]);
             }
         }
 
         let mut result = Vec::new();
         for u in 0..self.n {
             let m = self.mate[u as usize];
-            if m != -1 && m > u { result.push((u, m)); }
+            if m != -1 && m > u { result.push((u as usize, m as usize)); }
         }
         result.sort_unstable();
         result
@@ -620,61 +877,14 @@ impl Solver {
 
 // ── Validation and main ──────────────────────────────────────────────
 
-fn validate_matching(n: i32, graph: &[Vec<i32>], matching: &[(i32, i32)]) {
-    let mut deg = vec![0i32; n as usize];
-    let mut errors = 0;
-    for &(u, v) in matching {
-        if graph[u as usize].binary_search(&v).is_err() {
-            eprintln!("ERROR: Edge ({},{}) not in graph!", u, v);
-            errors += 1;
-        }
-        deg[u as usize] += 1;
-        deg[v as usize] += 1;
-    }
-    for i in 0..n as usize {
-        if deg[i] > 1 {
-            eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]);
-            errors += 1;
-        }
-    }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
-}
-
-fn load_graph(filename: &str) -> Result<(i32, Vec<(i32, i32)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    let n: i32 = parts[0].parse()?;
-    let _m: i32 = parts[1].parse()?;
-
-    let mut edges = Vec::new();
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: i32 = parts[0].parse()?;
-            let v: i32 = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-    Ok((n, edges))
-}
-
 fn main() {
     println!("Edmonds' Blossom Algorithm (Simple) - Rust Implementation");
     println!("==========================================================\n");
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} {} {} {} {} {} {} {} {}", args[0],
+                  args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
         std::process::exit(1);
     }
 
@@ -683,33 +893,160 @@ fn main() {
         match a.as_str() {
             "--greedy" => gm = 1,
             "--greedy-md" => gm = 2,
+            "--greedy-ks" => gm = 3,
+            "--greedy-random" => gm = 4,
             _ => {}
         }
     }
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    if gm == 0 && aug3 > 0 { gm = 1; }
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            if n > MAX_VERTICES {
+                let e = error::SuiteError::TooManyVertices {
+                    n, ids: "i32".to_string(), algo: "this implementation".to_string(),
+                };
+                eprintln!("Error: {}", e);
+                std::process::exit(e.exit_code());
+            }
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(n) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
 
             let start = Instant::now();
             let mut sol = Solver::new(n, &edges);
+            sol.greedy_seed = seed;
+            sol.aug3_passes = aug3;
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                sol.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                sol.stop = Some(&time_limit::EXPIRED);
+            }
+            if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("edmonds-simple {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                sol.trace = Some(&trace::record);
+            } else if log::enabled(log::Level::Debug) {
+                sol.trace = Some(&log::event);
+            }
+            if let Some(ref pairs) = init {
+                sol.set_mate(&types::mate_of(n, pairs));
+            }
             let matching = sol.solve(gm);
             let duration = start.elapsed();
+            let gap = time_limit.filter(|_| time_limit::expired())
+                .map(|limit| (limit, time_limit::gap_bound(n, &edges, &matching)));
 
-            validate_matching(n, &sol.adj, &matching);
+            let mut report = verify::validate_general(n, &edges, &matching);
+            if args::has_flag(&args, "--certify") && report.is_valid() {
+                report.optimal = Some(vertex_classes::certify(n, &edges, &matching));
+            }
+            report.print();
 
             println!("Matching size: {}", matching.len());
             if gm > 0 {
                 println!("Greedy init size: {}", sol.greedy_size);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", sol.aug3_size);
+                }
                 if !matching.is_empty() {
                     println!("Greedy/Final: {:.2}%", 100.0 * sol.greedy_size as f64 / matching.len() as f64);
                 }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            if let Some(path) = args::value_of(&args, "--dot") {
+                if let Err(e) = dot::save(path, n, &edges, &matching, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = args::value_of(&args, "--svg") {
+                if let Err(e) = svg::save(path, n, &edges, &matching, None, svg_layout) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
@@ -22,6 +22,33 @@ EVEN = 1
 ODD = 2
 
 
+MASK64 = (1 << 64) - 1
+
+
+class SplitMix64:
+    """SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+    stream, so --greedy-random --seed S picks the same matching there."""
+
+    def __init__(self, seed):
+        self.state = seed & MASK64
+
+    def next(self):
+        self.state = (self.state + 0x9E3779B97F4A7C15) & MASK64
+        z = self.state
+        z = ((z ^ (z >> 30)) * 0xBF58476D1CE4E5B9) & MASK64
+        z = ((z ^ (z >> 27)) * 0x94D049BB133111EB) & MASK64
+        return z ^ (z >> 31)
+
+    def below(self, bound):
+        # uniform in [0, bound)
+        return (self.next() * bound) >> 64
+
+    def shuffle(self, items):
+        for i in range(len(items), 1, -1):
+            j = self.below(i)
+            items[i - 1], items[j] = items[j], items[i - 1]
+
+
 class GabowOptimized:
     def __init__(self, n, edges):
         self.n = n
@@ -464,6 +491,7 @@ class GabowOptimized:
     #                      MAIN ENTRY POINT
     # ================================================================
     greedy_size = 0
+    aug3_size = 0
 
     def _greedy_init(self):
         cnt = 0
@@ -498,11 +526,113 @@ class GabowOptimized:
                 cnt += 1
         return cnt
 
-    def maximum_matching(self, greedy_mode=0):
+    def _greedy_init_ks(self):
+        # Karp-Sipser: match degree-1 exposed vertices first; otherwise the
+        # lowest-id exposed vertex with an exposed neighbor
+        cnt = 0
+        n = self.n
+        deg = [0] * n
+        leaves = []
+        for u in range(n):
+            if self.mate[u] != NIL:
+                continue
+            deg[u] = sum(1 for v in self.graph[u] if self.mate[v] == NIL)
+            if deg[u] == 1:
+                leaves.append(u)
+        nxt = 0
+        while True:
+            if leaves:
+                u = leaves.pop()
+                if self.mate[u] != NIL or deg[u] != 1:
+                    continue
+            else:
+                while nxt < n and (self.mate[nxt] != NIL or deg[nxt] == 0):
+                    nxt += 1
+                if nxt == n:
+                    break
+                u = nxt
+            v = next(w for w in self.graph[u] if self.mate[w] == NIL)
+            self.mate[u] = v
+            self.mate[v] = u
+            cnt += 1
+            for x in (u, v):
+                for w in self.graph[x]:
+                    if self.mate[w] == NIL:
+                        deg[w] -= 1
+                        if deg[w] == 1:
+                            leaves.append(w)
+        return cnt
+
+    def _greedy_init_random(self, seed):
+        # Random greedy: vertices in a seeded random order, each exposed one
+        # matched with a uniformly random unmatched neighbor
+        rng = SplitMix64(seed)
+        order = list(range(self.n))
+        rng.shuffle(order)
+        cnt = 0
+        for u in order:
+            if self.mate[u] != NIL:
+                continue
+            free = [v for v in self.graph[u] if self.mate[v] == NIL]
+            if not free:
+                continue
+            v = free[rng.below(len(free))]
+            self.mate[u] = v
+            self.mate[v] = u
+            cnt += 1
+        return cnt
+
+    def _augment3(self, passes):
+        # --init-aug3, after the greedy start: up to `passes` sweeps over the
+        # free vertices, each taking the first path u - v = w - x from u (x
+        # free, x != u) or a free neighbor outright. A pass without one ends
+        # it; nxt[w] skips neighbors of w that are matched for good.
+        mate, graph = self.mate, self.graph
+        nxt = [0] * self.n
+        cnt = 0
+        for _ in range(passes):
+            before = cnt
+            for u in range(self.n):
+                if mate[u] != NIL:
+                    continue
+                for v in graph[u]:
+                    if mate[v] == NIL:
+                        mate[u] = v
+                        mate[v] = u
+                        cnt += 1
+                        break
+                    w = mate[v]
+                    nw = graph[w]
+                    j = nxt[w]
+                    while j < len(nw) and mate[nw[j]] != NIL:
+                        j += 1
+                    nxt[w] = j
+                    while j < len(nw) and (nw[j] == u or mate[nw[j]] != NIL):
+                        j += 1
+                    if j < len(nw):
+                        x = nw[j]
+                        mate[u] = v
+                        mate[v] = u
+                        mate[w] = x
+                        mate[x] = w
+                        cnt += 1
+                        break
+            if cnt == before:
+                break
+        return cnt
+
+    def maximum_matching(self, greedy_mode=0, seed=1, aug3=0):
         if greedy_mode == 1:
             self.greedy_size = self._greedy_init()
         elif greedy_mode == 2:
             self.greedy_size = self._greedy_init_md()
+        elif greedy_mode == 3:
+            self.greedy_size = self._greedy_init_ks()
+        elif greedy_mode == 4:
+            self.greedy_size = self._greedy_init_random(seed)
+        if aug3 > 0:
+            self.aug3_size = self._augment3(aug3)
+            self.greedy_size += self.aug3_size
 
         while self.phase_1():
             self.phase_2()
@@ -557,22 +687,43 @@ def main():
     print("==================================================================\n")
 
     if len(sys.argv) < 2:
-        print(f"Usage: {sys.argv[0]} <filename> [--greedy|--greedy-md]")
+        print(f"Usage: {sys.argv[0]} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]")
         sys.exit(1)
 
     greedy_mode = 0
-    for arg in sys.argv[2:]:
+    seed = 1
+    aug3 = 0
+    aug3_passes = 3
+    for i, arg in enumerate(sys.argv[2:], 2):
         if arg == "--greedy":
             greedy_mode = 1
         elif arg == "--greedy-md":
             greedy_mode = 2
+        elif arg == "--greedy-ks":
+            greedy_mode = 3
+        elif arg == "--greedy-random":
+            greedy_mode = 4
+        elif arg == "--seed" and i + 1 < len(sys.argv):
+            seed = int(sys.argv[i + 1])
+        elif arg == "--init-aug3":
+            aug3 = 1
+        elif arg == "--aug3-passes" and i + 1 < len(sys.argv):
+            aug3_passes = int(sys.argv[i + 1])
+    if aug3_passes < 1:
+        print("Error: --aug3-passes must be a positive count", file=sys.stderr)
+        sys.exit(1)
+    # --init-aug3 alone runs after the plain greedy start
+    if aug3:
+        aug3 = aug3_passes
+        if greedy_mode == 0:
+            greedy_mode = 1
 
     n, edges = load_graph(sys.argv[1])
     print(f"Graph: {n} vertices, {len(edges)} edges")
 
     t0 = time.time()
     gabow = GabowOptimized(n, edges)
-    matching = gabow.maximum_matching(greedy_mode)
+    matching = gabow.maximum_matching(greedy_mode, seed, aug3)
     t1 = time.time()
 
     validate_matching(n, gabow.graph, matching)
@@ -580,6 +731,8 @@ def main():
     print(f"Matching size: {len(matching)}")
     if greedy_mode > 0:
         print(f"Greedy init size: {gabow.greedy_size}")
+        if aug3 > 0:
+            print(f"Aug3 augmentations: {gabow.aug3_size}")
         if matching:
             print(f"Greedy/Final: {100.0 * gabow.greedy_size / len(matching):.2f}%")
         else:
@@ -0,0 +1,269 @@
+/*
+ * Gabow Adaptive - Gabow simple's forest phases, then the O(E√V) phases
+ *
+ * Starts with forest phases of the simple search (gabow_simple.rs): one
+ * forest grown from all free vertices, every vertex-disjoint augmenting
+ * path it meets kept, all of them flipped, reset. While augmenting paths
+ * are short and plentiful such a phase takes many at the price of one
+ * O(E) search and none of the level bookkeeping. Once the live statistics
+ * say that is over, the matching is handed to the phase-structured
+ * machinery of gabow_optimized.rs (the v2 architecture: level BFS,
+ * contracted graph H, all shortest paths per phase), which finishes it
+ * with its O(√V) phase bound.
+ *
+ * The switch is taken after the first forest phase where any of
+ *   - free vertices left <= free * n            (--switch-free, fraction)
+ *   - its longest path had more than L edges     (--switch-length L)
+ *   - it augmented fewer than P paths            (--switch-paths P)
+ * holds, or before the first one if the greedy start (--greedy,
+ * --greedy-md) already leaves few enough free vertices. --switch-free 1
+ * is Gabow optimized alone; --switch-free 0 --switch-length N
+ * --switch-paths 0 with N >= n never switches.
+ *
+ * Rust implementation -- fully deterministic, no hash containers.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::time::Instant;
+
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../gabow-simple/rust/gabow_simple.rs"]
+#[allow(dead_code)]
+mod gabow_simple;
+#[path = "gabow_optimized.rs"]
+#[allow(dead_code)]
+mod gabow_optimized;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/phase_stats.rs"]
+mod phase_stats;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+
+use self::types::{ix, Vertex, NONE};
+
+pub(crate) struct Thresholds {
+    pub(crate) free: f64,
+    pub(crate) length: usize,
+    pub(crate) paths: usize,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Thresholds { free: 0.001, length: 0, paths: 10 }
+    }
+}
+
+impl Thresholds {
+    pub(crate) fn from_args(args: &[String]) -> Result<Self, String> {
+        fn parse<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> Result<T, String> {
+            match args::value_of(args, name) {
+                Some(s) => s.parse().map_err(|_| format!("invalid {} '{}'", name, s)),
+                None => Ok(default),
+            }
+        }
+        let d = Thresholds::default();
+        Ok(Thresholds {
+            free: parse(args, "--switch-free", d.free)?,
+            length: parse(args, "--switch-length", d.length)?,
+            paths: parse(args, "--switch-paths", d.paths)?,
+        })
+    }
+}
+
+pub(crate) const SWITCH_USAGE: &str = "[--switch-free F] [--switch-length L] [--switch-paths P]";
+
+/* How a run went: the forest phases (augmentations each), why they
+ * stopped (None if they finished the matching), and the optimized phases
+ * after them. */
+pub(crate) struct Adaptive {
+    pub(crate) greedy_size: usize,
+    pub(crate) aug3_size: usize,
+    pub(crate) forest_phases: Vec<usize>,
+    pub(crate) switched: Option<(&'static str, usize)>, /* (reason, free vertices) */
+    pub(crate) phases: Vec<(usize, usize)>,
+}
+
+/* `seed` seeds --greedy-random (greedy_mode 4); `aug3` > 0 follows the
+ * greedy start with that many --init-aug3 passes. `init`, if given, is the
+ * mate array to start from (--init-matching), in place of the greedy
+ * start. */
+pub(crate) fn maximum_matching(n: usize, edges: &[(usize, usize)], greedy_mode: i32, seed: u64, aug3: usize,
+                               init: Option<&[Vertex]>, t: &Thresholds) -> (Vec<(usize, usize)>, Adaptive) {
+    let mut simple = gabow_simple::GabowSimple::new(n, edges);
+    simple.greedy_seed = seed;
+    simple.aug3_passes = aug3;
+    if let Some(mate) = init {
+        simple.set_mate(mate);
+    }
+    simple.start(greedy_mode);
+    let limit = t.free * n as f64;
+    let mut forest_phases = Vec::new();
+    let mut reason = "free";
+    let stopped = if simple.free_with_edges() as f64 <= limit {
+        true
+    } else {
+        simple.augment_while(&mut |s| {
+            forest_phases.push(s.paths);
+            reason = if s.free as f64 <= limit {
+                "free"
+            } else if t.length > 0 && s.longest > t.length {
+                "length"
+            } else if s.paths < t.paths {
+                "paths"
+            } else {
+                return true;
+            };
+            false
+        })
+    };
+    let mut run = Adaptive {
+        greedy_size: simple.greedy_size, aug3_size: simple.aug3_size,
+        forest_phases, switched: None, phases: Vec::new(),
+    };
+    let matching = if stopped {
+        run.switched = Some((reason, simple.free_with_edges()));
+        let mut opt = gabow_optimized::GabowOptimized::new(n, edges);
+        opt.set_mate(simple.mates());
+        let matching = opt.maximum_matching(0);
+        run.phases = opt.phases;
+        matching
+    } else {
+        let mut matching = Vec::new();
+        for (u, &m) in simple.mates().iter().enumerate() {
+            if m != NONE && ix(m) > u {
+                matching.push((u, ix(m)));
+            }
+        }
+        matching
+    };
+    (matching, run)
+}
+
+fn main() {
+    println!("Gabow Adaptive (Simple -> Optimized) - Rust Implementation");
+    println!("==========================================================\n");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} {} {} {} {}", args[0], args::AUG3_USAGE, SWITCH_USAGE,
+                  phase_stats::PHASE_STATS_USAGE, matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+
+    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-random") {
+        4
+    } else if args.iter().any(|a| a == "--greedy-ks") {
+        3
+    } else if args.iter().any(|a| a == "--greedy-md") {
+        2
+    } else if args.iter().any(|a| a == "--greedy") {
+        1
+    } else {
+        0
+    };
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    let greedy_mode = if greedy_mode == 0 && aug3 > 0 { 1 } else { greedy_mode };
+    let show_phases = args::has_flag(&args, "--phase-stats");
+    let thresholds = match Thresholds::from_args(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            println!("Graph: {} vertices, {} edges", n, edges.len());
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            let start = Instant::now();
+            let mate = init.as_ref().map(|pairs| types::mate_of(n, pairs));
+            let (matching, run) = maximum_matching(n, &edges, greedy_mode, seed, aug3, mate.as_deref(), &thresholds);
+            let duration = start.elapsed();
+            let report = verify::validate_general(n, &edges, &matching);
+            report.print();
+            println!("Matching size: {}", matching.len());
+            println!("Forest phases: {} ({} augmentations)", run.forest_phases.len(),
+                     run.forest_phases.iter().sum::<usize>());
+            match run.switched {
+                Some((reason, free)) => println!("Switched: {} ({} free vertices), {} phases",
+                                                 reason, free, run.phases.len()),
+                None => println!("Switched: never"),
+            }
+            if show_phases {
+                phase_stats::write_phase_stats(&mut std::io::stdout(), &run.phases).unwrap();
+            }
+            if greedy_mode > 0 {
+                let gs = run.greedy_size;
+                let fs = matching.len();
+                println!("Greedy init size: {}", gs);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", run.aug3_size);
+                }
+                if fs > 0 { println!("Greedy/Final: {:.2}%", 100.0 * gs as f64 / fs as f64); }
+                else { println!("Greedy/Final: NA"); }
+            }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!("Time: {} ms", duration.as_millis());
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
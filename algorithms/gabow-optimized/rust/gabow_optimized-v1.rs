@@ -14,10 +14,29 @@
  */
 
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::time::Instant;
 
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dsu.rs"]
+mod dsu;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+
+use self::types::Vertex;
+
 const NIL: i32 = -1;
 const UNLABELED: i32 = 0;
 const EVEN: i32 = 1;
@@ -31,8 +50,8 @@ struct GabowOptimized {
     parent: Vec<i32>,
     source_bridge: Vec<i32>,
     target_bridge: Vec<i32>,
-    base_par: Vec<usize>,
-    dbase_par: Vec<usize>,
+    base: dsu::Dsu,
+    dbase: dsu::Dsu,
     level_queue: Vec<Vec<(usize, usize)>>,
     lca_tag1: Vec<usize>,
     lca_tag2: Vec<usize>,
@@ -50,7 +69,7 @@ struct GabowOptimized {
     dir_h: Vec<i32>,
     even_time_h: Vec<i32>,
     t_h: i32,
-    db2_par: Vec<usize>,
+    db2: dsu::Dsu,
     contracted_into: Vec<Vec<usize>>,
 }
 
@@ -72,8 +91,8 @@ impl GabowOptimized {
             parent: vec![NIL; n],
             source_bridge: vec![NIL; n],
             target_bridge: vec![NIL; n],
-            base_par: (0..n).collect(),
-            dbase_par: (0..n).collect(),
+            base: dsu::Dsu::new(n),
+            dbase: dsu::Dsu::new(n),
             level_queue: vec![Vec::new(); n + 2],
             lca_tag1: vec![0; n],
             lca_tag2: vec![0; n],
@@ -91,64 +110,16 @@ impl GabowOptimized {
             dir_h: vec![0; n],
             even_time_h: vec![0; n],
             t_h: 0,
-            db2_par: (0..n).collect(),
+            db2: dsu::Dsu::new(n),
             contracted_into: vec![Vec::new(); n],
         }
     }
 
-    fn find_base(&mut self, mut v: usize) -> usize {
-        while self.base_par[v] != v {
-            self.base_par[v] = self.base_par[self.base_par[v]];
-            v = self.base_par[v];
-        }
-        v
-    }
-    fn union_base(&mut self, a: usize, b: usize, r: usize) {
-        let fa = self.find_base(a);
-        let fb = self.find_base(b);
-        self.base_par[fa] = r;
-        self.base_par[fb] = r;
-    }
-
-    fn find_dbase(&mut self, mut v: usize) -> usize {
-        while self.dbase_par[v] != v {
-            self.dbase_par[v] = self.dbase_par[self.dbase_par[v]];
-            v = self.dbase_par[v];
-        }
-        v
-    }
-    fn union_dbase(&mut self, a: usize, b: usize) {
-        let fa = self.find_dbase(a);
-        let fb = self.find_dbase(b);
-        if fa != fb { self.dbase_par[fa] = fb; }
-    }
-    fn make_rep_dbase(&mut self, v: usize) {
-        let r = self.find_dbase(v);
-        if r != v { self.dbase_par[r] = v; self.dbase_par[v] = v; }
-    }
-
-    fn find_db2(&mut self, mut v: usize) -> usize {
-        while self.db2_par[v] != v {
-            self.db2_par[v] = self.db2_par[self.db2_par[v]];
-            v = self.db2_par[v];
-        }
-        v
-    }
-    fn union_db2(&mut self, a: usize, b: usize) {
-        let fa = self.find_db2(a);
-        let fb = self.find_db2(b);
-        if fa != fb { self.db2_par[fa] = fb; }
-    }
-    fn make_rep_db2(&mut self, v: usize) {
-        let r = self.find_db2(v);
-        if r != v { self.db2_par[r] = v; self.db2_par[v] = v; }
-    }
-
     fn find_lca(&mut self, u: usize, v: usize) -> i32 {
         self.lca_epoch += 1;
         let ep = self.lca_epoch;
-        let mut hx = self.find_base(u);
-        let mut hy = self.find_base(v);
+        let mut hx = self.base.find(u);
+        let mut hy = self.base.find(v);
         self.lca_tag1[hx] = ep;
         self.lca_tag2[hy] = ep;
         loop {
@@ -158,11 +129,11 @@ impl GabowOptimized {
             let hyr = self.mate[hy] == NIL || self.parent[self.mate[hy] as usize] == NIL;
             if hxr && hyr { return NIL; }
             if !hxr {
-                hx = self.find_base(self.parent[self.mate[hx] as usize] as usize);
+                hx = self.base.find(self.parent[self.mate[hx] as usize] as usize);
                 self.lca_tag1[hx] = ep;
             }
             if !hyr {
-                hy = self.find_base(self.parent[self.mate[hy] as usize] as usize);
+                hy = self.base.find(self.parent[self.mate[hy] as usize] as usize);
                 self.lca_tag2[hy] = ep;
             }
         }
@@ -170,21 +141,20 @@ impl GabowOptimized {
 
     fn shrink_path(&mut self, b: usize, x: usize, y: usize,
                    dunions: &mut Vec<(usize, usize)>) {
-        let mut v = self.find_base(x);
+        let mut v = self.base.find(x);
         while v != b {
-            self.union_base(v, b, b);
+            self.base.union_into(v, b);
             dunions.push((v, b));
             let mv = self.mate[v] as usize;
-            self.union_base(mv, b, b);
+            self.base.union_into(mv, b);
             dunions.push((mv, b));
-            self.base_par[b] = b;
             self.source_bridge[mv] = x as i32;
             self.target_bridge[mv] = y as i32;
             let d = self.delta;
             let neighbors: Vec<usize> = self.graph[mv].clone();
             for w in neighbors {
                 if w as i32 == self.mate[mv] { continue; }
-                let bw = self.find_base(w);
+                let bw = self.base.find(w);
                 if self.label[bw] == ODD { continue; }
                 if self.label[bw] == UNLABELED {
                     self.level_queue[(d + 1) as usize].push((mv, w));
@@ -192,7 +162,7 @@ impl GabowOptimized {
                     self.level_queue[d as usize].push((mv, w));
                 }
             }
-            v = self.find_base(self.parent[mv] as usize);
+            v = self.base.find(self.parent[mv] as usize);
         }
         dunions.push((b, b));
     }
@@ -203,9 +173,9 @@ impl GabowOptimized {
         for q in &mut self.level_queue { q.clear(); }
         let mut dunions: Vec<(usize, usize)> = Vec::new();
 
+        self.base.reset();
+        self.dbase.reset();
         for i in 0..self.n {
-            self.base_par[i] = i;
-            self.dbase_par[i] = i;
             self.label[i] = UNLABELED;
             self.parent[i] = NIL;
             self.source_bridge[i] = NIL;
@@ -221,7 +191,7 @@ impl GabowOptimized {
                 let neighbors: Vec<usize> = self.graph[v].clone();
                 for u in neighbors {
                     if u as i32 == self.mate[v] { continue; }
-                    let bu = self.find_base(u);
+                    let bu = self.base.find(u);
                     if self.label[bu] == ODD { continue; }
                     if self.label[bu] == UNLABELED {
                         self.level_queue[1].push((v, u));
@@ -238,8 +208,8 @@ impl GabowOptimized {
             let d = self.delta as usize;
             while !self.level_queue[d].is_empty() {
                 let (mut z, mut u) = self.level_queue[d].pop().unwrap();
-                let mut bz = self.find_base(z);
-                let mut bu = self.find_base(u);
+                let mut bz = self.base.find(z);
+                let mut bu = self.base.find(u);
                 if self.label[bz] != EVEN {
                     std::mem::swap(&mut z, &mut u);
                     std::mem::swap(&mut bz, &mut bu);
@@ -263,7 +233,7 @@ impl GabowOptimized {
                     let delta = self.delta;
                     for w in neighbors {
                         if w as i32 == self.mate[mv] { continue; }
-                        let bw = self.find_base(w);
+                        let bw = self.base.find(w);
                         if self.label[bw] == ODD { continue; }
                         if self.label[bw] == UNLABELED {
                             self.level_queue[(delta + 1) as usize].push((mv, w));
@@ -286,15 +256,15 @@ impl GabowOptimized {
             if found_sap {
                 let tn: Vec<usize> = self.tree_nodes.clone();
                 for &v in &tn {
-                    let db = self.find_dbase(v);
+                    let db = self.dbase.find(v);
                     self.contracted_into[db].push(v);
                     self.mate_h[v] = NIL;
                 }
                 for &u in &tn {
-                    let uh = self.find_dbase(u);
+                    let uh = self.dbase.find(u);
                     let mv = self.mate[u];
                     if mv != NIL && self.in_tree[mv as usize] {
-                        let vh = self.find_dbase(mv as usize);
+                        let vh = self.dbase.find(mv as usize);
                         if uh != vh {
                             self.mate_h[uh] = vh as i32;
                             self.mate_h[vh] = uh as i32;
@@ -305,8 +275,8 @@ impl GabowOptimized {
             }
 
             for (a, b) in dunions.drain(..) {
-                if a == b { self.make_rep_dbase(a); }
-                else { self.union_dbase(a, b); }
+                if a == b { self.dbase.make_rep(a); }
+                else { self.dbase.union(a, b); }
             }
             self.delta += 1;
         }
@@ -327,10 +297,10 @@ impl GabowOptimized {
 
                     if !self.in_tree[w] { continue; }
                     if self.mate[v] == w as i32 { continue; }
-                    let dv = self.find_dbase(v);
-                    let dw = self.find_dbase(w);
+                    let dv = self.dbase.find(v);
+                    let dw = self.dbase.find(w);
                     if dv == dw { continue; }
-                    let uh = self.find_db2(self.rep[w]);
+                    let uh = self.db2.find(self.rep[w]);
                     if self.mate_h[vh] != NIL && self.mate_h[vh] as usize == uh { continue; }
                     if self.label_h[uh] == ODD { continue; }
 
@@ -352,8 +322,8 @@ impl GabowOptimized {
                         stk.push(Frame { vh: muh, ci_idx: 0, adj_idx: 0 });
                         continue 'outer;
                     } else if self.label_h[uh] == EVEN {
-                        let bh = self.find_db2(vh);
-                        let zh = self.find_db2(uh);
+                        let bh = self.db2.find(vh);
+                        let zh = self.db2.find(uh);
                         if self.even_time_h[bh] < self.even_time_h[zh] {
                             let mut tmp: Vec<usize> = Vec::new();
                             let mut endpoints: Vec<usize> = Vec::new();
@@ -366,10 +336,10 @@ impl GabowOptimized {
                                 let ps = self.parent_h_src[mc] as usize;
                                 let pt = self.parent_h_tgt[mc] as usize;
                                 let next = if self.rep[ps] == mc { self.rep[pt] } else { self.rep[ps] };
-                                cur = self.find_db2(next);
+                                cur = self.db2.find(next);
                             }
-                            for &nd in &endpoints { self.union_db2(nd, bh); }
-                            self.make_rep_db2(bh);
+                            for &nd in &endpoints { self.db2.union(nd, bh); }
+                            self.db2.make_rep(bh);
                             for &mc in &tmp {
                                 self.bridge_h_src[mc] = v as i32;
                                 self.bridge_h_tgt[mc] = w as i32;
@@ -486,14 +456,14 @@ impl GabowOptimized {
     fn phase_2(&mut self) {
         let tn: Vec<usize> = self.tree_nodes.clone();
         for &v in &tn {
-            let db = self.find_dbase(v);
+            let db = self.dbase.find(v);
             self.rep[v] = db;
             self.label_h[v] = UNLABELED;
             self.parent_h_src[v] = NIL; self.parent_h_tgt[v] = NIL;
             self.bridge_h_src[v] = NIL; self.bridge_h_tgt[v] = NIL;
             self.dir_h[v] = 0;
             self.even_time_h[v] = 0;
-            self.db2_par[v] = v;
+            self.db2.detach(v);
         }
         self.t_h = 0;
 
@@ -521,13 +491,21 @@ impl GabowOptimized {
         for path in &all_paths { self.augment_g(path); }
 
         for &v in &tn {
-            let db = self.find_dbase(v);
+            let db = self.dbase.find(v);
             self.contracted_into[db].clear();
             self.contracted_into[v].clear();
             self.mate_h[v] = NIL;
         }
     }
 
+    /* Start from a matching found elsewhere (--init-matching); the greedy
+     * pass of maximum_matching then only adds to it. */
+    fn set_mate(&mut self, mate: &[Vertex]) {
+        for (m, &v) in self.mate.iter_mut().zip(mate) {
+            *m = types::to_i32(v);
+        }
+    }
+
     fn maximum_matching(&mut self) -> Vec<(usize, usize)> {
         for u in 0..self.n {
             if self.mate[u] != NIL { continue; }
@@ -553,72 +531,65 @@ impl GabowOptimized {
     }
 }
 
-fn validate_matching(n: usize, graph: &[Vec<usize>], matching: &[(usize, usize)]) {
-    let mut deg = vec![0i32; n];
-    let mut errors = 0;
-    for &(u, v) in matching {
-        if graph[u].binary_search(&v).is_err() {
-            eprintln!("ERROR: Edge ({}, {}) not in graph!", u, v);
-            errors += 1;
-        }
-        deg[u] += 1; deg[v] += 1;
-    }
-    for i in 0..n {
-        if deg[i] > 1 { eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]); errors += 1; }
-    }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
-}
-
-fn load_graph(filename: &str) -> Result<(usize, Vec<(usize, usize)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    let n: usize = parts[0].parse()?;
-    let m: usize = parts[1].parse()?;
-    let mut edges = Vec::with_capacity(m);
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: usize = parts[0].parse()?;
-            let v: usize = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-    Ok((n, edges))
-}
-
 fn main() {
     println!("Gabow's Scaling Algorithm (Optimized) - Rust Implementation");
     println!("=============================================================\n");
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename>", args[0]);
+        eprintln!("Usage: {} <filename> {} {} {} {} {}", args[0], matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::REPORT_USAGE,
+                  log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
         std::process::exit(1);
     }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
             let start = Instant::now();
             let mut gabow = GabowOptimized::new(n, &edges);
+            if let Some(ref pairs) = init {
+                gabow.set_mate(&types::mate_of(n, pairs));
+            }
             let matching = gabow.maximum_matching();
             let duration = start.elapsed();
-            validate_matching(n, &gabow.graph, &matching);
+            let report = verify::validate_general(n, &edges, &matching);
+            report.print();
             println!("Matching size: {}", matching.len());
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
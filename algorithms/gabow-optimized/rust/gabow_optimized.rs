@@ -13,74 +13,136 @@
  * dual machinery. Rust implementation â€” fully deterministic, no hash containers.
  */
 
+#![allow(clippy::duplicate_mod)]
+
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
-const NIL: i32 = -1;
-const UNLABELED: i32 = 0;
-const EVEN: i32 = 1;
-const ODD: i32 = 2;
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dsu.rs"]
+mod dsu;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../common/rust/ordering.rs"]
+mod ordering;
+#[path = "../../common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../common/rust/phase_stats.rs"]
+mod phase_stats;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use self::types::{ix, vx, Vertex, NONE};
+
+const UNLABELED: u8 = 0;
+const EVEN: u8 = 1;
+const ODD: u8 = 2;
 
-struct GabowOptimized {
+/* A frame of find_ap_hg's DFS: an H-node, the position in its
+ * contracted_into list, and the position in that G-vertex's adjacency. */
+struct HFrame { vh: usize, ci_idx: usize, adj_idx: usize }
+
+pub(crate) struct GabowOptimized {
     n: usize,
-    greedy_size: usize,
-    graph: Vec<Vec<usize>>,
-    mate: Vec<i32>,
+    pub(crate) greedy_size: usize,
+    pub(crate) greedy_seed: u64, /* for greedy_mode 4 (--greedy-random) */
+    pub(crate) aug3_passes: usize, /* --init-aug3 passes after the greedy start; 0 = off */
+    pub(crate) aug3_size: usize,   /* pairs those passes added, counted in greedy_size */
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path and blossom (trace.rs) */
+    trace_mate: Vec<usize>, /* for the tracer: the mates before the last augmentation */
+    pub(crate) phases: Vec<(usize, usize)>, /* (path length, augmentations) */
+    pub(crate) max_path: Option<usize>, /* --epsilon: longest augmenting path searched, in edges */
+    graph: Vec<Vec<Vertex>>,
+    mate: Vec<Vertex>,
 
-    label: Vec<i32>,
-    parent: Vec<i32>,
-    source_bridge: Vec<i32>,
-    target_bridge: Vec<i32>,
+    label: Vec<u8>,
+    parent: Vec<Vertex>,
+    source_bridge: Vec<Vertex>,
+    target_bridge: Vec<Vertex>,
 
-    base_par: Vec<usize>,
-    dbase_par: Vec<usize>,
+    base: dsu::Dsu,
+    dbase: dsu::Dsu,
 
-    level_queue: Vec<Vec<(usize, usize)>>,
+    level_queue: Vec<Vec<(Vertex, Vertex)>>,
 
-    lca_tag1: Vec<usize>,
-    lca_tag2: Vec<usize>,
-    lca_epoch: usize,
+    lca_tag1: Vec<u32>,
+    lca_tag2: Vec<u32>,
+    lca_epoch: u32,
 
     in_tree: Vec<bool>,
     tree_nodes: Vec<usize>,
     delta: i32,
 
-    rep: Vec<usize>,
-    mate_h: Vec<i32>,
-    label_h: Vec<i32>,
-    parent_h_src: Vec<i32>,
-    parent_h_tgt: Vec<i32>,
-    bridge_h_src: Vec<i32>,
-    bridge_h_tgt: Vec<i32>,
-    dir_h: Vec<i32>,
+    rep: Vec<Vertex>,
+    mate_h: Vec<Vertex>,
+    label_h: Vec<u8>,
+    parent_h_src: Vec<Vertex>,
+    parent_h_tgt: Vec<Vertex>,
+    bridge_h_src: Vec<Vertex>,
+    bridge_h_tgt: Vec<Vertex>,
+    dir_h: Vec<i8>,
     even_time_h: Vec<i32>,
     t_h: i32,
-    db2_par: Vec<usize>,
-    contracted_into: Vec<Vec<usize>>,
+    db2: dsu::Dsu,
+    contracted_into: Vec<Vec<Vertex>>,
+    /* find_ap_hg's stack and blossom lists, kept from one search to the
+     * next so that phase 2 allocates nothing once they have grown */
+    h_stack: Vec<HFrame>,
+    h_cycle: Vec<usize>,
+    h_ends: Vec<usize>,
 }
 
 impl GabowOptimized {
-    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)]) -> Self {
         let mut graph = vec![Vec::new(); n];
         for &(u, v) in edges {
             if u < n && v < n && u != v {
-                graph[u].push(v);
-                graph[v].push(u);
+                graph[u].push(vx(v));
+                graph[v].push(vx(u));
             }
         }
         for adj in &mut graph { adj.sort_unstable(); adj.dedup(); }
 
         GabowOptimized {
             n, graph,
-            mate: vec![NIL; n],
+            mate: vec![NONE; n],
             label: vec![UNLABELED; n],
-            parent: vec![NIL; n],
-            source_bridge: vec![NIL; n],
-            target_bridge: vec![NIL; n],
-            base_par: (0..n).collect(),
-            dbase_par: (0..n).collect(),
+            parent: vec![NONE; n],
+            source_bridge: vec![NONE; n],
+            target_bridge: vec![NONE; n],
+            base: dsu::Dsu::new(n),
+            dbase: dsu::Dsu::new(n),
             level_queue: vec![Vec::new(); n + 2],
             lca_tag1: vec![0; n],
             lca_tag2: vec![0; n],
@@ -89,123 +151,94 @@ impl GabowOptimized {
             tree_nodes: Vec::new(),
             delta: 0,
             rep: vec![0; n],
-            mate_h: vec![NIL; n],
+            mate_h: vec![NONE; n],
             label_h: vec![UNLABELED; n],
-            parent_h_src: vec![NIL; n],
-            parent_h_tgt: vec![NIL; n],
-            bridge_h_src: vec![NIL; n],
-            bridge_h_tgt: vec![NIL; n],
+            parent_h_src: vec![NONE; n],
+            parent_h_tgt: vec![NONE; n],
+            bridge_h_src: vec![NONE; n],
+            bridge_h_tgt: vec![NONE; n],
             dir_h: vec![0; n],
             even_time_h: vec![0; n],
             t_h: 0,
-            db2_par: (0..n).collect(),
+            db2: dsu::Dsu::new(n),
             contracted_into: vec![Vec::new(); n],
+            h_stack: Vec::new(),
+            h_cycle: Vec::new(),
+            h_ends: Vec::new(),
             greedy_size: 0,
+            greedy_seed: 1,
+            aug3_passes: 0,
+            aug3_size: 0,
+            stop: None,
+            progress: None,
+            trace: None,
+            trace_mate: Vec::new(),
+            phases: Vec::new(),
+            max_path: None,
         }
     }
 
-    /* ---- union-find: base ---- */
-    fn find_base(&mut self, mut v: usize) -> usize {
-        while self.base_par[v] != v {
-            self.base_par[v] = self.base_par[self.base_par[v]];
-            v = self.base_par[v];
-        }
-        v
-    }
-    fn union_base(&mut self, a: usize, b: usize, r: usize) {
-        let fa = self.find_base(a);
-        let fb = self.find_base(b);
-        self.base_par[fa] = r;
-        self.base_par[fb] = r;
-    }
-
-    /* ---- union-find: dbase ---- */
-    fn find_dbase(&mut self, mut v: usize) -> usize {
-        while self.dbase_par[v] != v {
-            self.dbase_par[v] = self.dbase_par[self.dbase_par[v]];
-            v = self.dbase_par[v];
-        }
-        v
-    }
-    fn union_dbase(&mut self, a: usize, b: usize) {
-        let fa = self.find_dbase(a);
-        let fb = self.find_dbase(b);
-        if fa != fb { self.dbase_par[fa] = fb; }
-    }
-    fn make_rep_dbase(&mut self, v: usize) {
-        let r = self.find_dbase(v);
-        if r != v { self.dbase_par[r] = v; self.dbase_par[v] = v; }
-    }
-
-    /* ---- union-find: dbase2 ---- */
-    fn find_db2(&mut self, mut v: usize) -> usize {
-        while self.db2_par[v] != v {
-            self.db2_par[v] = self.db2_par[self.db2_par[v]];
-            v = self.db2_par[v];
-        }
-        v
-    }
-    fn union_db2(&mut self, a: usize, b: usize) {
-        let fa = self.find_db2(a);
-        let fb = self.find_db2(b);
-        if fa != fb { self.db2_par[fa] = fb; }
-    }
-    fn make_rep_db2(&mut self, v: usize) {
-        let r = self.find_db2(v);
-        if r != v { self.db2_par[r] = v; self.db2_par[v] = v; }
-    }
-
     /* ---- interleaved LCA ---- */
-    fn find_lca(&mut self, u: usize, v: usize) -> i32 {
+    fn find_lca(&mut self, u: usize, v: usize) -> Vertex {
+        if self.lca_epoch == u32::MAX {
+            self.lca_tag1.fill(0);
+            self.lca_tag2.fill(0);
+            self.lca_epoch = 0;
+        }
         self.lca_epoch += 1;
         let ep = self.lca_epoch;
-        let mut hx = self.find_base(u);
-        let mut hy = self.find_base(v);
+        let mut hx = self.base.find(u);
+        let mut hy = self.base.find(v);
         self.lca_tag1[hx] = ep;
         self.lca_tag2[hy] = ep;
         loop {
-            if self.lca_tag1[hy] == ep { return hy as i32; }
-            if self.lca_tag2[hx] == ep { return hx as i32; }
-            let hxr = self.mate[hx] == NIL || self.parent[self.mate[hx] as usize] == NIL;
-            let hyr = self.mate[hy] == NIL || self.parent[self.mate[hy] as usize] == NIL;
-            if hxr && hyr { return NIL; }
+            if self.lca_tag1[hy] == ep { return vx(hy); }
+            if self.lca_tag2[hx] == ep { return vx(hx); }
+            let hxr = self.mate[hx] == NONE || self.parent[ix(self.mate[hx])] == NONE;
+            let hyr = self.mate[hy] == NONE || self.parent[ix(self.mate[hy])] == NONE;
+            if hxr && hyr { return NONE; }
             if !hxr {
-                hx = self.find_base(self.parent[self.mate[hx] as usize] as usize);
+                hx = self.base.find(ix(self.parent[ix(self.mate[hx])]));
                 self.lca_tag1[hx] = ep;
             }
             if !hyr {
-                hy = self.find_base(self.parent[self.mate[hy] as usize] as usize);
+                hy = self.base.find(ix(self.parent[ix(self.mate[hy])]));
                 self.lca_tag2[hy] = ep;
             }
         }
     }
 
+    /* Queue the edges from EVEN v to its neighbors other than its mate:
+     * at level d to an EVEN base, at d + 1 to an unlabeled one. Indexes
+     * graph[v] instead of copying it, so the BFS allocates nothing but
+     * queue growth. */
+    fn queue_edges(&mut self, v: usize, d: usize) {
+        for k in 0..self.graph[v].len() {
+            let w = self.graph[v][k];
+            if w == self.mate[v] { continue; }
+            let bw = self.base.find(ix(w));
+            if self.label[bw] == UNLABELED {
+                self.level_queue[d + 1].push((vx(v), w));
+            } else if self.label[bw] == EVEN {
+                self.level_queue[d].push((vx(v), w));
+            }
+        }
+    }
+
     /* ---- shrink_path ---- */
     fn shrink_path(&mut self, b: usize, x: usize, y: usize,
                    dunions: &mut Vec<(usize, usize)>) {
-        let mut v = self.find_base(x);
+        let mut v = self.base.find(x);
         while v != b {
-            self.union_base(v, b, b);
+            self.base.union_into(v, b);
             dunions.push((v, b));
-            let mv = self.mate[v] as usize;
-            self.union_base(mv, b, b);
+            let mv = ix(self.mate[v]);
+            self.base.union_into(mv, b);
             dunions.push((mv, b));
-            self.base_par[b] = b;
-            self.source_bridge[mv] = x as i32;
-            self.target_bridge[mv] = y as i32;
-            let d = self.delta;
-            let neighbors: Vec<usize> = self.graph[mv].clone();
-            for w in neighbors {
-                if w as i32 == self.mate[mv] { continue; }
-                let bw = self.find_base(w);
-                if self.label[bw] == ODD { continue; }
-                if self.label[bw] == UNLABELED {
-                    self.level_queue[(d + 1) as usize].push((mv, w));
-                } else if self.label[bw] == EVEN {
-                    self.level_queue[d as usize].push((mv, w));
-                }
-            }
-            v = self.find_base(self.parent[mv] as usize);
+            self.source_bridge[mv] = vx(x);
+            self.target_bridge[mv] = vx(y);
+            self.queue_edges(mv, self.delta as usize);
+            v = self.base.find(ix(self.parent[mv]));
         }
         dunions.push((b, b));
     }
@@ -219,79 +252,61 @@ impl GabowOptimized {
         for q in &mut self.level_queue { q.clear(); }
         let mut dunions: Vec<(usize, usize)> = Vec::new();
 
+        self.base.reset();
+        self.dbase.reset();
         for i in 0..self.n {
-            self.base_par[i] = i;
-            self.dbase_par[i] = i;
             self.label[i] = UNLABELED;
-            self.parent[i] = NIL;
-            self.source_bridge[i] = NIL;
-            self.target_bridge[i] = NIL;
+            self.parent[i] = NONE;
+            self.source_bridge[i] = NONE;
+            self.target_bridge[i] = NONE;
             self.in_tree[i] = false;
         }
 
         /* Free vertices are EVEN roots at Delta=0 */
         for v in 0..self.n {
-            if self.mate[v] == NIL {
+            if self.mate[v] == NONE {
                 self.label[v] = EVEN;
                 self.in_tree[v] = true;
                 self.tree_nodes.push(v);
-                let neighbors: Vec<usize> = self.graph[v].clone();
-                for u in neighbors {
-                    if u as i32 == self.mate[v] { continue; }
-                    let bu = self.find_base(u);
-                    if self.label[bu] == ODD { continue; }
-                    if self.label[bu] == UNLABELED {
-                        self.level_queue[1].push((v, u));
-                    } else if self.label[bu] == EVEN {
-                        self.level_queue[0].push((v, u));
-                    }
-                }
+                self.queue_edges(v, 0);
             }
         }
 
         let mut found_sap = false;
+        /* paths at level Delta have 2 Delta + 1 edges */
+        let last = self.max_path.map_or(self.n, |l| ((l - 1) / 2).min(self.n)) as i32;
 
-        while self.delta <= self.n as i32 {
+        while self.delta <= last {
             let d = self.delta as usize;
-            while !self.level_queue[d].is_empty() {
-                let (mut z, mut u) = self.level_queue[d].pop().unwrap();
-                let mut bz = self.find_base(z);
-                let mut bu = self.find_base(u);
+            while let Some((z, u)) = self.level_queue[d].pop() {
+                let (mut z, mut u) = (ix(z), ix(u));
+                let mut bz = self.base.find(z);
+                let mut bu = self.base.find(u);
                 if self.label[bz] != EVEN {
                     std::mem::swap(&mut z, &mut u);
                     std::mem::swap(&mut bz, &mut bu);
                 }
                 if bz == bu || self.label[bz] != EVEN { continue; }
-                if u as i32 == self.mate[z] || self.label[bu] == ODD { continue; }
+                if vx(u) == self.mate[z] || self.label[bu] == ODD { continue; }
 
                 if self.label[bu] == UNLABELED {
                     let mv = self.mate[u];
-                    if mv == NIL { continue; }
-                    let mv = mv as usize;
-                    self.parent[u] = z as i32;
-                    self.parent[mv] = u as i32;
+                    if mv == NONE { continue; }
+                    let mv = ix(mv);
+                    self.parent[u] = vx(z);
+                    self.parent[mv] = vx(u);
                     self.label[u] = ODD;
                     self.label[mv] = EVEN;
                     self.in_tree[u] = true;
                     self.in_tree[mv] = true;
                     self.tree_nodes.push(u);
                     self.tree_nodes.push(mv);
-                    let neighbors: Vec<usize> = self.graph[mv].clone();
-                    let delta = self.delta;
-                    for w in neighbors {
-                        if w as i32 == self.mate[mv] { continue; }
-                        let bw = self.find_base(w);
-                        if self.label[bw] == ODD { continue; }
-                        if self.label[bw] == UNLABELED {
-                            self.level_queue[(delta + 1) as usize].push((mv, w));
-                        } else if self.label[bw] == EVEN {
-                            self.level_queue[delta as usize].push((mv, w));
-                        }
-                    }
+                    self.queue_edges(mv, self.delta as usize);
                 } else if self.label[bu] == EVEN {
                     let lca = self.find_lca(z, u);
-                    if lca != NIL {
-                        let lca = lca as usize;
+                    if lca != NONE {
+                        let lca = ix(lca);
+                        self.trace_blossom(lca, z, u);
                         self.shrink_path(lca, z, u, &mut dunions);
                         self.shrink_path(lca, u, z, &mut dunions);
                     } else {
@@ -302,29 +317,30 @@ impl GabowOptimized {
 
             if found_sap {
                 /* Build H: contracted_into and mateH */
-                let tn: Vec<usize> = self.tree_nodes.clone();
+                let tn = std::mem::take(&mut self.tree_nodes);
                 for &v in &tn {
-                    let db = self.find_dbase(v);
-                    self.contracted_into[db].push(v);
-                    self.mate_h[v] = NIL;
+                    let db = self.dbase.find(v);
+                    self.contracted_into[db].push(vx(v));
+                    self.mate_h[v] = NONE;
                 }
                 for &u in &tn {
-                    let uh = self.find_dbase(u);
+                    let uh = self.dbase.find(u);
                     let mv = self.mate[u];
-                    if mv != NIL && self.in_tree[mv as usize] {
-                        let vh = self.find_dbase(mv as usize);
+                    if mv != NONE && self.in_tree[ix(mv)] {
+                        let vh = self.dbase.find(ix(mv));
                         if uh != vh {
-                            self.mate_h[uh] = vh as i32;
-                            self.mate_h[vh] = uh as i32;
+                            self.mate_h[uh] = vx(vh);
+                            self.mate_h[vh] = vx(uh);
                         }
                     }
                 }
+                self.tree_nodes = tn;
                 return true;
             }
 
             for (a, b) in dunions.drain(..) {
-                if a == b { self.make_rep_dbase(a); }
-                else { self.union_dbase(a, b); }
+                if a == b { self.dbase.make_rep(a); }
+                else { self.dbase.union(a, b); }
             }
             self.delta += 1;
         }
@@ -337,76 +353,82 @@ impl GabowOptimized {
 
     /* find_apHG: ITERATIVE DFS in H.
      * Scans graph[v] for each G-vertex in contracted_into[vh].
-     * Returns the free H-node found, or NIL. */
-    fn find_ap_hg(&mut self, root_vh: usize) -> i32 {
-        struct Frame { vh: usize, ci_idx: usize, adj_idx: usize }
-        let mut stk: Vec<Frame> = vec![Frame { vh: root_vh, ci_idx: 0, adj_idx: 0 }];
+     * Returns the free H-node found, or NONE. */
+    fn find_ap_hg(&mut self, root_vh: usize) -> Vertex {
+        let mut stk = std::mem::take(&mut self.h_stack);
+        stk.clear();
+        stk.push(HFrame { vh: root_vh, ci_idx: 0, adj_idx: 0 });
 
         'outer: while let Some(f) = stk.last_mut() {
             let vh = f.vh;
             while f.ci_idx < self.contracted_into[vh].len() {
-                let v = self.contracted_into[vh][f.ci_idx];
+                let v = ix(self.contracted_into[vh][f.ci_idx]);
                 while f.adj_idx < self.graph[v].len() {
-                    let w = self.graph[v][f.adj_idx];
+                    let w = ix(self.graph[v][f.adj_idx]);
                     f.adj_idx += 1;
 
                     if !self.in_tree[w] { continue; }
-                    if self.mate[v] == w as i32 { continue; }
-                    let dv = self.find_dbase(v);
-                    let dw = self.find_dbase(w);
+                    if self.mate[v] == vx(w) { continue; }
+                    let dv = self.dbase.find(v);
+                    let dw = self.dbase.find(w);
                     if dv == dw { continue; }
-                    let uh = self.find_db2(self.rep[w]);
-                    if self.mate_h[vh] != NIL && self.mate_h[vh] as usize == uh { continue; }
+                    let uh = self.db2.find(ix(self.rep[w]));
+                    if self.mate_h[vh] != NONE && ix(self.mate_h[vh]) == uh { continue; }
                     if self.label_h[uh] == ODD { continue; }
 
                     if self.label_h[uh] == UNLABELED {
                         let muh = self.mate_h[uh];
-                        if muh == NIL {
+                        if muh == NONE {
                             self.label_h[uh] = ODD;
-                            self.parent_h_src[uh] = w as i32;
-                            self.parent_h_tgt[uh] = v as i32;
-                            return uh as i32;
+                            self.parent_h_src[uh] = vx(w);
+                            self.parent_h_tgt[uh] = vx(v);
+                            self.h_stack = stk;
+                            return vx(uh);
                         }
                         self.label_h[uh] = ODD;
-                        self.parent_h_src[uh] = w as i32;
-                        self.parent_h_tgt[uh] = v as i32;
-                        let muh = muh as usize;
+                        self.parent_h_src[uh] = vx(w);
+                        self.parent_h_tgt[uh] = vx(v);
+                        let muh = ix(muh);
                         self.label_h[muh] = EVEN;
                         self.even_time_h[muh] = self.t_h;
                         self.t_h += 1;
-                        stk.push(Frame { vh: muh, ci_idx: 0, adj_idx: 0 });
+                        stk.push(HFrame { vh: muh, ci_idx: 0, adj_idx: 0 });
                         continue 'outer;
                     } else if self.label_h[uh] == EVEN {
-                        let bh = self.find_db2(vh);
-                        let zh = self.find_db2(uh);
+                        let bh = self.db2.find(vh);
+                        let zh = self.db2.find(uh);
                         if self.even_time_h[bh] < self.even_time_h[zh] {
-                            let mut tmp: Vec<usize> = Vec::new();
-                            let mut endpoints: Vec<usize> = Vec::new();
+                            let mut tmp = std::mem::take(&mut self.h_cycle);
+                            let mut endpoints = std::mem::take(&mut self.h_ends);
+                            tmp.clear();
+                            endpoints.clear();
                             let mut cur = zh;
                             while cur != bh {
                                 endpoints.push(cur);
-                                let mc = self.mate_h[cur] as usize;
+                                let mc = ix(self.mate_h[cur]);
                                 endpoints.push(mc);
                                 tmp.push(mc);
-                                let ps = self.parent_h_src[mc] as usize;
-                                let pt = self.parent_h_tgt[mc] as usize;
-                                let next = if self.rep[ps] == mc {
-                                    self.rep[pt]
+                                let ps = ix(self.parent_h_src[mc]);
+                                let pt = ix(self.parent_h_tgt[mc]);
+                                let next = if ix(self.rep[ps]) == mc {
+                                    ix(self.rep[pt])
                                 } else {
-                                    self.rep[ps]
+                                    ix(self.rep[ps])
                                 };
-                                cur = self.find_db2(next);
+                                cur = self.db2.find(next);
                             }
-                            for &nd in &endpoints { self.union_db2(nd, bh); }
-                            self.make_rep_db2(bh);
+                            for &nd in &endpoints { self.db2.union(nd, bh); }
+                            self.db2.make_rep(bh);
                             for &mc in &tmp {
-                                self.bridge_h_src[mc] = v as i32;
-                                self.bridge_h_tgt[mc] = w as i32;
+                                self.bridge_h_src[mc] = vx(v);
+                                self.bridge_h_tgt[mc] = vx(w);
                                 self.dir_h[mc] = -1;
                             }
                             for i in (0..tmp.len()).rev() {
-                                stk.push(Frame { vh: tmp[i], ci_idx: 0, adj_idx: 0 });
+                                stk.push(HFrame { vh: tmp[i], ci_idx: 0, adj_idx: 0 });
                             }
+                            self.h_cycle = tmp;
+                            self.h_ends = endpoints;
                             continue 'outer;
                         }
                     }
@@ -416,7 +438,8 @@ impl GabowOptimized {
             }
             stk.pop();
         }
-        NIL
+        self.h_stack = stk;
+        NONE
     }
 
     /* trace_h_path: iterative, collects non-matching G-edges along H-path */
@@ -429,25 +452,25 @@ impl GabowOptimized {
         while let Some(f) = stk.last_mut() {
             if f.vh == f.uh { stk.pop(); continue; }
             if self.label_h[f.vh] == EVEN {
-                let mvh = self.mate_h[f.vh] as usize;
-                let ps = self.parent_h_src[mvh] as usize;
-                let pt = self.parent_h_tgt[mvh] as usize;
+                let mvh = ix(self.mate_h[f.vh]);
+                let ps = ix(self.parent_h_src[mvh]);
+                let pt = ix(self.parent_h_tgt[mvh]);
                 edges_out.push((ps, pt));
-                f.vh = if self.rep[ps] == mvh { self.rep[pt] } else { self.rep[ps] };
+                f.vh = if ix(self.rep[ps]) == mvh { ix(self.rep[pt]) } else { ix(self.rep[ps]) };
                 continue;
             }
             if f.phase == 0 {
-                let bs = self.bridge_h_src[f.vh] as usize;
-                let bt = self.bridge_h_tgt[f.vh] as usize;
+                let bs = ix(self.bridge_h_src[f.vh]);
+                let bt = ix(self.bridge_h_tgt[f.vh]);
                 f.bs = bs; f.bt = bt;
                 if self.dir_h[f.vh] == 1 {
-                    f.side_a = self.rep[bs]; f.side_b = self.rep[bt];
+                    f.side_a = ix(self.rep[bs]); f.side_b = ix(self.rep[bt]);
                 } else {
-                    f.side_a = self.rep[bt]; f.side_b = self.rep[bs];
+                    f.side_a = ix(self.rep[bt]); f.side_b = ix(self.rep[bs]);
                 }
                 f.phase = 1;
-                let mt = if self.mate_h[f.vh] != NIL {
-                    self.rep[self.mate_h[f.vh] as usize]
+                let mt = if self.mate_h[f.vh] != NONE {
+                    ix(self.rep[ix(self.mate_h[f.vh])])
                 } else { f.vh };
                 let sa = f.side_a;
                 stk.push(Frame { vh: sa, uh: mt, phase: 0,
@@ -475,15 +498,15 @@ impl GabowOptimized {
             if f.v == f.u { stk.pop(); continue; }
             if f.phase == 0 {
                 if self.label[f.v] == EVEN {
-                    let mv = self.mate[f.v] as usize;
-                    let pmv = self.parent[mv] as usize;
+                    let mv = ix(self.mate[f.v]);
+                    let pmv = ix(self.parent[mv]);
                     pairs.push((mv, pmv));
                     f.v = pmv;
                     continue;
                 }
-                let sb = self.source_bridge[f.v] as usize;
-                let tb = self.target_bridge[f.v] as usize;
-                let mv = self.mate[f.v] as usize;
+                let sb = ix(self.source_bridge[f.v]);
+                let tb = ix(self.target_bridge[f.v]);
+                let mv = ix(self.mate[f.v]);
                 f.sb = sb; f.tb = tb; f.phase = 1;
                 stk.push(Frame { v: sb, u: mv, phase: 0, sb: 0, tb: 0 });
                 continue;
@@ -504,51 +527,54 @@ impl GabowOptimized {
         let mut pairs: Vec<(usize, usize)> = Vec::new();
         for &(u, v) in h_edges {
             pairs.push((u, v));
-            let ru = self.rep[u];
+            let ru = ix(self.rep[u]);
             self.find_path_in_g(u, ru, &mut pairs);
-            let rv = self.rep[v];
+            let rv = ix(self.rep[v]);
             self.find_path_in_g(v, rv, &mut pairs);
         }
         for &(a, b) in &pairs {
-            self.mate[a] = b as i32;
-            self.mate[b] = a as i32;
+            self.mate[a] = vx(b);
+            self.mate[b] = vx(a);
+        }
+        if let Some(&(a, _)) = pairs.first() {
+            self.trace_augment(a);
         }
     }
 
-    /* phase_2: find all SAPs in H, unfold and augment */
-    fn phase_2(&mut self) {
-        let tn: Vec<usize> = self.tree_nodes.clone();
+    /* phase_2: find all SAPs in H, unfold and augment; returns the count */
+    fn phase_2(&mut self) -> usize {
+        let tn = std::mem::take(&mut self.tree_nodes);
         for &v in &tn {
-            let db = self.find_dbase(v);
-            self.rep[v] = db;
+            let db = self.dbase.find(v);
+            self.rep[v] = vx(db);
             self.label_h[v] = UNLABELED;
-            self.parent_h_src[v] = NIL; self.parent_h_tgt[v] = NIL;
-            self.bridge_h_src[v] = NIL; self.bridge_h_tgt[v] = NIL;
+            self.parent_h_src[v] = NONE; self.parent_h_tgt[v] = NONE;
+            self.bridge_h_src[v] = NONE; self.bridge_h_tgt[v] = NONE;
             self.dir_h[v] = 0;
             self.even_time_h[v] = 0;
-            self.db2_par[v] = v;
+            self.db2.detach(v);
         }
         self.t_h = 0;
 
         let mut all_paths: Vec<Vec<(usize, usize)>> = Vec::new();
         for &vh in &tn {
-            if vh != self.rep[vh] { continue; }
-            if self.label_h[vh] != UNLABELED || self.mate_h[vh] != NIL { continue; }
+            if vx(vh) != self.rep[vh] { continue; }
+            if self.label_h[vh] != UNLABELED || self.mate_h[vh] != NONE { continue; }
             self.label_h[vh] = EVEN;
             self.even_time_h[vh] = self.t_h;
             self.t_h += 1;
 
             let free_node = self.find_ap_hg(vh);
-            if free_node != NIL {
-                let free_node = free_node as usize;
+            if free_node != NONE {
+                let free_node = ix(free_node);
                 let mut h_nm: Vec<(usize, usize)> = Vec::new();
-                let ps = self.parent_h_src[free_node] as usize;
-                let pt = self.parent_h_tgt[free_node] as usize;
+                let ps = ix(self.parent_h_src[free_node]);
+                let pt = ix(self.parent_h_tgt[free_node]);
                 h_nm.push((ps, pt));
-                let next = if self.rep[ps] == free_node {
-                    self.rep[pt]
+                let next = if ix(self.rep[ps]) == free_node {
+                    ix(self.rep[pt])
                 } else {
-                    self.rep[ps]
+                    ix(self.rep[ps])
                 };
                 self.trace_h_path(next, vh, &mut h_nm);
                 all_paths.push(h_nm);
@@ -556,14 +582,17 @@ impl GabowOptimized {
         }
 
         for path in &all_paths { self.augment_g(path); }
+        let augmented = all_paths.len();
 
         /* Clean up */
         for &v in &tn {
-            let db = self.find_dbase(v);
+            let db = self.dbase.find(v);
             self.contracted_into[db].clear();
             self.contracted_into[v].clear();
-            self.mate_h[v] = NIL;
+            self.mate_h[v] = NONE;
         }
+        self.tree_nodes = tn;
+        augmented
     }
 
     /* ================================================================ */
@@ -573,12 +602,13 @@ impl GabowOptimized {
     fn greedy_init(&mut self) -> usize {
         let mut cnt: usize = 0;
         for u in 0..self.n {
-            if self.mate[u] != NIL { continue; }
-            let neighbors: Vec<usize> = self.graph[u].clone();
-            for &v in &neighbors {
-                if self.mate[v] == NIL {
-                    self.mate[u] = v as i32;
-                    self.mate[v] = u as i32;
+            if self.mate[u] != NONE { continue; }
+            let mate = &mut self.mate;
+            for &v in &self.graph[u] {
+                let v = ix(v);
+                if mate[v] == NONE {
+                    mate[u] = vx(v);
+                    mate[v] = vx(u);
                     cnt += 1;
                     break;
                 }
@@ -593,43 +623,213 @@ impl GabowOptimized {
         let mut deg = vec![0usize; self.n];
         for u in 0..self.n {
             for &v in &self.graph[u] {
-                deg[v] += 1;
+                deg[ix(v)] += 1;
             }
         }
         let mut order: Vec<usize> = (0..self.n).collect();
         order.sort_unstable_by(|&a, &b| deg[a].cmp(&deg[b]).then(a.cmp(&b)));
         for u in order {
-            if self.mate[u] != NIL { continue; }
-            let mut best: i32 = -1;
+            if self.mate[u] != NONE { continue; }
+            let mut best = NONE;
             let mut best_deg = usize::MAX;
-            let neighbors: Vec<usize> = self.graph[u].clone();
-            for &v in &neighbors {
-                if self.mate[v] == NIL && deg[v] < best_deg {
-                    best = v as i32;
+            for &v in &self.graph[u] {
+                let v = ix(v);
+                if self.mate[v] == NONE && deg[v] < best_deg {
+                    best = vx(v);
                     best_deg = deg[v];
                 }
             }
-            if best >= 0 {
+            if best != NONE {
                 self.mate[u] = best;
-                self.mate[best as usize] = u as i32;
+                self.mate[ix(best)] = vx(u);
                 cnt += 1;
             }
         }
         cnt
     }
 
-    fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor,
+     * match the two; otherwise match the lowest-id exposed vertex that has
+     * one with its first unmatched neighbor. deg counts unmatched neighbors. */
+    fn greedy_init_ks(&mut self) -> usize {
+        let mut deg: Vec<usize> = (0..self.n)
+            .map(|u| if self.mate[u] == NONE { self.graph[u].iter().filter(|&&v| self.mate[ix(v)] == NONE).count() } else { 0 })
+            .collect();
+        let mut leaves: Vec<usize> = (0..self.n).filter(|&v| deg[v] == 1).collect();
+        let mut next = 0;
+        let mut cnt: usize = 0;
+        loop {
+            let u = match leaves.pop() {
+                Some(u) if self.mate[u] != NONE || deg[u] != 1 => continue,
+                Some(u) => u,
+                None => {
+                    while next < self.n && (self.mate[next] != NONE || deg[next] == 0) { next += 1; }
+                    if next == self.n { break; }
+                    next
+                }
+            };
+            let v = ix(*self.graph[u].iter().find(|&&v| self.mate[ix(v)] == NONE).unwrap());
+            self.mate[u] = vx(v);
+            self.mate[v] = vx(u);
+            cnt += 1;
+            for &x in &[u, v] {
+                for &w in &self.graph[x] {
+                    let w = ix(w);
+                    if self.mate[w] != NONE { continue; }
+                    deg[w] -= 1;
+                    if deg[w] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched neighbor. The
+     * same seed gives the same matching. */
+    fn greedy_init_random(&mut self, seed: u64) -> usize {
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<usize> = (0..self.n).collect();
+        rng.shuffle(&mut order);
+        let mut cnt: usize = 0;
+        for u in order {
+            if self.mate[u] != NONE { continue; }
+            let free = self.graph[u].iter().filter(|&&v| self.mate[ix(v)] == NONE).count();
+            if free == 0 { continue; }
+            let k = rng.below(free as u64) as usize;
+            let v = ix(*self.graph[u].iter().filter(|&&v| self.mate[ix(v)] == NONE).nth(k).unwrap());
+            self.mate[u] = vx(v);
+            self.mate[v] = vx(u);
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+     * free vertices, each taking the first path u - v = w - x from u (x
+     * free, x != u) or a free neighbor outright; see gabow_simple.rs. */
+    fn augment3(&mut self, passes: usize) -> usize {
+        let mut next = vec![0usize; self.n];
+        let mut cnt = 0usize;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..self.n {
+                if self.mate[u] != NONE { continue; }
+                for k in 0..self.graph[u].len() {
+                    let v = ix(self.graph[u][k]);
+                    if self.mate[v] == NONE {
+                        self.mate[u] = vx(v);
+                        self.mate[v] = vx(u);
+                        cnt += 1;
+                        break;
+                    }
+                    let w = ix(self.mate[v]);
+                    let nw = &self.graph[w];
+                    while next[w] < nw.len() && self.mate[ix(nw[next[w]])] != NONE { next[w] += 1; }
+                    let mut j = next[w];
+                    while j < nw.len() && (ix(nw[j]) == u || self.mate[ix(nw[j])] != NONE) { j += 1; }
+                    if j < nw.len() {
+                        let x = ix(nw[j]);
+                        self.mate[u] = vx(v);
+                        self.mate[v] = vx(u);
+                        self.mate[w] = vx(x);
+                        self.mate[x] = vx(w);
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
+    /* A stop was requested (SIGINT/SIGTERM in combi): the caller gets the
+     * matching of the last finished phase. */
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+    }
+
+    fn trace_blossom(&self, base: usize, v: usize, w: usize) {
+        if let Some(t) = self.trace {
+            t("blossom", &[base, v, w]);
+        }
+    }
+
+    /* x is on the path just flipped. */
+    fn trace_augment(&mut self, x: usize) {
+        if let Some(t) = self.trace {
+            let mut before = std::mem::take(&mut self.trace_mate);
+            trace::augmented(t, x, &|v| self.mate(v), &mut before);
+            self.trace_mate = before;
+        }
+    }
+
+    /* Start from a matching found elsewhere (mate[v] = NONE for free v),
+     * e.g. by Gabow simple in gabow_adaptive.rs; then maximum_matching(0)
+     * runs the phases from there. */
+    #[allow(dead_code)]
+    pub(crate) fn set_mate(&mut self, mate: &[Vertex]) {
+        self.mate.copy_from_slice(mate);
+    }
+
+    /* Per-vertex queries on the current matching, as on matchers::Solution. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        self.mate.get(v).and_then(|&m| types::some(m))
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n).filter(move |&v| self.mate[v] == NONE)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.mate.iter().filter(|&&m| m != NONE).count() / 2
+    }
+
+    pub(crate) fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
         self.greedy_size = match greedy_mode {
             1 => self.greedy_init(),
             2 => self.greedy_init_md(),
+            3 => self.greedy_init_ks(),
+            4 => self.greedy_init_random(self.greedy_seed),
             _ => 0,
         };
-        while self.phase_1() { self.phase_2(); }
+        if self.aug3_passes > 0 {
+            self.aug3_size = self.augment3(self.aug3_passes);
+            self.greedy_size += self.aug3_size;
+        }
+        /* for the progress reports: pairs before the phases, and found since */
+        let start = if self.progress.is_some() { self.matching_size() } else { 0 };
+        if let Some(t) = self.trace {
+            self.trace_mate = trace::begin(t, self.n, &|v| self.mate(v));
+        }
+        let mut found = 0;
+        /* phase_1 stops at the level Delta of the shortest augmenting paths */
+        while !self.stopped() && self.phase_1() {
+            let length = 2 * self.delta as usize + 1;
+            let augmented = self.phase_2();
+            self.phases.push((length, augmented));
+            found += augmented;
+            if let Some(report) = self.progress {
+                report(self.phases.len(), found, self.n - 2 * (start + found));
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[self.phases.len()]);
+            }
+        }
 
         let mut result = Vec::new();
         for u in 0..self.n {
-            if self.mate[u] != NIL && (self.mate[u] as usize) > u {
-                result.push((u, self.mate[u] as usize));
+            if self.mate[u] != NONE && (ix(self.mate[u])) > u {
+                result.push((u, ix(self.mate[u])));
             }
         }
         result.sort_unstable();
@@ -641,80 +841,256 @@ impl GabowOptimized {
 /*                    VALIDATION AND MAIN                            */
 /* ================================================================ */
 
-fn validate_matching(n: usize, graph: &[Vec<usize>], matching: &[(usize, usize)]) {
-    let mut deg = vec![0i32; n];
-    let mut errors = 0;
-    for &(u, v) in matching {
-        if graph[u].binary_search(&v).is_err() {
-            eprintln!("ERROR: Edge ({}, {}) not in graph!", u, v);
-            errors += 1;
-        }
-        deg[u] += 1; deg[v] += 1;
-    }
-    for i in 0..n {
-        if deg[i] > 1 { eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]); errors += 1; }
-    }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
-}
-
-fn load_graph(filename: &str) -> Result<(usize, Vec<(usize, usize)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    let n: usize = parts[0].parse()?;
-    let m: usize = parts[1].parse()?;
-    let mut edges = Vec::with_capacity(m);
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: usize = parts[0].parse()?;
-            let v: usize = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-    Ok((n, edges))
-}
-
 fn main() {
     println!("Gabow's Scaling Algorithm (Optimized) - Rust Implementation");
     println!("=============================================================\n");
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}", args[0],
+                  args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, args::EPSILON_USAGE, phase0::PHASE0_USAGE, ordering::REORDER_USAGE, phase_stats::PHASE_STATS_USAGE,
+                  matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
         std::process::exit(1);
     }
 
-    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-md") { 2 } else if args.iter().any(|a| a == "--greedy") { 1 } else { 0 };
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-random") {
+        4
+    } else if args.iter().any(|a| a == "--greedy-ks") {
+        3
+    } else if args.iter().any(|a| a == "--greedy-md") {
+        2
+    } else if args.iter().any(|a| a == "--greedy") {
+        1
+    } else {
+        0
+    };
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    let greedy_mode = if greedy_mode == 0 && aug3 > 0 { 1 } else { greedy_mode };
+    let max_path = match args::epsilon_of(&args) {
+        Ok(e) => e.map(args::max_path_length),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let show_phases = args::has_flag(&args, "--phase-stats");
+    let phase0_mode = phase0::Mode::from_args(&args);
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let reorder = match ordering::Reorder::from_args(&args) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* The trace is in the solver's ids, which phase 0 and --reorder change. */
+    if args::value_of(&args, "--trace").is_some()
+        && (phase0_mode != phase0::Mode::Off || reorder != ordering::Reorder::Natural) {
+        eprintln!("Error: --trace does not combine with --phase0, --crown, --fold or --reorder");
+        std::process::exit(1);
+    }
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(n) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if init.is_some() && phase0_mode != phase0::Mode::Off {
+                eprintln!("Error: --init-matching does not combine with --phase0, --crown or --fold");
+                std::process::exit(1);
+            }
+            /* Phase 0 counts towards the solve time; the solver and
+             * --reorder see only the components left over. */
+            let phase0_start = Instant::now();
+            let split = phase0::run(phase0_mode, n, &edges);
+            let phase0_time = phase0_start.elapsed();
+            /* Pairs fixed before the solver runs count as initial matching. */
+            let phase0_pairs = split.as_ref().map_or(0, |s| s.stats.solved_pairs);
+            /* the input graph, to validate the merged matching against */
+            let input_edges = split.as_ref().map(|_| edges.clone());
+            /* the solver may take the edges over; the drawings need them after */
+            let drawn_edges = (args::value_of(&args, "--dot").is_some() || args::value_of(&args, "--svg").is_some())
+                .then(|| edges.clone());
+            let (sn, sedges) = match split {
+                Some(ref s) => {
+                    println!("Phase 0: {} ({} ms)", s.summary(), phase0_time.as_millis());
+                    (s.rest_n, s.rest_edges.clone())
+                }
+                None => (n, edges),
+            };
+            let reorder_start = Instant::now();
+            let relabel = ordering::relabeling(reorder, sn, &sedges);
+            let sedges = match relabel {
+                Some(ref r) => r.apply(&sedges),
+                None => sedges,
+            };
+            if relabel.is_some() {
+                println!("Reorder: {} ({} ms)", reorder.name(), reorder_start.elapsed().as_millis());
+            }
             let start = Instant::now();
-            let mut gabow = GabowOptimized::new(n, &edges);
+            let mut gabow = GabowOptimized::new(sn, &sedges);
+            gabow.greedy_seed = seed;
+            gabow.aug3_passes = aug3;
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                gabow.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                gabow.stop = Some(&time_limit::EXPIRED);
+            }
+            if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("gabow-opt {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                gabow.trace = Some(&trace::record);
+            } else if log::enabled(log::Level::Debug) {
+                gabow.trace = Some(&log::event);
+            }
+            gabow.max_path = max_path;
+            if let Some(ref pairs) = init {
+                let pairs = match relabel {
+                    Some(ref r) => r.apply(pairs),
+                    None => pairs.clone(),
+                };
+                gabow.set_mate(&types::mate_of(sn, &pairs));
+            }
             let matching = gabow.maximum_matching(greedy_mode);
-            let duration = start.elapsed();
-            validate_matching(n, &gabow.graph, &matching);
+            let duration = start.elapsed() + phase0_time;
+            let gap = time_limit.filter(|_| time_limit::expired())
+                .map(|limit| (limit, time_limit::gap_bound(sn, &sedges, &matching)));
+            /* with the Tutte-Berge certificate under --certify, of the graph validated against */
+            let certify = args::has_flag(&args, "--certify");
+            let validate = |n: usize, edges: &[(usize, usize)], m: &[(usize, usize)]| {
+                let mut report = verify::validate_general(n, edges, m);
+                if certify && report.is_valid() {
+                    report.optimal = Some(vertex_classes::certify(n, edges, m));
+                }
+                report
+            };
+            let (matching, report) = match (split, input_edges) {
+                /* Merged into original ids, validated against the input. */
+                (Some(s), Some(input)) => {
+                    let rest = match relabel {
+                        Some(ref r) => r.restore(&matching),
+                        None => matching,
+                    };
+                    let merged = s.merge(&rest);
+                    let report = validate(n, &input, &merged);
+                    (merged, report)
+                }
+                /* Validated in the solver's labeling, against its own graph. */
+                _ => {
+                    let report = validate(sn, &sedges, &matching);
+                    (matching, report)
+                }
+            };
+            report.print();
+            if show_phases {
+                phase_stats::write_phase_stats(&mut std::io::stdout(), &gabow.phases).unwrap();
+            }
             println!("Matching size: {}", matching.len());
+            if let Some(l) = max_path {
+                println!("Approximation: augmenting paths of at most {} edges (at least {:.2}% of the maximum)",
+                         l, 100.0 * args::approximation_ratio(l));
+            }
             if greedy_mode > 0 {
-                let gs = gabow.greedy_size;
+                let gs = gabow.greedy_size + phase0_pairs;
                 let fs = matching.len();
                 println!("Greedy init size: {}", gs);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", gabow.aug3_size);
+                }
                 if fs > 0 { println!("Greedy/Final: {:.2}%", 100.0 * gs as f64 / fs as f64); }
                 else { println!("Greedy/Final: NA"); }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            if let Some(path) = args::value_of(&args, "--dot") {
+                if let Err(e) = dot::save(path, n, drawn_edges.as_ref().unwrap(), &matching, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = args::value_of(&args, "--svg") {
+                if let Err(e) = svg::save(path, n, drawn_edges.as_ref().unwrap(), &matching, None, svg_layout) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
@@ -0,0 +1,217 @@
+/*
+ * Gabow's Scaling Algorithm (Weighted) - Maximum Weight Matching
+ *
+ * The weighted counterpart of gabow_optimized.rs, which keeps only the
+ * cardinality search: integer edge weights ("u v w" lines) are brought in
+ * one bit at a time, most significant first, as in Gabow (1985) and
+ * Gabow-Tarjan (1991). Scale s solves the weights w >> (B - s), B the bit
+ * length of the largest weight, starting from the previous scale's duals
+ * and matching; the last scale solves the weights themselves.
+ *
+ * Maximum weight is solved as maximum-weight perfect matching on the
+ * doubled graph: a copy v' of every vertex, the edges copied, and an edge
+ * (v, v') of weight 0. A perfect matching always exists there, and its
+ * part on the original vertices is a maximum-weight matching. Perfect
+ * matching lets free vertices carry any dual, so a scale can start from
+ * old duals instead of the usual all-equal ones.
+ *
+ * Between scales (duals in the doubled units of weighted_blossom.rs,
+ * slack = y(u) + y(v) - 2w):
+ *   - blossoms are liquidated: each blossom's dual moves onto its
+ *     vertices, which keeps every edge feasible;
+ *   - duals become 2y + 1 for weights 2w + b, b the new bit: every edge
+ *     stays feasible, all duals share one parity, and an edge tight
+ *     before stays tight exactly when its new bit is 1;
+ *   - an old matched pair whose bit is 0 is made tight again where one
+ *     end's dual can drop by 2 (retighten);
+ *   - the old matched pairs now tight are kept, and Gabow optimized's
+ *     O(E√V) phases (set_mate, then maximum_matching) extend them to a
+ *     maximum matching of the tight edges;
+ *   - Edmonds' primal-dual stages (weighted_blossom.rs, warm_start) then
+ *     finish the scale exactly.
+ *
+ * The exact finish is Edmonds-Galil, one augmentation per O(V^2) stage,
+ * not Gabow-Tarjan's approximate search with its O(√V) phases per scale:
+ * the worst case is O(V^3 log W), not O(E√V log(VW)). What scaling buys
+ * here is the warm start: most pairs carry over, so few stages are left
+ * per scale (--scale-stats shows the counts).
+ *
+ * Rust implementation -- fully deterministic, no hash containers.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::time::Instant;
+
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "gabow_optimized.rs"]
+#[allow(dead_code)]
+mod gabow_optimized;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+/* One scale: its bits, the pairs carried over tight, the tight-edge
+ * matching they grew to, and the final matching of the doubled graph. */
+pub(crate) struct Scale {
+    pub(crate) bits: u32,
+    pub(crate) carried: usize,
+    pub(crate) tight: usize,
+    pub(crate) matched: usize,
+}
+
+/* Old matched pairs whose new bit is 0 have slack 2 after the doubling.
+ * Lower one end's dual by 2 where every edge there keeps slack >= 0,
+ * which makes the pair tight again; parity is kept. */
+fn retighten(n: usize, edges: &[(usize, usize, i64)], pairs: &[(usize, usize)], duals: &mut [i64]) {
+    let mut adj: Vec<Vec<(usize, i64)>> = vec![Vec::new(); n];
+    let mut least = vec![i64::MAX; n];
+    for &(u, v, w) in edges {
+        adj[u].push((v, w));
+        adj[v].push((u, w));
+        let slack = duals[u] + duals[v] - 2 * w;
+        least[u] = least[u].min(slack);
+        least[v] = least[v].min(slack);
+    }
+    for &(u, v) in pairs {
+        let w = adj[u].iter().filter(|e| e.0 == v).map(|e| e.1).max().unwrap();
+        if duals[u] + duals[v] - 2 * w != 2 { continue; }
+        let end = if least[u] >= 2 { u } else if least[v] >= 2 { v } else { continue };
+        duals[end] -= 2;
+        least[end] -= 2;
+        for &(x, wx) in &adj[end] {
+            least[x] = least[x].min(duals[end] + duals[x] - 2 * wx);
+        }
+    }
+}
+
+/* Maximum-weight matching of (n, edges) as sorted (u, v) pairs, u < v,
+ * with the scales it took. Edges of weight <= 0 never help and are
+ * dropped, as are self-loops and out-of-range endpoints. */
+pub(crate) fn max_weight_matching(n: usize, edges: &[(usize, usize, i64)]) -> (Vec<(usize, usize)>, Vec<Scale>) {
+    let kept: Vec<(usize, usize, i64)> = edges.iter().cloned()
+        .filter(|&(u, v, w)| u < n && v < n && u != v && w > 0)
+        .collect();
+    let top = kept.iter().map(|e| e.2).max().unwrap_or(0);
+    let bits = 64 - top.leading_zeros();
+    let mut doubled: Vec<(usize, usize, i64)> = Vec::with_capacity(2 * kept.len() + n);
+    for &(u, v, w) in &kept {
+        doubled.push((u, v, w));
+        doubled.push((n + u, n + v, w));
+    }
+    for v in 0..n {
+        doubled.push((v, n + v, 0));
+    }
+
+    let mut duals = vec![0i64; 2 * n];
+    let mut pairs: Vec<(usize, usize)> = Vec::new();
+    let mut scales = Vec::new();
+    for s in 1..=bits {
+        let scaled: Vec<(usize, usize, i64)> = doubled.iter().map(|&(u, v, w)| (u, v, w >> (bits - s))).collect();
+        for d in duals.iter_mut() {
+            *d = 2 * *d + 1;
+        }
+        retighten(2 * n, &scaled, &pairs, &mut duals);
+        let mut tight_edges = Vec::new();
+        for &(u, v, w) in &scaled {
+            if duals[u] + duals[v] == 2 * w {
+                tight_edges.push((u.min(v), u.max(v)));
+            }
+        }
+        tight_edges.sort_unstable();
+        let mut mate = vec![types::NONE; 2 * n];
+        let mut carried = 0;
+        for &(u, v) in &pairs {
+            if tight_edges.binary_search(&(u, v)).is_ok() {
+                mate[u] = types::vx(v);
+                mate[v] = types::vx(u);
+                carried += 1;
+            }
+        }
+        let mut cardinality = gabow_optimized::GabowOptimized::new(2 * n, &tight_edges);
+        cardinality.set_mate(&mate);
+        let start = cardinality.maximum_matching(0);
+
+        let mut exact = weighted_blossom::WeightedBlossom::new(2 * n, &scaled);
+        exact.warm_start(&duals, &start);
+        pairs = exact.maximum_weight_matching(true);
+        duals = exact.liquidated_duals();
+        scales.push(Scale { bits: s, carried, tight: start.len(), matched: pairs.len() });
+    }
+
+    let mut matching: Vec<(usize, usize)> = pairs.into_iter().filter(|&(_, v)| v < n).collect();
+    matching.sort_unstable();
+    (matching, scales)
+}
+
+fn main() {
+    println!("Gabow's Scaling Algorithm (Weighted) - Rust Implementation");
+    println!("==========================================================\n");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <filename> [--scale-stats] {} {} {} {}", args[0],
+                  matching_io::OUTPUT_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+    let show_scales = args::has_flag(&args, "--scale-stats");
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_weighted_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            println!("Graph: {} vertices, {} edges", n, edges.len());
+            let start = Instant::now();
+            let (matching, scales) = max_weight_matching(n, &edges);
+            let duration = start.elapsed();
+            let report = verify::validate_weighted((n, n), &edges, &matching, false, true);
+            report.print();
+            println!("Matching size: {}", matching.len());
+            println!("Scales: {}", scales.len());
+            if show_scales {
+                println!("{:>6} {:>9} {:>9} {:>9}", "bits", "carried", "tight", "matched");
+                for s in &scales {
+                    println!("{:>6} {:>9} {:>9} {:>9}", s.bits, s.carried, s.tight, s.matched);
+                }
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!("Time: {} ms", duration.as_millis());
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
@@ -20,6 +20,7 @@
 
 #include <cstdio>
 #include <cstdlib>
+#include <cstdint>
 #include <vector>
 #include <algorithm>
 #include <string>
@@ -31,9 +32,31 @@ static const int UNLABELED = 0;
 static const int EVEN = 1;
 static const int ODD = 2;
 
+/* SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+   stream, so --greedy-random --seed S picks the same matching there */
+struct SplitMix64 {
+    uint64_t state;
+    explicit SplitMix64(uint64_t seed) : state(seed) {}
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+    /* uniform in [0, bound) */
+    uint64_t below(uint64_t bound) { return (uint64_t)(((unsigned __int128)next() * bound) >> 64); }
+    template <typename T> void shuffle(std::vector<T>& v) {
+        for (size_t i = v.size(); i > 1; i--) std::swap(v[i - 1], v[below(i)]);
+    }
+};
+
 struct GabowSimple {
     int n;
     int greedy_size = 0;
+    uint64_t greedy_seed = 1; /* for greedy_mode 4 (--greedy-random) */
+    int aug3_passes = 0; /* --init-aug3 passes after the greedy start; 0 = off */
+    int aug3_size = 0;   /* pairs those passes added, counted in greedy_size */
     std::vector<std::vector<int>> graph;
     std::vector<int> mate;
     std::vector<int> base;
@@ -114,6 +137,87 @@ struct GabowSimple {
         return cnt;
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor, match the two;
+       otherwise match the lowest-id exposed vertex that has one with its first unmatched neighbor */
+    int greedy_init_ks() {
+        int cnt = 0;
+        std::vector<int> deg(n, 0), leaves;
+        for (int u = 0; u < n; u++) {
+            if (mate[u] != NIL) continue;
+            for (int v : graph[u]) if (mate[v] == NIL) deg[u]++;
+            if (deg[u] == 1) leaves.push_back(u);
+        }
+        int next = 0;
+        while (true) {
+            int u;
+            if (!leaves.empty()) {
+                u = leaves.back(); leaves.pop_back();
+                if (mate[u] != NIL || deg[u] != 1) continue;
+            } else {
+                while (next < n && (mate[next] != NIL || deg[next] == 0)) next++;
+                if (next == n) break;
+                u = next;
+            }
+            int v = NIL;
+            for (int w : graph[u]) if (mate[w] == NIL) { v = w; break; }
+            mate[u] = v; mate[v] = u; cnt++;
+            for (int x : {u, v})
+                for (int w : graph[x])
+                    if (mate[w] == NIL && --deg[w] == 1) leaves.push_back(w);
+        }
+        return cnt;
+    }
+
+    /* Random greedy: vertices in a seeded random order, each exposed one
+       matched with a uniformly random unmatched neighbor */
+    int greedy_init_random(uint64_t seed) {
+        SplitMix64 rng(seed);
+        std::vector<int> order(n);
+        for (int u = 0; u < n; u++) order[u] = u;
+        rng.shuffle(order);
+        int cnt = 0;
+        std::vector<int> free;
+        for (int u : order) {
+            if (mate[u] != NIL) continue;
+            free.clear();
+            for (int v : graph[u]) if (mate[v] == NIL) free.push_back(v);
+            if (free.empty()) continue;
+            int v = free[rng.below(free.size())];
+            mate[u] = v; mate[v] = u; cnt++;
+        }
+        return cnt;
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+       free vertices, each taking the first path u - v = w - x from u (x
+       free, x != u) or a free neighbor outright. A pass without one ends
+       it; next[w] skips neighbors of w that are matched for good. */
+    int augment3(int passes) {
+        std::vector<size_t> next(n, 0);
+        int cnt = 0;
+        for (int p = 0; p < passes; p++) {
+            int before = cnt;
+            for (int u = 0; u < n; u++) {
+                if (mate[u] != NIL) continue;
+                for (int v : graph[u]) {
+                    if (mate[v] == NIL) { mate[u] = v; mate[v] = u; cnt++; break; }
+                    int w = mate[v];
+                    const std::vector<int>& nw = graph[w];
+                    while (next[w] < nw.size() && mate[nw[next[w]]] != NIL) next[w]++;
+                    size_t j = next[w];
+                    while (j < nw.size() && (nw[j] == u || mate[nw[j]] != NIL)) j++;
+                    if (j < nw.size()) {
+                        int x = nw[j];
+                        mate[u] = v; mate[v] = u; mate[w] = x; mate[x] = w; cnt++;
+                        break;
+                    }
+                }
+            }
+            if (cnt == before) break;
+        }
+        return cnt;
+    }
+
     /* Path-halving find for union-find base */
     int find_base(int v) {
         while (base[v] != v) {
@@ -321,7 +425,10 @@ struct GabowSimple {
         int greedy_count = 0;
         if (greedy_mode == 1) greedy_count = greedy_init();
         else if (greedy_mode == 2) greedy_count = greedy_init_md();
+        else if (greedy_mode == 3) greedy_count = greedy_init_ks();
+        else if (greedy_mode == 4) greedy_count = greedy_init_random(greedy_seed);
         greedy_size = greedy_count;
+        if (aug3_passes > 0) { aug3_size = augment3(aug3_passes); greedy_size += aug3_size; }
 
         while (find_and_augment()) {}
 
@@ -364,12 +471,22 @@ int main(int argc, char* argv[]) {
     printf("Gabow's Algorithm (Simple) - C++ Implementation\n");
     printf("=================================================\n\n");
 
-    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md]\n", argv[0]); return 1; }
+    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]\n", argv[0]); return 1; }
     int greedy_mode = 0;
+    uint64_t seed = 1;
+    int aug3 = 0, aug3_passes = 3;
     for (int i = 2; i < argc; i++) {
         if (std::string(argv[i]) == "--greedy") greedy_mode = 1;
         else if (std::string(argv[i]) == "--greedy-md") greedy_mode = 2;
+        else if (std::string(argv[i]) == "--greedy-ks") greedy_mode = 3;
+        else if (std::string(argv[i]) == "--greedy-random") greedy_mode = 4;
+        else if (std::string(argv[i]) == "--seed" && i + 1 < argc) seed = strtoull(argv[++i], nullptr, 10);
+        else if (std::string(argv[i]) == "--init-aug3") aug3 = 1;
+        else if (std::string(argv[i]) == "--aug3-passes" && i + 1 < argc) aug3_passes = atoi(argv[++i]);
     }
+    if (aug3_passes < 1) { fprintf(stderr, "Error: --aug3-passes must be a positive count\n"); return 1; }
+    /* --init-aug3 alone runs after the plain greedy start */
+    if (aug3) { aug3 = aug3_passes; if (greedy_mode == 0) greedy_mode = 1; }
 
     FILE* f = fopen(argv[1], "r");
     if (!f) { fprintf(stderr, "Cannot open file: %s\n", argv[1]); return 1; }
@@ -390,6 +507,8 @@ int main(int argc, char* argv[]) {
 
     auto t0 = std::chrono::high_resolution_clock::now();
     GabowSimple gabow(n, edges);
+    gabow.greedy_seed = seed;
+    gabow.aug3_passes = aug3;
     auto matching = gabow.maximum_matching(greedy_mode);
     auto t1 = std::chrono::high_resolution_clock::now();
 
@@ -401,6 +520,7 @@ int main(int argc, char* argv[]) {
         int gs = gabow.greedy_size;
         int fs = (int)matching.size();
         printf("Greedy init size: %d\n", gs);
+        if (aug3 > 0) printf("Aug3 augmentations: %d\n", gabow.aug3_size);
         if (fs > 0) printf("Greedy/Final: %.2f%%\n", 100.0 * gs / fs);
         else printf("Greedy/Final: NA\n");
     }
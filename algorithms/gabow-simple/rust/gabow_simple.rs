@@ -9,9 +9,22 @@
  * Forest search: each iteration labels ALL free vertices as EVEN roots
  * simultaneously and grows a search forest. An augmenting path is found
  * when two different trees meet (EVEN-EVEN edge across trees, detected
- * by find_lca returning NIL). One augmentation per iteration, then full
+ * by find_lca returning NONE). One augmentation per iteration, then full
  * reset and repeat until no augmenting path exists.
  *
+ * With --threads N the free vertices are split into N blocks and each
+ * round grows N forests on N threads, one per block; the vertex-disjoint
+ * paths they return are augmented together (see
+ * find_and_augment_parallel). --numa pins each forest's thread to a CPU,
+ * spread over the sockets, and allocates the forest there (numa.rs).
+ *
+ * With --blossoms each contraction also builds an explicit blossom
+ * object (common/rust/blossoms.rs: the odd cycle of nested blossoms and
+ * its edges) and augmenting paths are lifted through those instead of
+ * through the bridges. The cardinality search never needs to expand one
+ * -- all blossoms go with the forest at the reset -- but a weighted
+ * search does, and these are the objects it takes apart.
+ *
  * Complexity: O(V * E) -- each iteration does O(E) work, at most V/2
  * augmentations total.
  *
@@ -19,142 +32,137 @@
  */
 
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
 use std::time::Instant;
 
-const NIL: i32 = -1;
-const UNLABELED: i32 = 0;
-const EVEN: i32 = 1;
-const ODD: i32 = 2;
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/blossoms.rs"]
+mod blossoms;
+#[path = "../../common/rust/dsu.rs"]
+mod dsu;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/numa.rs"]
+mod numa;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
 
-struct GabowSimple {
-    n: usize,
-    greedy_size: usize,
-    graph: Vec<Vec<usize>>,
-    mate: Vec<i32>,
-    base: Vec<usize>,
-    parent: Vec<i32>,
-    label: Vec<i32>,
+use self::types::{ix, vx, Vertex, NONE};
+
+const UNLABELED: u8 = 0;
+const EVEN: u8 = 1;
+const ODD: u8 = 2;
+
+/* Per-search state of one forest. The sequential search keeps one; the
+ * parallel search keeps one per thread, so threads share only the graph
+ * and the matching, both read-only during a round. */
+struct Forest {
+    base: dsu::Dsu,
+    parent: Vec<Vertex>,
+    label: Vec<u8>,
 
     /* Bridge recording for ODD vertices absorbed into blossoms */
-    bridge_src: Vec<i32>,
-    bridge_tgt: Vec<i32>,
+    bridge_src: Vec<Vertex>,
+    bridge_tgt: Vec<Vertex>,
 
-    /* Epoch-based interleaved LCA */
-    lca_tag1: Vec<usize>,
-    lca_tag2: Vec<usize>,
-    lca_epoch: usize,
+    /* Epoch-based interleaved LCA; the tags are cleared when the u32
+     * epoch wraps */
+    lca_tag1: Vec<u32>,
+    lca_tag2: Vec<u32>,
+    lca_epoch: u32,
+
+    /* The search queue, kept from one search to the next so that a search
+     * allocates nothing once it has grown */
+    queue: Vec<Vertex>,
+
+    /* Explicit blossoms (--blossoms); boxed, so the default search keeps
+     * its small Forest */
+    blossoms: Option<Box<blossoms::Blossoms>>,
+
+    /* Paths kept by a forest phase (augment_while); None for the one-path
+     * searches */
+    phase: Option<Box<PhasePaths>>,
 }
 
-impl GabowSimple {
-    fn new(n: usize, edges: &[(usize, usize)]) -> Self {
-        let mut graph = vec![Vec::new(); n];
-        for &(u, v) in edges {
-            if u < n && v < n && u != v {
-                graph[u].push(v);
-                graph[v].push(u);
-            }
-        }
-        for adj in &mut graph {
-            adj.sort_unstable();
-            adj.dedup();
-        }
+/* The paths of one forest phase: a search that keeps going after a path,
+ * keeping every later one that shares no vertex with those kept. All are
+ * augmenting for the matching the forest grew on, so they are flipped
+ * together afterwards. */
+struct PhasePaths {
+    used: Vec<bool>,
+    paths: Vec<Vec<(Vertex, Vertex)>>,
+}
 
-        GabowSimple {
-            n,
-            graph,
-            mate: vec![NIL; n],
-            base: vec![0; n],
-            parent: vec![NIL; n],
+impl Forest {
+    fn new(n: usize, explicit_blossoms: bool) -> Self {
+        Forest {
+            base: dsu::Dsu::new(n),
+            parent: vec![NONE; n],
             label: vec![UNLABELED; n],
-            bridge_src: vec![NIL; n],
-            bridge_tgt: vec![NIL; n],
+            bridge_src: vec![NONE; n],
+            bridge_tgt: vec![NONE; n],
             lca_tag1: vec![0; n],
             lca_tag2: vec![0; n],
             lca_epoch: 0,
-            greedy_size: 0,
+            queue: Vec::new(),
+            blossoms: if explicit_blossoms { Some(Box::new(blossoms::Blossoms::new(n))) } else { None },
+            phase: None,
         }
     }
 
-    fn greedy_init(&mut self) -> usize {
-        let mut cnt = 0usize;
-        for u in 0..self.n {
-            if self.mate[u] != NIL { continue; }
-            let neighbors: Vec<usize> = self.graph[u].clone();
-            for &v in &neighbors {
-                if self.mate[v] == NIL {
-                    self.mate[u] = v as i32;
-                    self.mate[v] = u as i32;
-                    cnt += 1;
-                    break;
-                }
-            }
-        }
-        cnt
-    }
-
-    fn greedy_init_md(&mut self) -> usize {
-        let mut cnt = 0usize;
-        let mut deg = vec![0usize; self.n];
-        for u in 0..self.n {
-            for &v in &self.graph[u] {
-                deg[v] += 1;
-            }
-        }
-        let mut order: Vec<usize> = (0..self.n).collect();
-        order.sort_unstable_by(|&a, &b| deg[a].cmp(&deg[b]).then(a.cmp(&b)));
-        for u in order {
-            if self.mate[u] != NIL { continue; }
-            let mut best: i32 = -1;
-            let mut best_deg = usize::MAX;
-            let neighbors: Vec<usize> = self.graph[u].clone();
-            for &v in &neighbors {
-                if self.mate[v] == NIL && deg[v] < best_deg {
-                    best = v as i32;
-                    best_deg = deg[v];
-                }
-            }
-            if best >= 0 {
-                self.mate[u] = best;
-                self.mate[best as usize] = u as i32;
-                cnt += 1;
-            }
-        }
-        cnt
-    }
-
-    /* Path-halving find for union-find base */
-    fn find_base(&mut self, mut v: usize) -> usize {
-        while self.base[v] != v {
-            self.base[v] = self.base[self.base[v]];
-            v = self.base[v];
-        }
-        v
-    }
-
     /* Interleaved LCA using epoch tags.
-     * Returns the LCA base if u and v are in the same tree, or NIL if
+     * Returns the LCA base if u and v are in the same tree, or NONE if
      * they are in different trees (= augmenting path). */
-    fn find_lca(&mut self, u: usize, v: usize) -> i32 {
+    fn find_lca(&mut self, mate: &[Vertex], u: usize, v: usize) -> Vertex {
+        if self.lca_epoch == u32::MAX {
+            self.lca_tag1.iter_mut().for_each(|t| *t = 0);
+            self.lca_tag2.iter_mut().for_each(|t| *t = 0);
+            self.lca_epoch = 0;
+        }
         self.lca_epoch += 1;
         let ep = self.lca_epoch;
-        let mut hx = self.find_base(u);
-        let mut hy = self.find_base(v);
+        let mut hx = self.base.find(u);
+        let mut hy = self.base.find(v);
         self.lca_tag1[hx] = ep;
         self.lca_tag2[hy] = ep;
         loop {
-            if self.lca_tag1[hy] == ep { return hy as i32; }
-            if self.lca_tag2[hx] == ep { return hx as i32; }
-            let hxr = self.mate[hx] == NIL;
-            let hyr = self.mate[hy] == NIL;
-            if hxr && hyr { return NIL; }
+            if self.lca_tag1[hy] == ep { return vx(hy); }
+            if self.lca_tag2[hx] == ep { return vx(hx); }
+            let hxr = mate[hx] == NONE;
+            let hyr = mate[hy] == NONE;
+            if hxr && hyr { return NONE; }
             if !hxr {
-                hx = self.find_base(self.parent[self.mate[hx] as usize] as usize);
+                hx = self.base.find(ix(self.parent[ix(mate[hx])]));
                 self.lca_tag1[hx] = ep;
             }
             if !hyr {
-                hy = self.find_base(self.parent[self.mate[hy] as usize] as usize);
+                hy = self.base.find(ix(self.parent[ix(mate[hy])]));
                 self.lca_tag2[hy] = ep;
             }
         }
@@ -165,48 +173,46 @@ impl GabowSimple {
      * and enqueue mv as newly-EVEN if it wasn't already. */
     fn shrink_path(
         &mut self,
+        mate: &[Vertex],
         lca: usize,
         x: usize,
         y: usize,
-        queue: &mut Vec<usize>,
+        queue: &mut Vec<Vertex>,
     ) {
-        let mut v = self.find_base(x);
+        let mut v = self.base.find(x);
         while v != lca {
-            let mv = self.mate[v] as usize;
+            let mv = ix(mate[v]);
             /* Union both v and mv into lca's component */
-            let fv = self.find_base(v);
-            self.base[fv] = lca;
-            let fmv = self.find_base(mv);
-            self.base[fmv] = lca;
-            self.base[lca] = lca;
+            self.base.union_into(v, lca);
+            self.base.union_into(mv, lca);
 
             /* Record bridge for mv */
-            self.bridge_src[mv] = x as i32;
-            self.bridge_tgt[mv] = y as i32;
+            self.bridge_src[mv] = vx(x);
+            self.bridge_tgt[mv] = vx(y);
 
             /* If mv was ODD and not yet enqueued as EVEN, enqueue it */
             if self.label[mv] != EVEN {
                 self.label[mv] = EVEN;
-                queue.push(mv);
+                queue.push(vx(mv));
             }
 
             /* Walk up */
-            v = self.find_base(self.parent[mv] as usize);
+            v = self.base.find(ix(self.parent[mv]));
         }
     }
 
-    /* Trace from vertex v to vertex u (or to a root if u==NIL),
+    /* Trace from vertex v to vertex u (or to a root if u==NONE),
      * collecting edge pairs for augmentation.
      *   - No bridge -> "originally EVEN": step mate -> parent
      *   - Has bridge -> "originally ODD, absorbed into blossom":
      *     recurse through bridge */
-    fn trace_path(&self, v: i32, u: i32, pairs: &mut Vec<(i32, i32)>) {
+    fn trace_path(&self, mate: &[Vertex], v: Vertex, u: Vertex, pairs: &mut Vec<(Vertex, Vertex)>) {
         struct Frame {
-            v: i32,
-            u: i32,
+            v: Vertex,
+            u: Vertex,
             phase: i32,
-            sb: i32,
-            tb: i32,
+            sb: Vertex,
+            tb: Vertex,
         }
         let mut stk: Vec<Frame> = vec![Frame { v, u, phase: 0, sb: 0, tb: 0 }];
 
@@ -220,16 +226,16 @@ impl GabowSimple {
             }
 
             if f.phase == 0 {
-                let fv = f.v as usize;
-                if self.bridge_src[fv] == NIL {
+                let fv = ix(f.v);
+                if self.bridge_src[fv] == NONE {
                     /* Originally EVEN vertex (no bridge) */
-                    if self.mate[fv] == NIL {
+                    if mate[fv] == NONE {
                         /* Root (free vertex) -- done */
                         stk.pop();
                         continue;
                     }
-                    let mv = self.mate[fv];
-                    let pmv = self.parent[mv as usize];
+                    let mv = mate[fv];
+                    let pmv = self.parent[ix(mv)];
                     pairs.push((mv, pmv));
                     stk[len - 1].v = pmv;
                     continue;
@@ -237,7 +243,7 @@ impl GabowSimple {
                 /* Has bridge -- originally ODD, absorbed into blossom */
                 let sb = self.bridge_src[fv];
                 let tb = self.bridge_tgt[fv];
-                let mate_fv = self.mate[fv];
+                let mate_fv = mate[fv];
                 stk[len - 1].sb = sb;
                 stk[len - 1].tb = tb;
                 stk[len - 1].phase = 1;
@@ -257,159 +263,626 @@ impl GabowSimple {
         }
     }
 
-    /* Augment along the path:
-     *   root_u ~~~ u -- v ~~~ root_v
-     * Collect all edge pairs, then flip mate for all of them. */
-    fn augment_two_sides(&mut self, u: usize, v: usize) {
-        let mut pairs: Vec<(i32, i32)> = Vec::new();
-        /* The cross-tree edge */
-        pairs.push((u as i32, v as i32));
-        /* Trace from u to its root */
-        self.trace_path(u as i32, NIL, &mut pairs);
-        /* Trace from v to its root */
-        self.trace_path(v as i32, NIL, &mut pairs);
-        /* Flip all */
-        for &(a, b) in &pairs {
-            self.mate[a as usize] = b;
-            self.mate[b as usize] = a;
+    /* --blossoms: the explicit blossom for the EVEN-EVEN edge (u, v). This
+     * and trace_to_root stay out of line, off the default search's path. */
+    #[inline(never)]
+    fn contract_blossom(&mut self, mate: &[Vertex], lca: usize, u: usize, v: usize) {
+        if let Some(ref mut b) = self.blossoms {
+            b.contract_bridge(lca, u, v, mate, &self.parent);
         }
     }
 
-    /* Find one augmenting path in the forest and augment.
-     * Returns true if an augmentation was performed. */
-    fn find_and_augment(&mut self) -> bool {
-        /* Reset per-iteration state */
-        for i in 0..self.n {
-            self.base[i] = i;
-            self.parent[i] = NIL;
-            self.label[i] = UNLABELED;
-            self.bridge_src[i] = NIL;
-            self.bridge_tgt[i] = NIL;
+    /* Unmatched edges from EVEN vertex v to its root: through the bridges,
+     * or with --blossoms through the blossom cycles. */
+    #[inline(never)]
+    fn trace_to_root(&self, mate: &[Vertex], v: usize, pairs: &mut Vec<(Vertex, Vertex)>) {
+        match self.blossoms {
+            Some(ref b) => {
+                let mut path = Vec::new();
+                b.climb(v, mate, &self.parent, &mut path);
+                pairs.extend(path.iter().map(|&(x, y)| (vx(x), vx(y))));
+            }
+            None => self.trace_path(mate, vx(v), NONE, pairs),
         }
+    }
 
-        let mut queue: Vec<usize> = Vec::with_capacity(self.n);
-
-        /* All free vertices become EVEN roots */
-        for v in 0..self.n {
-            if self.mate[v] == NIL {
-                self.label[v] = EVEN;
-                queue.push(v);
+    /* Grow a forest from `roots` (free vertices, EVEN; None for every free
+     * vertex) until an augmenting path turns up, and return its edges,
+     * matched and unmatched:
+     *   root_u ~~~ u -- v ~~~ root_v
+     * The path ends where two trees meet, or at a free vertex that is not a
+     * root (one left to another thread); then the v side is empty. */
+    fn search(&mut self, g: &GabowSimple, roots: Option<&[usize]>) -> Option<Vec<(Vertex, Vertex)>> {
+        let mate = &g.mate[..];
+        let mut queue = std::mem::take(&mut self.queue);
+        queue.clear();
+        /* Reset per-iteration state. A round that finds its path early does
+         * little else, so this runs a whole array at a time (fill), not one
+         * vertex at a time over all six. */
+        self.base.reset();
+        self.parent.fill(NONE);
+        self.label.fill(UNLABELED);
+        self.bridge_src.fill(NONE);
+        self.bridge_tgt.fill(NONE);
+        /* the roots: every free vertex, or the block `roots` names */
+        if roots.is_none() {
+            for (i, &m) in mate.iter().enumerate() {
+                if m == NONE {
+                    self.label[i] = EVEN;
+                    queue.push(vx(i));
+                }
             }
         }
+        if let Some(ref mut b) = self.blossoms {
+            b.reset();
+        }
+        for &v in roots.unwrap_or(&[]) {
+            self.label[v] = EVEN;
+            queue.push(vx(v));
+        }
 
         let mut qi = 0;
         while qi < queue.len() {
-            let u = queue[qi];
+            let u = ix(queue[qi]);
             qi += 1;
 
             /* Check that u is still effectively EVEN */
-            let bu = self.find_base(u);
+            let bu = self.base.find(u);
             if self.label[bu] != EVEN { continue; }
 
-            let neighbors = self.graph[u].clone();
-            for &v in &neighbors {
-                let bu2 = self.find_base(u);
-                let bv = self.find_base(v);
+            for &v in g.neighbors(u) {
+                let v = ix(v);
+                let bu2 = self.base.find(u);
+                let bv = self.base.find(v);
                 if bu2 == bv { continue; }
-                if v as i32 == self.mate[u] { continue; }
+                if vx(v) == mate[u] { continue; }
 
                 if self.label[bv] == UNLABELED {
+                    if mate[v] == NONE {
+                        /* Free vertex outside the forest -> augmenting path */
+                        let mut pairs = vec![(vx(u), vx(v))];
+                        self.trace_to_root(mate, u, &mut pairs);
+                        if self.phase.is_none() {
+                            self.queue = queue;
+                            return Some(pairs);
+                        }
+                        self.keep_path(pairs);
+                        continue;
+                    }
                     /* v is matched and unlabeled -> grow step */
                     self.label[v] = ODD;
-                    self.parent[v] = u as i32;
-                    let w = self.mate[v] as usize;
+                    self.parent[v] = vx(u);
+                    let w = ix(mate[v]);
                     self.label[w] = EVEN;
-                    queue.push(w);
+                    queue.push(vx(w));
 
                 } else if self.label[bv] == EVEN {
                     /* EVEN-EVEN edge: blossom or augmenting path */
-                    let lca = self.find_lca(u, v);
-                    if lca != NIL {
+                    let lca = self.find_lca(mate, u, v);
+                    if lca != NONE {
                         /* Same tree -> blossom contraction */
-                        let lca_u = lca as usize;
-                        self.shrink_path(lca_u, u, v, &mut queue);
-                        self.shrink_path(lca_u, v, u, &mut queue);
+                        let lca_u = ix(lca);
+                        if let Some(t) = g.trace {
+                            t("blossom", &[lca_u, u, v]);
+                        }
+                        if self.blossoms.is_some() {
+                            self.contract_blossom(mate, lca_u, u, v);
+                        }
+                        self.shrink_path(mate, lca_u, u, v, &mut queue);
+                        self.shrink_path(mate, lca_u, v, u, &mut queue);
                     } else {
                         /* Different trees -> augmenting path! */
-                        self.augment_two_sides(u, v);
-                        return true;
+                        let mut pairs = vec![(vx(u), vx(v))];
+                        self.trace_to_root(mate, u, &mut pairs);
+                        self.trace_to_root(mate, v, &mut pairs);
+                        if self.phase.is_none() {
+                            self.queue = queue;
+                            return Some(pairs);
+                        }
+                        self.keep_path(pairs);
                     }
                 }
                 /* label[bv] == ODD: ignore */
             }
         }
-        false
+        self.queue = queue;
+        None
     }
 
-    fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
+    /* Forest phase: keep the path unless it meets one kept before. */
+    #[inline(never)]
+    fn keep_path(&mut self, pairs: Vec<(Vertex, Vertex)>) {
+        let phase = self.phase.as_mut().unwrap();
+        if pairs.iter().any(|&(a, b)| phase.used[ix(a)] || phase.used[ix(b)]) { return; }
+        for &(a, b) in &pairs {
+            phase.used[ix(a)] = true;
+            phase.used[ix(b)] = true;
+        }
+        phase.paths.push(pairs);
+    }
+}
+
+/* One forest phase, for callers that watch the run (see
+ * GabowSimple::augment_while; not used by this binary). */
+#[allow(dead_code)]
+pub(crate) struct PhaseStats {
+    pub(crate) free: usize,    /* free vertices with an edge left after the augmentations */
+    pub(crate) paths: usize,   /* vertex-disjoint paths augmented */
+    pub(crate) longest: usize, /* edges on the longest of them */
+}
+
+pub(crate) struct GabowSimple {
+    n: usize,
+    pub(crate) greedy_size: usize,
+    pub(crate) greedy_seed: u64, /* for greedy_mode 4 (--greedy-random) */
+    pub(crate) aug3_passes: usize, /* --init-aug3 passes after the greedy start; 0 = off */
+    pub(crate) aug3_size: usize,   /* pairs those passes added, counted in greedy_size */
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path and blossom (trace.rs) */
+    trace_mate: Vec<usize>, /* for the tracer: the mates before the last augmentation */
+    pub(crate) explicit_blossoms: bool,
+    pub(crate) numa: Option<Vec<Vec<usize>>>, /* --numa: the CPUs of each node (numa.rs) */
+    /* CSR adjacency: neighbors of v are adj[adj_start[v]..adj_start[v + 1]] */
+    adj_start: Vec<usize>,
+    adj: Vec<Vertex>,
+    mate: Vec<Vertex>,
+}
+
+impl GabowSimple {
+    /* The CSR is filled in place by degree counts, without a list per
+     * vertex: those cost 24 bytes a vertex and 8 an entry more, which
+     * is most of the peak on a sparse graph. */
+    pub(crate) fn new(n: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adj_start = vec![0usize; n + 1];
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                adj_start[u + 1] += 1;
+                adj_start[v + 1] += 1;
+            }
+        }
+        for v in 0..n {
+            adj_start[v + 1] += adj_start[v];
+        }
+        let mut adj = vec![0 as Vertex; adj_start[n]];
+        let mut fill = adj_start.clone();
+        for &(u, v) in edges {
+            if u < n && v < n && u != v {
+                adj[fill[u]] = vx(v);
+                fill[u] += 1;
+                adj[fill[v]] = vx(u);
+                fill[v] += 1;
+            }
+        }
+        drop(fill);
+        /* sort and dedup each list, closing the gaps the duplicates leave */
+        let mut end = 0;
+        for v in 0..n {
+            let (lo, hi) = (adj_start[v], adj_start[v + 1]);
+            adj[lo..hi].sort_unstable();
+            adj_start[v] = end;
+            for k in lo..hi {
+                if k == lo || adj[k] != adj[k - 1] {
+                    adj[end] = adj[k];
+                    end += 1;
+                }
+            }
+        }
+        adj_start[n] = end;
+        adj.truncate(end);
+        adj.shrink_to_fit();
+
+        GabowSimple {
+            n,
+            adj_start,
+            adj,
+            mate: vec![NONE; n],
+            greedy_size: 0,
+            greedy_seed: 1,
+            aug3_passes: 0,
+            aug3_size: 0,
+            stop: None,
+            progress: None,
+            trace: None,
+            trace_mate: Vec::new(),
+            explicit_blossoms: false,
+            numa: None,
+        }
+    }
+
+    fn neighbors(&self, v: usize) -> &[Vertex] {
+        &self.adj[self.adj_start[v]..self.adj_start[v + 1]]
+    }
+
+    fn greedy_init(&mut self) -> usize {
+        let mut cnt = 0usize;
+        for u in 0..self.n {
+            if self.mate[u] != NONE { continue; }
+            for k in self.adj_start[u]..self.adj_start[u + 1] {
+                let v = ix(self.adj[k]);
+                if self.mate[v] == NONE {
+                    self.mate[u] = vx(v);
+                    self.mate[v] = vx(u);
+                    cnt += 1;
+                    break;
+                }
+            }
+        }
+        cnt
+    }
+
+    fn greedy_init_md(&mut self) -> usize {
+        let mut cnt = 0usize;
+        let deg: Vec<usize> = self.adj_start.windows(2).map(|w| w[1] - w[0]).collect();
+        let mut order: Vec<usize> = (0..self.n).collect();
+        order.sort_unstable_by(|&a, &b| deg[a].cmp(&deg[b]).then(a.cmp(&b)));
+        for u in order {
+            if self.mate[u] != NONE { continue; }
+            let mut best = NONE;
+            let mut best_deg = usize::MAX;
+            for &v in self.neighbors(u) {
+                let v = ix(v);
+                if self.mate[v] == NONE && deg[v] < best_deg {
+                    best = vx(v);
+                    best_deg = deg[v];
+                }
+            }
+            if best != NONE {
+                self.mate[u] = best;
+                self.mate[ix(best)] = vx(u);
+                cnt += 1;
+            }
+        }
+        cnt
+    }
+
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor,
+     * match the two; otherwise match the lowest-id exposed vertex that has
+     * one with its first unmatched neighbor. deg counts unmatched neighbors. */
+    fn greedy_init_ks(&mut self) -> usize {
+        let mut deg: Vec<usize> = (0..self.n)
+            .map(|u| if self.mate[u] == NONE { self.neighbors(u).iter().filter(|&&v| self.mate[ix(v)] == NONE).count() } else { 0 })
+            .collect();
+        let mut leaves: Vec<usize> = (0..self.n).filter(|&v| deg[v] == 1).collect();
+        let mut next = 0;
+        let mut cnt = 0usize;
+        loop {
+            let u = match leaves.pop() {
+                Some(u) if self.mate[u] != NONE || deg[u] != 1 => continue,
+                Some(u) => u,
+                None => {
+                    while next < self.n && (self.mate[next] != NONE || deg[next] == 0) { next += 1; }
+                    if next == self.n { break; }
+                    next
+                }
+            };
+            let v = ix(*self.neighbors(u).iter().find(|&&v| self.mate[ix(v)] == NONE).unwrap());
+            self.mate[u] = vx(v);
+            self.mate[v] = vx(u);
+            cnt += 1;
+            for &x in &[u, v] {
+                for &w in self.neighbors(x) {
+                    let w = ix(w);
+                    if self.mate[w] != NONE { continue; }
+                    deg[w] -= 1;
+                    if deg[w] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched neighbor. The
+     * same seed gives the same matching. */
+    fn greedy_init_random(&mut self, seed: u64) -> usize {
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<usize> = (0..self.n).collect();
+        rng.shuffle(&mut order);
+        let mut cnt = 0usize;
+        for u in order {
+            if self.mate[u] != NONE { continue; }
+            let free = self.neighbors(u).iter().filter(|&&v| self.mate[ix(v)] == NONE).count();
+            if free == 0 { continue; }
+            let k = rng.below(free as u64) as usize;
+            let v = ix(*self.neighbors(u).iter().filter(|&&v| self.mate[ix(v)] == NONE).nth(k).unwrap());
+            self.mate[u] = vx(v);
+            self.mate[v] = vx(u);
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+     * free vertices in id order, each augmenting along the first path
+     * u - v = w - x found from u (v matched to w, x free, x != u), or
+     * matching u with a free neighbor outright. A pass without one ends
+     * it. Matched vertices stay matched, so next[w] skips them for good. */
+    fn augment3(&mut self, passes: usize) -> usize {
+        let mut next: Vec<usize> = self.adj_start[..self.n].to_vec();
+        let mut cnt = 0usize;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..self.n {
+                if self.mate[u] != NONE { continue; }
+                for k in self.adj_start[u]..self.adj_start[u + 1] {
+                    let v = ix(self.adj[k]);
+                    if self.mate[v] == NONE {
+                        self.mate[u] = vx(v);
+                        self.mate[v] = vx(u);
+                        cnt += 1;
+                        break;
+                    }
+                    let w = ix(self.mate[v]);
+                    let end = self.adj_start[w + 1];
+                    while next[w] < end && self.mate[ix(self.adj[next[w]])] != NONE { next[w] += 1; }
+                    let mut j = next[w];
+                    while j < end && (ix(self.adj[j]) == u || self.mate[ix(self.adj[j])] != NONE) { j += 1; }
+                    if j < end {
+                        let x = ix(self.adj[j]);
+                        self.mate[u] = vx(v);
+                        self.mate[v] = vx(u);
+                        self.mate[w] = vx(x);
+                        self.mate[x] = vx(w);
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
+    fn free_vertices(&self) -> Vec<usize> {
+        (0..self.n).filter(|&v| self.mate[v] == NONE).collect()
+    }
+
+    fn flip(&mut self, pairs: &[(Vertex, Vertex)]) {
+        for &(a, b) in pairs {
+            self.mate[ix(a)] = b;
+            self.mate[ix(b)] = a;
+        }
+        if let Some(&(a, _)) = pairs.first() {
+            self.trace_augment(ix(a));
+        }
+    }
+
+    /* Find one augmenting path in the forest of all free vertices and
+     * augment. Returns the augmentations performed, 0 or 1. */
+    fn find_and_augment(&mut self, forest: &mut Forest) -> usize {
+        match forest.search(self, None) {
+            Some(pairs) => {
+                self.flip(&pairs);
+                1
+            }
+            None => 0,
+        }
+    }
+
+    /* Parallel round: the free vertices are split into one contiguous
+     * block of roots per forest, and each thread grows its own forest
+     * (the other blocks' free vertices are path ends to it, not roots).
+     * Trees of different threads may overlap, so the paths found are
+     * resolved in thread order: a path is kept only if it shares no vertex
+     * with a kept one, and all kept paths are augmented together. The
+     * result does not depend on the scheduling. A round that finds nothing
+     * falls back to the sequential search over all roots, so termination
+     * does not rest on the split searches being complete. */
+    fn find_and_augment_parallel(&mut self, forests: &mut [Forest]) -> usize {
+        let roots = self.free_vertices();
+        if roots.len() < 2 * forests.len() {
+            return self.find_and_augment(&mut forests[0]);
+        }
+        let block = roots.len().div_ceil(forests.len());
+        let cpus = self.forest_cpus(forests.len());
+        let found: Vec<Option<Vec<(Vertex, Vertex)>>> = {
+            let g = &*self;
+            thread::scope(|s| {
+                let handles: Vec<_> = forests.iter_mut().zip(roots.chunks(block)).zip(&cpus)
+                    .map(|((f, r), &cpu)| s.spawn(move || {
+                        if let Some(cpu) = cpu { numa::pin(cpu); }
+                        f.search(g, Some(r))
+                    }))
+                    .collect();
+                handles.into_iter().map(|h| h.join().unwrap()).collect()
+            })
+        };
+
+        let mut used = vec![false; self.n];
+        let mut augmented = 0;
+        for pairs in found.into_iter().flatten() {
+            if pairs.iter().any(|&(a, b)| used[ix(a)] || used[ix(b)]) { continue; }
+            for &(a, b) in &pairs {
+                used[ix(a)] = true;
+                used[ix(b)] = true;
+            }
+            self.flip(&pairs);
+            augmented += 1;
+        }
+        if augmented > 0 { augmented } else { self.find_and_augment(&mut forests[0]) }
+    }
+
+    /* The CPU each forest's thread is pinned to under --numa (None: not
+     * pinned), the same in every round. */
+    fn forest_cpus(&self, threads: usize) -> Vec<Option<usize>> {
+        match self.numa {
+            Some(ref nodes) => {
+                let topo = numa::Topology { nodes: nodes.clone() };
+                (0..threads).map(|t| Some(topo.cpu_of(t, threads))).collect()
+            }
+            None => vec![None; threads],
+        }
+    }
+
+    /* One forest per thread; under --numa each is allocated by a thread on
+     * its CPU, so that its arrays are in that node's memory. */
+    fn forests(&self, threads: usize) -> Vec<Forest> {
+        let (n, explicit) = (self.n, self.explicit_blossoms);
+        if threads < 2 || self.numa.is_none() {
+            return (0..threads.max(1)).map(|_| Forest::new(n, explicit)).collect();
+        }
+        thread::scope(|s| {
+            let handles: Vec<_> = self.forest_cpus(threads).into_iter()
+                .map(|cpu| s.spawn(move || {
+                    if let Some(cpu) = cpu { numa::pin(cpu); }
+                    Forest::new(n, explicit)
+                }))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        })
+    }
+
+    pub(crate) fn start(&mut self, greedy_mode: i32) {
         self.greedy_size = match greedy_mode {
             1 => self.greedy_init(),
             2 => self.greedy_init_md(),
+            3 => self.greedy_init_ks(),
+            4 => self.greedy_init_random(self.greedy_seed),
             _ => 0,
         };
+        if self.aug3_passes > 0 {
+            self.aug3_size = self.augment3(self.aug3_passes);
+            self.greedy_size += self.aug3_size;
+        }
+        if let Some(t) = self.trace {
+            self.trace_mate = trace::begin(t, self.n, &|v| self.mate(v));
+        }
+    }
 
-        while self.find_and_augment() {}
-
+    fn matching(&self) -> Vec<(usize, usize)> {
         let mut matching = Vec::new();
         for u in 0..self.n {
-            if self.mate[u] != NIL && (self.mate[u] as usize) > u {
-                matching.push((u, self.mate[u] as usize));
+            if self.mate[u] != NONE && ix(self.mate[u]) > u {
+                matching.push((u, ix(self.mate[u])));
             }
         }
         matching.sort_unstable();
         matching
     }
-}
 
-fn validate_matching(n: usize, graph: &[Vec<usize>], matching: &[(usize, usize)]) {
-    let mut deg = vec![0i32; n];
-    let mut errors = 0;
+    /* Free vertices that could still be matched; isolated ones never are. */
+    #[allow(dead_code)]
+    pub(crate) fn free_with_edges(&self) -> usize {
+        (0..self.n).filter(|&v| self.mate[v] == NONE && self.adj_start[v] < self.adj_start[v + 1]).count()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn mates(&self) -> &[Vertex] {
+        &self.mate
+    }
+
+    /* Per-vertex queries on the current matching, as on matchers::Solution. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        self.mate.get(v).and_then(|&m| types::some(m))
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
 
-    for &(u, v) in matching {
-        if graph[u].binary_search(&v).is_err() {
-            eprintln!("ERROR: Edge ({}, {}) not in graph!", u, v);
-            errors += 1;
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.n).filter(move |&v| self.mate[v] == NONE)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.mate.iter().filter(|&&m| m != NONE).count() / 2
+    }
+
+    /* A stop was requested (SIGINT/SIGTERM in combi): the caller gets the
+     * matching of the last finished phase. */
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+    }
+
+    /* x is on the path just flipped. */
+    fn trace_augment(&mut self, x: usize) {
+        if let Some(t) = self.trace {
+            let mut before = std::mem::take(&mut self.trace_mate);
+            trace::augmented(t, x, &|v| self.mate(v), &mut before);
+            self.trace_mate = before;
         }
-        deg[u] += 1;
-        deg[v] += 1;
     }
-    for i in 0..n {
-        if deg[i] > 1 {
-            eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]);
-            errors += 1;
+
+    /* Start from a matching found elsewhere (mate[v] = NONE for free v),
+     * e.g. --init-matching; maximum_matching(0) then only augments it. */
+    pub(crate) fn set_mate(&mut self, mate: &[Vertex]) {
+        self.mate.copy_from_slice(mate);
+    }
+
+    /* Forest phases (after start): each grows one forest over all free
+     * vertices to the end and augments the vertex-disjoint paths it kept
+     * (see PhasePaths). Runs while `go_on` accepts the last phase's
+     * statistics; returns true if it stopped there, false once the
+     * matching is maximum. Used by gabow_adaptive.rs, which hands the
+     * matching over to the phase-structured search. */
+    #[allow(dead_code)]
+    pub(crate) fn augment_while(&mut self, go_on: &mut dyn FnMut(&PhaseStats) -> bool) -> bool {
+        let mut forest = Forest::new(self.n, self.explicit_blossoms);
+        forest.phase = Some(Box::new(PhasePaths { used: vec![false; self.n], paths: Vec::new() }));
+        let mut free = self.free_with_edges();
+        loop {
+            forest.search(self, None);
+            let phase = forest.phase.as_mut().unwrap();
+            if phase.paths.is_empty() {
+                return false;
+            }
+            let paths = std::mem::take(&mut phase.paths);
+            let mut longest = 0;
+            for pairs in &paths {
+                for &(a, b) in pairs {
+                    phase.used[ix(a)] = false;
+                    phase.used[ix(b)] = false;
+                }
+                self.flip(pairs);
+                longest = longest.max(2 * pairs.len() - 1);
+            }
+            free -= 2 * paths.len();
+            let more = go_on(&PhaseStats { free, paths: paths.len(), longest });
+            if free == 0 {
+                return false;
+            }
+            if !more {
+                return true;
+            }
         }
     }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
 
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!(
-        "{}",
-        if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" }
-    );
-    println!("=========================\n");
-}
+    pub(crate) fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
+        self.maximum_matching_parallel(greedy_mode, 1)
+    }
+
+    /* With threads > 1 every round searches from disjoint root blocks on
+     * that many threads; see find_and_augment_parallel. */
+    pub(crate) fn maximum_matching_parallel(&mut self, greedy_mode: i32, threads: usize) -> Vec<(usize, usize)> {
+        self.start(greedy_mode);
 
-fn load_graph(filename: &str) -> Result<(usize, Vec<(usize, usize)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    let n: usize = parts[0].parse()?;
-    let m: usize = parts[1].parse()?;
-    let mut edges = Vec::with_capacity(m);
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: usize = parts[0].parse()?;
-            let v: usize = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-    Ok((n, edges))
+        let mut forests = self.forests(threads);
+        /* for the progress reports: pairs before the rounds, and found since */
+        let start = if self.progress.is_some() { self.matching_size() } else { 0 };
+        let (mut round, mut found) = (0, 0);
+        while !self.stopped() {
+            let augmented = if forests.len() == 1 {
+                self.find_and_augment(&mut forests[0])
+            } else {
+                self.find_and_augment_parallel(&mut forests)
+            };
+            round += 1;
+            found += augmented;
+            if let Some(report) = self.progress {
+                report(round, found, self.n - 2 * (start + found));
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[round]);
+            }
+            if augmented == 0 { break; }
+        }
+        self.matching()
+    }
 }
 
 fn main() {
@@ -418,42 +891,200 @@ fn main() {
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} [--threads N {}] [--blossoms] {} {} {} {} {} {} {} {} {}", args[0],
+                  args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, numa::NUMA_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
         std::process::exit(1);
     }
 
-    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-md") {
+    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-random") {
+        4
+    } else if args.iter().any(|a| a == "--greedy-ks") {
+        3
+    } else if args.iter().any(|a| a == "--greedy-md") {
         2
     } else if args.iter().any(|a| a == "--greedy") {
         1
     } else {
         0
     };
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    let greedy_mode = if greedy_mode == 0 && aug3 > 0 { 1 } else { greedy_mode };
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    /* --threads N: grow the forest from N disjoint root blocks at once;
+     * 0 means one per available core. */
+    let threads: usize = match args::value_of(&args, "--threads").map(|s| s.parse::<usize>()) {
+        None => 1,
+        Some(Ok(0)) => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        Some(Ok(t)) => t,
+        Some(Err(_)) => {
+            eprintln!("Error: --threads expects a number");
+            std::process::exit(1);
+        }
+    };
+
+    let numa = args::has_flag(&args, "--numa");
+    if numa && threads < 2 {
+        eprintln!("Error: --numa places the threads of --threads N (N > 1)");
+        std::process::exit(1);
+    }
+
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(n) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
             let start = Instant::now();
             let mut gabow = GabowSimple::new(n, &edges);
-            let matching = gabow.maximum_matching(greedy_mode);
+            gabow.explicit_blossoms = args::has_flag(&args, "--blossoms");
+            if numa {
+                gabow.numa = Some(numa::Topology::detect().nodes);
+            }
+            gabow.greedy_seed = seed;
+            gabow.aug3_passes = aug3;
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                gabow.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                gabow.stop = Some(&time_limit::EXPIRED);
+            }
+            if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("gabow-simple {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                gabow.trace = Some(&trace::record);
+            } else if log::enabled(log::Level::Debug) {
+                gabow.trace = Some(&log::event);
+            }
+            if let Some(ref pairs) = init {
+                gabow.set_mate(&types::mate_of(n, pairs));
+            }
+            let matching = if threads > 1 {
+                gabow.maximum_matching_parallel(greedy_mode, threads)
+            } else {
+                gabow.maximum_matching(greedy_mode)
+            };
             let duration = start.elapsed();
-            validate_matching(n, &gabow.graph, &matching);
+            let gap = time_limit.filter(|_| time_limit::expired())
+                .map(|limit| (limit, time_limit::gap_bound(n, &edges, &matching)));
+            let mut report = verify::validate_general(n, &edges, &matching);
+            if args::has_flag(&args, "--certify") && report.is_valid() {
+                report.optimal = Some(vertex_classes::certify(n, &edges, &matching));
+            }
+            report.print();
             println!("Matching size: {}", matching.len());
+            if threads > 1 {
+                println!("Threads: {}", threads);
+            }
+            if let Some(ref nodes) = gabow.numa {
+                println!("NUMA: {} node(s), {} in use, threads pinned", nodes.len(), nodes.len().min(threads));
+            }
+            if gabow.explicit_blossoms {
+                println!("Blossoms: explicit");
+            }
             if greedy_mode > 0 {
                 let gs = gabow.greedy_size;
                 let fs = matching.len();
                 println!("Greedy init size: {}", gs);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", gabow.aug3_size);
+                }
                 if fs > 0 {
                     println!("Greedy/Final: {:.2}%", 100.0 * gs as f64 / fs as f64);
                 } else {
                     println!("Greedy/Final: NA");
                 }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            if let Some(path) = args::value_of(&args, "--dot") {
+                if let Err(e) = dot::save(path, n, &edges, &matching, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = args::value_of(&args, "--svg") {
+                if let Err(e) = svg::save(path, n, &edges, &matching, None, svg_layout) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
@@ -9,6 +9,7 @@
 
 #include <cstdio>
 #include <cstdlib>
+#include <cstdint>
 #include <vector>
 #include <algorithm>
 #include <string>
@@ -17,9 +18,31 @@
 
 static const int NIL = -1;
 
+/* SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+   stream, so --greedy-random --seed S picks the same matching there */
+struct SplitMix64 {
+    uint64_t state;
+    explicit SplitMix64(uint64_t seed) : state(seed) {}
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+    /* uniform in [0, bound) */
+    uint64_t below(uint64_t bound) { return (uint64_t)(((unsigned __int128)next() * bound) >> 64); }
+    template <typename T> void shuffle(std::vector<T>& v) {
+        for (size_t i = v.size(); i > 1; i--) std::swap(v[i - 1], v[below(i)]);
+    }
+};
+
 struct HopcroftKarp {
     int left_count;
     int greedy_size = 0;
+    uint64_t greedy_seed = 1; /* for greedy_mode 4 (--greedy-random) */
+    int aug3_passes = 0; /* --init-aug3 passes after the greedy start; 0 = off */
+    int aug3_size = 0;   /* pairs those passes added, counted in greedy_size */
     int right_count;
     std::vector<std::vector<int>> graph; /* graph[u] = list of right nodes */
     std::vector<int> pair_left;
@@ -120,12 +143,104 @@ struct HopcroftKarp {
         return cnt;
     }
 
+    /* Karp-Sipser over both sides (right vertex v is left_count + v here): while an exposed
+       vertex has a single unmatched neighbor, match the two; otherwise match the lowest-id
+       exposed left vertex that has one with its first unmatched neighbor */
+    int greedy_init_ks() {
+        int cnt = 0;
+        int total = left_count + right_count;
+        std::vector<std::vector<int>> adj(total);
+        for (int u = 0; u < left_count; u++)
+            for (int v : graph[u]) { adj[u].push_back(left_count + v); adj[left_count + v].push_back(u); }
+        std::vector<char> matched(total);
+        for (int x = 0; x < total; x++)
+            matched[x] = x < left_count ? pair_left[x] != NIL : pair_right[x - left_count] != NIL;
+        std::vector<int> deg(total, 0), leaves;
+        for (int x = 0; x < total; x++) {
+            if (matched[x]) continue;
+            for (int y : adj[x]) if (!matched[y]) deg[x]++;
+            if (deg[x] == 1) leaves.push_back(x);
+        }
+        int next = 0;
+        while (true) {
+            int x;
+            if (!leaves.empty()) {
+                x = leaves.back(); leaves.pop_back();
+                if (matched[x] || deg[x] != 1) continue;
+            } else {
+                while (next < left_count && (matched[next] || deg[next] == 0)) next++;
+                if (next == left_count) break;
+                x = next;
+            }
+            int y = -1;
+            for (int w : adj[x]) if (!matched[w]) { y = w; break; }
+            int u = x < left_count ? x : y, v = (x < left_count ? y : x) - left_count;
+            pair_left[u] = v; pair_right[v] = u; cnt++;
+            matched[x] = matched[y] = 1;
+            for (int z : {x, y})
+                for (int w : adj[z])
+                    if (!matched[w] && --deg[w] == 1) leaves.push_back(w);
+        }
+        return cnt;
+    }
+
+    /* Random greedy: left vertices in a seeded random order, each exposed
+       one matched with a uniformly random unmatched right neighbor */
+    int greedy_init_random(uint64_t seed) {
+        SplitMix64 rng(seed);
+        std::vector<int> order(left_count);
+        for (int u = 0; u < left_count; u++) order[u] = u;
+        rng.shuffle(order);
+        int cnt = 0;
+        std::vector<int> free;
+        for (int u : order) {
+            if (pair_left[u] != NIL) continue;
+            free.clear();
+            for (int v : graph[u]) if (pair_right[v] == NIL) free.push_back(v);
+            if (free.empty()) continue;
+            int v = free[rng.below(free.size())];
+            pair_left[u] = v; pair_right[v] = u; cnt++;
+        }
+        return cnt;
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+       free left vertices, each taking the first path u - v = w - x from u
+       (x a free right vertex) or a free right neighbor outright. A pass
+       without one ends it; next[w] skips right neighbors matched for good. */
+    int augment3(int passes) {
+        std::vector<size_t> next(left_count, 0);
+        int cnt = 0;
+        for (int p = 0; p < passes; p++) {
+            int before = cnt;
+            for (int u = 0; u < left_count; u++) {
+                if (pair_left[u] != NIL) continue;
+                for (int v : graph[u]) {
+                    if (pair_right[v] == NIL) { pair_left[u] = v; pair_right[v] = u; cnt++; break; }
+                    int w = pair_right[v];
+                    const std::vector<int>& nw = graph[w];
+                    while (next[w] < nw.size() && pair_right[nw[next[w]]] != NIL) next[w]++;
+                    if (next[w] < nw.size()) {
+                        int x = nw[next[w]];
+                        pair_left[u] = v; pair_right[v] = u; pair_left[w] = x; pair_right[x] = w; cnt++;
+                        break;
+                    }
+                }
+            }
+            if (cnt == before) break;
+        }
+        return cnt;
+    }
+
 
     std::vector<std::pair<int,int>> maximum_matching(int greedy_mode = 0) {
         int greedy_count = 0;
         if (greedy_mode == 1) greedy_count = greedy_init();
         else if (greedy_mode == 2) greedy_count = greedy_init_md();
+        else if (greedy_mode == 3) greedy_count = greedy_init_ks();
+        else if (greedy_mode == 4) greedy_count = greedy_init_random(greedy_seed);
         greedy_size = greedy_count;
+        if (aug3_passes > 0) { aug3_size = augment3(aug3_passes); greedy_size += aug3_size; }
         while (bfs()) {
             for (int u = 0; u < left_count; u++) {
                 if (pair_left[u] == NIL) dfs(u);
@@ -175,12 +290,22 @@ int main(int argc, char* argv[]) {
     printf("Hopcroft-Karp Algorithm - C++ Implementation\n");
     printf("==============================================\n\n");
 
-    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md]\n", argv[0]); return 1; }
+    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]\n", argv[0]); return 1; }
     int greedy_mode = 0;
+    uint64_t seed = 1;
+    int aug3 = 0, aug3_passes = 3;
     for (int i = 2; i < argc; i++) {
         if (std::string(argv[i]) == "--greedy") greedy_mode = 1;
         else if (std::string(argv[i]) == "--greedy-md") greedy_mode = 2;
+        else if (std::string(argv[i]) == "--greedy-ks") greedy_mode = 3;
+        else if (std::string(argv[i]) == "--greedy-random") greedy_mode = 4;
+        else if (std::string(argv[i]) == "--seed" && i + 1 < argc) seed = strtoull(argv[++i], nullptr, 10);
+        else if (std::string(argv[i]) == "--init-aug3") aug3 = 1;
+        else if (std::string(argv[i]) == "--aug3-passes" && i + 1 < argc) aug3_passes = atoi(argv[++i]);
     }
+    if (aug3_passes < 1) { fprintf(stderr, "Error: --aug3-passes must be a positive count\n"); return 1; }
+    /* --init-aug3 alone runs after the plain greedy start */
+    if (aug3) { aug3 = aug3_passes; if (greedy_mode == 0) greedy_mode = 1; }
 
     FILE* f = fopen(argv[1], "r");
     if (!f) { fprintf(stderr, "Cannot open file: %s\n", argv[1]); return 1; }
@@ -201,6 +326,8 @@ int main(int argc, char* argv[]) {
 
     auto t0 = std::chrono::high_resolution_clock::now();
     HopcroftKarp hk(lc, rc, edges);
+    hk.greedy_seed = seed;
+    hk.aug3_passes = aug3;
     auto matching = hk.maximum_matching(greedy_mode);
     auto t1 = std::chrono::high_resolution_clock::now();
 
@@ -212,6 +339,7 @@ int main(int argc, char* argv[]) {
         int gs = hk.greedy_size;
         int fs = (int)matching.size();
         printf("Greedy init size: %d\n", gs);
+        if (aug3 > 0) printf("Aug3 augmentations: %d\n", hk.aug3_size);
         if (fs > 0) printf("Greedy/Final: %.2f%%\n", 100.0 * gs / fs);
         else printf("Greedy/Final: NA\n");
     }
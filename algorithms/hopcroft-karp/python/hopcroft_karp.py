@@ -12,6 +12,33 @@ NIL = -1
 INF = float('inf')
 
 
+MASK64 = (1 << 64) - 1
+
+
+class SplitMix64:
+    """SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+    stream, so --greedy-random --seed S picks the same matching there."""
+
+    def __init__(self, seed):
+        self.state = seed & MASK64
+
+    def next(self):
+        self.state = (self.state + 0x9E3779B97F4A7C15) & MASK64
+        z = self.state
+        z = ((z ^ (z >> 30)) * 0xBF58476D1CE4E5B9) & MASK64
+        z = ((z ^ (z >> 27)) * 0x94D049BB133111EB) & MASK64
+        return z ^ (z >> 31)
+
+    def below(self, bound):
+        # uniform in [0, bound)
+        return (self.next() * bound) >> 64
+
+    def shuffle(self, items):
+        for i in range(len(items), 1, -1):
+            j = self.below(i)
+            items[i - 1], items[j] = items[j], items[i - 1]
+
+
 class HopcroftKarp:
     def __init__(self, left_count, right_count, edges):
         self.left_count = left_count
@@ -68,11 +95,18 @@ class HopcroftKarp:
         self.dist[u] = INF
         return False
 
-    def maximum_matching(self, greedy_mode=0):
+    def maximum_matching(self, greedy_mode=0, seed=1, aug3=0):
         if greedy_mode == 1:
             self.greedy_size = self._greedy_init()
         elif greedy_mode == 2:
             self.greedy_size = self._greedy_init_md()
+        elif greedy_mode == 3:
+            self.greedy_size = self._greedy_init_ks()
+        elif greedy_mode == 4:
+            self.greedy_size = self._greedy_init_random(seed)
+        if aug3 > 0:
+            self.aug3_size = self._augment3(aug3)
+            self.greedy_size += self.aug3_size
 
         while self.bfs():
             for u in range(self.left_count):
@@ -87,6 +121,7 @@ class HopcroftKarp:
         return matching
 
     greedy_size = 0
+    aug3_size = 0
 
     def _greedy_init(self):
         cnt = 0
@@ -128,6 +163,110 @@ class HopcroftKarp:
                 cnt += 1
         return cnt
 
+    def _greedy_init_ks(self):
+        # Karp-Sipser over both sides (right vertex v is lc + v here): match
+        # degree-1 exposed vertices first; otherwise the lowest-id exposed
+        # left vertex with an exposed neighbor
+        cnt = 0
+        lc = self.left_count
+        total = lc + self.right_count
+        adj = [[] for _ in range(total)]
+        for u in range(lc):
+            for v in self.graph[u]:
+                adj[u].append(lc + v)
+                adj[lc + v].append(u)
+        matched = [self.pair_left[x] != NIL if x < lc else self.pair_right[x - lc] != NIL
+                   for x in range(total)]
+        deg = [0] * total
+        leaves = []
+        for x in range(total):
+            if matched[x]:
+                continue
+            deg[x] = sum(1 for y in adj[x] if not matched[y])
+            if deg[x] == 1:
+                leaves.append(x)
+        nxt = 0
+        while True:
+            if leaves:
+                x = leaves.pop()
+                if matched[x] or deg[x] != 1:
+                    continue
+            else:
+                while nxt < lc and (matched[nxt] or deg[nxt] == 0):
+                    nxt += 1
+                if nxt == lc:
+                    break
+                x = nxt
+            y = next(w for w in adj[x] if not matched[w])
+            u, v = (x, y - lc) if x < lc else (y, x - lc)
+            self.pair_left[u] = v
+            self.pair_right[v] = u
+            cnt += 1
+            matched[x] = matched[y] = True
+            for z in (x, y):
+                for w in adj[z]:
+                    if not matched[w]:
+                        deg[w] -= 1
+                        if deg[w] == 1:
+                            leaves.append(w)
+        return cnt
+
+    def _greedy_init_random(self, seed):
+        # Random greedy: left vertices in a seeded random order, each exposed
+        # one matched with a uniformly random unmatched right neighbor
+        rng = SplitMix64(seed)
+        order = list(range(self.left_count))
+        rng.shuffle(order)
+        cnt = 0
+        for u in order:
+            if self.pair_left[u] != NIL:
+                continue
+            free = [v for v in self.graph[u] if self.pair_right[v] == NIL]
+            if not free:
+                continue
+            v = free[rng.below(len(free))]
+            self.pair_left[u] = v
+            self.pair_right[v] = u
+            cnt += 1
+        return cnt
+
+    def _augment3(self, passes):
+        # --init-aug3, after the greedy start: up to `passes` sweeps over the
+        # free left vertices, each taking the first path u - v = w - x from u
+        # (x a free right vertex) or a free right neighbor outright. A pass
+        # without one ends it; nxt[w] skips right neighbors matched for good.
+        pair_left, pair_right, graph = self.pair_left, self.pair_right, self.graph
+        nxt = [0] * self.left_count
+        cnt = 0
+        for _ in range(passes):
+            before = cnt
+            for u in range(self.left_count):
+                if pair_left[u] != NIL:
+                    continue
+                for v in graph[u]:
+                    if pair_right[v] == NIL:
+                        pair_left[u] = v
+                        pair_right[v] = u
+                        cnt += 1
+                        break
+                    w = pair_right[v]
+                    nw = graph[w]
+                    j = nxt[w]
+                    while j < len(nw) and pair_right[nw[j]] != NIL:
+                        j += 1
+                    nxt[w] = j
+                    if j < len(nw):
+                        x = nw[j]
+                        pair_left[u] = v
+                        pair_right[v] = u
+                        pair_left[w] = x
+                        pair_right[x] = w
+                        cnt += 1
+                        break
+            if cnt == before:
+                break
+        return cnt
+
 
 def validate_matching(left_count, right_count, graph, matching):
     left_deg = [0] * left_count
@@ -181,22 +320,43 @@ def main():
     print()
 
     if len(sys.argv) < 2:
-        print(f"Usage: python {sys.argv[0]} <filename> [--greedy|--greedy-md]")
+        print(f"Usage: python {sys.argv[0]} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]")
         sys.exit(1)
 
     greedy_mode = 0
-    for arg in sys.argv[2:]:
+    seed = 1
+    aug3 = 0
+    aug3_passes = 3
+    for i, arg in enumerate(sys.argv[2:], 2):
         if arg == "--greedy":
             greedy_mode = 1
         elif arg == "--greedy-md":
             greedy_mode = 2
+        elif arg == "--greedy-ks":
+            greedy_mode = 3
+        elif arg == "--greedy-random":
+            greedy_mode = 4
+        elif arg == "--seed" and i + 1 < len(sys.argv):
+            seed = int(sys.argv[i + 1])
+        elif arg == "--init-aug3":
+            aug3 = 1
+        elif arg == "--aug3-passes" and i + 1 < len(sys.argv):
+            aug3_passes = int(sys.argv[i + 1])
+    if aug3_passes < 1:
+        print("Error: --aug3-passes must be a positive count", file=sys.stderr)
+        sys.exit(1)
+    # --init-aug3 alone runs after the plain greedy start
+    if aug3:
+        aug3 = aug3_passes
+        if greedy_mode == 0:
+            greedy_mode = 1
 
     left_count, right_count, edges = load_graph(sys.argv[1])
     print(f"Graph: {left_count} left, {right_count} right, {len(edges)} edges")
 
     t0 = time.time()
     hk = HopcroftKarp(left_count, right_count, edges)
-    matching = hk.maximum_matching(greedy_mode)
+    matching = hk.maximum_matching(greedy_mode, seed, aug3)
     t1 = time.time()
 
     validate_matching(left_count, right_count, hk.graph, matching)
@@ -204,6 +364,8 @@ def main():
     print(f"Matching size: {len(matching)}")
     if greedy_mode > 0:
         print(f"Greedy init size: {hk.greedy_size}")
+        if aug3 > 0:
+            print(f"Aug3 augmentations: {hk.aug3_size}")
         if matching:
             print(f"Greedy/Final: {100.0 * hk.greedy_size / len(matching):.2f}%")
         else:
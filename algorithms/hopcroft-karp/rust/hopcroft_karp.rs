@@ -2,27 +2,86 @@
  * Hopcroft-Karp Algorithm - O(EâˆšV) Maximum Bipartite Matching
  *
  * Rust implementation â€” fully deterministic, no hash containers.
+ *
+ * With --threads N the phases run on N threads (see Parallel), after the
+ * parallel Hopcroft-Karp of Azad, Buluc and Pothen: each BFS layer is
+ * split among the threads, and the DFS from the free left vertices runs on
+ * all of them at once, a search claiming each right vertex it enters. The
+ * paths found are disjoint and as many as the sequential phase would find
+ * in their place; which ones depends on the scheduling, so the pairs can
+ * differ between runs, but not their number. --numa pins the threads
+ * across the sockets and splits the graph into one CSR block per node,
+ * each thread taking its own node's vertices first (numa.rs).
+ *
+ * With --general the input is an "n m" graph, split into left and right
+ * by a 2-coloring (Classes); a graph with an odd cycle is refused with
+ * the cycle. The pairs, the trace and the drawings are in its own ids.
  */
 
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread;
 use std::time::Instant;
 
-const NIL: i32 = -1;
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/numa.rs"]
+mod numa;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
 
-struct HopcroftKarp {
+use self::error::SuiteError;
+use self::types::{ix, vx, AtomicVertex, Vertex, NONE, UNREACHED};
+
+pub(crate) struct HopcroftKarp {
     left_count: usize,
     greedy_size: usize,
+    pub(crate) greedy_seed: u64, /* for greedy_mode 4 (--greedy-random) */
+    pub(crate) aug3_passes: usize, /* --init-aug3 passes after the greedy start; 0 = off */
+    aug3_size: usize,
+    pub(crate) numa: Option<Vec<Vec<usize>>>, /* --numa: the CPUs of each node (numa.rs) */
     right_count: usize,
     graph: Vec<Vec<usize>>,
-    pair_left: Vec<i32>,
-    pair_right: Vec<i32>,
-    dist: Vec<i32>,
+    pair_left: Vec<Vertex>,
+    pair_right: Vec<Vertex>,
+    dist: Vec<Vertex>,
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path (trace.rs) */
+    trace_mate: Vec<usize>, /* for the tracer: the mates before the last augmentation */
 }
 
 impl HopcroftKarp {
-    fn new(left_count: usize, right_count: usize, edges: &[(usize, usize)]) -> Self {
+    pub(crate) fn new(left_count: usize, right_count: usize, edges: &[(usize, usize)]) -> Self {
         let mut graph = vec![Vec::new(); left_count];
         for &(u, v) in edges {
             if u < left_count && v < right_count {
@@ -34,11 +93,19 @@ impl HopcroftKarp {
         HopcroftKarp {
             left_count,
             greedy_size: 0,
+            greedy_seed: 1,
+            aug3_passes: 0,
+            aug3_size: 0,
+            numa: None,
             right_count,
             graph,
-            pair_left: vec![NIL; left_count],
-            pair_right: vec![NIL; right_count],
+            pair_left: vec![NONE; left_count],
+            pair_right: vec![NONE; right_count],
             dist: vec![0; left_count + 1],
+            stop: None,
+            progress: None,
+            trace: None,
+            trace_mate: Vec::new(),
         }
     }
 
@@ -47,70 +114,69 @@ impl HopcroftKarp {
         let mut qi = 0;
 
         for u in 0..self.left_count {
-            if self.pair_left[u] == NIL {
+            if self.pair_left[u] == NONE {
                 self.dist[u] = 0;
                 queue.push(u);
             } else {
-                self.dist[u] = i32::MAX;
+                self.dist[u] = UNREACHED;
             }
         }
-        self.dist[self.left_count] = i32::MAX;
+        self.dist[self.left_count] = UNREACHED;
 
         while qi < queue.len() {
             let u = queue[qi];
             qi += 1;
             if self.dist[u] < self.dist[self.left_count] {
                 for &v in &self.graph[u] {
-                    let paired = if self.pair_right[v] == NIL {
+                    let paired = if self.pair_right[v] == NONE {
                         self.left_count
                     } else {
-                        self.pair_right[v] as usize
+                        ix(self.pair_right[v])
                     };
-                    if self.dist[paired] == i32::MAX {
+                    if self.dist[paired] == UNREACHED {
                         self.dist[paired] = self.dist[u] + 1;
-                        if self.pair_right[v] != NIL {
-                            queue.push(self.pair_right[v] as usize);
+                        if self.pair_right[v] != NONE {
+                            queue.push(ix(self.pair_right[v]));
                         }
                     }
                 }
             }
         }
-        self.dist[self.left_count] != i32::MAX
+        self.dist[self.left_count] != UNREACHED
     }
 
-    fn dfs(&mut self, u_opt: i32) -> bool {
-        if u_opt == NIL { return true; }
-        let u = u_opt as usize;
+    fn dfs(&mut self, u_opt: Vertex) -> bool {
+        if u_opt == NONE { return true; }
+        let u = ix(u_opt);
 
         let neighbors = self.graph[u].clone();
         for &v in &neighbors {
-            let paired = if self.pair_right[v] == NIL {
+            let paired = if self.pair_right[v] == NONE {
                 self.left_count
             } else {
-                self.pair_right[v] as usize
+                ix(self.pair_right[v])
             };
             if self.dist[paired] == self.dist[u] + 1 {
                 if self.dfs(self.pair_right[v]) {
-                    self.pair_right[v] = u as i32;
-                    self.pair_left[u] = v as i32;
+                    self.pair_right[v] = vx(u);
+                    self.pair_left[u] = vx(v);
                     return true;
                 }
             }
         }
-        self.dist[u] = i32::MAX;
+        self.dist[u] = UNREACHED;
         false
     }
 
-
     fn greedy_init(&mut self) -> usize {
         let mut cnt: usize = 0;
         for u in 0..self.left_count {
-            if self.pair_left[u] != NIL { continue; }
+            if self.pair_left[u] != NONE { continue; }
             let neighbors: Vec<usize> = self.graph[u].clone();
             for &v in &neighbors {
-                if self.pair_right[v] == NIL {
-                    self.pair_left[u] = v as i32;
-                    self.pair_right[v] = u as i32;
+                if self.pair_right[v] == NONE {
+                    self.pair_left[u] = vx(v);
+                    self.pair_right[v] = vx(u);
                     cnt += 1;
                     break;
                 }
@@ -131,107 +197,696 @@ impl HopcroftKarp {
         let mut order: Vec<usize> = (0..self.left_count).collect();
         order.sort_unstable_by(|&a, &b| self.graph[a].len().cmp(&self.graph[b].len()).then(a.cmp(&b)));
         for u in order {
-            if self.pair_left[u] != NIL { continue; }
-            let mut best: i32 = -1;
+            if self.pair_left[u] != NONE { continue; }
+            let mut best = NONE;
             let mut best_deg = usize::MAX;
             let neighbors: Vec<usize> = self.graph[u].clone();
             for &v in &neighbors {
-                if self.pair_right[v] == NIL && deg[v] < best_deg {
-                    best = v as i32;
+                if self.pair_right[v] == NONE && deg[v] < best_deg {
+                    best = vx(v);
                     best_deg = deg[v];
                 }
             }
-            if best >= 0 {
+            if best != NONE {
                 self.pair_left[u] = best;
-                self.pair_right[best as usize] = u as i32;
+                self.pair_right[ix(best)] = vx(u);
                 cnt += 1;
             }
         }
         cnt
     }
 
-    fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
+    /* Karp-Sipser over both sides (right vertex v is left_count + v here):
+     * while an exposed vertex has a single unmatched neighbor, match the
+     * two; otherwise match the lowest-id exposed left vertex that has one
+     * with its first unmatched neighbor. deg counts unmatched neighbors. */
+    fn greedy_init_ks(&mut self) -> usize {
+        let l = self.left_count;
+        let total = l + self.right_count;
+        let mut adj: Vec<Vec<usize>> = vec![Vec::new(); total];
+        for u in 0..l {
+            for &v in &self.graph[u] {
+                adj[u].push(l + v);
+                adj[l + v].push(u);
+            }
+        }
+        let mut matched: Vec<bool> = (0..total)
+            .map(|x| if x < l { self.pair_left[x] != NONE } else { self.pair_right[x - l] != NONE })
+            .collect();
+        let mut deg: Vec<usize> = (0..total)
+            .map(|x| if matched[x] { 0 } else { adj[x].iter().filter(|&&y| !matched[y]).count() })
+            .collect();
+        let mut leaves: Vec<usize> = (0..total).filter(|&x| deg[x] == 1).collect();
+        let mut next = 0;
+        let mut cnt: usize = 0;
+        loop {
+            let x = match leaves.pop() {
+                Some(x) if matched[x] || deg[x] != 1 => continue,
+                Some(x) => x,
+                None => {
+                    while next < l && (matched[next] || deg[next] == 0) { next += 1; }
+                    if next == l { break; }
+                    next
+                }
+            };
+            let y = *adj[x].iter().find(|&&y| !matched[y]).unwrap();
+            let (u, v) = if x < l { (x, y - l) } else { (y, x - l) };
+            self.pair_left[u] = vx(v);
+            self.pair_right[v] = vx(u);
+            cnt += 1;
+            for &z in &[x, y] {
+                matched[z] = true;
+            }
+            for &z in &[x, y] {
+                for &w in &adj[z] {
+                    if matched[w] { continue; }
+                    deg[w] -= 1;
+                    if deg[w] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: left vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched right neighbor.
+     * The same seed gives the same matching. */
+    fn greedy_init_random(&mut self, seed: u64) -> usize {
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<usize> = (0..self.left_count).collect();
+        rng.shuffle(&mut order);
+        let mut cnt = 0;
+        for u in order {
+            if self.pair_left[u] != NONE { continue; }
+            let free = self.graph[u].iter().filter(|&&v| self.pair_right[v] == NONE).count();
+            if free == 0 { continue; }
+            let k = rng.below(free as u64) as usize;
+            let v = *self.graph[u].iter().filter(|&&v| self.pair_right[v] == NONE).nth(k).unwrap();
+            self.pair_left[u] = vx(v);
+            self.pair_right[v] = vx(u);
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+     * free left vertices, each taking the first path u - v = w - x from u
+     * (x a free right vertex) or a free right neighbor outright. A pass
+     * without one ends it; next[w] skips right neighbors matched for good. */
+    fn augment3(&mut self, passes: usize) -> usize {
+        let mut next = vec![0usize; self.left_count];
+        let mut cnt = 0;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..self.left_count {
+                if self.pair_left[u] != NONE { continue; }
+                for k in 0..self.graph[u].len() {
+                    let v = self.graph[u][k];
+                    if self.pair_right[v] == NONE {
+                        self.pair_left[u] = vx(v);
+                        self.pair_right[v] = vx(u);
+                        cnt += 1;
+                        break;
+                    }
+                    let w = ix(self.pair_right[v]);
+                    let nw = &self.graph[w];
+                    while next[w] < nw.len() && self.pair_right[nw[next[w]]] != NONE { next[w] += 1; }
+                    if next[w] < nw.len() {
+                        let x = nw[next[w]];
+                        self.pair_left[u] = vx(v);
+                        self.pair_right[v] = vx(u);
+                        self.pair_left[w] = vx(x);
+                        self.pair_right[x] = vx(w);
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
+    /* Start from a known matching, e.g. one of a subgraph or the
+     * --init-matching file: maximum_matching(0) then only augments it, and
+     * its vertices stay matched. Pairs that are not edges, or reuse a
+     * vertex, are skipped. */
+    pub(crate) fn seed(&mut self, matching: &[(usize, usize)]) {
+        for &(u, v) in matching {
+            if u < self.left_count && self.graph[u].binary_search(&v).is_ok()
+                && self.pair_left[u] == NONE && self.pair_right[v] == NONE {
+                self.pair_left[u] = vx(v);
+                self.pair_right[v] = vx(u);
+            }
+        }
+    }
+
+    /* Size of the initial matching of the last maximum_matching(greedy_mode). */
+    pub(crate) fn greedy_size(&self) -> usize {
+        self.greedy_size
+    }
+
+    /* Pairs the --init-aug3 passes added, counted in greedy_size(). */
+    pub(crate) fn aug3_size(&self) -> usize {
+        self.aug3_size
+    }
+
+    /* Per-vertex queries on the current matching, as on matchers::Solution,
+     * with one id space: left vertex u is u, right vertex v is left + v. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        if v < self.left_count {
+            types::some(self.pair_left[v]).map(|w| self.left_count + w)
+        } else {
+            self.pair_right.get(v - self.left_count).and_then(|&u| types::some(u))
+        }
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.left_count + self.right_count).filter(move |&v| !self.is_matched(v))
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.pair_left.iter().filter(|&&v| v != NONE).count()
+    }
+
+    /* u is on the path just flipped (combined ids, as mate()). */
+    fn trace_augment(&mut self, u: usize) {
+        if let Some(t) = self.trace {
+            let mut before = std::mem::take(&mut self.trace_mate);
+            trace::augmented(t, u, &|v| self.mate(v), &mut before);
+            self.trace_mate = before;
+        }
+    }
+
+    pub(crate) fn maximum_matching(&mut self, greedy_mode: i32) -> Vec<(usize, usize)> {
+        self.maximum_matching_parallel(greedy_mode, 1)
+    }
+
+    /* With threads > 1 the phases run on that many threads; see Parallel.
+     * The tracer follows single augmentations, so it keeps them on one. */
+    pub(crate) fn maximum_matching_parallel(&mut self, greedy_mode: i32, threads: usize) -> Vec<(usize, usize)> {
         self.greedy_size = match greedy_mode {
             1 => self.greedy_init(),
             2 => self.greedy_init_md(),
+            3 => self.greedy_init_ks(),
+            4 => self.greedy_init_random(self.greedy_seed),
             _ => 0,
         };
-        while self.bfs() {
-            for u in 0..self.left_count {
-                if self.pair_left[u] == NIL {
-                    self.dfs(u as i32);
+        if self.aug3_passes > 0 {
+            self.aug3_size = self.augment3(self.aug3_passes);
+            self.greedy_size += self.aug3_size;
+        }
+        /* for the progress reports: pairs before the phases, and found since */
+        let start = if self.progress.is_some() { self.matching_size() } else { 0 };
+        if let Some(t) = self.trace {
+            self.trace_mate = trace::begin(t, self.left_count + self.right_count, &|v| self.mate(v));
+        }
+        let (mut phase, mut found) = (0, 0);
+        /* the threads share the graph, moved out of self while they run */
+        let graph = if threads > 1 && self.trace.is_none() { std::mem::take(&mut self.graph) } else { Vec::new() };
+        let parallel = if graph.is_empty() { None } else { Some(Parallel::new(self, &graph, threads)) };
+        while !self.stop.is_some_and(|s| s.load(Ordering::Relaxed)) {
+            if let Some(ref p) = parallel {
+                match p.layers() {
+                    Some(nil) => found += p.augment_all(nil),
+                    None => break,
+                }
+            } else {
+                if !self.bfs() { break; }
+                for u in 0..self.left_count {
+                    if self.pair_left[u] == NONE && self.dfs(vx(u)) {
+                        found += 1;
+                        self.trace_augment(u);
+                    }
                 }
             }
+            phase += 1;
+            if let Some(report) = self.progress {
+                report(phase, found, self.left_count + self.right_count - 2 * (start + found));
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[phase]);
+            }
+        }
+        if let Some(p) = parallel {
+            self.pair_left = p.pair_left.into_iter().map(AtomicVertex::into_inner).collect();
+            self.pair_right = p.pair_right.into_iter().map(AtomicVertex::into_inner).collect();
+            self.graph = graph;
         }
 
         let mut matching = Vec::new();
         for u in 0..self.left_count {
-            if self.pair_left[u] != NIL {
-                matching.push((u, self.pair_left[u] as usize));
+            if self.pair_left[u] != NONE {
+                matching.push((u, ix(self.pair_left[u])));
             }
         }
         matching.sort_unstable();
         matching
     }
+
+    /* Among the maximum matchings, one of least total weight (`heavier`:
+     * greatest), replacing the current matching; returns it and its weight.
+     * Parallel edges count with their best weight. This is a min-cost pass
+     * after the cardinality phases, shaped like them: each phase is a
+     * Dijkstra from all free left vertices on the reduced costs
+     * c(u, v) - c_min + pot[u] - pot[v] >= 0, which moves the potentials so
+     * that the cheapest augmenting paths become tight (reduced cost 0), then
+     * augments along a maximal set of disjoint tight paths. Matched edges
+     * stay tight, and after each phase the matching is a cheapest one of its
+     * size. It stops when no free right vertex is reachable, at the maximum
+     * size, which must be Hopcroft-Karp's. Subtracting c_min changes every
+     * size-k matching by the same amount, and keeps the free vertices of
+     * each side at one potential. A search stops at the nearest free right
+     * vertex and resets only what it touched; O(E log V) per phase. */
+    pub(crate) fn tie_break(&mut self, weighted: &[(usize, usize, i64)], heavier: bool) -> (Vec<(usize, usize)>, i64) {
+        let (left, right) = (self.left_count, self.right_count);
+        let mut cost: Vec<Vec<i64>> = self.graph.iter().map(|a| vec![INF; a.len()]).collect();
+        for &(u, v, w) in weighted {
+            if u >= left || v >= right { continue; }
+            if let Ok(k) = self.graph[u].binary_search(&v) {
+                cost[u][k] = cost[u][k].min(if heavier { -w } else { w });
+            }
+        }
+        let c_min = cost.iter().flat_map(|c| c.iter()).cloned().min().unwrap_or(0);
+
+        let mut p = MinCostPass {
+            graph: &self.graph,
+            cost: &cost,
+            c_min,
+            pot_left: vec![0; left],
+            pot_right: vec![0; right],
+            mate_left: vec![FREE; left],
+            mate_right: vec![FREE; right],
+            dist_left: vec![INF; left],
+            dist_right: vec![INF; right],
+            seen_left: Vec::new(),
+            seen_right: Vec::new(),
+            heap: BinaryHeap::new(),
+            best: INF,
+        };
+        let mut free: Vec<usize> = (0..left).filter(|&u| !self.graph[u].is_empty()).collect();
+        while p.search(&free) {
+            p.augment(&free);
+            let mate_left = &p.mate_left;
+            free.retain(|&u| mate_left[u] == FREE);
+        }
+
+        let mut matching = Vec::new();
+        let mut weight = 0i64;
+        for v in 0..right {
+            self.pair_right[v] = if p.mate_right[v] == FREE { NONE } else { vx(p.mate_right[v]) };
+        }
+        for u in 0..left {
+            let v = p.mate_left[u];
+            self.pair_left[u] = if v == FREE { NONE } else { vx(v) };
+            if v == FREE { continue; }
+            let c = cost[u][self.graph[u].binary_search(&v).unwrap()];
+            weight += if heavier { -c } else { c };
+            matching.push((u, v));
+        }
+        (matching, weight)
+    }
 }
 
-fn validate_matching(
-    left_count: usize, right_count: usize,
-    graph: &[Vec<usize>], matching: &[(usize, usize)],
-) {
-    let mut left_deg = vec![0i32; left_count];
-    let mut right_deg = vec![0i32; right_count];
-    let mut errors = 0;
+const INF: i64 = i64::MAX / 4;
+const FREE: usize = usize::MAX;
+
+/* State of HopcroftKarp::tie_break between searches. */
+struct MinCostPass<'a> {
+    graph: &'a [Vec<usize>],
+    cost: &'a [Vec<i64>],
+    c_min: i64,
+    pot_left: Vec<i64>,
+    pot_right: Vec<i64>,
+    mate_left: Vec<usize>,
+    mate_right: Vec<usize>,
+    dist_left: Vec<i64>,
+    dist_right: Vec<i64>,
+    seen_left: Vec<usize>,
+    seen_right: Vec<usize>,
+    heap: BinaryHeap<Reverse<(i64, usize)>>,
+    best: i64, /* reduced distance of the nearest free right vertex */
+}
+
+impl<'a> MinCostPass<'a> {
+    fn relax(&mut self, u: usize, d: i64) {
+        for (k, &v) in self.graph[u].iter().enumerate() {
+            if self.mate_left[u] == v { continue; }
+            let nd = d + self.reduced(u, k);
+            if nd >= self.dist_right[v] { continue; }
+            if self.dist_right[v] == INF { self.seen_right.push(v); }
+            self.dist_right[v] = nd;
+            let w = self.mate_right[v];
+            if w == FREE {
+                self.best = self.best.min(nd);
+            } else {
+                /* the matched edge back to w is tight */
+                if self.dist_left[w] == INF { self.seen_left.push(w); }
+                self.dist_left[w] = nd;
+                self.heap.push(Reverse((nd, w)));
+            }
+        }
+    }
+
+    /* Dijkstra from the free left vertices (all at distance 0) to the
+     * nearest free right vertex; false if none is reachable. Potentials
+     * then move by min(dist, best) - best, so unreached vertices keep
+     * theirs. */
+    fn search(&mut self, free: &[usize]) -> bool {
+        self.best = INF;
+        for &u in free {
+            if self.best == 0 { break; }
+            self.dist_left[u] = 0;
+            self.seen_left.push(u);
+            self.relax(u, 0);
+        }
+        while let Some(Reverse((d, u))) = self.heap.pop() {
+            if d >= self.best { break; }
+            if d > self.dist_left[u] { continue; }
+            self.relax(u, d);
+        }
+        self.heap.clear();
+        let best = self.best;
+        for &u in &self.seen_left {
+            if self.dist_left[u] < best { self.pot_left[u] -= best - self.dist_left[u]; }
+            self.dist_left[u] = INF;
+        }
+        for &v in &self.seen_right {
+            if self.dist_right[v] < best { self.pot_right[v] -= best - self.dist_right[v]; }
+            self.dist_right[v] = INF;
+        }
+        self.seen_left.clear();
+        self.seen_right.clear();
+        best < INF
+    }
+
+    fn reduced(&self, u: usize, k: usize) -> i64 {
+        self.cost[u][k] - self.c_min + self.pot_left[u] - self.pot_right[self.graph[u][k]]
+    }
+
+    /* After a search every shortest augmenting path is tight. Augment
+     * along a maximal set of vertex-disjoint tight paths, found by
+     * depth-first search from the free left vertices as in the cardinality
+     * phases (iteratively: paths can be long). */
+    fn augment(&mut self, free: &[usize]) {
+        let mut seen_left = vec![false; self.pot_left.len()];
+        let mut seen_right = vec![false; self.pot_right.len()];
+        let mut next = vec![0usize; self.pot_left.len()];
+        let mut stack: Vec<(usize, usize)> = Vec::new(); /* (left, right it was entered by) */
+        for &root in free {
+            stack.push((root, FREE));
+            seen_left[root] = true;
+            while let Some(&(u, _)) = stack.last() {
+                let k = next[u];
+                if k == self.graph[u].len() {
+                    stack.pop();
+                    continue;
+                }
+                next[u] += 1;
+                let v = self.graph[u][k];
+                if seen_right[v] || self.mate_left[u] == v || self.reduced(u, k) != 0 { continue; }
+                seen_right[v] = true;
+                let w = self.mate_right[v];
+                if w == FREE {
+                    let mut v = v;
+                    while let Some((u, by)) = stack.pop() {
+                        self.mate_left[u] = v;
+                        self.mate_right[v] = u;
+                        v = by;
+                    }
+                } else if !seen_left[w] {
+                    seen_left[w] = true;
+                    stack.push((w, v));
+                }
+            }
+        }
+    }
+}
+
+/* Roots or frontier vertices a thread takes at a time (OpenMP's dynamic
+ * schedule); a list no longer than this stays on the calling thread. */
+const CHUNK: usize = 256;
+
+/* Runs `f` over `items` a chunk at a time on up to `threads` threads,
+ * each taking the next chunk when done, and gathers what they push. */
+fn in_chunks<T: Sync, R: Send>(threads: usize, items: &[T], f: &(dyn Fn(&[T], &mut Vec<R>) + Sync)) -> Vec<R> {
+    if items.len() <= CHUNK {
+        let mut out = Vec::new();
+        f(items, &mut out);
+        return out;
+    }
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut out = Vec::new();
+        loop {
+            let i = next.fetch_add(CHUNK, Ordering::Relaxed);
+            if i >= items.len() { break; }
+            f(&items[i..(i + CHUNK).min(items.len())], &mut out);
+        }
+        out
+    };
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..threads.min(items.len() / CHUNK + 1)).map(|_| s.spawn(work)).collect();
+        handles.into_iter().flat_map(|h| h.join().unwrap()).collect()
+    })
+}
+
+/* The phases on several threads. The mates and BFS layers are atomics,
+ * loaded and stored relaxed: the threads of one step meet only at the
+ * join that ends it, and over the right vertices, which `claimed` hands to
+ * one search each per phase. A search reads a right vertex's mate to test
+ * the layer, then claims it; only the claimant flips the pair, and the
+ * mate is reached only through it, so the left vertices of a search are
+ * its own too. A right vertex claimed by a search that failed leads
+ * nowhere (its mate is dead), as when the sequential DFS backtracks over
+ * it, so a path the phase could still add runs into no claimed vertex and
+ * would have been found: the paths are a maximal disjoint set. With
+ * --numa the adjacency is read from `numa`'s per-node blocks instead, and
+ * each list is split by node before the threads take it. */
+struct Parallel<'a> {
+    graph: &'a [Vec<usize>],
+    numa: Option<(numa::Topology, numa::Csr)>,
+    threads: usize,
+    pair_left: Vec<AtomicVertex>,
+    pair_right: Vec<AtomicVertex>,
+    dist: Vec<AtomicVertex>,  /* BFS layer of each left vertex, UNREACHED once dead */
+    claimed: Vec<AtomicBool>, /* right vertices a search of this phase entered */
+}
+
+impl<'a> Parallel<'a> {
+    fn new(hk: &HopcroftKarp, graph: &'a [Vec<usize>], threads: usize) -> Self {
+        let numa = hk.numa.clone().map(|nodes| {
+            let topo = numa::Topology { nodes };
+            let csr = numa::Csr::build(&topo, graph, topo.used_nodes(threads));
+            (topo, csr)
+        });
+        Parallel {
+            graph,
+            numa,
+            threads,
+            pair_left: hk.pair_left.iter().map(|&v| AtomicVertex::new(v)).collect(),
+            pair_right: hk.pair_right.iter().map(|&u| AtomicVertex::new(u)).collect(),
+            dist: (0..hk.left_count).map(|_| AtomicVertex::new(UNREACHED)).collect(),
+            claimed: (0..hk.right_count).map(|_| AtomicBool::new(false)).collect(),
+        }
+    }
+
+    fn neighbors(&self, u: usize) -> &[usize] {
+        match self.numa {
+            Some((_, ref csr)) => csr.neighbors(u),
+            None => &self.graph[u],
+        }
+    }
 
-    for &(u, v) in matching {
-        if !graph[u].binary_search(&v).is_ok() {
-            eprintln!("ERROR: Edge ({}, {}) not in graph!", u, v);
-            errors += 1;
+    /* in_chunks, or numa::chunks_on over the items split by node. */
+    fn in_chunks<R: Send>(&self, items: &[usize], f: &(dyn Fn(&[usize], &mut Vec<R>) + Sync)) -> Vec<R> {
+        match self.numa {
+            Some((ref topo, ref csr)) if items.len() > CHUNK => numa::chunks_on(topo, self.threads, CHUNK, &csr.split(items), f),
+            _ => in_chunks(self.threads, items, f),
         }
-        left_deg[u] += 1;
-        right_deg[v] += 1;
     }
-    for i in 0..left_count {
-        if left_deg[i] > 1 { eprintln!("ERROR: Left vertex {} in {} edges!", i, left_deg[i]); errors += 1; }
+
+    fn free_left(&self) -> Vec<usize> {
+        (0..self.pair_left.len()).filter(|&u| self.pair_left[u].load(Ordering::Relaxed) == NONE).collect()
     }
-    for i in 0..right_count {
-        if right_deg[i] > 1 { eprintln!("ERROR: Right vertex {} in {} edges!", i, right_deg[i]); errors += 1; }
+
+    /* Level-synchronous BFS from the free left vertices, each layer split
+     * among the threads; a left vertex joins the next layer by swapping
+     * its distance out of UNREACHED. Returns the layer after the one that
+     * first meets a free right vertex, or None if none does. */
+    fn layers(&self) -> Option<Vertex> {
+        for d in &self.dist { d.store(UNREACHED, Ordering::Relaxed); }
+        let mut frontier = self.free_left();
+        for &u in &frontier { self.dist[u].store(0, Ordering::Relaxed); }
+        let mut level: Vertex = 0;
+        let free_right = AtomicBool::new(false);
+        while !frontier.is_empty() {
+            frontier = self.in_chunks(&frontier, &|chunk, next| {
+                for &u in chunk {
+                    for &v in self.neighbors(u) {
+                        let w = self.pair_right[v].load(Ordering::Relaxed);
+                        if w == NONE {
+                            free_right.store(true, Ordering::Relaxed);
+                        } else if self.dist[ix(w)]
+                            .compare_exchange(UNREACHED, level + 1, Ordering::Relaxed, Ordering::Relaxed).is_ok() {
+                            next.push(ix(w));
+                        }
+                    }
+                }
+            });
+            level += 1;
+            if free_right.load(Ordering::Relaxed) { return Some(level); }
+        }
+        None
     }
-    let matched_left = left_deg.iter().filter(|&&d| d > 0).count();
-    let matched_right = right_deg.iter().filter(|&&d| d > 0).count();
 
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {} left, {} right", matched_left, matched_right);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
+    /* Iterative DFS from the free left vertex `root` along the layers,
+     * claiming each right vertex it enters; flips the path it finds. */
+    fn augment(&self, root: usize, nil: Vertex, stack: &mut Vec<(usize, usize)>, path: &mut Vec<usize>) -> bool {
+        stack.clear();
+        path.clear();
+        stack.push((root, 0));
+        while let Some(&(u, k)) = stack.last() {
+            let adj = self.neighbors(u);
+            if k == adj.len() {
+                self.dist[u].store(UNREACHED, Ordering::Relaxed);
+                stack.pop();
+                path.pop();
+                continue;
+            }
+            stack.last_mut().unwrap().1 += 1;
+            let v = adj[k];
+            let next = self.dist[u].load(Ordering::Relaxed) + 1;
+            let w = self.pair_right[v].load(Ordering::Relaxed);
+            let fits = if w == NONE { next == nil } else { self.dist[ix(w)].load(Ordering::Relaxed) == next };
+            if !fits || self.claimed[v].swap(true, Ordering::Relaxed) { continue; }
+            path.push(v);
+            if w == NONE {
+                for (&(u, _), &v) in stack.iter().zip(path.iter()) {
+                    self.pair_left[u].store(vx(v), Ordering::Relaxed);
+                    self.pair_right[v].store(vx(u), Ordering::Relaxed);
+                }
+                return true;
+            }
+            stack.push((ix(w), 0));
+        }
+        false
+    }
+
+    /* One phase's DFS from every free left vertex, on the threads; the
+     * number of paths flipped. */
+    fn augment_all(&self, nil: Vertex) -> usize {
+        for c in &self.claimed { c.store(false, Ordering::Relaxed); }
+        let roots = self.free_left();
+        self.in_chunks(&roots, &|chunk, found| {
+            let (mut stack, mut path) = (Vec::new(), Vec::new());
+            for &u in chunk {
+                if self.augment(u, nil, &mut stack, &mut path) { found.push(u); }
+            }
+        }).len()
+    }
 }
 
-fn load_graph(filename: &str) -> Result<(usize, usize, Vec<(usize, usize)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
+/* --general: an "n m" graph read as a bipartite one, its vertices split
+ * into the classes of graph_io::bipartition (left the first), each class
+ * numbered in graph order, as matchers does for "auto". */
+struct Classes {
+    n: usize,
+    edges: Vec<(usize, usize)>, /* in the graph's ids */
+    side: Vec<bool>,            /* true for a right vertex */
+    local: Vec<usize>,          /* each vertex's id in its class */
+    ids: Vec<usize>,            /* graph id of each Hopcroft-Karp id, right vertex v as left + v */
+    left: usize,
+}
 
-    let first = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first.split_whitespace().collect();
-    if parts.len() != 3 {
-        return Err("First line must have 3 numbers".into());
+impl Classes {
+    fn new(n: usize, edges: Vec<(usize, usize)>, names: &graph_io::InputNames) -> Result<Classes, SuiteError> {
+        let side = graph_io::bipartition(n, &edges, names)?;
+        let mut local = vec![0usize; n];
+        let mut ids: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+        for v in 0..n {
+            let k = side[v] as usize;
+            local[v] = ids[k].len();
+            ids[k].push(v);
+        }
+        let left = ids[0].len();
+        let [mut ids, right] = ids;
+        ids.extend(right);
+        Ok(Classes { n, edges, side, local, ids, left })
+    }
+
+    /* A graph edge as a (left, right) pair. */
+    fn oriented(&self, u: usize, v: usize) -> (usize, usize) {
+        if self.side[u] { (self.local[v], self.local[u]) } else { (self.local[u], self.local[v]) }
     }
-    let left_count: usize = parts[0].parse()?;
-    let right_count: usize = parts[1].parse()?;
-    let m: usize = parts[2].parse()?;
 
-    let mut edges = Vec::with_capacity(m);
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: usize = parts[0].parse()?;
-            let v: usize = parts[1].parse()?;
-            edges.push((u, v));
+    /* (left, right) pairs as sorted graph pairs, u < v. */
+    fn graph_pairs(&self, pairs: &[(usize, usize)]) -> Vec<(usize, usize)> {
+        let mut mapped: Vec<(usize, usize)> = pairs.iter()
+            .map(|&(a, b)| {
+                let (u, v) = (self.ids[a], self.ids[self.left + b]);
+                (u.min(v), u.max(v))
+            })
+            .collect();
+        mapped.sort_unstable();
+        mapped
+    }
+}
+
+/* The graph file as (left, right, edges), with its Classes under
+ * --general and the names of its load; the weights are 0 unless
+ * `weighted`. */
+type Loaded = (usize, usize, Vec<(usize, usize, i64)>, Option<Classes>, graph_io::InputNames);
+
+fn load(filename: &str, opts: &graph_io::LoadOptions, general: bool, weighted: bool) -> Result<Loaded, SuiteError> {
+    if !general {
+        return if weighted {
+            graph_io::load_weighted_bipartite_graph_named(filename, opts)
+                .map(|(l, r, edges, names)| (l, r, edges, None, names))
+        } else {
+            graph_io::load_bipartite_graph_named(filename, opts)
+                .map(|(l, r, edges, names)| (l, r, edges.into_iter().map(|(u, v)| (u, v, 0)).collect(), None, names))
+        };
+    }
+    let (n, edges, names) = if weighted {
+        graph_io::load_weighted_graph_named(filename, opts)?
+    } else {
+        let (n, edges, names) = graph_io::load_graph_named(filename, opts)?;
+        (n, edges.into_iter().map(|(u, v)| (u, v, 0)).collect(), names)
+    };
+    let classes = Classes::new(n, edges.iter().map(|&(u, v, _)| (u, v)).collect(), &names)?;
+    let oriented = edges.iter()
+        .filter(|&&(u, v, _)| u < n && v < n && u != v)
+        .map(|&(u, v, w)| {
+            let (a, b) = classes.oriented(u, v);
+            (a, b, w)
+        })
+        .collect();
+    Ok((classes.left, n - classes.left, oriented, Some(classes), names))
+}
+
+/* Under --general, the tracer (--trace or --log-level) with the ids
+ * mapped back to the graph's; `phase` carries a count, not a vertex. */
+static CLASS_IDS: OnceLock<Vec<usize>> = OnceLock::new();
+static CLASS_TRACER: OnceLock<types::TraceSink> = OnceLock::new();
+
+fn class_trace(event: &str, vertices: &[usize]) {
+    if let (Some(t), Some(ids)) = (CLASS_TRACER.get(), CLASS_IDS.get()) {
+        if event == "phase" {
+            t(event, vertices);
+        } else {
+            let mapped: Vec<usize> = vertices.iter().map(|&v| ids[v]).collect();
+            t(event, &mapped);
         }
     }
-    Ok((left_count, right_count, edges))
 }
 
 fn main() {
@@ -240,35 +895,280 @@ fn main() {
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--general] [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} [--threads N {}] [--tie-break lighter|heavier] {} {} {} {} {} {} {} {} {}",
+                  args[0], args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, numa::NUMA_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+
+    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-random") {
+        4
+    } else if args.iter().any(|a| a == "--greedy-ks") {
+        3
+    } else if args.iter().any(|a| a == "--greedy-md") {
+        2
+    } else if args.iter().any(|a| a == "--greedy") {
+        1
+    } else {
+        0
+    };
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    let greedy_mode = if greedy_mode == 0 && aug3 > 0 { 1 } else { greedy_mode };
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --tie-break reads "u v w" edge lines and picks among the maximum
+     * matchings by total weight. */
+    let heavier = match args::value_of(&args, "--tie-break") {
+        None => None,
+        Some("lighter") => Some(false),
+        Some("heavier") => Some(true),
+        Some(other) => {
+            eprintln!("Error: invalid --tie-break value '{}' (expected lighter or heavier)", other);
+            std::process::exit(1);
+        }
+    };
+    /* A stopped search is not maximum, and the tie-break pass starts
+     * from a maximum matching. */
+    if heavier.is_some() && time_limit.is_some() {
+        eprintln!("Error: --tie-break and --time-limit cannot be combined");
+        std::process::exit(1);
+    }
+    /* The tie-break pass swaps pairs outside the traced search. */
+    if heavier.is_some() && args::value_of(&args, "--trace").is_some() {
+        eprintln!("Error: --tie-break and --trace cannot be combined");
+        std::process::exit(1);
+    }
+    /* --threads N: run the phases on N threads; 0 means one per available
+     * core. The trace logs one augmentation at a time, on one thread. */
+    let threads: usize = match args::value_of(&args, "--threads").map(|s| s.parse::<usize>()) {
+        None => 1,
+        Some(Ok(0)) => thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        Some(Ok(t)) => t,
+        Some(Err(_)) => {
+            eprintln!("Error: --threads expects a number");
+            std::process::exit(1);
+        }
+    };
+    if threads > 1 && args::value_of(&args, "--trace").is_some() {
+        eprintln!("Error: --threads and --trace cannot be combined");
         std::process::exit(1);
     }
+    let numa = args::has_flag(&args, "--numa");
+    if numa && threads < 2 {
+        eprintln!("Error: --numa places the threads of --threads N (N > 1)");
+        std::process::exit(1);
+    }
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-md") { 2 } else if args.iter().any(|a| a == "--greedy") { 1 } else { 0 };
-    match load_graph(&args[1]) {
-        Ok((left_count, right_count, edges)) => {
-            println!("Graph: {} left, {} right, {} edges", left_count, right_count, edges.len());
+    /* --general reads an "n m" file and splits it by a 2-coloring. */
+    match load(&args[1], &load_opts, args::has_flag(&args, "--general"), heavier.is_some()) {
+        Ok((left_count, right_count, weighted, classes, names)) => {
+            let edges: Vec<(usize, usize)> = weighted.iter().map(|&(u, v, _)| (u, v)).collect();
+            match classes {
+                Some(ref c) => println!("Graph: {} vertices, {} edges, 2-colored: {} left, {} right",
+                                        c.n, c.edges.len(), left_count, right_count),
+                None => println!("Graph: {} left, {} right, {} edges", left_count, right_count, edges.len()),
+            }
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(left_count + right_count) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            /* a --general starting matching is in the graph's ids */
+            let init = match classes {
+                Some(ref c) => matching_io::init_from_args(&args, (c.n, c.n), &c.edges, false, &names)
+                    .map(|i| i.map(|pairs| pairs.iter().map(|&(u, v)| c.oriented(u, v)).collect())),
+                None => matching_io::init_from_args(&args, (left_count, right_count), &edges, true, &names),
+            };
+            let init = match init {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
 
             let start = Instant::now();
             let mut hk = HopcroftKarp::new(left_count, right_count, &edges);
-            let matching = hk.maximum_matching(greedy_mode);
+            hk.greedy_seed = seed;
+            hk.aug3_passes = aug3;
+            if numa {
+                hk.numa = Some(numa::Topology::detect().nodes);
+            }
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                hk.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                hk.stop = Some(&time_limit::EXPIRED);
+            }
+            let tracer: Option<types::TraceSink> = if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("hopcroft-karp {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                Some(&trace::record)
+            } else if log::enabled(log::Level::Debug) {
+                Some(&log::event)
+            } else {
+                None
+            };
+            hk.trace = match (&classes, tracer) {
+                (Some(c), Some(t)) => {
+                    let _ = CLASS_IDS.set(c.ids.clone());
+                    let _ = CLASS_TRACER.set(t);
+                    Some(&class_trace)
+                }
+                (_, t) => t,
+            };
+            if let Some(ref pairs) = init {
+                hk.seed(pairs);
+            }
+            let mut matching = if threads > 1 {
+                hk.maximum_matching_parallel(greedy_mode, threads)
+            } else {
+                hk.maximum_matching(greedy_mode)
+            };
+            let mut weight = None;
+            if let Some(heavier) = heavier {
+                let size = matching.len();
+                let (best, w) = hk.tie_break(&weighted, heavier);
+                if best.len() != size {
+                    eprintln!("ERROR: tie-break pass found {} pairs, Hopcroft-Karp {}!", best.len(), size);
+                }
+                matching = best;
+                weight = Some(w);
+            }
             let duration = start.elapsed();
+            /* The gap, the certificate and the drawings take one id space:
+             * the graph's own under --general, else right vertex v as
+             * left_count + v. */
+            let one_space = || {
+                match classes {
+                    Some(ref c) => (c.n, c.edges.clone(), c.graph_pairs(&matching)),
+                    None => {
+                        let shift = |&(u, v): &(usize, usize)| (u, left_count + v);
+                        (left_count + right_count, edges.iter().map(shift).collect(), matching.iter().map(shift).collect())
+                    }
+                }
+            };
+            let gap = time_limit.filter(|_| time_limit::expired()).map(|limit| {
+                let (n, edges, matching) = one_space();
+                (limit, time_limit::gap_bound(n, &edges, &matching))
+            });
 
-            validate_matching(left_count, right_count, &hk.graph, &matching);
+            /* --general reports and writes the pairs in the graph's ids */
+            let graph_matching = classes.as_ref().map(|c| c.graph_pairs(&matching));
+            let mut report = match (&classes, &graph_matching) {
+                (Some(c), Some(pairs)) => verify::validate_general(c.n, &c.edges, pairs),
+                _ => verify::validate((left_count, right_count), &edges, &matching, true),
+            };
+            if args::has_flag(&args, "--certify") && report.is_valid() {
+                let (n, edges, matching) = one_space();
+                report.optimal = Some(vertex_classes::certify(n, &edges, &matching));
+            }
+            report.print();
 
             println!("Matching size: {}", matching.len());
+            if threads > 1 {
+                println!("Threads: {}", threads);
+            }
+            if let Some(ref nodes) = hk.numa {
+                println!("NUMA: {} node(s), {} in use, threads pinned", nodes.len(), nodes.len().min(threads));
+            }
+            if let (Some(w), Some(heavier)) = (weight, heavier) {
+                println!("Matching weight: {} ({} among maximum matchings)", w,
+                         if heavier { "heaviest" } else { "lightest" });
+            }
             if greedy_mode > 0 {
-                let gs = hk.greedy_size;
+                let gs = hk.greedy_size();
                 let fs = matching.len();
                 println!("Greedy init size: {}", gs);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", hk.aug3_size());
+                }
                 if fs > 0 { println!("Greedy/Final: {:.2}%", 100.0 * gs as f64 / fs as f64); }
                 else { println!("Greedy/Final: NA"); }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            let saved = match graph_matching {
+                Some(ref pairs) => matching_io::save_from_args(&args, pairs, false, &names),
+                None => matching_io::save_from_args(&args, &matching, true, &names),
+            };
+            if let Err(e) = saved {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            /* a --general graph is drawn as the general graph it was read as */
+            let dot_path = args::value_of(&args, "--dot");
+            let svg_path = args::value_of(&args, "--svg");
+            if dot_path.is_some() || svg_path.is_some() {
+                let (n, edges, matching) = one_space();
+                let left = if classes.is_some() { None } else { Some(left_count) };
+                let drawn = dot_path.map_or(Ok(()), |path| dot::save(path, n, &edges, &matching, left))
+                    .and_then(|_| svg_path.map_or(Ok(()), |path| svg::save(path, n, &edges, &matching, left, svg_layout)));
+                if let Err(e) = drawn {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
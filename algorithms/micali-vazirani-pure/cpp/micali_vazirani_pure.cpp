@@ -9,6 +9,7 @@
 
 #include <cstdio>
 #include <cstdlib>
+#include <cstdint>
 #include <vector>
 #include <algorithm>
 #include <chrono>
@@ -26,6 +27,25 @@ static const int DDFS_PATH  = 2;
 /* =========================================================================
  * Node
  * ========================================================================= */
+/* SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+   stream, so --greedy-random --seed S picks the same matching there */
+struct SplitMix64 {
+    uint64_t state;
+    explicit SplitMix64(uint64_t seed) : state(seed) {}
+    uint64_t next() {
+        state += 0x9e3779b97f4a7c15ULL;
+        uint64_t z = state;
+        z = (z ^ (z >> 30)) * 0xbf58476d1ce4e5b9ULL;
+        z = (z ^ (z >> 27)) * 0x94d049bb133111ebULL;
+        return z ^ (z >> 31);
+    }
+    /* uniform in [0, bound) */
+    uint64_t below(uint64_t bound) { return (uint64_t)(((unsigned __int128)next() * bound) >> 64); }
+    template <typename T> void shuffle(std::vector<T>& v) {
+        for (size_t i = v.size(); i > 1; i--) std::swap(v[i - 1], v[below(i)]);
+    }
+};
+
 struct Node {
     std::vector<int> preds;
     std::vector<std::pair<int,int>> pred_to; /* (target, index in target's preds) */
@@ -180,6 +200,110 @@ struct MVGraph {
         return cnt;
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor, match the two;
+       otherwise match the lowest-id exposed vertex that has one with its first unmatched neighbor */
+    int greedy_init_ks() {
+        int cnt = 0;
+        int nn = (int)nodes.size();
+        std::vector<int> free_deg(nn, 0), leaves;
+        for (int j = 0; j < nn; j++) {
+            if (nodes[j].match != NIL) continue;
+            for (int k = 0; k < deg[j]; k++)
+                if (nodes[edges[adj_start[j] + k]].match == NIL) free_deg[j]++;
+            if (free_deg[j] == 1) leaves.push_back(j);
+        }
+        int next = 0;
+        while (true) {
+            int j;
+            if (!leaves.empty()) {
+                j = leaves.back(); leaves.pop_back();
+                if (nodes[j].match != NIL || free_deg[j] != 1) continue;
+            } else {
+                while (next < nn && (nodes[next].match != NIL || free_deg[next] == 0)) next++;
+                if (next == nn) break;
+                j = next;
+            }
+            int i = NIL;
+            for (int k = 0; k < deg[j]; k++)
+                if (nodes[edges[adj_start[j] + k]].match == NIL) { i = edges[adj_start[j] + k]; break; }
+            nodes[j].match = i;
+            nodes[i].match = j;
+            matchnum++;
+            cnt++;
+            for (int x : {j, i})
+                for (int k = 0; k < deg[x]; k++) {
+                    int w = edges[adj_start[x] + k];
+                    if (nodes[w].match == NIL && --free_deg[w] == 1) leaves.push_back(w);
+                }
+        }
+        return cnt;
+    }
+
+    /* Random greedy: vertices in a seeded random order, each exposed one
+       matched with a uniformly random unmatched neighbor */
+    int greedy_init_random(uint64_t seed) {
+        SplitMix64 rng(seed);
+        int nn = (int)nodes.size();
+        std::vector<int> order(nn);
+        for (int j = 0; j < nn; j++) order[j] = j;
+        rng.shuffle(order);
+        int cnt = 0;
+        std::vector<int> free;
+        for (int j : order) {
+            if (nodes[j].match != NIL) continue;
+            free.clear();
+            for (int k = 0; k < deg[j]; k++) {
+                int i = edges[adj_start[j] + k];
+                if (nodes[i].match == NIL) free.push_back(i);
+            }
+            if (free.empty()) continue;
+            int i = free[rng.below(free.size())];
+            nodes[j].match = i;
+            nodes[i].match = j;
+            matchnum++;
+            cnt++;
+        }
+        return cnt;
+    }
+
+    /* --init-aug3, after the greedy start: up to `passes` sweeps over the
+       free vertices, each taking the first path u - v = w - x from u (x
+       free, x != u) or a free neighbor outright. A pass without one ends
+       it; next[w] skips neighbors of w that are matched for good. */
+    int augment3(int passes) {
+        int nn = (int)nodes.size();
+        std::vector<int> next(adj_start.begin(), adj_start.end());
+        int cnt = 0;
+        for (int p = 0; p < passes; p++) {
+            int before = cnt;
+            for (int u = 0; u < nn; u++) {
+                if (nodes[u].match != NIL) continue;
+                for (int k = adj_start[u]; k < adj_start[u] + deg[u]; k++) {
+                    int v = edges[k];
+                    if (nodes[v].match == NIL) {
+                        nodes[u].match = v; nodes[v].match = u;
+                        matchnum++; cnt++;
+                        break;
+                    }
+                    int w = nodes[v].match;
+                    int end = adj_start[w] + deg[w];
+                    while (next[w] < end && nodes[edges[next[w]]].match != NIL) next[w]++;
+                    int j = next[w];
+                    while (j < end && (edges[j] == u || nodes[edges[j]].match != NIL)) j++;
+                    if (j < end) {
+                        int x = edges[j];
+                        nodes[u].match = v; nodes[v].match = u;
+                        nodes[w].match = x; nodes[x].match = w;
+                        matchnum++; cnt++;
+                        break;
+                    }
+                }
+            }
+            if (cnt == before) break;
+        }
+        return cnt;
+    }
+
     /* ---- helpers ---- */
     void add_to_level(int level, int node) {
         if (level >= (int)levels.size()) levels.resize(level + 1);
@@ -600,12 +724,22 @@ int main(int argc, char* argv[]) {
     printf("Micali-Vazirani Pure Algorithm - C++ Implementation\n");
     printf("====================================================\n\n");
 
-    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md]\n", argv[0]); return 1; }
+    if (argc < 2) { printf("Usage: %s <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]\n", argv[0]); return 1; }
     int greedy_mode = 0;
+    uint64_t seed = 1;
+    int aug3 = 0, aug3_passes = 3;
     for (int i = 2; i < argc; i++) {
         if (std::string(argv[i]) == "--greedy") greedy_mode = 1;
         else if (std::string(argv[i]) == "--greedy-md") greedy_mode = 2;
+        else if (std::string(argv[i]) == "--greedy-ks") greedy_mode = 3;
+        else if (std::string(argv[i]) == "--greedy-random") greedy_mode = 4;
+        else if (std::string(argv[i]) == "--seed" && i + 1 < argc) seed = strtoull(argv[++i], nullptr, 10);
+        else if (std::string(argv[i]) == "--init-aug3") aug3 = 1;
+        else if (std::string(argv[i]) == "--aug3-passes" && i + 1 < argc) aug3_passes = atoi(argv[++i]);
     }
+    if (aug3_passes < 1) { fprintf(stderr, "Error: --aug3-passes must be a positive count\n"); return 1; }
+    /* --init-aug3 alone runs after the plain greedy start */
+    if (aug3) { aug3 = aug3_passes; if (greedy_mode == 0) greedy_mode = 1; }
 
     FILE* f = fopen(argv[1], "r");
     if (!f) { fprintf(stderr, "Cannot open file: %s\n", argv[1]); return 1; }
@@ -630,6 +764,10 @@ int main(int argc, char* argv[]) {
     int greedy_count = 0;
     if (greedy_mode == 1) greedy_count = mv.greedy_init();
     else if (greedy_mode == 2) greedy_count = mv.greedy_init_md();
+    else if (greedy_mode == 3) greedy_count = mv.greedy_init_ks();
+    else if (greedy_mode == 4) greedy_count = mv.greedy_init_random(seed);
+    int aug3_count = aug3 > 0 ? mv.augment3(aug3) : 0;
+    greedy_count += aug3_count;
     mv.max_match();
     auto t1 = std::chrono::high_resolution_clock::now();
 
@@ -657,6 +795,7 @@ int main(int argc, char* argv[]) {
         int gs = greedy_count;
         int fs = mv.matchnum;
         printf("Greedy init size: %d\n", gs);
+        if (aug3 > 0) printf("Aug3 augmentations: %d\n", aug3_count);
         if (fs > 0) printf("Greedy/Final: %.2f%%\n", 100.0 * gs / fs);
         else printf("Greedy/Final: NA\n");
     }
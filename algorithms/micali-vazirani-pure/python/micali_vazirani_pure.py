@@ -22,6 +22,33 @@ DDFS_PATH  = 2
 # =========================================================================
 # Node
 # =========================================================================
+MASK64 = (1 << 64) - 1
+
+
+class SplitMix64:
+    """SplitMix64, as rng.rs in the Rust suite: the same seed gives the same
+    stream, so --greedy-random --seed S picks the same matching there."""
+
+    def __init__(self, seed):
+        self.state = seed & MASK64
+
+    def next(self):
+        self.state = (self.state + 0x9E3779B97F4A7C15) & MASK64
+        z = self.state
+        z = ((z ^ (z >> 30)) * 0xBF58476D1CE4E5B9) & MASK64
+        z = ((z ^ (z >> 27)) * 0x94D049BB133111EB) & MASK64
+        return z ^ (z >> 31)
+
+    def below(self, bound):
+        # uniform in [0, bound)
+        return (self.next() * bound) >> 64
+
+    def shuffle(self, items):
+        for i in range(len(items), 1, -1):
+            j = self.below(i)
+            items[i - 1], items[j] = items[j], items[i - 1]
+
+
 class Node:
     __slots__ = (
         'preds', 'pred_to', 'hanging_bridges',
@@ -139,6 +166,7 @@ class MVGraph:
 
     # ---- greedy initialization ----
     greedy_size = 0
+    aug3_size = 0
 
     def greedy_init(self):
         n = len(self.nodes)
@@ -175,6 +203,111 @@ class MVGraph:
                     cnt += 1
         return cnt
 
+    def greedy_init_ks(self):
+        # Karp-Sipser: match degree-1 exposed vertices first; otherwise the
+        # lowest-id exposed vertex with an exposed neighbor
+        n = len(self.nodes)
+        cnt = 0
+
+        def nbrs(j):
+            return (self.edges[self.adj_start[j] + k] for k in range(self.deg[j]))
+
+        free = [0] * n
+        leaves = []
+        for j in range(n):
+            if self.nodes[j].match != NIL:
+                continue
+            free[j] = sum(1 for i in nbrs(j) if self.nodes[i].match == NIL)
+            if free[j] == 1:
+                leaves.append(j)
+        nxt = 0
+        while True:
+            if leaves:
+                j = leaves.pop()
+                if self.nodes[j].match != NIL or free[j] != 1:
+                    continue
+            else:
+                while nxt < n and (self.nodes[nxt].match != NIL or free[nxt] == 0):
+                    nxt += 1
+                if nxt == n:
+                    break
+                j = nxt
+            i = next(w for w in nbrs(j) if self.nodes[w].match == NIL)
+            self.nodes[j].match = i
+            self.nodes[i].match = j
+            self.matchnum += 1
+            cnt += 1
+            for x in (j, i):
+                for w in nbrs(x):
+                    if self.nodes[w].match == NIL:
+                        free[w] -= 1
+                        if free[w] == 1:
+                            leaves.append(w)
+        return cnt
+
+    def greedy_init_random(self, seed):
+        # Random greedy: vertices in a seeded random order, each exposed one
+        # matched with a uniformly random unmatched neighbor
+        rng = SplitMix64(seed)
+        order = list(range(len(self.nodes)))
+        rng.shuffle(order)
+        cnt = 0
+        for j in order:
+            if self.nodes[j].match != NIL:
+                continue
+            free = [self.edges[self.adj_start[j] + k] for k in range(self.deg[j])
+                    if self.nodes[self.edges[self.adj_start[j] + k]].match == NIL]
+            if not free:
+                continue
+            i = free[rng.below(len(free))]
+            self.nodes[j].match = i
+            self.nodes[i].match = j
+            self.matchnum += 1
+            cnt += 1
+        return cnt
+
+    def augment3(self, passes):
+        # --init-aug3, after the greedy start: up to `passes` sweeps over the
+        # free vertices, each taking the first path u - v = w - x from u (x
+        # free, x != u) or a free neighbor outright. A pass without one ends
+        # it; nxt[w] skips neighbors of w that are matched for good.
+        nodes, edges = self.nodes, self.edges
+        nxt = list(self.adj_start)
+        cnt = 0
+        for _ in range(passes):
+            before = cnt
+            for u in range(len(nodes)):
+                if nodes[u].match != NIL:
+                    continue
+                for k in range(self.adj_start[u], self.adj_start[u] + self.deg[u]):
+                    v = edges[k]
+                    if nodes[v].match == NIL:
+                        nodes[u].match = v
+                        nodes[v].match = u
+                        self.matchnum += 1
+                        cnt += 1
+                        break
+                    w = nodes[v].match
+                    end = self.adj_start[w] + self.deg[w]
+                    j = nxt[w]
+                    while j < end and nodes[edges[j]].match != NIL:
+                        j += 1
+                    nxt[w] = j
+                    while j < end and (edges[j] == u or nodes[edges[j]].match != NIL):
+                        j += 1
+                    if j < end:
+                        x = edges[j]
+                        nodes[u].match = v
+                        nodes[v].match = u
+                        nodes[w].match = x
+                        nodes[x].match = w
+                        self.matchnum += 1
+                        cnt += 1
+                        break
+            if cnt == before:
+                break
+        return cnt
+
     # ---- helpers ----
     def add_to_level(self, level, node):
         while level >= len(self.levels):
@@ -631,15 +764,36 @@ def main():
     print()
 
     if len(sys.argv) < 2:
-        print(f"Usage: python {sys.argv[0]} <filename> [--greedy|--greedy-md]")
+        print(f"Usage: python {sys.argv[0]} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] [--init-aug3 [--aug3-passes K]]")
         sys.exit(1)
 
     greedy_mode = 0
-    for arg in sys.argv[2:]:
+    seed = 1
+    aug3 = 0
+    aug3_passes = 3
+    for i, arg in enumerate(sys.argv[2:], 2):
         if arg == "--greedy":
             greedy_mode = 1
         elif arg == "--greedy-md":
             greedy_mode = 2
+        elif arg == "--greedy-ks":
+            greedy_mode = 3
+        elif arg == "--greedy-random":
+            greedy_mode = 4
+        elif arg == "--seed" and i + 1 < len(sys.argv):
+            seed = int(sys.argv[i + 1])
+        elif arg == "--init-aug3":
+            aug3 = 1
+        elif arg == "--aug3-passes" and i + 1 < len(sys.argv):
+            aug3_passes = int(sys.argv[i + 1])
+    if aug3_passes < 1:
+        print("Error: --aug3-passes must be a positive count", file=sys.stderr)
+        sys.exit(1)
+    # --init-aug3 alone runs after the plain greedy start
+    if aug3:
+        aug3 = aug3_passes
+        if greedy_mode == 0:
+            greedy_mode = 1
 
     n, edge_list = load_graph(sys.argv[1])
     print(f"Graph: {n} vertices, {len(edge_list)} edges")
@@ -651,6 +805,13 @@ def main():
         mv.greedy_size = mv.greedy_init()
     elif greedy_mode == 2:
         mv.greedy_size = mv.greedy_init_md()
+    elif greedy_mode == 3:
+        mv.greedy_size = mv.greedy_init_ks()
+    elif greedy_mode == 4:
+        mv.greedy_size = mv.greedy_init_random(seed)
+    if aug3 > 0:
+        mv.aug3_size = mv.augment3(aug3)
+        mv.greedy_size += mv.aug3_size
     mv.max_match()
     t1 = time.time()
 
@@ -661,6 +822,8 @@ def main():
     print(f"Matching size: {len(matching)}")
     if greedy_mode > 0:
         print(f"Greedy init size: {mv.greedy_size}")
+        if aug3 > 0:
+            print(f"Aug3 augmentations: {mv.aug3_size}")
         if matching:
             print(f"Greedy/Final: {100.0 * mv.greedy_size / len(matching):.2f}%")
         else:
@@ -7,36 +7,154 @@
  * All integers, no hash containers, fully deterministic.
  */
 
+#![allow(clippy::duplicate_mod)]
+
 use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
-const NIL: i32 = -1;
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/dot.rs"]
+mod dot;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../common/rust/ordering.rs"]
+mod ordering;
+#[path = "../../common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../common/rust/phase_stats.rs"]
+mod phase_stats;
+#[path = "../../common/rust/progress.rs"]
+mod progress;
+#[path = "../../common/rust/rng.rs"]
+mod rng;
+#[path = "../../common/rust/svg.rs"]
+mod svg;
+#[path = "../../common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../common/rust/trace.rs"]
+mod trace;
+#[path = "../../common/rust/types.rs"]
+mod types;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+#[path = "../../common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use self::types::{ix, vx, Vertex, NONE};
+
+/* A level or tenacity not set yet */
+const UNSET: i32 = -1;
 
 /* DDFS result codes */
 const DDFS_EMPTY: i32 = 0;
 const DDFS_PETAL: i32 = 1;
 const DDFS_PATH: i32 = 2;
 
+/* =========================================================================
+ * Buckets — the per-level node lists and per-tenacity bridge lists
+ * ========================================================================= */
+
+/* Arena capacity kept across phases is at most SHRINK_FACTOR times what
+ * the phase just finished used (or MIN_RETAINED entries). */
+const SHRINK_FACTOR: usize = 4;
+const MIN_RETAINED: usize = 1024;
+const END: u32 = u32::MAX;
+
+/* Every bucket is a list threaded through one arena and appended at its
+ * tail: a phase allocates nothing once the arena has grown, and a level
+ * index near n costs two u32s instead of a Vec header plus a small
+ * allocation that is never returned. Entries are walked from `head` up to
+ * the tail seen when the walk began, so appends made meanwhile are left
+ * for later, as the old per-level snapshots did. */
+struct Buckets<T: Copy> {
+    first: Vec<u32>,
+    last: Vec<u32>,
+    items: Vec<T>,
+    next: Vec<u32>,
+}
+
+impl<T: Copy> Buckets<T> {
+    fn new() -> Self {
+        Buckets { first: Vec::new(), last: Vec::new(), items: Vec::new(), next: Vec::new() }
+    }
+
+    fn push(&mut self, b: usize, x: T) {
+        if self.first.len() <= b {
+            self.first.resize(b + 1, END);
+            self.last.resize(b + 1, END);
+        }
+        let e = self.items.len() as u32;
+        self.items.push(x);
+        self.next.push(END);
+        match self.last[b] {
+            END => self.first[b] = e,
+            t => self.next[t as usize] = e,
+        }
+        self.last[b] = e;
+    }
+
+    /* Walk position (entry, stop) at the head of bucket b; None if empty. */
+    fn span(&self, b: usize) -> Option<(usize, usize)> {
+        if b >= self.first.len() || self.first[b] == END { return None; }
+        Some((self.first[b] as usize, self.last[b] as usize))
+    }
+
+    /* The walk position after entry e, None once e is the stop entry. */
+    fn after(&self, e: usize, stop: usize) -> Option<(usize, usize)> {
+        if e == stop { None } else { Some((self.next[e] as usize, stop)) }
+    }
+
+    /* Empty every bucket, keeping the arena for the next phase unless it is
+     * far larger than this phase needed. */
+    fn clear(&mut self) {
+        let used = self.items.len().max(MIN_RETAINED);
+        let buckets = self.first.len().max(MIN_RETAINED);
+        self.items.clear();
+        self.next.clear();
+        self.first.clear();
+        self.last.clear();
+        if self.items.capacity() > SHRINK_FACTOR * used {
+            self.items.shrink_to(2 * used);
+            self.next.shrink_to(2 * used);
+        }
+        if self.first.capacity() > SHRINK_FACTOR * buckets {
+            self.first.shrink_to(2 * buckets);
+            self.last.shrink_to(2 * buckets);
+        }
+    }
+}
+
 /* =========================================================================
  * Node
  * ========================================================================= */
 struct Node {
-    preds: Vec<i32>,
-    pred_to: Vec<(usize, usize)>, /* (target, index in target's preds) */
-    hanging_bridges: Vec<usize>,
+    preds: Vec<Vertex>,
+    pred_to: Vec<(Vertex, u32)>, /* (target, index in target's preds) */
+    hanging_bridges: Vec<Vertex>,
 
     min_level: i32,
     max_level: i32,
     even_level: i32,
     odd_level: i32,
-    match_: i32,
-    bud: i32,
-    above: i32,
-    below: i32,
-    ddfs_green: i32,
-    ddfs_red: i32,
+    match_: Vertex,
+    bud: Vertex,
+    above: Vertex,
+    below: Vertex,
+    ddfs_green: Vertex,
+    ddfs_red: Vertex,
     number_preds: i32,
     deleted: bool,
     visited: bool,
@@ -48,16 +166,16 @@ impl Node {
             preds: Vec::new(),
             pred_to: Vec::new(),
             hanging_bridges: Vec::new(),
-            min_level: NIL,
-            max_level: NIL,
-            even_level: NIL,
-            odd_level: NIL,
-            match_: NIL,
-            bud: NIL,
-            above: NIL,
-            below: NIL,
-            ddfs_green: NIL,
-            ddfs_red: NIL,
+            min_level: UNSET,
+            max_level: UNSET,
+            even_level: UNSET,
+            odd_level: UNSET,
+            match_: NONE,
+            bud: NONE,
+            above: NONE,
+            below: NONE,
+            ddfs_green: NONE,
+            ddfs_red: NONE,
             number_preds: 0,
             deleted: false,
             visited: false,
@@ -77,22 +195,22 @@ impl Node {
     }
 
     fn outer(&self) -> bool {
-        self.even_level != NIL && (self.odd_level == NIL || self.even_level < self.odd_level)
+        self.even_level != UNSET && (self.odd_level == UNSET || self.even_level < self.odd_level)
     }
 
     fn reset(&mut self) {
         self.preds.clear();
         self.pred_to.clear();
         self.hanging_bridges.clear();
-        self.min_level = NIL;
-        self.max_level = NIL;
-        self.even_level = NIL;
-        self.odd_level = NIL;
-        self.bud = NIL;
-        self.above = NIL;
-        self.below = NIL;
-        self.ddfs_green = NIL;
-        self.ddfs_red = NIL;
+        self.min_level = UNSET;
+        self.max_level = UNSET;
+        self.even_level = UNSET;
+        self.odd_level = UNSET;
+        self.bud = NONE;
+        self.above = NONE;
+        self.below = NONE;
+        self.ddfs_green = NONE;
+        self.ddfs_red = NONE;
         self.number_preds = 0;
         self.deleted = false;
         self.visited = false;
@@ -102,79 +220,153 @@ impl Node {
 /* =========================================================================
  * MVGraph â€” the full algorithm
  * ========================================================================= */
-struct MVGraph {
+pub(crate) struct MVGraph {
     nodes: Vec<Node>,
-    edges: Vec<usize>,       /* flat adjacency array (CSR) */
-    adj_start: Vec<usize>,
-    deg: Vec<usize>,
+    edges: Vec<Vertex>,      /* flat adjacency array (CSR) */
+    adj_start: Vec<usize>,   /* n + 1 offsets into edges */
 
-    levels: Vec<Vec<usize>>,
-    bridges: Vec<Vec<(usize, usize)>>,
+    levels: Buckets<Vertex>,
+    bridges: Buckets<(Vertex, Vertex)>,
 
-    green_stack: Vec<(i32, usize)>,
-    red_stack: Vec<(i32, usize)>,
+    green_stack: Vec<(Vertex, usize)>,
+    red_stack: Vec<(Vertex, usize)>,
     path_found: Vec<usize>,
     ddfs_nodes_seen: Vec<usize>,
-    ddfs_bottleneck: i32,
+    ddfs_bottleneck: Vertex,
 
     matchnum: usize,
     bridgenum: i32,
     todonum: i32,
+
+    pub(crate) phases: Vec<(usize, usize)>, /* (path length, augmentations) */
+    pub(crate) stop: Option<&'static AtomicBool>, /* raised to stop at the next phase (interrupt.rs) */
+    pub(crate) progress: Option<&'static (dyn Fn(usize, usize, usize) + Sync)>, /* after each phase (progress.rs) */
+    pub(crate) trace: Option<types::TraceSink>, /* every augmenting path and petal (trace.rs) */
+    pub(crate) max_path: Option<usize>, /* --epsilon: longest augmenting path searched, in edges */
 }
 
 impl MVGraph {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         MVGraph {
             nodes: Vec::new(),
             edges: Vec::new(),
             adj_start: Vec::new(),
-            deg: Vec::new(),
-            levels: Vec::new(),
-            bridges: Vec::new(),
+            levels: Buckets::new(),
+            bridges: Buckets::new(),
             green_stack: Vec::new(),
             red_stack: Vec::new(),
             path_found: Vec::new(),
             ddfs_nodes_seen: Vec::new(),
-            ddfs_bottleneck: NIL,
+            ddfs_bottleneck: NONE,
             matchnum: 0,
             bridgenum: 0,
             todonum: 0,
+            phases: Vec::new(),
+            stop: None,
+            progress: None,
+            trace: None,
+            max_path: None,
         }
     }
 
     /* ---- construction ---- */
-    fn build(&mut self, n: usize, edge_list: &[(usize, usize)]) {
+    /* The CSR is filled in place by degree counts; a list per vertex on
+     * the way would add 24 bytes a vertex and 8 an entry to the peak. */
+    pub(crate) fn build(&mut self, n: usize, edge_list: &[(usize, usize)]) {
         self.nodes = (0..n).map(|_| Node::new()).collect();
-        let mut adj = vec![Vec::new(); n];
+        let mut start = vec![0usize; n + 1];
         for &(u, v) in edge_list {
             if u < n && v < n && u != v {
-                adj[u].push(v);
-                adj[v].push(u);
+                start[u + 1] += 1;
+                start[v + 1] += 1;
             }
         }
-        for a in &mut adj { a.sort_unstable(); a.dedup(); }
-
-        self.adj_start = vec![0; n];
-        self.deg = vec![0; n];
-        self.edges.clear();
         for i in 0..n {
-            self.adj_start[i] = self.edges.len();
-            self.deg[i] = adj[i].len();
-            for &nb in &adj[i] { self.edges.push(nb); }
+            start[i + 1] += start[i];
+        }
+        let mut edges = vec![0 as Vertex; start[n]];
+        let mut fill = start.clone();
+        for &(u, v) in edge_list {
+            if u < n && v < n && u != v {
+                edges[fill[u]] = vx(v);
+                fill[u] += 1;
+                edges[fill[v]] = vx(u);
+                fill[v] += 1;
+            }
+        }
+        drop(fill);
+        /* sort and dedup each list, closing the gaps the duplicates leave */
+        let mut end = 0;
+        for i in 0..n {
+            let (lo, hi) = (start[i], start[i + 1]);
+            edges[lo..hi].sort_unstable();
+            start[i] = end;
+            for k in lo..hi {
+                if k == lo || edges[k] != edges[k - 1] {
+                    edges[end] = edges[k];
+                    end += 1;
+                }
+            }
+        }
+        start[n] = end;
+        edges.truncate(end);
+        edges.shrink_to_fit();
+        self.adj_start = start;
+        self.edges = edges;
+    }
+
+    fn deg(&self, v: usize) -> usize {
+        self.adj_start[v + 1] - self.adj_start[v]
+    }
+
+    /* A stop was requested (SIGINT/SIGTERM in combi): the caller gets the
+     * matching of the last finished phase. */
+    fn stopped(&self) -> bool {
+        self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+    }
+
+    /* Start from a matching found elsewhere (mate[v] = NONE for free v),
+     * e.g. --init-matching, in place of greedy_init; after build. */
+    pub(crate) fn set_mate(&mut self, mate: &[Vertex]) {
+        self.matchnum = 0;
+        for (v, &m) in mate.iter().enumerate() {
+            self.nodes[v].match_ = m;
+            if m != NONE && ix(m) > v { self.matchnum += 1; }
         }
     }
 
+    /* Per-vertex queries on the current matching, as on matchers::Solution. */
+    #[allow(dead_code)]
+    pub(crate) fn mate(&self, v: usize) -> Option<usize> {
+        self.nodes.get(v).and_then(|node| types::some(node.match_))
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn is_matched(&self, v: usize) -> bool {
+        self.mate(v).is_some()
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn unmatched_vertices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |&v| self.nodes[v].match_ == NONE)
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn matching_size(&self) -> usize {
+        self.matchnum
+    }
+
     /* ---- greedy initialization ---- */
-    fn greedy_init(&mut self) -> usize {
+    pub(crate) fn greedy_init(&mut self) -> usize {
         let mut cnt: usize = 0;
         let n = self.nodes.len();
         for j in 0..n {
-            if self.nodes[j].match_ == NIL {
-                for k in 0..self.deg[j] {
-                    let i = self.edges[self.adj_start[j] + k];
-                    if self.nodes[i].match_ == NIL {
-                        self.nodes[j].match_ = i as i32;
-                        self.nodes[i].match_ = j as i32;
+            if self.nodes[j].match_ == NONE {
+                for k in 0..self.deg(j) {
+                    let i = ix(self.edges[self.adj_start[j] + k]);
+                    if self.nodes[i].match_ == NONE {
+                        self.nodes[j].match_ = vx(i);
+                        self.nodes[i].match_ = vx(j);
                         self.matchnum += 1;
                         cnt += 1;
                         break;
@@ -186,25 +378,25 @@ impl MVGraph {
     }
 
     /* Min-degree greedy: match each exposed vertex with lowest-degree unmatched neighbor */
-    fn greedy_init_md(&mut self) -> usize {
+    pub(crate) fn greedy_init_md(&mut self) -> usize {
         let mut cnt: usize = 0;
         let nn = self.nodes.len();
         let mut order: Vec<usize> = (0..nn).collect();
-        order.sort_unstable_by(|&a, &b| self.deg[a].cmp(&self.deg[b]).then(a.cmp(&b)));
+        order.sort_unstable_by(|&a, &b| self.deg(a).cmp(&self.deg(b)).then(a.cmp(&b)));
         for j in order {
-            if self.nodes[j].match_ != NIL { continue; }
-            let mut best: i32 = -1;
+            if self.nodes[j].match_ != NONE { continue; }
+            let mut best = NONE;
             let mut best_deg = usize::MAX;
-            for k in 0..self.deg[j] {
-                let i = self.edges[self.adj_start[j] + k];
-                if self.nodes[i].match_ == NIL && self.deg[i] < best_deg {
-                    best = i as i32;
-                    best_deg = self.deg[i];
+            for k in 0..self.deg(j) {
+                let i = ix(self.edges[self.adj_start[j] + k]);
+                if self.nodes[i].match_ == NONE && self.deg(i) < best_deg {
+                    best = vx(i);
+                    best_deg = self.deg(i);
                 }
             }
-            if best >= 0 {
+            if best != NONE {
                 self.nodes[j].match_ = best;
-                self.nodes[best as usize].match_ = j as i32;
+                self.nodes[ix(best)].match_ = vx(j);
                 self.matchnum += 1;
                 cnt += 1;
             }
@@ -212,56 +404,171 @@ impl MVGraph {
         cnt
     }
 
+    /* Karp-Sipser: while an exposed vertex has a single unmatched neighbor,
+     * match the two; otherwise match the lowest-id exposed vertex that has
+     * one with its first unmatched neighbor. free counts unmatched neighbors. */
+    pub(crate) fn greedy_init_ks(&mut self) -> usize {
+        let nn = self.nodes.len();
+        let mut free = vec![0usize; nn];
+        for (j, f) in free.iter_mut().enumerate() {
+            if self.nodes[j].match_ == NONE {
+                let nbrs = &self.edges[self.adj_start[j]..self.adj_start[j] + self.deg(j)];
+                *f = nbrs.iter().filter(|&&i| self.nodes[ix(i)].match_ == NONE).count();
+            }
+        }
+        let mut leaves: Vec<usize> = (0..nn).filter(|&j| free[j] == 1).collect();
+        let mut next = 0;
+        let mut cnt: usize = 0;
+        loop {
+            let j = match leaves.pop() {
+                Some(j) if self.nodes[j].match_ != NONE || free[j] != 1 => continue,
+                Some(j) => j,
+                None => {
+                    while next < nn && (self.nodes[next].match_ != NONE || free[next] == 0) { next += 1; }
+                    if next == nn { break; }
+                    next
+                }
+            };
+            let nbrs = &self.edges[self.adj_start[j]..self.adj_start[j] + self.deg(j)];
+            let i = ix(*nbrs.iter().find(|&&i| self.nodes[ix(i)].match_ == NONE).unwrap());
+            self.nodes[j].match_ = vx(i);
+            self.nodes[i].match_ = vx(j);
+            self.matchnum += 1;
+            cnt += 1;
+            for &x in &[j, i] {
+                for k in self.adj_start[x]..self.adj_start[x] + self.deg(x) {
+                    let w = ix(self.edges[k]);
+                    if self.nodes[w].match_ != NONE { continue; }
+                    free[w] -= 1;
+                    if free[w] == 1 { leaves.push(w); }
+                }
+            }
+        }
+        cnt
+    }
+
+    /* Random greedy: vertices in a seeded random order (rng.rs), each
+     * exposed one matched with a uniformly random unmatched neighbor. The
+     * same seed gives the same matching. */
+    pub(crate) fn greedy_init_random(&mut self, seed: u64) -> usize {
+        let nn = self.nodes.len();
+        let mut rng = rng::Rng::new(seed);
+        let mut order: Vec<usize> = (0..nn).collect();
+        rng.shuffle(&mut order);
+        let mut cnt: usize = 0;
+        for j in order {
+            if self.nodes[j].match_ != NONE { continue; }
+            let nbrs = &self.edges[self.adj_start[j]..self.adj_start[j] + self.deg(j)];
+            let free = nbrs.iter().filter(|&&i| self.nodes[ix(i)].match_ == NONE).count();
+            if free == 0 { continue; }
+            let k = rng.below(free as u64) as usize;
+            let i = ix(*nbrs.iter().filter(|&&i| self.nodes[ix(i)].match_ == NONE).nth(k).unwrap());
+            self.nodes[j].match_ = vx(i);
+            self.nodes[i].match_ = vx(j);
+            self.matchnum += 1;
+            cnt += 1;
+        }
+        cnt
+    }
+
+    /* --init-aug3, after a greedy_init*: up to `passes` sweeps over the
+     * free vertices, each taking the first path u - v = w - x from u (x
+     * free, x != u) or a free neighbor outright. A pass without one ends
+     * it; next[w] skips neighbors of w that are matched for good. */
+    pub(crate) fn augment3(&mut self, passes: usize) -> usize {
+        let nn = self.nodes.len();
+        let mut next: Vec<usize> = self.adj_start.clone();
+        let mut cnt: usize = 0;
+        for _ in 0..passes {
+            let before = cnt;
+            for u in 0..nn {
+                if self.nodes[u].match_ != NONE { continue; }
+                for k in self.adj_start[u]..self.adj_start[u] + self.deg(u) {
+                    let v = ix(self.edges[k]);
+                    if self.nodes[v].match_ == NONE {
+                        self.nodes[u].match_ = vx(v);
+                        self.nodes[v].match_ = vx(u);
+                        self.matchnum += 1;
+                        cnt += 1;
+                        break;
+                    }
+                    let w = ix(self.nodes[v].match_);
+                    let end = self.adj_start[w] + self.deg(w);
+                    while next[w] < end && self.nodes[ix(self.edges[next[w]])].match_ != NONE { next[w] += 1; }
+                    let mut j = next[w];
+                    while j < end && (ix(self.edges[j]) == u || self.nodes[ix(self.edges[j])].match_ != NONE) { j += 1; }
+                    if j < end {
+                        let x = ix(self.edges[j]);
+                        self.nodes[u].match_ = vx(v);
+                        self.nodes[v].match_ = vx(u);
+                        self.nodes[w].match_ = vx(x);
+                        self.nodes[x].match_ = vx(w);
+                        self.matchnum += 1;
+                        cnt += 1;
+                        break;
+                    }
+                }
+            }
+            if cnt == before { break; }
+        }
+        cnt
+    }
+
     /* ---- helpers ---- */
     fn add_to_level(&mut self, level: usize, node: usize) {
-        while self.levels.len() <= level { self.levels.push(Vec::new()); }
-        self.levels[level].push(node);
+        self.levels.push(level, vx(node));
         self.todonum += 1;
     }
 
     fn add_to_bridges(&mut self, level: usize, n1: usize, n2: usize) {
-        while self.bridges.len() <= level { self.bridges.push(Vec::new()); }
-        self.bridges[level].push((n1, n2));
+        self.bridges.push(level, (vx(n1), vx(n2)));
         self.bridgenum += 1;
     }
 
     fn tenacity(&self, n1: usize, n2: usize) -> i32 {
-        if self.nodes[n1].match_ == n2 as i32 {
+        if self.nodes[n1].match_ == vx(n2) {
             /* matched bridge */
-            if self.nodes[n1].odd_level != NIL && self.nodes[n2].odd_level != NIL {
+            if self.nodes[n1].odd_level != UNSET && self.nodes[n2].odd_level != UNSET {
                 return self.nodes[n1].odd_level + self.nodes[n2].odd_level + 1;
             }
         } else {
             /* unmatched bridge */
-            if self.nodes[n1].even_level != NIL && self.nodes[n2].even_level != NIL {
+            if self.nodes[n1].even_level != UNSET && self.nodes[n2].even_level != UNSET {
                 return self.nodes[n1].even_level + self.nodes[n2].even_level + 1;
             }
         }
-        NIL
+        UNSET
     }
 
-    fn bud_star(&self, c: usize) -> usize {
-        let b = self.nodes[c].bud;
-        if b == NIL { c } else { self.bud_star(b as usize) }
+    /* The outermost bud over c: bud chains grow as long as petals nest,
+     * so they are followed in a loop. */
+    fn bud_star(&self, mut c: usize) -> usize {
+        while self.nodes[c].bud != NONE {
+            c = ix(self.nodes[c].bud);
+        }
+        c
     }
 
-    fn bud_star_includes(&self, c: usize, goal: usize) -> bool {
-        if c == goal { return true; }
-        let b = self.nodes[c].bud;
-        if b == NIL { return false; }
-        self.bud_star_includes(b as usize, goal)
+    /* Whether goal is on the bud chain from c. */
+    fn bud_star_includes(&self, mut c: usize, goal: usize) -> bool {
+        loop {
+            if c == goal { return true; }
+            let b = self.nodes[c].bud;
+            if b == NONE { return false; }
+            c = ix(b);
+        }
     }
 
     /* ---- reset between phases ---- */
     fn phase_reset(&mut self) {
-        for lv in &mut self.levels { lv.clear(); }
-        for br in &mut self.bridges { br.clear(); }
+        self.levels.clear();
+        self.bridges.clear();
         self.bridgenum = 0;
         self.todonum = 0;
         let n = self.nodes.len();
         for i in 0..n {
             self.nodes[i].reset();
-            if self.nodes[i].match_ == NIL {
+            if self.nodes[i].match_ == NONE {
                 self.add_to_level(0, i);
                 self.nodes[i].set_min_level(0);
             }
@@ -272,21 +579,21 @@ impl MVGraph {
     fn step_to(&mut self, to: usize, from: usize, level: i32) {
         let next = level + 1;
         let tl = self.nodes[to].min_level;
-        if tl == NIL || tl >= next {
+        if tl == UNSET || tl >= next {
             if tl != next {
                 self.add_to_level(next as usize, to);
                 self.nodes[to].set_min_level(next);
             }
-            self.nodes[to].preds.push(from as i32);
+            self.nodes[to].preds.push(vx(from));
             self.nodes[to].number_preds += 1;
             let idx = self.nodes[to].preds.len() - 1;
-            self.nodes[from].pred_to.push((to, idx));
+            self.nodes[from].pred_to.push((vx(to), idx as u32));
         } else {
             /* found a bridge */
             let ten = self.tenacity(to, from);
-            if ten == NIL {
-                self.nodes[to].hanging_bridges.push(from);
-                self.nodes[from].hanging_bridges.push(to);
+            if ten == UNSET {
+                self.nodes[to].hanging_bridges.push(vx(from));
+                self.nodes[from].hanging_bridges.push(vx(to));
             } else {
                 self.add_to_bridges(((ten - 1) / 2) as usize, to, from);
             }
@@ -295,25 +602,26 @@ impl MVGraph {
 
     /* ---- MIN phase ---- */
     fn min_phase(&mut self, i: usize) {
-        if i >= self.levels.len() { return; }
-        let level_snap = self.levels[i].clone();
-        for &current in &level_snap {
+        let mut walk = self.levels.span(i);
+        while let Some((e, stop)) = walk {
+            walk = self.levels.after(e, stop);
+            let current = ix(self.levels.items[e]);
             self.todonum -= 1;
             let match_ = self.nodes[current].match_;
             if i % 2 == 0 {
                 /* even level â€” explore non-matching edges */
                 let start = self.adj_start[current];
-                let d = self.deg[current];
+                let d = self.deg(current);
                 for j in 0..d {
-                    let edge = self.edges[start + j];
-                    if edge as i32 != match_ {
+                    let edge = ix(self.edges[start + j]);
+                    if vx(edge) != match_ {
                         self.step_to(edge, current, i as i32);
                     }
                 }
             } else {
                 /* odd level â€” follow matching edge only */
-                if match_ != NIL {
-                    self.step_to(match_ as usize, current, i as i32);
+                if match_ != NONE {
+                    self.step_to(ix(match_), current, i as i32);
                 }
             }
         }
@@ -322,10 +630,11 @@ impl MVGraph {
     /* ---- MAX phase ---- */
     fn max_phase(&mut self, i: usize) -> bool {
         let mut found = false;
-        if i >= self.bridges.len() { return false; }
-
-        let bridge_snap = self.bridges[i].clone();
-        for &(n1, n2) in &bridge_snap {
+        let mut walk = self.bridges.span(i);
+        while let Some((e, stop)) = walk {
+            walk = self.bridges.after(e, stop);
+            let (n1, n2) = self.bridges.items[e];
+            let (n1, n2) = (ix(n1), ix(n2));
             self.bridgenum -= 1;
             if self.nodes[n1].deleted || self.nodes[n2].deleted { continue; }
 
@@ -335,28 +644,35 @@ impl MVGraph {
             if result == DDFS_PATH {
                 self.find_path(n1, n2);
                 self.augment_path();
+                if let Some(t) = self.trace {
+                    t("augment", &self.path_found);
+                }
                 if self.nodes.len() / 2 <= self.matchnum { return true; }
                 self.remove_path();
                 found = true;
             } else {
                 /* DDFS_PETAL */
-                let b = self.ddfs_bottleneck as usize;
+                let b = ix(self.ddfs_bottleneck);
+                if let Some(t) = self.trace {
+                    t("blossom", &[b, n1, n2]);
+                }
                 let current_ten = (i * 2 + 1) as i32;
-                let seen = self.ddfs_nodes_seen.clone();
+                let seen = std::mem::take(&mut self.ddfs_nodes_seen);
                 for &itt in &seen {
-                    self.nodes[itt].bud = b as i32;
+                    self.nodes[itt].bud = vx(b);
                     let ml = self.nodes[itt].min_level;
                     self.nodes[itt].set_max_level(current_ten - ml);
                     let max_lv = self.nodes[itt].max_level as usize;
                     self.add_to_level(max_lv, itt);
-                    let hangs = self.nodes[itt].hanging_bridges.clone();
-                    for &hanging in &hangs {
+                    for k in 0..self.nodes[itt].hanging_bridges.len() {
+                        let hanging = ix(self.nodes[itt].hanging_bridges[k]);
                         let hanging_ten = self.tenacity(itt, hanging);
-                        if hanging_ten != NIL {
+                        if hanging_ten != UNSET {
                             self.add_to_bridges(((hanging_ten - 1) / 2) as usize, itt, hanging);
                         }
                     }
                 }
+                self.ddfs_nodes_seen = seen;
             }
         }
         found
@@ -366,82 +682,98 @@ impl MVGraph {
      * DDFS â€” Double Depth-First Search
      * ================================================================== */
 
-    fn add_pred_to_stack(preds: &[i32], cur_node: usize, stack: &mut Vec<(i32, usize)>) {
+    fn add_pred_to_stack(preds: &[Vertex], cur_node: usize, stack: &mut Vec<(Vertex, usize)>) {
         for &pred in preds {
-            if pred != NIL {
-                stack.push((cur_node as i32, pred as usize));
+            if pred != NONE {
+                stack.push((vx(cur_node), ix(pred)));
             }
         }
     }
 
-    fn edge_valid(e: (i32, i32)) -> bool {
-        !(e.0 == NIL && e.1 == NIL)
+    fn edge_valid(e: (Vertex, Vertex)) -> bool {
+        !(e.0 == NONE && e.1 == NONE)
     }
 
-    fn stack_pop(stack: &mut Vec<(i32, usize)>) -> (i32, i32) {
+    fn stack_pop(stack: &mut Vec<(Vertex, usize)>) -> (Vertex, Vertex) {
         if let Some((a, b)) = stack.pop() {
-            (a, b as i32)
+            (a, vx(b))
         } else {
-            (NIL, NIL)
+            (NONE, NONE)
         }
     }
 
-    fn prepare_next(&mut self, nx: &mut (i32, i32)) {
-        if nx.0 != NIL {
-            self.nodes[nx.0 as usize].below = nx.1;
+    fn prepare_next(&mut self, nx: &mut (Vertex, Vertex)) {
+        if nx.0 != NONE {
+            self.nodes[ix(nx.0)].below = nx.1;
         }
-        nx.1 = self.bud_star(nx.1 as usize) as i32;
+        nx.1 = vx(self.bud_star(ix(nx.1)));
     }
 
-    fn level_of(&self, nx: &(i32, i32)) -> i32 {
-        let n = self.bud_star(nx.1 as usize);
+    fn level_of(&self, nx: &(Vertex, Vertex)) -> i32 {
+        let n = self.bud_star(ix(nx.1));
         self.nodes[n].min_level
     }
 
-    fn step_into(&mut self, c: &mut i32, nx: &mut (i32, i32),
-                 stack: &mut Vec<(i32, usize)>, green_top: usize, red_top: usize) {
+    fn step_into(&mut self, c: &mut Vertex, nx: &mut (Vertex, Vertex),
+                 stack: &mut Vec<(Vertex, usize)>, green_top: usize, red_top: usize) {
         self.prepare_next(nx);
-        let nx_second = nx.1 as usize;
+        let nx_second = ix(nx.1);
         if !self.nodes[nx_second].visited {
             self.nodes[nx_second].above = nx.0;
-            *c = nx_second as i32;
+            *c = vx(nx_second);
             self.nodes[nx_second].visited = true;
-            self.nodes[nx_second].ddfs_green = green_top as i32;
-            self.nodes[nx_second].ddfs_red = red_top as i32;
+            self.nodes[nx_second].ddfs_green = vx(green_top);
+            self.nodes[nx_second].ddfs_red = vx(red_top);
             self.ddfs_nodes_seen.push(nx_second);
-            let preds = self.nodes[nx_second].preds.clone();
-            Self::add_pred_to_stack(&preds, nx_second, stack);
+            Self::add_pred_to_stack(&self.nodes[nx_second].preds, nx_second, stack);
         }
         let popped = Self::stack_pop(stack);
         nx.0 = popped.0;
         nx.1 = popped.1;
     }
 
+    /* Back on a side's last good edge: from its tail up the `above` chain,
+     * point each node's `below` at the pred whose bud* is the node under it. */
+    fn relink_below(&mut self, mut tmp: Vertex) {
+        while self.nodes[ix(tmp)].above != NONE {
+            let rc = ix(self.nodes[ix(tmp)].above);
+            for k in 0..self.nodes[rc].preds.len() {
+                let ri = self.nodes[rc].preds[k];
+                if ri == NONE { continue; }
+                if self.bud_star(ix(ri)) == ix(tmp) {
+                    self.nodes[rc].below = ri;
+                    break;
+                }
+            }
+            tmp = self.nodes[ix(tmp)].above;
+        }
+    }
+
     fn ddfs(&mut self, green_top: usize, red_top: usize) -> i32 {
         self.ddfs_nodes_seen.clear();
-        self.ddfs_bottleneck = NIL;
+        self.ddfs_bottleneck = NONE;
 
         self.red_stack.clear();
         self.green_stack.clear();
 
-        let mut g: i32 = NIL;
-        let mut r: i32 = NIL;
+        let mut g = NONE;
+        let mut r = NONE;
 
         if self.bud_star(red_top) == self.bud_star(green_top) { return DDFS_EMPTY; }
         if self.nodes[green_top].min_level == 0 && self.nodes[red_top].min_level == 0 {
             return DDFS_PATH;
         }
 
-        let mut ng: (i32, i32) = (NIL, green_top as i32);
-        let mut nr: (i32, i32) = (NIL, red_top as i32);
-        let mut red_before: (i32, i32) = (NIL, NIL);
-        let mut green_before: (i32, i32) = (NIL, NIL);
+        let mut ng: (Vertex, Vertex) = (NONE, vx(green_top));
+        let mut nr: (Vertex, Vertex) = (NONE, vx(red_top));
+        let mut red_before: (Vertex, Vertex) = (NONE, NONE);
+        let mut green_before: (Vertex, Vertex) = (NONE, NONE);
 
         loop {
             /* check termination */
-            if !(r == NIL || g == NIL ||
-                 self.nodes[r as usize].min_level > 0 ||
-                 self.nodes[g as usize].min_level > 0) {
+            if !(r == NONE || g == NONE ||
+                 self.nodes[ix(r)].min_level > 0 ||
+                 self.nodes[ix(g)].min_level > 0) {
                 break;
             }
 
@@ -458,19 +790,7 @@ impl MVGraph {
 
                 if !Self::edge_valid((nr.0, nr.1)) {
                     nr = red_before;
-                    let mut tmp = red_before.0;
-                    while self.nodes[tmp as usize].above != NIL {
-                        let rc = self.nodes[tmp as usize].above as usize;
-                        let preds = self.nodes[rc].preds.clone();
-                        for &ri in &preds {
-                            if ri == NIL { continue; }
-                            if self.bud_star(ri as usize) == tmp as usize {
-                                self.nodes[rc].below = ri;
-                                break;
-                            }
-                        }
-                        tmp = self.nodes[tmp as usize].above;
-                    }
+                    self.relink_below(red_before.0);
                 }
 
                 while Self::edge_valid((ng.0, ng.1))
@@ -482,24 +802,12 @@ impl MVGraph {
 
                 if !Self::edge_valid((ng.0, ng.1)) {
                     ng = green_before;
-                    let mut tmp = green_before.0;
-                    while self.nodes[tmp as usize].above != NIL {
-                        let rc = self.nodes[tmp as usize].above as usize;
-                        let preds = self.nodes[rc].preds.clone();
-                        for &ri in &preds {
-                            if ri == NIL { continue; }
-                            if self.bud_star(ri as usize) == tmp as usize {
-                                self.nodes[rc].below = ri;
-                                break;
-                            }
-                        }
-                        tmp = self.nodes[tmp as usize].above;
-                    }
+                    self.relink_below(green_before.0);
                 }
             }
 
             /* check collision */
-            if self.bud_star(nr.1 as usize) == self.bud_star(ng.1 as usize) {
+            if self.bud_star(ix(nr.1)) == self.bud_star(ix(ng.1)) {
                 if !self.red_stack.is_empty() {
                     red_before = nr;
                     self.prepare_next(&mut nr);
@@ -548,11 +856,11 @@ impl MVGraph {
     }
 
     fn walk_down_path(&mut self, start: usize) {
-        let mut cur = start as i32;
-        while cur != NIL {
-            let c = cur as usize;
-            if self.nodes[c].bud != NIL {
-                cur = self.walk_blossom(c) as i32;
+        let mut cur = vx(start);
+        while cur != NONE {
+            let c = ix(cur);
+            if self.nodes[c].bud != NONE {
+                cur = vx(self.walk_blossom(c));
             } else {
                 self.path_found.push(c);
                 cur = self.nodes[c].below;
@@ -560,72 +868,116 @@ impl MVGraph {
         }
     }
 
-    fn jump_bridge(&mut self, cur: usize) -> usize {
-        let dg = self.nodes[cur].ddfs_green;
-        let dr = self.nodes[cur].ddfs_red;
-
-        if dg == cur as i32 { return dr as usize; }
-        if dr == cur as i32 { return dg as usize; }
-
-        if self.bud_star_includes(dg as usize, cur) {
-            let before = self.path_found.len();
-            let mut b = dg as usize;
-            while b != cur { b = self.walk_blossom(b); }
-            self.path_found[before..].reverse();
-            return dr as usize;
-        } else {
-            let before = self.path_found.len();
-            let mut b = dr as usize;
-            while b != cur { b = self.walk_blossom(b); }
-            self.path_found[before..].reverse();
-            return dg as usize;
-        }
-    }
-
+    /* The path through the petal (blossom) cur lies in, from cur down to
+     * the petal's bud, appended to path_found; returns the bud. A petal
+     * walked across holds the petals nested in it, walked the same way,
+     * so the walk runs on an explicit stack of frames, as expand_blossom
+     * and augment_blossom do in the Edmonds solvers: recursion overflowed
+     * the stack on long chains of nested petals. */
     fn walk_blossom(&mut self, cur: usize) -> usize {
-        if self.nodes[cur].outer() {
-            self.walk_blossom_down(cur, NIL)
-        } else {
-            let top = self.walk_blossom_up(cur);
-            let before = top;
-            let jumped = self.jump_bridge(top);
-            self.walk_blossom_down(jumped, before as i32)
-        }
-    }
-
-    fn walk_blossom_down(&mut self, cur: usize, before: i32) -> usize {
-        let before_node = if before == NIL { cur } else { before as usize };
-        let b = self.nodes[cur].bud;
-        let mut c = cur as i32;
-        while c != NIL && c != b {
-            let cu = c as usize;
-            if self.nodes[cu].ddfs_green != self.nodes[before_node].ddfs_green ||
-               self.nodes[cu].ddfs_red != self.nodes[before_node].ddfs_red {
-                c = self.walk_blossom(cu) as i32;
-            } else {
-                self.path_found.push(cu);
-                c = self.nodes[cu].below;
-            }
+        #[derive(Clone, Copy)]
+        enum Frame {
+            /* a petal entered at cur */
+            Petal { cur: usize },
+            /* up from c along `above` to the top of the petal, which goes
+             * to the Jump frame below */
+            Up { c: usize },
+            /* across the bridge from top: `other` is the side left to walk
+             * down once the side holding top has been walked */
+            Jump { top: usize, other: Option<usize> },
+            /* down from c along `below` to bud, stepping into the petals
+             * whose DDFS colors differ from those of `before` */
+            Down { c: Vertex, bud: Vertex, before: usize },
+            /* b = walk_blossom(b) until goal, then path_found[from..]
+             * reversed */
+            Chain { b: usize, goal: usize, from: usize },
         }
-        c as usize
-    }
-
-    fn walk_blossom_up(&mut self, cur: usize) -> usize {
-        let mut c = cur;
-        loop {
-            self.path_found.push(c);
-            if self.nodes[c].above == NIL { break; }
-            let b_node = self.nodes[c].above as usize;
-            let b = self.nodes[b_node].below;
-            if b != c as i32 && self.bud_star_includes(b as usize, c) {
-                let before = self.path_found.len();
-                let mut bb = b as usize;
-                while bb != c { bb = self.walk_blossom(bb); }
-                self.path_found[before..].reverse();
-            }
-            c = self.nodes[c].above as usize;
+        let mut stack = vec![Frame::Petal { cur }];
+        while let Some(&frame) = stack.last() {
+            let si = stack.len() - 1;
+            match frame {
+                Frame::Petal { cur } => {
+                    if self.nodes[cur].outer() {
+                        stack[si] = Frame::Down { c: vx(cur), bud: self.nodes[cur].bud, before: cur };
+                    } else {
+                        stack[si] = Frame::Jump { top: cur, other: None };
+                        stack.push(Frame::Up { c: cur });
+                    }
+                }
+                Frame::Up { mut c } => {
+                    loop {
+                        self.path_found.push(c);
+                        let above = self.nodes[c].above;
+                        if above == NONE {
+                            stack.pop();
+                            if let Some(Frame::Jump { top, .. }) = stack.last_mut() {
+                                *top = c;
+                            }
+                            break;
+                        }
+                        let b = self.nodes[ix(above)].below;
+                        if b != vx(c) && self.bud_star_includes(ix(b), c) {
+                            /* resumed past the chain, at the petal above */
+                            stack[si] = Frame::Up { c: ix(above) };
+                            stack.push(Frame::Chain { b: ix(b), goal: c, from: self.path_found.len() });
+                            break;
+                        }
+                        c = ix(above);
+                    }
+                }
+                Frame::Jump { top, other: None } => {
+                    let dg = self.nodes[top].ddfs_green;
+                    let dr = self.nodes[top].ddfs_red;
+                    if dg == vx(top) || dr == vx(top) {
+                        let side = ix(if dg == vx(top) { dr } else { dg });
+                        stack[si] = Frame::Down { c: vx(side), bud: self.nodes[side].bud, before: top };
+                    } else {
+                        let (first, other) = if self.bud_star_includes(ix(dg), top) { (dg, dr) } else { (dr, dg) };
+                        stack[si] = Frame::Jump { top, other: Some(ix(other)) };
+                        stack.push(Frame::Chain { b: ix(first), goal: top, from: self.path_found.len() });
+                    }
+                }
+                Frame::Jump { top, other: Some(side) } => {
+                    stack[si] = Frame::Down { c: vx(side), bud: self.nodes[side].bud, before: top };
+                }
+                Frame::Down { mut c, bud, before } => {
+                    let mut nested = false;
+                    while c != NONE && c != bud {
+                        let cu = ix(c);
+                        if self.nodes[cu].ddfs_green != self.nodes[before].ddfs_green ||
+                           self.nodes[cu].ddfs_red != self.nodes[before].ddfs_red {
+                            /* c is set to the nested petal's bud when it returns */
+                            stack[si] = Frame::Down { c, bud, before };
+                            stack.push(Frame::Petal { cur: cu });
+                            nested = true;
+                            break;
+                        }
+                        self.path_found.push(cu);
+                        c = self.nodes[cu].below;
+                    }
+                    if nested {
+                        continue;
+                    }
+                    /* the petal is walked: hand its bud to the frame that
+                     * stepped into it */
+                    stack.pop();
+                    match stack.last_mut() {
+                        Some(Frame::Down { c: parent, .. }) => *parent = c,
+                        Some(Frame::Chain { b, .. }) => *b = ix(c),
+                        _ => return ix(c),
+                    }
+                }
+                Frame::Chain { b, goal, from } => {
+                    if b != goal {
+                        stack.push(Frame::Petal { cur: b });
+                    } else {
+                        self.path_found[from..].reverse();
+                        stack.pop();
+                    }
+                }
+            }
         }
-        c
+        unreachable!("the walk ends in its outermost Down frame")
     }
 
     fn augment_path(&mut self) {
@@ -633,8 +985,8 @@ impl MVGraph {
         while i + 1 < self.path_found.len() {
             let n1 = self.path_found[i];
             let n2 = self.path_found[i + 1];
-            self.nodes[n1].match_ = n2 as i32;
-            self.nodes[n2].match_ = n1 as i32;
+            self.nodes[n1].match_ = vx(n2);
+            self.nodes[n2].match_ = vx(n1);
             i += 2;
         }
         self.matchnum += 1;
@@ -644,10 +996,11 @@ impl MVGraph {
         while let Some(current) = self.path_found.pop() {
             if !self.nodes[current].deleted {
                 self.nodes[current].deleted = true;
-                let pred_to = self.nodes[current].pred_to.clone();
-                for &(target, idx) in &pred_to {
+                for k in 0..self.nodes[current].pred_to.len() {
+                    let (target, idx) = self.nodes[current].pred_to[k];
+                    let (target, idx) = (ix(target), idx as usize);
                     if !self.nodes[target].deleted {
-                        self.nodes[target].preds[idx] = NIL;
+                        self.nodes[target].preds[idx] = NONE;
                         self.nodes[target].number_preds -= 1;
                         if self.nodes[target].number_preds <= 0 {
                             self.path_found.push(target);
@@ -659,39 +1012,57 @@ impl MVGraph {
     }
 
     /* ---- main matching driver ---- */
-    fn max_match(&mut self) {
+    pub(crate) fn max_match(&mut self) {
         let n = self.nodes.len();
         for i in 0..n {
-            if self.nodes[i].match_ == NIL {
+            if self.nodes[i].match_ == NONE {
                 self.add_to_level(0, i);
                 self.nodes[i].set_min_level(0);
             }
         }
-        let mut found = self.max_match_phase();
-        while self.nodes.len() / 2 > self.matchnum && found {
-            self.phase_reset();
-            found = self.max_match_phase();
+        let start = self.matchnum;
+        if let Some(t) = self.trace {
+            /* find_path lists each path in order, so no copy of the mates is kept */
+            trace::begin(t, n, &|v| self.mate(v));
+        }
+        let mut phase = 0;
+        while !self.stopped() && (phase == 0 || self.nodes.len() / 2 > self.matchnum) {
+            if phase > 0 { self.phase_reset(); }
+            let found = self.max_match_phase();
+            phase += 1;
+            if let Some(report) = self.progress {
+                report(phase, self.matchnum - start, self.nodes.len() - 2 * self.matchnum);
+            }
+            if let Some(t) = self.trace {
+                t("phase", &[phase]);
+            }
+            if !found { break; }
         }
     }
 
+    /* Search level i augments along paths of tenacity (length) 2i+1; with
+     * max_path the levels past it are not searched and the phase finds
+     * nothing. */
     fn max_match_phase(&mut self) -> bool {
         let n = self.nodes.len();
-        let mut found = false;
-        for i in 0..(n / 2 + 1) {
-            if !found {
-                if self.todonum <= 0 && self.bridgenum <= 0 { return false; }
-                self.min_phase(i);
-                found = self.max_phase(i);
+        let before = self.matchnum;
+        let levels = self.max_path.map_or(n / 2 + 1, |l| l.div_ceil(2).min(n / 2 + 1));
+        for i in 0..levels {
+            if self.todonum <= 0 && self.bridgenum <= 0 { return false; }
+            self.min_phase(i);
+            if self.max_phase(i) {
+                self.phases.push((2 * i + 1, self.matchnum - before));
+                return true;
             }
         }
-        found
+        false
     }
 
-    fn get_matching(&self) -> Vec<(usize, usize)> {
+    pub(crate) fn get_matching(&self) -> Vec<(usize, usize)> {
         let mut result = Vec::new();
         for i in 0..self.nodes.len() {
-            if self.nodes[i].match_ != NIL && (self.nodes[i].match_ as usize) > i {
-                result.push((i, self.nodes[i].match_ as usize));
+            if self.nodes[i].match_ != NONE && ix(self.nodes[i].match_) > i {
+                result.push((i, ix(self.nodes[i].match_)));
             }
         }
         result
@@ -702,53 +1073,6 @@ impl MVGraph {
  * File I/O, validation, and main
  * ========================================================================= */
 
-fn load_graph(filename: &str) -> Result<(usize, Vec<(usize, usize)>), Box<dyn std::error::Error>> {
-    let file = File::open(filename)?;
-    let reader = BufReader::new(file);
-    let mut lines = reader.lines();
-
-    let first_line = lines.next().ok_or("Empty file")??;
-    let parts: Vec<&str> = first_line.split_whitespace().collect();
-    let n: usize = parts[0].parse()?;
-    let m: usize = parts[1].parse()?;
-
-    let mut edges = Vec::with_capacity(m);
-    for line in lines {
-        let line = line?;
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 2 {
-            let u: usize = parts[0].parse()?;
-            let v: usize = parts[1].parse()?;
-            edges.push((u, v));
-        }
-    }
-
-    Ok((n, edges))
-}
-
-fn validate_matching(n: usize, matching: &[(usize, usize)]) {
-    let mut deg = vec![0i32; n];
-    let mut errors = 0;
-
-    for &(u, v) in matching {
-        deg[u] += 1;
-        deg[v] += 1;
-    }
-    for i in 0..n {
-        if deg[i] > 1 {
-            eprintln!("ERROR: Vertex {} in {} edges!", i, deg[i]);
-            errors += 1;
-        }
-    }
-    let matched = deg.iter().filter(|&&d| d > 0).count();
-
-    println!("\n=== Validation Report ===");
-    println!("Matching size: {}", matching.len());
-    println!("Matched vertices: {}", matched);
-    println!("{}", if errors > 0 { "VALIDATION FAILED" } else { "VALIDATION PASSED" });
-    println!("=========================\n");
-}
-
 fn main() {
     println!("Micali-Vazirani Pure Algorithm - Rust Implementation");
     println!("=====================================================");
@@ -756,42 +1080,264 @@ fn main() {
 
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
-        eprintln!("Usage: {} <filename> [--greedy|--greedy-md]", args[0]);
+        eprintln!("Usage: {} <filename> [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} {} {} {} {} {} {} {} {} {} {} {} {}", args[0],
+                  args::AUG3_USAGE, progress::PROGRESS_USAGE, time_limit::TIME_LIMIT_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, args::EPSILON_USAGE, phase0::PHASE0_USAGE, ordering::REORDER_USAGE, phase_stats::PHASE_STATS_USAGE,
+                  matching_io::INIT_USAGE, matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
         std::process::exit(1);
     }
 
-    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-md") { 2 } else if args.iter().any(|a| a == "--greedy") { 1 } else { 0 };
-    match load_graph(&args[1]) {
-        Ok((n, edges)) => {
+    let greedy_mode: i32 = if args.iter().any(|a| a == "--greedy-random") {
+        4
+    } else if args.iter().any(|a| a == "--greedy-ks") {
+        3
+    } else if args.iter().any(|a| a == "--greedy-md") {
+        2
+    } else if args.iter().any(|a| a == "--greedy") {
+        1
+    } else {
+        0
+    };
+    let seed = match args::seed_of(&args) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let aug3 = match args::aug3_of(&args) {
+        Ok(k) => k,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* --init-aug3 alone runs after the plain greedy start */
+    let greedy_mode = if greedy_mode == 0 && aug3 > 0 { 1 } else { greedy_mode };
+    let max_path = match args::epsilon_of(&args) {
+        Ok(e) => e.map(args::max_path_length),
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let time_limit = match time_limit::time_limit_of(&args) {
+        Ok(t) => t,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let svg_layout = match svg::Layout::from_args(&args) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let show_phases = args::has_flag(&args, "--phase-stats");
+    let phase0_mode = phase0::Mode::from_args(&args);
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let reorder = match ordering::Reorder::from_args(&args) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    /* The trace is in the solver's ids, which phase 0 and --reorder change. */
+    if args::value_of(&args, "--trace").is_some()
+        && (phase0_mode != phase0::Mode::Off || reorder != ordering::Reorder::Natural) {
+        eprintln!("Error: --trace does not combine with --phase0, --crown, --fold or --reorder");
+        std::process::exit(1);
+    }
+
+    match graph_io::load_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
             println!("Graph: {} vertices, {} edges", n, edges.len());
+            if args::value_of(&args, "--svg").is_some() {
+                if let Err(e) = svg::check_size(n) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            let init = match matching_io::init_from_args(&args, (n, n), &edges, false, &names) {
+                Ok(i) => i,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+            if init.is_some() && phase0_mode != phase0::Mode::Off {
+                eprintln!("Error: --init-matching does not combine with --phase0, --crown or --fold");
+                std::process::exit(1);
+            }
+
+            /* Phase 0 counts towards the solve time; the solver and
+             * --reorder see only the components left over. */
+            let phase0_start = Instant::now();
+            let split = phase0::run(phase0_mode, n, &edges);
+            let phase0_time = phase0_start.elapsed();
+            /* Pairs fixed before the solver runs count as initial matching. */
+            let phase0_pairs = split.as_ref().map_or(0, |s| s.stats.solved_pairs);
+            /* the input graph, to validate the merged matching against */
+            let input_edges = split.as_ref().map(|_| edges.clone());
+            /* the solver may take the edges over; the drawings need them after */
+            let drawn_edges = (args::value_of(&args, "--dot").is_some() || args::value_of(&args, "--svg").is_some())
+                .then(|| edges.clone());
+            let (sn, sedges) = match split {
+                Some(ref s) => {
+                    println!("Phase 0: {} ({} ms)", s.summary(), phase0_time.as_millis());
+                    (s.rest_n, s.rest_edges.clone())
+                }
+                None => (n, edges),
+            };
+
+            let reorder_start = Instant::now();
+            let relabel = ordering::relabeling(reorder, sn, &sedges);
+            let sedges = match relabel {
+                Some(ref r) => r.apply(&sedges),
+                None => sedges,
+            };
+            if relabel.is_some() {
+                println!("Reorder: {} ({} ms)", reorder.name(), reorder_start.elapsed().as_millis());
+            }
 
             let start = Instant::now();
             let mut mv = MVGraph::new();
-            mv.build(n, &edges);
+            mv.build(sn, &sedges);
+            if args::has_flag(&args, "--progress") {
+                progress::start();
+                mv.progress = Some(&progress::report);
+            }
+            if let Some(limit) = time_limit {
+                time_limit::start(limit, &time_limit::EXPIRED);
+                mv.stop = Some(&time_limit::EXPIRED);
+            }
+            if let Some(path) = args::value_of(&args, "--trace") {
+                if let Err(e) = trace::open(path, &format!("mv-pure {}", args[1])) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+                mv.trace = Some(&trace::record);
+            } else if log::enabled(log::Level::Debug) {
+                mv.trace = Some(&log::event);
+            }
+            mv.max_path = max_path;
+            if let Some(ref pairs) = init {
+                let pairs = match relabel {
+                    Some(ref r) => r.apply(pairs),
+                    None => pairs.clone(),
+                };
+                mv.set_mate(&types::mate_of(sn, &pairs));
+            }
             let greedy_count: usize = match greedy_mode {
                 1 => mv.greedy_init(),
                 2 => mv.greedy_init_md(),
+                3 => mv.greedy_init_ks(),
+                4 => mv.greedy_init_random(seed),
                 _ => 0,
             };
+            let aug3_count = if aug3 > 0 { mv.augment3(aug3) } else { 0 };
+            let greedy_count = greedy_count + aug3_count;
             mv.max_match();
-            let duration = start.elapsed();
+            let duration = start.elapsed() + phase0_time;
+            let gap = time_limit.filter(|_| time_limit::expired())
+                .map(|limit| (limit, time_limit::gap_bound(sn, &sedges, &mv.get_matching())));
 
             let matching = mv.get_matching();
-            validate_matching(n, &matching);
+            let restore = |m: Vec<(usize, usize)>| match relabel {
+                Some(ref r) => r.restore(&m),
+                None => m,
+            };
+            /* with the Tutte-Berge certificate under --certify, of the graph validated against */
+            let certify = args::has_flag(&args, "--certify");
+            let validate = |n: usize, edges: &[(usize, usize)], m: &[(usize, usize)]| {
+                let mut report = verify::validate_general(n, edges, m);
+                if certify && report.is_valid() {
+                    report.optimal = Some(vertex_classes::certify(n, edges, m));
+                }
+                report
+            };
+            let (matching, report) = match (&split, &input_edges) {
+                /* Merged into original ids, validated against the input. */
+                (Some(s), Some(input)) => {
+                    let merged = s.merge(&restore(matching));
+                    let report = validate(n, input, &merged);
+                    (merged, report)
+                }
+                /* Validated in the solver's labeling, against its own graph. */
+                _ => {
+                    let report = validate(sn, &sedges, &matching);
+                    (restore(matching), report)
+                }
+            };
+            report.print();
+            if show_phases {
+                phase_stats::write_phase_stats(&mut std::io::stdout(), &mv.phases).unwrap();
+            }
 
             println!("Matching size: {}", matching.len());
+            if let Some(l) = max_path {
+                println!("Approximation: augmenting paths of at most {} edges (at least {:.2}% of the maximum)",
+                         l, 100.0 * args::approximation_ratio(l));
+            }
             if greedy_mode > 0 {
-                let gs = greedy_count;
+                let gs = greedy_count + phase0_pairs;
                 let fs = matching.len();
                 println!("Greedy init size: {}", gs);
+                if aug3 > 0 {
+                    println!("Aug3 augmentations: {}", aug3_count);
+                }
                 if fs > 0 { println!("Greedy/Final: {:.2}%", 100.0 * gs as f64 / fs as f64); }
                 else { println!("Greedy/Final: NA"); }
             }
+            if let Some(ref pairs) = init {
+                matching_io::write_init(&mut std::io::stdout(), pairs.len(), matching.len()).unwrap();
+            }
+            if let Err(e) = matching_io::save_from_args(&args, &matching, false, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Some((limit, gap)) = gap {
+                time_limit::write_report(&mut std::io::stdout(), limit, gap).unwrap();
+            }
+            if let Some(path) = args::value_of(&args, "--dot") {
+                if let Err(e) = dot::save(path, n, drawn_edges.as_ref().unwrap(), &matching, None) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Some(path) = args::value_of(&args, "--svg") {
+                if let Err(e) = svg::save(path, n, drawn_edges.as_ref().unwrap(), &matching, None, svg_layout) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if args::value_of(&args, "--trace").is_some() {
+                if let Err(e) = trace::finish(matching.len()) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
             println!("Time: {} ms", duration.as_millis());
         }
         Err(e) => {
             eprintln!("Error: {}", e);
-            std::process::exit(1);
+            std::process::exit(e.exit_code());
         }
     }
 }
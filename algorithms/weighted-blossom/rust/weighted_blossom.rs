@@ -0,0 +1,890 @@
+/*
+ * Edmonds' Weighted Blossom Algorithm — Maximum Weight Matching
+ *
+ * Rust implementation of the primal-dual method of Edmonds (1965) with
+ * Galil's O(V^3) bookkeeping (Galil 1986): S-blossoms keep the least-slack
+ * edge to every neighbouring S-blossom, so each dual update costs O(V).
+ * The structure follows Joris van Rantwijk's reference implementation.
+ *
+ * Edge endpoints are numbered p = 2k (first end of edge k) and 2k + 1
+ * (second end); p ^ 1 is the opposite end. mate[v] holds the remote
+ * endpoint of v's matched edge. Weights are integers: every dual variable
+ * stays integral, so there is no rounding.
+ *
+ * With max_cardinality the result is a maximum-weight matching among the
+ * maximum-cardinality ones; min_weight_max_cardinality_matching (--minimize)
+ * and min_weight_perfect_matching build on that.
+ *
+ * max_weight_matching_with_duals also returns the final duals (Duals),
+ * and check_duals verifies complementary slackness against them, a proof
+ * of optimality (--check-duals).
+ *
+ * Complexity: O(V^3) time, O(V + E) space.
+ */
+
+use std::env;
+use std::time::Instant;
+
+#[path = "../../common/rust/args.rs"]
+mod args;
+#[path = "../../common/rust/error.rs"]
+mod error;
+#[path = "../../common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../common/rust/json.rs"]
+mod json;
+#[path = "../../common/rust/log.rs"]
+mod log;
+#[path = "../../common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../common/rust/verify.rs"]
+mod verify;
+
+const NIL: usize = usize::MAX;
+
+/* Labels of top-level blossoms (and of the vertices that carry them). */
+const FREE: u8 = 0;
+const S: u8 = 1;
+const T: u8 = 2;
+const BREADCRUMB: u8 = 4; /* set temporarily by scan_blossom */
+
+pub(crate) struct WeightedBlossom {
+    nvertex: usize,
+    edges: Vec<(usize, usize, i64)>,
+    endpoint: Vec<usize>,
+    neighbend: Vec<Vec<usize>>,
+    mate: Vec<usize>,
+
+    /* indexed by vertex or blossom id (blossoms are nvertex .. 2 * nvertex) */
+    label: Vec<u8>,
+    labelend: Vec<usize>,
+    inblossom: Vec<usize>,
+    blossomparent: Vec<usize>,
+    blossomchilds: Vec<Vec<usize>>,
+    blossombase: Vec<usize>,
+    blossomendps: Vec<Vec<usize>>,
+    bestedge: Vec<usize>,
+    blossombestedges: Vec<Option<Vec<usize>>>,
+    unusedblossoms: Vec<usize>,
+    pub(crate) dualvar: Vec<i64>,
+    allowedge: Vec<bool>,
+    queue: Vec<usize>,
+}
+
+/* Python-style index into a cyclic list (j may be negative). */
+fn at(list: &[usize], j: isize) -> usize {
+    let len = list.len() as isize;
+    list[(((j % len) + len) % len) as usize]
+}
+
+impl WeightedBlossom {
+    /* Self-loops and out-of-range endpoints are dropped; parallel edges are
+     * kept (an optimum uses the best of them). */
+    pub(crate) fn new(n: usize, edges: &[(usize, usize, i64)]) -> Self {
+        let edges: Vec<(usize, usize, i64)> = edges.iter()
+            .cloned()
+            .filter(|&(u, v, _)| u < n && v < n && u != v)
+            .collect();
+        let nedge = edges.len();
+        let maxweight = edges.iter().map(|e| e.2).max().unwrap_or(0).max(0);
+        let mut endpoint = Vec::with_capacity(2 * nedge);
+        let mut neighbend = vec![Vec::new(); n];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            endpoint.push(i);
+            endpoint.push(j);
+            neighbend[i].push(2 * k + 1);
+            neighbend[j].push(2 * k);
+        }
+        let mut dualvar = vec![maxweight; n];
+        dualvar.extend(vec![0; n]);
+        let mut blossombase: Vec<usize> = (0..n).collect();
+        blossombase.extend(vec![NIL; n]);
+
+        WeightedBlossom {
+            nvertex: n,
+            edges,
+            endpoint,
+            neighbend,
+            mate: vec![NIL; n],
+            label: vec![FREE; 2 * n],
+            labelend: vec![NIL; 2 * n],
+            inblossom: (0..n).collect(),
+            blossomparent: vec![NIL; 2 * n],
+            blossomchilds: vec![Vec::new(); 2 * n],
+            blossombase,
+            blossomendps: vec![Vec::new(); 2 * n],
+            bestedge: vec![NIL; 2 * n],
+            blossombestedges: vec![None; 2 * n],
+            unusedblossoms: (n..2 * n).collect(),
+            dualvar,
+            allowedge: vec![false; nedge],
+            queue: Vec::new(),
+        }
+    }
+
+    fn slack(&self, k: usize) -> i64 {
+        let (i, j, w) = self.edges[k];
+        self.dualvar[i] + self.dualvar[j] - 2 * w
+    }
+
+    fn blossom_leaves(&self, b: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![b];
+        while let Some(t) = stack.pop() {
+            if t < self.nvertex {
+                out.push(t);
+            } else {
+                stack.extend(self.blossomchilds[t].iter().rev());
+            }
+        }
+        out
+    }
+
+    /* Label w's top-level blossom t (S or T) via endpoint p; a T-blossom
+     * passes an S label on to its mate. */
+    fn assign_label(&mut self, mut w: usize, mut t: u8, mut p: usize) {
+        loop {
+            let b = self.inblossom[w];
+            debug_assert!(self.label[w] == FREE && self.label[b] == FREE);
+            self.label[w] = t;
+            self.label[b] = t;
+            self.labelend[w] = p;
+            self.labelend[b] = p;
+            self.bestedge[w] = NIL;
+            self.bestedge[b] = NIL;
+            if t == S {
+                let leaves = self.blossom_leaves(b);
+                self.queue.extend(leaves);
+                return;
+            }
+            let base = self.blossombase[b];
+            debug_assert!(self.mate[base] != NIL);
+            let mb = self.mate[base];
+            w = self.endpoint[mb];
+            t = S;
+            p = mb ^ 1;
+        }
+    }
+
+    /* Trace back from v and w to the roots; the first common blossom is the
+     * base of a new blossom, NIL means an augmenting path. */
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = Vec::new();
+        let mut base = NIL;
+        while v != NIL || w != NIL {
+            let mut b = self.inblossom[v];
+            if self.label[b] & BREADCRUMB != 0 {
+                base = self.blossombase[b];
+                break;
+            }
+            debug_assert!(self.label[b] == S);
+            path.push(b);
+            self.label[b] = S | BREADCRUMB;
+            if self.labelend[b] == NIL {
+                v = NIL;
+            } else {
+                v = self.endpoint[self.labelend[b]];
+                b = self.inblossom[v];
+                debug_assert!(self.label[b] == T);
+                v = self.endpoint[self.labelend[b]];
+            }
+            if w != NIL {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = S;
+        }
+        base
+    }
+
+    /* New blossom with the given base, closed by edge k between two
+     * S-vertices. */
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let bb = self.inblossom[base];
+        let mut bv = self.inblossom[v];
+        let mut bw = self.inblossom[w];
+        let b = self.unusedblossoms.pop().expect("out of blossom ids");
+        self.blossombase[b] = base;
+        self.blossomparent[b] = NIL;
+        self.blossomparent[bb] = b;
+
+        let mut path = Vec::new();
+        let mut endps = Vec::new();
+        while bv != bb {
+            self.blossomparent[bv] = b;
+            path.push(bv);
+            endps.push(self.labelend[bv]);
+            v = self.endpoint[self.labelend[bv]];
+            bv = self.inblossom[v];
+        }
+        path.push(bb);
+        path.reverse();
+        endps.reverse();
+        endps.push(2 * k);
+        while bw != bb {
+            self.blossomparent[bw] = b;
+            path.push(bw);
+            endps.push(self.labelend[bw] ^ 1);
+            w = self.endpoint[self.labelend[bw]];
+            bw = self.inblossom[w];
+        }
+        debug_assert!(self.label[bb] == S);
+        self.blossomchilds[b] = path.clone();
+        self.blossomendps[b] = endps;
+        self.label[b] = S;
+        self.labelend[b] = self.labelend[bb];
+        self.dualvar[b] = 0;
+        for v in self.blossom_leaves(b) {
+            if self.label[self.inblossom[v]] == T {
+                /* former T-vertices become S-vertices */
+                self.queue.push(v);
+            }
+            self.inblossom[v] = b;
+        }
+
+        /* Least-slack edge from the new blossom to each neighbouring
+         * S-blossom. */
+        let mut bestedgeto = vec![NIL; 2 * self.nvertex];
+        for &bv in &path {
+            let nblists: Vec<Vec<usize>> = match self.blossombestedges[bv].take() {
+                Some(list) => vec![list],
+                None => self.blossom_leaves(bv).iter()
+                    .map(|&v| self.neighbend[v].iter().map(|&p| p / 2).collect())
+                    .collect(),
+            };
+            for nblist in nblists {
+                for k in nblist {
+                    let (i, mut j, _) = self.edges[k];
+                    if self.inblossom[j] == b {
+                        j = i;
+                    }
+                    let bj = self.inblossom[j];
+                    if bj != b && self.label[bj] == S
+                        && (bestedgeto[bj] == NIL || self.slack(k) < self.slack(bestedgeto[bj]))
+                    {
+                        bestedgeto[bj] = k;
+                    }
+                }
+            }
+            self.bestedge[bv] = NIL;
+        }
+        let best: Vec<usize> = bestedgeto.into_iter().filter(|&k| k != NIL).collect();
+        self.bestedge[b] = NIL;
+        for &k in &best {
+            if self.bestedge[b] == NIL || self.slack(k) < self.slack(self.bestedge[b]) {
+                self.bestedge[b] = k;
+            }
+        }
+        self.blossombestedges[b] = Some(best);
+    }
+
+    /* Expand top-level blossom b. Mid-stage (T-blossom with zero dual) the
+     * children on the even path to the entry child get relabeled. */
+    fn expand_blossom(&mut self, b: usize, endstage: bool) {
+        let childs = self.blossomchilds[b].clone();
+        for &s in &childs {
+            self.blossomparent[s] = NIL;
+            if s < self.nvertex {
+                self.inblossom[s] = s;
+            } else if endstage && self.dualvar[s] == 0 {
+                self.expand_blossom(s, endstage);
+            } else {
+                for v in self.blossom_leaves(s) {
+                    self.inblossom[v] = s;
+                }
+            }
+        }
+
+        if !endstage && self.label[b] == T {
+            let endps = self.blossomendps[b].clone();
+            let entrychild = self.inblossom[self.endpoint[self.labelend[b] ^ 1]];
+            let mut j = childs.iter().position(|&c| c == entrychild).unwrap() as isize;
+            let (jstep, endptrick): (isize, usize) = if j & 1 != 0 {
+                j -= childs.len() as isize;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            let back = |j: isize| j - endptrick as isize;
+            let mut p = self.labelend[b];
+            while j != 0 {
+                /* relabel the T-sub-blossom */
+                self.label[self.endpoint[p ^ 1]] = FREE;
+                self.label[self.endpoint[at(&endps, back(j)) ^ endptrick ^ 1]] = FREE;
+                let w = self.endpoint[p ^ 1];
+                self.assign_label(w, T, p);
+                /* step to the next S-sub-blossom and note its forward endpoint */
+                self.allowedge[at(&endps, back(j)) / 2] = true;
+                j += jstep;
+                p = at(&endps, back(j)) ^ endptrick;
+                /* step to the next T-sub-blossom */
+                self.allowedge[p / 2] = true;
+                j += jstep;
+            }
+            /* relabel the base T-sub-blossom without stepping through to its mate */
+            let bv = at(&childs, j);
+            let x = self.endpoint[p ^ 1];
+            self.label[x] = T;
+            self.label[bv] = T;
+            self.labelend[x] = p;
+            self.labelend[bv] = p;
+            self.bestedge[bv] = NIL;
+            /* continue along the blossom until back at the entry child */
+            j += jstep;
+            while at(&childs, j) != entrychild {
+                let bv = at(&childs, j);
+                if self.label[bv] == S {
+                    j += jstep;
+                    continue;
+                }
+                let reached = self.blossom_leaves(bv).into_iter().find(|&v| self.label[v] != FREE);
+                if let Some(v) = reached {
+                    debug_assert!(self.label[v] == T && self.inblossom[v] == bv);
+                    self.label[v] = FREE;
+                    let m = self.endpoint[self.mate[self.blossombase[bv]]];
+                    self.label[m] = FREE;
+                    let le = self.labelend[v];
+                    self.assign_label(v, T, le);
+                }
+                j += jstep;
+            }
+        }
+
+        self.label[b] = FREE;
+        self.labelend[b] = NIL;
+        self.blossomchilds[b] = Vec::new();
+        self.blossomendps[b] = Vec::new();
+        self.blossombase[b] = NIL;
+        self.blossombestedges[b] = None;
+        self.bestedge[b] = NIL;
+        self.unusedblossoms.push(b);
+    }
+
+    /* Swap matched/unmatched edges along the even path from vertex v to the
+     * base of blossom b, making v the new base. */
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossomparent[t] != b {
+            t = self.blossomparent[t];
+        }
+        if t >= self.nvertex {
+            self.augment_blossom(t, v);
+        }
+        let childs = self.blossomchilds[b].clone();
+        let endps = self.blossomendps[b].clone();
+        let i = childs.iter().position(|&c| c == t).unwrap();
+        let mut j = i as isize;
+        let (jstep, endptrick): (isize, usize) = if i & 1 != 0 {
+            j -= childs.len() as isize;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        while j != 0 {
+            j += jstep;
+            let t = at(&childs, j);
+            let p = at(&endps, j - endptrick as isize) ^ endptrick;
+            if t >= self.nvertex {
+                let e = self.endpoint[p];
+                self.augment_blossom(t, e);
+            }
+            j += jstep;
+            let t = at(&childs, j);
+            if t >= self.nvertex {
+                let e = self.endpoint[p ^ 1];
+                self.augment_blossom(t, e);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+        /* rotate so that the child containing v comes first */
+        self.blossomchilds[b].rotate_left(i);
+        self.blossomendps[b].rotate_left(i);
+        self.blossombase[b] = self.blossombase[self.blossomchilds[b][0]];
+        debug_assert!(self.blossombase[b] == v);
+    }
+
+    /* Augment along the path through edge k between two S-vertices. */
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for &(mut s, mut p) in &[(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.inblossom[s];
+                debug_assert!(self.label[bs] == S);
+                if bs >= self.nvertex {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.labelend[bs] == NIL {
+                    break; /* reached a root */
+                }
+                let t = self.endpoint[self.labelend[bs]];
+                let bt = self.inblossom[t];
+                debug_assert!(self.label[bt] == T);
+                s = self.endpoint[self.labelend[bt]];
+                let j = self.endpoint[self.labelend[bt] ^ 1];
+                if bt >= self.nvertex {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.labelend[bt];
+                p = self.labelend[bt] ^ 1;
+            }
+        }
+    }
+
+    /* One stage: grow the forest and adjust duals until an augmentation
+     * (true) or no further improvement is possible (false). */
+    fn stage(&mut self, max_cardinality: bool) -> bool {
+        let n = self.nvertex;
+        for x in self.label.iter_mut() { *x = FREE; }
+        for x in self.bestedge.iter_mut() { *x = NIL; }
+        for x in self.blossombestedges[n..].iter_mut() { *x = None; }
+        for x in self.allowedge.iter_mut() { *x = false; }
+        self.queue.clear();
+        for v in 0..n {
+            if self.mate[v] == NIL && self.label[self.inblossom[v]] == FREE {
+                self.assign_label(v, S, NIL);
+            }
+        }
+
+        loop {
+            while let Some(v) = self.queue.pop() {
+                debug_assert!(self.label[self.inblossom[v]] == S);
+                for idx in 0..self.neighbend[v].len() {
+                    let p = self.neighbend[v][idx];
+                    let k = p / 2;
+                    let w = self.endpoint[p];
+                    if self.inblossom[v] == self.inblossom[w] {
+                        continue;
+                    }
+                    let mut kslack = 0;
+                    if !self.allowedge[k] {
+                        kslack = self.slack(k);
+                        if kslack <= 0 {
+                            self.allowedge[k] = true;
+                        }
+                    }
+                    let bw = self.inblossom[w];
+                    if self.allowedge[k] {
+                        if self.label[bw] == FREE {
+                            self.assign_label(w, T, p ^ 1);
+                        } else if self.label[bw] == S {
+                            let base = self.scan_blossom(v, w);
+                            if base != NIL {
+                                self.add_blossom(base, k);
+                            } else {
+                                self.augment_matching(k);
+                                return true;
+                            }
+                        } else if self.label[w] == FREE {
+                            /* w is inside a T-blossom but not yet reached */
+                            self.label[w] = T;
+                            self.labelend[w] = p ^ 1;
+                        }
+                    } else if self.label[bw] == S {
+                        let b = self.inblossom[v];
+                        if self.bestedge[b] == NIL || kslack < self.slack(self.bestedge[b]) {
+                            self.bestedge[b] = k;
+                        }
+                    } else if self.label[w] == FREE
+                        && (self.bestedge[w] == NIL || kslack < self.slack(self.bestedge[w]))
+                    {
+                        self.bestedge[w] = k;
+                    }
+                }
+            }
+
+            /* Dual update: the smallest of the four bounds. */
+            let mut deltatype = 0;
+            let mut delta = 0;
+            let mut deltaedge = NIL;
+            let mut deltablossom = NIL;
+            if !max_cardinality {
+                deltatype = 1;
+                delta = *self.dualvar[..n].iter().min().unwrap_or(&0);
+            }
+            for v in 0..n {
+                if self.label[self.inblossom[v]] == FREE && self.bestedge[v] != NIL {
+                    let d = self.slack(self.bestedge[v]);
+                    if deltatype == 0 || d < delta {
+                        delta = d;
+                        deltatype = 2;
+                        deltaedge = self.bestedge[v];
+                    }
+                }
+            }
+            for b in 0..2 * n {
+                if self.blossomparent[b] == NIL && self.label[b] == S && self.bestedge[b] != NIL {
+                    let kslack = self.slack(self.bestedge[b]);
+                    debug_assert!(kslack % 2 == 0);
+                    let d = kslack / 2;
+                    if deltatype == 0 || d < delta {
+                        delta = d;
+                        deltatype = 3;
+                        deltaedge = self.bestedge[b];
+                    }
+                }
+            }
+            for b in n..2 * n {
+                if self.blossombase[b] != NIL && self.blossomparent[b] == NIL && self.label[b] == T
+                    && (deltatype == 0 || self.dualvar[b] < delta)
+                {
+                    delta = self.dualvar[b];
+                    deltatype = 4;
+                    deltablossom = b;
+                }
+            }
+            if deltatype == 0 {
+                /* max_cardinality: no augmenting path left */
+                deltatype = 1;
+                delta = (*self.dualvar[..n].iter().min().unwrap_or(&0)).max(0);
+            }
+
+            for v in 0..n {
+                match self.label[self.inblossom[v]] {
+                    S => self.dualvar[v] -= delta,
+                    T => self.dualvar[v] += delta,
+                    _ => {}
+                }
+            }
+            for b in n..2 * n {
+                if self.blossombase[b] != NIL && self.blossomparent[b] == NIL {
+                    match self.label[b] {
+                        S => self.dualvar[b] += delta,
+                        T => self.dualvar[b] -= delta,
+                        _ => {}
+                    }
+                }
+            }
+
+            match deltatype {
+                1 => return false,
+                2 => {
+                    self.allowedge[deltaedge] = true;
+                    let (mut i, j, _) = self.edges[deltaedge];
+                    if self.label[self.inblossom[i]] == FREE {
+                        i = j;
+                    }
+                    self.queue.push(i);
+                }
+                3 => {
+                    self.allowedge[deltaedge] = true;
+                    let (i, _, _) = self.edges[deltaedge];
+                    self.queue.push(i);
+                }
+                _ => self.expand_blossom(deltablossom, false),
+            }
+        }
+    }
+
+    /* Warm start (gabow_scaling.rs): vertex duals `duals`, feasible on
+     * every edge and all of one parity, and `pairs` as the matching, each
+     * pair joined by a tight edge. No blossoms. Call before the first
+     * stage, with max_cardinality: free vertices need not share a dual. */
+    #[allow(dead_code)]
+    pub(crate) fn warm_start(&mut self, duals: &[i64], pairs: &[(usize, usize)]) {
+        self.dualvar[..self.nvertex].copy_from_slice(duals);
+        for &(u, v) in pairs {
+            let p = self.neighbend[u].iter().cloned()
+                .find(|&p| self.endpoint[p] == v && self.slack(p / 2) == 0)
+                .expect("warm start pair is not a tight edge");
+            self.mate[u] = p;
+            self.mate[v] = p ^ 1;
+        }
+    }
+
+    /* Vertex duals with each blossom's dual pushed down to the vertices
+     * inside it: still feasible once every blossom is dissolved. */
+    #[allow(dead_code)]
+    pub(crate) fn liquidated_duals(&self) -> Vec<i64> {
+        (0..self.nvertex).map(|v| {
+            let mut d = self.dualvar[v];
+            let mut b = self.blossomparent[v];
+            while b != NIL {
+                d += self.dualvar[b];
+                b = self.blossomparent[b];
+            }
+            d
+        }).collect()
+    }
+
+    /* The current duals as the matching polytope's: every live blossom,
+     * nested ones included, by its vertices, with z_B = 2 * dualvar[b]. */
+    pub(crate) fn duals(&self) -> Duals {
+        let n = self.nvertex;
+        let blossoms = (n..2 * n)
+            .filter(|&b| self.blossombase[b] != NIL)
+            .map(|b| {
+                let mut leaves = self.blossom_leaves(b);
+                leaves.sort_unstable();
+                (leaves, 2 * self.dualvar[b])
+            })
+            .collect();
+        Duals { vertex: self.dualvar[..n].to_vec(), blossoms }
+    }
+
+    /* Maximum-weight matching as sorted (u, v) pairs, u < v. */
+    pub(crate) fn maximum_weight_matching(&mut self, max_cardinality: bool) -> Vec<(usize, usize)> {
+        let n = self.nvertex;
+        for _ in 0..n {
+            if !self.stage(max_cardinality) {
+                break;
+            }
+            /* end of stage: expand S-blossoms whose dual reached zero */
+            for b in n..2 * n {
+                if self.blossomparent[b] == NIL && self.blossombase[b] != NIL
+                    && self.label[b] == S && self.dualvar[b] == 0
+                {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+        let mut result = Vec::new();
+        for v in 0..n {
+            if self.mate[v] != NIL {
+                let u = self.endpoint[self.mate[v]];
+                if v < u { result.push((v, u)); }
+            }
+        }
+        result
+    }
+}
+
+/* The dual solution of the matching polytope LP (Edmonds 1965) that the
+ * solver ends with, doubled so that it stays integral: y_v for every
+ * vertex, z_B for every odd set B (a blossom, as its sorted vertices).
+ * The doubled slack of an edge uv is y_u + y_v + (the z_B of every B
+ * holding both u and v) - 2w; check_duals tells whether it proves the
+ * matching optimal. */
+pub(crate) struct Duals {
+    pub(crate) vertex: Vec<i64>,
+    pub(crate) blossoms: Vec<(Vec<usize>, i64)>,
+}
+
+/* A maximum-weight matching with the duals that certify it. */
+pub(crate) struct WeightedMatching {
+    pub(crate) pairs: Vec<(usize, usize)>,
+    pub(crate) duals: Duals,
+}
+
+/* Maximum-weight matching of (n, edges), with its duals. */
+pub(crate) fn max_weight_matching_with_duals(
+    n: usize, edges: &[(usize, usize, i64)], max_cardinality: bool,
+) -> WeightedMatching {
+    let mut solver = WeightedBlossom::new(n, edges);
+    let pairs = solver.maximum_weight_matching(max_cardinality);
+    WeightedMatching { pairs, duals: solver.duals() }
+}
+
+/* Maximum-weight matching of (n, edges). */
+#[allow(dead_code)]
+pub(crate) fn max_weight_matching(
+    n: usize, edges: &[(usize, usize, i64)], max_cardinality: bool,
+) -> Vec<(usize, usize)> {
+    WeightedBlossom::new(n, edges).maximum_weight_matching(max_cardinality)
+}
+
+/* The weights W + 1 - w (W the largest weight): all positive, and
+ * maximizing them over maximum-cardinality matchings minimizes the
+ * original weight. */
+pub(crate) fn flipped_weights(edges: &[(usize, usize, i64)]) -> Vec<(usize, usize, i64)> {
+    let top = edges.iter().map(|e| e.2).max().unwrap_or(0);
+    edges.iter().map(|&(u, v, w)| (u, v, top + 1 - w)).collect()
+}
+
+/* Minimum-weight matching of (n, edges) among the maximum-cardinality
+ * ones (the lightest of all matchings would be the empty one), through
+ * flipped_weights. */
+#[allow(dead_code)]
+pub(crate) fn min_weight_max_cardinality_matching(
+    n: usize, edges: &[(usize, usize, i64)],
+) -> Vec<(usize, usize)> {
+    max_weight_matching(n, &flipped_weights(edges), true)
+}
+
+/* Minimum-weight perfect matching of (n, edges), or None if the graph has
+ * no perfect matching. */
+#[allow(dead_code)]
+pub(crate) fn min_weight_perfect_matching(
+    n: usize, edges: &[(usize, usize, i64)],
+) -> Option<Vec<(usize, usize)>> {
+    let matching = min_weight_max_cardinality_matching(n, edges);
+    if 2 * matching.len() == n { Some(matching) } else { None }
+}
+
+/* Complementary slackness of result against (n, edges), the weights the
+ * solver maximized: the duals are feasible (every y_v and z_B >= 0, every
+ * edge slack >= 0), every matched pair is joined by a tight edge, every
+ * free vertex has y_v = 0, and every blossom with z_B > 0 is full (holds
+ * (|B| - 1) / 2 matched pairs). Together they prove the matching optimal
+ * by LP duality. With max_cardinality y may go negative; the check then
+ * raises every y_v and every weight by c = -min(y) (the slacks do not
+ * move): the certificate for the weights w + c, which proves the matching
+ * heaviest among those of its size (that the size is the largest is not
+ * part of it). The first violation as an error. */
+pub(crate) fn check_duals(
+    n: usize, edges: &[(usize, usize, i64)], result: &WeightedMatching, max_cardinality: bool,
+) -> Result<(), String> {
+    let duals = &result.duals;
+    let offset = if max_cardinality { (-duals.vertex.iter().cloned().min().unwrap_or(0)).max(0) } else { 0 };
+    let y = |v: usize| duals.vertex[v] + offset;
+    if let Some(v) = (0..n).find(|&v| y(v) < 0) {
+        return Err(format!("vertex {} has dual {}", v, y(v)));
+    }
+    /* holding[v]: the blossoms holding v, in ascending order */
+    let mut holding: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, &(ref leaves, z)) in duals.blossoms.iter().enumerate() {
+        if z < 0 {
+            return Err(format!("blossom {:?} has dual {}", leaves, z));
+        }
+        if leaves.len() < 3 || leaves.len() % 2 == 0 {
+            return Err(format!("blossom {:?} is not an odd set of 3 or more vertices", leaves));
+        }
+        for &v in leaves {
+            holding[v].push(i);
+        }
+    }
+    /* the blossoms holding both u and v */
+    let shared = |u: usize, v: usize| -> Vec<usize> {
+        holding[u].iter().cloned().filter(|b| holding[v].binary_search(b).is_ok()).collect()
+    };
+
+    let mut mate = vec![NIL; n];
+    for &(u, v) in &result.pairs {
+        mate[u] = v;
+        mate[v] = u;
+    }
+    let mut tight = vec![false; n];
+    for &(u, v, w) in edges.iter().filter(|&&(u, v, _)| u < n && v < n && u != v) {
+        let z: i64 = shared(u, v).iter().map(|&b| duals.blossoms[b].1).sum();
+        let slack = duals.vertex[u] + duals.vertex[v] + z - 2 * w;
+        if slack < 0 {
+            return Err(format!("edge ({}, {}, {}) has slack {}", u, v, w, slack));
+        }
+        if slack == 0 && mate[u] == v {
+            tight[u] = true;
+            tight[v] = true;
+        }
+    }
+    let mut inside = vec![0usize; duals.blossoms.len()];
+    for &(u, v) in &result.pairs {
+        if !tight[u] {
+            return Err(format!("matched pair ({}, {}) is not joined by a tight edge", u, v));
+        }
+        for b in shared(u, v) {
+            inside[b] += 1;
+        }
+    }
+    if let Some(v) = (0..n).find(|&v| mate[v] == NIL && y(v) != 0) {
+        return Err(format!("free vertex {} has dual {}", v, y(v)));
+    }
+    for (b, &(ref leaves, z)) in duals.blossoms.iter().enumerate() {
+        if z > 0 && 2 * inside[b] + 1 != leaves.len() {
+            return Err(format!("blossom {:?} has dual {} but only {} matched pairs inside", leaves, z, inside[b]));
+        }
+    }
+    Ok(())
+}
+
+// ── Validation and main ──────────────────────────────────────────────
+
+fn main() {
+    println!("Edmonds' Weighted Blossom Algorithm - Rust Implementation");
+    println!("==========================================================\n");
+
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Usage: {} <filename> [--maximize|--minimize] [--max-cardinality|--min-perfect] [--check-duals] {} {} {} {}", args[0],
+                  matching_io::OUTPUT_USAGE, verify::REPORT_USAGE, log::LOG_USAGE, graph_io::LOAD_USAGE);
+        std::process::exit(1);
+    }
+    let max_card = args::has_flag(&args, "--max-cardinality");
+    let min_perfect = args::has_flag(&args, "--min-perfect");
+    let minimize = args::has_flag(&args, "--minimize");
+    let check = args::has_flag(&args, "--check-duals");
+    if max_card && min_perfect {
+        eprintln!("Error: --max-cardinality and --min-perfect are mutually exclusive");
+        std::process::exit(1);
+    }
+    if args::has_flag(&args, "--maximize") && (minimize || min_perfect) {
+        eprintln!("Error: --maximize does not combine with --minimize or --min-perfect");
+        std::process::exit(1);
+    }
+
+    if let Err(e) = log::from_args(&args) {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+    let load_opts = match graph_io::LoadOptions::from_args(&args) {
+        Ok(o) => o,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match graph_io::load_weighted_graph_named(&args[1], &load_opts) {
+        Ok((n, edges, names)) => {
+            println!("Graph: {} vertices, {} edges", n, edges.len());
+            println!("Objective: {}", if min_perfect {
+                "minimum-weight perfect matching"
+            } else if minimize {
+                "minimum-weight maximum-cardinality matching"
+            } else if max_card {
+                "maximum-weight maximum-cardinality matching"
+            } else {
+                "maximum-weight matching"
+            });
+
+            /* the minimizing objectives maximize flipped weights */
+            let flipped;
+            let (solved, cardinality): (&[(usize, usize, i64)], bool) = if min_perfect || minimize {
+                flipped = flipped_weights(&edges);
+                (&flipped, true)
+            } else {
+                (&edges, max_card)
+            };
+            let start = Instant::now();
+            let result = max_weight_matching_with_duals(n, solved, cardinality);
+            let duration = start.elapsed();
+            if min_perfect && 2 * result.pairs.len() != n {
+                eprintln!("Error: the graph has no perfect matching");
+                std::process::exit(1);
+            }
+            let matching = &result.pairs;
+
+            let report = verify::validate_weighted((n, n), &edges, matching, false, !(min_perfect || minimize));
+            report.print();
+            if check {
+                let positive = result.duals.blossoms.iter().filter(|b| b.1 > 0).count();
+                println!("=== Dual Check ===");
+                println!("Blossoms: {} ({} with a positive dual)", result.duals.blossoms.len(), positive);
+                match check_duals(n, solved, &result, cardinality) {
+                    Ok(()) => println!("COMPLEMENTARY SLACKNESS HOLDS: the matching is optimal"),
+                    Err(e) => {
+                        println!("DUAL CHECK FAILED: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                println!("==================\n");
+            }
+
+            println!("Matching size: {}", matching.len());
+            if let Err(e) = matching_io::save_weighted_from_args(&args, matching, false, min_perfect || minimize, &names) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            if let Err(e) = verify::save_from_args(&args, &report.to_json()) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!("Time: {} ms", duration.as_millis());
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
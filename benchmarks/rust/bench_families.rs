@@ -0,0 +1,369 @@
+/*
+ * Benchmark suite: every matcher on standard instance families
+ *
+ * Generates seeded instances of five families at several sizes and times
+ * each solver on each of them:
+ *   - random-sparse: n vertices, 3n/2 random edges (mean degree 3),
+ *   - random-dense: n sqrt(n) random edges (mean degree 2 sqrt(n)),
+ *   - bipartite-disguised: a random bipartite graph of mean degree 3 on
+ *     two halves, the vertex ids shuffled so that nothing in the input
+ *     says it is bipartite,
+ *   - grid: the sqrt(n) x sqrt(n) grid, ids shuffled,
+ *   - blossom-adversarial: chains of triangles with a free pendant at
+ *     every link, solved from a given matching; every search runs through
+ *     a triangle blossom at each link below its root (see blossom_chains).
+ * --families also takes the adversarial families of `combi gen`
+ * (adversarial.rs: chains, nested-blossoms, even-collisions), in their
+ * default shapes and numbering, each solved from its starting matching
+ * and checked against its known maximum. They are not run by default.
+ * The five general matchers of matchers.rs run on every instance; on the
+ * bipartite ones (a 2-coloring exists) so do Hopcroft-Karp and Pothen-Fan
+ * (PF+), on the color classes.
+ *
+ * The suite builds with plain rustc and has no Cargo manifest, so this is
+ * not a Criterion bench; it follows the same scheme by hand. Each cell is
+ * one warm-up run and --samples timed runs, of which the median, minimum
+ * and maximum are reported. Only the solve is timed: building the solver's
+ * graph from the edge list and the search, not the generator. A solver
+ * whose warm-up on a family takes more than --budget seconds is timed on
+ * that run alone and skipped at the larger sizes of the family.
+ *
+ * Every result on an instance must have the same size, or the run fails.
+ * --csv writes one line per cell; --baseline reads such a file back and
+ * marks each cell whose median grew by more than --threshold percent (and
+ * by more than a millisecond) as REGRESSED.
+ *
+ * Usage: bench_families [--sizes N,N,...] [--families F,F,...] [--algos A,A,...]
+ *                       [--samples K] [--budget SECONDS] [--seed S]
+ *                       [--csv FILE] [--baseline FILE] [--threshold PERCENT]
+ * Exit status 1 if the solvers disagreed or a cell regressed.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::time::Instant;
+
+#[path = "../../algorithms/common/rust/adversarial.rs"]
+mod adversarial;
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/pothen-fan/rust/pothen_fan.rs"]
+#[allow(dead_code)]
+mod pothen_fan;
+
+use hopcroft_karp::HopcroftKarp;
+use pothen_fan::PothenFan;
+
+const FAMILIES: [&str; 5] = ["random-sparse", "random-dense", "bipartite-disguised", "grid", "blossom-adversarial"];
+
+/* Run on the bipartite families, after the general matchers. */
+const BIPARTITE_ALGORITHMS: [&str; 2] = ["hopcroft-karp", "pothen-fan"];
+
+struct Instance {
+    n: usize,
+    edges: Vec<(usize, usize)>,
+    init: Vec<(usize, usize)>, /* the matching the solve starts from */
+    maximum: Option<usize>,    /* known by construction */
+}
+
+/* Vertex ids through a random permutation, edges and pairs alike, and
+ * the edge order shuffled. */
+fn disguise(rng: &mut rng::Rng, inst: &mut Instance) {
+    let mut perm: Vec<usize> = (0..inst.n).collect();
+    rng.shuffle(&mut perm);
+    for e in inst.edges.iter_mut().chain(inst.init.iter_mut()) {
+        *e = (perm[e.0], perm[e.1]);
+    }
+    rng.shuffle(&mut inst.edges);
+}
+
+fn random_edges(rng: &mut rng::Rng, n: usize, m: usize) -> Vec<(usize, usize)> {
+    let mut edges = Vec::with_capacity(m);
+    while edges.len() < m && n > 1 {
+        let (u, v) = (rng.below(n as u64) as usize, rng.below(n as u64) as usize);
+        if u != v { edges.push((u, v)); }
+    }
+    edges
+}
+
+/* Chains of triangles. A chain of length L is a free root r, blocks
+ * (a, b, c, d, p) for i < L and a free end t, with edges r-a_0, a_i-b_i,
+ * b_i-c_i, b_i-d_i, c_i-d_i, d_i-a_{i+1}, d_{L-1}-t and a free pendant
+ * p_i-a_i, and a_i-b_i and c_i-d_i matched. A search reaching b_i even
+ * labels c_i odd and d_i even, and b_i-d_i closes the triangle into a
+ * blossom, at every block on its way. A chain has one augmenting path,
+ * from any of its free vertices to t, lifted through up to L blossoms;
+ * after it each pendant roots a search that fails, having passed every
+ * block below it, unless the solver keeps what an earlier failed search
+ * learned. L is about sqrt(n), so there are about sqrt(n) / 5 chains. */
+fn blossom_chains(n: usize) -> Instance {
+    let length = ((n as f64).sqrt() as usize).max(1);
+    let chains = (n / (5 * length + 2)).max(1);
+    let mut edges = Vec::new();
+    let mut init = Vec::new();
+    let mut next = 0;
+    for _ in 0..chains {
+        let root = next;
+        next += 1;
+        let mut tail = root;
+        for _ in 0..length {
+            let (a, b, c, d, p) = (next, next + 1, next + 2, next + 3, next + 4);
+            next += 5;
+            edges.extend_from_slice(&[(tail, a), (a, b), (b, c), (b, d), (c, d), (p, a)]);
+            init.push((a, b));
+            init.push((c, d));
+            tail = d;
+        }
+        edges.push((tail, next));
+        next += 1;
+    }
+    Instance { n: next, edges, init, maximum: None }
+}
+
+fn generate(family: &str, n: usize, seed: u64) -> Instance {
+    let mut rng = rng::Rng::new(seed);
+    if adversarial::FAMILIES.contains(&family) {
+        let a = adversarial::generate(family, n, &adversarial::GenOptions::default(), &mut rng).unwrap();
+        return Instance { n: a.n, edges: a.edges, init: a.init, maximum: Some(a.maximum) };
+    }
+    let mut inst = match family {
+        "random-sparse" => Instance { n, edges: random_edges(&mut rng, n, 3 * n / 2), init: Vec::new(), maximum: None },
+        "random-dense" => {
+            let m = (n as f64 * (n as f64).sqrt()) as usize;
+            Instance { n, edges: random_edges(&mut rng, n, m.min(n * (n - 1) / 2)), init: Vec::new(), maximum: None }
+        }
+        "bipartite-disguised" => {
+            let half = (n / 2).max(1);
+            let mut edges = Vec::with_capacity(3 * half);
+            for _ in 0..3 * half {
+                edges.push((rng.below(half as u64) as usize, half + rng.below(half as u64) as usize));
+            }
+            Instance { n: 2 * half, edges, init: Vec::new(), maximum: None }
+        }
+        "grid" => {
+            let side = ((n as f64).sqrt() as usize).max(1);
+            let mut edges = Vec::with_capacity(2 * side * side);
+            for r in 0..side {
+                for c in 0..side {
+                    let v = r * side + c;
+                    if c + 1 < side { edges.push((v, v + 1)); }
+                    if r + 1 < side { edges.push((v, v + side)); }
+                }
+            }
+            Instance { n: side * side, edges, init: Vec::new(), maximum: None }
+        }
+        _ => blossom_chains(n),
+    };
+    disguise(&mut rng, &mut inst);
+    inst
+}
+
+/* Matching size of one solve of `algo`, with its time in milliseconds.
+ * `side` is the 2-coloring for the bipartite solvers. */
+fn time_one(algo: &str, inst: &Instance, side: Option<&[bool]>) -> Result<(usize, f64), String> {
+    if BIPARTITE_ALGORITHMS.contains(&algo) {
+        let side = side.ok_or_else(|| format!("{} needs a bipartite instance", algo))?;
+        /* vertex ids within each color class, and the edges left to right */
+        let mut local = vec![0; inst.n];
+        let (mut left, mut right) = (0, 0);
+        for v in 0..inst.n {
+            if side[v] { local[v] = right; right += 1; } else { local[v] = left; left += 1; }
+        }
+        let edges: Vec<(usize, usize)> = inst.edges.iter()
+            .map(|&(u, v)| if side[u] { (local[v], local[u]) } else { (local[u], local[v]) })
+            .collect();
+        let start = Instant::now();
+        let size = if algo == "hopcroft-karp" {
+            HopcroftKarp::new(left, right, &edges).maximum_matching(0).len()
+        } else {
+            PothenFan::new(left, right, &edges).maximum_matching(false).len()
+        };
+        return Ok((size, start.elapsed().as_secs_f64() * 1000.0));
+    }
+    let start = Instant::now();
+    let solution = if inst.init.is_empty() {
+        matchers::solve(algo, inst.n, &inst.edges, 0, 1, 0)?
+    } else {
+        matchers::solve_from(algo, inst.n, &inst.edges, &inst.init)?
+    };
+    Ok((solution.matching.len(), start.elapsed().as_secs_f64() * 1000.0))
+}
+
+struct Cell {
+    family: String,
+    n: usize,
+    m: usize,
+    algo: String,
+    size: usize,
+    samples: usize,
+    median: f64,
+    min: f64,
+    max: f64,
+}
+
+const CSV_HEADER: &str = "family,n,m,algorithm,size,samples,median_ms,min_ms,max_ms";
+
+fn write_csv(path: &str, cells: &[Cell]) -> std::io::Result<()> {
+    let mut f = fs::File::create(path)?;
+    writeln!(f, "{}", CSV_HEADER)?;
+    for c in cells {
+        writeln!(f, "{},{},{},{},{},{},{:.3},{:.3},{:.3}", c.family, c.n, c.m, c.algo, c.size, c.samples, c.median, c.min, c.max)?;
+    }
+    Ok(())
+}
+
+/* (family, n, algorithm) -> median_ms of a --csv file. */
+fn read_baseline(path: &str) -> Result<Vec<(String, usize, String, f64)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate().skip(1) {
+        let f: Vec<&str> = line.split(',').collect();
+        let parsed = if f.len() == 9 { f[1].parse::<usize>().ok().zip(f[6].parse::<f64>().ok()) } else { None };
+        match parsed {
+            Some((n, median)) => rows.push((f[0].to_string(), n, f[3].to_string(), median)),
+            None => return Err(format!("{}: line {}: expected {}", path, i + 1, CSV_HEADER)),
+        }
+    }
+    Ok(rows)
+}
+
+fn list_or_exit<'a>(args: &'a [String], name: &str, default: &[&'a str], allowed: Option<&[&str]>) -> Vec<&'a str> {
+    let list: Vec<&str> = match args::value_of(args, name) {
+        None => default.to_vec(),
+        Some(s) => s.split(',').filter(|x| !x.is_empty()).collect(),
+    };
+    if let Some(allowed) = allowed {
+        if let Some(bad) = list.iter().find(|x| !allowed.contains(x)) {
+            eprintln!("Error: unknown value for {}: {} (expected one of {})", name, bad, allowed.join(", "));
+            std::process::exit(1);
+        }
+    }
+    list
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let sizes: Vec<usize> = list_or_exit(&args, "--sizes", &["1000", "4000", "16000"], None).iter()
+        .map(|s| match s.parse::<usize>() {
+            Ok(n) if n >= 2 => n,
+            _ => {
+                eprintln!("Error: invalid value for --sizes: {} (expected vertex counts of at least 2)", s);
+                std::process::exit(1);
+            }
+        })
+        .collect();
+    let mut all_families: Vec<&str> = FAMILIES.to_vec();
+    all_families.extend_from_slice(&adversarial::FAMILIES);
+    let families = list_or_exit(&args, "--families", &FAMILIES, Some(&all_families));
+    let mut known: Vec<&str> = matchers::GENERAL_ALGORITHMS.to_vec();
+    known.extend_from_slice(&BIPARTITE_ALGORITHMS);
+    let algos = list_or_exit(&args, "--algos", &known, Some(&known));
+    let samples: usize = args::parse_or_exit(&args, "--samples", 5);
+    let budget: f64 = args::parse_or_exit(&args, "--budget", 10.0);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let threshold: f64 = args::parse_or_exit(&args, "--threshold", 10.0);
+    if samples == 0 {
+        eprintln!("Error: --samples must be positive");
+        std::process::exit(1);
+    }
+    let baseline = match args::value_of(&args, "--baseline").map(read_baseline) {
+        None => Vec::new(),
+        Some(Ok(rows)) => rows,
+        Some(Err(e)) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Matcher benchmarks: sizes {:?}, {} samples, budget {} s, seed {}", sizes, samples, budget, seed);
+
+    let mut cells = Vec::new();
+    let mut disagreements = 0;
+    let mut regressions = 0;
+    for family in &families {
+        println!("\n{}", family);
+        println!("  {:>8} {:>9}  {:<16} {:>7} {:>11} {:>11} {:>11}", "n", "m", "algorithm", "size", "median ms", "min ms", "max ms");
+        let mut over_budget: Vec<&str> = Vec::new();
+        for (k, &n) in sizes.iter().enumerate() {
+            let inst = generate(family, n, seed.wrapping_add(k as u64));
+            let side = matchers::two_coloring(inst.n, &inst.edges);
+            let mut sizes_seen: Vec<(usize, &str)> = Vec::new();
+            for &algo in &algos {
+                if over_budget.contains(&algo) || (BIPARTITE_ALGORITHMS.contains(&algo) && side.is_none()) {
+                    continue;
+                }
+                let warm = match time_one(algo, &inst, side.as_deref()) {
+                    Ok(r) => r,
+                    Err(e) => {
+                        eprintln!("Error: {}: {}", algo, e);
+                        std::process::exit(1);
+                    }
+                };
+                let mut times = vec![warm.1];
+                if warm.1 > budget * 1000.0 {
+                    over_budget.push(algo);
+                } else {
+                    times.clear();
+                    for _ in 0..samples {
+                        times.push(time_one(algo, &inst, side.as_deref()).map(|r| r.1).unwrap_or(f64::NAN));
+                    }
+                }
+                times.sort_by(|a, b| a.total_cmp(b));
+                let cell = Cell {
+                    family: family.to_string(), n: inst.n, m: inst.edges.len(), algo: algo.to_string(), size: warm.0,
+                    samples: times.len(), median: times[times.len() / 2], min: times[0], max: times[times.len() - 1],
+                };
+                let mut note = String::new();
+                if over_budget.contains(&algo) {
+                    note = format!("  (warm-up only, over budget; skipped above n = {})", inst.n);
+                }
+                if let Some(&(_, _, _, old)) = baseline.iter().find(|b| b.0 == cell.family && b.1 == cell.n && b.2 == cell.algo) {
+                    let change = 100.0 * (cell.median - old) / old.max(1e-9);
+                    if change > threshold && cell.median - old > 1.0 {
+                        regressions += 1;
+                        note.push_str(&format!("  REGRESSED {:+.1}% (was {:.3} ms)", change, old));
+                    } else {
+                        note.push_str(&format!("  {:+.1}%", change));
+                    }
+                }
+                println!("  {:>8} {:>9}  {:<16} {:>7} {:>11.3} {:>11.3} {:>11.3}{}",
+                         cell.n, cell.m, cell.algo, cell.size, cell.median, cell.min, cell.max, note);
+                sizes_seen.push((cell.size, algo));
+                cells.push(cell);
+            }
+            if let Some(maximum) = inst.maximum {
+                sizes_seen.push((maximum, "known maximum"));
+            }
+            if sizes_seen.windows(2).any(|w| w[0].0 != w[1].0) {
+                disagreements += 1;
+                let all: Vec<String> = sizes_seen.iter().map(|(s, a)| format!("{} {}", a, s)).collect();
+                println!("  DISAGREEMENT on {} n = {}: {}", family, inst.n, all.join(", "));
+            }
+        }
+    }
+
+    if let Some(path) = args::value_of(&args, "--csv") {
+        if let Err(e) = write_csv(path, &cells) {
+            eprintln!("Error: {}: {}", path, e);
+            std::process::exit(1);
+        }
+        println!("\nWrote {} cells to {}", cells.len(), path);
+    }
+    if disagreements > 0 || regressions > 0 {
+        println!("\n{} DISAGREEMENT(S), {} REGRESSION(S)", disagreements, regressions);
+        std::process::exit(1);
+    }
+    println!("\nDONE ({} cells)", cells.len());
+}
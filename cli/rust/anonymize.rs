@@ -0,0 +1,98 @@
+/*
+ * combi anonymize - degree-constrained edge selection.
+ *
+ * Keeps a largest subset of the graph's edges such that no vertex keeps
+ * more than its capacity: a maximum simple b-matching (see b_matching.rs).
+ * Capping the degrees of high-degree vertices hides the hubs that make a
+ * published graph easy to re-identify, while losing as few edges as
+ * possible.
+ *
+ * Capacities come from a file of "vertex capacity" lines (--caps) and/or a
+ * cap for every unlisted vertex (--default-cap). The kept edges can be
+ * written as a new "n m" edge list (--output).
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::b_matching::{self, BMatching};
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+
+fn write_edges(path: &str, n: usize, edges: &[(usize, usize)]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    writeln!(f, "{} {}", n, edges.len())?;
+    for &(u, v) in edges {
+        writeln!(f, "{} {}", u, v)?;
+    }
+    f.flush()
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let file = &args[2];
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let default_cap = match args::value_of(args, "--default-cap") {
+        Some(s) => s.parse::<usize>().map_err(|_| format!("invalid --default-cap '{}'", s))?,
+        None => b_matching::UNLIMITED,
+    };
+    let caps_file = args::value_of(args, "--caps");
+    if caps_file.is_none() && default_cap == b_matching::UNLIMITED {
+        return Err("nothing to enforce: give --caps FILE and/or --default-cap K".into());
+    }
+
+    let (n, edges) = graph_io::load_graph(file, opts)?;
+    let caps = match caps_file {
+        Some(path) => b_matching::load_capacities(path, n, default_cap).map_err(|e| e.to_string())?,
+        None => vec![default_cap; n],
+    };
+
+    let start = Instant::now();
+    let bm = BMatching::new(n, &edges, &caps);
+    let (kept, reduced_size) = b_matching::max_b_matching(algo, &bm)?;
+    let duration = start.elapsed();
+
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    println!("Candidate edges: {} (distinct, no self-loops)", bm.edges.len());
+    println!("Binding vertices: {} (capacity below degree)", bm.binding);
+    println!("Reduced graph: {} vertices, {} edges", bm.reduced_n, bm.reduced.len());
+    println!("Backend: {}", algo);
+
+    let mut errors = b_matching::check_b_matching(&bm, &kept);
+    let expected = bm.expected_size(reduced_size);
+    if kept.len() != expected {
+        errors.push(format!("Kept {} edges, matching bound says {}!", kept.len(), expected));
+    }
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    let mut load = vec![0usize; n];
+    for &(u, v) in &kept {
+        load[u] += 1;
+        load[v] += 1;
+    }
+    let saturated = (0..n).filter(|&v| bm.caps[v] != b_matching::UNLIMITED && load[v] == bm.caps[v]).count();
+    println!("\n=== Validation Report ===");
+    println!("Kept edges: {}", kept.len());
+    println!("Saturated vertices: {}", saturated);
+    println!("Max kept degree: {}", load.iter().cloned().max().unwrap_or(0));
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show-edges") {
+        for &(u, v) in &kept {
+            println!("{} {}", u, v);
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_edges(path, n, &kept).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} edges to {}", kept.len(), path);
+    }
+    println!("Kept edges: {}", kept.len());
+    println!("Dropped edges: {}", bm.edges.len() - kept.len());
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
@@ -0,0 +1,80 @@
+/*
+ * combi bounds - quick lower and upper bounds on the maximum matching size.
+ *
+ * Lower bounds are actual matchings (greedy, Karp-Sipser) and are checked
+ * against the graph; upper bounds are counting arguments (vertices,
+ * components) and the LP bound of matching_bounds.rs. When the best of
+ * each side meet, that is the matching number and no exact run is needed.
+ * The LP bound, the only one that is not linear time, runs only when the
+ * others leave a gap, and not at all with --no-lp. If one is still left,
+ * the LP's half-integral optimum, rounded (one vertex dropped per odd
+ * cycle), is tried as a lower bound.
+ */
+
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matching_bounds;
+
+fn timed<F: FnOnce() -> usize>(name: &str, kind: &str, f: F) -> usize {
+    let start = Instant::now();
+    let value = f();
+    println!("  {:<6} {:<12} {:>10}   {} ms", kind, name, value, start.elapsed().as_millis());
+    value
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges\n", n, edges.len());
+
+    let start = Instant::now();
+    let mut errors = Vec::new();
+    let greedy = timed("greedy", "lower", || {
+        let m = matching_bounds::greedy_matching(n, &edges);
+        errors.extend(matching_bounds::check_matching(n, &edges, &m));
+        m.len()
+    });
+    let mut ks_matching = Vec::new();
+    let ks = timed("karp-sipser", "lower", || {
+        ks_matching = matching_bounds::karp_sipser(n, &edges);
+        errors.extend(matching_bounds::check_matching(n, &edges, &ks_matching));
+        ks_matching.len()
+    });
+    let mut lower = greedy.max(ks);
+    let mut upper = timed("vertices", "upper", || matching_bounds::vertex_bound(n, &edges));
+    upper = upper.min(timed("components", "upper", || matching_bounds::component_bound(n, &edges)));
+    if lower < upper && !args::has_flag(args, "--no-lp") {
+        upper = upper.min(timed("lp", "upper", || matching_bounds::lp_bound(n, &edges, &ks_matching)));
+        if lower < upper {
+            lower = lower.max(timed("lp-rounding", "lower", || {
+                let m = matching_bounds::half_integral_matching(n, &edges, &ks_matching).rounded(n, &edges);
+                errors.extend(matching_bounds::check_matching(n, &edges, &m));
+                m.len()
+            }));
+        }
+    }
+    let duration = start.elapsed();
+    if lower > upper {
+        errors.push(format!("Lower bound {} exceeds upper bound {}!", lower, upper));
+    }
+
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Lower bound: {} (matchings checked)", lower);
+    println!("Upper bound: {}", upper);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if lower == upper {
+        println!("Matching number: {} (bounds meet)", lower);
+    } else {
+        println!("Matching number: {} ..= {} (gap {}, {:.3}%)", lower, upper, upper - lower,
+                 100.0 * (upper - lower) as f64 / upper as f64);
+    }
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
@@ -0,0 +1,131 @@
+/*
+ * combi check - validate a saved matching file against its graph.
+ *
+ * Reads a matching in the format of matching_io.rs (what the solvers'
+ * --output-matching writes) and checks it against the graph file: the
+ * count line, ids in range, every pair an edge, no vertex twice. A file
+ * that is valid but not in canonical order passes with a note.
+ * --bipartite reads a "left right m" graph and (left, right) pairs;
+ * --weighted reads "u v w" edge lines and reports the matching's weight
+ * (a pair over parallel edges counts the heaviest). --maximum also solves
+ * the graph (--algo A, or Hopcroft-Karp when bipartite) and fails a
+ * matching smaller than the maximum; --certify checks it with the
+ * Tutte-Berge certificate instead (vertex_classes.rs), without a second
+ * solver. Either is the report's "Optimal:" status, beside "Maximal:"
+ * (no edge with both ends unmatched), which never fails the check. Under --format snap the file is in
+ * the graph's original ids, under --format labels in its labels, and the
+ * report in the ids they were mapped to. Under
+ * --edge-ids it names edges (see matching_io.rs), and the weight is that
+ * of the edges named. --report-json FILE also writes the report as JSON
+ * (verify.rs), with the maximum and the note.
+ */
+
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::hopcroft_karp::HopcroftKarp;
+use super::matchers;
+use super::matching_io;
+use super::verify;
+use super::vertex_classes;
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    if args.len() < 4 {
+        return Err("check needs a graph file and a matching file".into());
+    }
+    let bipartite = args::has_flag(args, "--bipartite");
+    let weighted = args::has_flag(args, "--weighted");
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+
+    /* (left, right) bounds; right == left for a general graph */
+    let (bounds, weighted_edges, names) = if bipartite {
+        let (l, r, e, names) = if weighted {
+            graph_io::load_weighted_bipartite_graph_named(&args[2], opts)
+        } else {
+            graph_io::load_bipartite_graph_named(&args[2], opts)
+                .map(|(l, r, e, names)| (l, r, e.into_iter().map(|(u, v)| (u, v, 0)).collect(), names))
+        }?;
+        println!("Graph: {} left, {} right, {} edges", l, r, e.len());
+        ((l, r), e, names)
+    } else {
+        let (n, e, names) = if weighted {
+            graph_io::load_weighted_graph_named(&args[2], opts)
+        } else {
+            graph_io::load_graph_named(&args[2], opts)
+                .map(|(n, e, names)| (n, e.into_iter().map(|(u, v)| (u, v, 0)).collect(), names))
+        }?;
+        println!("Graph: {} vertices, {} edges", n, e.len());
+        ((n, n), e, names)
+    };
+    let edges: Vec<(usize, usize)> = weighted_edges.iter().map(|&(u, v, _)| (u, v)).collect();
+    /* the exact weight of the edges an --edge-ids file names */
+    let (pairs, named_weight) = match names.edges {
+        Some(ref table) => {
+            let ids = matching_io::load_edge_ids(&args[3])?;
+            let pairs = table.pairs_of(&ids).map_err(|e| format!("{}: {}", args[3], e))?;
+            (pairs, Some(ids.iter().filter_map(|&id| table.edge(id)).map(|e| e.2).sum::<i64>()))
+        }
+        None => (matching_io::load_pairs(&args[3], bipartite, &names)?, None),
+    };
+    println!("Matching file: {} ({} pairs)", args[3], pairs.len());
+
+    let start = Instant::now();
+    let (mut errors, note) = matching_io::check_pairs(&pairs, bounds, bipartite);
+    /* the graph checks see only the pairs that passed the format's */
+    let valid: Vec<(usize, usize)> = pairs.iter().cloned()
+        .filter(|&(u, v)| u < bounds.0 && v < bounds.1 && (bipartite || u != v))
+        .collect();
+    let mut report = verify::validate(bounds, &edges, &valid, bipartite);
+    errors.append(&mut report.errors);
+    let maximum = if args::has_flag(args, "--maximum") {
+        let size = if bipartite {
+            HopcroftKarp::new(bounds.0, bounds.1, &edges).maximum_matching(0).len()
+        } else {
+            matchers::maximum_matching(algo, bounds.0, &edges)?.len()
+        };
+        Some(size)
+    } else {
+        None
+    };
+    /* on the union graph, right ids after the left ones, when bipartite */
+    let certificate = if args::has_flag(args, "--certify") && errors.is_empty() {
+        let shift = |&(u, v): &(usize, usize)| if bipartite { (u, bounds.0 + v) } else { (u, v) };
+        let n = if bipartite { bounds.0 + bounds.1 } else { bounds.0 };
+        let union: Vec<(usize, usize)> = edges.iter().map(shift).collect();
+        let shifted: Vec<(usize, usize)> = pairs.iter().map(shift).collect();
+        Some(vertex_classes::certify(n, &union, &shifted))
+    } else {
+        None
+    };
+    let duration = start.elapsed();
+
+    report.size = pairs.len();
+    report.errors = errors;
+    if weighted && report.is_valid() {
+        report.weight = Some(named_weight.unwrap_or_else(|| verify::weight_of(&weighted_edges, &pairs, bipartite, true)));
+    }
+    if let Some(size) = maximum {
+        let solver = if bipartite { "hopcroft-karp" } else { algo };
+        let found = format!("{} finds {} pairs", solver, size);
+        report.optimal = Some(if pairs.len() < size { Err(found) } else { Ok(found) });
+    }
+    if certificate.is_some() && report.is_optimal() {
+        report.optimal = certificate;
+    }
+    if let Some(ref note) = note {
+        report.extra.push(format!("Note: {} (not the canonical order)", note));
+    }
+    report.print();
+    println!("Time: {} ms", duration.as_millis());
+    let json = report.to_json().with("maximum", maximum).with("note", note);
+    verify::save_from_args(args, &json)?;
+    if !report.is_valid() {
+        return Err(format!("{} problem(s) in {}", report.errors.len(), args[3]).into());
+    }
+    if !report.is_optimal() {
+        return Err(format!("{} is not a maximum matching", args[3]).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,76 @@
+/*
+ * combi classify - which vertices every maximum matching covers.
+ *
+ * Solves the graph with --algo and reads the Gallai-Edmonds decomposition
+ * off the matching (see vertex_classes.rs): each vertex is matched in
+ * every maximum matching (an assignment that is forced to be served), in
+ * some but not all, or in none. The validation checks the decomposition's
+ * identities, which also prove the matching maximum. --show prints the
+ * class of each vertex, and --output writes them as "v class" lines.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+use super::vertex_classes::{self, Classification, VertexClass};
+
+fn write_classes(path: &str, c: &Classification) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for (v, class) in c.class.iter().enumerate() {
+        writeln!(f, "{} {}", v, class.name())?;
+    }
+    f.flush()
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    matchers::check_vertex_count(algo, n)?;
+
+    let start = Instant::now();
+    let matching = matchers::maximum_matching(algo, n, &edges)?;
+    println!("Algorithm: {} (matching size {}, {} ms)", algo, matching.len(), start.elapsed().as_millis());
+    let start = Instant::now();
+    let c = vertex_classes::vertex_classification(n, &edges, &matching);
+    let duration = start.elapsed();
+    let (d, a, rest) = c.sizes();
+    let checked = vertex_classes::check_classification(n, &edges, &matching, &c);
+    let components = match checked {
+        Ok(k) => format!("{} components", k),
+        Err(ref errors) => {
+            for e in errors {
+                eprintln!("ERROR: {}", e);
+            }
+            "components not counted".to_string()
+        }
+    };
+    println!("Gallai-Edmonds: D {} ({}), A {}, C {}", d, components, a, rest);
+
+    println!("\n=== Validation Report ===");
+    println!("Matching size: {}", matching.len());
+    println!("Free vertices: {}", n - 2 * matching.len());
+    println!("{}", if checked.is_ok() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show") {
+        for (v, class) in c.class.iter().enumerate() {
+            println!("{} {}", v, class.name());
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_classes(path, &c).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} vertex classes to {}", n, path);
+    }
+    println!("Matched in every maximum matching: {} (A {}, C {})", c.count(VertexClass::Always), a, rest);
+    println!("Matched in some but not all: {}", c.count(VertexClass::Sometimes));
+    println!("Matched in none: {}", c.count(VertexClass::Never));
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
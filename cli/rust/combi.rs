@@ -0,0 +1,855 @@
+/*
+ * combi - command-line front end for the matching suite (Rust).
+ *
+ * Usage:
+ *   combi solve <file> [--algo A [--explain]] [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]]
+ *               [--init-aug3 [--aug3-passes K]]
+ *               [--phase0|--crown|--fold] [--reorder R] [--phase-stats] [--progress] [--time-limit SECONDS]
+ *               [--trace FILE] [--dot FILE] [--svg FILE [--layout force|circle]]
+ *               [--init-matching FILE] [--output-matching FILE] [--lp-gap] [--count-matchings] [--certify]
+ *               [--forbid FILE] [--force-edges FILE]
+ *               [--no-memory-check] [--mem-report] [--dry-run]
+ *               [--maximize|--minimize|--tie-break lex|lighter|heavier] [--check-duals] [--ignore-weights]
+ *               [--edge-ids] [--socket PATH]
+ *   combi serve [--socket PATH]
+ *   combi stats|clear|shutdown [--socket PATH]
+ *   combi rpc '<json-rpc message>' [--socket PATH]
+ *   combi anonymize <file> [--caps FILE] [--default-cap K] [--algo A]
+ *                   [--output FILE] [--show-edges]
+ *   combi postman <weighted file> [--start V] [--t-set FILE] [--output FILE] [--show-tour]
+ *   combi cover <file> [--k K] [--approx|--bipartite] [--independent-set] [--output FILE] [--show]
+ *   combi geomatch <points file> [--k K] [--full] [--output FILE] [--show-pairs]
+ *   combi stable <preferences file> [--propose left|right] [--output FILE] [--show-pairs]
+ *   combi allocate <rankings file> [--popular] [--output FILE] [--show-pairs]
+ *   combi vweight <file> [--weights FILE] [--default-weight W] [--output FILE] [--show-pairs]
+ *   combi kidney <arcs file> [--altruists FILE] [--max-cycle 2|3] [--algo A] [--node-limit N]
+ *                [--output FILE] [--show]
+ *   combi edgedom <file> [--maximum] [--algo A] [--improve] [--output FILE] [--show]
+ *   combi ffactor <file> [--degrees FILE] [--default-degree F] [--algo A] [--output FILE] [--show]
+ *   combi bounds <file> [--no-lp]
+ *   combi enumerate <file> [--limit K] [--algo A] [--output FILE] [--show]
+ *   combi classify <file> [--algo A] [--output FILE] [--show]
+ *   combi btf <matrix.mtx> [--row-perm FILE] [--col-perm FILE] [--blocks FILE] [--one-based] [--show]
+ *   combi sparsify <file> [--rounds K|all] [--maximum] [--algo A] [--until-preserved] [--no-verify]
+ *                  [--output FILE] [--schedule FILE]
+ *   combi hypermatch <triples file> [--no-improve] [--output FILE] [--show]
+ *   combi maxcut <file> [--weighted] [--restarts R] [--seed S] [--no-pairs] [--output FILE] [--show]
+ *   combi check <file> <matching file> [--bipartite] [--weighted] [--maximum] [--algo A] [--certify]
+ *               [--edge-ids] [--report-json FILE]
+ *   combi plant <n> [--bipartite] [--degree D] [--max-potential W] [--gap G] [--spread S] [--seed S]
+ *               [--output FILE] [--planted FILE]
+ *   combi gen chains|nested-blossoms|even-collisions <n> [--depth D] [--levels L] [--width W] [--degree D]
+ *             [--seed S] [--shuffle] [--output FILE] [--init FILE]
+ *   combi recover <n> [--bipartite] [--degrees D,...] [--gaps G,...] [--trials T] [--algos A,...]
+ *                 [--max-potential W] [--spread S] [--seed S] [--csv FILE]
+ *   combi convert <in> <out> [--to text|binary] [--bipartite] [--weighted]
+ *   combi replay <file> <trace file> [--bipartite]
+ *
+ * `solve` runs in-process, or on a running `combi serve` daemon when
+ * --socket is given (see serve.rs); --dry-run only checks the input and
+ * prints the plan (see plan.rs). A local solve first checks the memory
+ * estimate of plan.rs against what is available, from the file's header,
+ * and refuses to start a run that would not fit (--no-memory-check skips
+ * it). Ctrl-C (SIGINT) or SIGTERM during a local solve stops it at the
+ * next phase boundary: the matching found so far is validated and printed
+ * (and saved by --output-matching) with an `Interrupted:` line, and the
+ * exit status is 128 + the signal, 130 for Ctrl-C (see interrupt.rs).
+ * --progress prints the phase, augmentations and free vertices to stderr
+ * about once a second while the solver runs (see progress.rs).
+ * --time-limit stops the solver the same way once that many seconds have
+ * passed and adds a `Time limit:` line bounding how many pairs the
+ * matching can be short of the maximum (see time_limit.rs); the exit
+ * status stays 0.
+ * --trace writes every starting pair, phase, blossom and augmenting path
+ * of the solve to a file, and `replay` re-applies such a file to its graph,
+ * checking that each intermediate matching is valid (see trace.rs).
+ * --dot draws the graph and the matching in GraphViz DOT, with the
+ * blossoms of the matching as clusters (see dot.rs), and --svg draws it
+ * here, laid out by forces or on a circle, up to 2000 vertices (see svg.rs).
+ * --algo auto picks the solver from the graph (see matchers.rs), and
+ * --explain prints the statistics and the rule behind the pick. A graph
+ * with edge weights ("u v w" lines) goes to the weighted blossom solver
+ * instead, --maximize or --minimize (--check-duals verifies its duals),
+ * and a cardinality solver forced on it is refused unless --ignore-weights
+ * (see weighted.rs).
+ * --lp-gap is for studying instance families: after the solve it reports
+ * the fractional matching LP optimum and its gap to the matching size
+ * (see matching_bounds.rs).
+ * --count-matchings counts the maximum matchings of a small or narrow
+ * graph, by permanents and a frontier DP (see count_matchings.rs).
+ * --forbid solves the graph minus the pairs of a side file, so the
+ * matching avoids them, and --force-edges puts the pairs of another in the
+ * matching and solves what is left of the graph without their vertices;
+ * either reports the drop against the unconstrained maximum matching (see
+ * constraints.rs).
+ * --tie-break lex turns the maximum matching found into the
+ * lexicographically smallest one (see tie_break.rs); lighter and heavier
+ * break the tie by the weights of a weighted graph (see weighted.rs).
+ * The standalone per-algorithm binaries
+ * are unchanged; combi drives the same solvers through matchers.rs.
+ * The daemon also speaks JSON-RPC 2.0 for other languages (see rpc.rs).
+ * `anonymize` is a b-matching application (see anonymize.rs), `postman` a
+ * weighted matching one (see postman.rs), `cover` a bipartite matching
+ * kernel for vertex cover (see cover.rs), `geomatch` a weighted perfect
+ * matching of points (see geomatch.rs), `stable` preference-based matching
+ * by deferred acceptance (see stable.rs), `allocate` rank-maximal and
+ * popular matchings under one-sided preferences (see allocate.rs),
+ * `vweight` matchings of maximum total vertex weight (see vweight.rs),
+ * `kidney` kidney exchange cycles and chains (see kidney.rs), `edgedom`
+ * edge dominating sets from maximal matchings (see edgedom.rs), `ffactor`
+ * subgraphs with prescribed degrees by Tutte's gadget (see ffactor.rs), `bounds`
+ * quick lower and upper bounds on the matching number (see bounds.rs),
+ * `enumerate` the maximum matchings of a graph, one at a time up to a
+ * limit, with the pairs they share (see enumerate.rs), `classify` the
+ * vertices every maximum matching covers, some do, or none does, by the
+ * Gallai-Edmonds decomposition (see classify.rs), `btf` the maximum
+ * transversal and block triangular permutations of a Matrix Market sparse
+ * matrix, by the Dulmage-Mendelsohn decomposition (see btf.rs),
+ * `sparsify` a union of edge-disjoint matchings with (nearly) the matching
+ * number of the graph, or a decomposition into matchings (see sparsify.rs),
+ * `hypermatch` disjoint triples of a 3-uniform hypergraph by local search
+ * (see hypermatch.rs), `maxcut` maximum cuts by local search with pair
+ * flips chosen by weighted matching (see maxcut.rs),
+ * `check` validates a saved matching file against its graph (see check.rs),
+ * `plant` weighted instances with a known optimal matching (see plant.rs),
+ * `gen` adversarial instances for the cardinality solvers (see gen.rs),
+ * `recover` experiments on how often solvers find the planted matching
+ * (see recover.rs), `convert` edge lists to and from the binary format that
+ * every loader also reads (see convert.rs), `replay` --trace logs (see
+ * replay.rs).
+ * Every command takes --log-level: the loaders' warnings and the shared
+ * modules' reports go through log.rs, which it filters (error, warn,
+ * info by default, debug, trace); debug and trace add a local solve's
+ * phases and augmenting paths on stderr when there is no --trace file.
+ *
+ * Build: rustc -O combi.rs -o combi
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::io::{self, Write};
+use std::time::Instant;
+
+#[path = "../../algorithms/common/rust/adversarial.rs"]
+mod adversarial;
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/b_matching.rs"]
+mod b_matching;
+#[path = "../../algorithms/common/rust/block_triangular.rs"]
+mod block_triangular;
+#[path = "../../algorithms/common/rust/constraints.rs"]
+mod constraints;
+#[path = "../../algorithms/common/rust/count_matchings.rs"]
+mod count_matchings;
+#[path = "../../algorithms/common/rust/dot.rs"]
+mod dot;
+#[path = "../../algorithms/common/rust/dsu.rs"]
+mod dsu;
+#[path = "../../algorithms/common/rust/edge_dominating.rs"]
+mod edge_dominating;
+#[path = "../../algorithms/common/rust/enumerate_matchings.rs"]
+mod enumerate_matchings;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/f_factor.rs"]
+mod f_factor;
+#[path = "../../algorithms/common/rust/geometric_matching.rs"]
+mod geometric_matching;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/common/rust/hypergraph_matching.rs"]
+mod hypergraph_matching;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/interrupt.rs"]
+mod interrupt;
+#[path = "../../algorithms/common/rust/json.rs"]
+mod json;
+#[path = "../../algorithms/common/rust/kidney_exchange.rs"]
+mod kidney_exchange;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/matching_bounds.rs"]
+mod matching_bounds;
+#[path = "../../algorithms/common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../algorithms/common/rust/max_cut.rs"]
+mod max_cut;
+#[path = "../../algorithms/common/rust/ordering.rs"]
+mod ordering;
+#[path = "../../algorithms/common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../algorithms/common/rust/phase_stats.rs"]
+mod phase_stats;
+#[path = "../../algorithms/common/rust/planted.rs"]
+mod planted;
+#[path = "../../algorithms/common/rust/progress.rs"]
+mod progress;
+#[path = "../../algorithms/common/rust/rank_matching.rs"]
+mod rank_matching;
+#[path = "../../algorithms/common/rust/recovery.rs"]
+mod recovery;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/sparsifier.rs"]
+mod sparsifier;
+#[path = "../../algorithms/common/rust/stable_matching.rs"]
+mod stable_matching;
+#[path = "../../algorithms/common/rust/svg.rs"]
+mod svg;
+#[path = "../../algorithms/common/rust/t_join.rs"]
+mod t_join;
+#[path = "../../algorithms/common/rust/tie_break.rs"]
+mod tie_break;
+#[path = "../../algorithms/common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../algorithms/common/rust/trace.rs"]
+mod trace;
+#[path = "../../algorithms/common/rust/verify.rs"]
+mod verify;
+#[path = "../../algorithms/common/rust/vertex_classes.rs"]
+mod vertex_classes;
+#[path = "../../algorithms/common/rust/vertex_cover.rs"]
+mod vertex_cover;
+#[path = "../../algorithms/common/rust/vertex_weighted.rs"]
+mod vertex_weighted;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+mod allocate;
+mod anonymize;
+mod bounds;
+mod btf;
+mod check;
+mod classify;
+mod convert;
+mod cover;
+mod edgedom;
+mod enumerate;
+mod ffactor;
+mod gen;
+mod geomatch;
+mod hypermatch;
+mod kidney;
+mod maxcut;
+mod memory;
+mod plan;
+mod plant;
+mod postman;
+mod recover;
+mod replay;
+mod rpc;
+mod serve;
+mod solve_options;
+mod sparsify;
+mod stable;
+mod vweight;
+mod weighted;
+
+use error::SuiteError;
+use solve_options::SolveOptions;
+
+fn usage(prog: &str) -> ! {
+    eprintln!("Usage:");
+    eprintln!("  {} solve <file> [--algo {}|{}|{} [--explain]] [--greedy|--greedy-md|--greedy-ks|--greedy-random [--seed S]] {} {} {} {} {} {} {} \
+               {} {} {} {} [--lp-gap] [--count-matchings] {} [--forbid FILE] [--force-edges FILE] {} {} [--dry-run] {} [--socket PATH] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), matchers::AUTO_ALGORITHM, matchers::WEIGHTED_ALGORITHM, args::AUG3_USAGE,
+              phase0::PHASE0_USAGE, ordering::REORDER_USAGE, phase_stats::PHASE_STATS_USAGE, progress::PROGRESS_USAGE,
+              time_limit::TIME_LIMIT_USAGE, trace::TRACE_USAGE, dot::DOT_USAGE, svg::SVG_USAGE, matching_io::INIT_USAGE,
+              matching_io::OUTPUT_USAGE, verify::CERTIFY_USAGE, plan::MEMORY_USAGE, memory::MEM_REPORT_USAGE, weighted::WEIGHT_USAGE, graph_io::LOAD_USAGE);
+    eprintln!("  {} serve [--socket PATH] {}", prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} stats|clear|shutdown [--socket PATH]", prog);
+    eprintln!("  {} rpc '<json-rpc message>' [--socket PATH]", prog);
+    eprintln!("  {} anonymize <file> [--caps FILE] [--default-cap K] [--algo {}] [--output FILE] \
+               [--show-edges] {}", prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} postman <weighted file> [--start V] [--t-set FILE] [--output FILE] [--show-tour] {}",
+              prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} cover <file> [--k K] [--approx|--bipartite] [--independent-set] [--output FILE] [--show] {}",
+              prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} geomatch <points file> [--k K] [--full] [--output FILE] [--show-pairs]", prog);
+    eprintln!("  {} stable <preferences file> [--propose left|right] [--output FILE] [--show-pairs]", prog);
+    eprintln!("  {} allocate <rankings file> [--popular] [--output FILE] [--show-pairs]", prog);
+    eprintln!("  {} vweight <file> [--weights FILE] [--default-weight W] [--output FILE] [--show-pairs] {}",
+              prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} kidney <arcs file> [--altruists FILE] [--max-cycle 2|3] [--algo {}] [--node-limit N] \
+               [--output FILE] [--show] {}", prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} edgedom <file> [--maximum] [--algo {}] [--improve] [--output FILE] [--show] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} ffactor <file> [--degrees FILE] [--default-degree F] [--algo {}] [--output FILE] [--show] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} bounds <file> [--no-lp] {}", prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} enumerate <file> [--limit K] [--algo {}] [--output FILE] [--show] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} classify <file> [--algo {}] [--output FILE] [--show] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} btf <matrix.mtx> [--row-perm FILE] [--col-perm FILE] [--blocks FILE] [--one-based] [--show]", prog);
+    eprintln!("  {} sparsify <file> [--rounds K|all] [--maximum] [--algo {}] [--until-preserved] [--no-verify] \
+               [--output FILE] [--schedule FILE] {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), graph_io::LOAD_USAGE);
+    eprintln!("  {} hypermatch <triples file> [--no-improve] [--output FILE] [--show]", prog);
+    eprintln!("  {} maxcut <file> [--weighted] [--restarts R] [--seed S] [--no-pairs] [--output FILE] [--show] {}",
+              prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} check <file> <matching file> [--bipartite] [--weighted] [--maximum] [--algo {}] {} {} {}",
+              prog, matchers::GENERAL_ALGORITHMS.join("|"), verify::CERTIFY_USAGE, verify::REPORT_USAGE, graph_io::LOAD_USAGE);
+    eprintln!("  {} plant <n> [--bipartite] [--degree D] [--max-potential W] [--gap G] [--spread S] [--seed S] \
+               [--output FILE] [--planted FILE]", prog);
+    eprintln!("  {} gen {} <n> [--depth D] [--levels L] [--width W] [--degree D] [--seed S] [--shuffle] \
+               [--output FILE] [--init FILE]", prog, adversarial::FAMILIES.join("|"));
+    eprintln!("  {} recover <n> [--bipartite] [--degrees D,...] [--gaps G,...] [--trials T] [--algos {}] \
+               [--max-potential W] [--spread S] [--seed S] [--csv FILE]", prog, recovery::algorithm_names().join(","));
+    eprintln!("  {} convert <in> <out> [--to text|binary] [--bipartite] [--weighted] {}", prog, graph_io::LOAD_USAGE);
+    eprintln!("  {} replay <file> <trace file> [--bipartite] {}", prog, graph_io::LOAD_USAGE);
+    eprintln!("Every command: {}", log::LOG_USAGE);
+    std::process::exit(1);
+}
+
+/* Solve on the graph relabeled by opts.reorder, matching returned in
+ * original ids. With an opts.phase0 mode the exact reductions of phase0.rs
+ * run first (tree and unicyclic components, also 2-core peeling and
+ * crowns), and only the rest is relabeled and solved; its summary line is
+ * returned too. Also returns the solver time (phase 0 included) and the
+ * relabeling time (ms). The solver starts from opts.greedy_mode (seeded by
+ * opts.seed) and opts.aug3 --init-aug3 passes, or from `init`, a matching
+ * of the graph (--init-matching); phase 0 would renumber what it leaves,
+ * so the two do not combine. */
+pub(crate) fn solve_reordered(
+    n: usize, edges: &[(usize, usize)], opts: &SolveOptions, init: Option<&[(usize, usize)]>,
+) -> Result<(matchers::Solution, u128, u128, Option<String>), String> {
+    if init.is_some() && opts.phase0 != phase0::Mode::Off {
+        return Err("--init-matching does not combine with --phase0, --crown or --fold".to_string());
+    }
+    let phase0_start = Instant::now();
+    let split = phase0::run(opts.phase0, n, edges);
+    let phase0_ms = phase0_start.elapsed().as_millis();
+    let (n, edges) = match split {
+        Some(ref s) => (s.rest_n, &s.rest_edges[..]),
+        None => (n, edges),
+    };
+
+    let reorder_start = Instant::now();
+    let relabel = ordering::relabeling(opts.reorder, n, edges);
+    let relabeled = relabel.as_ref().map(|r| r.apply(edges));
+    let reorder_ms = reorder_start.elapsed().as_millis();
+
+    let start = Instant::now();
+    let solver_edges = relabeled.as_ref().map_or(edges, |e| &e[..]);
+    let mut sol = match init {
+        Some(pairs) => {
+            let pairs = relabel.as_ref().map_or_else(|| pairs.to_vec(), |r| r.apply(pairs));
+            matchers::solve_from(opts.algo, n, solver_edges, &pairs)?
+        }
+        None => matchers::solve(opts.algo, n, solver_edges, opts.greedy_mode, opts.seed, opts.aug3)?,
+    };
+    let solve_ms = start.elapsed().as_millis() + phase0_ms;
+    if let Some(ref r) = relabel {
+        sol.matching = r.restore(&sol.matching);
+    }
+    let summary = split.map(|s| {
+        sol.matching = s.merge(&sol.matching);
+        /* pairs fixed before the solver runs count as initial matching */
+        sol.greedy_size += s.stats.solved_pairs;
+        format!("{} ({} ms)", s.summary(), phase0_ms)
+    });
+    Ok((sol, solve_ms, reorder_ms, summary))
+}
+
+/* Solve and print the usual report; shared by local runs and the daemon.
+ * `init` is the --init-matching start as loaded, `constraints` the --forbid
+ * and --force-edges pairs (`edges` is then what is left to solve) and
+ * `names` those of the graph's load, for --output-matching. */
+pub(crate) fn write_solution(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize)], opts: &SolveOptions, init: Option<&[(usize, usize)]>,
+    constraints: Option<&constraints::Constrained>, names: &graph_io::InputNames,
+) -> io::Result<()> {
+    let algo = opts.algo;
+    let heap_before = memory::mark();
+    let calls_before = memory::allocations();
+    let (mut sol, solve_ms, reorder_ms, phase0_summary) =
+        match solve_reordered(n, edges, opts, init) {
+            Ok(r) => r,
+            Err(e) => return writeln!(out, "Error: {}", e),
+        };
+    let heap_peak = memory::peak();
+    let solve_calls = memory::allocations() - calls_before;
+    /* before the forced pairs join: they are in every matching the tie is between */
+    let tie_break_summary = if opts.lex { Some(refine_lexicographic(n, edges, &mut sol.matching)) } else { None };
+    let count_summary = if opts.count { Some(count_summary(n, edges, sol.matching.len())) } else { None };
+    /* forced pairs count as initial matching, as phase 0's do; the matching is one of the allowed edges */
+    let edges = match constraints {
+        Some(c) => {
+            sol.matching.extend_from_slice(&c.forced);
+            sol.greedy_size += c.forced.len();
+            &c.allowed[..]
+        }
+        None => edges,
+    };
+
+    match sol.route {
+        Some(ref route) => {
+            writeln!(out, "Algorithm: {} ({})", algo, route.summary)?;
+            if opts.explain {
+                for r in &route.reasons {
+                    writeln!(out, "  {}", r)?;
+                }
+            }
+        }
+        None => writeln!(out, "Algorithm: {}", algo)?,
+    }
+    if let Some(ref s) = phase0_summary {
+        writeln!(out, "Phase 0: {}", s)?;
+    }
+    if opts.reorder != ordering::Reorder::Natural {
+        writeln!(out, "Reorder: {} ({} ms)", opts.reorder.name(), reorder_ms)?;
+    }
+    if let Some(ref s) = tie_break_summary {
+        writeln!(out, "Tie-break: {}", s)?;
+    }
+    let mut report = verify::validate_general(n, edges, &sol.matching);
+    if opts.certify && report.is_valid() {
+        report.optimal = Some(vertex_classes::certify(n, edges, &sol.matching));
+    }
+    report.write(out)?;
+    if opts.show_phases {
+        if sol.phases.is_empty() && !sol.matching.is_empty() {
+            writeln!(out, "Phase statistics: not recorded by {}\n", algo)?;
+        } else {
+            phase_stats::write_phase_stats(out, &sol.phases)?;
+        }
+    }
+    writeln!(out, "Matching size: {}", sol.matching.len())?;
+    if opts.greedy_mode > 0 {
+        writeln!(out, "Greedy init size: {}", sol.greedy_size)?;
+        if opts.aug3 > 0 {
+            writeln!(out, "Aug3 augmentations: {}", sol.aug3_size)?;
+        }
+        if !sol.matching.is_empty() {
+            writeln!(out, "Greedy/Final: {:.2}%",
+                     100.0 * sol.greedy_size as f64 / sol.matching.len() as f64)?;
+        } else {
+            writeln!(out, "Greedy/Final: NA")?;
+        }
+    }
+    if init.is_some() {
+        matching_io::write_init(out, sol.greedy_size, sol.matching.len())?;
+    }
+    if opts.lp_gap {
+        write_lp_gap(out, n, edges, &sol.matching)?;
+    }
+    if let Some(ref s) = count_summary {
+        writeln!(out, "{}", s)?;
+    }
+    if let Some(c) = constraints {
+        write_constraint_drop(out, algo, n, &c.all, sol.matching.len())?;
+    }
+    if opts.mem_report {
+        memory::write_report(out, algo, n, edges.len(), heap_before, heap_peak, solve_calls)?;
+    }
+    if let Some(sig) = interrupt::caught() {
+        writeln!(out, "Interrupted: {} (stopped at a phase boundary; the matching is valid but may not be maximum)",
+                 interrupt::name(sig))?;
+    }
+    if let Some(limit) = opts.time_limit.filter(|_| time_limit::expired()) {
+        time_limit::write_report(out, limit, time_limit::gap_bound(n, edges, &sol.matching))?;
+    }
+    if let Some(path) = opts.output {
+        if let Err(e) = matching_io::save_matching(path, &sol.matching, false, names) {
+            return writeln!(out, "Error: {}", e);
+        }
+        writeln!(out, "Wrote {} pairs to {}", sol.matching.len(), path)?;
+    }
+    if let Some(path) = opts.dot {
+        if let Err(e) = dot::save(path, n, edges, &sol.matching, None) {
+            return writeln!(out, "Error: {}", e);
+        }
+        writeln!(out, "Wrote the drawing to {}", path)?;
+    }
+    if let Some((path, layout)) = opts.svg {
+        if let Err(e) = svg::save(path, n, edges, &sol.matching, None, layout) {
+            return writeln!(out, "Error: {}", e);
+        }
+        writeln!(out, "Wrote the picture to {}", path)?;
+    }
+    if opts.trace.is_some() {
+        if let Err(e) = trace::finish(sol.matching.len()) {
+            return writeln!(out, "Error: {}", e);
+        }
+    }
+    writeln!(out, "Time: {} ms", solve_ms)
+}
+
+/* The fractional matching LP optimum and its gap to `matching`, a maximum
+ * matching unless the run was interrupted or ran out of time. */
+fn write_lp_gap(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)],
+) -> io::Result<()> {
+    if interrupt::caught().is_some() {
+        return writeln!(out, "LP optimum: skipped (interrupted, the matching may not be maximum)");
+    }
+    if time_limit::expired() {
+        return writeln!(out, "LP optimum: skipped (time limit reached, the matching may not be maximum)");
+    }
+    let start = Instant::now();
+    let (r, x) = matching_bounds::lp_relaxation(n, edges, matching);
+    writeln!(out, "LP optimum: {:.1} (fractional matching, {} ms)", r.lp(), start.elapsed().as_millis())?;
+    writeln!(out, "LP solution: {} edges at 1, {} odd cycle(s) at 1/2 ({} vertices)", x.ones.len(), x.cycles.len(),
+             x.cycles.iter().map(|c| c.len()).sum::<usize>())?;
+    let size = matching.len();
+    if size > 0 {
+        writeln!(out, "Integrality gap: {:.1} (LP/matching {:.5}) in {} of {} components",
+                 r.lp() - size as f64, r.lp() / size as f64, r.gap_components, r.components)
+    } else {
+        writeln!(out, "Integrality gap: 0.0 (no edges)")
+    }
+}
+
+/* --tie-break lex: `matching` becomes the lexicographically smallest
+ * maximum matching of (n, edges); returns the summary line. */
+fn refine_lexicographic(n: usize, edges: &[(usize, usize)], matching: &mut Vec<(usize, usize)>) -> String {
+    if interrupt::caught().is_some() || time_limit::expired() {
+        return "skipped (the matching may not be maximum)".to_string();
+    }
+    let start = Instant::now();
+    let mut before: Vec<(usize, usize)> = matching.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    before.sort_unstable();
+    *matching = tie_break::lexicographic(n, edges, &before);
+    let changed = matching.iter().filter(|p| before.binary_search(p).is_err()).count();
+    format!("lexicographically smallest, {} of {} pairs changed ({} ms)", changed, matching.len(),
+            start.elapsed().as_millis())
+}
+
+/* --count-matchings: the number of maximum matchings of (n, edges), the
+ * graph the solver saw, checked against `size`, the solver's matching. */
+fn count_summary(n: usize, edges: &[(usize, usize)], size: usize) -> String {
+    if interrupt::caught().is_some() || time_limit::expired() {
+        return "Maximum matchings: skipped (the matching may not be maximum)".to_string();
+    }
+    let start = Instant::now();
+    let c = match count_matchings::count_maximum_matchings(n, edges) {
+        Ok(c) => c,
+        Err(e) => return format!("Maximum matchings: not counted ({})", e),
+    };
+    if c.size != size {
+        return format!("ERROR: the count is of matchings of {} pairs, the solver found {}!", c.size, size);
+    }
+    let ms = start.elapsed().as_millis();
+    if c.width > 0 {
+        format!("Maximum matchings: {} ({} component(s), {} by permanent, frontier width {}, {} ms)", c.count,
+                c.components, c.ryser, c.width, ms)
+    } else {
+        format!("Maximum matchings: {} ({} component(s), {} by permanent, {} ms)", c.count, c.components, c.ryser, ms)
+    }
+}
+
+/* The unconstrained maximum matching of (n, edges), the graph before
+ * --forbid and --force-edges, against `size`, the matching under them. */
+fn write_constraint_drop(
+    out: &mut dyn Write, algo: &str, n: usize, edges: &[(usize, usize)], size: usize,
+) -> io::Result<()> {
+    if interrupt::caught().is_some() || time_limit::expired() {
+        return writeln!(out, "Unconstrained matching size: skipped (the matching may not be maximum)");
+    }
+    let start = Instant::now();
+    let best = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m.len(),
+        Err(e) => return writeln!(out, "Error: {}", e),
+    };
+    let drop = best.saturating_sub(size);
+    writeln!(out, "Unconstrained matching size: {} (drop {}, {:.3}%, {} ms)", best, drop,
+             if best > 0 { 100.0 * drop as f64 / best as f64 } else { 0.0 }, start.elapsed().as_millis())
+}
+
+/* The system allocator, but a failed allocation ends the process with a
+ * one-line "Error:" and status 1, as every other refusal does, instead of
+ * the runtime's abort. The memory check before a solve refuses most such
+ * runs before they load; this covers --no-memory-check and a wrong guess.
+ * It also counts the bytes in use for --mem-report (memory.rs). */
+struct ReportingAlloc;
+
+#[global_allocator]
+static ALLOCATOR: ReportingAlloc = ReportingAlloc;
+
+unsafe impl GlobalAlloc for ReportingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        memory::grew(layout.size());
+        checked(System.alloc(layout), layout.size())
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        memory::grew(layout.size());
+        checked(System.alloc_zeroed(layout), layout.size())
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        memory::shrank(layout.size());
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        memory::shrank(layout.size());
+        memory::grew(new_size);
+        checked(System.realloc(ptr, layout, new_size), new_size)
+    }
+}
+
+fn checked(ptr: *mut u8, size: usize) -> *mut u8 {
+    if ptr.is_null() {
+        /* formatted on the stack: nothing may allocate here */
+        let mut buf = [0u8; 256];
+        let len = {
+            let mut cur = io::Cursor::new(&mut buf[..]);
+            let _ = writeln!(cur, "Error: out of memory (an allocation of {} bytes failed); \
+                                   `combi solve --dry-run` estimates the peak", size);
+            cur.position() as usize
+        };
+        let _ = io::stderr().write_all(&buf[..len]);
+        std::process::exit(1);
+    }
+    ptr
+}
+
+/* The error's exit status is SuiteError::exit_code: 65 and up for an
+ * input that cannot be read or solved, 1 for any other refusal. */
+fn exit_on_error<T, E: Into<SuiteError>>(r: Result<T, E>) -> T {
+    match r {
+        Ok(v) => v,
+        Err(e) => {
+            let e = e.into();
+            eprintln!("Error: {}", e);
+            std::process::exit(e.exit_code());
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 { usage(&args[0]); }
+    let socket = args::value_of(&args, "--socket");
+    let load_opts = exit_on_error(graph_io::LoadOptions::from_args(&args));
+    exit_on_error(log::from_args(&args));
+
+    match args[1].as_str() {
+        "solve" => {
+            if args.len() < 3 { usage(&args[0]); }
+            /* a weighted file goes to the weighted solver; refusals before anything is loaded */
+            let objective = exit_on_error(weighted::route(&args, &args[2], &load_opts));
+            let mut opts = exit_on_error(SolveOptions::from_args(&args));
+            if objective.is_some() {
+                opts.algo = matchers::WEIGHTED_ALGORITHM;
+            }
+            let algo = opts.algo;
+            if socket.is_some() && (opts.local_only() || !load_opts.input.has_header() || load_opts.edge_ids) {
+                eprintln!("Error: --reorder, --phase-stats, --init-aug3, --lp-gap, --explain, --progress, --time-limit, --phase0, --crown, \
+                           --fold, --init-matching, --output-matching, --trace, --dot, --svg, --forbid, --force-edges, --tie-break, --count-matchings, --mem-report, --certify, --format snap, --format labels and --edge-ids are local only (use the JSON-RPC solve method with a daemon)");
+                std::process::exit(1);
+            }
+            if socket.is_some() && objective.is_some() {
+                eprintln!("Error: a weighted graph is solved locally only (the daemon runs the cardinality solvers; \
+                           --ignore-weights solves it there by cardinality)");
+                std::process::exit(1);
+            }
+            /* The trace is in the solver's ids, which phase 0 and --reorder change. */
+            if opts.trace.is_some() && (opts.phase0 != phase0::Mode::Off || opts.reorder != ordering::Reorder::Natural) {
+                eprintln!("Error: --trace does not combine with --phase0, --crown, --fold or --reorder");
+                std::process::exit(1);
+            }
+            /* The LP, the trace and the certificate are of the graph the solver sees, which the forced pairs are not in. */
+            if opts.force.is_some() && (opts.lp_gap || opts.trace.is_some() || opts.certify) {
+                eprintln!("Error: --force-edges does not combine with --lp-gap, --trace or --certify");
+                std::process::exit(1);
+            }
+            /* The trace ends at the solver's matching, not the refined one. */
+            if opts.lex && opts.trace.is_some() {
+                eprintln!("Error: --tie-break lex does not combine with --trace");
+                std::process::exit(1);
+            }
+            if args::has_flag(&args, "--dry-run") {
+                exit_on_error(plan::run(&args[2], &load_opts, &opts, socket));
+            } else if let Some(sock) = socket {
+                let path = exit_on_error(std::fs::canonicalize(&args[2]));
+                let mode = if opts.greedy_mode == 4 { format!("4:{}", opts.seed) } else { opts.greedy_mode.to_string() };
+                /* the daemon parses the file as this run would have */
+                let flags: String = load_opts.to_args().iter().map(|f| format!("{} ", f)).collect();
+                let line = format!("SOLVE {} {} {}{}", algo, mode, flags, path.display());
+                exit_on_error(serve::request(sock, &line));
+            } else {
+                if opts.init.is_some() && opts.phase0 != phase0::Mode::Off {
+                    eprintln!("Error: --init-matching does not combine with --phase0, --crown or --fold");
+                    std::process::exit(1);
+                }
+                /* a SNAP or labeled file has no header to size the graph by ahead of the load */
+                if !args::has_flag(&args, "--no-memory-check") && load_opts.input.has_header() {
+                    let (n, m) = exit_on_error(graph_io::read_graph_header(&args[2]));
+                    exit_on_error(plan::check_memory(algo, n, m));
+                }
+                if let Some(objective) = objective {
+                    let stdout = io::stdout();
+                    exit_on_error(weighted::run(&mut stdout.lock(), &args[2], &load_opts, objective,
+                                                args::has_flag(&args, "--check-duals"), &opts));
+                    return;
+                }
+                let (n, mut edges, names) = exit_on_error(graph_io::load_graph_named(&args[2], &load_opts));
+                println!("Graph: {} vertices, {} edges", n, edges.len());
+                exit_on_error(matchers::check_vertex_count(algo, n));
+                let constrained = if opts.forbid.is_some() || opts.force.is_some() {
+                    let all = edges.clone();
+                    let mut forbidden = Vec::new();
+                    if let Some(path) = opts.forbid {
+                        forbidden = exit_on_error(constraints::load_pairs(path, n, &names));
+                        let (kept, removed) = constraints::remove_forbidden(&edges, &forbidden);
+                        println!("Forbidden pairs: {} listed, {} edges removed", forbidden.len(), removed);
+                        edges = kept;
+                    }
+                    let mut forced = Vec::new();
+                    if let Some(path) = opts.force {
+                        forced = exit_on_error(constraints::load_pairs(path, n, &names));
+                        exit_on_error(constraints::check_forced(n, &edges, &forced, &forbidden)
+                                          .map_err(|e| format!("{}: {}", path, e)));
+                    }
+                    let (rest, removed) = constraints::remove_forced(n, &edges, &forced);
+                    if opts.force.is_some() {
+                        println!("Forced pairs: {} listed, {} edges removed at their {} vertices",
+                                 forced.len(), removed, 2 * forced.len());
+                    }
+                    Some(constraints::Constrained { all, allowed: std::mem::replace(&mut edges, rest), forced })
+                } else {
+                    None
+                };
+                if opts.svg.is_some() {
+                    exit_on_error(svg::check_size(n));
+                }
+                let init = exit_on_error(matching_io::init_from_args(&args, (n, n), &edges, false, &names));
+                interrupt::install();
+                matchers::stop_on(&interrupt::STOP);
+                if opts.progress {
+                    progress::start();
+                    matchers::progress_on(&progress::report);
+                }
+                if let Some(limit) = opts.time_limit {
+                    time_limit::start(limit, &interrupt::STOP);
+                }
+                if let Some(path) = opts.trace {
+                    exit_on_error(trace::open(path, &format!("{} {}", algo, args[2])));
+                    matchers::trace_on(&trace::record);
+                } else if log::enabled(log::Level::Debug) {
+                    matchers::trace_on(&log::event);
+                }
+                let stdout = io::stdout();
+                exit_on_error(write_solution(&mut stdout.lock(), n, &edges, &opts, init.as_deref(),
+                                             constrained.as_ref(), &names));
+                if let Some(sig) = interrupt::caught() {
+                    let _ = io::stdout().flush();
+                    std::process::exit(interrupt::exit_code(sig));
+                }
+            }
+        }
+        "serve" => {
+            let sock = socket.unwrap_or(serve::DEFAULT_SOCKET);
+            exit_on_error(serve::serve(sock, load_opts));
+        }
+        "stats" | "clear" | "shutdown" => {
+            let sock = socket.unwrap_or(serve::DEFAULT_SOCKET);
+            exit_on_error(serve::request(sock, &args[1].to_uppercase()));
+        }
+        "rpc" => {
+            if args.len() < 3 { usage(&args[0]); }
+            let sock = socket.unwrap_or(serve::DEFAULT_SOCKET);
+            exit_on_error(serve::request(sock, &args[2]));
+        }
+        "anonymize" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(anonymize::run(&args, &load_opts));
+        }
+        "postman" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(postman::run(&args, &load_opts));
+        }
+        "cover" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(cover::run(&args, &load_opts));
+        }
+        "geomatch" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(geomatch::run(&args));
+        }
+        "stable" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(stable::run(&args));
+        }
+        "allocate" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(allocate::run(&args));
+        }
+        "vweight" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(vweight::run(&args, &load_opts));
+        }
+        "kidney" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(kidney::run(&args, &load_opts));
+        }
+        "edgedom" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(edgedom::run(&args, &load_opts));
+        }
+        "ffactor" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(ffactor::run(&args, &load_opts));
+        }
+        "check" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(check::run(&args, &load_opts));
+        }
+        "bounds" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(bounds::run(&args, &load_opts));
+        }
+        "enumerate" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(enumerate::run(&args, &load_opts));
+        }
+        "classify" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(classify::run(&args, &load_opts));
+        }
+        "btf" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(btf::run(&args));
+        }
+        "sparsify" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(sparsify::run(&args, &load_opts));
+        }
+        "hypermatch" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(hypermatch::run(&args));
+        }
+        "maxcut" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(maxcut::run(&args, &load_opts));
+        }
+        "plant" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(plant::run(&args));
+        }
+        "gen" => {
+            if args.len() < 4 { usage(&args[0]); }
+            exit_on_error(gen::run(&args));
+        }
+        "recover" => {
+            if args.len() < 3 { usage(&args[0]); }
+            exit_on_error(recover::run(&args));
+        }
+        "convert" => {
+            if args.len() < 4 { usage(&args[0]); }
+            exit_on_error(convert::run(&args, &load_opts));
+        }
+        "replay" => {
+            if args.len() < 4 { usage(&args[0]); }
+            exit_on_error(replay::run(&args, &load_opts));
+        }
+        _ => usage(&args[0]),
+    }
+}
@@ -0,0 +1,83 @@
+/*
+ * combi convert - edge lists between text and the binary format.
+ *
+ * The binary format (graph_io.rs: a 40-byte header, u32 id pairs, i64
+ * weights) loads with no parsing at all, so a large instance that is
+ * solved again and again pays for the text once. Every loader reads both
+ * forms, so the converted file goes wherever the text one did.
+ *
+ * The direction defaults to the other form (--to text|binary picks it).
+ * A binary file says whether it is bipartite and weighted; for text,
+ * JSON and GraphML, --bipartite and --weighted say so. The loader options apply as on any
+ * load, so e.g. --merge-parallel sum folds the graph on the way.
+ */
+
+use std::fs::File;
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+
+/* Weighted edges as the writers take them. */
+fn split(edges: Vec<(usize, usize, i64)>) -> (Vec<(usize, usize)>, Option<Vec<i64>>) {
+    (edges.iter().map(|&(u, v, _)| (u, v)).collect(), Some(edges.iter().map(|e| e.2).collect()))
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let (input, output) = (&args[2], &args[3]);
+    let header = graph_io::binary_header(input)?;
+    let form = graph_io::input_format(input)?;
+    let (bipartite, weighted) = match header {
+        Some(h) => (h.bipartite, h.weighted),
+        None => (args::has_flag(args, "--bipartite"), args::has_flag(args, "--weighted")),
+    };
+    let binary = match args::value_of(args, "--to") {
+        None => header.is_none(),
+        Some("binary") => true,
+        Some("text") => false,
+        Some(s) => return Err(format!("invalid value for --to: {} (expected text or binary)", s).into()),
+    };
+
+    let start = Instant::now();
+    let (left, right, edges, weights) = match (bipartite, weighted) {
+        (false, false) => {
+            let (n, edges) = graph_io::load_graph(input, opts)?;
+            (n, None, edges, None)
+        }
+        (false, true) => {
+            let (n, edges) = graph_io::load_weighted_graph(input, opts)?;
+            let (edges, weights) = split(edges);
+            (n, None, edges, weights)
+        }
+        (true, false) => {
+            let (left, right, edges) = graph_io::load_bipartite_graph(input, opts)?;
+            (left, Some(right), edges, None)
+        }
+        (true, true) => {
+            let (left, right, edges) = graph_io::load_weighted_bipartite_graph(input, opts)?;
+            let (edges, weights) = split(edges);
+            (left, Some(right), edges, weights)
+        }
+    };
+    let read_ms = start.elapsed().as_millis();
+    match right {
+        Some(right) => println!("Graph: {} + {} vertices, {} edges", left, right, edges.len()),
+        None => println!("Graph: {} vertices, {} edges", left, edges.len()),
+    }
+    println!("Read: {} ({}{}) in {} ms", input, form,
+             if weighted { ", weighted" } else { "" }, read_ms);
+
+    let start = Instant::now();
+    let file = File::create(output).map_err(|e| format!("{}: {}", output, e))?;
+    let written = if binary {
+        graph_io::write_binary(file, left, right, &edges, weights.as_deref())
+    } else {
+        graph_io::write_text(file, left, right, &edges, weights.as_deref())
+    };
+    written.map_err(|e| format!("{}: {}", output, e))?;
+    let size = std::fs::metadata(output).map(|m| m.len()).unwrap_or(0);
+    println!("Wrote: {} ({}, {} bytes) in {} ms", output, if binary { "binary" } else { "text" }, size,
+             start.elapsed().as_millis());
+    Ok(())
+}
@@ -0,0 +1,182 @@
+/*
+ * combi cover - minimum vertex cover / maximum independent set.
+ *
+ * Exact, via the matching-based LP kernel and branching of
+ * vertex_cover.rs. With --k K it answers the parameterized question "is
+ * there a cover of at most K vertices?", and gives up early when the LP
+ * bound already exceeds K. --independent-set reports the complement.
+ * --approx replaces the branching by a maximal-matching 2-approximation of
+ * the kernel, for graphs too large to solve exactly. --bipartite reads a
+ * bipartite file and solves it exactly by Konig's theorem.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::vertex_cover;
+
+fn write_vertices(path: &str, vertices: &[usize]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &v in vertices {
+        writeln!(f, "{}", v)?;
+    }
+    f.flush()
+}
+
+fn write_sides(path: &str, left: &[usize], right: &[usize]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &u in left {
+        writeln!(f, "L {}", u)?;
+    }
+    for &v in right {
+        writeln!(f, "R {}", v)?;
+    }
+    f.flush()
+}
+
+fn complement(n: usize, set: &[usize]) -> Vec<usize> {
+    let mut inside = vec![false; n];
+    for &v in set { inside[v] = true; }
+    (0..n).filter(|&v| !inside[v]).collect()
+}
+
+fn run_bipartite(args: &[String], opts: &LoadOptions, k: Option<usize>, independent: bool) -> Result<(), SuiteError> {
+    let (left, right, edges) = graph_io::load_bipartite_graph(&args[2], opts)?;
+    println!("Graph: {} left, {} right, {} edges", left, right, edges.len());
+
+    let start = Instant::now();
+    let cover = vertex_cover::konig_cover(left, right, &edges);
+    let duration = start.elapsed();
+    let size = cover.left.len() + cover.right.len();
+    println!("Maximum matching: {} (Konig)", cover.matching_size);
+    if let Some(k) = k {
+        if size > k {
+            println!("No vertex cover of size <= {} (minimum {})", k, size);
+            println!("Time: {} ms", duration.as_millis());
+            return Ok(());
+        }
+    }
+
+    let errors = vertex_cover::check_bipartite_cover(left, right, &edges, &cover);
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Cover size: {} ({} left, {} right)", size, cover.left.len(), cover.right.len());
+    println!("Independent set size: {}", left + right - size);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    let (shown_left, shown_right) = if independent {
+        (complement(left, &cover.left), complement(right, &cover.right))
+    } else {
+        (cover.left.clone(), cover.right.clone())
+    };
+    if args::has_flag(args, "--show") {
+        let l: Vec<String> = shown_left.iter().map(|v| v.to_string()).collect();
+        let r: Vec<String> = shown_right.iter().map(|v| v.to_string()).collect();
+        println!("left: {}\nright: {}\n", l.join(" "), r.join(" "));
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_sides(path, &shown_left, &shown_right).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} vertices to {}", shown_left.len() + shown_right.len(), path);
+    }
+    if independent {
+        println!("Independent set size: {}", left + right - size);
+    } else {
+        println!("Cover size: {}", size);
+    }
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let k = match args::value_of(args, "--k") {
+        Some(s) => Some(s.parse::<usize>().map_err(|_| format!("invalid --k '{}'", s))?),
+        None => None,
+    };
+    let independent = args::has_flag(args, "--independent-set");
+    let approx = args::has_flag(args, "--approx");
+    if args::has_flag(args, "--bipartite") {
+        if approx {
+            return Err("--approx is for general graphs; --bipartite is already exact".into());
+        }
+        return run_bipartite(args, opts, k, independent);
+    }
+    if approx && k.is_some() {
+        return Err("--k needs the exact search; drop --approx".into());
+    }
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+
+    let start = Instant::now();
+    let kernel = vertex_cover::lp_kernel(n, &edges);
+    println!("LP bound: {}", kernel.lp_bound);
+    println!("Kernel: {} vertices ({} forced into the cover, {} excluded)",
+             kernel.kernel.len(), kernel.forced.len(), kernel.excluded.len());
+    if let Some(k) = k {
+        if kernel.lp_bound > k {
+            println!("No vertex cover of size <= {} (LP bound {})", k, kernel.lp_bound);
+            println!("Time: {} ms", start.elapsed().as_millis());
+            return Ok(());
+        }
+    }
+    let (cover, duration) = if approx {
+        let a = vertex_cover::approx_cover(n, &edges, &kernel);
+        let duration = start.elapsed();
+        println!("Lower bound: {} (ratio at most {:.3})", a.lower_bound,
+                 if a.lower_bound > 0 { a.cover.len() as f64 / a.lower_bound as f64 } else { 1.0 });
+        (a.cover, duration)
+    } else {
+        let budget = k.map(|k| k - kernel.forced.len());
+        let branching = vertex_cover::solve_kernel(n, &edges, &kernel, budget);
+        let duration = start.elapsed();
+        println!("Branch nodes: {}", branching.nodes);
+        let mut cover = match branching.cover {
+            Some(c) => c,
+            None => {
+                println!("No vertex cover of size <= {}", k.unwrap_or(0));
+                println!("Time: {} ms", duration.as_millis());
+                return Ok(());
+            }
+        };
+        cover.extend_from_slice(&kernel.forced);
+        cover.sort_unstable();
+        (cover, duration)
+    };
+
+    let mut errors = vertex_cover::check_cover(n, &edges, &cover);
+    if cover.len() < kernel.lp_bound {
+        errors.push(format!("Cover of {} vertices is below the LP bound {}!", cover.len(), kernel.lp_bound));
+    }
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    let independent_set = complement(n, &cover);
+    println!("\n=== Validation Report ===");
+    println!("Cover size: {}{}", cover.len(), if approx { " (2-approximation)" } else { "" });
+    println!("Independent set size: {}", independent_set.len());
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    let shown = if independent { &independent_set } else { &cover };
+    if args::has_flag(args, "--show") {
+        let s: Vec<String> = shown.iter().map(|v| v.to_string()).collect();
+        println!("{}\n", s.join(" "));
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_vertices(path, shown).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} vertices to {}", shown.len(), path);
+    }
+    if independent {
+        println!("Independent set size: {}", independent_set.len());
+    } else {
+        println!("Cover size: {}", cover.len());
+    }
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
@@ -0,0 +1,71 @@
+/*
+ * combi edgedom - edge dominating set: few edges such that every edge of
+ * the graph shares an endpoint with one of them.
+ *
+ * A maximal matching is one, and has at most twice the minimum number of
+ * edges. By default it is the greedy maximal matching of
+ * edge_dominating.rs; --maximum takes a maximum matching from --algo
+ * instead, which also gives the better lower bound. --improve reruns the
+ * endpoints as a vertex cover: prune it, rematch it, and keep the smaller
+ * set. The result is checked for domination against the graph.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::edge_dominating;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+
+fn write_edges(path: &str, edges: &[(usize, usize)]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &(u, v) in edges {
+        writeln!(f, "{} {}", u, v)?;
+    }
+    f.flush()
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let maximum = args::has_flag(args, "--maximum");
+    let improve = args::has_flag(args, "--improve");
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+
+    let start = Instant::now();
+    let result = edge_dominating::edge_dominating_set(algo, n, &edges, maximum, improve)?;
+    let duration = start.elapsed();
+    println!("Starting matching: {} ({})", result.matching_size,
+             if maximum { format!("maximum, {}", algo) } else { "greedy maximal".to_string() });
+    if improve {
+        println!("Improvement rounds: {} ({} edges saved)", result.rounds, result.matching_size - result.edges.len());
+    }
+    println!("Lower bound: {} (ratio at most {:.3})", result.lower_bound,
+             if result.lower_bound > 0 { result.edges.len() as f64 / result.lower_bound as f64 } else { 1.0 });
+
+    let errors = edge_dominating::check_edge_dominating(n, &edges, &result.edges);
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Dominating set size: {}", result.edges.len());
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show") {
+        for &(u, v) in &result.edges {
+            println!("{} {}", u, v);
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_edges(path, &result.edges).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} edges to {}", result.edges.len(), path);
+    }
+    println!("Dominating set size: {}", result.edges.len());
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
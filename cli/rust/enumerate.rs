@@ -0,0 +1,130 @@
+/*
+ * combi enumerate - list the maximum matchings of a graph, up to a limit.
+ *
+ * Solves the graph with --algo, then streams the other maximum matchings
+ * from that one (see enumerate_matchings.rs), at most --limit K in all
+ * (1000 by default). Every matching listed is checked: a matching of the
+ * graph, as large as the first, and not listed before (by a 64-bit hash).
+ * The report is a sensitivity summary of the assignment: the pairs in
+ * every matching listed, and those in some but not all. Once the
+ * enumeration is complete these are exact: the pairs every maximum
+ * matching holds, and the edges none does. --show prints each matching as
+ * it is found, and --output writes them as "index u v" lines.
+ */
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::enumerate_matchings::{self, MaximumMatchings};
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+
+/* What is wrong with `m` as the `index`th maximum matching, if anything. */
+fn check(index: usize, m: &[(usize, usize)], n: usize, edges: &HashSet<(usize, usize)>, size: usize)
+         -> Option<String> {
+    if m.len() != size {
+        return Some(format!("matching {} has {} pairs, the first {}", index, m.len(), size));
+    }
+    let mut used = vec![false; n];
+    for &(u, v) in m {
+        if !edges.contains(&(u, v)) || used[u] || used[v] {
+            return Some(format!("matching {}: ({}, {}) is not a free edge of the graph", index, u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+    }
+    None
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let limit = match args::value_of(args, "--limit") {
+        Some(s) => match s.parse::<usize>() {
+            Ok(k) if k > 0 => k,
+            _ => return Err(format!("invalid --limit '{}' (a positive count)", s).into()),
+        },
+        None => enumerate_matchings::DEFAULT_LIMIT,
+    };
+    let show = args::has_flag(args, "--show");
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    matchers::check_vertex_count(algo, n)?;
+
+    let start = Instant::now();
+    let first = matchers::maximum_matching(algo, n, &edges)?;
+    println!("Algorithm: {} (matching size {}, {} ms)", algo, first.len(), start.elapsed().as_millis());
+
+    let mut out = match args::value_of(args, "--output") {
+        Some(path) => Some((path, BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?))),
+        None => None,
+    };
+    let edge_set: HashSet<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    let mut seen = HashSet::new();
+    let mut held: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut errors = Vec::new();
+    let mut listed = 0;
+    let start = Instant::now();
+    let mut all = MaximumMatchings::new(n, &edges, &first, limit);
+    for m in all.by_ref() {
+        listed += 1;
+        if let Some(e) = check(listed, &m, n, &edge_set, first.len()) {
+            errors.push(e);
+        }
+        if show {
+            let pairs: Vec<String> = m.iter().map(|&(u, v)| format!("{}-{}", u, v)).collect();
+            println!("  {:>6}: {}", listed, pairs.join(" "));
+        }
+        if let Some((path, ref mut f)) = out {
+            for &(u, v) in &m {
+                writeln!(f, "{} {} {}", listed, u, v).map_err(|e| format!("{}: {}", path, e))?;
+            }
+        }
+        for &p in &m {
+            *held.entry(p).or_insert(0) += 1;
+        }
+        let mut h = DefaultHasher::new();
+        m.hash(&mut h);
+        if !seen.insert(h.finish()) {
+            errors.push(format!("matching {} was listed before", listed));
+        }
+    }
+    let duration = start.elapsed();
+    let complete = all.exhausted();
+    println!("Maximum matchings: {} listed, {} ({} searches)", listed,
+             if complete { "all of them".to_string() } else { format!("stopped at --limit {}", limit) },
+             all.searches);
+
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Matchings listed: {}", listed);
+    println!("Matching size: {}", first.len());
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if let Some((path, mut f)) = out {
+        f.flush().map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} matchings to {}", listed, path);
+    }
+    let always = held.values().filter(|&&c| c == listed).count();
+    if complete {
+        println!("Pairs in every maximum matching: {} of {}", always, first.len());
+        println!("Pairs in some but not all: {}", held.len() - always);
+        println!("Edges in none: {}", edge_set.len() - held.len());
+    } else {
+        println!("Pairs in every matching listed: {} of {}", always, first.len());
+        println!("Pairs in some but not all listed: {}", held.len() - always);
+    }
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
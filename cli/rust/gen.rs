@@ -0,0 +1,92 @@
+/*
+ * combi gen - adversarial instances for the cardinality solvers.
+ *
+ * Writes an unweighted graph ("n m", then "u v") of one of the families of
+ * adversarial.rs on about <n> vertices: chains (against the per-root
+ * restart of the C++ and Python Edmonds simple), nested-blossoms (petal
+ * handling) or even-collisions (Gabow's blossoms from EVEN-EVEN edges).
+ * --depth, --levels, --width and --degree set the shapes, and --shuffle
+ * relabels the vertices at random (chains then loses the numbering it
+ * relies on). The same --seed gives the same instance. The graph goes to
+ * --output, or to stdout with the summary on stderr; --init writes the
+ * starting matching the family is meant to be solved from as a matching
+ * file (matching_io.rs), for `combi solve --init-matching`. The summary
+ * gives the maximum matching size, known by construction.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use super::adversarial::{self, GenOptions};
+use super::args;
+use super::graph_io;
+use super::matching_io;
+use super::rng::Rng;
+
+fn parse_opt(args: &[String], name: &str) -> Result<Option<usize>, String> {
+    match args::value_of(args, name) {
+        None => Ok(None),
+        Some(s) => match s.parse() {
+            Ok(k) if k > 0 => Ok(Some(k)),
+            _ => Err(format!("invalid {} '{}' (expected a positive integer)", name, s)),
+        },
+    }
+}
+
+fn write_graph(out: &mut dyn Write, inst: &adversarial::Instance) -> io::Result<()> {
+    writeln!(out, "{} {}", inst.n, inst.edges.len())?;
+    for &(u, v) in &inst.edges {
+        writeln!(out, "{} {}", u, v)?;
+    }
+    out.flush()
+}
+
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let family = args[2].as_str();
+    let n: usize = match args[3].parse() {
+        Ok(n) if n >= 2 => n,
+        _ => return Err(format!("invalid vertex count '{}'", args[3])),
+    };
+    let opts = GenOptions {
+        depth: parse_opt(args, "--depth")?,
+        levels: parse_opt(args, "--levels")?,
+        width: parse_opt(args, "--width")?,
+        degree: parse_opt(args, "--degree")?,
+    };
+    let seed = args::seed_of(args)?;
+    let mut rng = Rng::new(seed);
+    let mut inst = adversarial::generate(family, n, &opts, &mut rng)?;
+    if args::has_flag(args, "--shuffle") {
+        adversarial::shuffle(&mut inst, &mut rng);
+    }
+
+    let output = args::value_of(args, "--output");
+    match output {
+        Some(path) => {
+            let mut f = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+            write_graph(&mut f, &inst).map_err(|e| format!("{}: {}", path, e))?;
+        }
+        None => {
+            let stdout = io::stdout();
+            write_graph(&mut stdout.lock(), &inst).map_err(|e| e.to_string())?;
+        }
+    }
+    if let Some(path) = args::value_of(args, "--init") {
+        matching_io::save_matching(path, &inst.init, false, &graph_io::InputNames::default())?;
+    }
+
+    let mut info: Box<dyn Write> = if output.is_some() { Box::new(io::stdout()) } else { Box::new(io::stderr()) };
+    let lines = [
+        format!("Generated: {}, {} vertices, {} edges, seed {}", family, inst.n, inst.edges.len(), seed),
+        if inst.init.is_empty() {
+            "Starting matching: empty".to_string()
+        } else {
+            format!("Starting matching: {} pairs (--init)", inst.init.len())
+        },
+        format!("Maximum matching: {}", inst.maximum),
+    ];
+    for line in &lines {
+        writeln!(info, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
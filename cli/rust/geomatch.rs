@@ -0,0 +1,109 @@
+/*
+ * combi geomatch - minimum-weight perfect matching of points in the plane.
+ *
+ * The pairing step of Christofides' TSP heuristic and of postman tours on
+ * geometric instances. The complete graph is never built: the solver works
+ * on k-nearest-neighbour candidates and adds pairs only when the blossom
+ * duals show they are needed (see geometric_matching.rs). --full solves the
+ * complete graph as well and compares the weights.
+ *
+ * Input: "n", then n lines "x y"; weights are TSPLIB EUC_2D distances.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::geometric_matching::{self, GeometricMatching};
+use super::weighted_blossom;
+
+fn write_pairs(path: &str, pairs: &[(usize, usize)]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &(u, v) in pairs {
+        writeln!(f, "{} {}", u, v)?;
+    }
+    f.flush()
+}
+
+fn check_pairs(points: &[(f64, f64)], gm: &GeometricMatching) -> Vec<String> {
+    let n = points.len();
+    let mut errors = Vec::new();
+    let mut used = vec![false; n];
+    let mut weight = 0;
+    for &(u, v) in &gm.pairs {
+        if u >= n || v >= n || u == v || used[u] || used[v] {
+            errors.push(format!("Pair ({}, {}) is not part of a matching!", u, v));
+            continue;
+        }
+        used[u] = true;
+        used[v] = true;
+        weight += geometric_matching::distance(points[u], points[v]);
+    }
+    if let Some(v) = (0..n).find(|&v| !used[v]) {
+        errors.push(format!("Point {} is unmatched!", v));
+    }
+    if weight != gm.weight {
+        errors.push(format!("Pairs weigh {}, solver reports {}!", weight, gm.weight));
+    }
+    errors
+}
+
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let k = match args::value_of(args, "--k") {
+        Some(s) => match s.parse::<usize>() {
+            Ok(k) if k > 0 => k,
+            _ => return Err(format!("--k must be a positive integer, got '{}'", s)),
+        },
+        None => geometric_matching::DEFAULT_K,
+    };
+    let points = geometric_matching::load_points(&args[2]).map_err(|e| e.to_string())?;
+    let n = points.len();
+    println!("Points: {}", n);
+
+    let start = Instant::now();
+    let gm = geometric_matching::min_weight_perfect_matching(&points, k)?;
+    let duration = start.elapsed();
+    println!("Candidate edges: {} (k = {}, complete graph {})", gm.candidates, k, n * n.saturating_sub(1) / 2);
+    println!("Blossom runs: {}", gm.rounds);
+    println!("Edges linking exposed points: {}", gm.linked);
+    println!("Pairs added by the dual check: {}", gm.added);
+
+    let mut errors = check_pairs(&points, &gm);
+    if args::has_flag(args, "--full") {
+        let mut all = Vec::with_capacity(n * n.saturating_sub(1) / 2);
+        for u in 0..n {
+            for v in u + 1..n {
+                all.push((u, v, geometric_matching::distance(points[u], points[v])));
+            }
+        }
+        let full = weighted_blossom::min_weight_perfect_matching(n, &all).unwrap_or_default();
+        let w: i64 = full.iter().map(|&(u, v)| geometric_matching::distance(points[u], points[v])).sum();
+        println!("Complete-graph weight: {}", w);
+        if w != gm.weight {
+            errors.push(format!("Complete graph gives weight {}, pruned solve {}!", w, gm.weight));
+        }
+    }
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Matching size: {}", gm.pairs.len());
+    println!("Matching weight: {}", gm.weight);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show-pairs") {
+        for &(u, v) in &gm.pairs {
+            println!("{} {}", u, v);
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_pairs(path, &gm.pairs).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} pairs to {}", gm.pairs.len(), path);
+    }
+    println!("Matching weight: {}", gm.weight);
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
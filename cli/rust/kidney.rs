@@ -0,0 +1,96 @@
+/*
+ * combi kidney - kidney exchange on a directed compatibility graph.
+ *
+ * Arc "u v" means the donor of pair u can give to the patient of pair v.
+ * --altruists FILE lists donors without a patient, who start chains. With
+ * --max-cycle 2 (the default) the exchange is a matching: mutual pairs and
+ * altruist arcs, solved by --algo, or by the vertex-weighted matcher when
+ * there are altruists. --max-cycle 3 adds 3-cycles and two-transplant
+ * chains, solved by the branch-and-bound of kidney_exchange.rs.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::kidney_exchange::{self, Exchange, Pool};
+use super::matchers;
+
+const DEFAULT_NODE_LIMIT: usize = 1_000_000;
+
+fn join(v: &[usize]) -> String {
+    v.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+fn write_exchange(path: &str, ex: &Exchange) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for c in &ex.cycles {
+        writeln!(f, "cycle {}", join(c))?;
+    }
+    for c in &ex.chains {
+        writeln!(f, "chain {}", join(c))?;
+    }
+    f.flush()
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let max_len = match args::value_of(args, "--max-cycle") {
+        Some(s) => s.parse::<usize>().map_err(|_| format!("invalid --max-cycle '{}'", s))?,
+        None => 2,
+    };
+    let node_limit = match args::value_of(args, "--node-limit") {
+        Some(s) => s.parse::<usize>().map_err(|_| format!("invalid --node-limit '{}'", s))?,
+        None => DEFAULT_NODE_LIMIT,
+    };
+    let (n, arcs) = graph_io::load_graph(&args[2], opts)?;
+    let altruists = match args::value_of(args, "--altruists") {
+        Some(path) => kidney_exchange::load_altruists(path, n).map_err(|e| e.to_string())?,
+        None => Vec::new(),
+    };
+    let pool = Pool::new(n, &arcs, &altruists);
+    println!("Pool: {} pairs, {} altruists, {} compatibility arcs", pool.pairs(), altruists.len(), pool.arcs());
+    println!("Cycles and chains: at most {} vertices", max_len);
+
+    let start = Instant::now();
+    let ex = kidney_exchange::solve(algo, &pool, max_len, node_limit)?;
+    let duration = start.elapsed();
+    if max_len == 2 {
+        println!("Backend: {}", if altruists.is_empty() { algo } else { "vertex-weighted matching" });
+    } else {
+        println!("Branch nodes: {}{}", ex.nodes, if ex.optimal { "" } else { " (node limit reached)" });
+    }
+
+    let errors = kidney_exchange::check_exchange(&pool, &ex, max_len);
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    let mut sizes = [0usize; 4];
+    for c in &ex.cycles { sizes[c.len()] += 1; }
+    println!("\n=== Validation Report ===");
+    println!("Transplants: {}{}", ex.transplants, if ex.optimal { "" } else { " (best found, not proven optimal)" });
+    println!("Cycles: {} of 2, {} of 3", sizes[2], sizes[3]);
+    println!("Chains: {}", ex.chains.len());
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show") {
+        for c in &ex.cycles {
+            println!("cycle {}", join(c));
+        }
+        for c in &ex.chains {
+            println!("chain {}", join(c));
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_exchange(path, &ex).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} cycles and {} chains to {}", ex.cycles.len(), ex.chains.len(), path);
+    }
+    println!("Transplants: {}", ex.transplants);
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
@@ -0,0 +1,86 @@
+/*
+ * combi solve --mem-report - the heap a solve needed.
+ *
+ * combi's allocator (ReportingAlloc in combi.rs) counts the bytes in use
+ * and the most in use since the last mark(). write_solution marks before
+ * the solve and reads the peak after it, so the report is the solver's
+ * own arrays (with phase 0 and the --reorder copy when those run) over
+ * what was already held: the loaded edge list, 16 bytes per edge.
+ *
+ * One run gives one total, printed per vertex and per edge of the graph.
+ * The split into a per-vertex and a per-edge part is the model of plan.rs,
+ * fitted on two graphs with different m / n; it is printed alongside.
+ * The counts are of heap bytes asked for, not pages the kernel handed out,
+ * so they sit below the peak RSS the model estimates by the process's code
+ * and allocator slack.
+ *
+ * It also counts the allocations the solve made: its arrays, a handful,
+ * and whatever its loops ask for. A search loop that copies a neighbor
+ * list shows up there as one allocation per vertex scanned.
+ */
+
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::plan;
+
+pub const MEM_REPORT_USAGE: &str = "[--mem-report]";
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/* Called by the allocator; nothing here may allocate. A realloc counts as
+ * one more allocation. */
+pub(crate) fn grew(bytes: usize) {
+    CALLS.fetch_add(1, Ordering::Relaxed);
+    let now = IN_USE.fetch_add(bytes, Ordering::Relaxed) + bytes;
+    PEAK.fetch_max(now, Ordering::Relaxed);
+}
+
+pub(crate) fn shrank(bytes: usize) {
+    IN_USE.fetch_sub(bytes, Ordering::Relaxed);
+}
+
+/* Starts a new peak from what is in use now, and returns that. */
+pub(crate) fn mark() -> usize {
+    let now = IN_USE.load(Ordering::Relaxed);
+    PEAK.store(now, Ordering::Relaxed);
+    now
+}
+
+pub(crate) fn peak() -> usize {
+    PEAK.load(Ordering::Relaxed)
+}
+
+/* Allocations so far, for the count of a solve. */
+pub(crate) fn allocations() -> usize {
+    CALLS.load(Ordering::Relaxed)
+}
+
+fn mib(bytes: usize) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1 << 20) as f64)
+}
+
+fn per(bytes: usize, count: usize) -> String {
+    if count == 0 { "NA".to_string() } else { format!("{:.1}", bytes as f64 / count as f64) }
+}
+
+/* `before` is the heap in use at the mark, `peak` the most in use during
+ * the solve and `calls` the allocations it made; the model printed with
+ * them is the per-vertex and per-edge bytes of plan.rs for `algo`. */
+pub(crate) fn write_report(out: &mut dyn Write, algo: &str, n: usize, m: usize, before: usize, peak: usize,
+                           calls: usize) -> io::Result<()> {
+    let solve = peak.saturating_sub(before);
+    let model = plan::bytes_per(algo);
+    writeln!(out, "=== Memory Report ===")?;
+    writeln!(out, "Heap before the solve: {} ({} bytes per edge)", mib(before), per(before, m))?;
+    writeln!(out, "Solve peak: {} above that", mib(solve))?;
+    writeln!(out, "  per vertex: {} bytes", per(solve, n))?;
+    writeln!(out, "  per edge: {} bytes", per(solve, m))?;
+    writeln!(out, "Allocations in the solve: {}", calls)?;
+    if let Some((v, e)) = model {
+        writeln!(out, "Model ({}): {} bytes per vertex + {} per edge, peak RSS of the whole run", algo, v, e)?;
+    }
+    writeln!(out, "=====================\n")
+}
@@ -0,0 +1,361 @@
+/*
+ * combi solve --dry-run - check the instance and print the solve plan.
+ *
+ * The file is loaded exactly as a real run would load it (same
+ * --on-mismatch, --strict / --auto-grow and --merge-parallel handling,
+ * same warnings), then described: size and degrees, connected components
+ * (and how many of them --phase0 would solve exactly), whether the graph
+ * is bipartite. The plan lists the algorithm with its bound, the
+ * initializer (a --init-matching file is loaded and checked), the reductions, where the solve would run, and an estimate
+ * of the peak memory. For --algo auto it names the solver auto would pick
+ * (with --explain, why). Nothing is solved, so this takes seconds on inputs
+ * whose solve takes hours.
+ *
+ * The memory estimate is fitted to the peak RSS of `combi solve --greedy`
+ * on two random graphs (1M vertices / 1.5M edges and 100k / 2M) per
+ * algorithm, loading included; it is a guide, not a bound. A local
+ * `combi solve` also checks it against the available memory before
+ * loading, from the header's n and m (check_memory): a run that would be
+ * OOM-killed mid-solve is refused up front instead.
+ */
+
+use std::fs;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+use super::matching_io;
+use super::phase0;
+use super::solve_options::SolveOptions;
+
+/* Bytes per vertex and per edge of the whole process, and the time
+ * bound, per algorithm. */
+fn model(algo: &str) -> Option<(u64, u64, &'static str)> {
+    match algo {
+        "edmonds-simple" => Some((111, 40, "O(V^2 E)")),
+        "edmonds-opt" => Some((108, 35, "O(VE)")),
+        /* refitted once their adjacency and per-vertex arrays went to u32
+         * and u8 (the bytes per vertex of each: combi solve --mem-report) */
+        "gabow-simple" => Some((46, 40, "O(VE)")),
+        "gabow-opt" => Some((210, 32, "O(E sqrt V)")),
+        "mv-pure" => Some((189, 33, "O(E sqrt V)")),
+        /* fitted on 2k-30k vertices: the O(V^3) solve keeps it off larger ones */
+        matchers::WEIGHTED_ALGORITHM => Some((298, 84, "O(V^3)")),
+        /* the heaviest of the general solvers it picks from, per vertex and
+         * per edge; Hopcroft-Karp needs less than any of them */
+        matchers::AUTO_ALGORITHM => matchers::AUTO_CANDIDATES.iter()
+            .filter_map(|a| model(a))
+            .reduce(|a, b| (a.0.max(b.0), a.1.max(b.1), "picked")),
+        _ => None,
+    }
+}
+
+/* The model's bytes per vertex and per edge (combi solve --mem-report). */
+pub(crate) fn bytes_per(algo: &str) -> Option<(u64, u64)> {
+    model(algo).map(|(per_vertex, per_edge, _)| (per_vertex, per_edge))
+}
+
+/* Baseline of the combi process itself (code, stdlib, buffers). */
+const BASE_BYTES: u64 = 14 << 20;
+
+/* Estimated peak memory of `combi solve --algo algo` on n vertices and m
+ * edges; None for an unknown algorithm. */
+pub(crate) fn estimate(algo: &str, n: usize, m: usize) -> Option<u64> {
+    model(algo).map(|(per_vertex, per_edge, _)| {
+        BASE_BYTES.saturating_add(per_vertex.saturating_mul(n as u64))
+            .saturating_add(per_edge.saturating_mul(m as u64))
+    })
+}
+
+/* Memory a new allocation can still get: MemAvailable from /proc/meminfo,
+ * lowered to the headroom under the cgroup's memory.max when there is a
+ * limit (containers and batch jobs are killed at that, not at the host's
+ * RAM). None where neither can be read, e.g. off Linux. */
+pub(crate) fn available_memory() -> Option<u64> {
+    let meminfo = fs::read_to_string("/proc/meminfo").ok().and_then(|s| {
+        s.lines()
+            .find(|l| l.starts_with("MemAvailable:"))
+            .and_then(|l| l.split_whitespace().nth(1))
+            .and_then(|kib| kib.parse::<u64>().ok())
+            .map(|kib| kib << 10)
+    });
+    let read = |name: &str| -> Option<u64> {
+        fs::read_to_string(format!("/sys/fs/cgroup/{}", name)).ok()?.trim().parse().ok()
+    };
+    let cgroup = match (read("memory.max"), read("memory.current")) {
+        (Some(max), Some(current)) => Some(max.saturating_sub(current)),
+        _ => None, /* "max" (no limit) does not parse */
+    };
+    match (meminfo, cgroup) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+pub const MEMORY_USAGE: &str = "[--no-memory-check]";
+
+/* Refuse to start `algo` on n vertices and m edges when its estimate
+ * exceeds the available memory. The message names the algorithms whose
+ * estimate would fit, if any. Passes when the memory cannot be read or the
+ * algorithm has no model (the solve reports that itself). */
+pub(crate) fn check_memory(algo: &str, n: usize, m: usize) -> Result<(), String> {
+    let (need, avail) = match (estimate(algo, n, m), available_memory()) {
+        (Some(need), Some(avail)) => (need, avail),
+        _ => return Ok(()),
+    };
+    if need <= avail {
+        return Ok(());
+    }
+    /* the cardinality solvers are no alternative on a weighted graph */
+    let others: &[&str] = if algo == matchers::WEIGHTED_ALGORITHM { &[] } else { &matchers::GENERAL_ALGORITHMS };
+    let mut fits: Vec<(u64, &str)> = others.iter()
+        .filter_map(|&a| estimate(a, n, m).map(|e| (e, a)))
+        .filter(|&(e, _)| e <= avail)
+        .collect();
+    fits.sort();
+    let advice = if fits.is_empty() {
+        "no algorithm fits: run on a machine with more memory".to_string()
+    } else {
+        let names: Vec<String> = fits.iter().map(|&(e, a)| format!("{} ({})", a, human(e))).collect();
+        format!("these would fit: --algo {}", names.join(", "))
+    };
+    Err(format!("{} needs about {} on {} vertices / {} edges, but only {} is available; {} \
+                 (or --no-memory-check to start anyway)", algo, human(need), n, m, human(avail), advice))
+}
+
+fn human(bytes: u64) -> String {
+    if bytes >= 10 << 30 {
+        format!("{} GiB", bytes >> 30)
+    } else if bytes >= 10 << 20 {
+        format!("{} MiB", bytes >> 20)
+    } else {
+        format!("{} KiB", (bytes + 1023) >> 10)
+    }
+}
+
+/* What a dry run reports about the graph. */
+pub(crate) struct Properties {
+    pub(crate) isolated: usize,
+    pub(crate) self_loops: usize,
+    pub(crate) parallel: usize,
+    pub(crate) max_degree: usize,
+    pub(crate) components: usize,          /* with at least one edge */
+    pub(crate) largest: (usize, usize),    /* (vertices, edges) */
+    pub(crate) sparse: (usize, usize),     /* tree or unicyclic: (components, vertices) */
+    pub(crate) sides: Option<(usize, usize)>, /* 2-coloring of the non-isolated vertices */
+    pub(crate) upper: usize,               /* half of each component, rounded down */
+}
+
+pub(crate) fn properties(n: usize, edges: &[(usize, usize)]) -> Properties {
+    let mut pairs: Vec<(usize, usize)> = Vec::with_capacity(edges.len());
+    let mut self_loops = 0;
+    for &(u, v) in edges {
+        if u >= n || v >= n { continue; }
+        if u == v { self_loops += 1; } else { pairs.push((u.min(v), u.max(v))); }
+    }
+    pairs.sort_unstable();
+    let before = pairs.len();
+    pairs.dedup();
+    let parallel = before - pairs.len();
+
+    let mut deg = vec![0usize; n];
+    for &(u, v) in &pairs {
+        deg[u] += 1;
+        deg[v] += 1;
+    }
+    let mut start = vec![0usize; n + 1];
+    for v in 0..n {
+        start[v + 1] = start[v] + deg[v];
+    }
+    let mut adj = vec![0usize; start[n]];
+    let mut fill = start.clone();
+    for &(u, v) in &pairs {
+        adj[fill[u]] = v;
+        fill[u] += 1;
+        adj[fill[v]] = u;
+        fill[v] += 1;
+    }
+
+    /* BFS per component, 2-coloring as it goes */
+    const NONE: u8 = 2;
+    let mut color = vec![NONE; n];
+    let mut bipartite = true;
+    let mut props = Properties {
+        isolated: deg.iter().filter(|&&d| d == 0).count(),
+        self_loops,
+        parallel,
+        max_degree: deg.iter().cloned().max().unwrap_or(0),
+        components: 0,
+        largest: (0, 0),
+        sparse: (0, 0),
+        sides: None,
+        upper: 0,
+    };
+    let mut queue = Vec::new();
+    for s in 0..n {
+        if deg[s] == 0 || color[s] != NONE { continue; }
+        color[s] = 0;
+        queue.clear();
+        queue.push(s);
+        let mut head = 0;
+        let mut degree_sum = 0;
+        while head < queue.len() {
+            let u = queue[head];
+            head += 1;
+            degree_sum += deg[u];
+            for &w in &adj[start[u]..start[u + 1]] {
+                if color[w] == NONE {
+                    color[w] = 1 - color[u];
+                    queue.push(w);
+                } else if color[w] == color[u] {
+                    bipartite = false;
+                }
+            }
+        }
+        let (verts, comp_edges) = (queue.len(), degree_sum / 2);
+        props.components += 1;
+        props.upper += verts / 2;
+        if (verts, comp_edges) > props.largest {
+            props.largest = (verts, comp_edges);
+        }
+        if comp_edges <= verts {
+            props.sparse.0 += 1;
+            props.sparse.1 += verts;
+        }
+    }
+    if bipartite {
+        let zero = color.iter().filter(|&&c| c == 0).count();
+        props.sides = Some((zero, n - props.isolated - zero));
+    }
+    props
+}
+
+/* Problems found go on stderr and fail the run, as a real run would fail. */
+pub(crate) fn run(path: &str, opts: &LoadOptions, solve: &SolveOptions, socket: Option<&str>) -> Result<(), SuiteError> {
+    let start = Instant::now();
+    let (n, edges, names) = if solve.algo == matchers::WEIGHTED_ALGORITHM {
+        let (n, edges, names) = graph_io::load_weighted_graph_named(path, opts)?;
+        (n, edges.into_iter().map(|(u, v, _)| (u, v)).collect(), names)
+    } else {
+        graph_io::load_graph_named(path, opts)?
+    };
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    let init = solve.init.map(|p| (p, matching_io::load_init(p, (n, n), &edges, false, &names).map(|pairs| pairs.len())));
+    let stdout = io::stdout();
+    let problems = write_plan(&mut stdout.lock(), n, &edges, solve, socket, init).map_err(|e| e.to_string())?;
+    for p in &problems {
+        eprintln!("ERROR: {}", p);
+    }
+    println!("\nDry run: nothing solved");
+    println!("Time: {} ms", start.elapsed().as_millis());
+    if problems.is_empty() { Ok(()) } else { Err(format!("{} problem(s) in the plan", problems.len()).into()) }
+}
+
+fn write_plan(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize)], solve: &SolveOptions, socket: Option<&str>,
+    init: Option<(&str, Result<usize, String>)>,
+) -> io::Result<Vec<String>> {
+    let algo = solve.algo;
+    let mut problems = Vec::new();
+    let model = model(algo);
+    if model.is_none() {
+        problems.push(format!("unknown algorithm '{}' (expected one of: {}, {})",
+                              algo, matchers::GENERAL_ALGORITHMS.join(", "), matchers::AUTO_ALGORITHM));
+    } else if let Err(e) = matchers::check_vertex_count(algo, n) {
+        problems.push(e.to_string());
+    }
+    let p = properties(n, edges);
+
+    writeln!(out, "\n=== Instance ===")?;
+    writeln!(out, "Vertices: {} ({} isolated)", n, p.isolated)?;
+    let distinct = edges.len() - p.self_loops - p.parallel;
+    writeln!(out, "Edges: {} ({} distinct, {} self-loops, {} parallel copies)",
+             edges.len(), distinct, p.self_loops, p.parallel)?;
+    let active = n - p.isolated;
+    if active > 0 {
+        writeln!(out, "Degree: max {}, mean {:.2}", p.max_degree, 2.0 * distinct as f64 / active as f64)?;
+    }
+    writeln!(out, "Components: {} with edges, largest {} vertices / {} edges",
+             p.components, p.largest.0, p.largest.1)?;
+    writeln!(out, "Tree or unicyclic components: {} ({} vertices, solved exactly by --phase0)",
+             p.sparse.0, p.sparse.1)?;
+    match p.sides {
+        Some((a, b)) => writeln!(out, "Bipartite: yes ({} + {}; hopcroft_karp on a \"left right m\" file \
+                                        also solves it)", a, b)?,
+        None => writeln!(out, "Bipartite: no (odd cycle)")?,
+    }
+    writeln!(out, "Matching size at most: {} (half of each component)", p.upper)?;
+
+    writeln!(out, "\n=== Plan ===")?;
+    match model {
+        Some(_) if algo == matchers::AUTO_ALGORITHM => {
+            let (_, route) = matchers::auto_route(n, edges);
+            let bound = self::model(route.algo).map_or("O(E sqrt V)", |(_, _, b)| b);
+            writeln!(out, "Algorithm: {} ({}; {})", algo, route.summary, bound)?;
+            if solve.explain {
+                for r in &route.reasons {
+                    writeln!(out, "  {}", r)?;
+                }
+            }
+        }
+        Some((_, _, bound)) => writeln!(out, "Algorithm: {} ({})", algo, bound)?,
+        None => writeln!(out, "Algorithm: {} (unknown)", algo)?,
+    }
+    match init {
+        Some((path, loaded)) => {
+            match loaded {
+                Ok(k) => writeln!(out, "Initial matching: {} pairs from {} (--init-matching)", k, path)?,
+                Err(e) => {
+                    writeln!(out, "Initial matching: {} (--init-matching, unusable)", path)?;
+                    problems.push(e);
+                }
+            }
+            if solve.greedy_mode > 0 {
+                problems.push("--init-matching replaces the greedy start; drop --greedy, --greedy-md, --greedy-ks, --greedy-random or --init-aug3".to_string());
+            }
+            if solve.phase0 != phase0::Mode::Off {
+                problems.push("--init-matching does not combine with --phase0, --crown or --fold".to_string());
+            }
+        }
+        None => {
+            let names = ["none", "greedy (--greedy)", "min-degree greedy (--greedy-md)", "Karp-Sipser (--greedy-ks)",
+                         "random greedy (--greedy-random)"];
+            write!(out, "Initial matching: {}", names[solve.greedy_mode.clamp(0, 4) as usize])?;
+            if solve.aug3 > 0 {
+                write!(out, ", then up to {} length-3 augmenting passes (--init-aug3)", solve.aug3)?;
+            }
+            writeln!(out)?;
+        }
+    }
+    writeln!(out, "Phase 0: {}", match solve.phase0 {
+        phase0::Mode::Off => "off",
+        phase0::Mode::Components => "tree and unicyclic components (--phase0)",
+        phase0::Mode::Crown => "2-core peeling and crowns, then components (--crown)",
+        phase0::Mode::Fold => "leaf rule and degree-2 folds (--fold)",
+    })?;
+    writeln!(out, "Reorder: {}", solve.reorder.name())?;
+    match socket {
+        Some(s) => writeln!(out, "Runs on: daemon at {} (the daemon loads the file again)", s)?,
+        None => writeln!(out, "Runs on: this process")?,
+    }
+    if let Some(path) = solve.output {
+        writeln!(out, "Matching file: {}", path)?;
+    }
+    if let Some(need) = estimate(algo, n, edges.len()) {
+        writeln!(out, "Estimated peak memory: {}", human(need))?;
+        if socket.is_none() {
+            match available_memory() {
+                Some(avail) => {
+                    writeln!(out, "Available memory: {}", human(avail))?;
+                    if let Err(e) = check_memory(algo, n, edges.len()) {
+                        problems.push(e);
+                    }
+                }
+                None => writeln!(out, "Available memory: unknown (not checked)")?,
+            }
+        }
+    }
+    Ok(problems)
+}
@@ -0,0 +1,91 @@
+/*
+ * combi plant - weighted test instance with a known optimal matching.
+ *
+ * Writes a weighted graph ("n m", then "u v w"; with --bipartite the
+ * "left right m" format) on <n> vertices in which a random perfect
+ * matching is planted as the maximum-weight matching, unique by at least
+ * the reported margin when --gap is positive (see planted.rs). --degree
+ * sets the noise edges per vertex, --gap and --spread their slack below
+ * the vertex potentials, and --max-potential the potential range; the
+ * same --seed gives the same instance. The graph goes to --output, or to
+ * stdout with the summary on stderr; --planted writes the planted pairs
+ * as a matching file (matching_io.rs), for `combi check`.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+use super::args;
+use super::graph_io;
+use super::matching_io;
+use super::planted::{self, PlantOptions};
+use super::rng::Rng;
+
+fn parse<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> Result<T, String> {
+    match args::value_of(args, name) {
+        Some(s) => s.parse().map_err(|_| format!("invalid {} '{}'", name, s)),
+        None => Ok(default),
+    }
+}
+
+fn write_graph(out: &mut dyn Write, p: &planted::Planted, bipartite: bool) -> io::Result<()> {
+    if bipartite {
+        writeln!(out, "{} {} {}", p.left, p.right, p.edges.len())?;
+    } else {
+        writeln!(out, "{} {}", p.left, p.edges.len())?;
+    }
+    for &(u, v, w) in &p.edges {
+        writeln!(out, "{} {} {}", u, v, w)?;
+    }
+    out.flush()
+}
+
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let defaults = PlantOptions::default();
+    let opts = PlantOptions {
+        n: args[2].parse().map_err(|_| format!("invalid vertex count '{}'", args[2]))?,
+        bipartite: args::has_flag(args, "--bipartite"),
+        degree: parse(args, "--degree", defaults.degree)?,
+        max_potential: parse(args, "--max-potential", defaults.max_potential)?,
+        gap: parse(args, "--gap", defaults.gap)?,
+        spread: parse(args, "--spread", defaults.spread)?,
+    };
+    let seed: u64 = parse(args, "--seed", 1)?;
+    let p = planted::plant(&opts, &mut Rng::new(seed))?;
+
+    let output = args::value_of(args, "--output");
+    match output {
+        Some(path) => {
+            let mut f = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+            write_graph(&mut f, &p, opts.bipartite).map_err(|e| format!("{}: {}", path, e))?;
+        }
+        None => {
+            let stdout = io::stdout();
+            write_graph(&mut stdout.lock(), &p, opts.bipartite).map_err(|e| e.to_string())?;
+        }
+    }
+    if let Some(path) = args::value_of(args, "--planted") {
+        matching_io::save_matching(path, &p.matching, opts.bipartite, &graph_io::InputNames::default())?;
+    }
+
+    let mut info: Box<dyn Write> = if output.is_some() { Box::new(io::stdout()) } else { Box::new(io::stderr()) };
+    let shape = if opts.bipartite {
+        format!("{} + {} vertices", p.left, p.right)
+    } else {
+        format!("{} vertices", p.left)
+    };
+    let lines = [
+        format!("Planted: {}, {} edges ({} noise), seed {}", shape, p.edges.len(),
+                p.edges.len() - p.matching.len(), seed),
+        format!("Planted matching: {} pairs, weight {}", p.matching.len(), p.weight),
+        if opts.gap > 0 {
+            format!("Margin: {} (every other matching is at least this much lighter)", p.margin)
+        } else {
+            "Margin: 0 (--gap 0: an optimum, maybe not the only one)".to_string()
+        },
+    ];
+    for line in &lines {
+        writeln!(info, "{}", line).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,238 @@
+/*
+ * combi postman - Chinese Postman (route inspection) tour.
+ *
+ * The shortest closed walk that traverses every edge at least once. A
+ * connected graph has an Euler tour iff every degree is even, so the
+ * odd-degree vertices T are paired up as cheaply as possible and the
+ * edges of a minimum T-join are traversed twice (Edmonds-Johnson 1973;
+ * see t_join.rs). The tour is then read off the augmented multigraph with
+ * Hierholzer's algorithm.
+ *
+ * Input: weighted edge list ("n m", then "u v w" with w >= 0). Parallel
+ * edges and self-loops are allowed and are all traversed.
+ *
+ * With --t-set FILE no tour is built: the minimum T-join for the vertex
+ * set in FILE is reported instead (--output writes its edges as a
+ * weighted edge list). The graph need not be connected, but every
+ * component must hold an even number of T vertices.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::t_join;
+
+const NIL: usize = usize::MAX;
+
+struct Tour {
+    vertices: Vec<usize>, /* closed walk, first == last */
+    edges: Vec<usize>,    /* traversed edge per step, as input edge indices */
+}
+
+/* Vertices with at least one edge must form one component. */
+fn check_connected(n: usize, edges: &[(usize, usize, i64)]) -> Result<(), String> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v, _) in edges {
+        adj[u].push(v);
+        adj[v].push(u);
+    }
+    let start = match (0..n).find(|&v| !adj[v].is_empty()) {
+        Some(s) => s,
+        None => return Ok(()),
+    };
+    let mut seen = vec![false; n];
+    seen[start] = true;
+    let mut stack = vec![start];
+    while let Some(v) = stack.pop() {
+        for &w in &adj[v] {
+            if !seen[w] {
+                seen[w] = true;
+                stack.push(w);
+            }
+        }
+    }
+    match (0..n).find(|&v| !seen[v] && !adj[v].is_empty()) {
+        Some(v) => Err(format!("graph is disconnected (vertices {} and {} share no component), \
+                                no closed tour exists", start, v)),
+        None => Ok(()),
+    }
+}
+
+/* Hierholzer on the multigraph `copies` (input edge index per copy). */
+fn euler_tour(n: usize, edges: &[(usize, usize, i64)], copies: &[usize], start: usize) -> Tour {
+    let mut adj = vec![Vec::new(); n];
+    for (c, &k) in copies.iter().enumerate() {
+        let (u, v, _) = edges[k];
+        adj[u].push(c);
+        if u != v { adj[v].push(c); }
+    }
+    let mut next = vec![0usize; n];
+    let mut used = vec![false; copies.len()];
+    let mut vertices = Vec::with_capacity(copies.len() + 1);
+    let mut steps = Vec::with_capacity(copies.len());
+    let mut stack = vec![(start, NIL)];
+    while let Some(&(v, _)) = stack.last() {
+        while next[v] < adj[v].len() && used[adj[v][next[v]]] {
+            next[v] += 1;
+        }
+        if next[v] == adj[v].len() {
+            let (v, c) = stack.pop().unwrap();
+            vertices.push(v);
+            if c != NIL { steps.push(copies[c]); }
+        } else {
+            let c = adj[v][next[v]];
+            used[c] = true;
+            let (a, b, _) = edges[copies[c]];
+            stack.push((if a == v { b } else { a }, c));
+        }
+    }
+    vertices.reverse();
+    steps.reverse();
+    Tour { vertices, edges: steps }
+}
+
+fn validate_tour(
+    edges: &[(usize, usize, i64)], tour: &Tour, start: usize, expected: usize,
+) -> Vec<String> {
+    let mut errors = Vec::new();
+    let mut count = vec![0usize; edges.len()];
+    for (i, &k) in tour.edges.iter().enumerate() {
+        let (u, v, _) = edges[k];
+        let (a, b) = (tour.vertices[i], tour.vertices[i + 1]);
+        if !((a, b) == (u, v) || (a, b) == (v, u)) {
+            errors.push(format!("Step {} ({} -> {}) does not follow edge ({}, {})!", i, a, b, u, v));
+        }
+        count[k] += 1;
+    }
+    if tour.vertices.first() != Some(&start) || tour.vertices.last() != Some(&start) {
+        errors.push(format!("Tour is not closed at vertex {}!", start));
+    }
+    if let Some(k) = (0..edges.len()).find(|&k| count[k] == 0) {
+        errors.push(format!("Edge ({}, {}) is never traversed!", edges[k].0, edges[k].1));
+    }
+    if tour.edges.len() != expected {
+        errors.push(format!("Tour has {} steps, expected {}!", tour.edges.len(), expected));
+    }
+    errors
+}
+
+fn write_tour(path: &str, tour: &Tour) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &v in &tour.vertices {
+        writeln!(f, "{}", v)?;
+    }
+    f.flush()
+}
+
+fn write_join(path: &str, n: usize, edges: &[(usize, usize, i64)], join: &[usize]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    writeln!(f, "{} {}", n, join.len())?;
+    for &k in join {
+        let (u, v, w) = edges[k];
+        writeln!(f, "{} {} {}", u, v, w)?;
+    }
+    f.flush()
+}
+
+/* --t-set: a minimum T-join for a given T, no tour. */
+fn run_t_join(args: &[String], path: &str, n: usize, edges: &[(usize, usize, i64)]) -> Result<(), SuiteError> {
+    let t = t_join::load_vertex_set(path, n).map_err(|e| e.to_string())?;
+    println!("T vertices: {}", t.len());
+
+    let timer = Instant::now();
+    let join = t_join::min_t_join(n, edges, &t)?;
+    let duration = timer.elapsed();
+
+    let errors = t_join::check_t_join(n, edges, &t, &join);
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Join edges: {}", join.edges.len());
+    println!("Join weight: {}", join.weight);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if let Some(out) = args::value_of(args, "--output") {
+        write_join(out, n, edges, &join.edges).map_err(|e| format!("{}: {}", out, e))?;
+        println!("Wrote {} join edges to {}", join.edges.len(), out);
+    }
+    println!("T pairs matched: {}", join.pairs.len());
+    println!("Join weight: {}", join.weight);
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let (n, edges) = graph_io::load_weighted_graph(&args[2], opts)?;
+    if let Some(&(u, v, w)) = edges.iter().find(|e| e.2 < 0) {
+        return Err(format!("edge ({}, {}) has negative weight {}", u, v, w).into());
+    }
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    if let Some(path) = args::value_of(args, "--t-set") {
+        return run_t_join(args, path, n, &edges);
+    }
+    if edges.is_empty() {
+        println!("Nothing to traverse");
+        return Ok(());
+    }
+    check_connected(n, &edges)?;
+
+    let mut deg = vec![0usize; n];
+    for &(u, v, _) in &edges {
+        deg[u] += 1;
+        deg[v] += 1;
+    }
+    let start = match args::value_of(args, "--start") {
+        Some(s) => match s.parse::<usize>() {
+            Ok(v) if v < n && deg[v] > 0 => v,
+            _ => return Err(format!("--start must be a vertex with an edge, got '{}'", s).into()),
+        },
+        None => (0..n).find(|&v| deg[v] > 0).unwrap(),
+    };
+    let odd: Vec<usize> = (0..n).filter(|&v| deg[v] % 2 == 1).collect();
+    println!("Odd-degree vertices: {}", odd.len());
+
+    let timer = Instant::now();
+    let join = t_join::min_t_join(n, &edges, &odd)?;
+    let mut copies: Vec<usize> = (0..edges.len()).collect();
+    copies.extend_from_slice(&join.edges);
+    let tour = euler_tour(n, &edges, &copies, start);
+    let duration = timer.elapsed();
+
+    let base: i64 = edges.iter().map(|e| e.2).sum();
+    let walked: i64 = tour.edges.iter().map(|&k| edges[k].2).sum();
+    let mut errors = validate_tour(&edges, &tour, start, copies.len());
+    if walked != base + join.weight {
+        errors.push(format!("Walked weight {} differs from edge weight + T-join weight {}!",
+                            walked, base + join.weight));
+    }
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    println!("\n=== Validation Report ===");
+    println!("Tour steps: {}", tour.edges.len());
+    println!("Repeated edges: {}", join.edges.len());
+    println!("Walked weight: {}", walked);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show-tour") {
+        let s: Vec<String> = tour.vertices.iter().map(|v| v.to_string()).collect();
+        println!("{}\n", s.join(" -> "));
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_tour(path, &tour).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} tour vertices to {}", tour.vertices.len(), path);
+    }
+    println!("Odd pairs matched: {}", join.pairs.len());
+    println!("Edge weight: {}", base);
+    println!("Added weight: {}", join.weight);
+    println!("Tour weight: {}", base + join.weight);
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
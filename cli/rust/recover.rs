@@ -0,0 +1,89 @@
+/*
+ * combi recover - planted-matching recovery experiments.
+ *
+ * Plants `--trials` instances of <n> vertices (see plant.rs) for every
+ * noise degree in --degrees and gap in --gaps, runs each algorithm in
+ * --algos on them, and reports per cell how often it returned exactly the
+ * planted matching, the share of planted pairs it found, its weight
+ * against the planted weight, and its time (see recovery.rs). Rows print
+ * as a table while the cells run; --csv FILE also writes them as CSV for
+ * plotting. Trial t is `combi plant <n> --degree D --gap G --seed S+t`,
+ * so any instance can be regenerated.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::planted::PlantOptions;
+use super::recovery::{self, RecoveryOptions};
+
+fn parse<T: std::str::FromStr>(args: &[String], name: &str, default: T) -> Result<T, String> {
+    match args::value_of(args, name) {
+        Some(s) => s.parse().map_err(|_| format!("invalid {} '{}'", name, s)),
+        None => Ok(default),
+    }
+}
+
+fn parse_list<T: std::str::FromStr>(args: &[String], name: &str, default: &str) -> Result<Vec<T>, String> {
+    let list = args::value_of(args, name).unwrap_or(default);
+    list.split(',').map(|s| s.trim().parse().map_err(|_| format!("invalid {} entry '{}'", name, s))).collect()
+}
+
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let defaults = PlantOptions::default();
+    let bipartite = args::has_flag(args, "--bipartite");
+    let opts = RecoveryOptions {
+        plant: PlantOptions {
+            n: args[2].parse().map_err(|_| format!("invalid vertex count '{}'", args[2]))?,
+            bipartite,
+            max_potential: parse(args, "--max-potential", defaults.max_potential)?,
+            spread: parse(args, "--spread", defaults.spread)?,
+            ..defaults
+        },
+        degrees: parse_list(args, "--degrees", "1,2,4,8")?,
+        gaps: parse_list(args, "--gaps", "0,1,10,100")?,
+        trials: parse(args, "--trials", 5)?,
+        seed: parse(args, "--seed", 1)?,
+        algos: parse_list(args, "--algos",
+                          if bipartite { "weighted,hk-tie-break,greedy,mv-pure" } else { "weighted,greedy,mv-pure" })?,
+    };
+
+    recovery::validate(&opts)?;
+
+    let mut csv = match args::value_of(args, "--csv") {
+        Some(path) => {
+            let mut f = BufWriter::new(File::create(path).map_err(|e| format!("{}: {}", path, e))?);
+            writeln!(f, "{}", recovery::CSV_HEADER).map_err(|e| format!("{}: {}", path, e))?;
+            Some((path, f))
+        }
+        None => None,
+    };
+
+    println!("Recovery: {} vertices ({}), {} trials per cell, seed {}, max potential {}, spread {}",
+             opts.plant.n, if bipartite { "bipartite" } else { "general" }, opts.trials, opts.seed,
+             opts.plant.max_potential, opts.plant.spread);
+    println!("{}", recovery::TABLE_HEADER);
+    let start = Instant::now();
+    let mut written: io::Result<()> = Ok(());
+    recovery::run(&opts, &mut |rows| {
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        for r in rows {
+            if written.is_ok() {
+                written = recovery::write_table_row(&mut out, r);
+            }
+            if let (true, Some((_, f))) = (written.is_ok(), csv.as_mut()) {
+                written = recovery::write_csv_row(f, r);
+            }
+        }
+    })?;
+    written.map_err(|e| e.to_string())?;
+    if let Some((path, mut f)) = csv {
+        f.flush().map_err(|e| format!("{}: {}", path, e))?;
+        println!("CSV: {}", path);
+    }
+    println!("Time: {} ms", start.elapsed().as_millis());
+    Ok(())
+}
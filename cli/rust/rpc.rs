@@ -0,0 +1,337 @@
+/*
+ * JSON-RPC 2.0 on the combi serve socket, for tooling that is not written
+ * in Rust (Python notebooks, web UIs) and wants to drive the cache
+ * interactively without bindings.
+ *
+ * Framing: one JSON message (request, notification or batch) per line, one
+ * reply line per message that expects one. The connection stays open until
+ * the client closes it. The server is single-threaded, so other clients
+ * wait while a session is open.
+ *
+ * Methods (named params; `graph` is the handle returned by load):
+ *   load   {path} | {n, edges}                     -> graph summary + cached
+ *   solve  {graph, algo?, greedy?, seed?, reorder?, phase0?, crown?, matching?}
+ *                                                  -> size, greedy_size, valid, time_ms, phases[, matching]
+ *   update {graph, add?, remove?, add_vertices?}   -> graph summary + added, removed
+ *   query  {graph, vertex?}                        -> graph summary, or {vertex, mate, degree}
+ *   stats  {}                                      -> cached graphs, hits, misses, parse_ms_saved
+ *
+ * Edge lists are arrays of [u, v] pairs. Updating a graph detaches it from
+ * its file (a later load of the same file parses it again) and drops its
+ * last solution.
+ */
+
+use std::io::{self, BufRead, Write};
+
+use super::graph_io::LoadOptions;
+use super::json::{self, Json};
+use super::matchers;
+use super::ordering::Reorder;
+use super::serve::{Cache, CachedGraph};
+use super::verify;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const APP_ERROR: i64 = -32000;
+const NO_SUCH_GRAPH: i64 = -32001;
+const NOT_SOLVED: i64 = -32002;
+
+type RpcResult = Result<Json, (i64, String)>;
+
+fn invalid<T>(msg: &str) -> Result<T, (i64, String)> {
+    Err((INVALID_PARAMS, msg.to_string()))
+}
+
+fn error_response(id: Json, code: i64, message: &str) -> Json {
+    Json::obj()
+        .with("jsonrpc", "2.0")
+        .with("error", Json::obj().with("code", code).with("message", message))
+        .with("id", id)
+}
+
+fn pairs_json(pairs: &[(usize, usize)]) -> Json {
+    Json::Arr(pairs.iter().map(|&(u, v)| Json::Arr(vec![u.into(), v.into()])).collect())
+}
+
+fn edge_list(value: &Json, n: usize) -> Result<Vec<(usize, usize)>, (i64, String)> {
+    let items = match value.as_array() {
+        Some(items) => items,
+        None => return invalid("edge list must be an array of [u, v] pairs"),
+    };
+    let mut edges = Vec::with_capacity(items.len());
+    for item in items {
+        let pair = item.as_array().map(|p| (p.len(), p));
+        let (u, v) = match pair {
+            Some((2, p)) => match (p[0].as_usize(), p[1].as_usize()) {
+                (Some(u), Some(v)) => (u, v),
+                _ => return invalid(&format!("bad edge {}", item)),
+            },
+            _ => return invalid(&format!("bad edge {}", item)),
+        };
+        if u >= n || v >= n {
+            return invalid(&format!("edge [{}, {}] out of range for {} vertices", u, v, n));
+        }
+        edges.push((u, v));
+    }
+    Ok(edges)
+}
+
+fn summary(e: &CachedGraph) -> Json {
+    Json::obj()
+        .with("graph", e.id)
+        .with("n", e.n)
+        .with("m", e.edges.len())
+        .with("path", if e.in_memory { Json::Null } else { e.path.as_str().into() })
+        .with("size", e.matching.as_ref().map(|m| m.len()))
+}
+
+fn graph_index(cache: &Cache, params: &Json) -> Result<usize, (i64, String)> {
+    let id = match params.get("graph").and_then(|g| g.as_usize()) {
+        Some(id) => id,
+        None => return invalid("missing graph handle"),
+    };
+    cache.position(id).ok_or((NO_SUCH_GRAPH, format!("no graph {} (evicted or never loaded)", id)))
+}
+
+fn load(cache: &mut Cache, opts: &LoadOptions, params: &Json) -> RpcResult {
+    if let Some(path) = params.get("path") {
+        let path = match path.as_str() {
+            Some(p) => p,
+            None => return invalid("path must be a string"),
+        };
+        let (i, hit) = cache.get_or_load(path, opts).map_err(|e| (APP_ERROR, e))?;
+        let e = &cache.entries[i];
+        return Ok(summary(e).with("cached", hit).with("parse_ms", e.parse_ms as u64));
+    }
+    let n = match params.get("n").and_then(|n| n.as_usize()) {
+        Some(n) => n,
+        None => return invalid("load needs a path, or n and edges"),
+    };
+    let edges = match params.get("edges") {
+        Some(list) => edge_list(list, n)?,
+        None => Vec::new(),
+    };
+    let i = cache.insert(0, "<inline>", n, edges, 0, true);
+    Ok(summary(&cache.entries[i]).with("cached", false).with("parse_ms", 0usize))
+}
+
+fn solve(cache: &mut Cache, params: &Json) -> RpcResult {
+    let i = graph_index(cache, params)?;
+    let algo = match params.get("algo") {
+        None => matchers::DEFAULT_ALGORITHM,
+        Some(a) => match a.as_str() {
+            Some(a) if matchers::GENERAL_ALGORITHMS.contains(&a) || a == matchers::AUTO_ALGORITHM => a,
+            _ => return invalid(&format!("algo must be one of {}, {}",
+                                         matchers::GENERAL_ALGORITHMS.join(", "), matchers::AUTO_ALGORITHM)),
+        },
+    };
+    let greedy_mode = match params.get("greedy").map(|g| g.as_str()) {
+        None | Some(Some("none")) => 0,
+        Some(Some("greedy")) => 1,
+        Some(Some("greedy-md")) => 2,
+        Some(Some("greedy-ks")) => 3,
+        Some(Some("greedy-random")) => 4,
+        _ => return invalid("greedy must be \"none\", \"greedy\", \"greedy-md\", \"greedy-ks\" or \"greedy-random\""),
+    };
+    let seed = match params.get("seed") {
+        None => 1,
+        Some(s) => match s.as_usize() {
+            Some(s) => s as u64,
+            None => return invalid("seed must be a non-negative integer"),
+        },
+    };
+    let reorder = match params.get("reorder") {
+        None => Reorder::Natural,
+        Some(r) => match r.as_str().map(Reorder::parse) {
+            Some(Ok(r)) => r,
+            _ => return invalid("reorder must be \"natural\", \"peripheral\", \"bfs\", \"rcm\" or \"degree\""),
+        },
+    };
+    let mut flags = [false; 3];
+    for (k, key) in ["phase0", "crown", "fold"].iter().enumerate() {
+        flags[k] = match params.get(key) {
+            None => false,
+            Some(p) => match p.as_bool() {
+                Some(b) => b,
+                None => return invalid(&format!("{} must be a boolean", key)),
+            },
+        };
+    }
+    /* crown includes the component pass */
+    let phase0_mode = if flags[2] {
+        super::phase0::Mode::Fold
+    } else if flags[1] {
+        super::phase0::Mode::Crown
+    } else if flags[0] {
+        super::phase0::Mode::Components
+    } else {
+        super::phase0::Mode::Off
+    };
+    let want_matching = params.get("matching").and_then(|m| m.as_bool()).unwrap_or(false);
+
+    let e = &mut cache.entries[i];
+    let solve = super::SolveOptions { algo, greedy_mode, seed, reorder, phase0: phase0_mode, ..Default::default() };
+    let (sol, ms, _, _) = super::solve_reordered(e.n, &e.edges, &solve, None).map_err(|m| (APP_ERROR, m))?;
+    let phases: Vec<Json> = sol.phases.iter()
+        .map(|&(len, aug)| Json::Arr(vec![len.into(), aug.into()]))
+        .collect();
+    let valid = verify::validate_general(e.n, &e.edges, &sol.matching).is_valid();
+
+    let mut result = Json::obj()
+        .with("graph", e.id)
+        .with("algo", algo)
+        .with("route", sol.route.as_ref().map(|r| r.summary.clone()))
+        .with("size", sol.matching.len())
+        .with("greedy_size", if greedy_mode > 0 { Some(sol.greedy_size) } else { None })
+        .with("valid", valid)
+        .with("time_ms", ms as u64)
+        .with("phases", phases);
+    if want_matching {
+        result = result.with("matching", pairs_json(&sol.matching));
+    }
+    e.matching = Some(sol.matching);
+    Ok(result)
+}
+
+fn normalized(u: usize, v: usize) -> (usize, usize) {
+    if u < v { (u, v) } else { (v, u) }
+}
+
+fn update(cache: &mut Cache, params: &Json) -> RpcResult {
+    let i = graph_index(cache, params)?;
+    let grow = match params.get("add_vertices") {
+        None => 0,
+        Some(k) => match k.as_usize() {
+            Some(k) => k,
+            None => return invalid("add_vertices must be a non-negative integer"),
+        },
+    };
+    let n = cache.entries[i].n + grow;
+    let add = match params.get("add") {
+        Some(list) => edge_list(list, n)?,
+        None => Vec::new(),
+    };
+    let mut gone: Vec<(usize, usize)> = match params.get("remove") {
+        Some(list) => edge_list(list, n)?.into_iter().map(|(u, v)| normalized(u, v)).collect(),
+        None => Vec::new(),
+    };
+    gone.sort_unstable();
+    gone.dedup();
+
+    let e = &mut cache.entries[i];
+    let before = e.edges.len();
+    if !gone.is_empty() {
+        e.edges.retain(|&(u, v)| gone.binary_search(&normalized(u, v)).is_err());
+    }
+    let removed = before - e.edges.len();
+    let added = add.len();
+    e.edges.extend(add);
+    e.n = n;
+    e.in_memory = true;
+    e.matching = None;
+    Ok(summary(e).with("added", added).with("removed", removed))
+}
+
+fn query(cache: &Cache, params: &Json) -> RpcResult {
+    let e = &cache.entries[graph_index(cache, params)?];
+    let v = match params.get("vertex") {
+        None => return Ok(summary(e)),
+        Some(v) => match v.as_usize() {
+            Some(v) if v < e.n => v,
+            _ => return invalid(&format!("vertex must be in 0..{}", e.n)),
+        },
+    };
+    let matching = match e.matching {
+        Some(ref m) => m,
+        None => return Err((NOT_SOLVED, "graph has not been solved since its last load or update".to_string())),
+    };
+    let mate = matching.iter()
+        .find(|&&(a, b)| a == v || b == v)
+        .map(|&(a, b)| if a == v { b } else { a });
+    let mut nbrs: Vec<usize> = e.edges.iter()
+        .filter(|&&(a, b)| a != b && (a == v || b == v))
+        .map(|&(a, b)| if a == v { b } else { a })
+        .collect();
+    nbrs.sort_unstable();
+    nbrs.dedup();
+    Ok(Json::obj().with("graph", e.id).with("vertex", v).with("mate", mate).with("degree", nbrs.len()))
+}
+
+fn stats(cache: &Cache) -> RpcResult {
+    let graphs: Vec<Json> = cache.entries.iter()
+        .map(|e| summary(e)
+            .with("hash", if e.in_memory { None } else { Some(format!("{:016x}", e.hash)) })
+            .with("parse_ms", e.parse_ms as u64)
+            .with("hits", e.hits))
+        .collect();
+    Ok(Json::obj()
+        .with("graphs", graphs)
+        .with("hits", cache.hits)
+        .with("misses", cache.misses)
+        .with("parse_ms_saved", cache.parse_ms_saved() as u64))
+}
+
+/* Reply for one request object, or None for a notification (no id). */
+fn handle_request(cache: &mut Cache, opts: &LoadOptions, req: &Json) -> Option<Json> {
+    let id = req.get("id").cloned();
+    let method = match (req.get("jsonrpc").and_then(|v| v.as_str()),
+                        req.get("method").and_then(|m| m.as_str())) {
+        (Some("2.0"), Some(m)) => m,
+        _ => return Some(error_response(id.unwrap_or(Json::Null), INVALID_REQUEST,
+                                        "expected {\"jsonrpc\": \"2.0\", \"method\": ...}")),
+    };
+    let no_params = Json::obj();
+    let params = req.get("params").unwrap_or(&no_params);
+    let result = match *params {
+        Json::Obj(_) => match method {
+            "load" => load(cache, opts, params),
+            "solve" => solve(cache, params),
+            "update" => update(cache, params),
+            "query" => query(cache, params),
+            "stats" => stats(cache),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'", method))),
+        },
+        _ => invalid("params must be an object"),
+    };
+    let id = id?;
+    Some(match result {
+        Ok(r) => Json::obj().with("jsonrpc", "2.0").with("result", r).with("id", id),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn handle_message(cache: &mut Cache, opts: &LoadOptions, line: &str) -> Option<Json> {
+    match json::parse(line.trim()) {
+        Err(e) => Some(error_response(Json::Null, PARSE_ERROR, &e)),
+        Ok(Json::Arr(items)) => {
+            if items.is_empty() {
+                return Some(error_response(Json::Null, INVALID_REQUEST, "empty batch"));
+            }
+            let replies: Vec<Json> = items.iter().filter_map(|r| handle_request(cache, opts, r)).collect();
+            if replies.is_empty() { None } else { Some(Json::Arr(replies)) }
+        }
+        Ok(req) => handle_request(cache, opts, &req),
+    }
+}
+
+/* Serve JSON-RPC messages, starting with the already-read `first` line,
+ * until the client closes the connection. */
+pub(crate) fn session<R: BufRead>(
+    cache: &mut Cache, opts: &LoadOptions, first: String, reader: &mut R, out: &mut dyn Write,
+) -> io::Result<()> {
+    let mut line = first;
+    loop {
+        if !line.trim().is_empty() {
+            if let Some(reply) = handle_message(cache, opts, &line) {
+                writeln!(out, "{}", reply)?;
+                out.flush()?;
+            }
+        }
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+    }
+}
@@ -0,0 +1,267 @@
+/*
+ * combi serve - keep parsed graphs in memory and answer solve requests
+ * over a Unix domain socket.
+ *
+ * Graphs are cached by the file contents and the loader flags they were
+ * parsed under: running again with different solver flags (or on a renamed
+ * copy) skips the parse, while an edited file, or the same file under
+ * another --on-mismatch, --merge-parallel, ..., is parsed again. An entry
+ * keeps the file's bytes; a lookup narrows by length and a 64-bit FNV-1a
+ * hash and then compares them, so two files that collide in the hash are
+ * still told apart. Reading and comparing the bytes is I/O bound and much
+ * cheaper than parsing them.
+ *
+ * Protocol: one request line per connection; the reply is plain text,
+ * terminated by the server closing the connection.
+ *   SOLVE <algo> <greedy_mode>[:<seed>] [<loader flag> ...] <absolute path>
+ *   STATS
+ *   CLEAR
+ *   SHUTDOWN
+ * The loader flags are the client's, as LoadOptions::to_args writes them
+ * (`--on-mismatch=error`, `--strict`, ...); none means the defaults. The
+ * daemon's own loader flags apply to the JSON-RPC load method, and its
+ * --load-threads to every load. A connection whose first line starts with
+ * '{' or '[' speaks JSON-RPC 2.0 instead, one message per line, until the
+ * client closes it (see rpc.rs).
+ */
+
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::time::Instant;
+
+use super::graph_io::{self, LoadOptions};
+
+pub const DEFAULT_SOCKET: &str = "/tmp/combi.sock";
+
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for &b in bytes {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
+pub(crate) struct CachedGraph {
+    pub(crate) id: usize,
+    pub(crate) hash: u64,
+    pub(crate) path: String,
+    pub(crate) n: usize,
+    pub(crate) edges: Vec<(usize, usize)>,
+    pub(crate) parse_ms: u128,
+    pub(crate) hits: usize,
+    /* Edited or sent inline over RPC: no longer matches any file. */
+    pub(crate) in_memory: bool,
+    /* The file's bytes and the loader flags it was parsed under
+     * (LoadOptions::to_args), compared on a lookup; empty in memory. */
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) load: Vec<String>,
+    /* Last solve result, kept for RPC queries; dropped on update. */
+    pub(crate) matching: Option<Vec<(usize, usize)>>,
+}
+
+/* Linear scan: a daemon holds a handful of huge graphs, not many small ones. */
+pub(crate) struct Cache {
+    pub(crate) entries: Vec<CachedGraph>,
+    pub(crate) hits: usize,
+    pub(crate) misses: usize,
+    next_id: usize,
+}
+
+impl Cache {
+    fn new() -> Self {
+        Cache { entries: Vec::new(), hits: 0, misses: 0, next_id: 1 }
+    }
+
+    pub(crate) fn insert(&mut self, hash: u64, path: &str, n: usize, edges: Vec<(usize, usize)>,
+                         parse_ms: u128, in_memory: bool) -> usize {
+        self.entries.push(CachedGraph {
+            id: self.next_id, hash, path: path.to_string(), n, edges,
+            parse_ms, hits: 0, in_memory, bytes: Vec::new(), load: Vec::new(), matching: None,
+        });
+        self.next_id += 1;
+        self.entries.len() - 1
+    }
+
+    pub(crate) fn parse_ms_saved(&self) -> u128 {
+        self.entries.iter().map(|e| e.parse_ms * e.hits as u128).sum()
+    }
+
+    pub(crate) fn position(&self, id: usize) -> Option<usize> {
+        self.entries.iter().position(|e| e.id == id)
+    }
+
+    /* Index of the cached graph for `path` loaded under `opts`, parsing it
+     * on a miss. */
+    pub(crate) fn get_or_load(&mut self, path: &str, opts: &LoadOptions) -> Result<(usize, bool), String> {
+        let bytes = fs::read(path).map_err(|e| format!("{}: {}", path, e))?;
+        let hash = content_hash(&bytes);
+        let load = opts.to_args();
+        let found = self.entries.iter().position(|e| {
+            !e.in_memory && e.bytes.len() == bytes.len() && e.hash == hash && e.load == load && e.bytes == bytes
+        });
+        if let Some(i) = found {
+            self.hits += 1;
+            self.entries[i].hits += 1;
+            self.entries[i].path = path.to_string();
+            return Ok((i, true));
+        }
+        let start = Instant::now();
+        /* a compressed file is hashed as stored and parsed through the decompressor */
+        let parsed = if graph_io::is_compressed(path) {
+            graph_io::load_graph(path, opts)
+        } else {
+            graph_io::parse_graph(&bytes[..], opts)
+        };
+        let (n, edges) = parsed.map_err(|e| e.to_string())?;
+        self.misses += 1;
+        let i = self.insert(hash, path, n, edges, start.elapsed().as_millis(), false);
+        self.entries[i].bytes = bytes;
+        self.entries[i].load = load;
+        Ok((i, false))
+    }
+
+    fn write_stats(&self, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "Cached graphs: {}", self.entries.len())?;
+        writeln!(out, "Hits: {}  Misses: {}", self.hits, self.misses)?;
+        for e in &self.entries {
+            writeln!(out, "  #{} {:016x}  {} vertices, {} edges, parse {} ms, {} hits  {}{}{}",
+                     e.id, e.hash, e.n, e.edges.len(), e.parse_ms, e.hits, e.path,
+                     if e.load.is_empty() { String::new() } else { format!(" ({})", e.load.join(" ")) },
+                     if e.in_memory { " (in memory)" } else { "" })?;
+        }
+        writeln!(out, "Parse time saved: {} ms", self.parse_ms_saved())
+    }
+}
+
+/* Handle one request line; returns false on SHUTDOWN. */
+fn handle(cache: &mut Cache, opts: &LoadOptions, line: &str, out: &mut dyn Write) -> io::Result<bool> {
+    let mut parts = line.trim_end().splitn(4, ' ');
+    match parts.next().unwrap_or("") {
+        "SOLVE" => {
+            let algo = parts.next().unwrap_or("");
+            /* "4:<seed>" for --greedy-random */
+            let mode = parts.next().unwrap_or("0");
+            let (gm, seed) = match mode.find(':') {
+                Some(k) => (&mode[..k], mode[k + 1..].parse().unwrap_or(1)),
+                None => (mode, 1),
+            };
+            let gm: i32 = gm.parse().unwrap_or(0);
+            /* the client's loader flags, then the path (which may hold spaces) */
+            let mut flags = Vec::new();
+            let mut path = parts.next().unwrap_or("");
+            while path.starts_with("--") {
+                let (flag, rest) = path.split_at(path.find(' ').unwrap_or(path.len()));
+                flags.push(flag.to_string());
+                path = rest.trim_start_matches(' ');
+            }
+            let load = match LoadOptions::from_args(&flags) {
+                Ok(load) if !load.input.has_header() || load.edge_ids => {
+                    writeln!(out, "Error: --format snap, --format labels and --edge-ids are local only")?;
+                    return Ok(true);
+                }
+                Ok(load) => LoadOptions { threads: opts.threads, block_bytes: opts.block_bytes, ..load },
+                Err(e) => {
+                    writeln!(out, "Error: {}", e)?;
+                    return Ok(true);
+                }
+            };
+            match cache.get_or_load(path, &load) {
+                Ok((i, hit)) => {
+                    let e = &cache.entries[i];
+                    writeln!(out, "Graph: {} vertices, {} edges", e.n, e.edges.len())?;
+                    if hit {
+                        writeln!(out, "Cache: hit {:016x} (saved {} ms parse)", e.hash, e.parse_ms)?;
+                    } else {
+                        writeln!(out, "Cache: miss {:016x} (parsed in {} ms)", e.hash, e.parse_ms)?;
+                    }
+                    let solve = super::SolveOptions { algo, greedy_mode: gm, seed, ..Default::default() };
+                    super::write_solution(out, e.n, &e.edges, &solve, None, None, &graph_io::InputNames::default())?;
+                }
+                Err(e) => writeln!(out, "Error: {}", e)?,
+            }
+        }
+        "STATS" => cache.write_stats(out)?,
+        "CLEAR" => {
+            let k = cache.entries.len();
+            cache.entries.clear();
+            writeln!(out, "Evicted {} graphs", k)?;
+        }
+        "SHUTDOWN" => {
+            writeln!(out, "Shutting down")?;
+            return Ok(false);
+        }
+        other => writeln!(out, "Error: unknown request '{}'", other)?,
+    }
+    Ok(true)
+}
+
+#[cfg(unix)]
+pub fn serve(socket: &str, opts: LoadOptions) -> io::Result<()> {
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if UnixStream::connect(socket).is_ok() {
+        return Err(io::Error::new(io::ErrorKind::AddrInUse,
+                                  format!("a server is already listening on {}", socket)));
+    }
+    let _ = fs::remove_file(socket); /* stale socket from a killed server */
+    let listener = UnixListener::bind(socket)?;
+    println!("combi serve: listening on {}", socket);
+
+    let mut cache = Cache::new();
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => { eprintln!("accept: {}", e); continue; }
+        };
+        let mut line = String::new();
+        let mut reader = BufReader::new(&stream);
+        if reader.read_line(&mut line).is_err() { continue; }
+        let mut out = &stream;
+        let first = line.trim_start();
+        if first.starts_with('{') || first.starts_with('[') {
+            if let Err(e) = super::rpc::session(&mut cache, &opts, line, &mut reader, &mut out) {
+                eprintln!("rpc client: {}", e);
+            }
+            continue;
+        }
+        match handle(&mut cache, &opts, &line, &mut out) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(e) => eprintln!("client: {}", e),
+        }
+    }
+    fs::remove_file(socket)
+}
+
+/* Send one request and copy the reply to stdout (an error reply to
+ * stderr, exiting with status 1). */
+#[cfg(unix)]
+pub fn request(socket: &str, line: &str) -> io::Result<()> {
+    use std::os::unix::net::UnixStream;
+
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {} (is `combi serve` running?)", socket, e)))?;
+    stream.write_all(line.as_bytes())?;
+    stream.write_all(b"\n")?;
+    stream.shutdown(std::net::Shutdown::Write)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    /* a refused request fails the command, as the local run would */
+    if reply.starts_with("Error: ") {
+        eprint!("{}", reply);
+        std::process::exit(1);
+    }
+    print!("{}", reply);
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn serve(_socket: &str, _opts: LoadOptions) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "combi serve needs Unix domain sockets"))
+}
+
+#[cfg(not(unix))]
+pub fn request(_socket: &str, _line: &str) -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "combi serve needs Unix domain sockets"))
+}
@@ -0,0 +1,126 @@
+/*
+ * The solve command's settings: what `combi solve` reads from its command
+ * line, and what the daemon and the dry-run plan are handed.
+ */
+
+use std::time::Duration;
+
+use super::args;
+use super::matchers;
+use super::ordering;
+use super::phase0;
+use super::svg;
+use super::time_limit;
+
+fn greedy_mode_of(args: &[String]) -> i32 {
+    if args::has_flag(args, "--greedy-random") {
+        4
+    } else if args::has_flag(args, "--greedy-ks") {
+        3
+    } else if args::has_flag(args, "--greedy-md") {
+        2
+    } else if args::has_flag(args, "--greedy") {
+        1
+    } else {
+        0
+    }
+}
+
+/* The solve command's settings, read once from the command line. The
+ * daemon solves with the defaults but for the algorithm and the greedy
+ * start; any other setting is local only. */
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct SolveOptions<'a> {
+    pub algo: &'a str,
+    pub greedy_mode: i32,
+    pub seed: u64,
+    pub aug3: usize,
+    pub reorder: ordering::Reorder,
+    pub show_phases: bool,                      /* --phase-stats */
+    pub phase0: phase0::Mode,
+    pub init: Option<&'a str>,                  /* --init-matching */
+    pub output: Option<&'a str>,                /* --output-matching */
+    pub dot: Option<&'a str>,
+    pub svg: Option<(&'a str, svg::Layout)>,
+    pub lp_gap: bool,
+    pub explain: bool,                          /* the reasons behind an "auto" pick */
+    pub progress: bool,
+    pub time_limit: Option<Duration>,
+    pub trace: Option<&'a str>,
+    pub forbid: Option<&'a str>,
+    pub force: Option<&'a str>,                 /* --force-edges */
+    pub lex: bool,                              /* --tie-break lex */
+    pub count: bool,                            /* --count-matchings */
+    pub certify: bool,                          /* the Tutte-Berge check of the result */
+    pub mem_report: bool,                       /* the heap the solve took */
+}
+
+impl<'a> Default for SolveOptions<'a> {
+    fn default() -> Self {
+        SolveOptions {
+            algo: matchers::DEFAULT_ALGORITHM,
+            greedy_mode: 0,
+            seed: 1,
+            aug3: 0,
+            reorder: ordering::Reorder::Natural,
+            show_phases: false,
+            phase0: phase0::Mode::Off,
+            init: None,
+            output: None,
+            dot: None,
+            svg: None,
+            lp_gap: false,
+            explain: false,
+            progress: false,
+            time_limit: None,
+            trace: None,
+            forbid: None,
+            force: None,
+            lex: false,
+            count: false,
+            certify: false,
+            mem_report: false,
+        }
+    }
+}
+
+impl<'a> SolveOptions<'a> {
+    pub fn from_args(args: &'a [String]) -> Result<Self, String> {
+        let aug3 = args::aug3_of(args)?;
+        /* --init-aug3 alone runs after the plain greedy start */
+        let greedy_mode = match greedy_mode_of(args) {
+            0 if aug3 > 0 => 1,
+            gm => gm,
+        };
+        let svg_layout = svg::Layout::from_args(args)?;
+        Ok(SolveOptions {
+            algo: args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM),
+            greedy_mode,
+            seed: args::seed_of(args)?,
+            aug3,
+            reorder: ordering::Reorder::from_args(args)?,
+            show_phases: args::has_flag(args, "--phase-stats"),
+            phase0: phase0::Mode::from_args(args),
+            init: args::value_of(args, "--init-matching"),
+            output: args::value_of(args, "--output-matching"),
+            dot: args::value_of(args, "--dot"),
+            svg: args::value_of(args, "--svg").map(|p| (p, svg_layout)),
+            lp_gap: args::has_flag(args, "--lp-gap"),
+            explain: args::has_flag(args, "--explain"),
+            progress: args::has_flag(args, "--progress"),
+            time_limit: time_limit::time_limit_of(args)?,
+            trace: args::value_of(args, "--trace"),
+            forbid: args::value_of(args, "--forbid"),
+            force: args::value_of(args, "--force-edges"),
+            lex: args::value_of(args, "--tie-break") == Some("lex"),
+            count: args::has_flag(args, "--count-matchings"),
+            certify: args::has_flag(args, "--certify"),
+            mem_report: args::has_flag(args, "--mem-report"),
+        })
+    }
+
+    /* Whether a setting the daemon does not take is set. */
+    pub fn local_only(&self) -> bool {
+        *self != SolveOptions { algo: self.algo, greedy_mode: self.greedy_mode, seed: self.seed, ..Default::default() }
+    }
+}
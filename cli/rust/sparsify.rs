@@ -0,0 +1,132 @@
+/*
+ * combi sparsify - a sparse subgraph with the matching number of the graph.
+ *
+ * Keeps the union of --rounds K edge-disjoint matchings (see sparsifier.rs):
+ * first-fit maximal ones by default, one linear pass each as in a
+ * streaming setting, or maximum ones from --algo with --maximum. The
+ * report compares nu of the kept subgraph with nu of the graph, both
+ * solved with --algo; --no-verify skips the two solves. --until-preserved
+ * solves the graph first and stops at the first round whose subgraph has
+ * its matching number (K, if given, still caps the rounds): a small
+ * certificate. The kept edges can be written as a new "n m" edge list
+ * (--output). --rounds all runs until no edge is left: with --maximum the
+ * decomposition into matchings, largest first, and --schedule writes the
+ * rounds as "round u v" lines, a schedule of pairwise meetings.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+use super::sparsifier;
+
+const DEFAULT_ROUNDS: usize = 3;
+
+fn write_edges(path: &str, n: usize, edges: &[(usize, usize)]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    writeln!(f, "{} {}", n, edges.len())?;
+    for &(u, v) in edges {
+        writeln!(f, "{} {}", u, v)?;
+    }
+    f.flush()
+}
+
+fn write_schedule(path: &str, rounds: &[Vec<(usize, usize)>]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for (i, round) in rounds.iter().enumerate() {
+        for &(u, v) in round {
+            writeln!(f, "{} {} {}", i + 1, u, v)?;
+        }
+    }
+    f.flush()
+}
+
+fn percent(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 100.0 } else { 100.0 * part as f64 / whole as f64 }
+}
+
+pub(crate) fn run(args: &[String], opts: &LoadOptions) -> Result<(), SuiteError> {
+    let algo = args::value_of(args, "--algo").unwrap_or(matchers::DEFAULT_ALGORITHM);
+    let maximum = args::has_flag(args, "--maximum");
+    let until = args::has_flag(args, "--until-preserved");
+    let verify = !args::has_flag(args, "--no-verify");
+    let rounds = match args::value_of(args, "--rounds") {
+        Some("all") => usize::MAX,
+        Some(s) => match s.parse::<usize>() {
+            Ok(k) if k > 0 => k,
+            _ => return Err(format!("invalid --rounds '{}' (a positive count, or all)", s).into()),
+        },
+        None if until => usize::MAX,
+        None => DEFAULT_ROUNDS,
+    };
+    if until && !verify {
+        return Err("--until-preserved needs the graph's matching number, and --no-verify skips it".into());
+    }
+    let (n, edges) = graph_io::load_graph(&args[2], opts)?;
+    println!("Graph: {} vertices, {} edges", n, edges.len());
+    matchers::check_vertex_count(algo, n)?;
+
+    let start = Instant::now();
+    let nu = if verify { Some(matchers::maximum_matching(algo, n, &edges)?.len()) } else { None };
+    let solve_ms = start.elapsed().as_millis();
+    let start = Instant::now();
+    let s = sparsifier::sparsify(algo, n, &edges, maximum, rounds, if until { nu } else { None })?;
+    let duration = start.elapsed();
+    let kept = s.edges();
+    println!("Rounds: {} ({})", s.rounds.len(),
+             if maximum { format!("maximum, {}", algo) } else { "first-fit maximal".to_string() });
+    let mut total = 0;
+    for (i, round) in s.rounds.iter().enumerate() {
+        total += round.len();
+        match s.nu_after.get(i) {
+            Some(reached) => {
+                println!("  round {:<3} {:>10} edges, {:>10} kept, nu {}", i + 1, round.len(), total, reached)
+            }
+            None => println!("  round {:<3} {:>10} edges, {:>10} kept", i + 1, round.len(), total),
+        }
+    }
+
+    let mut errors = sparsifier::check_sparsifier(n, &edges, &s);
+    let kept_nu = match nu {
+        Some(_) => Some(matchers::maximum_matching(algo, n, &kept)?.len()),
+        None => None,
+    };
+    if let (Some(nu), Some(k)) = (nu, kept_nu) {
+        if k > nu {
+            errors.push(format!("Subgraph matching {} exceeds the graph's {}!", k, nu));
+        }
+    }
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    let mut degree = vec![0usize; n];
+    for &(u, v) in &kept {
+        degree[u] += 1;
+        degree[v] += 1;
+    }
+    println!("\n=== Validation Report ===");
+    println!("Kept edges: {} ({:.2}% of {})", kept.len(), percent(kept.len(), edges.len()), edges.len());
+    println!("Max kept degree: {}", degree.iter().cloned().max().unwrap_or(0));
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if let Some(path) = args::value_of(args, "--output") {
+        write_edges(path, n, &kept).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} edges to {}", kept.len(), path);
+    }
+    if let Some(path) = args::value_of(args, "--schedule") {
+        write_schedule(path, &s.rounds).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} rounds to {}", s.rounds.len(), path);
+    }
+    if let (Some(nu), Some(k)) = (nu, kept_nu) {
+        println!("Matching number: {} in the graph, {} in the subgraph ({:.3}%{})", nu, k, percent(k, nu),
+                 if k == nu { ", preserved" } else { "" });
+        println!("Solve time: {} ms", solve_ms);
+    }
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
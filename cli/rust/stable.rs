@@ -0,0 +1,68 @@
+/*
+ * combi stable - stable marriage and hospitals/residents.
+ *
+ * Deferred acceptance from stable_matching.rs; --propose picks the side
+ * that proposes and so gets its optimal stable matching (default left).
+ * The result is checked for capacities and blocking pairs.
+ *
+ * Input: "<left> <right>", then one "[capacity:] preference list" line per
+ * left agent and per right agent.
+ */
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::time::Instant;
+
+use super::args;
+use super::stable_matching::{self, Side};
+
+fn write_pairs(path: &str, pairs: &[(usize, usize)]) -> io::Result<()> {
+    let mut f = BufWriter::new(File::create(path)?);
+    for &(l, r) in pairs {
+        writeln!(f, "{} {}", l, r)?;
+    }
+    f.flush()
+}
+
+pub(crate) fn run(args: &[String]) -> Result<(), String> {
+    let proposer = match args::value_of(args, "--propose") {
+        Some(s) => Side::parse(s).ok_or_else(|| format!("--propose must be left or right, got '{}'", s))?,
+        None => Side::Left,
+    };
+    let inst = stable_matching::load_instance(&args[2]).map_err(|e| e.to_string())?;
+    let slots = |c: &[usize]| c.iter().sum::<usize>();
+    println!("Instance: {} left ({} slots), {} right ({} slots), {} acceptable pairs",
+             inst.left.len(), slots(&inst.left.capacity), inst.right.len(), slots(&inst.right.capacity),
+             inst.acceptable_pairs().len());
+
+    let start = Instant::now();
+    let sm = stable_matching::gale_shapley(&inst, proposer);
+    let duration = start.elapsed();
+    println!("Proposing side: {}", proposer.name());
+    println!("Proposals: {}", sm.proposals);
+
+    let errors = stable_matching::check_stable(&inst, &sm.pairs);
+    for e in &errors {
+        eprintln!("ERROR: {}", e);
+    }
+    let (left_sum, right_sum) = stable_matching::rank_sums(&inst, &sm.pairs);
+    println!("\n=== Validation Report ===");
+    println!("Matching size: {}", sm.pairs.len());
+    println!("Rank sums: left {}, right {}", left_sum, right_sum);
+    println!("{}", if errors.is_empty() { "VALIDATION PASSED" } else { "VALIDATION FAILED" });
+    println!("=========================\n");
+
+    if args::has_flag(args, "--show-pairs") {
+        for &(l, r) in &sm.pairs {
+            println!("{} {}", l, r);
+        }
+        println!();
+    }
+    if let Some(path) = args::value_of(args, "--output") {
+        write_pairs(path, &sm.pairs).map_err(|e| format!("{}: {}", path, e))?;
+        println!("Wrote {} pairs to {}", sm.pairs.len(), path);
+    }
+    println!("Matching size: {}", sm.pairs.len());
+    println!("Time: {} ms", duration.as_millis());
+    Ok(())
+}
@@ -0,0 +1,188 @@
+/*
+ * combi solve on a weighted graph: a file whose edge lines carry a third
+ * column, "u v w" (graph_io::has_weights), is solved by Edmonds' weighted
+ * blossom algorithm (weighted_blossom.rs) instead of a cardinality solver.
+ *
+ *   --maximize  (the default) a maximum-weight matching, of any size
+ *   --minimize  a minimum-weight matching among the maximum-cardinality
+ *               ones (the lightest of all matchings is the empty one when
+ *               the weights are positive)
+ *   --tie-break lighter|heavier
+ *               the same as --minimize, or a maximum-weight matching
+ *               among the maximum-cardinality ones: the weight breaks the
+ *               tie between maximum matchings (--tie-break lex, the
+ *               lexicographically smallest, is for unweighted graphs, see
+ *               tie_break.rs)
+ *
+ * An unset --algo, or auto, routes a weighted file here, and --algo
+ * weighted-blossom asks for it. A cardinality solver named on a weighted
+ * file is refused rather than silently dropping the weights;
+ * --ignore-weights reads the file unweighted instead. The flags that
+ * steer the cardinality solvers (greedy starts, phase 0, --trace, ...)
+ * are refused too. A matched pair weighs its heaviest parallel edge, or
+ * its lightest when minimizing, as the solver sees it.
+ *
+ * --check-duals checks the solver's final duals against the matching
+ * (weighted_blossom::check_duals): complementary slackness, an optimality
+ * proof by LP duality. A failure exits 1.
+ */
+
+use std::io::{self, Write};
+use std::time::Instant;
+
+use super::args;
+use super::dot;
+use super::error::SuiteError;
+use super::graph_io::{self, LoadOptions};
+use super::matchers;
+use super::matching_io;
+use super::solve_options::SolveOptions;
+use super::svg;
+use super::verify;
+use super::weighted_blossom;
+
+pub(crate) const WEIGHT_USAGE: &str =
+    "[--maximize|--minimize|--tie-break lex|lighter|heavier] [--check-duals] [--ignore-weights]";
+
+/* Flags of the cardinality solvers, refused on a weighted solve. */
+const CARDINALITY_FLAGS: [&str; 19] = [
+    "--greedy", "--greedy-md", "--greedy-ks", "--greedy-random", "--init-aug3", "--reorder", "--phase0", "--crown",
+    "--fold", "--phase-stats", "--progress", "--time-limit", "--trace", "--init-matching", "--lp-gap", "--forbid",
+    "--force-edges", "--count-matchings", "--mem-report",
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Objective {
+    Maximize,
+    Minimize,
+    Heavier,
+}
+
+impl Objective {
+    fn name(self) -> &'static str {
+        match self {
+            Objective::Maximize => "maximize: maximum-weight matching",
+            Objective::Minimize => "minimize: minimum-weight maximum-cardinality matching",
+            Objective::Heavier => "tie-break heavier: maximum-weight maximum-cardinality matching",
+        }
+    }
+}
+
+/* The objective of a weighted solve of `path`, or None for a cardinality
+ * one; the refusals above as errors. */
+pub(crate) fn route(args: &[String], path: &str, opts: &LoadOptions) -> Result<Option<Objective>, SuiteError> {
+    let objective = match (args::has_flag(args, "--maximize"), args::has_flag(args, "--minimize")) {
+        (true, true) => return Err("--maximize and --minimize are mutually exclusive".into()),
+        (true, false) => Some(Objective::Maximize),
+        (false, true) => Some(Objective::Minimize),
+        (false, false) => None,
+    };
+    let (objective, lex) = match (args::value_of(args, "--tie-break"), objective) {
+        (None, objective) => (objective, false),
+        (Some(_), Some(_)) => return Err("--tie-break does not combine with --maximize or --minimize".into()),
+        (Some("lex"), None) => (None, true),
+        (Some("lighter"), None) => (Some(Objective::Minimize), false),
+        (Some("heavier"), None) => (Some(Objective::Heavier), false),
+        (Some(other), None) => {
+            return Err(format!("invalid --tie-break value '{}' (expected lex, lighter or heavier)", other).into());
+        }
+    };
+    let algo = args::value_of(args, "--algo");
+    if args::has_flag(args, "--ignore-weights") {
+        if objective.is_some() || algo == Some(matchers::WEIGHTED_ALGORITHM) || args::has_flag(args, "--check-duals") {
+            return Err(format!("--ignore-weights does not combine with --maximize, --minimize, --tie-break \
+                                lighter|heavier, --check-duals or --algo {}", matchers::WEIGHTED_ALGORITHM).into());
+        }
+        return Ok(None);
+    }
+    let weighted = graph_io::has_weights(path, opts.input)?;
+    if lex && weighted {
+        return Err(format!("--tie-break lex is for the cardinality solvers, and {} has edge weights (--tie-break \
+                            lighter|heavier breaks the tie by weight, --ignore-weights solves it by cardinality)",
+                           path).into());
+    }
+    let objective = match algo {
+        Some(a) if a == matchers::WEIGHTED_ALGORITHM && !weighted => {
+            return Err(format!("{} needs edge weights, and {} has no third column", a, path).into());
+        }
+        Some(a) if a != matchers::WEIGHTED_ALGORITHM && a != matchers::AUTO_ALGORITHM && weighted => {
+            return Err(format!("{} is a cardinality solver and {} has edge weights: leave --algo unset (or use \
+                                --algo {}) to solve it weighted, or pass --ignore-weights", a, path,
+                               matchers::WEIGHTED_ALGORITHM).into());
+        }
+        _ if weighted => objective.unwrap_or(Objective::Maximize),
+        _ if objective.is_some() || args::has_flag(args, "--check-duals") => {
+            return Err(format!("--maximize, --minimize, --tie-break lighter|heavier and --check-duals need edge \
+                                weights, and {} has no third column", path).into());
+        }
+        _ => return Ok(None),
+    };
+    if let Some(flag) = CARDINALITY_FLAGS.iter().find(|f| args::has_flag(args, f)) {
+        return Err(format!("{} is for the cardinality solvers, and {} has edge weights (--ignore-weights \
+                            solves it by cardinality)", flag, path).into());
+    }
+    Ok(Some(objective))
+}
+
+/* Loads `path` weighted, solves and prints the report of a cardinality
+ * solve with the objective and the weight added; of `solve`, the output
+ * paths are used. */
+pub(crate) fn run(
+    out: &mut dyn Write, path: &str, opts: &LoadOptions, objective: Objective, check_duals: bool,
+    solve: &SolveOptions,
+) -> Result<(), SuiteError> {
+    let (n, edges, names) = graph_io::load_weighted_graph_named(path, opts)?;
+    writeln!(out, "Graph: {} vertices, {} edges (weighted)", n, edges.len())?;
+    if solve.svg.is_some() {
+        svg::check_size(n)?;
+    }
+    /* minimizing maximizes flipped weights over the largest matchings */
+    let flipped;
+    let (solved, cardinality): (&[(usize, usize, i64)], bool) = match objective {
+        Objective::Maximize => (&edges, false),
+        Objective::Heavier => (&edges, true),
+        Objective::Minimize => {
+            flipped = weighted_blossom::flipped_weights(&edges);
+            (&flipped, true)
+        }
+    };
+    let start = Instant::now();
+    let result = weighted_blossom::max_weight_matching_with_duals(n, solved, cardinality);
+    let solve_ms = start.elapsed().as_millis();
+    let matching = &result.pairs;
+    let plain: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+    write_report(out, n, &edges, &plain, matching, objective)?;
+    if check_duals {
+        let positive = result.duals.blossoms.iter().filter(|b| b.1 > 0).count();
+        writeln!(out, "Blossoms: {} ({} with a positive dual)", result.duals.blossoms.len(), positive)?;
+        weighted_blossom::check_duals(n, solved, &result, cardinality)
+            .map_err(|e| format!("dual check failed: {}", e))?;
+        writeln!(out, "Duals: complementary slackness holds, the matching is optimal")?;
+    }
+    if let Some(p) = solve.output {
+        matching_io::save_weighted_matching(p, matching, false, objective == Objective::Minimize, &names)?;
+        writeln!(out, "Wrote {} pairs to {}", matching.len(), p)?;
+    }
+    if let Some(p) = solve.dot {
+        dot::save(p, n, &plain, matching, None)?;
+        writeln!(out, "Wrote the drawing to {}", p)?;
+    }
+    if let Some((p, layout)) = solve.svg {
+        svg::save(p, n, &plain, matching, None, layout)?;
+        writeln!(out, "Wrote the picture to {}", p)?;
+    }
+    writeln!(out, "Time: {} ms", solve_ms)?;
+    Ok(())
+}
+
+fn write_report(
+    out: &mut dyn Write, n: usize, edges: &[(usize, usize, i64)], plain: &[(usize, usize)],
+    matching: &[(usize, usize)], objective: Objective,
+) -> io::Result<()> {
+    writeln!(out, "Algorithm: {} ({})", matchers::WEIGHTED_ALGORITHM, objective.name())?;
+    let mut report = verify::validate((n, n), plain, matching, false);
+    report.weight = Some(verify::weight_of(edges, matching, false, objective != Objective::Minimize));
+    report.write(out)?;
+    writeln!(out, "Matching size: {}", matching.len())?;
+    writeln!(out, "Matching weight: {}", report.weight.unwrap_or(0))
+}
@@ -50,7 +50,7 @@ mkdir -p "$RESULTS/raw"
 # ── general matching algorithms ──────────────────────────────────────────
 GENERAL_ALGOS="edmonds-blossom-simple edmonds-blossom-optimized gabow-simple gabow-optimized micali-vazirani"
 MV_PURE="micali-vazirani-pure"
-BIPARTITE_ALGOS="hopcroft-karp"
+BIPARTITE_ALGOS="hopcroft-karp pothen-fan"
 LANGS="cpp rust python"
 
 # derive source filename from algorithm directory name
@@ -258,6 +258,29 @@ for graph in $BIPARTITE_GRAPHS; do
     fi
 done
 
+# Randomized oracles, the instance corpus and the fuzz targets (tests/rust/)
+echo ""
+for src in "$REPO"/tests/rust/*.rs; do
+    name="$(basename "$src" .rs)"
+    bin="$REPO/tests/rust/${name}_rust"
+    case "$name" in
+        instance_corpus) set -- --dir "$REPO/tests/instances"; see="results/raw/$name.log" ;;
+        fuzz_targets)    set -- --iters 2000 --dump-dir "$RESULTS/fuzz_crashes"; see="results/fuzz_crashes/" ;;
+        *)               set -- --dump-dir "$RESULTS/${name}_failures"; see="results/${name}_failures/" ;;
+    esac
+    if rustc -O "$src" -o "$bin" 2>"$RESULTS/raw/${name}_compile.log"; then
+        if "$bin" "$@" > "$RESULTS/raw/$name.log" 2>&1; then
+            printf "  %-50s  ✓\n" "$name"
+            cross_ok=$((cross_ok + 1))
+        else
+            printf "  %-50s  ✗  (see %s)\n" "$name" "$see"
+            cross_errors=$((cross_errors + 1))
+        fi
+    else
+        printf "  %-50s  COMPILE FAILED\n" "$name"
+    fi
+done
+
 echo ""
 if [ "$cross_errors" -eq 0 ]; then
     echo "ALL CROSS-VALIDATION PASSED ✓ ($cross_ok graphs)"
@@ -5,7 +5,9 @@
 #   ./run_large_benchmarks.sh                                    # defaults
 #   ./run_large_benchmarks.sh --sizes 100k 1m --langs cpp rust
 #   ./run_large_benchmarks.sh --algos hk mv-pure gabow-opt
+#   ./run_large_benchmarks.sh --algos edmonds-lists edmonds-matrix edmonds-bitset --langs rust
 #   ./run_large_benchmarks.sh --mode plain greedy greedy-md
+#   ./run_large_benchmarks.sh --mode greedy-random --seeds 1 2 3 4 5 6 7 8
 #   ./run_large_benchmarks.sh --runs 5 --timeout 600
 #   ./run_large_benchmarks.sh --list
 #
@@ -13,7 +15,10 @@
 #   sizes:    all found in data/large-benchmarks/
 #   langs:    cpp rust python
 #   algos:    all (filtered by feasibility)
-#   mode:     plain (options: plain, greedy, greedy-md)
+#   mode:     plain (options: plain, greedy, greedy-md, greedy-ks, greedy-random,
+#             greedy-aug3 = --greedy --init-aug3)
+#   seeds:    1 2 3 4 5 (greedy-random runs once per seed; the report gives
+#             the min/mean/max of init quality and time across seeds)
 #   runs:     3 (reports median)
 #   timeout:  300s per run
 #   datadir:  data/large-benchmarks
@@ -22,6 +27,11 @@
 #   results/large-benchmarks/<timestamp>/report.md
 #   results/large-benchmarks/<timestamp>/results.csv
 #   results/large-benchmarks/<timestamp>/raw/          (individual run logs)
+#
+# Dense graphs: general_dense_<size>_<i>.txt files in the data directory are
+# run alongside the sparse ones. The edmonds-lists/-matrix/-bitset entries run
+# the Rust Edmonds optimized binary with a forced --adjacency layout, only on
+# graphs of at most 5000 vertices, to compare the adjacency layouts.
 
 set -e
 
@@ -34,6 +44,7 @@ OUTDIR="$REPO/results/large-benchmarks"
 RUNS=3
 TIMEOUT=300
 LIST_ONLY=0
+SEEDS="1 2 3 4 5"
 
 # Filters (empty = all)
 F_SIZES=""
@@ -44,9 +55,9 @@ F_MODE=""
 # ── algorithm registry ────────────────────────────────────────────────
 # Short name → directory name, graph type, complexity class
 # Graph type: general | bipartite
-# Complexity: ve (O(VE)) | fast (O(E√V))
+# Complexity: ve (O(VE)) | fast (O(E√V)) | dense (adjacency matrix, n ≤ 5000)
 
-ALL_GENERAL="edmonds-simple edmonds-opt gabow-simple gabow-opt mv-pure"
+ALL_GENERAL="edmonds-simple edmonds-opt gabow-simple gabow-opt mv-pure edmonds-lists edmonds-matrix edmonds-bitset"
 ALL_BIPARTITE="hk"
 ALL_ALGOS="$ALL_GENERAL $ALL_BIPARTITE"
 
@@ -54,6 +65,8 @@ alg_dir() {
     case "$1" in
         edmonds-simple) echo "edmonds-blossom-simple" ;;
         edmonds-opt)    echo "edmonds-blossom-optimized" ;;
+        edmonds-lists|edmonds-matrix|edmonds-bitset)
+                        echo "edmonds-blossom-optimized" ;;
         gabow-simple)   echo "gabow-simple" ;;
         gabow-opt)      echo "gabow-optimized" ;;
         mv-pure)        echo "micali-vazirani-pure" ;;
@@ -70,6 +83,16 @@ alg_complexity() {
     case "$1" in
         edmonds-simple|edmonds-opt|gabow-simple) echo "ve" ;;
         gabow-opt|mv-pure|hk) echo "fast" ;;
+        edmonds-lists|edmonds-matrix|edmonds-bitset) echo "dense" ;;
+    esac
+}
+
+alg_args() {
+    # layout variants of the Rust Edmonds optimized binary
+    case "$1" in
+        edmonds-lists)  echo "--adjacency lists" ;;
+        edmonds-matrix) echo "--adjacency matrix" ;;
+        edmonds-bitset) echo "--adjacency bitset" ;;
     esac
 }
 
@@ -107,6 +130,13 @@ while [ $# -gt 0 ]; do
                 F_MODE="$F_MODE $1"; shift
             done
             ;;
+        --seeds)
+            shift
+            SEEDS=""
+            while [ $# -gt 0 ] && [ "${1#-}" = "$1" ]; do
+                SEEDS="$SEEDS $1"; shift
+            done
+            ;;
         --runs)    shift; RUNS="$1"; shift ;;
         --timeout) shift; TIMEOUT="$1"; shift ;;
         --datadir) shift; DATADIR="$1"; shift ;;
@@ -165,6 +195,15 @@ for f in "$DATADIR"/general_sparse_*.txt; do
     GENERAL_FILES="$GENERAL_FILES $f"
 done
 
+for f in "$DATADIR"/general_dense_*.txt; do
+    [ -f "$f" ] || continue
+    tag="$(basename "$f" .txt | sed 's/general_dense_//' | sed 's/_[0-9]*$//')"
+    if [ -n "$F_SIZES" ]; then
+        echo "$F_SIZES" | grep -qw "$tag" || continue
+    fi
+    GENERAL_FILES="$GENERAL_FILES $f"
+done
+
 for f in "$DATADIR"/bipartite_sparse_*.txt; do
     [ -f "$f" ] || continue
     tag="$(basename "$f" .txt | sed 's/bipartite_sparse_//' | sed 's/_[0-9]*$//')"
@@ -207,8 +246,8 @@ BIPARTITE_ALGOS="$(select_algos bipartite)"
 # Validate mode values
 for m in $F_MODE; do
     case "$m" in
-        plain|greedy|greedy-md) ;;
-        *) echo "ERROR: --mode values must be plain, greedy, or greedy-md (got: $m)"; exit 1 ;;
+        plain|greedy|greedy-md|greedy-ks|greedy-random|greedy-aug3) ;;
+        *) echo "ERROR: --mode values must be plain, greedy, greedy-md, greedy-ks, greedy-random or greedy-aug3 (got: $m)"; exit 1 ;;
     esac
 done
 
@@ -228,6 +267,11 @@ add_to_plan() {
         return
     fi
 
+    # Layout variants exist in Rust only and allocate an n x n matrix
+    if [ "$(alg_complexity "$alg")" = "dense" ]; then
+        [ "$lang" = "rust" ] && [ "$v" -le 5000 ] || return
+    fi
+
     plan_count=$((plan_count + 1))
     PLAN="$PLAN
 $alg|$graph|$lang|$gname|$v|$mode"
@@ -236,7 +280,14 @@ $alg|$graph|$lang|$gname|$v|$mode"
 add_modes() {
     alg="$1"; graph="$2"; lang="$3"
     for m in $F_MODE; do
-        add_to_plan "$alg" "$graph" "$lang" "$m"
+        if [ "$m" = "greedy-random" ]; then
+            # One job per seed, mode recorded as greedy-random:<seed>
+            for sd in $SEEDS; do
+                add_to_plan "$alg" "$graph" "$lang" "$m:$sd"
+            done
+        else
+            add_to_plan "$alg" "$graph" "$lang" "$m"
+        fi
     done
 }
 
@@ -266,6 +317,7 @@ echo "  Output:         $OUTDIR"
 echo "  Data directory: $DATADIR"
 echo "  Languages:     $F_LANGS"
 echo "  Mode:           $F_MODE"
+case " $F_MODE " in *" greedy-random "*) echo "  Seeds:         $SEEDS" ;; esac
 echo "  Runs per job:   $RUNS (report median)"
 echo "  Timeout:        ${TIMEOUT}s per run"
 echo "  Total jobs:     $plan_count"
@@ -359,7 +411,8 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
 
     dir="$(alg_dir "$alg")"
     base="$(alg_src "$alg")"
-    logbase="$OUTDIR/raw/${base}_${lang}_${gname}_${greedy}"
+    logbase="$OUTDIR/raw/${base}_${lang}_${gname}_$(echo "$greedy" | tr : -)"
+    case "$alg" in edmonds-lists|edmonds-matrix|edmonds-bitset) logbase="${logbase}_${alg#edmonds-}" ;; esac
 
     printf "  [%3d/%d] %-18s %-6s %-10s %-30s " "$job" "$plan_count" "$alg" "$lang" "$greedy" "$gname"
 
@@ -386,6 +439,10 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
     extra_args=""
     [ "$greedy" = "greedy" ] && extra_args="--greedy"
     [ "$greedy" = "greedy-md" ] && extra_args="--greedy-md"
+    [ "$greedy" = "greedy-ks" ] && extra_args="--greedy-ks"
+    [ "$greedy" = "greedy-aug3" ] && extra_args="--greedy --init-aug3"
+    case "$greedy" in greedy-random:*) extra_args="--greedy-random --seed ${greedy#greedy-random:}" ;; esac
+    extra_args="$extra_args $(alg_args "$alg")"
 
     # Run N times
     times=""
@@ -423,7 +480,7 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
     done
 
     # Compute median
-    clean_times="$(echo "$times" | tr ' ' '\n' | grep -v ERR | grep -v TIMEOUT | sort -n)"
+    clean_times="$(echo "$times" | tr ' ' '\n' | grep . | grep -v ERR | grep -v TIMEOUT | sort -n)"
     n_good="$(echo "$clean_times" | grep -c . || true)"
 
     if [ "$n_good" -gt 0 ]; then
@@ -442,7 +499,7 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
 
     echo "$alg,$gname,$lang,$v,$greedy,$size,$greedy_init,$greedy_pct,$med,$t1,$t2,$t3,$valid" >> "$CSV"
 
-    if [ "$greedy" = "greedy" ] || [ "$greedy" = "greedy-md" ]; then
+    if [ "$greedy" != "plain" ]; then
         printf "size=%-8s median=%-8s %-6s greedy_init=%-8s (%s)\n" "$size" "${med}ms" "$valid" "$greedy_init" "$greedy_pct"
     else
         printf "size=%-8s median=%-8s %s\n" "$size" "${med}ms" "$valid"
@@ -495,6 +552,7 @@ echo "Generated: $(date '+%Y-%m-%d %H:%M:%S')" >> "$REPORT"
 echo "Runs per job: $RUNS (median reported)" >> "$REPORT"
 echo "Timeout: ${TIMEOUT}s" >> "$REPORT"
 echo "Mode: $F_MODE" >> "$REPORT"
+case " $F_MODE " in *" greedy-random "*) echo "Seeds: $SEEDS" >> "$REPORT" ;; esac
 echo "" >> "$REPORT"
 
 # Summary
@@ -545,9 +603,9 @@ for gname in $graphs; do
         row="| $alg"
         alg_size=""
         for lang in $graph_langs; do
-            plain_line="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV")"
-            greedy_line="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md)"
-            greedymd_line="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV")"
+            plain_line="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" || true)"
+            greedy_line="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md || true)"
+            greedymd_line="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" || true)"
 
             if [ -n "$plain_line" ]; then
                 plain_ms="$(echo "$plain_line" | cut -d, -f9)"
@@ -590,11 +648,11 @@ for gname in $graphs; do
     graph_langs="$(grep ",$gname," "$CSV" | cut -d, -f3 | sort -u)"
     for alg in $graph_algos; do
         for lang in $graph_langs; do
-            plain_ms="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" | cut -d, -f9)"
-            greedy_ms="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md | cut -d, -f9)"
-            greedymd_ms="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f9)"
-            greedymd_init="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f7)"
-            greedymd_pct="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f8)"
+            plain_ms="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" | cut -d, -f9 || true)"
+            greedy_ms="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md | cut -d, -f9 || true)"
+            greedymd_ms="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f9 || true)"
+            greedymd_init="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f7 || true)"
+            greedymd_pct="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f8 || true)"
 
             [ -z "$plain_ms" ] && plain_ms="–"
             [ -z "$greedy_ms" ] && greedy_ms="–"
@@ -620,7 +678,7 @@ for gname in $graphs; do
                 md_speedup="–"
             fi
 
-            short_gname="$(echo "$gname" | sed 's/general_sparse_/g_/' | sed 's/bipartite_sparse_/b_/')"
+            short_gname="$(echo "$gname" | sed 's/general_sparse_/g_/' | sed 's/general_dense_/gd_/' | sed 's/bipartite_sparse_/b_/')"
             echo "| $alg | $short_gname | $lang | $plain_ms | $greedy_ms | ${speedup}× | $greedymd_ms | ${md_speedup}× | $greedymd_init | $greedymd_pct |" >> "$REPORT"
         done
     done
@@ -628,6 +686,39 @@ done
 
 echo "" >> "$REPORT"
 
+# Random greedy across seeds: spread of init quality and total time
+if grep -q ',greedy-random:' "$CSV"; then
+    echo "## Random Greedy Across Seeds" >> "$REPORT"
+    echo "" >> "$REPORT"
+    echo "Init = greedy init size, % = init / final, ms = median per seed." >> "$REPORT"
+    echo "" >> "$REPORT"
+    echo "| Algorithm | Graph | Lang | Seeds | Init min | Init mean | Init max | % min | % mean | % max | ms min | ms mean | ms max |" >> "$REPORT"
+    echo "|-----------|-------|------|------:|---------:|----------:|---------:|------:|-------:|------:|-------:|--------:|-------:|" >> "$REPORT"
+    tail -n +2 "$CSV" | awk -F, '
+        $5 ~ /^greedy-random:/ && $7 != "NA" && $9 != "ERR" {
+            k = $1 "," $2 "," $3
+            if (!(k in cnt)) { order[++nk] = k; imin[k] = imax[k] = $7; pmin[k] = pmax[k] = $8 + 0; tmin[k] = tmax[k] = $9 }
+            cnt[k]++
+            isum[k] += $7; psum[k] += $8; tsum[k] += $9
+            if ($7 + 0 < imin[k] + 0) imin[k] = $7
+            if ($7 + 0 > imax[k] + 0) imax[k] = $7
+            if ($8 + 0 < pmin[k]) pmin[k] = $8 + 0
+            if ($8 + 0 > pmax[k]) pmax[k] = $8 + 0
+            if ($9 + 0 < tmin[k] + 0) tmin[k] = $9
+            if ($9 + 0 > tmax[k] + 0) tmax[k] = $9
+        }
+        END {
+            for (i = 1; i <= nk; i++) {
+                k = order[i]; split(k, f, ",")
+                g = f[2]; sub(/general_sparse_/, "g_", g); sub(/general_dense_/, "gd_", g); sub(/bipartite_sparse_/, "b_", g)
+                printf "| %s | %s | %s | %d | %d | %.1f | %d | %.2f | %.2f | %.2f | %d | %.0f | %d |\n",
+                    f[1], g, f[3], cnt[k], imin[k], isum[k] / cnt[k], imax[k],
+                    pmin[k], psum[k] / cnt[k], pmax[k], tmin[k], tsum[k] / cnt[k], tmax[k]
+            }
+        }' >> "$REPORT"
+    echo "" >> "$REPORT"
+fi
+
 # Language speedup table (C++ = 1.0×) – using plain mode for fair comparison
 echo "## Language Speedups (C++ = 1.0×, plain mode)" >> "$REPORT"
 echo "" >> "$REPORT"
@@ -637,9 +728,9 @@ echo "|-----------|-------|-------:|--------:|---------:|----------:|-----------
 for gname in $graphs; do
     graph_algos="$(grep ",$gname," "$CSV" | cut -d, -f1 | sort -u)"
     for alg in $graph_algos; do
-        cpp_ms="$(grep "^$alg,$gname,cpp,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
-        rust_ms="$(grep "^$alg,$gname,rust,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
-        py_ms="$(grep "^$alg,$gname,python,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
+        cpp_ms="$(grep "^$alg,$gname,cpp,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
+        rust_ms="$(grep "^$alg,$gname,rust,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
+        py_ms="$(grep "^$alg,$gname,python,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
 
         [ -z "$cpp_ms" ] && cpp_ms="–"
         [ -z "$rust_ms" ] && rust_ms="–"
@@ -662,7 +753,7 @@ for gname in $graphs; do
             py_ratio="–"
         fi
 
-        short_gname="$(echo "$gname" | sed 's/general_sparse_/g_/' | sed 's/bipartite_sparse_/b_/')"
+        short_gname="$(echo "$gname" | sed 's/general_sparse_/g_/' | sed 's/general_dense_/gd_/' | sed 's/bipartite_sparse_/b_/')"
         # Format ratios: append × only if not a dash
         if [ "$rust_ratio" = "–" ]; then rust_fmt="–"; else rust_fmt="${rust_ratio}×"; fi
         if [ "$py_ratio" = "–" ]; then py_fmt="–"; else py_fmt="${py_ratio}×"; fi
@@ -7,6 +7,7 @@
 #   ./run_suitesparse_benchmarks.sh --algos gabow-opt mv-pure
 #   ./run_suitesparse_benchmarks.sh --mode plain greedy greedy-md
 #   ./run_suitesparse_benchmarks.sh --graphs fe_body auto ecology1
+#   ./run_suitesparse_benchmarks.sh --mode greedy-random --seeds 1 2 3 4 5 6 7 8
 #   ./run_suitesparse_benchmarks.sh --runs 5 --timeout 600
 #   ./run_suitesparse_benchmarks.sh --list
 #
@@ -14,7 +15,10 @@
 #   graphs:   all .txt files in data/general-unweighted/suitesparse/
 #   langs:    cpp rust python
 #   algos:    auto (all 5 for V ≤ 200k, GO+MV for larger)
-#   mode:     plain greedy-md
+#   mode:     plain greedy-md (options: plain, greedy, greedy-md, greedy-ks, greedy-random,
+#             greedy-aug3 = --greedy --init-aug3)
+#   seeds:    1 2 3 4 5 (greedy-random runs once per seed; the report gives
+#             the min/mean/max of init quality and time across seeds)
 #   runs:     3 (reports median)
 #   timeout:  600s per run
 #
@@ -34,6 +38,7 @@ OUTDIR="$REPO/results/suitesparse"
 RUNS=3
 TIMEOUT=600
 LIST_ONLY=0
+SEEDS="1 2 3 4 5"
 
 # Filters (empty = all)
 F_GRAPHS=""
@@ -92,6 +97,13 @@ while [ $# -gt 0 ]; do
                 F_MODE="$F_MODE $1"; shift
             done
             ;;
+        --seeds)
+            shift
+            SEEDS=""
+            while [ $# -gt 0 ] && [ "${1#-}" = "$1" ]; do
+                SEEDS="$SEEDS $1"; shift
+            done
+            ;;
         --runs)    shift; RUNS="$1"; shift ;;
         --timeout) shift; TIMEOUT="$1"; shift ;;
         --datadir) shift; DATADIR="$1"; shift ;;
@@ -112,8 +124,8 @@ done
 # Validate mode values
 for m in $F_MODE; do
     case "$m" in
-        plain|greedy|greedy-md) ;;
-        *) echo "ERROR: --mode values must be plain, greedy, or greedy-md (got: $m)"; exit 1 ;;
+        plain|greedy|greedy-md|greedy-ks|greedy-random|greedy-aug3) ;;
+        *) echo "ERROR: --mode values must be plain, greedy, greedy-md, greedy-ks, greedy-random or greedy-aug3 (got: $m)"; exit 1 ;;
     esac
 done
 
@@ -195,7 +207,14 @@ $alg|$graph|$lang|$gname|$v|$mode"
 add_modes() {
     alg="$1"; graph="$2"; lang="$3"
     for m in $F_MODE; do
-        add_to_plan "$alg" "$graph" "$lang" "$m"
+        if [ "$m" = "greedy-random" ]; then
+            # One job per seed, mode recorded as greedy-random:<seed>
+            for sd in $SEEDS; do
+                add_to_plan "$alg" "$graph" "$lang" "$m:$sd"
+            done
+        else
+            add_to_plan "$alg" "$graph" "$lang" "$m"
+        fi
     done
 }
 
@@ -217,6 +236,7 @@ echo "  Output:         $OUTDIR"
 echo "  Data directory: $DATADIR"
 echo "  Languages:     $F_LANGS"
 echo "  Mode:           $F_MODE"
+case " $F_MODE " in *" greedy-random "*) echo "  Seeds:         $SEEDS" ;; esac
 echo "  Runs per job:   $RUNS (report median)"
 echo "  Timeout:        ${TIMEOUT}s per run"
 echo "  Total jobs:     $plan_count"
@@ -307,7 +327,7 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
 
     dir="$(alg_dir "$alg")"
     base="$(alg_src "$alg")"
-    logbase="$OUTDIR/raw/${base}_${lang}_${gname}_${greedy}"
+    logbase="$OUTDIR/raw/${base}_${lang}_${gname}_$(echo "$greedy" | tr : -)"
 
     printf "  [%3d/%d] %-18s %-6s %-10s %-25s " "$job" "$plan_count" "$alg" "$lang" "$greedy" "$gname"
 
@@ -334,6 +354,9 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
     extra_args=""
     [ "$greedy" = "greedy" ] && extra_args="--greedy"
     [ "$greedy" = "greedy-md" ] && extra_args="--greedy-md"
+    [ "$greedy" = "greedy-ks" ] && extra_args="--greedy-ks"
+    [ "$greedy" = "greedy-aug3" ] && extra_args="--greedy --init-aug3"
+    case "$greedy" in greedy-random:*) extra_args="--greedy-random --seed ${greedy#greedy-random:}" ;; esac
 
     # Run N times
     times=""
@@ -388,7 +411,7 @@ echo "$PLAN" | while IFS='|' read -r alg graph lang gname v greedy; do
 
     echo "$alg,$gname,$lang,$v,$greedy,$size,$greedy_init,$greedy_pct,$med,$t1,$t2,$t3,$valid" >> "$CSV"
 
-    if [ "$greedy" = "greedy" ] || [ "$greedy" = "greedy-md" ]; then
+    if [ "$greedy" != "plain" ]; then
         printf "size=%-8s median=%-8s %-6s greedy_init=%-8s (%s)\n" "$size" "${med}ms" "$valid" "$greedy_init" "$greedy_pct"
     else
         printf "size=%-8s median=%-8s %s\n" "$size" "${med}ms" "$valid"
@@ -439,6 +462,7 @@ echo "Generated: $(date '+%Y-%m-%d %H:%M:%S')" >> "$REPORT"
 echo "Runs per job: $RUNS (median reported)" >> "$REPORT"
 echo "Timeout: ${TIMEOUT}s" >> "$REPORT"
 echo "Mode: $F_MODE" >> "$REPORT"
+case " $F_MODE " in *" greedy-random "*) echo "Seeds: $SEEDS" >> "$REPORT" ;; esac
 echo "" >> "$REPORT"
 
 # Summary
@@ -501,9 +525,9 @@ for gname in $graphs; do
         row="| $alg"
         alg_size=""
         for lang in $graph_langs; do
-            plain_line="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV")"
-            greedy_line="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md)"
-            greedymd_line="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV")"
+            plain_line="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" || true)"
+            greedy_line="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md || true)"
+            greedymd_line="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" || true)"
 
             if [ -n "$plain_line" ]; then
                 plain_ms="$(echo "$plain_line" | cut -d, -f9)"
@@ -546,11 +570,11 @@ for gname in $graphs; do
     graph_langs="$(grep ",$gname," "$CSV" | cut -d, -f3 | sort -u)"
     for alg in $graph_algos; do
         for lang in $graph_langs; do
-            plain_ms="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" | cut -d, -f9)"
-            greedy_ms="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md | cut -d, -f9)"
-            greedymd_ms="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f9)"
-            greedymd_init="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f7)"
-            greedymd_pct="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f8)"
+            plain_ms="$(grep "^$alg,$gname,$lang,.*,plain," "$CSV" | cut -d, -f9 || true)"
+            greedy_ms="$(grep "^$alg,$gname,$lang,.*,greedy," "$CSV" | grep -v greedy-md | cut -d, -f9 || true)"
+            greedymd_ms="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f9 || true)"
+            greedymd_init="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f7 || true)"
+            greedymd_pct="$(grep "^$alg,$gname,$lang,.*,greedy-md," "$CSV" | cut -d, -f8 || true)"
 
             [ -z "$plain_ms" ] && plain_ms="–"
             [ -z "$greedy_ms" ] && greedy_ms="–"
@@ -583,6 +607,39 @@ done
 
 echo "" >> "$REPORT"
 
+# Random greedy across seeds: spread of init quality and total time
+if grep -q ',greedy-random:' "$CSV"; then
+    echo "## Random Greedy Across Seeds" >> "$REPORT"
+    echo "" >> "$REPORT"
+    echo "Init = greedy init size, % = init / final, ms = median per seed." >> "$REPORT"
+    echo "" >> "$REPORT"
+    echo "| Algorithm | Graph | Lang | Seeds | Init min | Init mean | Init max | % min | % mean | % max | ms min | ms mean | ms max |" >> "$REPORT"
+    echo "|-----------|-------|------|------:|---------:|----------:|---------:|------:|-------:|------:|-------:|--------:|-------:|" >> "$REPORT"
+    tail -n +2 "$CSV" | awk -F, '
+        $5 ~ /^greedy-random:/ && $7 != "NA" && $9 != "ERR" {
+            k = $1 "," $2 "," $3
+            if (!(k in cnt)) { order[++nk] = k; imin[k] = imax[k] = $7; pmin[k] = pmax[k] = $8 + 0; tmin[k] = tmax[k] = $9 }
+            cnt[k]++
+            isum[k] += $7; psum[k] += $8; tsum[k] += $9
+            if ($7 + 0 < imin[k] + 0) imin[k] = $7
+            if ($7 + 0 > imax[k] + 0) imax[k] = $7
+            if ($8 + 0 < pmin[k]) pmin[k] = $8 + 0
+            if ($8 + 0 > pmax[k]) pmax[k] = $8 + 0
+            if ($9 + 0 < tmin[k] + 0) tmin[k] = $9
+            if ($9 + 0 > tmax[k] + 0) tmax[k] = $9
+        }
+        END {
+            for (i = 1; i <= nk; i++) {
+                k = order[i]; split(k, f, ",")
+                g = f[2]; sub(/general_sparse_/, "g_", g); sub(/general_dense_/, "gd_", g); sub(/bipartite_sparse_/, "b_", g)
+                printf "| %s | %s | %s | %d | %d | %.1f | %d | %.2f | %.2f | %.2f | %d | %.0f | %d |\n",
+                    f[1], g, f[3], cnt[k], imin[k], isum[k] / cnt[k], imax[k],
+                    pmin[k], psum[k] / cnt[k], pmax[k], tmin[k], tsum[k] / cnt[k], tmax[k]
+            }
+        }' >> "$REPORT"
+    echo "" >> "$REPORT"
+fi
+
 # Language speedup table (C++ = 1.0×)
 echo "## Language Speedups (C++ = 1.0×, plain mode)" >> "$REPORT"
 echo "" >> "$REPORT"
@@ -592,9 +649,9 @@ echo "|-----------|-------|-------:|--------:|---------:|----------:|-----------
 for gname in $graphs; do
     graph_algos="$(grep ",$gname," "$CSV" | cut -d, -f1 | sort -u)"
     for alg in $graph_algos; do
-        cpp_ms="$(grep "^$alg,$gname,cpp,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
-        rust_ms="$(grep "^$alg,$gname,rust,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
-        py_ms="$(grep "^$alg,$gname,python,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9)"
+        cpp_ms="$(grep "^$alg,$gname,cpp,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
+        rust_ms="$(grep "^$alg,$gname,rust,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
+        py_ms="$(grep "^$alg,$gname,python,.*,plain," "$CSV" 2>/dev/null | cut -d, -f9 || true)"
 
         [ -z "$cpp_ms" ] && cpp_ms="–"
         [ -z "$rust_ms" ] && rust_ms="–"
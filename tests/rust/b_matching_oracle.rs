@@ -0,0 +1,138 @@
+/*
+ * End-to-end test of the b-matching reduction (b_matching.rs)
+ *
+ * Generates seeded random small graphs with random vertex capacities
+ * (including 0 and unlimited), solves them through the matching reduction
+ * with every general matcher, and checks the kept edges form a valid
+ * b-matching whose size equals an exhaustive search over edge subsets.
+ *
+ * A failing instance is written to the dump directory as an "n m" edge
+ * list plus a capacity file, ready for `combi anonymize <graph> --caps <caps>`.
+ *
+ * Usage: b_matching_oracle [--cases N] [--seed S] [--max-m M] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/b_matching.rs"]
+mod b_matching;
+
+use b_matching::BMatching;
+
+/* Largest feasible edge subset, by branch and bound over the edges. */
+fn exhaustive(edges: &[(usize, usize)], left: &mut [usize], i: usize, cur: usize, best: &mut usize) {
+    if cur + (edges.len() - i) <= *best { return; }
+    if i == edges.len() {
+        *best = cur;
+        return;
+    }
+    let (u, v) = edges[i];
+    if left[u] > 0 && left[v] > 0 {
+        left[u] -= 1;
+        left[v] -= 1;
+        exhaustive(edges, left, i + 1, cur + 1, best);
+        left[u] += 1;
+        left[v] += 1;
+    }
+    exhaustive(edges, left, i + 1, cur, best);
+}
+
+fn oracle_size(bm: &BMatching) -> usize {
+    let mut left: Vec<usize> = bm.caps.iter().map(|&c| c.min(bm.edges.len())).collect();
+    let mut best = 0;
+    exhaustive(&bm.edges, &mut left, 0, 0, &mut best);
+    best
+}
+
+fn check(algo: &str, bm: &BMatching) -> Option<String> {
+    let (kept, reduced_size) = match b_matching::max_b_matching(algo, bm) {
+        Ok(r) => r,
+        Err(e) => return Some(e),
+    };
+    if let Some(e) = b_matching::check_b_matching(bm, &kept).into_iter().next() {
+        return Some(e);
+    }
+    if kept.len() != bm.expected_size(reduced_size) {
+        return Some(format!("kept {} edges but the reduced matching implies {}",
+                            kept.len(), bm.expected_size(reduced_size)));
+    }
+    let expected = oracle_size(bm);
+    if kept.len() != expected {
+        return Some(format!("kept {} edges but the oracle keeps {}", kept.len(), expected));
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_m: usize) -> (usize, Vec<(usize, usize)>, Vec<usize>) {
+    let n = 1 + rng.below(10) as usize;
+    let m = rng.below(max_m as u64 + 1) as usize;
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let u = rng.below(n as u64) as usize;
+        let v = rng.below(n as u64) as usize;
+        edges.push((u, v)); /* loops and duplicates are part of the test */
+    }
+    let caps = (0..n)
+        .map(|_| match rng.below(6) {
+            0 => b_matching::UNLIMITED,
+            k => (k - 1) as usize,
+        })
+        .collect();
+    (n, edges, caps)
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, edges: &[(usize, usize)], caps: &[usize]) -> io::Result<PathBuf> {
+    let graph = dump::edge_list(dir, &format!("{}.txt", name), n, edges)?;
+    let limited = caps.iter().enumerate().filter(|&(_, &b)| b != b_matching::UNLIMITED);
+    dump::lines(dir, &format!("{}.caps", name), limited.map(|(v, b)| format!("{} {}", v, b)))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_m: usize = args::parse_or_exit(&args, "--max-m", 18);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("b_matching_failures").to_string();
+
+    println!("b-matching reduction vs. exhaustive search: {} cases, seed {}, m <= {}",
+             cases, seed, max_m);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges, caps) = random_instance(&mut rng, max_m);
+        let bm = BMatching::new(n, &edges, &caps);
+        for algo in matchers::GENERAL_ALGORITHMS.iter() {
+            if let Some(why) = check(algo, &bm) {
+                failures += 1;
+                let name = format!("{}_seed{}_case{}", algo, seed, case);
+                let saved = dump::saved(dump_case(&dump_dir, &name, n, &edges, &caps));
+                println!("  FAIL  {:<15} case {:<5} n={} m={}: {}", algo, case, n, edges.len(), why);
+                println!("        saved: {}", saved);
+            }
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x {} matchers)", cases, matchers::GENERAL_ALGORITHMS.len());
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,242 @@
+/*
+ * Randomized test of the block triangular form against exhaustive search
+ *
+ * Generates seeded random small sparse matrices (square and rectangular,
+ * any density, with empty rows and columns and repeated entries) and
+ * checks block_triangular.rs as `combi btf` uses it:
+ *   - check_btf() accepts the permutations and blocks,
+ *   - the structural rank is the maximum transversal of a dynamic program
+ *     over column subsets,
+ *   - every square block is irreducible: each of its rows reaches every
+ *     other through entries and the block's diagonal,
+ *   - the horizontal and vertical blocks are the Gallai-Edmonds D and A of
+ *     the bipartite graph of rows and columns, by vertex_classes.rs from a
+ *     general matcher's maximum matching,
+ *   - with the blocks put in reverse order, check_btf() rejects the form
+ *     whenever an entry lies above the block diagonal.
+ * Every fourth case is instead a sparse matrix of up to 10 x --max-n rows
+ * and columns (about 3 entries a row), checked for all but the exhaustive
+ * search.
+ *
+ * A failing instance is written to the dump directory as a Matrix Market
+ * file, ready for `combi btf <name>.mtx --show`.
+ *
+ * Usage: block_triangular_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/block_triangular.rs"]
+mod block_triangular;
+#[path = "../../algorithms/common/rust/dot.rs"]
+mod dot;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use block_triangular::{BlockKind, Btf, Matrix};
+use dot::Label;
+
+/* Largest number of entries on distinct rows and columns, by the column
+ * subsets the first rows can use. */
+fn exhaustive_rank(m: &Matrix) -> usize {
+    let mut row_mask = vec![0usize; m.rows];
+    for &(r, c) in &m.entries {
+        row_mask[r] |= 1 << c;
+    }
+    let mut best = vec![None; 1 << m.cols];
+    best[0] = Some(0usize);
+    for &mask in &row_mask {
+        let mut next = best.clone();
+        for used in 0..(1usize << m.cols) {
+            if let Some(size) = best[used] {
+                let mut free = mask & !used;
+                while free != 0 {
+                    let c = free & free.wrapping_neg();
+                    free &= free - 1;
+                    if next[used | c].is_none_or(|s| s < size + 1) {
+                        next[used | c] = Some(size + 1);
+                    }
+                }
+            }
+        }
+        best = next;
+    }
+    best.iter().flatten().copied().max().unwrap_or(0)
+}
+
+/* The first square block whose rows do not all reach each other, by
+ * closing the reachability of its positions: i -> j on an entry (i, j). */
+fn reducible_block(m: &Matrix, btf: &Btf) -> Option<usize> {
+    let mut row_pos = vec![0; m.rows];
+    let mut col_pos = vec![0; m.cols];
+    for (k, &r) in btf.row_perm.iter().enumerate() { row_pos[r] = k; }
+    for (k, &c) in btf.col_perm.iter().enumerate() { col_pos[c] = k; }
+    (0..btf.blocks()).filter(|&b| btf.kind[b] == BlockKind::Square).find(|&b| {
+        let (rs, cs, size) = (btf.row_start[b], btf.col_start[b], btf.row_start[b + 1] - btf.row_start[b]);
+        let mut reach = vec![vec![false; size]; size];
+        for &(r, c) in &m.entries {
+            let (i, j) = (row_pos[r], col_pos[c]);
+            if i >= rs && i < rs + size && j >= cs && j < cs + size {
+                reach[i - rs][j - cs] = true;
+            }
+        }
+        for k in 0..size {
+            let through = reach[k].clone();
+            for row in reach.iter_mut().filter(|row| row[k]) {
+                for (r, &t) in row.iter_mut().zip(&through) { *r |= t; }
+            }
+        }
+        (0..size).any(|i| (0..size).any(|j| i != j && !reach[i][j]))
+    })
+}
+
+/* The same blocks, each in place, in reverse order. */
+fn reversed(btf: &Btf) -> Btf {
+    let mut out = Btf {
+        row_perm: Vec::new(),
+        col_perm: Vec::new(),
+        row_start: vec![0],
+        col_start: vec![0],
+        kind: Vec::new(),
+        rank: btf.rank,
+    };
+    for b in (0..btf.blocks()).rev() {
+        out.row_perm.extend_from_slice(&btf.row_perm[btf.row_start[b]..btf.row_start[b + 1]]);
+        out.col_perm.extend_from_slice(&btf.col_perm[btf.col_start[b]..btf.col_start[b + 1]]);
+        out.row_start.push(out.row_perm.len());
+        out.col_start.push(out.col_perm.len());
+        out.kind.push(btf.kind[b]);
+    }
+    out
+}
+
+fn check(m: &Matrix, case: usize, exhaustive: bool) -> Option<String> {
+    let btf = block_triangular::btf_order(m);
+    let errors = block_triangular::check_btf(m, &btf);
+    if !errors.is_empty() {
+        return Some(errors.join("; "));
+    }
+    if exhaustive {
+        let rank = exhaustive_rank(m);
+        if btf.rank != rank {
+            return Some(format!("structural rank {}, exhaustive search finds {}", btf.rank, rank));
+        }
+    }
+    if let Some(b) = reducible_block(m, &btf) {
+        return Some(format!("square block {} is reducible", b));
+    }
+
+    /* rows are vertices 0.., columns rows.. */
+    let n = m.rows + m.cols;
+    let edges: Vec<(usize, usize)> = m.entries.iter().map(|&(r, c)| (r, m.rows + c)).collect();
+    let algos = matchers::GENERAL_ALGORITHMS;
+    let algo = algos[case % algos.len()];
+    let matching = match matchers::maximum_matching(algo, n, &edges) {
+        Ok(x) => x,
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    let ge = vertex_classes::vertex_classification(n, &edges, &matching);
+    let mut expect = vec![Label::Outside; n];
+    for b in 0..btf.blocks() {
+        let (rows, cols) = match btf.kind[b] {
+            BlockKind::Horizontal => (Label::Odd, Label::Even),
+            BlockKind::Vertical => (Label::Even, Label::Odd),
+            BlockKind::Square => continue,
+        };
+        for &r in &btf.row_perm[btf.row_start[b]..btf.row_start[b + 1]] { expect[r] = rows; }
+        for &c in &btf.col_perm[btf.col_start[b]..btf.col_start[b + 1]] { expect[m.rows + c] = cols; }
+    }
+    if let Some(v) = (0..n).find(|&v| ge.label[v] != expect[v]) {
+        let name = if v < m.rows { format!("row {}", v) } else { format!("column {}", v - m.rows) };
+        return Some(format!("{} is {:?} by the blocks, {:?} by Gallai-Edmonds ({})", name, expect[v], ge.label[v], algo));
+    }
+
+    let mut block_of_row = vec![0; m.rows];
+    let mut block_of_col = vec![0; m.cols];
+    for b in 0..btf.blocks() {
+        for &r in &btf.row_perm[btf.row_start[b]..btf.row_start[b + 1]] { block_of_row[r] = b; }
+        for &c in &btf.col_perm[btf.col_start[b]..btf.col_start[b + 1]] { block_of_col[c] = b; }
+    }
+    let above = m.entries.iter().any(|&(r, c)| block_of_row[r] < block_of_col[c]);
+    if above && block_triangular::check_btf(m, &reversed(&btf)).is_empty() {
+        return Some("accepted with the blocks in reverse order".to_string());
+    }
+    None
+}
+
+fn random_matrix(rng: &mut rng::Rng, max_n: usize, sparse: bool) -> Matrix {
+    let rows = rng.below(max_n as u64 + 1) as usize;
+    let cols = if rng.below(2) == 0 { rows } else { rng.below(max_n as u64 + 1) as usize };
+    let p = if sparse { 3.0 / cols.max(1) as f64 } else { rng.next_f64() * 0.5 };
+    let mut entries = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            if rng.next_f64() < p {
+                entries.push((r, c));
+                if rng.below(10) == 0 { entries.push((r, c)); }
+            }
+        }
+    }
+    rng.shuffle(&mut entries);
+    Matrix { rows, cols, entries }
+}
+
+fn dump_case(dir: &str, name: &str, m: &Matrix) -> io::Result<PathBuf> {
+    let header = ["%%MatrixMarket matrix coordinate pattern general".to_string(),
+                  format!("{} {} {}", m.rows, m.cols, m.entries.len())];
+    let entries = m.entries.iter().map(|&(r, c)| format!("{} {}", r + 1, c + 1));
+    dump::lines(dir, &format!("{}.mtx", name), header.iter().cloned().chain(entries))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("block_triangular_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over column subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Block triangular form vs. exhaustive search: {} cases, seed {}, rows and columns <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let sparse = case % 4 == 3;
+        let m = random_matrix(&mut rng, if sparse { 10 * max_n } else { max_n }, sparse);
+        if let Some(why) = check(&m, case, !sparse) {
+            failures += 1;
+            let name = format!("block_triangular_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &m));
+            println!("  FAIL  case {:<5} {} x {}, {} entries: {}", case, m.rows, m.cols, m.entries.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
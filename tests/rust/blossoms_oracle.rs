@@ -0,0 +1,322 @@
+/*
+ * Randomized test of the explicit blossom objects (blossoms.rs)
+ *
+ * Runs a forest search built on nothing but blossoms.rs (top-level nodes
+ * for bases, contract_bridge for every blossom, climb for the paths) on
+ * seeded random graphs (sparse, dense, and chains of odd cycles that nest
+ * deeply) until the matching is maximum, and checks:
+ *   - after every contraction: check() finds nothing broken, and
+ *     path_to_base from every vertex of the new blossom is an even
+ *     alternating path to its base, on graph edges,
+ *   - every augmenting path climb() returns flips into a valid matching
+ *     one pair larger,
+ *   - at the end of a search, expanding the top-level blossoms in random
+ *     order, down to single vertices: each expansion returns the children
+ *     in cycle order, leaves check() clean, and keeps the paths inside the
+ *     blossoms still standing valid; afterwards every vertex is its own top,
+ *   - the final size is that of Gabow simple.
+ *
+ * A failing instance is written to the dump directory in the standard
+ * "n m" edge-list format.
+ *
+ * Usage: blossoms_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/blossoms.rs"]
+mod blossoms;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/gabow-simple/rust/gabow_simple.rs"]
+#[allow(dead_code)]
+mod gabow_simple;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/types.rs"]
+mod types;
+
+use types::{ix, vx, Vertex, NONE};
+
+const UNLABELED: u8 = 0;
+const EVEN: u8 = 1;
+const ODD: u8 = 2;
+
+/* Unmatched edges `path` must make an alternating path that leaves
+ * `start` by its matched edge and ends at `end`, or at any free vertex
+ * when `end` is None; an empty path when start is the end. */
+fn alternating(adj: &[Vec<bool>], mate: &[Vertex], start: usize, end: Option<usize>,
+               path: &[(usize, usize)]) -> Option<String> {
+    let n = mate.len();
+    let mut partner = vec![usize::MAX; n];
+    for &(a, b) in path {
+        if a >= n || b >= n || !adj[a][b] || mate[a] == vx(b) {
+            return Some(format!("({}, {}) is not an unmatched edge", a, b));
+        }
+        if partner[a] != usize::MAX || partner[b] != usize::MAX {
+            return Some(format!("({}, {}) reuses a vertex", a, b));
+        }
+        partner[a] = b;
+        partner[b] = a;
+    }
+    let done = |w: usize| match end {
+        Some(e) => w == e,
+        None => mate[w] == NONE,
+    };
+    let mut w = start;
+    let mut steps = 0;
+    while !done(w) {
+        if mate[w] == NONE || partner[ix(mate[w])] == usize::MAX || steps == path.len() {
+            return Some(format!("path from {} breaks off at {}", start, w));
+        }
+        w = partner[ix(mate[w])];
+        steps += 1;
+    }
+    if steps != path.len() {
+        return Some(format!("path from {} to {} uses {} of {} edges", start, w, steps, path.len()));
+    }
+    None
+}
+
+/* Even paths from every vertex of every top-level blossom to its base. */
+fn check_paths(bl: &blossoms::Blossoms, adj: &[Vec<bool>], mate: &[Vertex]) -> Option<String> {
+    let n = mate.len();
+    for v in 0..n {
+        let t = bl.top(v);
+        let mut path = Vec::new();
+        bl.path_to_base(t, v, &mut path);
+        if let Some(why) = alternating(adj, mate, v, Some(bl.base(v)), &path) {
+            return Some(format!("in blossom {}: {}", t, why));
+        }
+    }
+    None
+}
+
+/* One forest search from all free vertices; augments and returns true if
+ * it finds a path. Blossoms left at the end are expanded in random order. */
+fn search(rng: &mut rng::Rng, bl: &mut blossoms::Blossoms, adj: &[Vec<bool>], mate: &mut [Vertex])
+          -> Result<bool, String> {
+    let n = mate.len();
+    bl.reset();
+    let mut parent = vec![NONE; n];
+    let mut label = vec![UNLABELED; n];
+    let mut queue: Vec<usize> = (0..n).filter(|&v| mate[v] == NONE).collect();
+    for &v in &queue {
+        label[v] = EVEN;
+    }
+    let mut found = None;
+    let mut qi = 0;
+    'grow: while qi < queue.len() {
+        let u = queue[qi];
+        qi += 1;
+        for v in 0..n {
+            if !adj[u][v] || bl.top(u) == bl.top(v) || mate[u] == vx(v) { continue; }
+            match label[bl.base(v)] {
+                UNLABELED if mate[v] == NONE => {
+                    let mut path = vec![(u, v)];
+                    bl.climb(u, mate, &parent, &mut path);
+                    found = Some(path);
+                    break 'grow;
+                }
+                UNLABELED => {
+                    label[v] = ODD;
+                    parent[v] = vx(u);
+                    let w = ix(mate[v]);
+                    label[w] = EVEN;
+                    queue.push(w);
+                }
+                EVEN => {
+                    /* Bases from u up to its root, then from v until one repeats. */
+                    let mut on_u = vec![false; n];
+                    let mut b = bl.base(u);
+                    loop {
+                        on_u[b] = true;
+                        if mate[b] == NONE { break; }
+                        b = bl.base(ix(parent[ix(mate[b])]));
+                    }
+                    let mut b = bl.base(v);
+                    while !on_u[b] && mate[b] != NONE {
+                        b = bl.base(ix(parent[ix(mate[b])]));
+                    }
+                    if !on_u[b] {
+                        let mut path = vec![(u, v)];
+                        bl.climb(u, mate, &parent, &mut path);
+                        bl.climb(v, mate, &parent, &mut path);
+                        found = Some(path);
+                        break 'grow;
+                    }
+                    let id = bl.contract_bridge(b, u, v, mate, &parent);
+                    let errors = bl.check(mate);
+                    if !errors.is_empty() {
+                        return Err(format!("contracting ({}, {}): {}", u, v, errors[0]));
+                    }
+                    let mut inside = Vec::new();
+                    bl.leaves(id, &mut inside);
+                    for &x in &inside {
+                        if label[x] == ODD {
+                            label[x] = EVEN;
+                            queue.push(x);
+                        }
+                    }
+                    if let Some(why) = check_paths(bl, adj, mate) {
+                        return Err(format!("contracting ({}, {}): {}", u, v, why));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /* Take the blossoms apart, outermost first, in random order. */
+    loop {
+        let mut tops: Vec<usize> = (0..n).map(|v| bl.top(v)).filter(|&t| bl.is_blossom(t)).collect();
+        tops.sort_unstable();
+        tops.dedup();
+        if tops.is_empty() { break; }
+        let b = tops[rng.below(tops.len() as u64) as usize];
+        let before = bl.children(b).to_vec();
+        let base = bl.base_of(b);
+        let children = bl.expand(b);
+        if children != before || bl.base_of(children[0]) != base {
+            return Err(format!("expanding {}: children {:?}, were {:?}", b, children, before));
+        }
+        if let Some(&c) = children.iter().find(|&&c| bl.is_blossom(c) && bl.children(c).is_empty()) {
+            return Err(format!("expanding {}: child {} lost its cycle", b, c));
+        }
+        let errors = bl.check(mate);
+        if !errors.is_empty() {
+            return Err(format!("expanding {}: {}", b, errors[0]));
+        }
+        if let Some(why) = check_paths(bl, adj, mate) {
+            return Err(format!("expanding {}: {}", b, why));
+        }
+    }
+    if let Some(v) = (0..n).find(|&v| bl.top(v) != v) {
+        return Err(format!("vertex {} still under {} with every blossom expanded", v, bl.top(v)));
+    }
+
+    let path = match found {
+        Some(p) => p,
+        None => return Ok(false),
+    };
+    let size = mate.iter().filter(|&&m| m != NONE).count() / 2;
+    for &(a, b) in &path {
+        mate[a] = vx(b);
+        mate[b] = vx(a);
+    }
+    for v in 0..n {
+        if mate[v] != NONE && (mate[ix(mate[v])] != vx(v) || !adj[v][ix(mate[v])]) {
+            return Err(format!("augmenting path {:?} breaks the matching at {}", path, v));
+        }
+    }
+    if mate.iter().filter(|&&m| m != NONE).count() / 2 != size + 1 {
+        return Err(format!("augmenting path {:?} does not add a pair", path));
+    }
+    Ok(true)
+}
+
+fn check(rng: &mut rng::Rng, n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let mut adj = vec![vec![false; n]; n];
+    for &(u, v) in edges {
+        adj[u][v] = true;
+        adj[v][u] = true;
+    }
+    /* Start from a random partial matching, so trees have depth at once. */
+    let mut mate = vec![NONE; n];
+    for &(u, v) in edges {
+        if mate[u] == NONE && mate[v] == NONE && rng.below(2) == 0 {
+            mate[u] = vx(v);
+            mate[v] = vx(u);
+        }
+    }
+    let mut bl = blossoms::Blossoms::new(n);
+    loop {
+        match search(rng, &mut bl, &adj, &mut mate) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(why) => return Some(why),
+        }
+    }
+    let size = mate.iter().filter(|&&m| m != NONE).count() / 2;
+    let expected = gabow_simple::GabowSimple::new(n, edges).maximum_matching(0).len();
+    if size != expected {
+        return Some(format!("maximal at {} pairs, Gabow simple finds {}", size, expected));
+    }
+    None
+}
+
+/* Sparse, dense, or a chain of odd cycles sharing vertices, with a few
+ * chords: deep nesting once the cycles are matched around. */
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = 1 + rng.below(max_n as u64) as usize;
+    let mut edges = Vec::new();
+    match rng.below(3) {
+        0 | 1 => {
+            let p = if rng.below(2) == 0 { 2.5 / n as f64 } else { 0.3 + 0.5 * rng.next_f64() };
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.next_f64() < p { edges.push((u, v)); }
+                }
+            }
+        }
+        _ => {
+            let mut start = 0;
+            loop {
+                let len = 3 + 2 * rng.below(3) as usize;
+                if start + len > n { break; }
+                for i in 0..len {
+                    edges.push((start + i, start + (i + 1) % len));
+                }
+                start += len - 1;
+            }
+            for _ in 0..rng.below(n as u64 / 3 + 1) {
+                let (u, v) = (rng.below(n as u64) as usize, rng.below(n as u64) as usize);
+                if u != v { edges.push((u.min(v), u.max(v))); }
+            }
+            edges.sort_unstable();
+            edges.dedup();
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 5000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 30);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("blossoms_failures").to_string();
+    if max_n == 0 {
+        eprintln!("Error: --max-n must be at least 1");
+        std::process::exit(1);
+    }
+
+    println!("Explicit blossoms vs. path and structure checks: {} cases, seed {}, n <= {}",
+             cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n);
+        if let Some(why) = check(&mut rng, n, &edges) {
+            failures += 1;
+            let name = format!("blossoms_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: contract, lift, expand)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,65 @@
+/*
+ * Failure dumps shared by the programs in tests/rust.
+ *
+ * Each one includes this file with
+ *     #[path = "common/dump.rs"] mod dump;
+ * and saves a failing instance under its --dump-dir, so that the FAIL line
+ * can name a file to replay.
+ */
+
+#![allow(dead_code)]
+
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/* Writes `lines` to the file `name` in `dir`, creating the directory if
+ * needed, and returns the file's path. */
+pub fn lines<I>(dir: &str, name: &str, lines: I) -> io::Result<PathBuf>
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    fs::create_dir_all(dir)?;
+    let path = Path::new(dir).join(name);
+    let mut out = BufWriter::new(fs::File::create(&path)?);
+    for line in lines {
+        writeln!(out, "{}", line)?;
+    }
+    out.flush()?;
+    Ok(path)
+}
+
+/* An "n m" edge list. */
+pub fn edge_list(dir: &str, name: &str, n: usize, edges: &[(usize, usize)]) -> io::Result<PathBuf> {
+    let body = edges.iter().map(|&(u, v)| format!("{} {}", u, v));
+    lines(dir, name, Some(format!("{} {}", n, edges.len())).into_iter().chain(body))
+}
+
+/* A "left right m" edge list. */
+pub fn bipartite_edge_list(dir: &str, name: &str, left: usize, right: usize, edges: &[(usize, usize)])
+                           -> io::Result<PathBuf> {
+    let body = edges.iter().map(|&(u, v)| format!("{} {}", u, v));
+    lines(dir, name, Some(format!("{} {} {}", left, right, edges.len())).into_iter().chain(body))
+}
+
+/* An edge list of "u v w" lines, under "n m" (`right` None) or
+ * "left right m". */
+pub fn weighted_edge_list(dir: &str, name: &str, left: usize, right: Option<usize>, edges: &[(usize, usize, i64)])
+                          -> io::Result<PathBuf> {
+    let header = match right {
+        Some(right) => format!("{} {} {}", left, right, edges.len()),
+        None => format!("{} {}", left, edges.len()),
+    };
+    let body = edges.iter().map(|&(u, v, w)| format!("{} {} {}", u, v, w));
+    lines(dir, name, Some(header).into_iter().chain(body))
+}
+
+/* The saved path for the FAIL line, or why nothing was saved. */
+pub fn saved(result: io::Result<PathBuf>) -> String {
+    match result {
+        Ok(path) => path.display().to_string(),
+        Err(e) => format!("(not saved: {})", e),
+    }
+}
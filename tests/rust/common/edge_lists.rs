@@ -0,0 +1,83 @@
+/*
+ * Random edge lists shared by fuzz_targets and the oracles that grew out of
+ * its solver targets.
+ *
+ * Each one includes this file next to the generator with
+ *     #[path = "../../algorithms/common/rust/rng.rs"] mod rng;
+ *     #[path = "common/edge_lists.rs"] mod edge_lists;
+ * so that one seed gives the same graphs in all of them.
+ */
+
+#![allow(dead_code)]
+
+use super::rng::Rng;
+
+fn below(rng: &mut Rng, bound: usize) -> usize {
+    rng.below(bound as u64) as usize
+}
+
+/* A vertex id: usually in range, sometimes just past n, sometimes huge. */
+fn vertex(rng: &mut Rng, n: usize) -> usize {
+    match below(rng, 16) {
+        0 => n + below(rng, 4),
+        1 => [usize::MAX, 1usize << 31, 1usize << 32, (1usize << 32) + 1][below(rng, 4)],
+        _ => below(rng, n.max(1)),
+    }
+}
+
+/* Up to 3n + 3 edges on ids from vertex(), with self-loops and reversed
+ * duplicates: the solvers drop what is out of range. */
+pub fn arbitrary(rng: &mut Rng, n: usize) -> Vec<(usize, usize)> {
+    let m = below(rng, 3 * n + 4);
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let u = vertex(rng, n);
+        let v = if below(rng, 10) == 0 { u } else { vertex(rng, n) };
+        edges.push((u, v));
+        if below(rng, 10) == 0 { edges.push((v, u)); }
+    }
+    edges
+}
+
+/* A forest on n vertices: taken in random order, each vertex after the
+ * first hangs, with probability 3/4, below an earlier one (of the other
+ * side when `left` splits the ids into two sides). Ends are flipped at
+ * random. */
+pub fn forest(rng: &mut Rng, n: usize, left: Option<usize>) -> Vec<(usize, usize)> {
+    let mut order: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut order);
+    let mut edges = Vec::new();
+    for i in 1..n {
+        let v = order[i];
+        if below(rng, 4) == 0 { continue; }
+        let u = match left {
+            None => order[below(rng, i)],
+            Some(l) => {
+                let others: Vec<usize> = order[..i].iter().cloned().filter(|&u| (u < l) != (v < l)).collect();
+                if others.is_empty() { continue; }
+                others[below(rng, others.len())]
+            }
+        };
+        edges.push(if left.is_some() || below(rng, 2) == 0 { (u, v) } else { (v, u) });
+    }
+    edges
+}
+
+/* None if `matching` is a valid matching of the in-range, non-loop edges. */
+pub fn matching_error(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Option<String> {
+    let mut used = vec![false; n];
+    for &(u, v) in matching {
+        if u >= n || v >= n || u == v {
+            return Some(format!("bad pair ({}, {})", u, v));
+        }
+        if !edges.iter().any(|&(a, b)| (a, b) == (u, v) || (a, b) == (v, u)) {
+            return Some(format!("pair ({}, {}) is not an edge", u, v));
+        }
+        if used[u] || used[v] {
+            return Some(format!("pair ({}, {}) reuses a vertex", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+    }
+    None
+}
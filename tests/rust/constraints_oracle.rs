@@ -0,0 +1,294 @@
+/*
+ * Randomized test of forbidden and forced pairs against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges), a random set F of forbidden pairs (edges of the graph
+ * and not) and a random set K of forced pairs (mostly a matching of what F
+ * leaves, sometimes not), and checks constraints.rs as `combi solve
+ * --forbid --force-edges` uses it:
+ *   - pairs files written with comments, blank lines, both orders and
+ *     repeats load back as F and K, sorted and distinct,
+ *   - remove_forbidden keeps exactly the edges that join no pair of F, in
+ *     order, and counts the rest,
+ *   - check_forced refuses K exactly when a pair is forbidden, not an
+ *     edge, or shares a vertex with another,
+ *   - remove_forced keeps exactly the edges at no vertex of K,
+ *   - the matcher's maximum matching of what is left plus K is a matching
+ *     of G - F that contains K and uses no pair of F, and no such matching
+ *     is larger (a dynamic program over vertex subsets),
+ *   - the drop against nu(G) is at most nu of the forbidden edges plus |K|.
+ * The matchers rotate through the general algorithms.
+ *
+ * A failing instance is written to the dump directory as the graph in the
+ * "n m" format and its pairs files, ready for
+ * `combi solve <name>.txt --forbid <name>.forbid --force-edges <name>.force`.
+ *
+ * Usage: constraints_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/constraints.rs"]
+mod constraints;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* Maximum matching size of (n, edges) on the vertices of `within`. */
+fn exhaustive_nu(n: usize, edges: &[(usize, usize)], within: usize) -> usize {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    /* nu[s]: maximum matching of G[s] */
+    let mut nu = vec![0usize; 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut best = nu[rest];
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            best = best.max(1 + nu[rest & !(1 << w)]);
+        }
+        nu[s] = best;
+    }
+    nu[within]
+}
+
+/* A pairs file of `listed`, comments and blank lines mixed in. */
+fn pairs_text(rng: &mut rng::Rng, title: &str, listed: &[(usize, usize)]) -> String {
+    let mut text = format!("# {}\n", title);
+    for &(u, v) in listed {
+        if rng.below(8) == 0 { text.push('\n'); }
+        text.push_str(&format!("{} {}", u, v));
+        if rng.below(8) == 0 { text.push_str("  # note"); }
+        text.push('\n');
+    }
+    text
+}
+
+fn normalized(listed: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = listed.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    pairs
+}
+
+fn load(scratch: &Path, text: &str, n: usize, listed: &[(usize, usize)]) -> Result<Vec<(usize, usize)>, String> {
+    fs::write(scratch, text).map_err(|e| format!("cannot write {}: {}", scratch.display(), e))?;
+    let pairs = constraints::load_pairs(&scratch.display().to_string(), n, &graph_io::InputNames::default())
+        .map_err(|e| format!("load: {}", e))?;
+    if pairs != normalized(listed) {
+        return Err(format!("load: {:?}, listed {:?}", pairs, normalized(listed)));
+    }
+    Ok(pairs)
+}
+
+struct Instance {
+    n: usize,
+    edges: Vec<(usize, usize)>,
+    forbid: Vec<(usize, usize)>,
+    force: Vec<(usize, usize)>,
+    forbid_text: String,
+    force_text: String,
+}
+
+fn check(c: &Instance, scratch: &Path, algo: &str) -> Option<String> {
+    let (n, edges) = (c.n, &c.edges[..]);
+    let forbidden = match load(scratch, &c.forbid_text, n, &c.forbid) {
+        Ok(p) => p,
+        Err(e) => return Some(format!("forbidden {}", e)),
+    };
+    let forced = match load(scratch, &c.force_text, n, &c.force) {
+        Ok(p) => p,
+        Err(e) => return Some(format!("forced {}", e)),
+    };
+
+    let (allowed, removed) = constraints::remove_forbidden(edges, &forbidden);
+    let expect: Vec<(usize, usize)> = edges.iter().cloned()
+        .filter(|&(u, v)| !forbidden.contains(&(u.min(v), u.max(v))))
+        .collect();
+    if allowed != expect || removed != edges.len() - expect.len() {
+        return Some(format!("remove_forbidden: kept {:?} ({} removed), expected {:?}", allowed, removed, expect));
+    }
+
+    let is_allowed = |u: usize, v: usize| allowed.iter().any(|&(a, b)| (a, b) == (u, v) || (a, b) == (v, u));
+    let mut covered = 0usize;
+    let mut valid = true;
+    for &(u, v) in &forced {
+        if !is_allowed(u, v) || covered & (1 << u | 1 << v) != 0 {
+            valid = false;
+        }
+        covered |= 1 << u | 1 << v;
+    }
+    match constraints::check_forced(n, &allowed, &forced, &forbidden) {
+        Ok(()) if !valid => return Some(format!("check_forced accepted {:?}", forced)),
+        Err(e) if valid => return Some(format!("check_forced refused {:?}: {}", forced, e)),
+        Err(_) => return None,
+        Ok(()) => {}
+    }
+
+    let (rest, removed) = constraints::remove_forced(n, &allowed, &forced);
+    let expect: Vec<(usize, usize)> = allowed.iter().cloned()
+        .filter(|&(u, v)| covered & (1 << u | 1 << v) == 0)
+        .collect();
+    if rest != expect || removed != allowed.len() - expect.len() {
+        return Some(format!("remove_forced: kept {:?} ({} removed), expected {:?}", rest, removed, expect));
+    }
+
+    let mut matching = match matchers::maximum_matching(algo, n, &rest) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    matching.extend_from_slice(&forced);
+    let mut used = vec![false; n];
+    for &(u, v) in &matching {
+        if u == v || used[u] || used[v] || !is_allowed(u, v) {
+            return Some(format!("({}, {}) of the matching is not a free edge of G - F", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+    }
+    let all = (1usize << n) - 1;
+    let best = forced.len() + exhaustive_nu(n, &allowed, all & !covered);
+    if matching.len() != best {
+        return Some(format!("matching of {} pairs, the best with the forced pairs has {}", matching.len(), best));
+    }
+    let nu = exhaustive_nu(n, edges, all);
+    let in_graph: Vec<(usize, usize)> = edges.iter().cloned()
+        .filter(|&(u, v)| forbidden.binary_search(&(u.min(v), u.max(v))).is_ok())
+        .collect();
+    let nu_forbidden = exhaustive_nu(n, &in_graph, all);
+    if best > nu || nu - best > nu_forbidden + forced.len() {
+        return Some(format!("drop {} - {} exceeds nu {} of the forbidden edges plus {} forced", nu, best,
+                            nu_forbidden, forced.len()));
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> Instance {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.7;
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    /* forbidden pairs: some edges, some non-edges, in either order, repeated */
+    let mut forbid = Vec::new();
+    let mut force = Vec::new();
+    if n >= 2 {
+        let q = rng.next_f64() * 0.5;
+        for &(u, v) in &edges {
+            if u != v && rng.next_f64() < q { forbid.push((u, v)); }
+        }
+        for _ in 0..rng.below(3) {
+            let u = rng.below(n as u64) as usize;
+            let v = rng.below(n as u64) as usize;
+            if u != v { forbid.push((v, u)); }
+        }
+        if !forbid.is_empty() && rng.below(4) == 0 {
+            let again = forbid[rng.below(forbid.len() as u64) as usize];
+            forbid.push((again.1, again.0));
+        }
+        /* forced pairs: a first-fit matching of some allowed edges, now and
+         * then with a forbidden pair, a non-edge or a clash added */
+        let banned = normalized(&forbid);
+        let mut used = vec![false; n];
+        for &(u, v) in &edges {
+            if u != v && !used[u] && !used[v] && banned.binary_search(&(u.min(v), u.max(v))).is_err()
+                && rng.below(3) == 0
+            {
+                used[u] = true;
+                used[v] = true;
+                force.push(if rng.below(2) == 0 { (u, v) } else { (v, u) });
+            }
+        }
+        if rng.below(6) == 0 {
+            let u = rng.below(n as u64) as usize;
+            let v = rng.below(n as u64) as usize;
+            if u != v { force.push((u, v)); }
+        }
+        if !force.is_empty() && rng.below(4) == 0 {
+            let again = force[rng.below(force.len() as u64) as usize];
+            force.push((again.1, again.0));
+        }
+    }
+    rng.shuffle(&mut forbid);
+    rng.shuffle(&mut force);
+    let forbid_text = pairs_text(rng, "forbidden pairs", &forbid);
+    let force_text = pairs_text(rng, "forced pairs", &force);
+    Instance { n, edges, forbid, force, forbid_text, force_text }
+}
+
+fn dump_case(dir: &str, name: &str, c: &Instance) -> io::Result<PathBuf> {
+    let graph = dump::edge_list(dir, &format!("{}.txt", name), c.n, &c.edges)?;
+    fs::write(Path::new(dir).join(format!("{}.forbid", name)), &c.forbid_text)?;
+    fs::write(Path::new(dir).join(format!("{}.force", name)), &c.force_text)?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("constraints_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Forbidden and forced pairs vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let scratch = env::temp_dir().join(format!("constraints_oracle_{}.pairs", std::process::id()));
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let c = random_instance(&mut rng, max_n);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(&c, &scratch, algo) {
+            failures += 1;
+            let name = format!("constraints_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &c));
+            println!("  FAIL  case {:<5} n={} m={} |F|={} |K|={} ({}): {}", case, c.n, c.edges.len(), c.forbid.len(),
+                     c.force.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+    let _ = fs::remove_file(&scratch);
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
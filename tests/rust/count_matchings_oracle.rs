@@ -0,0 +1,272 @@
+/*
+ * Randomized test of the maximum matching count against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges; every other one bipartite with sides of equal size, so
+ * that Ryser's permanent runs, with and without a perfect matching) and
+ * checks count_matchings.rs as `combi solve --count-matchings` uses it:
+ *   - the matching number agrees with a maximum matching from one of the
+ *     general matchers, in rotation,
+ *   - the number of maximum matchings agrees with a dynamic program over
+ *     vertex subsets (the lowest vertex is left out or matched to a
+ *     neighbour, and sizes and counts combine as the module's do).
+ * Then families with known counts, beyond the exhaustive search:
+ *   - paths (1 maximum matching, or (n + 1) / 2 for odd n), cycles (2, or
+ *     n for odd n), complete bipartite K_k,k (k!), complete graphs
+ *     ((n - 1)!! perfect matchings for even n, n times that of K_(n-1) for
+ *     odd n), 2 x k ladders (Fibonacci numbers) and square grids (the
+ *     domino tilings, 2, 36, 6728, 12988816), each with its components
+ *     repeated so the counts multiply,
+ *   - a ladder whose count passes 2^128 and a dense graph wider than the
+ *     DP are errors, not wrong counts.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --count-matchings`.
+ *
+ * Usage: count_matchings_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/count_matchings.rs"]
+mod count_matchings;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* The matching number of (n, edges) and the number of maximum matchings. */
+fn exhaustive_count(n: usize, edges: &[(usize, usize)]) -> (usize, u128) {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    let mut best = vec![(0usize, 1u128); 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut pick = best[rest];
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            let (size, count) = best[rest & !(1 << w)];
+            if size + 1 > pick.0 {
+                pick = (size + 1, count);
+            } else if size + 1 == pick.0 {
+                pick.1 += count;
+            }
+        }
+        best[s] = pick;
+    }
+    best[(1usize << n) - 1]
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str) -> Option<String> {
+    let c = match count_matchings::count_maximum_matchings(n, edges) {
+        Ok(c) => c,
+        Err(e) => return Some(format!("not counted: {}", e)),
+    };
+    let nu = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m.len(),
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    if c.size != nu {
+        return Some(format!("matching number {}, {} found {}", c.size, algo, nu));
+    }
+    let (size, count) = exhaustive_count(n, edges);
+    if (c.size, c.count) != (size, count) {
+        return Some(format!("{} maximum matchings of {} pairs, exhaustive search {} of {}", c.count, c.size,
+                            count, size));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, bipartite: bool) -> (usize, Vec<(usize, usize)>) {
+    let p = rng.next_f64() * 0.7;
+    let mut edges = Vec::new();
+    if bipartite {
+        let k = rng.below(max_n as u64 / 2 + 1) as usize;
+        for u in 0..k {
+            for v in 0..k {
+                if rng.next_f64() < p.max(0.2) {
+                    edges.push(if rng.below(2) == 0 { (u, k + v) } else { (k + v, u) });
+                    if rng.below(10) == 0 { edges.push((k + v, u)); }
+                }
+            }
+        }
+        rng.shuffle(&mut edges);
+        return (2 * k, edges);
+    }
+    let n = rng.below(max_n as u64 + 1) as usize;
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+/* `times` disjoint copies of the graph of `make` on vertices 0..k. */
+fn copies(k: usize, times: usize, make: &dyn Fn(usize) -> Vec<(usize, usize)>) -> (usize, Vec<(usize, usize)>) {
+    let one = make(k);
+    let size = one.iter().map(|&(u, v)| u.max(v) + 1).max().unwrap_or(0);
+    let mut edges = Vec::new();
+    for c in 0..times {
+        edges.extend(one.iter().map(|&(u, v)| (c * size + u, c * size + v)));
+    }
+    (times * size, edges)
+}
+
+fn path(k: usize) -> Vec<(usize, usize)> {
+    (1..k).map(|v| (v - 1, v)).collect()
+}
+
+fn cycle(k: usize) -> Vec<(usize, usize)> {
+    let mut e = path(k);
+    e.push((k - 1, 0));
+    e
+}
+
+fn complete_bipartite(k: usize) -> Vec<(usize, usize)> {
+    (0..k).flat_map(|u| (0..k).map(move |v| (u, k + v))).collect()
+}
+
+fn complete(k: usize) -> Vec<(usize, usize)> {
+    (0..k).flat_map(|u| (u + 1..k).map(move |v| (u, v))).collect()
+}
+
+fn grid(rows: usize, cols: usize) -> Vec<(usize, usize)> {
+    let mut e = Vec::new();
+    for r in 0..rows {
+        for c in 0..cols {
+            let v = r * cols + c;
+            if c + 1 < cols { e.push((v, v + 1)); }
+            if r + 1 < rows { e.push((v, v + cols)); }
+        }
+    }
+    e
+}
+
+/* A known family: name, n, edges, count. */
+type Family = (String, usize, Vec<(usize, usize)>, u128);
+
+fn families() -> Vec<Family> {
+    let mut all = Vec::new();
+    let mut add = |name: String, (n, edges): (usize, Vec<(usize, usize)>), count: u128| {
+        all.push((name, n, edges, count))
+    };
+    for k in 2..40 {
+        add(format!("path {}", k), copies(k, 2, &path), if k % 2 == 0 { 1 } else { (k as u128).div_ceil(2).pow(2) });
+    }
+    for k in 3..40 {
+        add(format!("cycle {}", k), copies(k, 2, &cycle), if k % 2 == 0 { 4 } else { (k as u128).pow(2) });
+    }
+    let mut factorial = 1u128;
+    for k in 1..=14 {
+        factorial *= k as u128;
+        add(format!("K_{},{}", k, k), copies(k, 1, &complete_bipartite), factorial);
+    }
+    let mut double_factorial = 1u128; /* (k - 1)!! for the last even k */
+    for k in 2..=18 {
+        let count = if k % 2 == 0 {
+            double_factorial *= k as u128 - 1;
+            double_factorial
+        } else {
+            k as u128 * double_factorial
+        };
+        add(format!("K_{}", k), copies(k, 1, &complete), count);
+    }
+    let (mut a, mut b) = (1u128, 1u128); /* F(k), F(k + 1) */
+    for k in 1..90 {
+        add(format!("ladder 2 x {} twice", k), copies(k, 2, &|k| grid(2, k)), b * b);
+        let c = a + b;
+        a = b;
+        b = c;
+    }
+    for &(k, count) in &[(2usize, 2u128), (4, 36), (6, 6728), (8, 12988816)] {
+        add(format!("grid {} x {}", k, k), copies(k, 1, &|k| grid(k, k)), count);
+    }
+    all
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 14);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("count_matchings_failures").to_string();
+    if max_n > 18 {
+        eprintln!("Error: --max-n is at most 18 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Maximum matching count vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    let fail = |name: &str, n: usize, edges: &[(usize, usize)], why: &str| {
+        let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, edges));
+        println!("  FAIL  {} n={} m={}: {}", name, n, edges.len(), why);
+        println!("        saved: {}", saved);
+    };
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n, case % 2 == 1);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, algo) {
+            failures += 1;
+            fail(&format!("count_matchings_seed{}_case{}", seed, case), n, &edges, &format!("({}) {}", algo, why));
+        }
+    }
+    let known = families();
+    for (name, n, edges, count) in &known {
+        let why = match count_matchings::count_maximum_matchings(*n, edges) {
+            Ok(c) if c.count == *count => continue,
+            Ok(c) => format!("{}: {} maximum matchings, expected {}", name, c.count, count),
+            Err(e) => format!("{}: not counted: {}", name, e),
+        };
+        failures += 1;
+        fail(&format!("count_matchings_{}", name.replace([' ', ','], "_")), *n, edges, &why);
+    }
+    let (n, edges) = copies(200, 1, &|k| grid(2, k));
+    match count_matchings::count_maximum_matchings(n, &edges) {
+        Err(ref e) if e.contains("2^128") => {}
+        other => {
+            failures += 1;
+            let why = match other { Ok(c) => format!("counted {}", c.count), Err(e) => e };
+            fail("count_matchings_overflow", n, &edges, &format!("ladder 2 x 200: {}, expected an overflow", why));
+        }
+    }
+    let (n, edges) = copies(40, 1, &complete);
+    match count_matchings::count_maximum_matchings(n, &edges) {
+        Err(ref e) if e.contains("frontier width") => {}
+        other => {
+            failures += 1;
+            let why = match other { Ok(c) => format!("counted {}", c.count), Err(e) => e };
+            fail("count_matchings_wide", n, &edges, &format!("K_40: {}, expected too wide", why));
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases + {} known counts + overflow and width errors)", cases, known.len());
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,175 @@
+/*
+ * Randomized test of the disjoint A-paths reduction against exhaustive
+ * search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges) and a random terminal set A, and runs the Gallai
+ * reduction of disjoint_paths.rs with one general matcher per case, the
+ * matchers in rotation. Checks:
+ *   - nu(G~) - |V \ A| is the most vertex-disjoint A-paths, found here by
+ *     a search over the vertex subsets the paths use,
+ *   - the extracted paths are that many, each joining two distinct
+ *     terminals over graph edges with no terminal inside,
+ *   - no vertex is on two paths.
+ *
+ * A failing instance is written to the dump directory in the "n m t"
+ * format, ready for `disjoint_paths <name>.txt --algo <algo> --show-paths`.
+ *
+ * Usage: disjoint_paths_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/disjoint-paths/rust/disjoint_paths.rs"]
+#[allow(dead_code)]
+mod disjoint_paths;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+use disjoint_paths::PathPacking;
+
+/* Most disjoint A-paths avoiding the vertices of `used`: the lowest free
+ * terminal is left out, or joined to another one through every set of
+ * free non-terminals a simple path can take. */
+fn exhaustive(n: usize, adj: &[usize], terminals: usize, used: usize, memo: &mut [Option<usize>]) -> usize {
+    if let Some(best) = memo[used] {
+        return best;
+    }
+    let free = terminals & !used;
+    let mut best = 0;
+    if free.count_ones() >= 2 {
+        let a = free.trailing_zeros() as usize;
+        best = exhaustive(n, adj, terminals, used | 1 << a, memo);
+        let mut seen = vec![false; n << n];
+        let mut stack = vec![(1usize << a, a)];
+        while let Some((on_path, v)) = stack.pop() {
+            let mut next = adj[v] & !used & !on_path;
+            while next != 0 {
+                let w = next.trailing_zeros() as usize;
+                next &= next - 1;
+                if terminals >> w & 1 == 1 {
+                    best = best.max(1 + exhaustive(n, adj, terminals, used | on_path | 1 << w, memo));
+                } else if !seen[(on_path | 1 << w) * n + w] {
+                    seen[(on_path | 1 << w) * n + w] = true;
+                    stack.push((on_path | 1 << w, w));
+                }
+            }
+        }
+    }
+    memo[used] = Some(best);
+    best
+}
+
+fn check(n: usize, edges: &[(usize, usize)], terminals: &[usize], algo: &str) -> Option<String> {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    let terminal_set = terminals.iter().fold(0, |s, &a| s | 1 << a);
+    let opt = exhaustive(n, &adj, terminal_set, 0, &mut vec![None; 1 << n]);
+
+    let pp = PathPacking::new(n, edges, terminals);
+    let matching = match matchers::maximum_matching(algo, 2 * n, &pp.reduced_edges()) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("reduced graph refused: {}", e)),
+    };
+    let bound = matching.len() - pp.non_terminals();
+    if bound != opt {
+        return Some(format!("nu(G~) - |V \\ A| = {}, exhaustive search finds {} paths", bound, opt));
+    }
+    let paths = pp.extract_paths(&matching);
+    let mut used = 0usize;
+    for path in &paths {
+        let (a, b) = (path[0], path[path.len() - 1]);
+        if a == b || terminal_set >> a & 1 == 0 || terminal_set >> b & 1 == 0 {
+            return Some(format!("path {:?} does not join two distinct terminals", path));
+        }
+        if let Some(&v) = path[1..path.len() - 1].iter().find(|&&v| terminal_set >> v & 1 == 1) {
+            return Some(format!("path {:?} passes through terminal {}", path, v));
+        }
+        if let Some(w) = path.windows(2).find(|w| adj[w[0]] >> w[1] & 1 == 0) {
+            return Some(format!("path {:?} uses {}-{}, not an edge", path, w[0], w[1]));
+        }
+        for &v in path {
+            if used >> v & 1 == 1 {
+                return Some(format!("vertex {} on two paths", v));
+            }
+            used |= 1 << v;
+        }
+    }
+    if paths.len() != opt {
+        return Some(format!("{} paths extracted, {} exist", paths.len(), opt));
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>, Vec<usize>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.7;
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    let q = rng.next_f64();
+    let terminals = (0..n).filter(|_| rng.next_f64() < q).collect();
+    (n, edges, terminals)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("disjoint_paths_failures").to_string();
+    if max_n > 14 {
+        eprintln!("Error: --max-n is at most 14 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Disjoint A-paths vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges, terminals) = random_instance(&mut rng, max_n);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, &terminals, algo) {
+            failures += 1;
+            let name = format!("disjoint_paths_seed{}_case{}", seed, case);
+            let header = format!("{} {} {}", n, edges.len(), terminals.len());
+            let body = edges.iter().map(|&(u, v)| format!("{} {}", u, v));
+            let ids: Vec<String> = terminals.iter().map(|a| a.to_string()).collect();
+            let lines = Some(header).into_iter().chain(body).chain(Some(ids.join(" ")));
+            let saved = dump::saved(dump::lines(&dump_dir, &format!("{}.txt", name), lines));
+            println!("  FAIL  case {:<5} n={} m={} t={} ({}): {}", case, n, edges.len(), terminals.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,121 @@
+/*
+ * Randomized test of the --dot export and its blossoms
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and checks
+ * dot::blossoms over the maximum matchings of two matchers:
+ *   - the same EVEN, ODD and unreached vertices from both (the
+ *     Gallai-Edmonds decomposition is unique),
+ *   - each blossom odd, all EVEN, and matched inside but for its base,
+ *   - no edge from an EVEN vertex to another blossom or an unreached one,
+ *   - free vertices = EVEN components - ODD vertices,
+ *   - the DOT text with one red edge per pair, one double circle per free
+ *     vertex and one cluster per blossom.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --dot <name>.dot`.
+ *
+ * Usage: dot_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/dot.rs"]
+mod dot;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+fn check(n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let matching = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, edges) {
+        Ok(m) => m,
+        Err(_) => return None, /* rejected cleanly */
+    };
+    let other = matchers::maximum_matching("edmonds-simple", n, edges).unwrap();
+    let (label, base) = dot::blossoms(n, edges, &matching);
+    if dot::blossoms(n, edges, &other).0 != label {
+        return Some(format!("labels differ between two maximum matchings: {:?}", label));
+    }
+    let mut mate = vec![None; n];
+    for &(u, v) in &matching {
+        mate[u] = Some(v);
+        mate[v] = Some(u);
+    }
+    let clusters = dot::clusters(&base);
+    for (b, vs) in &clusters {
+        if vs.len() % 2 == 0 || vs.iter().any(|&v| label[v] != dot::Label::Even) {
+            return Some(format!("blossom {:?} (base {}) is even or not all EVEN", vs, b));
+        }
+        if let Some(&v) = vs.iter().find(|&&v| v != *b && mate[v].is_none_or(|w| base[w] != *b)) {
+            return Some(format!("vertex {} of blossom {} is not matched inside it", v, b));
+        }
+    }
+    for &(u, v) in edges {
+        if u < n && v < n && u != v && label[u] != dot::Label::Odd && label[v] != dot::Label::Odd
+            && (label[u] == dot::Label::Even || label[v] == dot::Label::Even) && base[u] != base[v] {
+            return Some(format!("edge {}-{} leaves an EVEN vertex for {:?} {:?}", u, v, label[u], label[v]));
+        }
+    }
+    let components = (0..n).filter(|&v| label[v] == dot::Label::Even && base[v] == v).count();
+    let odd = label.iter().filter(|&&l| l == dot::Label::Odd).count();
+    if n - 2 * matching.len() + odd != components {
+        return Some(format!("{} free vertices, {} EVEN components, {} ODD vertices",
+                            n - 2 * matching.len(), components, odd));
+    }
+    let mut text = Vec::new();
+    dot::write_dot(&mut text, n, edges, &matching, None).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    let count = |pattern: &str| text.lines().filter(|l| l.contains(pattern)).count();
+    if count("color=red") != matching.len() || count("doublecircle") != n - 2 * matching.len()
+        || count("subgraph cluster_") != clusters.len() {
+        return Some(format!("DOT text does not match {} pairs and {} blossoms:\n{}",
+                            matching.len(), clusters.len(), text));
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("dot_failures").to_string();
+
+    println!("DOT blossoms vs. the Gallai-Edmonds decomposition: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = edge_lists::arbitrary(&mut rng, n);
+        if let Some(why) = check(n, &edges) {
+            failures += 1;
+            let name = format!("dot_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,241 @@
+/*
+ * Randomized test of the shared union-find (dsu.rs) against a naive model
+ *
+ * Runs seeded random operation sequences on Dsu and RollbackDsu next to a
+ * set-id-per-vertex model and checks after every operation:
+ *   - same() and size_of() agree with the model for every vertex,
+ *   - union_into and make_rep leave the requested vertex as the
+ *     representative, union keeps b's, union_by_size the larger set's,
+ *   - RollbackDsu trees stay within log2(set size) of their root, and
+ *     rollback(mark) restores exactly the partition of the snapshot.
+ *
+ * A failing sequence is written to the dump directory, one operation per
+ * line, with the failure as the last line.
+ *
+ * Usage: dsu_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/dsu.rs"]
+mod dsu;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* Naive partition: set id per vertex. */
+#[derive(Clone)]
+struct Model {
+    id: Vec<usize>,
+}
+
+impl Model {
+    fn new(n: usize) -> Self {
+        Model { id: (0..n).collect() }
+    }
+
+    fn join(&mut self, a: usize, b: usize) {
+        let (from, to) = (self.id[a], self.id[b]);
+        for x in self.id.iter_mut() {
+            if *x == from { *x = to; }
+        }
+    }
+
+    fn size(&self, v: usize) -> usize {
+        self.id.iter().filter(|&&x| x == self.id[v]).count()
+    }
+}
+
+fn agree_dsu(d: &mut dsu::Dsu, m: &Model) -> Option<String> {
+    let n = m.id.len();
+    for v in 0..n {
+        if d.size_of(v) != m.size(v) {
+            return Some(format!("size_of({}) = {}, model {}", v, d.size_of(v), m.size(v)));
+        }
+        for w in 0..n {
+            if d.same(v, w) != (m.id[v] == m.id[w]) {
+                return Some(format!("same({}, {}) = {}", v, w, d.same(v, w)));
+            }
+        }
+    }
+    None
+}
+
+fn agree_rollback(d: &dsu::RollbackDsu, m: &Model) -> Option<String> {
+    let n = m.id.len();
+    for v in 0..n {
+        if d.size_of(v) != m.size(v) {
+            return Some(format!("size_of({}) = {}, model {}", v, d.size_of(v), m.size(v)));
+        }
+        let mut depth = 0;
+        let mut x = v;
+        while d.parent(x) != x {
+            x = d.parent(x);
+            depth += 1;
+        }
+        if (1usize << depth) > m.size(v) {
+            return Some(format!("{} is {} links below its root in a set of {}", v, depth, m.size(v)));
+        }
+        for w in 0..n {
+            if d.same(v, w) != (m.id[v] == m.id[w]) {
+                return Some(format!("same({}, {}) = {}", v, w, d.same(v, w)));
+            }
+        }
+    }
+    None
+}
+
+fn check_dsu(rng: &mut rng::Rng, n: usize, ops: usize, log: &mut Vec<String>) -> Option<String> {
+    let mut d = dsu::Dsu::new(n);
+    let mut m = Model::new(n);
+    for _ in 0..ops {
+        let a = rng.below(n as u64) as usize;
+        let b = rng.below(n as u64) as usize;
+        match rng.below(6) {
+            0 => {
+                log.push(format!("union {} {}", a, b));
+                let want = d.find(b);
+                let r = d.union(a, b);
+                m.join(a, b);
+                if r != want || d.find(a) != want {
+                    return Some(format!("union returned {}, find({}) = {}, b's root was {}", r, a, d.find(a), want));
+                }
+            }
+            1 => {
+                log.push(format!("union_into {} {}", a, b));
+                d.union_into(a, b);
+                m.join(a, b);
+                if d.find(a) != b {
+                    return Some(format!("find({}) = {} after union_into", a, d.find(a)));
+                }
+            }
+            2 => {
+                log.push(format!("union_by_size {} {}", a, b));
+                let (ra, rb) = (d.find(a), d.find(b));
+                let want = if ra != rb && d.size_of(a) > d.size_of(b) { ra } else { rb };
+                let r = d.union_by_size(a, b);
+                m.join(a, b);
+                if r != want || d.find(a) != want {
+                    return Some(format!("union_by_size returned {}, expected {}", r, want));
+                }
+            }
+            3 => {
+                log.push(format!("make_rep {}", a));
+                d.make_rep(a);
+                if d.find(a) != a {
+                    return Some(format!("find({}) = {} after make_rep", a, d.find(a)));
+                }
+            }
+            4 => {
+                /* detach a whole set, the only sound use */
+                log.push(format!("detach set of {}", a));
+                let members: Vec<usize> = (0..n).filter(|&v| m.id[v] == m.id[a]).collect();
+                for &v in &members {
+                    d.detach(v);
+                    m.id[v] = n + v;
+                }
+            }
+            _ => {
+                if rng.below(8) == 0 {
+                    log.push("reset".to_string());
+                    d.reset();
+                    m = Model::new(n);
+                }
+            }
+        }
+        if let Some(why) = agree_dsu(&mut d, &m) {
+            return Some(why);
+        }
+    }
+    None
+}
+
+fn check_rollback(rng: &mut rng::Rng, n: usize, ops: usize, log: &mut Vec<String>) -> Option<String> {
+    let mut d = dsu::RollbackDsu::new(n);
+    let mut m = Model::new(n);
+    let mut marks: Vec<(usize, Model)> = Vec::new();
+    for _ in 0..ops {
+        match rng.below(5) {
+            0 => {
+                log.push("snapshot".to_string());
+                marks.push((d.snapshot(), m.clone()));
+            }
+            1 if !marks.is_empty() => {
+                let k = rng.below(marks.len() as u64) as usize;
+                log.push(format!("rollback to snapshot {}", k));
+                marks.truncate(k + 1);
+                let (mark, saved) = marks.pop().unwrap();
+                d.rollback(mark);
+                m = saved;
+            }
+            _ => {
+                let a = rng.below(n as u64) as usize;
+                let b = rng.below(n as u64) as usize;
+                log.push(format!("union {} {}", a, b));
+                let joined = d.union(a, b);
+                if joined == (m.id[a] == m.id[b]) {
+                    return Some(format!("union returned {}", joined));
+                }
+                m.join(a, b);
+            }
+        }
+        if let Some(why) = agree_rollback(&d, &m) {
+            return Some(why);
+        }
+    }
+    None
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, log: &[String], why: &str) -> io::Result<PathBuf> {
+    let lines = Some(format!("n {}", n)).into_iter().chain(log.iter().cloned()).chain(Some(format!("FAIL {}", why)));
+    dump::lines(dir, &format!("{}.txt", name), lines)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 24);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("dsu_failures").to_string();
+    if max_n == 0 {
+        eprintln!("Error: --max-n must be at least 1");
+        std::process::exit(1);
+    }
+
+    println!("Union-find vs. naive partition: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = 1 + rng.below(max_n as u64) as usize;
+        let ops = 1 + rng.below(3 * n as u64) as usize;
+        let rollback = case % 2 == 1;
+        let mut log = Vec::new();
+        let result = if rollback {
+            check_rollback(&mut rng, n, ops, &mut log)
+        } else {
+            check_dsu(&mut rng, n, ops, &mut log)
+        };
+        if let Some(why) = result {
+            failures += 1;
+            let name = format!("dsu_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, n, &log, &why));
+            println!("  FAIL  case {:<5} n={} ops={} ({}): {}", case, n, log.len(),
+                     if rollback { "rollback" } else { "dsu" }, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: dsu, rollback)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * Randomized test of the edge dominating set approximation against
+ * exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges) and checks edge_dominating.rs from the greedy maximal and
+ * from a maximum matching, with and without the improvement rounds:
+ *   - check_edge_dominating accepts the set,
+ *   - its size is at least the optimum and at most twice it, where the
+ *     optimum is the least |C| - nu(G[C]) over vertex covers C, found here
+ *     by a dynamic program over vertex subsets,
+ *   - the reported lower bound is at most the optimum,
+ *   - the improvement never returns a larger set than its start.
+ * The matchers rotate through the general algorithms.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi edgedom`.
+ *
+ * Usage: edge_dominating_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/edge_dominating.rs"]
+mod edge_dominating;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* Fewest edges dominating (n, edges). */
+fn exhaustive(n: usize, edges: &[(usize, usize)]) -> usize {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    /* nu[s]: maximum matching of G[s] */
+    let mut nu = vec![0usize; 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut best = nu[rest];
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            best = best.max(1 + nu[rest & !(1 << w)]);
+        }
+        nu[s] = best;
+    }
+    let mut opt = usize::MAX;
+    for (c, &nu_c) in nu.iter().enumerate() {
+        let covers = edges.iter().all(|&(u, v)| u == v || (c >> u | c >> v) & 1 == 1);
+        if covers {
+            opt = opt.min(c.count_ones() as usize - nu_c);
+        }
+    }
+    opt
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str) -> Option<String> {
+    let opt = exhaustive(n, edges);
+    for &maximum in &[false, true] {
+        let mut start_size = 0;
+        for &improve in &[false, true] {
+            let what = format!("{}{}", if maximum { "maximum" } else { "maximal" }, if improve { " + improve" } else { "" });
+            let r = match edge_dominating::edge_dominating_set(algo, n, edges, maximum, improve) {
+                Ok(r) => r,
+                Err(e) => return Some(format!("{}: {}", what, e)),
+            };
+            let errors = edge_dominating::check_edge_dominating(n, edges, &r.edges);
+            if !errors.is_empty() {
+                return Some(format!("{}: {:?}: {:?}", what, r.edges, errors));
+            }
+            let size = r.edges.len();
+            if size < opt || size > 2 * opt {
+                return Some(format!("{}: {} edges, optimum {}", what, size, opt));
+            }
+            if r.lower_bound > opt {
+                return Some(format!("{}: lower bound {} above the optimum {}", what, r.lower_bound, opt));
+            }
+            if !improve {
+                start_size = size;
+            } else if size > start_size {
+                return Some(format!("{}: improved to {} edges from {}", what, size, start_size));
+            }
+        }
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.7;
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("edge_dominating_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Edge dominating set vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, algo) {
+            failures += 1;
+            let name = format!("edgedom_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}): {}", case, n, edges.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x 4 variants)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
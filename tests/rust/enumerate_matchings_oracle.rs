@@ -0,0 +1,232 @@
+/*
+ * Randomized test of the maximum matching enumeration against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges, in any order and orientation), takes a maximum matching
+ * from one of the general matchers, and checks enumerate_matchings.rs as
+ * `combi enumerate` uses it:
+ *   - the first matching listed is the matcher's, each one is sorted, each
+ *     pair u < v, and each is a matching of the graph of the same size,
+ *   - no matching is listed twice, and the set listed is every maximum
+ *     matching, from a search over all matchings (the lowest vertex left is
+ *     left out or matched to a neighbour),
+ *   - the walk reports itself exhausted, and with a limit below the total
+ *     it lists the first matchings of the full run and stops, not
+ *     exhausted.
+ * Every fourth case is instead a sparse graph of up to 5 x --max-n
+ * vertices (average degree about 3, where nested blossoms show up), whose
+ * matchings are counted by count_matchings.rs instead and listed up to
+ * 2000 of them.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi enumerate <name>.txt --show`.
+ *
+ * Usage: enumerate_matchings_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::collections::HashSet;
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/count_matchings.rs"]
+mod count_matchings;
+#[path = "../../algorithms/common/rust/dsu.rs"]
+mod dsu;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/enumerate_matchings.rs"]
+mod enumerate_matchings;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/tie_break.rs"]
+mod tie_break;
+
+use enumerate_matchings::MaximumMatchings;
+
+/* Largest number of matchings listed for a sparse case. */
+const SPARSE_LIMIT: usize = 2000;
+
+/* Every matching of the graph on the vertices of `left`, onto `current`,
+ * keeping the largest ones in `best`. */
+fn search(adj: &[Vec<usize>], left: &mut Vec<bool>, current: &mut Vec<(usize, usize)>,
+          best: &mut Vec<Vec<(usize, usize)>>) {
+    let v = match (0..adj.len()).find(|&v| left[v]) {
+        Some(v) => v,
+        None => {
+            if best.first().is_none_or(|b| current.len() > b.len()) {
+                best.clear();
+            }
+            if best.first().is_none_or(|b| current.len() == b.len()) {
+                let mut m = current.clone();
+                m.sort_unstable();
+                best.push(m);
+            }
+            return;
+        }
+    };
+    left[v] = false;
+    search(adj, left, current, best);
+    for &w in &adj[v] {
+        if left[w] {
+            left[w] = false;
+            current.push((v, w));
+            search(adj, left, current, best);
+            current.pop();
+            left[w] = true;
+        }
+    }
+    left[v] = true;
+}
+
+/* All maximum matchings of (n, edges), as sorted pair lists, u < v. */
+fn exhaustive_all(n: usize, edges: &[(usize, usize)]) -> Vec<Vec<(usize, usize)>> {
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u.min(v)].push(u.max(v));
+        }
+    }
+    for a in &mut adj {
+        a.sort_unstable();
+        a.dedup();
+    }
+    let mut best = Vec::new();
+    search(&adj, &mut vec![true; n], &mut Vec::new(), &mut best);
+    best
+}
+
+/* What is wrong with the matchings listed from `matching`, if anything. */
+fn check_listed(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)], listed: &[Vec<(usize, usize)>])
+                -> Option<String> {
+    if listed.first().map(|m| &m[..]) != Some(matching) {
+        return Some(format!("first listed {:?}, the matcher found {:?}", listed.first(), matching));
+    }
+    let graph: HashSet<(usize, usize)> = edges.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    let mut seen = HashSet::new();
+    for m in listed {
+        let mut used = vec![false; n];
+        for &(u, v) in m {
+            if u >= v || used[u] || used[v] || !graph.contains(&(u, v)) {
+                return Some(format!("({}, {}) of {:?} is not a free edge (u < v) of the graph", u, v, m));
+            }
+            used[u] = true;
+            used[v] = true;
+        }
+        let mut sorted = m.clone();
+        sorted.sort_unstable();
+        if &sorted != m || m.len() != matching.len() {
+            return Some(format!("{:?} is not sorted, or not of size {}", m, matching.len()));
+        }
+        if !seen.insert(m.clone()) {
+            return Some(format!("{:?} listed twice", m));
+        }
+    }
+    None
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str, sparse: bool, limit: usize) -> Option<String> {
+    let matching = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    let cap = if sparse { SPARSE_LIMIT } else { usize::MAX };
+    let mut all = MaximumMatchings::new(n, edges, &matching, cap);
+    let listed: Vec<Vec<(usize, usize)>> = all.by_ref().collect();
+    if let Some(why) = check_listed(n, edges, &matching, &listed) {
+        return Some(why);
+    }
+    if sparse {
+        match count_matchings::count_maximum_matchings(n, edges) {
+            Ok(c) if c.count < SPARSE_LIMIT as u128 && (c.count != listed.len() as u128 || !all.exhausted()) => {
+                return Some(format!("{} listed (exhausted: {}), count_matchings counts {}", listed.len(),
+                                    all.exhausted(), c.count));
+            }
+            Ok(c) if c.count >= SPARSE_LIMIT as u128 && listed.len() != SPARSE_LIMIT => {
+                return Some(format!("{} listed, count_matchings counts {}", listed.len(), c.count));
+            }
+            _ => {}
+        }
+    } else {
+        if !all.exhausted() {
+            return Some(format!("not exhausted after {} matchings", listed.len()));
+        }
+        let mut expect = exhaustive_all(n, edges);
+        let mut got = listed.clone();
+        expect.sort();
+        got.sort();
+        if got != expect {
+            return Some(format!("listed {} matchings, exhaustive search finds {}: {:?} vs. {:?}", got.len(),
+                                expect.len(), got, expect));
+        }
+    }
+    if limit < listed.len() {
+        let mut some = MaximumMatchings::new(n, edges, &matching, limit);
+        let prefix: Vec<Vec<(usize, usize)>> = some.by_ref().collect();
+        if prefix[..] != listed[..limit] || some.exhausted() {
+            return Some(format!("--limit {}: listed {} matchings (exhausted: {}), not the first of the full run",
+                                limit, prefix.len(), some.exhausted()));
+        }
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, sparse: bool) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = if sparse { 3.0 / n.max(1) as f64 } else { rng.next_f64() * 0.7 };
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push(if rng.below(2) == 0 { (u, v) } else { (v, u) });
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 11);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("enumerate_matchings_failures").to_string();
+    if max_n > 14 {
+        eprintln!("Error: --max-n is at most 14 (the exhaustive search lists every matching)");
+        std::process::exit(1);
+    }
+
+    println!("Maximum matching enumeration vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let sparse = case % 4 == 3;
+        let (n, edges) = random_graph(&mut rng, if sparse { 5 * max_n } else { max_n }, sparse);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        let limit = 1 + rng.below(8) as usize;
+        if let Some(why) = check(n, &edges, algo, sparse, limit) {
+            failures += 1;
+            let name = format!("enumerate_matchings_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}): {}", case, n, edges.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,194 @@
+/*
+ * Randomized test of the --epsilon approximation mode against
+ * brute force
+ *
+ * Generates seeded random small graphs and runs Micali-Vazirani and Gabow
+ * optimized with the longest augmenting path of --epsilon E,
+ * L = 2 ceil(1/E) - 1 edges (args::max_path_length), for E = 1, 1/2, 1/3,
+ * 1/4 and 1/5 in turn:
+ *   - the matching is valid (graph edges, no vertex twice),
+ *   - it has no augmenting path of at most L edges, found here by a
+ *     depth-bounded search over alternating paths,
+ *   - it has at least k/(k+1) of the brute-force maximum, L = 2k - 1,
+ * and without a limit (L past n) both give the maximum.
+ *
+ * A failing instance is written to the dump directory in the "n m"
+ * format, ready for `micali_vazirani_pure --epsilon E`.
+ *
+ * Usage: epsilon_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/brute-force/rust/brute_force.rs"]
+#[allow(dead_code)]
+mod brute_force;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/gabow-optimized/rust/gabow_optimized.rs"]
+#[allow(dead_code)]
+mod gabow_optimized;
+#[path = "../../algorithms/micali-vazirani-pure/rust/micali_vazirani_pure.rs"]
+#[allow(dead_code)]
+mod micali_vazirani_pure;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+const EPSILONS: [f64; 5] = [1.0, 0.5, 1.0 / 3.0, 0.25, 0.2];
+
+fn run(algo: &str, n: usize, edges: &[(usize, usize)], max_path: usize) -> Vec<(usize, usize)> {
+    if algo == "mv-pure" {
+        let mut mv = micali_vazirani_pure::MVGraph::new();
+        mv.build(n, edges);
+        mv.max_path = Some(max_path);
+        mv.max_match();
+        mv.get_matching()
+    } else {
+        let mut g = gabow_optimized::GabowOptimized::new(n, edges);
+        g.max_path = Some(max_path);
+        g.maximum_matching(0)
+    }
+}
+
+/* True if an alternating path of at most `left` more edges leads from v,
+ * reached by a matching edge (or a free start), to a free vertex. */
+fn augmenting_from(adj: &[Vec<usize>], mate: &[Option<usize>], v: usize, left: usize, on_path: &mut Vec<bool>) -> bool {
+    if left == 0 {
+        return false;
+    }
+    for &w in &adj[v] {
+        if on_path[w] || mate[v] == Some(w) {
+            continue;
+        }
+        match mate[w] {
+            None => return true,
+            Some(x) if !on_path[x] && left >= 3 => {
+                on_path[w] = true;
+                on_path[x] = true;
+                let found = augmenting_from(adj, mate, x, left - 2, on_path);
+                on_path[w] = false;
+                on_path[x] = false;
+                if found {
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+fn check(algo: &str, n: usize, edges: &[(usize, usize)], epsilon: f64, maximum: usize) -> Option<String> {
+    let length = args::max_path_length(epsilon);
+    let matching = run(algo, n, edges, length);
+
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    let mut mate = vec![None; n];
+    for &(u, v) in &matching {
+        if u >= n || v >= n || !adj[u].contains(&v) {
+            return Some(format!("({}, {}) is not an edge", u, v));
+        }
+        if mate[u].is_some() || mate[v].is_some() {
+            return Some(format!("({}, {}) reuses a vertex", u, v));
+        }
+        mate[u] = Some(v);
+        mate[v] = Some(u);
+    }
+    let mut on_path = vec![false; n];
+    for s in 0..n {
+        if mate[s].is_none() {
+            on_path[s] = true;
+            let found = augmenting_from(&adj, &mate, s, length, &mut on_path);
+            on_path[s] = false;
+            if found {
+                return Some(format!("epsilon {:.3}: an augmenting path of at most {} edges from {} is left",
+                                    epsilon, length, s));
+            }
+        }
+    }
+    let k = length.div_ceil(2);
+    if matching.len() * (k + 1) < maximum * k {
+        return Some(format!("epsilon {:.3}: {} pairs, below {}/{} of the maximum {}",
+                            epsilon, matching.len(), k, k + 1, maximum));
+    }
+    let exact = run(algo, n, edges, 2 * n + 1).len();
+    if exact != maximum {
+        return Some(format!("no limit: {} pairs, maximum {}", exact, maximum));
+    }
+    None
+}
+
+/* Sparse graphs, so that long shortest augmenting paths are common, with
+ * an odd cycle or two for the blossoms. */
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = 2 + rng.below(max_n as u64 - 1) as usize;
+    let m = rng.below(2 * n as u64) as usize;
+    let mut edges = Vec::new();
+    for _ in 0..m {
+        let u = rng.below(n as u64) as usize;
+        let v = rng.below(n as u64) as usize;
+        if u != v && !edges.contains(&(u, v)) && !edges.contains(&(v, u)) {
+            edges.push((u, v));
+        }
+    }
+    if rng.below(2) == 0 {
+        let len = 3 + 2 * rng.below(3) as usize;
+        for i in 0..len.min(n) {
+            let (u, v) = (i, (i + 1) % len.min(n));
+            if u != v && !edges.contains(&(u, v)) && !edges.contains(&(v, u)) {
+                edges.push((u, v));
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 5000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 24);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("epsilon_failures").to_string();
+    if !(2..=brute_force::MAX_N).contains(&max_n) {
+        eprintln!("Error: --max-n must be between 2 and {}", brute_force::MAX_N);
+        std::process::exit(1);
+    }
+
+    println!("--epsilon vs. brute force: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n);
+        let maximum = brute_force::BruteForce::new(n, &edges).maximum_matching().len();
+        let epsilon = EPSILONS[case % EPSILONS.len()];
+        for algo in ["mv-pure", "gabow-opt"].iter() {
+            if let Some(why) = check(algo, n, &edges, epsilon, maximum) {
+                failures += 1;
+                let name = format!("{}_seed{}_case{}", algo, seed, case);
+                let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+                println!("  FAIL  {:<9} case {:<5} n={} m={}: {}", algo, case, n, edges.len(), why);
+                println!("        saved: {}", saved);
+            }
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x mv-pure, gabow-opt; epsilon 1, 1/2, 1/3, 1/4, 1/5)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
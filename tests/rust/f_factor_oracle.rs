@@ -0,0 +1,153 @@
+/*
+ * End-to-end test of the f-factor reduction (f_factor.rs)
+ *
+ * Generates seeded random small graphs with prescribed degrees, half of
+ * them read off a random edge subset so that a factor exists, solves them
+ * through Tutte's gadget with every general matcher, and checks that a
+ * returned factor is valid and that "no factor" agrees with an exhaustive
+ * search over edge subsets.
+ *
+ * A failing instance is written to the dump directory as an "n m" edge
+ * list plus a degree file, ready for `combi ffactor <graph> --degrees <degrees>`.
+ *
+ * Usage: f_factor_oracle [--cases N] [--seed S] [--max-m M] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/f_factor.rs"]
+mod f_factor;
+
+use f_factor::{FFactor, Outcome};
+
+/* Whether some subset of edges[i..] gives every vertex its `left` degree. */
+fn exhaustive(edges: &[(usize, usize)], left: &mut [usize], i: usize) -> bool {
+    if i == edges.len() {
+        return left.iter().all(|&d| d == 0);
+    }
+    let (u, v) = edges[i];
+    if left[u] > 0 && left[v] > 0 {
+        left[u] -= 1;
+        left[v] -= 1;
+        let found = exhaustive(edges, left, i + 1);
+        left[u] += 1;
+        left[v] += 1;
+        if found { return true; }
+    }
+    exhaustive(edges, left, i + 1)
+}
+
+fn check(algo: &str, ff: &FFactor) -> Option<String> {
+    let (outcome, gadget_size) = match f_factor::f_factor(algo, ff) {
+        Ok(r) => r,
+        Err(e) => return Some(e),
+    };
+    let exists = exhaustive(&ff.edges, &mut ff.f.clone(), 0);
+    match outcome {
+        Outcome::Factor(factor) => {
+            if let Some(e) = f_factor::check_f_factor(ff, &factor).into_iter().next() {
+                return Some(e);
+            }
+            if 2 * gadget_size != ff.gadget_n {
+                return Some(format!("factor from a gadget matching of {} on {} nodes", gadget_size, ff.gadget_n));
+            }
+            if !exists {
+                return Some("factor found but the oracle finds none".to_string());
+            }
+        }
+        Outcome::Infeasible(why) => {
+            if exists {
+                return Some(format!("no factor ({}) but the oracle finds one", why));
+            }
+        }
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_m: usize) -> (usize, Vec<(usize, usize)>, Vec<usize>) {
+    let n = 1 + rng.below(9) as usize;
+    let m = rng.below(max_m as u64 + 1) as usize;
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let u = rng.below(n as u64) as usize;
+        let v = rng.below(n as u64) as usize;
+        edges.push((u, v)); /* loops and duplicates are part of the test */
+    }
+    let mut f = vec![0usize; n];
+    if rng.below(2) == 0 {
+        /* planted: the degrees of a random simple subgraph */
+        let mut kept: Vec<(usize, usize)> = edges.iter()
+            .filter(|&&(u, v)| u != v)
+            .map(|&(u, v)| (u.min(v), u.max(v)))
+            .collect();
+        kept.sort_unstable();
+        kept.dedup();
+        for &(u, v) in &kept {
+            if rng.below(2) == 0 {
+                f[u] += 1;
+                f[v] += 1;
+            }
+        }
+    } else {
+        for d in &mut f { *d = rng.below(4) as usize; }
+    }
+    (n, edges, f)
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, edges: &[(usize, usize)], f: &[usize]) -> io::Result<PathBuf> {
+    let graph = dump::edge_list(dir, &format!("{}.txt", name), n, edges)?;
+    dump::lines(dir, &format!("{}.degrees", name), f.iter().enumerate().map(|(v, d)| format!("{} {}", v, d)))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_m: usize = args::parse_or_exit(&args, "--max-m", 18);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("f_factor_failures").to_string();
+
+    println!("f-factor reduction vs. exhaustive search: {} cases, seed {}, m <= {}",
+             cases, seed, max_m);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    let mut feasible = 0;
+    for case in 0..cases {
+        let (n, edges, f) = random_instance(&mut rng, max_m);
+        let ff = FFactor::new(n, &edges, &f, 0);
+        if exhaustive(&ff.edges, &mut ff.f.clone(), 0) { feasible += 1; }
+        for algo in matchers::GENERAL_ALGORITHMS.iter() {
+            if let Some(why) = check(algo, &ff) {
+                failures += 1;
+                let name = format!("{}_seed{}_case{}", algo, seed, case);
+                let saved = dump::saved(dump_case(&dump_dir, &name, n, &edges, &f));
+                println!("  FAIL  {:<15} case {:<5} n={} m={}: {}", algo, case, n, edges.len(), why);
+                println!("        saved: {}", saved);
+            }
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x {} matchers, {} with a factor)",
+                 cases, matchers::GENERAL_ALGORITHMS.len(), feasible);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
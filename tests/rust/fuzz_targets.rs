@@ -0,0 +1,1415 @@
+/*
+ * Fuzz targets for the graph loaders and the solvers
+ *
+ * A self-contained mutation fuzzer (no cargo-fuzz / libFuzzer: the suite is
+ * built with plain rustc). Each target runs under catch_unwind; any panic,
+ * or a solver result that is not a valid matching, is a crash.
+ *
+ * Targets:
+ *   loader     arbitrary bytes -> parse_graph (every --on-mismatch policy,
+ *              --strict / --auto-grow / default, the --merge-parallel
+ *              policies and --strict-format in turn),
+ *              then, for small n, every general matcher on the result
+ *   bipartite  arbitrary bytes -> parse_bipartite_graph, then Hopcroft-Karp
+ *   format     valid files with comments, blank lines and CRLF endings
+ *              mixed in -> the same graph as the plain file by default,
+ *              refused by --strict-format (which reads the plain one)
+ *   blocks     arbitrary bytes -> every parse_* function with the input cut
+ *              into blocks of 1 to 32 bytes and lexed on 1 to 4 threads,
+ *              against one block on one thread (every policy in turn);
+ *              every 50th iteration also load_* on a file, mapped
+ *   binary     valid files (general or bipartite, with or without weights)
+ *              -> write_binary -> every parse_* function: the same graph
+ *              as the text; then the binary bytes mutated -> no panic, and
+ *              anything accepted stays within its bounds
+ *   structured valid files (general or bipartite, with or without weights)
+ *              written as JSON and as GraphML, keys, node order, node id
+ *              style, comments and whitespace varied -> every parse_*
+ *              function: the same graph as the text; then the documents
+ *              mutated -> no panic, and anything accepted stays within
+ *              its bounds
+ *   snap       valid files relabeled with increasing ids of up to 44 bits,
+ *              written headerless SNAP style (comment block, tabs or
+ *              spaces, weights) -> every parse_*_named function under
+ *              --format snap: the vertices on an edge renumbered in id
+ *              order, the id table in its InputNames, pairs through
+ *              original_pairs and back through remapped_pairs, an
+ *              unknown id refused; then mutated -> no panic, and anything
+ *              accepted stays within its bounds and has its id table
+ *   labels     valid files with every vertex named (bare names, quoted
+ *              ones with spaces, quotes, backslashes, '#' and '%'),
+ *              written headerless -> every parse_*_named function under
+ *              --format labels: ids in order of first appearance, the
+ *              label table in their InputNames, pairs through
+ *              labeled_pairs, a labeled matching file and back through
+ *              unlabeled_pairs, an unknown label refused; then mutated ->
+ *              no panic, and anything accepted stays within its bounds
+ *              and has its label table
+ *   weights    valid general files with or without a weight column, as
+ *              text with a header or SNAP style (comments mixed in),
+ *              binary and JSON, at times a third field that is no
+ *              integer -> graph_io::has_weights tells which,
+ *              the unweighted loaders read past the weights (and
+ *              --strict-format accepts the third column); then mutated
+ *              -> has_weights does not panic
+ *   edge-ids   multigraphs with many parallel edges (general or bipartite,
+ *              weighted or not) as text with comments, binary or JSON ->
+ *              the parse_*_named functions under --edge-ids: the table
+ *              in their InputNames holds each edge's line (position), a
+ *              greedy matching goes to ids through ids_of (the heaviest
+ *              or lightest parallel edge, the smallest id among equals)
+ *              and back through pairs_of, and through the matching file
+ *              writer and reader; an id past the last edge is refused;
+ *              then mutated -> no panic, and ids increasing
+ *   compressed valid files written through gzip and zstd -> load_graph on
+ *              the .gz / .zst file reads the same graph, and refuses the
+ *              file cut in half (every 100th iteration: the tools are
+ *              separate processes; skipped where they are missing)
+ *   solvers    arbitrary edge lists (ids past n, huge ids, self-loops,
+ *              duplicates) -> every general matcher
+ *   hk         the same for Hopcroft-Karp
+ *   coloring   the same for bipartite edge coloring: every in-range edge
+ *              colored below the maximum degree, no color twice at a vertex
+ *   merge      weighted edge lists with many parallel edges and extreme
+ *              weights -> graph_io::merge_parallel under every policy,
+ *              against a direct fold over the lines
+ *   matchfile  a matcher's (or Hopcroft-Karp's) matching, pairs shuffled
+ *              and flipped -> matching_io::write_matching -> read_matching
+ *              must give the canonical pairs; the text mutated -> the
+ *              reader and check_pairs must not panic
+ *
+ * Inputs are either random bytes or mutations of small valid files. Crash
+ * inputs are written to the dump directory: raw bytes for the loader
+ * targets, "n m" / "left right m" edge-list text for the solver targets.
+ *
+ * Usage: fuzz_targets [--target all|loader|bipartite|format|blocks|binary|compressed|structured|snap|labels|weights|edge-ids|
+ *                               solvers|hk|coloring|merge|matchfile]
+ *                     [--iters N] [--seed S] [--max-n N] [--dump-dir DIR]
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/common/rust/json.rs"]
+mod json;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../algorithms/common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../algorithms/common/rust/verify.rs"]
+mod verify;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/bipartite-edge-coloring/rust/bipartite_edge_coloring.rs"]
+#[allow(dead_code)]
+mod bipartite_edge_coloring;
+
+use error::SuiteError;
+use rng::Rng;
+
+const POLICIES: [&str; 4] = ["error", "warn", "truncate", "accept"];
+const MERGE_POLICIES: [graph_io::MergePolicy; 5] = [
+    graph_io::MergePolicy::Keep, graph_io::MergePolicy::Max, graph_io::MergePolicy::Min,
+    graph_io::MergePolicy::Sum, graph_io::MergePolicy::Error,
+];
+const RANGE_POLICIES: [graph_io::RangePolicy; 3] =
+    [graph_io::RangePolicy::Drop, graph_io::RangePolicy::Error, graph_io::RangePolicy::Grow];
+const FORMAT_POLICIES: [graph_io::FormatPolicy; 2] = [graph_io::FormatPolicy::Tolerant, graph_io::FormatPolicy::Strict];
+
+/* Non-data lines for the format target: comments of each kind, blanks. */
+const NOT_DATA: [&str; 7] = ["# note", "% 3 4", "c 1 2", "c", "", "   ", "\t# 0 1"];
+
+/* Tokens that tend to break hand-written parsers and index arithmetic. */
+const INTERESTING: [&str; 16] = [
+    "0", "1", "-1", "2147483647", "2147483648", "4294967295", "4294967296",
+    "18446744073709551615", "18446744073709551616", "99999999999999999999",
+    "1e3", "0x10", "+5", "\n", "\r\n", "\t",
+];
+
+/* A headerless load of any shape as (left, right, weighted edges) and its
+ * names; right == left for a general graph, weights 0 when unweighted. */
+type Parsed = (usize, usize, Vec<(usize, usize, i64)>, graph_io::InputNames);
+
+struct Fuzzer {
+    rng: Rng,
+    max_n: usize,
+    dump_dir: String,
+    crashes: usize,
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "panic".to_string()
+    }
+}
+
+fn edge_list_text(header: &str, edges: &[(usize, usize)]) -> Vec<u8> {
+    let mut text = format!("{}\n", header);
+    for &(u, v) in edges {
+        text.push_str(&format!("{} {}\n", u, v));
+    }
+    text.into_bytes()
+}
+
+/* What merge_parallel should return: one edge per pair at its first line,
+ * folded in line order; None where it should fail. */
+fn expected_merge(
+    edges: &[(usize, usize, i64)], symmetric: bool, policy: graph_io::MergePolicy,
+) -> Option<Vec<(usize, usize, i64)>> {
+    use graph_io::MergePolicy;
+    if policy == MergePolicy::Keep {
+        return Some(edges.to_vec());
+    }
+    let same = |a: &(usize, usize, i64), b: &(usize, usize, i64)| {
+        (a.0, a.1) == (b.0, b.1) || (symmetric && (a.0, a.1) == (b.1, b.0))
+    };
+    let mut out: Vec<(usize, usize, i64)> = Vec::new();
+    for e in edges {
+        match out.iter_mut().find(|o| same(o, e)) {
+            None => out.push(*e),
+            Some(o) => {
+                o.2 = match policy {
+                    MergePolicy::Max => o.2.max(e.2),
+                    MergePolicy::Min => o.2.min(e.2),
+                    MergePolicy::Sum => o.2.checked_add(e.2)?,
+                    _ => return None,
+                }
+            }
+        }
+    }
+    Some(out)
+}
+
+impl Fuzzer {
+    fn crash(&mut self, target: &str, iter: usize, what: &str, input: &[u8]) {
+        self.crashes += 1;
+        let name = format!("{}_{}.txt", target, iter);
+        let saved = fs::create_dir_all(&self.dump_dir)
+            .and_then(|_| fs::write(Path::new(&self.dump_dir).join(&name), input))
+            .map(|_| format!("{}/{}", self.dump_dir, name))
+            .unwrap_or_else(|e| format!("(not saved: {})", e));
+        println!("  CRASH {:<10} iter {:<7} {}", target, iter, what);
+        println!("        input: {}", saved);
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        self.rng.below(bound as u64) as usize
+    }
+
+    fn valid_file(&mut self, bipartite: bool) -> Vec<u8> {
+        let n = self.below(12);
+        if bipartite {
+            let right = self.below(12);
+            let edges: Vec<(usize, usize)> = (0..self.below(20))
+                .map(|_| (self.below(n.max(1)), self.below(right.max(1))))
+                .collect();
+            edge_list_text(&format!("{} {} {}", n, right, edges.len()), &edges)
+        } else {
+            let edges: Vec<(usize, usize)> = (0..self.below(20))
+                .map(|_| (self.below(n.max(1)), self.below(n.max(1))))
+                .collect();
+            edge_list_text(&format!("{} {}", n, edges.len()), &edges)
+        }
+    }
+
+    fn mutate(&mut self, data: &mut Vec<u8>) {
+        for _ in 0..1 + self.below(4) {
+            let len = data.len();
+            match self.below(6) {
+                0 if len > 0 => {
+                    let i = self.below(len);
+                    data[i] ^= 1 << self.below(8);
+                }
+                1 => {
+                    let i = self.below(len + 1);
+                    let b = self.rng.next_u64() as u8;
+                    data.insert(i, b);
+                }
+                2 if len > 0 => {
+                    let i = self.below(len);
+                    let j = (i + 1 + self.below(8)).min(len);
+                    data.drain(i..j);
+                }
+                3 if len > 0 => {
+                    let i = self.below(len);
+                    let j = (i + 1 + self.below(16)).min(len);
+                    let chunk: Vec<u8> = data[i..j].to_vec();
+                    let at = self.below(len + 1);
+                    for (k, &b) in chunk.iter().enumerate() { data.insert(at + k, b); }
+                }
+                _ => {
+                    let i = self.below(len + 1);
+                    let tok = INTERESTING[self.below(INTERESTING.len())];
+                    for (k, b) in tok.bytes().enumerate() { data.insert(i + k, b); }
+                }
+            }
+        }
+    }
+
+    fn input_bytes(&mut self, bipartite: bool) -> Vec<u8> {
+        if self.below(8) == 0 {
+            (0..self.below(64)).map(|_| self.rng.next_u64() as u8).collect()
+        } else {
+            let mut data = self.valid_file(bipartite);
+            self.mutate(&mut data);
+            data
+        }
+    }
+
+    /* Every general matcher on (n, edges); returns the first failure. */
+    fn run_matchers(&self, n: usize, edges: &[(usize, usize)]) -> Option<String> {
+        let mut size = None;
+        for algo in matchers::GENERAL_ALGORITHMS.iter() {
+            let run = panic::catch_unwind(AssertUnwindSafe(|| matchers::maximum_matching(algo, n, edges)));
+            let matching = match run {
+                Err(p) => return Some(format!("{} panicked: {}", algo, panic_message(p))),
+                Ok(Err(_)) => continue, /* rejected cleanly */
+                Ok(Ok(m)) => m,
+            };
+            if let Some(e) = edge_lists::matching_error(n, edges, &matching) {
+                return Some(format!("{}: {}", algo, e));
+            }
+            match size {
+                None => size = Some(matching.len()),
+                Some(s) if s != matching.len() => {
+                    return Some(format!("{} found {} but an earlier matcher found {}", algo, matching.len(), s));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    fn run_hk(&self, left: usize, right: usize, edges: &[(usize, usize)]) -> Option<String> {
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            hopcroft_karp::HopcroftKarp::new(left, right, edges).maximum_matching(0)
+        }));
+        let matching = match run {
+            Err(p) => return Some(format!("hopcroft-karp panicked: {}", panic_message(p))),
+            Ok(m) => m,
+        };
+        let mut used_l = vec![false; left];
+        let mut used_r = vec![false; right];
+        for &(u, v) in &matching {
+            if u >= left || v >= right || !edges.contains(&(u, v)) || used_l[u] || used_r[v] {
+                return Some(format!("hopcroft-karp: bad pair ({}, {})", u, v));
+            }
+            used_l[u] = true;
+            used_r[v] = true;
+        }
+        None
+    }
+
+    fn run_coloring(&self, left: usize, right: usize, edges: &[(usize, usize)]) -> Option<String> {
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            bipartite_edge_coloring::color_edges(left, right, edges)
+        }));
+        let ec = match run {
+            Err(p) => return Some(format!("edge coloring panicked: {}", panic_message(p))),
+            Ok(ec) => ec,
+        };
+        let mut deg = vec![0usize; left + right];
+        let mut seen = Vec::new();
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            if u >= left || v >= right { continue; }
+            deg[u] += 1;
+            deg[left + v] += 1;
+            let c = ec.colors[k];
+            if c >= ec.delta {
+                return Some(format!("edge coloring: edge ({}, {}) got color {}", u, v, c));
+            }
+            seen.push((u, c));
+            seen.push((left + v, c));
+        }
+        if deg.iter().cloned().max().unwrap_or(0) != ec.delta {
+            return Some(format!("edge coloring: {} colors for maximum degree {}",
+                                ec.delta, deg.iter().cloned().max().unwrap_or(0)));
+        }
+        seen.sort_unstable();
+        if let Some(w) = seen.windows(2).find(|w| w[0] == w[1]) {
+            return Some(format!("edge coloring: color {} twice at vertex {}", w[0].1, w[0].0));
+        }
+        None
+    }
+
+    fn loader(&mut self, iter: usize) {
+        let data = self.input_bytes(false);
+        for policy in POLICIES.iter() {
+            let opts = graph_io::LoadOptions {
+                on_mismatch: graph_io::MismatchPolicy::parse(policy).unwrap(),
+                out_of_range: RANGE_POLICIES[iter % RANGE_POLICIES.len()],
+                on_parallel: MERGE_POLICIES[iter / RANGE_POLICIES.len() % MERGE_POLICIES.len()],
+                format: FORMAT_POLICIES[iter / (RANGE_POLICIES.len() * MERGE_POLICIES.len()) % FORMAT_POLICIES.len()],
+                ..Default::default()
+            };
+            let run = panic::catch_unwind(AssertUnwindSafe(|| graph_io::parse_graph(&data[..], &opts)));
+            let what = match run {
+                Err(p) => Some(format!("parse_graph ({}) panicked: {}", policy, panic_message(p))),
+                Ok(Ok((n, ref edges))) if n <= self.max_n => self.run_matchers(n, edges),
+                Ok(_) => None,
+            };
+            if let Some(w) = what {
+                self.crash("loader", iter, &w, &data);
+                return;
+            }
+        }
+    }
+
+    fn bipartite(&mut self, iter: usize) {
+        let data = self.input_bytes(true);
+        for policy in POLICIES.iter() {
+            let opts = graph_io::LoadOptions {
+                on_mismatch: graph_io::MismatchPolicy::parse(policy).unwrap(),
+                out_of_range: RANGE_POLICIES[iter % RANGE_POLICIES.len()],
+                on_parallel: MERGE_POLICIES[iter / RANGE_POLICIES.len() % MERGE_POLICIES.len()],
+                format: FORMAT_POLICIES[iter / (RANGE_POLICIES.len() * MERGE_POLICIES.len()) % FORMAT_POLICIES.len()],
+                ..Default::default()
+            };
+            let run = panic::catch_unwind(AssertUnwindSafe(|| graph_io::parse_bipartite_graph(&data[..], &opts)));
+            let what = match run {
+                Err(p) => Some(format!("parse_bipartite_graph ({}) panicked: {}", policy, panic_message(p))),
+                Ok(Ok((l, r, ref edges))) if l <= self.max_n && r <= self.max_n => self.run_hk(l, r, edges),
+                Ok(_) => None,
+            };
+            if let Some(w) = what {
+                self.crash("bipartite", iter, &w, &data);
+                return;
+            }
+        }
+    }
+
+    /* A valid file (general on even iterations, bipartite on odd ones)
+     * with non-data lines, trailing comments and CRLF endings mixed in. */
+    fn format(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let plain = self.valid_file(bipartite);
+        let mut decorated = Vec::new();
+        let mut added = 0;
+        for line in plain.split_inclusive(|&b| b == b'\n') {
+            while self.below(4) == 0 {
+                decorated.extend_from_slice(NOT_DATA[self.below(NOT_DATA.len())].as_bytes());
+                decorated.push(b'\n');
+                added += 1;
+            }
+            decorated.extend_from_slice(&line[..line.len() - 1]);
+            if self.below(3) == 0 {
+                decorated.extend_from_slice([&b" # 5 6"[..], b"%"][self.below(2)]);
+                added += 1;
+            }
+            if self.below(4) == 0 {
+                decorated.push(b'\r');
+                added += 1;
+            }
+            decorated.push(b'\n');
+        }
+
+        let parse = |data: &[u8], format: graph_io::FormatPolicy| -> Result<graph_io::BipartiteGraph, String> {
+            let opts = graph_io::LoadOptions { format, ..Default::default() };
+            if bipartite {
+                graph_io::parse_bipartite_graph(data, &opts).map_err(|e| e.to_string())
+            } else {
+                graph_io::parse_graph(data, &opts).map(|(n, edges)| (n, n, edges)).map_err(|e| e.to_string())
+            }
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let expected = parse(&plain, graph_io::FormatPolicy::Tolerant);
+            if parse(&plain, graph_io::FormatPolicy::Strict) != expected {
+                return Some("--strict-format reads the plain file differently".to_string());
+            }
+            if parse(&decorated, graph_io::FormatPolicy::Tolerant) != expected {
+                return Some(format!("comments and blank lines change the graph (expected {:?})", expected));
+            }
+            if added > 0 && parse(&decorated, graph_io::FormatPolicy::Strict).is_ok() {
+                return Some(format!("--strict-format accepts {} non-data line(s) or comment(s)", added));
+            }
+            None
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("format", iter, &w, &decorated);
+        }
+    }
+
+    /* The same input through every loader, read whole and read in small
+     * blocks on several threads: results and errors must not change. */
+    fn blocks(&mut self, iter: usize) {
+        let data = self.input_bytes(iter % 2 == 1);
+        let whole = graph_io::LoadOptions {
+            on_mismatch: graph_io::MismatchPolicy::parse(POLICIES[iter % POLICIES.len()]).unwrap(),
+            out_of_range: RANGE_POLICIES[iter / POLICIES.len() % RANGE_POLICIES.len()],
+            format: FORMAT_POLICIES[iter / (POLICIES.len() * RANGE_POLICIES.len()) % FORMAT_POLICIES.len()],
+            threads: 1,
+            ..Default::default()
+        };
+        let cut = graph_io::LoadOptions { block_bytes: 1 + self.below(32), threads: 1 + self.below(4), ..whole.clone() };
+        let file = if iter.is_multiple_of(50) {
+            let path = env::temp_dir().join(format!("fuzz_blocks_{}.txt", std::process::id()));
+            fs::write(&path, &data).ok().map(|_| path)
+        } else {
+            None
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let all = |opts: &graph_io::LoadOptions| -> Vec<String> {
+                let mut out = vec![
+                    format!("{:?}", graph_io::parse_graph(&data[..], opts).map_err(|e| e.to_string())),
+                    format!("{:?}", graph_io::parse_weighted_graph(&data[..], opts).map_err(|e| e.to_string())),
+                    format!("{:?}", graph_io::parse_bipartite_graph(&data[..], opts).map_err(|e| e.to_string())),
+                    format!("{:?}", graph_io::parse_weighted_bipartite_graph(&data[..], opts).map_err(|e| e.to_string())),
+                ];
+                if let Some(path) = file.as_ref().and_then(|p| p.to_str()) {
+                    out.push(format!("{:?}", graph_io::load_graph(path, opts).map_err(|e| e.to_string())));
+                    out.push(format!("{:?}", graph_io::load_weighted_graph(path, opts).map_err(|e| e.to_string())));
+                    out.push(format!("{:?}", graph_io::load_bipartite_graph(path, opts).map_err(|e| e.to_string())));
+                    out.push(format!("{:?}", graph_io::load_weighted_bipartite_graph(path, opts)
+                        .map_err(|e| e.to_string())));
+                }
+                out
+            };
+            let (expected, got) = (all(&whole), all(&cut));
+            let file_differs = expected.len() > 4 && expected[..4] != expected[4..];
+            match expected.iter().zip(&got).position(|(a, b)| a != b) {
+                Some(i) => Some(format!("loader {} with {}-byte blocks on {} threads: {} instead of {}",
+                                        i, cut.block_bytes, cut.threads, got[i], expected[i])),
+                None if file_differs => Some(format!("mapped file read differently: {:?}", expected)),
+                None => None,
+            }
+        }));
+        if let Some(path) = file { let _ = fs::remove_file(path); }
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("blocks", iter, &w, &data);
+        }
+    }
+
+    /* A valid file written in the binary format reads back as the same
+     * graph; mutated, it is refused or read within bounds, never a panic. */
+    fn binary(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let plain = self.valid_file(bipartite);
+        let weights: Vec<i64> = (0..plain.len()).map(|_| self.rng.next_u64() as i64 >> self.below(64)).collect();
+        let opts = graph_io::LoadOptions::default();
+        let (left, right, edges) = if bipartite {
+            let (l, r, e) = graph_io::parse_bipartite_graph(&plain[..], &opts).unwrap();
+            (l, Some(r), e)
+        } else {
+            let (n, e) = graph_io::parse_graph(&plain[..], &opts).unwrap();
+            (n, None, e)
+        };
+        let weighted = iter % 4 >= 2;
+        let weights = if weighted { Some(&weights[..edges.len()]) } else { None };
+        let mut data = Vec::new();
+        graph_io::write_binary(&mut data, left, right, &edges, weights).unwrap();
+        let read = |data: &[u8]| -> Result<graph_io::WeightedBipartiteGraph, String> {
+            let e = |e: SuiteError| e.to_string();
+            let zero = |es: Vec<(usize, usize)>| es.into_iter().map(|(u, v)| (u, v, 0)).collect();
+            match (bipartite, weighted) {
+                (false, false) => graph_io::parse_graph(data, &opts).map(|(n, es)| (n, n, zero(es))).map_err(e),
+                (false, true) => graph_io::parse_weighted_graph(data, &opts).map(|(n, es)| (n, n, es)).map_err(e),
+                (true, false) => graph_io::parse_bipartite_graph(data, &opts).map(|(l, r, es)| (l, r, zero(es))).map_err(e),
+                (true, true) => graph_io::parse_weighted_bipartite_graph(data, &opts).map_err(e),
+            }
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let expected: Vec<(usize, usize, i64)> = edges.iter().enumerate()
+                .map(|(k, &(u, v))| (u, v, weights.map_or(0, |ws| ws[k])))
+                .collect();
+            let (l, r) = (left, right.unwrap_or(left));
+            if read(&data) != Ok((l, r, expected)) {
+                return Some(format!("binary file read back as {:?}", read(&data)));
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            match read(&bad) {
+                Ok((l, r, ref es)) if es.iter().any(|&(u, v, _)| u >= l || v >= r) => {
+                    Some(format!("mutated binary file accepted with an edge out of range: {:?}", es))
+                }
+                _ => None,
+            }
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("binary", iter, &w, &data);
+        }
+    }
+
+    /* Blanks between JSON tokens and XML tags: none, spaces or newlines. */
+    fn blank(&mut self) -> &'static str {
+        ["", "", " ", "\n", "\n  ", "\t"][self.below(6)]
+    }
+
+    fn json_document(&mut self, left: usize, right: Option<usize>, edges: &[(usize, usize)], weights: Option<&[i64]>) -> String {
+        let mut parts = match right {
+            Some(r) => vec![format!("\"left\": {}", left), format!("\"right\": {}", r)],
+            None => vec![format!("\"n\": {}", left)],
+        };
+        if self.below(3) == 0 {
+            parts.push(format!("\"m\": {}", edges.len()));
+        }
+        let mut list = Vec::new();
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let b = self.blank();
+            list.push(match weights {
+                Some(ws) if ws[k].abs() < 1 << 50 && self.below(2) == 0 => format!("{}[{}, {}, {}.0]", b, u, v, ws[k]),
+                Some(ws) => format!("{}[{},{},{}]", b, u, v, ws[k]),
+                None => format!("{}[{}, {}]", b, u, v),
+            });
+        }
+        parts.push(format!("\"edges\":{}[{}]", self.blank(), list.join(",")));
+        for extra in ["\"directed\": false", "\"graph\": {\"name\": \"a ]} \\\" b\", \"list\": [1, [2, {}], -3.5e2, null]}"].iter() {
+            if self.below(2) == 0 {
+                parts.push(extra.to_string());
+            }
+        }
+        for i in (1..parts.len()).rev() {
+            let j = self.below(i + 1);
+            parts.swap(i, j);
+        }
+        let sep = format!(",{}", self.blank());
+        format!("{}{{{}{}}}{}", self.blank(), self.blank(), parts.join(&sep), self.blank())
+    }
+
+    /* GraphML with numeric node ids ("7" or "n7", nodes in any order) or
+     * names (in vertex order); a bipartite graph's sides interleaved, each
+     * in order, and its edges written either way round. */
+    fn graphml_document(&mut self, left: usize, right: Option<usize>, edges: &[(usize, usize)], weights: Option<&[i64]>) -> String {
+        let style = self.below(3);
+        let name = |side: usize, x: usize| match (right.is_some(), style) {
+            (false, 0) => x.to_string(),
+            (false, 1) => format!("n{}", x),
+            (false, _) => format!("v_{}", x),
+            (true, _) => format!("{}{}", ["a", "b"][side], x),
+        };
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out += "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n";
+        out += "<key id=\"w\" for=\"edge\" attr.name=\"weight\" attr.type=\"long\"/>\n";
+        out += "<key id=\"s\" for=\"node\" attr.name=\"bipartite\" attr.type=\"int\"/>\n";
+        out += "<key id=\"c\" for=\"node\" attr.name=\"color\" attr.type=\"string\"/>\n";
+        out += &format!("<!-- {} vertices --><graph id=\"G\" edgedefault=\"undirected\">{}", left, self.blank());
+        let mut nodes: Vec<(usize, usize)> = (0..left).map(|x| (0, x)).collect();
+        match right {
+            Some(r) => {
+                nodes.clear();
+                let (mut a, mut b) = (0, 0);
+                while a < left || b < r {
+                    if b == r || (a < left && self.below(2) == 0) {
+                        nodes.push((0, a));
+                        a += 1;
+                    } else {
+                        nodes.push((1, b));
+                        b += 1;
+                    }
+                }
+            }
+            None if style < 2 => {
+                for i in (1..nodes.len()).rev() {
+                    let j = self.below(i + 1);
+                    nodes.swap(i, j);
+                }
+            }
+            None => {}
+        }
+        for (side, x) in nodes {
+            let b = self.blank();
+            match (right, self.below(3)) {
+                (Some(_), _) => out += &format!("<node id=\"{}\"><data key=\"s\">{}</data></node>{}", name(side, x), side, b),
+                (None, 0) => out += &format!("<node id=\"{}\"><data key=\"c\">red &amp; &lt;b&gt;</data></node>{}", name(0, x), b),
+                (None, _) => out += &format!("<node id='{}'/>{}", name(0, x), b),
+            }
+        }
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let (mut s, mut t) = (name(0, u), name(1, v));
+            if right.is_none() {
+                t = name(0, v);
+            } else if self.below(2) == 0 {
+                std::mem::swap(&mut s, &mut t);
+            }
+            match weights {
+                Some(ws) => out += &format!("<edge source=\"{}\" target=\"{}\">\n  <data key=\"w\">{}</data>\n</edge>{}",
+                                            s, t, ws[k], self.blank()),
+                None => out += &format!("<edge source=\"{}\" target=\"{}\"/>{}", s, t, self.blank()),
+            }
+        }
+        out + "</graph>\n</graphml>\n"
+    }
+
+    fn structured(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let weighted = iter % 4 >= 2;
+        let plain = self.valid_file(bipartite);
+        let opts = graph_io::LoadOptions::default();
+        let (left, right, edges) = if bipartite {
+            let (l, r, e) = graph_io::parse_bipartite_graph(&plain[..], &opts).unwrap();
+            (l, Some(r), e)
+        } else {
+            let (n, e) = graph_io::parse_graph(&plain[..], &opts).unwrap();
+            (n, None, e)
+        };
+        let weights: Vec<i64> = (0..edges.len()).map(|_| self.rng.next_u64() as i64 >> self.below(64)).collect();
+        let weights = if weighted { Some(&weights[..]) } else { None };
+        let data = if iter % 8 < 4 {
+            self.json_document(left, right, &edges, weights)
+        } else {
+            self.graphml_document(left, right, &edges, weights)
+        }.into_bytes();
+        let read = |data: &[u8]| -> Result<graph_io::WeightedBipartiteGraph, String> {
+            let e = |e: SuiteError| e.to_string();
+            let zero = |es: Vec<(usize, usize)>| es.into_iter().map(|(u, v)| (u, v, 0)).collect();
+            match (bipartite, weighted) {
+                (false, false) => graph_io::parse_graph(data, &opts).map(|(n, es)| (n, n, zero(es))).map_err(e),
+                (false, true) => graph_io::parse_weighted_graph(data, &opts).map(|(n, es)| (n, n, es)).map_err(e),
+                (true, false) => graph_io::parse_bipartite_graph(data, &opts).map(|(l, r, es)| (l, r, zero(es))).map_err(e),
+                (true, true) => graph_io::parse_weighted_bipartite_graph(data, &opts).map_err(e),
+            }
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let expected: Vec<(usize, usize, i64)> = edges.iter().enumerate()
+                .map(|(k, &(u, v))| (u, v, weights.map_or(0, |ws| ws[k])))
+                .collect();
+            let (l, r) = (left, right.unwrap_or(left));
+            /* named GraphML nodes number only the vertices written, so n is exact */
+            let got = read(&data);
+            if got != Ok((l, r, expected)) {
+                return Some(format!("document read back as {:?}", got));
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            match read(&bad) {
+                Ok((l, r, ref es)) if es.iter().any(|&(u, v, _)| u >= l || v >= r) => {
+                    Some(format!("mutated document accepted with an edge out of range: {:?}", es))
+                }
+                _ => None,
+            }
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("structured", iter, &w, &data);
+        }
+    }
+
+    /* Increasing ids with gaps of up to 2^40, from anywhere below 2^40. */
+    fn sparse_ids(&mut self, count: usize) -> Vec<usize> {
+        let mut next = self.rng.next_u64() as usize >> 24;
+        (0..count).map(|_| {
+            next += 1 + (self.rng.next_u64() as usize >> (24 + self.below(40)));
+            next
+        }).collect()
+    }
+
+    fn snap(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let weighted = iter % 4 >= 2;
+        let plain = self.valid_file(bipartite);
+        let opts = graph_io::LoadOptions::default();
+        let (left, right, edges) = if bipartite {
+            let (l, r, e) = graph_io::parse_bipartite_graph(&plain[..], &opts).unwrap();
+            (l, r, e)
+        } else {
+            let (n, e) = graph_io::parse_graph(&plain[..], &opts).unwrap();
+            (n, n, e)
+        };
+        let weights: Vec<i64> = (0..edges.len()).map(|_| self.rng.next_u64() as i64 >> self.below(64)).collect();
+        let left_ids = self.sparse_ids(left);
+        let right_ids = if bipartite { self.sparse_ids(right) } else { left_ids.clone() };
+
+        /* headerless, with SNAP's comment block, tabs or spaces */
+        let mut text = format!("# Undirected graph: fuzz_{}.txt\n# Nodes: {} Edges: {}\n# FromNodeId\tToNodeId\n",
+                               iter, left, edges.len());
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let sep = ["\t", " ", "  "][self.below(3)];
+            text += &format!("{}{}{}", left_ids[u], sep, right_ids[v]);
+            if weighted {
+                text += &format!("{}{}", sep, weights[k]);
+            }
+            text += ["\n", "\r\n"][self.below(2)];
+            if self.below(8) == 0 {
+                text += NOT_DATA[self.below(NOT_DATA.len())];
+                text += "\n";
+            }
+        }
+        let data = text.into_bytes();
+
+        /* the vertices on an edge, renumbered in id order */
+        let used = |side: usize| -> Vec<usize> {
+            let mut vs: Vec<usize> = edges.iter().flat_map(|&(u, v)| match (bipartite, side) {
+                (false, _) => vec![u, v],
+                (true, 0) => vec![u],
+                (true, _) => vec![v],
+            }).collect();
+            vs.sort_unstable();
+            vs.dedup();
+            vs
+        };
+        let (used_left, used_right) = (used(0), used(1));
+        let rank = |vs: &[usize], x: usize| vs.binary_search(&x).unwrap();
+        let expected: Vec<(usize, usize, i64)> = edges.iter().enumerate().map(|(k, &(u, v))| {
+            let w = if weighted { weights[k] } else { 0 };
+            if bipartite { (rank(&used_left, u), rank(&used_right, v), w) } else { (rank(&used_left, u), rank(&used_left, v), w) }
+        }).collect();
+        let map = graph_io::IdMap {
+            left: used_left.iter().map(|&x| left_ids[x]).collect(),
+            right: if bipartite { Some(used_right.iter().map(|&x| right_ids[x]).collect()) } else { None },
+        };
+        let bounds = (map.left.len(), map.right.as_ref().map_or(map.left.len(), |r| r.len()));
+
+        let snap = graph_io::LoadOptions { input: graph_io::InputFormat::Snap, ..graph_io::LoadOptions::default() };
+        let read = |data: &[u8]| -> Result<Parsed, String> {
+            let e = |e: SuiteError| e.to_string();
+            let zero = |es: Vec<(usize, usize)>| es.into_iter().map(|(u, v)| (u, v, 0)).collect();
+            match (bipartite, weighted) {
+                (false, false) => graph_io::parse_graph_named(data, &snap).map(|(n, es, ns)| (n, n, zero(es), ns)).map_err(e),
+                (false, true) => graph_io::parse_weighted_graph_named(data, &snap).map(|(n, es, ns)| (n, n, es, ns)).map_err(e),
+                (true, false) => graph_io::parse_bipartite_graph_named(data, &snap)
+                    .map(|(l, r, es, ns)| (l, r, zero(es), ns)).map_err(e),
+                (true, true) => graph_io::parse_weighted_bipartite_graph_named(data, &snap).map_err(e),
+            }
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let (l, r, es, names) = match read(&data) {
+                Ok(got) => got,
+                Err(e) => return Some(format!("edge list refused: {}", e)),
+            };
+            if (l, r, &es) != (bounds.0, bounds.1, &expected) {
+                return Some(format!("edge list read back as {:?}, expected {:?}", (l, r, &es), (bounds, &expected)));
+            }
+            if names.ids.as_ref() != Some(&map) {
+                return Some(format!("id table {:?}, expected {:?}", names.ids, map));
+            }
+            let pairs: Vec<(usize, usize)> = expected.iter().map(|&(u, v, _)| (u, v)).collect();
+            let original = names.original_pairs(&pairs, bipartite);
+            if names.remapped_pairs(original.clone(), bipartite) != Ok(pairs) {
+                return Some(format!("pairs {:?} in the original ids do not map back", original));
+            }
+            let missing = vec![(map.left.last().map_or(0, |&x| x + 1), 0)];
+            if names.remapped_pairs(missing, bipartite).is_ok() {
+                return Some("an id past the graph's was remapped".to_string());
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            match read(&bad) {
+                Ok((l, r, ref es, _)) if es.iter().any(|&(u, v, _)| u >= l || v >= r) => {
+                    Some(format!("mutated edge list accepted with an edge out of range: {:?}", es))
+                }
+                Ok((l, r, _, ref ns)) if ns.ids.as_ref().is_none_or(|m| m.left.len() != l
+                                                                 || m.right.as_ref().map_or(l, |rs| rs.len()) != r) => {
+                    Some(format!("mutated edge list read as {} x {} without a matching id table", l, r))
+                }
+                _ => None,
+            }
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("snap", iter, &w, &data);
+        }
+    }
+
+    /* A label for vertex `x` of one side: a bare name, or a quoted one
+     * with spaces, quotes, backslashes or comment characters in it. */
+    fn label(&mut self, side: &str, x: usize) -> String {
+        match self.below(4) {
+            0 => format!("{}{}", side, x),
+            1 => format!("{} {}", side, x),
+            2 => format!("{}#\"{}\"\\%", side, x),
+            _ => format!("{}-{}.é", side, x),
+        }
+    }
+
+    fn labels(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let weighted = iter % 4 >= 2;
+        let plain = self.valid_file(bipartite);
+        let opts = graph_io::LoadOptions::default();
+        let (left, right, edges) = if bipartite {
+            let (l, r, e) = graph_io::parse_bipartite_graph(&plain[..], &opts).unwrap();
+            (l, r, e)
+        } else {
+            let (n, e) = graph_io::parse_graph(&plain[..], &opts).unwrap();
+            (n, n, e)
+        };
+        let weights: Vec<i64> = (0..edges.len()).map(|_| self.rng.next_u64() as i64 >> self.below(64)).collect();
+        let left_labels: Vec<String> = (0..left).map(|x| self.label("u", x)).collect();
+        let right_labels: Vec<String> = if bipartite { (0..right).map(|x| self.label("v", x)).collect() } else { left_labels.clone() };
+
+        /* headerless, labels quoted where they must be and at times where
+         * they need not be, comments mixed in */
+        let mut text = String::from("# labeled graph\n");
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let sep = ["\t", " ", "  "][self.below(3)];
+            let write = |fz: &mut Fuzzer, label: &str| if fz.below(4) == 0 {
+                format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                graph_io::quote_label(label)
+            };
+            text += &write(self, &left_labels[u]);
+            text += sep;
+            text += &write(self, &right_labels[v]);
+            if weighted {
+                text += &format!("{}{}", sep, weights[k]);
+            }
+            text += ["\n", "\r\n"][self.below(2)];
+            /* "c" is a label here, not DIMACS's comment */
+            let not_data = NOT_DATA[self.below(NOT_DATA.len())];
+            if self.below(8) == 0 && !not_data.starts_with('c') {
+                text += not_data;
+                text += "\n";
+            }
+        }
+        let data = text.into_bytes();
+
+        /* the labels on an edge, numbered in order of first appearance */
+        let mut first = [vec![usize::MAX; left], vec![usize::MAX; right]];
+        let mut order: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+        let mut expected = Vec::new();
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let mut id = |side: usize, x: usize| {
+                if first[side][x] == usize::MAX {
+                    first[side][x] = order[side].len();
+                    order[side].push(x);
+                }
+                first[side][x]
+            };
+            let a = id(0, u);
+            let b = id(if bipartite { 1 } else { 0 }, v);
+            expected.push((a, b, if weighted { weights[k] } else { 0 }));
+        }
+        let table = graph_io::VertexLabels {
+            left: order[0].iter().map(|&x| left_labels[x].clone()).collect(),
+            right: if bipartite { Some(order[1].iter().map(|&x| right_labels[x].clone()).collect()) } else { None },
+        };
+        let bounds = (table.left.len(), table.right.as_ref().map_or(table.left.len(), |r| r.len()));
+
+        let labels = graph_io::LoadOptions { input: graph_io::InputFormat::Labels, ..graph_io::LoadOptions::default() };
+        let read = |data: &[u8]| -> Result<Parsed, String> {
+            let e = |e: SuiteError| e.to_string();
+            let zero = |es: Vec<(usize, usize)>| es.into_iter().map(|(u, v)| (u, v, 0)).collect();
+            match (bipartite, weighted) {
+                (false, false) => graph_io::parse_graph_named(data, &labels).map(|(n, es, ns)| (n, n, zero(es), ns)).map_err(e),
+                (false, true) => graph_io::parse_weighted_graph_named(data, &labels).map(|(n, es, ns)| (n, n, es, ns)).map_err(e),
+                (true, false) => graph_io::parse_bipartite_graph_named(data, &labels)
+                    .map(|(l, r, es, ns)| (l, r, zero(es), ns)).map_err(e),
+                (true, true) => graph_io::parse_weighted_bipartite_graph_named(data, &labels).map_err(e),
+            }
+        };
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let (l, r, es, names) = match read(&data) {
+                Ok(got) => got,
+                Err(e) => return Some(format!("edge list refused: {}", e)),
+            };
+            if (l, r, &es) != (bounds.0, bounds.1, &expected) {
+                return Some(format!("edge list read back as {:?}, expected {:?}", (l, r, &es), (bounds, &expected)));
+            }
+            if names.labels.as_ref() != Some(&table) {
+                return Some(format!("label table {:?}, expected {:?}", names.labels, table));
+            }
+            let pairs: Vec<(usize, usize)> = expected.iter().map(|&(u, v, _)| (u, v)).collect();
+            let labeled = names.labeled_pairs(&pairs, bipartite).unwrap_or_default();
+            if names.unlabeled_pairs(labeled.clone(), bipartite) != Ok(pairs) {
+                return Some(format!("pairs {:?} in labels do not map back", labeled));
+            }
+            let mut file = Vec::new();
+            matching_io::write_labeled_matching(&mut file, &labeled).unwrap();
+            if matching_io::read_labeled_matching(&file[..]) != Ok(labeled) {
+                return Some("a labeled matching file does not read back".to_string());
+            }
+            if names.unlabeled_pairs(vec![("no such label".to_string(), "u0".to_string())], bipartite).is_ok() {
+                return Some("a label the graph does not have was mapped".to_string());
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            match read(&bad) {
+                Ok((l, r, ref es, _)) if es.iter().any(|&(u, v, _)| u >= l || v >= r) => {
+                    Some(format!("mutated edge list accepted with an edge out of range: {:?}", es))
+                }
+                Ok((l, r, _, ref ns)) if ns.labels.as_ref().is_none_or(|t| t.left.len() != l
+                                                                    || t.right.as_ref().map_or(l, |rs| rs.len()) != r) => {
+                    Some(format!("mutated edge list read as {} x {} without a matching label table", l, r))
+                }
+                _ => None,
+            }
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(w) => w,
+        };
+        if let Some(w) = what {
+            self.crash("labels", iter, &w, &data);
+        }
+    }
+
+    /* A valid general file with or without weights: text with its header
+     * or SNAP style (comments mixed in), binary or JSON, on disk. */
+    fn weights(&mut self, iter: usize) {
+        let weighted = iter % 2 == 1;
+        let kind = iter / 2 % 4;
+        let plain = self.valid_file(false);
+        let opts = graph_io::LoadOptions::default();
+        let (n, edges) = graph_io::parse_graph(&plain[..], &opts).unwrap();
+        let weights: Vec<i64> = (0..edges.len()).map(|_| self.rng.next_u64() as i64 >> self.below(64)).collect();
+        let ws = if weighted { Some(&weights[..]) } else { None };
+        let mut bare = if kind == 1 { String::new() } else { format!("{} {}\n", n, edges.len()) };
+        let mut text = format!("{}{}", ["", "# weighted?\n", "% 1 2 3\n"][self.below(3)], bare);
+        /* a third field that is no integer is not a weight */
+        let junk = if !weighted && self.below(4) == 0 { [" x", " 1.5", " 7w"][self.below(3)] } else { "" };
+        for (k, &(u, v)) in edges.iter().enumerate() {
+            let line = match ws {
+                Some(ws) => format!("{} {} {}", u, v, ws[k]),
+                None => format!("{} {}", u, v),
+            };
+            bare += &format!("{}\n", line);
+            text += &line;
+            text += junk;
+            if self.below(4) == 0 {
+                text += [" # 5 6", "%7"][self.below(2)];
+            }
+            text += "\n";
+            if self.below(8) == 0 {
+                text += NOT_DATA[self.below(NOT_DATA.len())];
+                text += "\n";
+            }
+        }
+        let (input, data) = match kind {
+            0 => (graph_io::InputFormat::Auto, text.into_bytes()),
+            1 => (graph_io::InputFormat::Snap, text.into_bytes()),
+            2 => {
+                let mut data = Vec::new();
+                graph_io::write_binary(&mut data, n, None, &edges, ws).unwrap();
+                (graph_io::InputFormat::Auto, data)
+            }
+            _ => (graph_io::InputFormat::Auto, self.json_document(n, None, &edges, ws).into_bytes()),
+        };
+        /* a binary header carries its flag with no edges; text and JSON need a weighted edge */
+        let expected = weighted && (kind == 2 || !edges.is_empty());
+
+        let dir = env::temp_dir().join(format!("fuzz_weights_{}", std::process::id()));
+        if fs::create_dir_all(&dir).is_err() { return; }
+        let path = dir.join("graph.txt");
+        let path = path.to_str().unwrap();
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            fs::write(path, &data).map_err(|e| e.to_string())?;
+            match graph_io::has_weights(path, input) {
+                Ok(w) if w == expected => {}
+                got => return Err(format!("has_weights gives {:?}, the file is written {}", got.map_err(|e| e.to_string()),
+                                          if weighted { "weighted" } else { "unweighted" })),
+            }
+            if kind != 1 {
+                /* unweighted loads read past the weights, --strict-format too */
+                let got = graph_io::parse_graph(&data[..], &opts).map_err(|e| e.to_string());
+                if got != Ok((n, edges.clone())) {
+                    return Err(format!("read unweighted as {:?}", got));
+                }
+                let strict = graph_io::LoadOptions { format: graph_io::FormatPolicy::Strict, ..Default::default() };
+                let got = graph_io::parse_graph(bare.as_bytes(), &strict).map_err(|e| e.to_string());
+                if kind == 0 && got != Ok((n, edges.clone())) {
+                    return Err(format!("--strict-format reads the bare file as {:?}", got));
+                }
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            fs::write(path, &bad).map_err(|e| e.to_string())?;
+            let _ = graph_io::has_weights(path, input);
+            Ok(())
+        }));
+        let _ = fs::remove_dir_all(&dir);
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(Err(w)) => Some(w),
+            Ok(Ok(())) => None,
+        };
+        if let Some(w) = what {
+            self.crash("weights", iter, &w, &data);
+        }
+    }
+
+    /* A multigraph (general or bipartite, weighted on half the iterations)
+     * with many parallel edges, written as text with comments, binary or
+     * JSON, loaded under --edge-ids. */
+    fn edge_ids(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let weighted = iter % 4 >= 2;
+        let kind = iter / 4 % 3;
+        let (left, right) = (1 + self.below(8), 1 + self.below(8));
+        let right = if bipartite { right } else { left };
+        /* a few pairs, each on several lines */
+        let pool: Vec<(usize, usize)> = (0..1 + self.below(6)).map(|_| (self.below(left), self.below(right))).collect();
+        let edges: Vec<(usize, usize)> = (0..self.below(16)).map(|_| {
+            let (u, v) = pool[self.below(pool.len())];
+            if !bipartite && self.below(2) == 0 { (v, u) } else { (u, v) }
+        }).collect();
+        let weights: Vec<i64> = (0..edges.len()).map(|_| self.below(4) as i64 - 1).collect();
+        let ws = if weighted { Some(&weights[..]) } else { None };
+        let header = if bipartite { format!("{} {} {}", left, right, edges.len()) } else { format!("{} {}", left, edges.len()) };
+
+        let (data, ids) = match kind {
+            0 => {
+                let mut text = String::new();
+                let mut line = 0;
+                let mut ids = Vec::new();
+                let mut put = |text: &mut String, s: &str| { *text += s; *text += "\n"; line += 1; line };
+                while self.below(3) == 0 {
+                    put(&mut text, NOT_DATA[self.below(NOT_DATA.len())]);
+                }
+                put(&mut text, &header);
+                for (k, &(u, v)) in edges.iter().enumerate() {
+                    while self.below(4) == 0 {
+                        put(&mut text, NOT_DATA[self.below(NOT_DATA.len())]);
+                    }
+                    let w = if weighted { format!(" {}", weights[k]) } else { String::new() };
+                    ids.push(put(&mut text, &format!("{} {}{}", u, v, w)));
+                }
+                (text.into_bytes(), ids)
+            }
+            1 => {
+                let mut data = Vec::new();
+                graph_io::write_binary(&mut data, left, if bipartite { Some(right) } else { None }, &edges, ws).unwrap();
+                (data, (1..=edges.len()).collect())
+            }
+            _ => (self.json_document(left, if bipartite { Some(right) } else { None }, &edges, ws).into_bytes(),
+                  (1..=edges.len()).collect()),
+        };
+
+        let opts = graph_io::LoadOptions { edge_ids: true, ..graph_io::LoadOptions::default() };
+        /* the edge table of the load */
+        let read = |data: &[u8]| -> Result<Option<graph_io::EdgeIds>, String> {
+            let e = |e: SuiteError| e.to_string();
+            match (bipartite, weighted) {
+                (false, false) => graph_io::parse_graph_named(data, &opts).map(|g| g.2.edges).map_err(e),
+                (false, true) => graph_io::parse_weighted_graph_named(data, &opts).map(|g| g.2.edges).map_err(e),
+                (true, false) => graph_io::parse_bipartite_graph_named(data, &opts).map(|g| g.3.edges).map_err(e),
+                (true, true) => graph_io::parse_weighted_bipartite_graph_named(data, &opts).map(|g| g.3.edges).map_err(e),
+            }
+        };
+        let key = |(u, v): (usize, usize)| if bipartite { (u, v) } else { (u.min(v), u.max(v)) };
+        let lightest = self.below(2) == 0;
+        /* a greedy matching over the edges in random order, pairs turned at random */
+        let mut order: Vec<usize> = (0..edges.len()).collect();
+        for i in (1..order.len()).rev() {
+            order.swap(i, self.below(i + 1));
+        }
+        let mut used = (vec![false; left], vec![false; right]);
+        let mut pairs = Vec::new();
+        for &k in &order {
+            let (u, v) = edges[k];
+            let clash = if bipartite { used.0[u] || used.1[v] } else { u == v || used.0[u] || used.0[v] };
+            if !clash {
+                used.0[u] = true;
+                if bipartite { used.1[v] = true; } else { used.0[v] = true; }
+                pairs.push(if !bipartite && self.below(2) == 0 { (v, u) } else { (u, v) });
+            }
+        }
+
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            let table = read(&data).map_err(|e| format!("multigraph refused: {}", e))?
+                .ok_or("no edge table after an --edge-ids load")?;
+            if table.ids != ids {
+                return Err(format!("edge ids {:?}, expected {:?}", table.ids, ids));
+            }
+            for (k, &id) in ids.iter().enumerate() {
+                let w = if weighted { weights[k] } else { 0 };
+                if table.edge(id) != Some((edges[k].0, edges[k].1, w)) {
+                    return Err(format!("edge {} is {:?}, expected {:?}", id, table.edge(id), (edges[k], w)));
+                }
+            }
+            let chosen = table.ids_of(&pairs, lightest)?;
+            for (&pair, &id) in pairs.iter().zip(&chosen) {
+                /* the heaviest (lightest) parallel edge, the smallest id among equals */
+                let parallel: Vec<usize> = (0..edges.len()).filter(|&k| key(edges[k]) == key(pair)).collect();
+                let weight = |k: usize| if weighted { weights[k] } else { 0 };
+                let best = parallel.iter().cloned().min_by_key(|&k| if lightest { weight(k) } else { -weight(k) }).unwrap();
+                if id != ids[best] {
+                    return Err(format!("pair {:?} written as edge {}, expected {} (lightest: {})", pair, id, ids[best], lightest));
+                }
+            }
+            let back = table.pairs_of(&chosen)?;
+            if back.iter().map(|&p| key(p)).collect::<Vec<_>>() != pairs.iter().map(|&p| key(p)).collect::<Vec<_>>() {
+                return Err(format!("edge ids {:?} read back as {:?}, expected {:?}", chosen, back, pairs));
+            }
+            let mut text = Vec::new();
+            matching_io::write_edge_ids(&mut text, &chosen).unwrap();
+            let mut sorted = chosen.clone();
+            sorted.sort_unstable();
+            if matching_io::read_edge_ids(&text[..]) != Ok(sorted) {
+                return Err(format!("matching file read back as {:?}", matching_io::read_edge_ids(&text[..])));
+            }
+            let missing = ids.last().map_or(1, |&x| x + 1);
+            if table.pairs_of(&[missing]).is_ok() {
+                return Err(format!("edge id {} past the last edge accepted", missing));
+            }
+            let mut bad = data.clone();
+            self.mutate(&mut bad);
+            if let Ok(table) = read(&bad) {
+                let table = table.ok_or("no edge table after a mutated load")?;
+                if table.ids.windows(2).any(|w| w[0] >= w[1]) {
+                    return Err(format!("mutated file read with edge ids out of order: {:?}", table.ids));
+                }
+            }
+            Ok(())
+        }));
+        let what = match run {
+            Err(p) => Some(format!("loader panicked: {}", panic_message(p))),
+            Ok(Err(w)) => Some(w),
+            Ok(Ok(())) => None,
+        };
+        if let Some(w) = what {
+            self.crash("edge-ids", iter, &w, &data);
+        }
+    }
+
+    fn compressed(&mut self, iter: usize) {
+        if !iter.is_multiple_of(100) { return; }
+        let plain = self.valid_file(false);
+        let opts = graph_io::LoadOptions::default();
+        let expected = graph_io::parse_graph(&plain[..], &opts).map_err(|e| e.to_string());
+        let dir = env::temp_dir().join(format!("fuzz_compressed_{}", std::process::id()));
+        if fs::create_dir_all(&dir).is_err() { return; }
+        for &(ext, tool, flags) in [(".gz", "gzip", "-c"), (".zst", "zstd", "-qc")].iter() {
+            let packed = Command::new(tool).arg(flags)
+                .stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::null())
+                .spawn()
+                .and_then(|mut child| {
+                    child.stdin.take().unwrap().write_all(&plain)?;
+                    child.wait_with_output()
+                });
+            let packed = match packed {
+                Ok(out) if out.status.success() => out.stdout,
+                _ => continue, /* no such tool here */
+            };
+            let path = dir.join(format!("graph{}", ext));
+            let path = path.to_str().unwrap();
+            let load = |bytes: &[u8]| {
+                fs::write(path, bytes).map_err(|e| e.to_string())?;
+                graph_io::load_graph(path, &opts).map_err(|e| e.to_string())
+            };
+            let what = if load(&packed) != expected {
+                Some(format!("{} file read differently (expected {:?})", tool, expected))
+            } else if load(&packed[..packed.len() / 2]).is_ok() {
+                Some(format!("{} file cut to {} of {} bytes accepted", tool, packed.len() / 2, packed.len()))
+            } else {
+                None
+            };
+            if let Some(w) = what {
+                self.crash("compressed", iter, &w, &plain);
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    fn solvers(&mut self, iter: usize) {
+        let n = self.below(self.max_n + 1);
+        let edges = edge_lists::arbitrary(&mut self.rng, n);
+        if let Some(w) = self.run_matchers(n, &edges) {
+            let text = edge_list_text(&format!("{} {}", n, edges.len()), &edges);
+            self.crash("solvers", iter, &w, &text);
+        }
+    }
+
+    fn hk(&mut self, iter: usize) {
+        let left = self.below(self.max_n + 1);
+        let right = self.below(self.max_n + 1);
+        let mut edges = edge_lists::arbitrary(&mut self.rng, left.max(right));
+        if self.below(2) == 0 { edges.retain(|&(u, v)| u < left || v >= right); }
+        if let Some(w) = self.run_hk(left, right, &edges) {
+            let text = edge_list_text(&format!("{} {} {}", left, right, edges.len()), &edges);
+            self.crash("hk", iter, &w, &text);
+        }
+    }
+
+    fn merge(&mut self, iter: usize) {
+        let n = 1 + self.below(6);
+        let symmetric = iter.is_multiple_of(2);
+        let mut edges = Vec::new();
+        for _ in 0..self.below(24) {
+            let w = match self.below(8) {
+                0 => [i64::MAX, i64::MIN, i64::MAX / 2 + 1, -1][self.below(4)],
+                _ => self.below(21) as i64 - 10,
+            };
+            edges.push((self.below(n), self.below(n), w));
+        }
+        for &policy in MERGE_POLICIES.iter() {
+            let run = panic::catch_unwind(AssertUnwindSafe(|| graph_io::merge_parallel(edges.clone(), symmetric, policy)));
+            let what = match (run, expected_merge(&edges, symmetric, policy)) {
+                (Err(p), _) => Some(format!("merge_parallel ({:?}) panicked: {}", policy, panic_message(p))),
+                (Ok(Ok((got, folded))), Some(want)) => {
+                    if got != want || folded != edges.len() - want.len() {
+                        Some(format!("merge_parallel ({:?}, symmetric {}): {:?}, expected {:?}", policy, symmetric, got, want))
+                    } else {
+                        None
+                    }
+                }
+                (Ok(Ok((got, _))), None) => Some(format!("merge_parallel ({:?}) accepted: {:?}", policy, got)),
+                (Ok(Err(e)), Some(_)) => Some(format!("merge_parallel ({:?}) refused: {}", policy, e)),
+                (Ok(Err(_)), None) => None,
+            };
+            if let Some(w) = what {
+                let mut text = format!("{} {}\n", n, edges.len());
+                for &(u, v, w) in &edges {
+                    text.push_str(&format!("{} {} {}\n", u, v, w));
+                }
+                self.crash("merge", iter, &w, text.as_bytes());
+                return;
+            }
+        }
+    }
+
+    fn matchfile(&mut self, iter: usize) {
+        let bipartite = iter % 2 == 1;
+        let left = self.below(self.max_n + 1);
+        let right = if bipartite { self.below(self.max_n + 1) } else { left };
+        let edges: Vec<(usize, usize)> = (0..self.below(3 * left + 4))
+            .map(|_| (self.below(left.max(1)), self.below(right.max(1))))
+            .filter(|&(u, v)| u < left && v < right)
+            .collect();
+        let mut pairs = if bipartite {
+            hopcroft_karp::HopcroftKarp::new(left, right, &edges).maximum_matching(0)
+        } else {
+            matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, left, &edges).unwrap()
+        };
+        for i in (1..pairs.len()).rev() {
+            let j = self.below(i + 1);
+            pairs.swap(i, j);
+        }
+        if !bipartite {
+            for p in pairs.iter_mut() {
+                if self.below(2) == 0 { *p = (p.1, p.0); }
+            }
+        }
+        let want = matching_io::canonical(&pairs, bipartite);
+
+        let mut text = Vec::new();
+        matching_io::write_matching(&mut text, &pairs, bipartite).unwrap();
+        let what = match matching_io::read_matching(&text[..]) {
+            Err(e) => Some(format!("read_matching refused its own output: {}", e)),
+            Ok(ref got) if *got != want => Some(format!("read back {:?}, expected {:?}", got, want)),
+            Ok(ref got) => {
+                let (errors, note) = matching_io::check_pairs(got, (left, right), bipartite);
+                if !errors.is_empty() || note.is_some() {
+                    Some(format!("check_pairs on canonical pairs: {:?} {:?}", errors, note))
+                } else {
+                    None
+                }
+            }
+        };
+        if let Some(w) = what {
+            self.crash("matchfile", iter, &w, &text);
+            return;
+        }
+
+        self.mutate(&mut text);
+        let run = panic::catch_unwind(AssertUnwindSafe(|| {
+            if let Ok(got) = matching_io::read_matching(&text[..]) {
+                matching_io::check_pairs(&got, (left, right), bipartite);
+            }
+        }));
+        if let Err(p) = run {
+            let w = format!("read_matching / check_pairs panicked: {}", panic_message(p));
+            self.crash("matchfile", iter, &w, &text);
+        }
+    }
+
+    fn coloring(&mut self, iter: usize) {
+        let left = self.below(self.max_n + 1);
+        let right = self.below(self.max_n + 1);
+        let edges = edge_lists::arbitrary(&mut self.rng, left.max(right));
+        if let Some(w) = self.run_coloring(left, right, &edges) {
+            let text = edge_list_text(&format!("{} {} {}", left, right, edges.len()), &edges);
+            self.crash("coloring", iter, &w, &text);
+        }
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let target = args::value_of(&args, "--target").unwrap_or("all").to_string();
+    let iters: usize = args::parse_or_exit(&args, "--iters", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("fuzz_crashes").to_string();
+
+    let targets: Vec<&str> = match target.as_str() {
+        "all" => vec!["loader", "bipartite", "format", "blocks", "binary", "compressed", "structured", "snap", "labels",
+                     "weights", "edge-ids", "solvers", "hk", "coloring", "merge", "matchfile"],
+        "loader" | "bipartite" | "format" | "blocks" | "binary" | "compressed" | "structured" | "snap" | "labels" | "weights" | "edge-ids"
+        | "solvers" | "hk" | "coloring" | "merge" | "matchfile" => {
+            vec![target.as_str()]
+        }
+        _ => {
+            eprintln!("Error: unknown target '{}' (all, loader, bipartite, format, blocks, binary, compressed, structured, snap, labels, weights, edge-ids, solvers, hk, coloring, merge, \
+                       matchfile)", target);
+            std::process::exit(1);
+        }
+    };
+
+    /* Panics are reported by the fuzzer itself; silence the default hook. */
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut fz = Fuzzer { rng: Rng::new(seed), max_n, dump_dir, crashes: 0 };
+    for t in &targets {
+        println!("Fuzzing {}: {} iterations, seed {}, n <= {} for solver runs", t, iters, seed, max_n);
+        let before = fz.crashes;
+        for i in 0..iters {
+            match *t {
+                "loader" => fz.loader(i),
+                "bipartite" => fz.bipartite(i),
+                "format" => fz.format(i),
+                "blocks" => fz.blocks(i),
+                "binary" => fz.binary(i),
+                "compressed" => fz.compressed(i),
+                "structured" => fz.structured(i),
+                "snap" => fz.snap(i),
+                "labels" => fz.labels(i),
+                "weights" => fz.weights(i),
+                "edge-ids" => fz.edge_ids(i),
+                "solvers" => fz.solvers(i),
+                "hk" => fz.hk(i),
+                "merge" => fz.merge(i),
+                "matchfile" => fz.matchfile(i),
+                _ => fz.coloring(i),
+            }
+        }
+        println!("  {} crash(es)", fz.crashes - before);
+    }
+
+    if fz.crashes > 0 {
+        println!("{} CRASH(ES)", fz.crashes);
+        std::process::exit(1);
+    }
+    println!("NO CRASHES");
+}
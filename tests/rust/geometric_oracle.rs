@@ -0,0 +1,145 @@
+/*
+ * Randomized test of the pruned geometric matching against the complete
+ * graph
+ *
+ * Generates seeded random point sets (uniform, clusters of odd size,
+ * lattice points with duplicates, nearly collinear) and checks that
+ * geometric_matching.rs, started from a small random k, returns a perfect
+ * matching whose EUC_2D weight equals that of the weighted blossom solver
+ * on the complete graph. Small k and odd clusters force the exposed-point
+ * linking and the dual check to do the work.
+ *
+ * A failing instance is written to the dump directory as a points file,
+ * ready for `combi geomatch --full`.
+ *
+ * Usage: geometric_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/geometric_matching.rs"]
+mod geometric_matching;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+use geometric_matching::distance;
+
+fn check(points: &[(f64, f64)], k: usize) -> Option<String> {
+    let n = points.len();
+    let gm = match geometric_matching::min_weight_perfect_matching(points, k) {
+        Ok(gm) => gm,
+        Err(e) => return Some(e),
+    };
+    let mut used = vec![false; n];
+    let mut weight = 0;
+    for &(u, v) in &gm.pairs {
+        if u >= n || v >= n || u == v || used[u] || used[v] {
+            return Some(format!("bad pair ({}, {})", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+        weight += distance(points[u], points[v]);
+    }
+    if 2 * gm.pairs.len() != n {
+        return Some(format!("{} pairs for {} points", gm.pairs.len(), n));
+    }
+    if weight != gm.weight {
+        return Some(format!("pairs weigh {}, reported {}", weight, gm.weight));
+    }
+
+    let mut all = Vec::new();
+    for u in 0..n {
+        for v in u + 1..n {
+            all.push((u, v, distance(points[u], points[v])));
+        }
+    }
+    let full = weighted_blossom::min_weight_perfect_matching(n, &all).unwrap_or_default();
+    let best: i64 = full.iter().map(|&(u, v)| distance(points[u], points[v])).sum();
+    if best != weight {
+        return Some(format!("weight {} with k = {}, complete graph {}", weight, k, best));
+    }
+    None
+}
+
+fn random_points(rng: &mut rng::Rng, max_n: usize) -> Vec<(f64, f64)> {
+    let n = 2 * rng.below(max_n as u64 / 2 + 1) as usize;
+    let mut points = Vec::with_capacity(n);
+    match rng.below(4) {
+        0 => {
+            for _ in 0..n {
+                points.push((rng.next_f64() * 1000.0, rng.next_f64() * 1000.0));
+            }
+        }
+        1 => {
+            /* clusters of odd size far apart */
+            while points.len() < n {
+                let (cx, cy) = (rng.next_f64() * 1e5, rng.next_f64() * 1e5);
+                let size = (2 * rng.below(4) as usize + 1).min(n - points.len());
+                for _ in 0..size {
+                    points.push((cx + rng.next_f64() * 30.0, cy + rng.next_f64() * 30.0));
+                }
+            }
+        }
+        2 => {
+            /* small lattice: duplicates and many equal distances */
+            for _ in 0..n {
+                points.push((rng.below(5) as f64, rng.below(5) as f64));
+            }
+        }
+        _ => {
+            for _ in 0..n {
+                let t = rng.next_f64() * 1000.0;
+                points.push((t, 0.5 * t + rng.next_f64()));
+            }
+        }
+    }
+    points
+}
+
+fn dump_case(dir: &str, name: &str, points: &[(f64, f64)]) -> io::Result<PathBuf> {
+    let coords = points.iter().map(|&(x, y)| format!("{:?} {:?}", x, y));
+    dump::lines(dir, name, Some(points.len().to_string()).into_iter().chain(coords))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 1000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("geometric_failures").to_string();
+
+    println!("Pruned geometric matching vs. complete graph: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let points = random_points(&mut rng, max_n);
+        let k = 1 + rng.below(5) as usize;
+        if let Some(why) = check(&points, k) {
+            failures += 1;
+            let name = format!("geometric_seed{}_case{}.txt", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &points));
+            println!("  FAIL  case {:<5} n={} k={}: {}", case, points.len(), k, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ * Randomized test of the greedy initializers and --init-aug3
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and random
+ * forests, general or bipartite, and solves each with every general matcher
+ * and with Hopcroft-Karp under --greedy, --greedy-md, --greedy-ks and
+ * --greedy-random (seeded by the case), each again with 1 to 3 --init-aug3
+ * passes. Checks:
+ *   - every run reaches the maximum size,
+ *   - the initial matching is at least half of it (each initializer gives
+ *     a maximal matching), and all of it under Karp-Sipser on forests,
+ *   - one seed gives the same random initial size in every general matcher,
+ *   - with --init-aug3 the initial size is the greedy size plus the
+ *     reported augmentations, the same in every general matcher.
+ *
+ * A failing instance is written to the dump directory in the "n m" (or
+ * "left right m") format, ready for `combi solve <name>.txt --greedy-ks`
+ * and the other modes (or `hopcroft_karp` for the bipartite ones).
+ *
+ * Usage: greedy_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+fn check(left: usize, right: usize, edges: &[(usize, usize)], bipartite: bool, forest: bool, seed: u64, aug3: usize)
+         -> Option<String> {
+    let verdict = |name: &str, mode: i32, size: usize, got: usize, greedy: usize| {
+        if got != size || greedy > size || 2 * greedy < size || (forest && mode == 3 && greedy != size) {
+            Some(format!("{} greedy mode {}: {} from an initial {}, maximum {}", name, mode, got, greedy, size))
+        } else {
+            None
+        }
+    };
+    if bipartite {
+        let size = hopcroft_karp::HopcroftKarp::new(left, right, edges).maximum_matching(0).len();
+        for mode in 1..5 {
+            let mut hk = hopcroft_karp::HopcroftKarp::new(left, right, edges);
+            hk.greedy_seed = seed;
+            let got = hk.maximum_matching(mode).len();
+            if let Some(w) = verdict("hopcroft-karp", mode, size, got, hk.greedy_size()) {
+                return Some(w);
+            }
+            let mut hk3 = hopcroft_karp::HopcroftKarp::new(left, right, edges);
+            hk3.greedy_seed = seed;
+            hk3.aug3_passes = aug3;
+            let got = hk3.maximum_matching(mode).len();
+            if let Some(w) = verdict("hopcroft-karp --init-aug3", mode, size, got, hk3.greedy_size()) {
+                return Some(w);
+            }
+            if hk3.greedy_size() != hk.greedy_size() + hk3.aug3_size() {
+                return Some(format!("hopcroft-karp greedy mode {}: {} aug3 passes took {} to {}, not {} more",
+                                    mode, aug3, hk.greedy_size(), hk3.greedy_size(), hk3.aug3_size()));
+            }
+        }
+        return None;
+    }
+    let size = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, left, edges) {
+        Ok(m) => m.len(),
+        Err(_) => return None, /* rejected cleanly */
+    };
+    let mut random_init = None;
+    /* per greedy mode, the first solver's initial size after the aug3
+     * passes: the passes are deterministic, so all agree */
+    let mut aug3_init: [Option<(&str, usize)>; 5] = [None; 5];
+    for algo in matchers::GENERAL_ALGORITHMS.iter() {
+        for mode in 1..5 {
+            let sol = matchers::solve(algo, left, edges, mode, seed, 0).unwrap();
+            if let Some(e) = edge_lists::matching_error(left, edges, &sol.matching) {
+                return Some(format!("{} greedy mode {}: {}", algo, mode, e));
+            }
+            if let Some(w) = verdict(algo, mode, size, sol.matching.len(), sol.greedy_size) {
+                return Some(w);
+            }
+            if mode == 4 {
+                match random_init {
+                    None => random_init = Some((algo, sol.greedy_size)),
+                    Some((first, g)) if g != sol.greedy_size => {
+                        return Some(format!("seed {}: random greedy gives {} in {} but {} in {}",
+                                            seed, g, first, sol.greedy_size, algo));
+                    }
+                    _ => {}
+                }
+            }
+            let aug = matchers::solve(algo, left, edges, mode, seed, aug3).unwrap();
+            if let Some(e) = edge_lists::matching_error(left, edges, &aug.matching) {
+                return Some(format!("{} greedy mode {} --init-aug3: {}", algo, mode, e));
+            }
+            let name = format!("{} --init-aug3", algo);
+            if let Some(w) = verdict(&name, mode, size, aug.matching.len(), aug.greedy_size) {
+                return Some(w);
+            }
+            if aug.greedy_size != sol.greedy_size + aug.aug3_size {
+                return Some(format!("{} greedy mode {}: {} aug3 passes took {} to {}, not {} more",
+                                    algo, mode, aug3, sol.greedy_size, aug.greedy_size, aug.aug3_size));
+            }
+            match aug3_init[mode as usize] {
+                None => aug3_init[mode as usize] = Some((algo, aug.greedy_size)),
+                Some((first, g)) if g != aug.greedy_size => {
+                    return Some(format!("greedy mode {} with {} aug3 passes gives {} in {} but {} in {}",
+                                        mode, aug3, g, first, aug.greedy_size, algo));
+                }
+                _ => {}
+            }
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("greedy_failures").to_string();
+
+    println!("Greedy initializers vs. the maximum size: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let bipartite = case % 2 == 1;
+        let forest = case / 2 % 2 == 1;
+        let left = rng.below(max_n as u64 + 1) as usize;
+        let right = if bipartite { rng.below(max_n as u64 + 1) as usize } else { left };
+        let edges = match (forest, bipartite) {
+            (false, _) => edge_lists::arbitrary(&mut rng, left.max(right)),
+            (true, false) => edge_lists::forest(&mut rng, left, None),
+            (true, true) => edge_lists::forest(&mut rng, left + right, Some(left)).into_iter()
+                .map(|(u, v)| if u < left { (u, v - left) } else { (v, u - left) })
+                .collect(),
+        };
+        let aug3 = 1 + case % 3;
+        if let Some(why) = check(left, right, &edges, bipartite, forest, case as u64, aug3) {
+            failures += 1;
+            let name = format!("greedy_seed{}_case{}", seed, case);
+            let saved = if bipartite {
+                dump::saved(dump::bipartite_edge_list(&dump_dir, &format!("{}.txt", name), left, right, &edges))
+            } else {
+                dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), left, &edges))
+            };
+            println!("  FAIL  case {:<5} {}{} {}x{} m={} (--seed {} --aug3-passes {}): {}", case,
+                     if bipartite { "bipartite" } else { "general" }, if forest { " forest" } else { "" },
+                     left, right, edges.len(), case, aug3, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite, forests)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,171 @@
+/*
+ * Randomized test of the parallel Hopcroft-Karp phases against the
+ * sequential ones
+ *
+ * Generates seeded random bipartite graphs and runs
+ * HopcroftKarp::maximum_matching_parallel (`hopcroft_karp --threads N`) on
+ * 2 to 8 threads, checking that
+ *   - the matching is valid: graph edges, no vertex twice,
+ *   - it is as large as the sequential phases' maximum matching,
+ *   - a seeded start (hopcroft_karp --init-matching) stays matched, and
+ *     the per-vertex queries (mate, unmatched_vertices, ...) agree,
+ * also with the --numa layout on 1 to 3 pretend nodes (numa.rs), whose
+ * per-node blocks and lists must give the same.
+ * The shapes are sparse random graphs, dense ones, and ladders whose only
+ * augmenting paths run the length of the graph from a greedy start.
+ * Two cases in three are small (up to --max-n per side); the rest have up
+ * to 50 x --max-n, so that BFS layers and the free vertices span several
+ * chunks and really are split among the threads.
+ *
+ * A failing instance is written to the dump directory in the "left right
+ * m" format, ready for `hopcroft_karp <name>.txt --threads N`.
+ *
+ * Usage: hk_parallel_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+use hopcroft_karp::HopcroftKarp;
+
+fn check(left: usize, right: usize, edges: &[(usize, usize)], threads: usize, nodes: usize, greedy: i32, seeded: bool) -> Option<String> {
+    let size = HopcroftKarp::new(left, right, edges).maximum_matching(0).len();
+    /* every other pair of a greedy matching, as an --init-matching start */
+    let start: Vec<(usize, usize)> = if seeded {
+        HopcroftKarp::new(left, right, edges).maximum_matching(1).into_iter().step_by(2).collect()
+    } else {
+        Vec::new()
+    };
+    let mut hk = HopcroftKarp::new(left, right, edges);
+    /* --numa, on as many pretend nodes as the case says, all on CPU 0 */
+    if nodes > 0 {
+        hk.numa = Some(vec![vec![0]; nodes]);
+    }
+    hk.seed(&start);
+    let matching = hk.maximum_matching_parallel(greedy, threads);
+
+    let mut sorted = edges.to_vec();
+    sorted.sort_unstable();
+    let mut used_left = vec![false; left];
+    let mut used_right = vec![false; right];
+    for &(u, v) in &matching {
+        if u >= left || v >= right || sorted.binary_search(&(u, v)).is_err() {
+            return Some(format!("pair ({}, {}) is not an edge", u, v));
+        }
+        if used_left[u] || used_right[v] {
+            return Some(format!("pair ({}, {}) reuses a vertex", u, v));
+        }
+        used_left[u] = true;
+        used_right[v] = true;
+    }
+    if matching.len() != size {
+        return Some(format!("{} pairs, the sequential phases find {}", matching.len(), size));
+    }
+    if let Some(&(u, v)) = start.iter().find(|&&(u, v)| !used_left[u] || !used_right[v]) {
+        return Some(format!("seeded pair ({}, {}) left a vertex free", u, v));
+    }
+    /* the per-vertex queries number right vertex v as left + v */
+    let mut mate = vec![None; left + right];
+    for &(u, v) in &matching {
+        mate[u] = Some(left + v);
+        mate[left + v] = Some(u);
+    }
+    if let Some(x) = (0..left + right).find(|&x| hk.mate(x) != mate[x] || hk.is_matched(x) != mate[x].is_some()) {
+        return Some(format!("mate({}) is {:?}, the matching says {:?}", x, hk.mate(x), mate[x]));
+    }
+    let free: Vec<usize> = (0..left + right).filter(|&x| mate[x].is_none()).collect();
+    if hk.unmatched_vertices().collect::<Vec<_>>() != free || hk.matching_size() != matching.len() {
+        return Some("unmatched_vertices or matching_size disagree with the matching".to_string());
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, usize, Vec<(usize, usize)>) {
+    let left = 1 + rng.below(max_n as u64) as usize;
+    let right = 1 + rng.below(max_n as u64) as usize;
+    let mut edges = Vec::new();
+    match rng.below(3) {
+        // Average degree 1 to 4: many short paths, some long ones
+        0 => {
+            let m = (left + right) / 2 + rng.below(2 * (left + right) as u64) as usize;
+            for _ in 0..m {
+                edges.push((rng.below(left as u64) as usize, rng.below(right as u64) as usize));
+            }
+        }
+        // Dense, capped: large frontiers in few layers
+        1 => {
+            let p = (rng.next_f64() * 0.5).min(20.0 / right as f64);
+            for u in 0..left {
+                for v in 0..right {
+                    if rng.next_f64() < p { edges.push((u, v)); }
+                }
+            }
+        }
+        // Ladders u-u, u-(u+1): greedy takes u-(u+1) first where it can,
+        // leaving one path through the whole rung chain
+        _ => {
+            let n = left.min(right);
+            for u in 0..n {
+                if u + 1 < n { edges.push((u, u + 1)); }
+                edges.push((u, u));
+            }
+            for _ in 0..rng.below(n as u64 / 8 + 1) {
+                edges.push((rng.below(n as u64) as usize, rng.below(n as u64) as usize));
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (left, right, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 3000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("hk_parallel_failures").to_string();
+    if max_n == 0 {
+        eprintln!("Error: --max-n must be positive");
+        std::process::exit(1);
+    }
+
+    println!("Parallel Hopcroft-Karp vs. sequential: {} cases, seed {}, up to {} per side ({} in large cases)",
+             cases, seed, max_n, 50 * max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let large = case % 3 == 2;
+        let (left, right, edges) = random_graph(&mut rng, if large { 50 * max_n } else { max_n });
+        let threads = 2 + case % 7;
+        let greedy = (case / 7 % 5) as i32;
+        let seeded = case % 4 == 1;
+        let nodes = case % 5 % 4;
+        if let Some(why) = check(left, right, &edges, threads, nodes, greedy, seeded) {
+            failures += 1;
+            let name = format!("hk_parallel_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::bipartite_edge_list(&dump_dir, &format!("{}.txt", name), left, right, &edges));
+            println!("  FAIL  case {:<5} {} x {}, {} edges, {} threads, {} nodes: {}", case, left, right, edges.len(), threads, nodes, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
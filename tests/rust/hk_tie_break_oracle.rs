@@ -0,0 +1,167 @@
+/*
+ * Randomized test of the Hopcroft-Karp weighted tie-break against
+ * exhaustive search
+ *
+ * Generates seeded random small weighted bipartite graphs (negative and
+ * zero weights, repeated edges with different weights) and checks
+ * HopcroftKarp::tie_break in both directions:
+ *   - the matching is valid (graph edges, no vertex twice) and has the
+ *     size of Hopcroft-Karp's maximum matching,
+ *   - the reported weight is the sum of its edges' best weights,
+ *   - that weight is the least (greatest with heavier) over all maximum
+ *     matchings, found here by branching over the left vertices,
+ *   - the solver keeps it: a further maximum_matching call returns it,
+ *     and its per-vertex queries (mate, unmatched_vertices, ...) agree.
+ *
+ * A failing instance is written to the dump directory in the weighted
+ * "left right m" format, ready for `hopcroft_karp --tie-break`.
+ *
+ * Usage: hk_tie_break_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+const NONE: i64 = i64::MIN;
+
+/* Best (size, weight) over matchings of left vertices u.. avoiding `used`;
+ * w[u][v] is the best weight of u-v, or NONE. */
+fn exhaustive(w: &[Vec<i64>], u: usize, used: &mut Vec<bool>, heavier: bool) -> (usize, i64) {
+    if u == w.len() {
+        return (0, 0);
+    }
+    let better = |a: (usize, i64), b: (usize, i64)| {
+        a.0 > b.0 || (a.0 == b.0 && if heavier { a.1 > b.1 } else { a.1 < b.1 })
+    };
+    let mut best = exhaustive(w, u + 1, used, heavier);
+    for v in 0..used.len() {
+        if used[v] || w[u][v] == NONE { continue; }
+        used[v] = true;
+        let (s, x) = exhaustive(w, u + 1, used, heavier);
+        used[v] = false;
+        if better((s + 1, x + w[u][v]), best) {
+            best = (s + 1, x + w[u][v]);
+        }
+    }
+    best
+}
+
+fn check(left: usize, right: usize, edges: &[(usize, usize, i64)], heavier: bool) -> Option<String> {
+    let mut w = vec![vec![NONE; right]; left];
+    for &(u, v, x) in edges {
+        w[u][v] = if w[u][v] == NONE { x } else if heavier { w[u][v].max(x) } else { w[u][v].min(x) };
+    }
+    let plain: Vec<(usize, usize)> = edges.iter().map(|&(u, v, _)| (u, v)).collect();
+    let mut hk = hopcroft_karp::HopcroftKarp::new(left, right, &plain);
+    let size = hk.maximum_matching(0).len();
+    let (matching, weight) = hk.tie_break(edges, heavier);
+
+    let mut used_left = vec![false; left];
+    let mut used_right = vec![false; right];
+    let mut sum = 0;
+    for &(u, v) in &matching {
+        if u >= left || v >= right || w[u][v] == NONE {
+            return Some(format!("({}, {}) is not an edge", u, v));
+        }
+        if used_left[u] || used_right[v] {
+            return Some(format!("({}, {}) reuses a vertex", u, v));
+        }
+        used_left[u] = true;
+        used_right[v] = true;
+        sum += w[u][v];
+    }
+    if hk.maximum_matching(0) != matching {
+        return Some("the solver's pairs differ from the returned matching".to_string());
+    }
+    /* the per-vertex queries number right vertex v as left + v */
+    let mut mate = vec![None; left + right + 1];
+    for &(u, v) in &matching {
+        mate[u] = Some(left + v);
+        mate[left + v] = Some(u);
+    }
+    if let Some(v) = (0..=left + right).find(|&v| hk.mate(v) != mate[v] || hk.is_matched(v) != mate[v].is_some()) {
+        return Some(format!("mate({}) = {:?}, the matching says {:?}", v, hk.mate(v), mate[v]));
+    }
+    if !hk.unmatched_vertices().eq((0..left + right).filter(|&v| mate[v].is_none())) || hk.matching_size() != matching.len() {
+        return Some("unmatched_vertices or matching_size disagree with the matching".to_string());
+    }
+    if matching.len() != size {
+        return Some(format!("{} pairs, Hopcroft-Karp {}", matching.len(), size));
+    }
+    if sum != weight {
+        return Some(format!("reported weight {}, edges sum to {}", weight, sum));
+    }
+    let (opt_size, opt_weight) = exhaustive(&w, 0, &mut vec![false; right], heavier);
+    if opt_size != size || opt_weight != weight {
+        return Some(format!("{} pairs of weight {}, optimum {} of weight {}", size, weight, opt_size, opt_weight));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, usize, Vec<(usize, usize, i64)>) {
+    let left = rng.below(max_n as u64 + 1) as usize;
+    let right = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.6;
+    let span = [3, 20, 1000][rng.below(3) as usize];
+    let mut edges = Vec::new();
+    for u in 0..left {
+        for v in 0..right {
+            if rng.next_f64() < p {
+                edges.push((u, v, rng.below(2 * span + 1) as i64 - span as i64));
+                if rng.below(8) == 0 {
+                    edges.push((u, v, rng.below(2 * span + 1) as i64 - span as i64));
+                }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (left, right, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 7);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("hk_tie_break_failures").to_string();
+    if max_n > 9 {
+        eprintln!("Error: --max-n is at most 9 (the exhaustive search is over all matchings)");
+        std::process::exit(1);
+    }
+
+    println!("Hopcroft-Karp tie-break vs. exhaustive search: {} cases, seed {}, sides <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (left, right, edges) = random_graph(&mut rng, max_n);
+        let heavier = case % 2 == 1;
+        if let Some(why) = check(left, right, &edges, heavier) {
+            failures += 1;
+            let name = format!("tie_break_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::weighted_edge_list(&dump_dir, &format!("{}.txt", name), left, Some(right), &edges));
+            println!("  FAIL  case {:<5} {}x{} m={} ({}): {}", case, left, right, edges.len(),
+                     if heavier { "heavier" } else { "lighter" }, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: lighter, heavier)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,152 @@
+/*
+ * Randomized test of 3-uniform hypergraph matching against exhaustive search
+ *
+ * Generates seeded random small hypergraphs (repeated triples allowed, some
+ * with a planted perfect packing) and checks hypergraph_matching.rs:
+ *   - check_packing accepts the first-fit and the local-search packings,
+ *     and both are maximal (every triple meets a chosen one),
+ *   - the local-search packing has no 2-improvement, checked over all
+ *     pairs of triples, and is no smaller than first fit,
+ *   - the maximum, found by branching, is at most the upper bound, which
+ *     is at most twice the packing (three times without the search).
+ *
+ * A failing instance is written to the dump directory in the "n m" triples
+ * format, ready for `combi hypermatch`.
+ *
+ * Usage: hypergraph_matching_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/hypergraph_matching.rs"]
+mod hypergraph_matching;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+fn meets(a: &[usize; 3], b: &[usize; 3]) -> bool {
+    a.iter().any(|v| b.contains(v))
+}
+
+/* Largest number of disjoint triples among triples[i..] avoiding `used`. */
+fn maximum(triples: &[[usize; 3]], i: usize, used: &mut Vec<bool>) -> usize {
+    if i == triples.len() {
+        return 0;
+    }
+    let skip = maximum(triples, i + 1, used);
+    let t = triples[i];
+    if t.iter().any(|&v| used[v]) {
+        return skip;
+    }
+    for &v in &t { used[v] = true; }
+    let take = 1 + maximum(triples, i + 1, used);
+    for &v in &t { used[v] = false; }
+    skip.max(take)
+}
+
+fn check(n: usize, triples: &[[usize; 3]]) -> Option<String> {
+    let first = hypergraph_matching::pack(n, triples, false);
+    let local = hypergraph_matching::pack(n, triples, true);
+    for &(name, p) in &[("first fit", &first.triples), ("local search", &local.triples)] {
+        let errors = hypergraph_matching::check_packing(n, triples, p);
+        if !errors.is_empty() {
+            return Some(format!("{}: {:?}: {:?}", name, p, errors));
+        }
+        if let Some(t) = triples.iter().find(|t| !p.iter().any(|c| meets(t, c))) {
+            return Some(format!("{}: {:?} is not maximal, {:?} is free", name, p, t));
+        }
+    }
+    if first.triples.len() != first.greedy_size || local.greedy_size != first.greedy_size
+        || local.triples.len() < first.triples.len() {
+        return Some(format!("sizes: first fit {} (reported {}), local search {} from {}",
+                            first.triples.len(), first.greedy_size, local.triples.len(), local.greedy_size));
+    }
+    let p = &local.triples;
+    for s in p {
+        let blocked_by_s_only = |t: &&[usize; 3]| !p.contains(t) && meets(t, s)
+            && p.iter().all(|c| c == s || !meets(t, c));
+        let cands: Vec<&[usize; 3]> = triples.iter().filter(blocked_by_s_only).collect();
+        for (i, a) in cands.iter().enumerate() {
+            if let Some(b) = cands[i + 1..].iter().find(|b| !meets(a, b)) {
+                return Some(format!("local search: {:?} -> {:?}, {:?} improves {:?}", s, a, b, p));
+            }
+        }
+    }
+    let nu = maximum(triples, 0, &mut vec![false; n]);
+    for &(name, size, improved) in &[("first fit", first.triples.len(), false), ("local search", p.len(), true)] {
+        let upper = hypergraph_matching::upper_bound(n, triples, size, improved);
+        if upper < nu || upper > if improved { 2 } else { 3 } * size {
+            return Some(format!("{}: {} triples, upper bound {}, maximum {}", name, size, upper, nu));
+        }
+    }
+    None
+}
+
+fn random_hypergraph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<[usize; 3]>) {
+    let n = 3 + rng.below(max_n as u64 - 2) as usize;
+    let m = rng.below(2 * n as u64 + 1) as usize;
+    let mut triples = Vec::new();
+    if rng.below(3) == 0 {
+        let mut perm: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut perm);
+        for c in perm.chunks_exact(3) {
+            triples.push([c[0], c[1], c[2]]);
+        }
+    }
+    while triples.len() < m {
+        let a = rng.below(n as u64) as usize;
+        let b = rng.below(n as u64) as usize;
+        let c = rng.below(n as u64) as usize;
+        if a != b && b != c && a != c {
+            triples.push([a, b, c]);
+        }
+    }
+    for t in &mut triples { t.sort_unstable(); }
+    rng.shuffle(&mut triples);
+    (n, triples)
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, triples: &[[usize; 3]]) -> io::Result<PathBuf> {
+    let body = triples.iter().map(|t| format!("{} {} {}", t[0], t[1], t[2]));
+    dump::lines(dir, &format!("{}.txt", name), Some(format!("{} {}", n, triples.len())).into_iter().chain(body))
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 5000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 15);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("hypergraph_matching_failures").to_string();
+    if max_n < 3 {
+        eprintln!("Error: --max-n must be at least 3");
+        std::process::exit(1);
+    }
+
+    println!("Hypergraph matching vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, triples) = random_hypergraph(&mut rng, max_n);
+        if let Some(why) = check(n, &triples) {
+            failures += 1;
+            let name = format!("hypergraph_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, n, &triples));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, triples.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,189 @@
+/*
+ * Randomized test of --init-matching
+ *
+ * Generates seeded random edge lists, general on even cases and bipartite
+ * on odd ones (ids past n, huge ids, self-loops and repeated edges, as
+ * tests/rust/common/edge_lists.rs makes them), and a random matching of
+ * them, a third of the time with a random pair added. Checks:
+ *   - the --init-matching checks (matching_io::check_pairs, then
+ *     verify::validate) refuse exactly the inputs that are not matchings,
+ *   - from a valid one every general matcher (matchers::solve_from), or
+ *     Hopcroft-Karp (seed) on the bipartite cases, reaches the maximum size,
+ *     keeps the given vertices matched and reports the start as its initial
+ *     size.
+ *
+ * A failing instance is written to the dump directory as <name>.txt ("n m"
+ * or "left right m") and the matching as <name>.match, ready for
+ * `combi solve <name>.txt --init-matching <name>.match` (or
+ * `hopcroft_karp` for the bipartite ones).
+ *
+ * Usage: init_matching_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/json.rs"]
+mod json;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/verify.rs"]
+mod verify;
+
+struct Instance {
+    bipartite: bool,
+    left: usize,
+    right: usize,
+    edges: Vec<(usize, usize)>,
+    init: Vec<(usize, usize)>,
+    bad: bool,
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize, bipartite: bool) -> Instance {
+    let left = rng.below(max_n as u64 + 1) as usize;
+    let right = if bipartite { rng.below(max_n as u64 + 1) as usize } else { left };
+    let edges = edge_lists::arbitrary(rng, left.max(right));
+
+    /* a random matching: in-range edges in random order, each taken
+     * with probability 1/2 while both ends are free */
+    let mut order: Vec<(usize, usize)> = edges.iter().cloned()
+        .filter(|&(u, v)| u < left && v < right && (bipartite || u != v))
+        .collect();
+    rng.shuffle(&mut order);
+    let (mut used_left, mut used_right) = (vec![false; left], vec![false; right]);
+    let mut init = Vec::new();
+    for (u, v) in order {
+        let free = if bipartite { !used_left[u] && !used_right[v] } else { !used_left[u] && !used_left[v] };
+        if free && rng.below(2) == 0 {
+            used_left[u] = true;
+            if bipartite { used_right[v] = true; } else { used_left[v] = true; }
+            init.push((u, v));
+        }
+    }
+    let bad_pair = rng.below(3) == 0;
+    if bad_pair && left > 0 && right > 0 {
+        let (u, v) = (rng.below(left as u64) as usize, rng.below(right as u64) as usize);
+        init.insert(rng.below(init.len() as u64 + 1) as usize, (u, v));
+    }
+    let bad = if bipartite {
+        let mut sides = (vec![false; left], vec![false; right]);
+        init.iter().any(|&(u, v)| {
+            let reused = sides.0[u] || sides.1[v];
+            sides.0[u] = true;
+            sides.1[v] = true;
+            reused || !edges.contains(&(u, v))
+        })
+    } else {
+        edge_lists::matching_error(left, &edges, &init).is_some()
+    };
+    Instance { bipartite, left, right, edges, init, bad }
+}
+
+fn check(c: &Instance) -> Option<String> {
+    let (left, right, bipartite, init) = (c.left, c.right, c.bipartite, &c.init);
+    let (mut errors, _) = matching_io::check_pairs(init, (left, right), bipartite);
+    if errors.is_empty() {
+        errors = verify::validate((left, right), &c.edges, init, bipartite).errors;
+    }
+    if errors.is_empty() == c.bad {
+        return Some(format!("checks {} {:?}: {:?}", if c.bad { "accepted" } else { "refused" }, init, errors));
+    }
+    if c.bad {
+        return None;
+    }
+    let covered = |matching: &[(usize, usize)]| {
+        let mut matched = (vec![false; left], vec![false; right]);
+        for &(u, v) in matching {
+            matched.0[u] = true;
+            if bipartite { matched.1[v] = true; } else { matched.0[v] = true; }
+        }
+        init.iter().all(|&(u, v)| matched.0[u] && if bipartite { matched.1[v] } else { matched.0[v] })
+    };
+    if bipartite {
+        let size = hopcroft_karp::HopcroftKarp::new(left, right, &c.edges).maximum_matching(0).len();
+        let mut hk = hopcroft_karp::HopcroftKarp::new(left, right, &c.edges);
+        hk.seed(init);
+        let got = hk.maximum_matching(0);
+        if got.len() != size || !covered(&got) {
+            return Some(format!("hopcroft_karp from {:?}: {:?}, maximum {}", init, got, size));
+        }
+        return None;
+    }
+    let size = matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, left, &c.edges).unwrap().len();
+    for algo in matchers::GENERAL_ALGORITHMS.iter() {
+        let sol = matchers::solve_from(algo, left, &c.edges, init).unwrap();
+        if let Some(e) = edge_lists::matching_error(left, &c.edges, &sol.matching) {
+            return Some(format!("{} from {:?}: {}", algo, init, e));
+        }
+        if sol.matching.len() != size || !covered(&sol.matching) || sol.greedy_size != init.len() {
+            return Some(format!("{} from {:?}: {:?}, maximum {}", algo, init, sol.matching, size));
+        }
+    }
+    None
+}
+
+fn dump_case(dir: &str, name: &str, c: &Instance) -> io::Result<PathBuf> {
+    let graph = if c.bipartite {
+        dump::bipartite_edge_list(dir, &format!("{}.txt", name), c.left, c.right, &c.edges)?
+    } else {
+        dump::edge_list(dir, &format!("{}.txt", name), c.left, &c.edges)?
+    };
+    /* as given, not canonical: a bad pair stays where it was inserted */
+    let pairs = c.init.iter().map(|&(u, v)| format!("{} {}", u, v));
+    dump::lines(dir, &format!("{}.match", name), Some(c.init.len().to_string()).into_iter().chain(pairs))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("init_matching_failures").to_string();
+
+    println!("Initial matchings vs. the checks and the maximum size: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let c = random_instance(&mut rng, max_n, case % 2 == 1);
+        if let Some(why) = check(&c) {
+            failures += 1;
+            let name = format!("init_matching_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &c));
+            println!("  FAIL  case {:<5} {} {}x{} m={} |init|={}: {}", case,
+                     if c.bipartite { "bipartite" } else { "general" }, c.left, c.right, c.edges.len(), c.init.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
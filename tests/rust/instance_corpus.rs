@@ -0,0 +1,220 @@
+/*
+ * Regression test over the committed instance corpus (tests/instances/)
+ *
+ * Reads the manifest sizes.txt ("<path> <size>" per line, '#' comments)
+ * and loads every instance it names with the suite's own loaders
+ * (graph_io.rs): general/ files in the "n m" format, bipartite/ files in
+ * the "left right m" format. Then it runs
+ *   - all five general matchers and "auto" (matchers.rs) on every file, a
+ *     bipartite one with its right ids after the left ones, and
+ *   - Hopcroft-Karp and Pothen-Fan (PF and PF+) on the bipartite files,
+ * and checks that each result is a valid matching (graph edges, no vertex
+ * twice) of the manifest's size. A general file is also 2-colored
+ * (graph_io::bipartition, behind hopcroft_karp --general): the coloring
+ * must be proper, or the odd cycle it reports one of the graph's. When a <name>.init matching file sits
+ * next to an instance, every solver runs once more from it.
+ *
+ * The instances are small and hand-picked (odd cycles, Petersen, a Tutte
+ * barrier, a blossom the augmenting path must be lifted through, loader
+ * edge cases such as self-loops, repeated edges and comments) or written
+ * by `combi gen`, so the whole corpus takes well under a second.
+ *
+ * Usage: instance_corpus --dir DIR   (DIR: the repository's tests/instances)
+ * Exit status 1 if any run failed, --dir was missing, or the manifest or an
+ * instance did not load.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/common/rust/json.rs"]
+mod json;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/pothen-fan/rust/pothen_fan.rs"]
+#[allow(dead_code)]
+mod pothen_fan;
+#[path = "../../algorithms/common/rust/verify.rs"]
+mod verify;
+
+use graph_io::LoadOptions;
+use hopcroft_karp::HopcroftKarp;
+use pothen_fan::PothenFan;
+
+/* The manifest's (path, size) lines. */
+fn read_manifest(path: &Path) -> Result<Vec<(String, usize)>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut entries = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let t = line.split('#').next().unwrap_or("").trim();
+        if t.is_empty() { continue; }
+        let parts: Vec<&str> = t.split_whitespace().collect();
+        match (parts.len(), parts.get(1).and_then(|s| s.parse().ok())) {
+            (2, Some(size)) => entries.push((parts[0].to_string(), size)),
+            _ => return Err(format!("{}: line {}: expected \"<path> <size>\"", path.display(), i + 1)),
+        }
+    }
+    Ok(entries)
+}
+
+/* Ok if `matching` is a matching of (n, edges) with `expected` pairs. */
+fn check(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)], expected: usize) -> Result<(), String> {
+    let report = verify::validate_general(n, edges, matching);
+    if let Some(e) = report.errors.first() {
+        return Err(e.clone());
+    }
+    if report.size != expected {
+        return Err(format!("size {}, expected {}", report.size, expected));
+    }
+    Ok(())
+}
+
+/* The starting matching next to `path`, if there is one. */
+fn load_init(path: &Path) -> Result<Option<Vec<(usize, usize)>>, String> {
+    let init = path.with_extension("init");
+    if !init.exists() {
+        return Ok(None);
+    }
+    matching_io::load_matching(&init.to_string_lossy()).map(Some)
+}
+
+/* Ok if bipartition() gives a proper 2-coloring of (n, edges), or an odd
+ * cycle whose every step is an edge. */
+fn check_bipartition(n: usize, edges: &[(usize, usize)]) -> Result<(), String> {
+    let mut sorted: Vec<(usize, usize)> = edges.iter().map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    sorted.sort_unstable();
+    match graph_io::bipartition(n, edges, &graph_io::InputNames::default()) {
+        Ok(side) => match edges.iter().find(|&&(u, v)| u != v && side[u] == side[v]) {
+            Some(&(u, v)) => Err(format!("edge ({}, {}) inside one class", u, v)),
+            None => Ok(()),
+        },
+        Err(error::SuiteError::NotBipartite { cycle }) => {
+            if cycle.len() % 2 != 0 || cycle.first() != cycle.last() {
+                return Err(format!("{:?} is not a closed odd cycle", cycle));
+            }
+            match cycle.windows(2).find(|w| sorted.binary_search(&(w[0].min(w[1]), w[0].max(w[1]))).is_err()) {
+                Some(w) => Err(format!("odd cycle step ({}, {}) is not an edge", w[0], w[1])),
+                None => Ok(()),
+            }
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/* (run label, outcome) per solver run on one instance. */
+fn run_general(n: usize, edges: &[(usize, usize)], init: Option<&[(usize, usize)]>, expected: usize)
+               -> Vec<(String, Result<(), String>)> {
+    let mut results = Vec::new();
+    for algo in matchers::GENERAL_ALGORITHMS.iter().chain([matchers::AUTO_ALGORITHM].iter()) {
+        let solved = matchers::maximum_matching(algo, n, edges);
+        results.push((algo.to_string(), solved.and_then(|m| check(n, edges, &m, expected))));
+        if let Some(init) = init {
+            let solved = matchers::solve_from(algo, n, edges, init).map(|s| s.matching);
+            results.push((format!("{} --init", algo), solved.and_then(|m| check(n, edges, &m, expected))));
+        }
+    }
+    results
+}
+
+fn run_bipartite(left: usize, right: usize, edges: &[(usize, usize)], init: Option<&[(usize, usize)]>,
+                 expected: usize) -> Vec<(String, Result<(), String>)> {
+    /* the union graph, right ids after the left ones, for check() */
+    let n = left + right;
+    let shifted: Vec<(usize, usize)> = edges.iter().map(|&(u, v)| (u, left + v)).collect();
+    let shift = |m: Vec<(usize, usize)>| -> Vec<(usize, usize)> { m.into_iter().map(|(u, v)| (u, left + v)).collect() };
+    let start = init.unwrap_or(&[]);
+    let shifted_init = init.map(|m| shift(m.to_vec()));
+
+    let mut results = run_general(n, &shifted, shifted_init.as_deref(), expected);
+    for seeded in [false, true] {
+        if seeded && init.is_none() { continue; }
+        let suffix = if seeded { " --init" } else { "" };
+        let mut hk = HopcroftKarp::new(left, right, edges);
+        if seeded { hk.seed(start); }
+        results.push((format!("hopcroft-karp{}", suffix), check(n, &shifted, &shift(hk.maximum_matching(0)), expected)));
+        for fairness in [false, true] {
+            let mut pf = PothenFan::new(left, right, edges);
+            pf.fairness = fairness;
+            if seeded { pf.seed(start); }
+            let label = format!("{}{}", if fairness { "pothen-fan+" } else { "pothen-fan" }, suffix);
+            results.push((label, check(n, &shifted, &shift(pf.maximum_matching(false)), expected)));
+        }
+    }
+    results
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let dir = match args::value_of(&args, "--dir") {
+        Some(d) => Path::new(d).to_path_buf(),
+        None => {
+            eprintln!("Usage: {} --dir DIR   (DIR: the repository's tests/instances)", args[0]);
+            std::process::exit(1);
+        }
+    };
+    let manifest = match read_manifest(&dir.join("sizes.txt")) {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    println!("Instance corpus: {} instances in {}", manifest.len(), dir.display());
+
+    let opts = LoadOptions::default();
+    let (mut runs, mut failures) = (0, 0);
+    for (name, expected) in &manifest {
+        let path = dir.join(name);
+        let file = path.to_string_lossy().to_string();
+        let loaded = load_init(&path).and_then(|init| {
+            if name.starts_with("bipartite/") {
+                let (left, right, edges) = graph_io::load_bipartite_graph(&file, &opts).map_err(|e| e.to_string())?;
+                Ok(run_bipartite(left, right, &edges, init.as_deref(), *expected))
+            } else {
+                let (n, edges) = graph_io::load_graph(&file, &opts).map_err(|e| e.to_string())?;
+                let mut results = run_general(n, &edges, init.as_deref(), *expected);
+                results.push(("bipartition".to_string(), check_bipartition(n, &edges)));
+                Ok(results)
+            }
+        });
+        match loaded {
+            Err(e) => {
+                failures += 1;
+                println!("  FAIL  {}: {}", name, e);
+            }
+            Ok(results) => {
+                for (label, outcome) in results {
+                    runs += 1;
+                    if let Err(why) = outcome {
+                        failures += 1;
+                        println!("  FAIL  {:<40} {}: {}", name, label, why);
+                    }
+                }
+            }
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL PASS ({} instances, {} runs)", manifest.len(), runs);
+    } else {
+        println!("{} FAILURE(S)", failures);
+        std::process::exit(1);
+    }
+}
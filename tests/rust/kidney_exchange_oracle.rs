@@ -0,0 +1,172 @@
+/*
+ * Randomized test of kidney exchange against exhaustive search
+ *
+ * Generates seeded random small compatibility graphs (any density, with
+ * self-loops and repeated arcs, with and without altruistic donors) and
+ * checks kidney_exchange.rs for cycles and chains of at most 2 and of at
+ * most 3 vertices:
+ *   - check_exchange accepts the result,
+ *   - its transplant count is the optimum, found here by a dynamic program
+ *     over vertex subsets with the cycles and chains built from the arcs,
+ *   - the 3-vertex search reports itself optimal.
+ * Pools without altruists rotate through the general matchers.
+ *
+ * A failing instance is written to the dump directory as an "n m" arc list
+ * plus an ".altruists" file, ready for `combi kidney --altruists`.
+ *
+ * Usage: kidney_exchange_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/kidney_exchange.rs"]
+mod kidney_exchange;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/vertex_weighted.rs"]
+mod vertex_weighted;
+
+use kidney_exchange::Pool;
+
+struct Instance {
+    n: usize,
+    arcs: Vec<(usize, usize)>,
+    altruists: Vec<usize>,
+}
+
+/* Most transplants with cycles and chains of at most max_len vertices. */
+fn exhaustive(inst: &Instance, max_len: usize) -> usize {
+    let n = inst.n;
+    let mut alt = vec![false; n];
+    for &a in &inst.altruists { alt[a] = true; }
+    let mut arc = vec![vec![false; n]; n];
+    for &(u, v) in &inst.arcs { arc[u][v] = true; }
+    let pair = |x: usize| !alt[x];
+
+    /* (vertex mask, transplants) of every cycle and chain */
+    let mut cols: Vec<(usize, usize)> = Vec::new();
+    for a in 0..n {
+        for b in 0..n {
+            if a == b || !arc[a][b] || !pair(b) { continue; }
+            if pair(a) && arc[b][a] { cols.push((1 << a | 1 << b, 2)); }
+            if !pair(a) { cols.push((1 << a | 1 << b, 1)); }
+            if max_len < 3 { continue; }
+            for (c, from_c) in arc.iter().enumerate() {
+                if c == a || c == b || !arc[b][c] || !pair(c) { continue; }
+                if pair(a) && from_c[a] { cols.push((1 << a | 1 << b | 1 << c, 3)); }
+                if !pair(a) { cols.push((1 << a | 1 << b | 1 << c, 2)); }
+            }
+        }
+    }
+    let mut best = vec![0usize; 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros();
+        let mut b = best[s & !(1 << v)];
+        for &(mask, value) in &cols {
+            if mask >> v & 1 == 1 && mask & s == mask {
+                b = b.max(value + best[s & !mask]);
+            }
+        }
+        best[s] = b;
+    }
+    best[(1 << n) - 1]
+}
+
+fn check(inst: &Instance, algo: &str) -> Option<String> {
+    let pool = Pool::new(inst.n, &inst.arcs, &inst.altruists);
+    for &max_len in &kidney_exchange::MAX_CYCLE_LENGTHS {
+        let ex = match kidney_exchange::solve(algo, &pool, max_len, usize::MAX) {
+            Ok(ex) => ex,
+            Err(e) => return Some(format!("max-cycle {}: {}", max_len, e)),
+        };
+        let errors = kidney_exchange::check_exchange(&pool, &ex, max_len);
+        if !errors.is_empty() {
+            return Some(format!("max-cycle {}: {:?} {:?}: {:?}", max_len, ex.cycles, ex.chains, errors));
+        }
+        if !ex.optimal {
+            return Some(format!("max-cycle {}: not proven optimal", max_len));
+        }
+        let best = exhaustive(inst, max_len);
+        if ex.transplants != best {
+            return Some(format!("max-cycle {}: {} transplants, exhaustive search finds {}",
+                                max_len, ex.transplants, best));
+        }
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> Instance {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.6;
+    let mut arcs = Vec::new();
+    for u in 0..n {
+        for v in 0..n {
+            if rng.next_f64() < p {
+                arcs.push((u, v));
+                if rng.below(10) == 0 { arcs.push((u, v)); }
+            }
+        }
+    }
+    rng.shuffle(&mut arcs);
+    let altruists = if rng.below(2) == 0 {
+        Vec::new()
+    } else {
+        (0..n).filter(|_| rng.below(5) == 0).collect()
+    };
+    Instance { n, arcs, altruists }
+}
+
+fn dump_case(dir: &str, name: &str, inst: &Instance) -> io::Result<PathBuf> {
+    let arcs = dump::edge_list(dir, &format!("{}.txt", name), inst.n, &inst.arcs)?;
+    dump::lines(dir, &format!("{}.altruists", name), &inst.altruists)?;
+    Ok(arcs)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("kidney_exchange_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Kidney exchange vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let inst = random_instance(&mut rng, max_n);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(&inst, algo) {
+            failures += 1;
+            let name = format!("kidney_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &inst));
+            println!("  FAIL  case {:<5} n={} arcs={} altruists={} ({}): {}",
+                     case, inst.n, inst.arcs.len(), inst.altruists.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x 2 cycle limits)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,327 @@
+/*
+ * Randomized agreement test: every general matcher vs. the brute-force oracle
+ *
+ * Generates seeded random small graphs (sparse, dense, near-perfect,
+ * odd-cycle heavy and crown-heavy shapes), runs all five matchers through matchers.rs and
+ * checks each result is a valid matching of the oracle's size. "phase0"
+ * runs the exact tree/unicyclic pass of phase0.rs with mv-pure on the rest,
+ * "crown" its 2-core peeling and crown reduction the same way, "fold" the
+ * leaf rule and degree-2 folds, with the rest's matching unfolded.
+ * "auto" 2-colors the graph and runs Hopcroft-Karp when it is bipartite.
+ * "gabow-par" is Gabow simple's parallel forest search on three threads,
+ * pinned as --numa pins them (two pretend nodes) when n is odd,
+ * "gabow-blossoms" its search with explicit blossom objects (--blossoms).
+ * "gabow-forest" runs gabow_adaptive.rs's forest phases to the end,
+ * "gabow-adaptive" switches from them to the optimized phases at the
+ * first path longer than one edge.
+ *
+ * Last, mv-pure solves one large graph of petals nested 500000 deep
+ * from a given matching, to the one pair more.
+ *
+ * Each result is also read back through the per-vertex queries (mate,
+ * is_matched, unmatched_vertices, matching_size) of matchers::Solution,
+ * and of GabowSimple for "gabow-blossoms", which must describe it.
+ *
+ * A failing instance is first shrunk (edges dropped while the same matcher
+ * still fails, then vertices relabeled to the ones still in use) and then
+ * written in the standard "n m" edge-list format to the dump directory,
+ * ready to feed straight into the failing binary, next to a DOT drawing
+ * of it with the oracle's maximum matching and its blossoms (dot.rs).
+ *
+ * Usage: matcher_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 if any matcher disagreed.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/adversarial.rs"]
+mod adversarial;
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/dot.rs"]
+mod dot;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../algorithms/brute-force/rust/brute_force.rs"]
+#[allow(dead_code)]
+mod brute_force;
+#[path = "../../algorithms/gabow-simple/rust/gabow_simple.rs"]
+#[allow(dead_code)]
+mod gabow_simple;
+#[path = "../../algorithms/gabow-optimized/rust/gabow_adaptive.rs"]
+#[allow(dead_code)]
+mod gabow_adaptive;
+
+fn oracle_size(n: usize, edges: &[(usize, usize)]) -> usize {
+    brute_force::BruteForce::new(n, edges).maximum_matching().len()
+}
+
+/* Ok if the per-vertex queries (mate, is_matched, unmatched_vertices,
+ * matching_size) describe `matching` on n vertices. */
+fn check_queries(
+    n: usize, matching: &[(usize, usize)], mate: &dyn Fn(usize) -> Option<usize>, is_matched: &dyn Fn(usize) -> bool,
+    unmatched: Vec<usize>, size: usize,
+) -> Result<(), String> {
+    let mut expected = vec![None; n + 1];
+    for &(u, v) in matching {
+        expected[u] = Some(v);
+        expected[v] = Some(u);
+    }
+    for (v, &want) in expected.iter().enumerate() {
+        if mate(v) != want || is_matched(v) != want.is_some() {
+            return Err(format!("mate({}) = {:?}, is_matched {}, but the matching says {:?}",
+                               v, mate(v), is_matched(v), want));
+        }
+    }
+    let free: Vec<usize> = (0..n).filter(|&v| expected[v].is_none()).collect();
+    if unmatched != free {
+        return Err(format!("unmatched_vertices {:?}, expected {:?}", unmatched, free));
+    }
+    if size != matching.len() {
+        return Err(format!("matching_size {} for {} pairs", size, matching.len()));
+    }
+    Ok(())
+}
+
+fn run(algo: &str, n: usize, edges: &[(usize, usize)]) -> Result<Vec<(usize, usize)>, String> {
+    let mode = match algo {
+        "phase0" => phase0::Mode::Components,
+        "crown" => phase0::Mode::Crown,
+        "fold" => phase0::Mode::Fold,
+        "gabow-par" => {
+            let mut g = gabow_simple::GabowSimple::new(n, edges);
+            if n % 2 == 1 {
+                g.numa = Some(vec![vec![0]; 2]);
+            }
+            return Ok(g.maximum_matching_parallel(0, 3));
+        }
+        "gabow-blossoms" => {
+            let mut g = gabow_simple::GabowSimple::new(n, edges);
+            g.explicit_blossoms = true;
+            let matching = g.maximum_matching(0);
+            check_queries(n, &matching, &|v| g.mate(v), &|v| g.is_matched(v), g.unmatched_vertices().collect(),
+                          g.matching_size())?;
+            return Ok(matching);
+        }
+        "gabow-forest" | "gabow-adaptive" => {
+            let length = if algo == "gabow-forest" { 0 } else { 1 };
+            let t = gabow_adaptive::Thresholds { free: 0.0, length, paths: 0 };
+            return Ok(gabow_adaptive::maximum_matching(n, edges, 0, 1, 0, None, &t).0);
+        }
+        _ => {
+            let s = matchers::solve(algo, n, edges, 0, 1, 0)?;
+            check_queries(n, &s.matching, &|v| s.mate(v), &|v| s.is_matched(v), s.unmatched_vertices().collect(),
+                          s.matching_size())?;
+            return Ok(s.matching);
+        }
+    };
+    let split = phase0::run(mode, n, edges).unwrap();
+    let rest = matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, split.rest_n, &split.rest_edges)?;
+    Ok(split.merge(&rest))
+}
+
+/* None if `algo` agrees with the oracle on (n, edges), else what went wrong. */
+fn check(algo: &str, n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let matching = match run(algo, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(e),
+    };
+    let mut used = vec![false; n];
+    for &(u, v) in &matching {
+        if u >= n || v >= n || u == v {
+            return Some(format!("bad pair ({}, {})", u, v));
+        }
+        if !edges.iter().any(|&(a, b)| (a, b) == (u, v) || (a, b) == (v, u)) {
+            return Some(format!("pair ({}, {}) is not an edge", u, v));
+        }
+        if used[u] || used[v] {
+            return Some(format!("pair ({}, {}) reuses a vertex", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+    }
+    let expected = oracle_size(n, edges);
+    if matching.len() != expected {
+        return Some(format!("size {} but oracle says {}", matching.len(), expected));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    if n < 2 {
+        return (n, edges);
+    }
+    match rng.below(5) {
+        // Erdos-Renyi with a density anywhere from very sparse to complete
+        0 => {
+            let p = rng.next_f64() * rng.next_f64();
+            for u in 0..n {
+                for v in (u + 1)..n {
+                    if rng.next_f64() < p { edges.push((u, v)); }
+                }
+            }
+        }
+        // Average degree around 1-3: many components, long augmenting paths
+        1 => {
+            let m = n + rng.below(n as u64 * 2) as usize;
+            for _ in 0..m {
+                let u = rng.below(n as u64) as usize;
+                let v = rng.below(n as u64) as usize;
+                if u != v { edges.push((u, v)); }
+            }
+        }
+        // Chains of odd cycles sharing vertices: nested blossoms
+        2 => {
+            let mut start = 0;
+            while start + 2 < n {
+                let len = 3 + 2 * rng.below(3) as usize;
+                let end = (start + len).min(n);
+                for v in start..end - 1 { edges.push((v, v + 1)); }
+                edges.push((end - 1, start));
+                start = end - 1;
+            }
+        }
+        // Few hubs shared by many vertices of degree 2-3, over a random
+        // core: crowns that survive the leaf rule
+        3 => {
+            let mut perm: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut perm);
+            let hubs = 2 + rng.below(n as u64 / 6) as usize;
+            let core = hubs + rng.below((n - hubs) as u64 / 3 + 1) as usize;
+            let mut picks: Vec<usize> = (0..hubs).collect();
+            for v in hubs..n {
+                if v < core {
+                    for _ in 0..3 {
+                        let u = rng.below(core as u64) as usize;
+                        if u != v { edges.push((perm[u], perm[v])); }
+                    }
+                } else {
+                    rng.shuffle(&mut picks);
+                    for &u in picks.iter().take(2 + rng.below(2) as usize) { edges.push((perm[u], perm[v])); }
+                }
+            }
+        }
+        // Perfect matching plus noise, shuffled labels
+        _ => {
+            let mut perm: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut perm);
+            for i in (0..n - 1).step_by(2) { edges.push((perm[i], perm[i + 1])); }
+            for _ in 0..rng.below(n as u64 + 1) {
+                let u = rng.below(n as u64) as usize;
+                let v = rng.below(n as u64) as usize;
+                if u != v { edges.push((u, v)); }
+            }
+        }
+    }
+    (n, edges)
+}
+
+/* Petals nested NESTED_PAIRS / 2 deep: one copy of adversarial.rs's
+ * nested-blossoms, the free vertex 0 in a triangle with the pair 1-2, then
+ * NESTED_PAIRS pairs (2k+1, 2k+2), each joined to the pair before it by the
+ * edges 2k -- 2k+1 and 2k-1 -- 2k+2, and a free vertex at the end. Started
+ * from the pairs, the one augmenting path runs through every petal;
+ * mv-pure's recursive walk of them overflowed the stack here. */
+const NESTED_PAIRS: usize = 1_000_000;
+
+fn shrink(algo: &str, n: usize, edges: &[(usize, usize)]) -> (usize, Vec<(usize, usize)>) {
+    let mut edges = edges.to_vec();
+    let mut i = 0;
+    while i < edges.len() {
+        let mut fewer = edges.clone();
+        fewer.remove(i);
+        if check(algo, n, &fewer).is_some() { edges = fewer; } else { i += 1; }
+    }
+    let mut label = vec![usize::MAX; n];
+    let mut used = 0;
+    for &(u, v) in &edges {
+        for &x in &[u, v] {
+            if label[x] == usize::MAX { label[x] = used; used += 1; }
+        }
+    }
+    let relabeled: Vec<(usize, usize)> = edges.iter().map(|&(u, v)| (label[u], label[v])).collect();
+    if used < n && check(algo, used, &relabeled).is_some() {
+        return (used, relabeled);
+    }
+    (n, edges)
+}
+
+/* The edge list, and next to it a .dot drawing of the oracle's maximum
+ * matching. */
+fn dump_case(dir: &str, name: &str, n: usize, edges: &[(usize, usize)]) -> io::Result<PathBuf> {
+    let path = dump::edge_list(dir, name, n, edges)?;
+    let maximum = brute_force::BruteForce::new(n, edges).maximum_matching();
+    dot::write_dot(&mut fs::File::create(path.with_extension("dot"))?, n, edges, &maximum, None)?;
+    Ok(path)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 30);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("oracle_failures").to_string();
+    if max_n > brute_force::MAX_N {
+        eprintln!("Error: --max-n is limited to {}", brute_force::MAX_N);
+        std::process::exit(1);
+    }
+
+    println!("Matcher vs. brute-force oracle: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n);
+        for algo in matchers::GENERAL_ALGORITHMS.iter().chain(["auto", "phase0", "crown", "fold", "gabow-par", "gabow-blossoms", "gabow-forest", "gabow-adaptive"].iter()) {
+            if let Some(why) = check(algo, n, &edges) {
+                failures += 1;
+                let (sn, sedges) = shrink(algo, n, &edges);
+                let name = format!("{}_seed{}_case{}.txt", algo, seed, case);
+                let saved = dump::saved(dump_case(&dump_dir, &name, sn, &sedges));
+                println!("  FAIL  {:<15} case {:<5} n={} m={}: {}", algo, case, n, edges.len(), why);
+                println!("        shrunk to n={} m={}: {}", sn, sedges.len(), saved);
+            }
+        }
+    }
+
+    let nested = adversarial::nested_blossoms(2 * NESTED_PAIRS + 2, Some(NESTED_PAIRS));
+    let (n, edges, pairs) = (nested.n, nested.edges, nested.init);
+    match matchers::solve_from("mv-pure", n, &edges, &pairs) {
+        Ok(s) if s.matching.len() == pairs.len() + 1 => {}
+        Ok(s) => {
+            failures += 1;
+            println!("  FAIL  mv-pure on {} nested petals: size {}, expected {}", NESTED_PAIRS / 2, s.matching.len(),
+                     pairs.len() + 1);
+        }
+        Err(e) => {
+            failures += 1;
+            println!("  FAIL  mv-pure on {} nested petals: {}", NESTED_PAIRS / 2, e);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x {} matchers + auto, phase0, crown, fold, gabow-par, gabow-blossoms, gabow-forest, gabow-adaptive; mv-pure on {} nested petals)",
+                 cases, matchers::GENERAL_ALGORITHMS.len(), NESTED_PAIRS / 2);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,265 @@
+/*
+ * Randomized test of the matching-number bounds against the matchers
+ *
+ * Generates seeded random small graphs (general graphs of any density with
+ * self-loops and repeated edges, forests, and bipartite graphs) and checks
+ * matching_bounds.rs against a maximum matching nu:
+ *   - the greedy and Karp-Sipser matchings pass check_matching and are
+ *     maximal (no edge joins two exposed vertices),
+ *   - both are at most nu, and every upper bound is at least nu,
+ *   - Karp-Sipser equals nu on forests, and the LP bound equals nu on
+ *     bipartite graphs,
+ *   - the LP bound does not depend on the warm-start matching,
+ *   - lp_relaxation, seeded with a maximum matching, is at least nu and
+ *     the LP bound, equals nu exactly when no component has a gap, and is
+ *     nu on bipartite graphs; for n <= 12 twice it equals
+ *     n - max_S (isolated(G - S) - |S|), the fractional Tutte-Berge
+ *     formula, by brute force over S,
+ *   - the half-integral optimum (from a warm and a cold start) is a
+ *     matching plus disjoint odd cycles of graph edges, worth the LP
+ *     optimum, and rounds to a matching at most cycles/2 short of nu
+ *     that covers every vertex of the ones and all but one per cycle.
+ * nu comes from the general matchers in rotation.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi bounds`.
+ *
+ * Usage: matching_bounds_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/matching_bounds.rs"]
+mod matching_bounds;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind { General, Forest, Bipartite }
+
+fn maximal(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> bool {
+    let mut used = vec![false; n];
+    for &(u, v) in matching {
+        used[u] = true;
+        used[v] = true;
+    }
+    edges.iter().all(|&(u, v)| u == v || used[u] || used[v])
+}
+
+/* Twice the fractional matching number: n minus the largest surplus of
+ * vertices isolated by deleting a set S over |S|. */
+fn twice_fractional_brute(n: usize, edges: &[(usize, usize)]) -> usize {
+    let mut best = 0usize;
+    for s in 0u32..1 << n {
+        let mut touched = 0u32;
+        for &(u, v) in edges {
+            if u != v && s >> u & 1 == 0 && s >> v & 1 == 0 {
+                touched |= 1 << u | 1 << v;
+            }
+        }
+        let isolated = (!(s | touched) & ((1u64 << n) - 1) as u32).count_ones() as usize;
+        best = best.max(isolated.saturating_sub(s.count_ones() as usize));
+    }
+    n - best
+}
+
+fn check(n: usize, edges: &[(usize, usize)], kind: Kind, algo: &str) -> Option<String> {
+    let maximum = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(e),
+    };
+    let nu = maximum.len();
+    let greedy = matching_bounds::greedy_matching(n, edges);
+    let ks = matching_bounds::karp_sipser(n, edges);
+    for &(name, m) in &[("greedy", &greedy), ("karp-sipser", &ks)] {
+        let errors = matching_bounds::check_matching(n, edges, m);
+        if !errors.is_empty() {
+            return Some(format!("{}: {:?}: {:?}", name, m, errors));
+        }
+        if !maximal(n, edges, m) {
+            return Some(format!("{}: {:?} is not maximal", name, m));
+        }
+        if m.len() > nu {
+            return Some(format!("{}: {} pairs, nu = {}", name, m.len(), nu));
+        }
+    }
+    let lp = matching_bounds::lp_bound(n, edges, &ks);
+    let lp_cold = matching_bounds::lp_bound(n, edges, &[]);
+    if lp != lp_cold {
+        return Some(format!("lp: {} warm, {} cold", lp, lp_cold));
+    }
+    let uppers = [
+        ("vertices", matching_bounds::vertex_bound(n, edges)),
+        ("components", matching_bounds::component_bound(n, edges)),
+        ("lp", lp),
+    ];
+    for &(name, u) in &uppers {
+        if u < nu {
+            return Some(format!("{} bound {} below nu = {}", name, u, nu));
+        }
+    }
+    if kind == Kind::Forest && ks.len() != nu {
+        return Some(format!("karp-sipser: {} pairs on a forest, nu = {}", ks.len(), nu));
+    }
+    if kind == Kind::Bipartite && lp != nu {
+        return Some(format!("lp: {} on a bipartite graph, nu = {}", lp, nu));
+    }
+    let (r, x) = matching_bounds::lp_relaxation(n, edges, &maximum);
+    if r.twice_lp < 2 * nu || r.twice_lp < 2 * lp || (r.twice_lp == 2 * nu) != (r.gap_components == 0) {
+        return Some(format!("lp relaxation: twice {} with {} gap components, nu = {}, lp bound {}",
+                            r.twice_lp, r.gap_components, nu, lp));
+    }
+    if kind != Kind::General && r.twice_lp != 2 * nu {
+        return Some(format!("lp relaxation: twice {} on a bipartite graph, nu = {}", r.twice_lp, nu));
+    }
+    if n <= 12 && r.twice_lp != twice_fractional_brute(n, edges) {
+        return Some(format!("lp relaxation: twice {}, brute force {}", r.twice_lp, twice_fractional_brute(n, edges)));
+    }
+    for (name, x) in [("warm", x), ("cold", matching_bounds::half_integral_matching(n, edges, &[]))].iter() {
+        if let Some(why) = check_half_integral(n, edges, x, r.twice_lp, nu) {
+            return Some(format!("half-integral ({}): {}", name, why));
+        }
+    }
+    None
+}
+
+/* x is a basic LP optimum of (n, edges): a matching at 1 and disjoint odd
+ * cycles at 1/2, worth twice_lp / 2, and rounds to a matching of at least
+ * nu - cycles/2 pairs. */
+fn check_half_integral(
+    n: usize, edges: &[(usize, usize)], x: &matching_bounds::HalfIntegral, twice_lp: usize, nu: usize,
+) -> Option<String> {
+    if x.twice_value() != twice_lp {
+        return Some(format!("twice the value {}, the LP optimum twice {}", x.twice_value(), twice_lp));
+    }
+    let errors = matching_bounds::check_matching(n, edges, &x.ones);
+    if !errors.is_empty() {
+        return Some(format!("ones: {}", errors[0]));
+    }
+    let mut used = vec![false; n];
+    for &(u, v) in &x.ones {
+        used[u] = true;
+        used[v] = true;
+    }
+    for c in &x.cycles {
+        if c.len() < 3 || c.len() % 2 == 0 {
+            return Some(format!("cycle {:?} is not odd", c));
+        }
+        for (i, &v) in c.iter().enumerate() {
+            let w = c[(i + 1) % c.len()];
+            if used[v] {
+                return Some(format!("vertex {} on two parts", v));
+            }
+            used[v] = true;
+            if !matching_bounds::check_matching(n, edges, &[(v, w)]).is_empty() {
+                return Some(format!("cycle {:?}: ({}, {}) is not an edge", c, v, w));
+            }
+        }
+    }
+    let rounded = x.rounded(n, edges);
+    let errors = matching_bounds::check_matching(n, edges, &rounded);
+    if !errors.is_empty() {
+        return Some(format!("rounded: {}", errors[0]));
+    }
+    if rounded.len() > nu || 2 * (nu - rounded.len()) > x.cycles.len() {
+        return Some(format!("rounded to {} pairs with {} odd cycles, nu = {}", rounded.len(), x.cycles.len(), nu));
+    }
+    let mut covered = vec![false; n];
+    for &(u, v) in &rounded {
+        covered[u] = true;
+        covered[v] = true;
+    }
+    if let Some(&(u, v)) = x.ones.iter().find(|&&(u, v)| !covered[u] || !covered[v]) {
+        return Some(format!("rounding exposes an end of ({}, {}), an edge at 1", u, v));
+    }
+    if let Some(c) = x.cycles.iter().find(|c| c.iter().filter(|&&v| !covered[v]).count() > 1) {
+        return Some(format!("rounding exposes two vertices of cycle {:?}", c));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, kind: Kind) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    match kind {
+        Kind::General => {
+            let p = rng.next_f64() * 0.5;
+            for u in 0..n {
+                for v in u..n {
+                    if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                        edges.push((u, v));
+                        if rng.below(10) == 0 { edges.push((v, u)); }
+                    }
+                }
+            }
+        }
+        Kind::Forest => {
+            let mut label: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut label);
+            for v in 1..n {
+                if rng.below(8) != 0 {
+                    edges.push((label[rng.below(v as u64) as usize], label[v]));
+                }
+            }
+        }
+        Kind::Bipartite => {
+            let left = if n == 0 { 0 } else { rng.below(n as u64 + 1) as usize };
+            let mut side: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut side);
+            let p = rng.next_f64() * 0.5;
+            for i in 0..left {
+                for j in left..n {
+                    if rng.next_f64() < p { edges.push((side[i], side[j])); }
+                }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("matching_bounds_failures").to_string();
+
+    println!("Matching bounds vs. maximum matching: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let kind = [Kind::General, Kind::Forest, Kind::Bipartite][case % 3];
+        let (n, edges) = random_graph(&mut rng, max_n, kind);
+        let algo = matchers::GENERAL_ALGORITHMS[case / 3 % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, kind, algo) {
+            failures += 1;
+            let name = format!("bounds_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}): {}", case, n, edges.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, forests, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
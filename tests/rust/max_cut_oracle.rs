@@ -0,0 +1,139 @@
+/*
+ * Randomized test of the maximum cut local search (max_cut.rs) against
+ * exhaustive search
+ *
+ * Generates seeded random small weighted multigraphs (self-loops, parallel
+ * edges and, in some cases, negative weights) and checks that
+ *   - check_cut accepts the result, with single and pair flips alike,
+ *   - no edge is worth flipping as a pair at the end of the pair search,
+ *   - the pair search ends no lower than its single-flip optimum, which is
+ *     what --no-pairs returns from the same start,
+ *   - the cut is at most the maximum, found over all 2^(n-1) splits, which
+ *     is at most the upper bound, and with weights >= 0 at least half the
+ *     upper bound.
+ *
+ * A failing instance is written to the dump directory as a weighted "n m"
+ * edge list, ready for `combi maxcut <graph> --weighted`.
+ *
+ * Usage: max_cut_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/max_cut.rs"]
+mod max_cut;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+type Edges = Vec<(usize, usize, i64)>;
+
+/* Maximum cut weight; vertex n - 1 stays on side 0 by symmetry. */
+fn maximum(n: usize, edges: &Edges) -> i64 {
+    let mut best = i64::MIN;
+    for mask in 0u32..1 << (n - 1) {
+        let side: Vec<bool> = (0..n).map(|v| mask >> v & 1 == 1).collect();
+        best = best.max(max_cut::cut_weight(edges, &side));
+    }
+    best
+}
+
+/* An edge uv whose joint flip still gains, if any. */
+fn pair_move(edges: &Edges, side: &[bool]) -> Option<(usize, usize, i64)> {
+    let before = max_cut::cut_weight(edges, side);
+    for &(u, v, _) in edges {
+        if u == v { continue; }
+        let mut s = side.to_vec();
+        s[u] = !s[u];
+        s[v] = !s[v];
+        let gain = max_cut::cut_weight(edges, &s) - before;
+        if gain > 0 {
+            return Some((u, v, gain));
+        }
+    }
+    None
+}
+
+fn check(n: usize, edges: &Edges, start: &[bool]) -> Option<String> {
+    let single = max_cut::local_search(n, edges, start.to_vec(), false);
+    let paired = max_cut::local_search(n, edges, start.to_vec(), true);
+    for &(name, cut) in &[("single flips", &single), ("pair flips", &paired)] {
+        if let Some(e) = max_cut::check_cut(n, edges, cut).into_iter().next() {
+            return Some(format!("{}: {}", name, e));
+        }
+    }
+    if let Some((u, v, gain)) = pair_move(edges, &paired.side) {
+        return Some(format!("pair flips: flipping {} and {} still gains {}", u, v, gain));
+    }
+    if single.weight != paired.single_weight || paired.weight < single.weight {
+        return Some(format!("single flips reach {}, the pair search {} then {}",
+                            single.weight, paired.single_weight, paired.weight));
+    }
+    let best = maximum(n, edges);
+    let upper = max_cut::upper_bound(n, edges);
+    let half = edges.iter().all(|e| e.2 >= 0) && 2 * single.weight < upper;
+    if paired.weight > best || best > upper || half {
+        return Some(format!("cuts {} / {}, maximum {}, upper bound {}",
+                            single.weight, paired.weight, best, upper));
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> (usize, Edges, Vec<bool>) {
+    let n = 1 + rng.below(max_n as u64) as usize;
+    let m = rng.below(3 * n as u64 + 1) as usize;
+    let negative = rng.below(3) == 0;
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let u = rng.below(n as u64) as usize;
+        let v = rng.below(n as u64) as usize;
+        let w = if negative { rng.below(13) as i64 - 3 } else { rng.below(10) as i64 };
+        edges.push((u, v, w));
+    }
+    let start = (0..n).map(|_| rng.below(2) == 1).collect();
+    (n, edges, start)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 3000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("max_cut_failures").to_string();
+    if !(1..=20).contains(&max_n) {
+        eprintln!("Error: --max-n must be between 1 and 20");
+        std::process::exit(1);
+    }
+
+    println!("Maximum cut local search vs. exhaustive search: {} cases, seed {}, n <= {}",
+             cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges, start) = random_instance(&mut rng, max_n);
+        if let Some(why) = check(n, &edges, &start) {
+            failures += 1;
+            let name = format!("max_cut_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::weighted_edge_list(&dump_dir, &format!("{}.txt", name), n, None, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,161 @@
+/*
+ * Randomized test of the combi solve --dry-run graph properties
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and checks
+ * plan::properties against naive recounts:
+ *   - self-loops, parallel edges, isolated vertices and the maximum degree
+ *     over the in-range edges,
+ *   - components (with an edge) by label propagation to a fixpoint, the
+ *     largest one, the trees and unicyclic ones, and half of each,
+ *   - bipartiteness by the double cover: v and its copy v' meet exactly when
+ *     v's component has an odd cycle; the two sides cover every vertex that
+ *     is not isolated.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --dry-run`.
+ *
+ * Usage: plan_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/graph_io.rs"]
+mod graph_io;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/json.rs"]
+mod json;
+#[path = "../../algorithms/common/rust/log.rs"]
+mod log;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/matching_io.rs"]
+mod matching_io;
+#[path = "../../algorithms/common/rust/ordering.rs"]
+mod ordering;
+#[path = "../../algorithms/common/rust/phase0.rs"]
+mod phase0;
+#[path = "../../cli/rust/plan.rs"]
+#[allow(dead_code)]
+mod plan;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../cli/rust/solve_options.rs"]
+#[allow(dead_code)]
+mod solve_options;
+#[path = "../../algorithms/common/rust/svg.rs"]
+mod svg;
+#[path = "../../algorithms/common/rust/time_limit.rs"]
+mod time_limit;
+#[path = "../../algorithms/common/rust/verify.rs"]
+mod verify;
+
+fn check(n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let p = plan::properties(n, edges);
+    let mut pairs: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    pairs.sort_unstable();
+    pairs.dedup();
+    let loops = edges.iter().filter(|&&(u, v)| u < n && u == v).count();
+    let in_range = edges.iter().filter(|&&(u, v)| u < n && v < n).count();
+
+    /* label propagation to a fixpoint, on the graph and its double cover
+     * (u, v') and (u', v): v and v' meet exactly when v's component has
+     * an odd cycle */
+    let spread = |labels: &mut Vec<usize>, links: &[(usize, usize)]| loop {
+        let mut changed = false;
+        for &(a, b) in links {
+            let m = labels[a].min(labels[b]);
+            if labels[a] != m || labels[b] != m {
+                labels[a] = m;
+                labels[b] = m;
+                changed = true;
+            }
+        }
+        if !changed { break; }
+    };
+    let mut comp: Vec<usize> = (0..n).collect();
+    spread(&mut comp, &pairs);
+    let mut cover: Vec<usize> = (0..2 * n).collect();
+    let links: Vec<(usize, usize)> = pairs.iter().flat_map(|&(u, v)| vec![(u, n + v), (n + u, v)]).collect();
+    spread(&mut cover, &links);
+
+    let mut deg = vec![0usize; n];
+    for &(u, v) in &pairs {
+        deg[u] += 1;
+        deg[v] += 1;
+    }
+    let mut size = vec![(0usize, 0usize); n];
+    for v in 0..n {
+        if deg[v] > 0 { size[comp[v]].0 += 1; }
+    }
+    for &(u, _) in &pairs {
+        size[comp[u]].1 += 1;
+    }
+    let comps: Vec<(usize, usize)> = size.iter().cloned().filter(|s| s.0 > 0).collect();
+    let bipartite = (0..n).all(|v| cover[v] != cover[n + v]);
+
+    let mut wrong = Vec::new();
+    if p.self_loops != loops { wrong.push(format!("self-loops {} != {}", p.self_loops, loops)); }
+    if p.parallel != in_range - loops - pairs.len() {
+        wrong.push(format!("parallel {} != {}", p.parallel, in_range - loops - pairs.len()));
+    }
+    if p.isolated != deg.iter().filter(|&&d| d == 0).count() { wrong.push("isolated".to_string()); }
+    if p.max_degree != deg.iter().cloned().max().unwrap_or(0) { wrong.push("max degree".to_string()); }
+    if p.components != comps.len() { wrong.push(format!("components {} != {}", p.components, comps.len())); }
+    if p.largest != comps.iter().cloned().max().unwrap_or((0, 0)) { wrong.push("largest".to_string()); }
+    let sparse = comps.iter().filter(|c| c.1 <= c.0);
+    if p.sparse != (sparse.clone().count(), sparse.map(|c| c.0).sum()) { wrong.push("sparse".to_string()); }
+    if p.upper != comps.iter().map(|c| c.0 / 2).sum::<usize>() { wrong.push("upper".to_string()); }
+    if p.sides.is_some() != bipartite { wrong.push(format!("bipartite {:?} != {}", p.sides, bipartite)); }
+    if let Some((a, b)) = p.sides {
+        if a + b + p.isolated != n { wrong.push(format!("sides {} + {}", a, b)); }
+    }
+    if wrong.is_empty() { None } else { Some(wrong.join(", ")) }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("plan_failures").to_string();
+
+    println!("Dry-run properties vs. naive recounts: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = edge_lists::arbitrary(&mut rng, n);
+        if let Some(why) = check(n, &edges) {
+            failures += 1;
+            let name = format!("plan_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
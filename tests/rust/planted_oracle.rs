@@ -0,0 +1,219 @@
+/*
+ * Randomized test of the planted-matching generator
+ *
+ * Generates seeded planted instances (general and bipartite, sparse to
+ * complete noise, narrow and wide potentials, gaps from 0) and checks
+ * planted.rs against its promises:
+ *   - the planted matching is perfect, its pairs are edges, and its
+ *     weight is the reported one; all weights are at least 1 and no pair
+ *     repeats; the noise count is the requested one, capped by the pairs
+ *     available,
+ *   - on small instances, by search over all matchings: the planted
+ *     weight is the optimum, and with a positive gap every other matching
+ *     is at least the margin lighter,
+ *   - on larger ones (every fourth case, up to --max-n vertices): the
+ *     weighted blossom solver returns the planted matching (its weight
+ *     with gap 0), and on bipartite ones so does Hopcroft-Karp's
+ *     --tie-break heavier.
+ *
+ * A failing instance is written to the dump directory as a weighted "n m"
+ * edge list (bipartite ones with right ids after the left), ready for
+ * weighted_blossom_rust.
+ *
+ * Usage: planted_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/planted.rs"]
+mod planted;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+const SMALL_N: usize = 10;
+
+/* The instance as a general graph (n, edges, planted matching): bipartite
+ * right ids follow the left. */
+type General = (usize, Vec<(usize, usize, i64)>, Vec<(usize, usize)>);
+
+fn general(p: &planted::Planted, bipartite: bool) -> General {
+    if !bipartite {
+        return (p.left, p.edges.clone(), p.matching.clone());
+    }
+    let edges = p.edges.iter().map(|&(u, v, w)| (u, p.left + v, w)).collect();
+    let matching = p.matching.iter().map(|&(u, v)| (u, p.left + v)).collect();
+    (p.left + p.right, edges, matching)
+}
+
+/* Best weight over all matchings, and over those other than `planted`. */
+fn search(w: &[Vec<Option<i64>>], used: &mut Vec<bool>, v: usize, mate: &mut Vec<usize>,
+          planted_mate: &[usize], weight: i64, best: &mut (i64, Option<i64>)) {
+    let n = w.len();
+    let mut v = v;
+    while v < n && used[v] { v += 1; }
+    if v == n {
+        best.0 = best.0.max(weight);
+        if mate[..] != planted_mate[..] {
+            best.1 = Some(best.1.map_or(weight, |b| b.max(weight)));
+        }
+        return;
+    }
+    used[v] = true;
+    search(w, used, v + 1, mate, planted_mate, weight, best);
+    for u in v + 1..n {
+        if let (false, Some(x)) = (used[u], w[v][u]) {
+            used[u] = true;
+            mate[v] = u;
+            mate[u] = v;
+            search(w, used, v + 1, mate, planted_mate, weight + x, best);
+            mate[v] = usize::MAX;
+            mate[u] = usize::MAX;
+            used[u] = false;
+        }
+    }
+    used[v] = false;
+}
+
+fn check(opts: &planted::PlantOptions, p: &planted::Planted) -> Option<String> {
+    let (n, edges, matching) = general(p, opts.bipartite);
+    let k = opts.n / 2;
+    if matching.len() != k || n != opts.n {
+        return Some(format!("{} planted pairs on {} vertices, expected {} on {}", matching.len(), n, k, opts.n));
+    }
+    let mut w: Vec<Vec<Option<i64>>> = vec![vec![None; n]; n];
+    for &(u, v, x) in &edges {
+        if u >= n || v >= n || u == v || (opts.bipartite && (u >= p.left) == (v >= p.left)) {
+            return Some(format!("bad edge ({}, {})", u, v));
+        }
+        if w[u][v].is_some() {
+            return Some(format!("pair ({}, {}) repeats", u, v));
+        }
+        if x < 1 {
+            return Some(format!("edge ({}, {}) weighs {}", u, v, x));
+        }
+        w[u][v] = Some(x);
+        w[v][u] = Some(x);
+    }
+    let mut planted_mate = vec![usize::MAX; n];
+    let mut sum = 0;
+    for &(u, v) in &matching {
+        if planted_mate[u] != usize::MAX || planted_mate[v] != usize::MAX {
+            return Some(format!("planted pair ({}, {}) reuses a vertex", u, v));
+        }
+        match w[u][v] {
+            Some(x) => sum += x,
+            None => return Some(format!("planted pair ({}, {}) is not an edge", u, v)),
+        }
+        planted_mate[u] = v;
+        planted_mate[v] = u;
+    }
+    if sum != p.weight {
+        return Some(format!("reported weight {}, planted pairs sum to {}", p.weight, sum));
+    }
+    let available = if opts.bipartite { k * k - k } else { n * n.saturating_sub(1) / 2 - k };
+    let wanted = ((opts.degree * n as f64 / 2.0).round() as usize).min(available);
+    if edges.len() != k + wanted {
+        return Some(format!("{} noise edges, expected {}", edges.len() - k, wanted));
+    }
+
+    if n <= SMALL_N {
+        let mut best = (0, None);
+        search(&w, &mut vec![false; n], 0, &mut vec![usize::MAX; n], &planted_mate, 0, &mut best);
+        if best.0 != p.weight {
+            return Some(format!("optimum {}, planted {}", best.0, p.weight));
+        }
+        let limit = if opts.gap > 0 { p.weight - p.margin } else { p.weight };
+        if let Some(other) = best.1 {
+            if other > limit {
+                return Some(format!("another matching weighs {}, planted {} with margin {}", other, p.weight, p.margin));
+            }
+        }
+        return None;
+    }
+
+    let found = weighted_blossom::max_weight_matching(n, &edges, false);
+    let found_weight: i64 = found.iter().map(|&(u, v)| w[u][v].unwrap_or(0)).sum();
+    if found_weight != p.weight || (opts.gap > 0 && found != matching) {
+        return Some(format!("weighted blossom: {} pairs of weight {}, planted {} of weight {}",
+                            found.len(), found_weight, k, p.weight));
+    }
+    if opts.bipartite {
+        let plain: Vec<(usize, usize)> = p.edges.iter().map(|&(u, v, _)| (u, v)).collect();
+        let mut hk = hopcroft_karp::HopcroftKarp::new(p.left, p.right, &plain);
+        hk.maximum_matching(0);
+        let (mut hk_matching, hk_weight) = hk.tie_break(&p.edges, true);
+        hk_matching.sort_unstable();
+        if hk_weight != p.weight || (opts.gap > 0 && hk_matching != p.matching) {
+            return Some(format!("hopcroft-karp tie-break: {} pairs of weight {}, planted {} of weight {}",
+                                hk_matching.len(), hk_weight, k, p.weight));
+        }
+    }
+    None
+}
+
+fn random_options(rng: &mut rng::Rng, max_n: usize, large: bool) -> planted::PlantOptions {
+    let top = if large { max_n } else { SMALL_N.min(max_n) };
+    let n = 2 * rng.below(top as u64 / 2 + 1) as usize;
+    planted::PlantOptions {
+        n,
+        bipartite: rng.below(2) == 1,
+        degree: if rng.below(8) == 0 { n as f64 } else { rng.next_f64() * 6.0 },
+        max_potential: [1, 5, 1000][rng.below(3) as usize],
+        gap: [0, 1, 1, 3, 50][rng.below(5) as usize],
+        spread: [0, 2, 100][rng.below(3) as usize],
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 120);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("planted_failures").to_string();
+
+    println!("Planted matchings vs. exhaustive search and solvers: {} cases, seed {}, n <= {}",
+             cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let opts = random_options(&mut rng, max_n, case % 4 == 3);
+        let p = match planted::plant(&opts, &mut rng) {
+            Ok(p) => p,
+            Err(e) => {
+                failures += 1;
+                println!("  FAIL  case {:<5} n={}: {}", case, opts.n, e);
+                continue;
+            }
+        };
+        if let Some(why) = check(&opts, &p) {
+            failures += 1;
+            let (n, edges, _) = general(&p, opts.bipartite);
+            let name = format!("planted_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::weighted_edge_list(&dump_dir, &format!("{}.txt", name), n, None, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}, gap {}): {}", case, n, edges.len(),
+                     if opts.bipartite { "bipartite" } else { "general" }, opts.gap, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
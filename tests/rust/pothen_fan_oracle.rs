@@ -0,0 +1,158 @@
+/*
+ * Randomized test of Pothen-Fan (PF+) against Hopcroft-Karp
+ *
+ * Generates seeded random bipartite graphs and runs
+ * PothenFan::maximum_matching (`pothen_fan`), with and without the
+ * fairness of PF+ and the cheap start, checking that
+ *   - the matching is valid: graph edges, no vertex twice,
+ *   - it is as large as Hopcroft-Karp's, and
+ *   - a seeded start (pothen_fan --init-matching) stays matched.
+ * The shapes are sparse random graphs, dense ones, and ladders whose only
+ * augmenting paths run the length of the graph from a greedy start.
+ * Two cases in three are small (up to --max-n per side); the rest have up
+ * to 50 x --max-n, so that the lookahead pointers and the visited marks
+ * carry over many phases.
+ *
+ * A failing instance is written to the dump directory in the "left right
+ * m" format, ready for `pothen_fan <name>.txt`.
+ *
+ * Usage: pothen_fan_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/pothen-fan/rust/pothen_fan.rs"]
+#[allow(dead_code)]
+mod pothen_fan;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+use hopcroft_karp::HopcroftKarp;
+use pothen_fan::PothenFan;
+
+fn check(left: usize, right: usize, edges: &[(usize, usize)], fairness: bool, greedy: bool, seeded: bool) -> Option<String> {
+    let size = HopcroftKarp::new(left, right, edges).maximum_matching(0).len();
+    /* every other pair of a greedy matching, as an --init-matching start */
+    let start: Vec<(usize, usize)> = if seeded {
+        HopcroftKarp::new(left, right, edges).maximum_matching(1).into_iter().step_by(2).collect()
+    } else {
+        Vec::new()
+    };
+    let mut pf = PothenFan::new(left, right, edges);
+    pf.fairness = fairness;
+    pf.seed(&start);
+    let matching = pf.maximum_matching(greedy);
+
+    let mut sorted = edges.to_vec();
+    sorted.sort_unstable();
+    let mut used_left = vec![false; left];
+    let mut used_right = vec![false; right];
+    for &(u, v) in &matching {
+        if u >= left || v >= right || sorted.binary_search(&(u, v)).is_err() {
+            return Some(format!("pair ({}, {}) is not an edge", u, v));
+        }
+        if used_left[u] || used_right[v] {
+            return Some(format!("pair ({}, {}) reuses a vertex", u, v));
+        }
+        used_left[u] = true;
+        used_right[v] = true;
+    }
+    if matching.len() != size {
+        return Some(format!("{} pairs, Hopcroft-Karp finds {}", matching.len(), size));
+    }
+    if let Some(&(u, v)) = start.iter().find(|&&(u, v)| !used_left[u] || !used_right[v]) {
+        return Some(format!("seeded pair ({}, {}) left a vertex free", u, v));
+    }
+    if greedy && pf.greedy_size() > size {
+        return Some(format!("cheap start of {} pairs, above the maximum {}", pf.greedy_size(), size));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, usize, Vec<(usize, usize)>) {
+    let left = 1 + rng.below(max_n as u64) as usize;
+    let right = 1 + rng.below(max_n as u64) as usize;
+    let mut edges = Vec::new();
+    match rng.below(3) {
+        // Average degree 1 to 4: many short paths, some long ones
+        0 => {
+            let m = (left + right) / 2 + rng.below(2 * (left + right) as u64) as usize;
+            for _ in 0..m {
+                edges.push((rng.below(left as u64) as usize, rng.below(right as u64) as usize));
+            }
+        }
+        // Dense, capped: large frontiers in few layers
+        1 => {
+            let p = (rng.next_f64() * 0.5).min(20.0 / right as f64);
+            for u in 0..left {
+                for v in 0..right {
+                    if rng.next_f64() < p { edges.push((u, v)); }
+                }
+            }
+        }
+        // Ladders u-u, u-(u+1): greedy takes u-(u+1) first where it can,
+        // leaving one path through the whole rung chain
+        _ => {
+            let n = left.min(right);
+            for u in 0..n {
+                if u + 1 < n { edges.push((u, u + 1)); }
+                edges.push((u, u));
+            }
+            for _ in 0..rng.below(n as u64 / 8 + 1) {
+                edges.push((rng.below(n as u64) as usize, rng.below(n as u64) as usize));
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (left, right, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 3000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("pothen_fan_failures").to_string();
+    if max_n == 0 {
+        eprintln!("Error: --max-n must be positive");
+        std::process::exit(1);
+    }
+
+    println!("Pothen-Fan vs. Hopcroft-Karp: {} cases, seed {}, up to {} per side ({} in large cases)",
+             cases, seed, max_n, 50 * max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let large = case % 3 == 2;
+        let (left, right, edges) = random_graph(&mut rng, if large { 50 * max_n } else { max_n });
+        let fairness = case % 2 == 0;
+        let greedy = case / 2 % 2 == 1;
+        let seeded = case % 5 == 1;
+        if let Some(why) = check(left, right, &edges, fairness, greedy, seeded) {
+            failures += 1;
+            let name = format!("pothen_fan_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::bipartite_edge_list(&dump_dir, &format!("{}.txt", name), left, right, &edges));
+            println!("  FAIL  case {:<5} {} x {}, {} edges, {}: {}", case, left, right, edges.len(),
+                     if fairness { "PF+" } else { "PF" }, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,111 @@
+/*
+ * Randomized test of the --progress phase reports
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them), every
+ * other one cut to a random bipartite graph so that auto runs
+ * Hopcroft-Karp, and solves each with every general matcher and auto under
+ * one of the greedy modes, a recorder passed to matchers::progress_on.
+ * Checks that the reports
+ *   - number the phases 1, 2, ...,
+ *   - never lower the augmentation count,
+ *   - give n minus twice the pairs so far as the free vertices,
+ *   - end at the final matching size.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --progress`.
+ *
+ * Usage: progress_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::sync::Mutex;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* The reports of the current solve, (phase, augmentations, free vertices). */
+static REPORTS: Mutex<Vec<(usize, usize, usize)>> = Mutex::new(Vec::new());
+
+fn record(phase: usize, augmentations: usize, free: usize) {
+    REPORTS.lock().unwrap().push((phase, augmentations, free));
+}
+
+fn check(n: usize, edges: &[(usize, usize)], mode: i32, seed: u64) -> Option<String> {
+    for algo in matchers::GENERAL_ALGORITHMS.iter().chain([matchers::AUTO_ALGORITHM].iter()) {
+        REPORTS.lock().unwrap().clear();
+        let sol = match matchers::solve(algo, n, edges, mode, seed, 0) {
+            Ok(s) => s,
+            Err(_) => return None, /* rejected cleanly */
+        };
+        let reports = REPORTS.lock().unwrap().clone();
+        let start = sol.greedy_size;
+        let mut last = 0;
+        for (i, &(phase, augmentations, free)) in reports.iter().enumerate() {
+            if phase != i + 1 || augmentations < last || free != n - 2 * (start + augmentations) {
+                return Some(format!("{}, greedy mode {}: report {:?} after {} augmentations, from {} pairs",
+                                    algo, mode, (phase, augmentations, free), last, start));
+            }
+            last = augmentations;
+        }
+        if start + last != sol.matching.len() {
+            return Some(format!("{}, greedy mode {}: reports end at {} + {} pairs, the matching has {}",
+                                algo, mode, start, last, sol.matching.len()));
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("progress_failures").to_string();
+
+    println!("Progress reports vs. the final matching: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    matchers::progress_on(&record);
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = if case.is_multiple_of(2) {
+            edge_lists::arbitrary(&mut rng, n)
+        } else {
+            let left = rng.below(n as u64 + 1) as usize;
+            edge_lists::arbitrary(&mut rng, n).into_iter().filter(|&(u, v)| (u < left) != (v < left)).collect()
+        };
+        let mode = (case % 5) as i32;
+        if let Some(why) = check(n, &edges, mode, case as u64) {
+            failures += 1;
+            let name = format!("progress_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} (greedy mode {}, --seed {}): {}", case, n, edges.len(), mode, case, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
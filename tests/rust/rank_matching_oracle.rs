@@ -0,0 +1,209 @@
+/*
+ * Randomized test of rank-maximal and popular matchings against
+ * enumeration
+ *
+ * Generates seeded random small instances of one-sided preferences (ties,
+ * incomplete and empty lists, post capacities of up to 3) and enumerates
+ * every matching that respects the capacities. It checks rank_matching.rs:
+ *   - rank_maximal returns a valid matching whose signature is the
+ *     lexicographic maximum over all matchings,
+ *   - with unit capacities, popular returns a matching that no other
+ *     matching beats in a vote, or None exactly when no such matching
+ *     exists; with larger capacities it refuses,
+ *   - check_matching accepts what both return.
+ * Popularity is decided here by comparing every pair of matchings.
+ *
+ * A failing instance is written to the dump directory as a rankings file,
+ * ready for `combi allocate`.
+ *
+ * Usage: rank_matching_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/rank_matching.rs"]
+mod rank_matching;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+use rank_matching::Instance;
+
+const NIL: usize = usize::MAX;
+
+/* Capacity-respecting matchings, as the post of each applicant (NIL = none). */
+fn enumerate(inst: &Instance, a: usize, load: &mut [usize], cur: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+    if a == inst.applicants() {
+        out.push(cur.clone());
+        return;
+    }
+    cur.push(NIL);
+    enumerate(inst, a + 1, load, cur, out);
+    cur.pop();
+    let posts: Vec<usize> = inst.prefs[a].iter().flat_map(|g| g.iter().cloned()).collect();
+    for p in posts {
+        if load[p] < inst.capacity[p] {
+            load[p] += 1;
+            cur.push(p);
+            enumerate(inst, a + 1, load, cur, out);
+            cur.pop();
+            load[p] -= 1;
+        }
+    }
+}
+
+fn as_posts(inst: &Instance, pairs: &[(usize, usize)]) -> Vec<usize> {
+    let mut posts = vec![NIL; inst.applicants()];
+    for &(a, p) in pairs { posts[a] = p; }
+    posts
+}
+
+/* Rank of each applicant's post; unmatched ranks below every list. */
+fn ranks(inst: &Instance, posts: &[usize]) -> Vec<usize> {
+    posts.iter().enumerate()
+        .map(|(a, &p)| if p == NIL { usize::MAX } else { inst.rank_of(a, p).unwrap() })
+        .collect()
+}
+
+fn signature(inst: &Instance, posts: &[usize]) -> Vec<usize> {
+    let mut sig = vec![0; inst.max_rank()];
+    for r in ranks(inst, posts) {
+        if r != usize::MAX { sig[r - 1] += 1; }
+    }
+    sig
+}
+
+/* Applicants preferring x to y, minus those preferring y to x. */
+fn vote(x: &[usize], y: &[usize]) -> i64 {
+    x.iter().zip(y).map(|(&a, &b)| if a < b { 1 } else if a > b { -1 } else { 0 }).sum()
+}
+
+fn check(inst: &Instance) -> Option<String> {
+    let mut all = Vec::new();
+    enumerate(inst, 0, &mut vec![0; inst.posts], &mut Vec::new(), &mut all);
+
+    let rm = rank_matching::rank_maximal(inst);
+    let errors = rank_matching::check_matching(inst, &rm.pairs);
+    if !errors.is_empty() {
+        return Some(format!("rank-maximal {:?}: {:?}", rm.pairs, errors));
+    }
+    let got = as_posts(inst, &rm.pairs);
+    if signature(inst, &got) != rm.signature {
+        return Some(format!("rank-maximal {:?}: reported signature {:?}", rm.pairs, rm.signature));
+    }
+    let best = all.iter().map(|m| signature(inst, m)).max().unwrap();
+    if rm.signature != best {
+        return Some(format!("rank-maximal {:?}: signature {:?}, best {:?}", rm.pairs, rm.signature, best));
+    }
+
+    let unit = inst.capacity.iter().all(|&c| c == 1);
+    let pop = match (rank_matching::popular(inst), unit) {
+        (Ok(p), true) => p,
+        (Err(_), false) => return None,
+        (Ok(_), false) => return Some("popular accepted capacities above 1".to_string()),
+        (Err(e), true) => return Some(format!("popular failed: {}", e)),
+    };
+    let rk: Vec<Vec<usize>> = all.iter().map(|m| ranks(inst, m)).collect();
+    let is_popular = |x: &[usize]| rk.iter().all(|y| vote(y, x) <= 0);
+    match pop {
+        Some(pairs) => {
+            let errors = rank_matching::check_matching(inst, &pairs);
+            if !errors.is_empty() {
+                return Some(format!("popular {:?}: {:?}", pairs, errors));
+            }
+            if !is_popular(&ranks(inst, &as_posts(inst, &pairs))) {
+                return Some(format!("popular {:?} loses a vote", pairs));
+            }
+        }
+        None => {
+            if let Some(i) = (0..all.len()).find(|&i| is_popular(&rk[i])) {
+                return Some(format!("no popular matching reported, but {:?} is", all[i]));
+            }
+        }
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> Instance {
+    let na = rng.below(max_n as u64 + 1) as usize;
+    let posts = rng.below(max_n as u64 + 1) as usize;
+    let capped = rng.below(3) == 0;
+    let ties = rng.below(2) == 0;
+    let capacity = (0..posts).map(|_| if capped { 1 + rng.below(3) as usize } else { 1 }).collect();
+    let mut prefs = Vec::new();
+    for _ in 0..na {
+        let mut list: Vec<usize> = (0..posts).collect();
+        rng.shuffle(&mut list);
+        let keep = if rng.below(3) == 0 { posts } else { rng.below(posts as u64 + 1) as usize };
+        list.truncate(keep);
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for p in list {
+            match groups.last_mut() {
+                Some(g) if ties && rng.below(3) == 0 => g.push(p),
+                _ => groups.push(vec![p]),
+            }
+        }
+        prefs.push(groups);
+    }
+    Instance { posts, capacity, prefs }
+}
+
+fn dump_case(dir: &str, name: &str, inst: &Instance) -> io::Result<PathBuf> {
+    let mut lines = vec![format!("{} {}", inst.applicants(), inst.posts)];
+    for groups in &inst.prefs {
+        let items: Vec<String> = groups.iter().map(|g| {
+            let ids: Vec<String> = g.iter().map(|p| p.to_string()).collect();
+            if ids.len() == 1 { ids[0].clone() } else { format!("({})", ids.join(" ")) }
+        }).collect();
+        lines.push(if items.is_empty() { "-".to_string() } else { items.join(" ") });
+    }
+    let caps: Vec<String> = inst.capacity.iter().map(|c| c.to_string()).collect();
+    lines.push(format!("capacities: {}", caps.join(" ")));
+    dump::lines(dir, name, lines)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 5000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 6);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("rank_matching_failures").to_string();
+    if max_n > 6 {
+        eprintln!("Error: --max-n is at most 6 (all matchings are enumerated)");
+        std::process::exit(1);
+    }
+
+    println!("Rank-maximal and popular matchings vs. enumeration: {} cases, seed {}, sides <= {}",
+             cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let inst = random_instance(&mut rng, max_n);
+        if let Some(why) = check(&inst) {
+            failures += 1;
+            let name = format!("rank_seed{}_case{}.txt", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &inst));
+            println!("  FAIL  case {:<5} {}x{}: {}", case, inst.applicants(), inst.posts, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
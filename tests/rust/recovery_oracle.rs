@@ -0,0 +1,243 @@
+/*
+ * Randomized test of the planted-recovery experiments (recovery.rs)
+ *
+ * Runs seeded small experiments (random sizes, degree and gap grids,
+ * trial counts and algorithm subsets, general and bipartite) and checks
+ * the rows against an independent scoring of every trial:
+ *   - one row per cell and algorithm, in grid order,
+ *   - each trial is the instance planted with seed + t; its result is a
+ *     matching of the instance, and the row's exact count, mean planted
+ *     pair fraction and mean and least weight ratio are those of the
+ *     trials,
+ *   - no result outweighs the planted matching, the weighted solver and
+ *     Hopcroft-Karp's tie-break reach its weight, and with a positive gap
+ *     they return it exactly,
+ *   - the cardinality matchers return perfect matchings, and every edge
+ *     greedy leaves out meets a matched edge at least as heavy,
+ *   - a second run gives the same rows (times aside).
+ *
+ * A failing experiment is written to the dump directory as the
+ * `combi recover` command line that repeats it, with the failure.
+ *
+ * Usage: recovery_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/planted.rs"]
+mod planted;
+#[path = "../../algorithms/common/rust/recovery.rs"]
+mod recovery;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+const EPS: f64 = 1e-9;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= EPS * a.abs().max(b.abs()).max(1.0)
+}
+
+/* An edge of the instance left out of greedy's matching without a matched
+ * edge at least as heavy at one of its ends. */
+fn greedy_violation(p: &planted::Planted, matching: &[(usize, usize)]) -> Option<(usize, usize)> {
+    let (n, edges, _) = recovery::general(p);
+    let mut weight = vec![vec![0; n]; n];
+    for &(u, v, x) in &edges {
+        weight[u][v] = x;
+        weight[v][u] = x;
+    }
+    let mut matched = vec![i64::MIN; n];
+    for &(u, v) in matching {
+        matched[u] = weight[u][v];
+        matched[v] = weight[u][v];
+    }
+    edges.iter().find(|&&(u, v, x)| !matching.contains(&(u.min(v), u.max(v))) && matched[u] < x && matched[v] < x)
+        .map(|&(u, v, _)| (u, v))
+}
+
+/* Exact?, pair fraction and weight ratio of one result, scored here. */
+fn score(p: &planted::Planted, matching: &[(usize, usize)]) -> Result<(bool, f64, f64), String> {
+    let (n, edges, wanted) = recovery::general(p);
+    let mut w = vec![vec![None; n]; n];
+    for &(u, v, x) in &edges {
+        w[u][v] = Some(x);
+        w[v][u] = Some(x);
+    }
+    let mut used = vec![false; n];
+    let mut weight = 0;
+    for &(u, v) in matching {
+        if u >= n || v >= n || used[u] || used[v] {
+            return Err(format!("({}, {}) is out of range or reuses a vertex", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+        weight += w[u][v].ok_or_else(|| format!("({}, {}) is not an edge", u, v))?;
+    }
+    let found = matching.iter().filter(|e| wanted.contains(e)).count();
+    let fraction = if wanted.is_empty() { 1.0 } else { found as f64 / wanted.len() as f64 };
+    let ratio = if p.weight == 0 { 1.0 } else { weight as f64 / p.weight as f64 };
+    Ok((matching == &wanted[..], fraction, ratio))
+}
+
+fn check(opts: &recovery::RecoveryOptions) -> Option<String> {
+    let rows = match recovery::run(opts, &mut |_| {}) {
+        Ok(r) => r,
+        Err(e) => return Some(e),
+    };
+    let cells = opts.degrees.len() * opts.gaps.len();
+    if rows.len() != cells * opts.algos.len() {
+        return Some(format!("{} rows for {} cells x {} algorithms", rows.len(), cells, opts.algos.len()));
+    }
+    let mut i = 0;
+    for &degree in &opts.degrees {
+        for &gap in &opts.gaps {
+            let cell = planted::PlantOptions { degree, gap, ..opts.plant };
+            let trials: Vec<planted::Planted> = (0..opts.trials)
+                .map(|t| planted::plant(&cell, &mut rng::Rng::new(opts.seed.wrapping_add(t as u64))).unwrap())
+                .collect();
+            for algo in &opts.algos {
+                let r = &rows[i];
+                i += 1;
+                if r.degree != degree || r.gap != gap || &r.algo != algo || r.trials != opts.trials {
+                    return Some(format!("row {} is ({}, {}, {}), expected ({}, {}, {})",
+                                        i - 1, r.degree, r.gap, r.algo, degree, gap, algo));
+                }
+                let (mut exact, mut fraction, mut ratio, mut least) = (0, 0.0, 0.0, f64::INFINITY);
+                for (t, p) in trials.iter().enumerate() {
+                    let matching = recovery::solve(algo, p).unwrap();
+                    let (e, f, w) = match score(p, &matching) {
+                        Ok(s) => s,
+                        Err(why) => return Some(format!("{} on trial {}: {}", algo, t, why)),
+                    };
+                    if w > 1.0 + EPS {
+                        return Some(format!("{} on trial {} outweighs the planted matching ({})", algo, t, w));
+                    }
+                    let weighted = algo == "weighted" || algo == "hk-tie-break";
+                    if weighted && (!close(w, 1.0) || (gap > 0 && !e)) {
+                        return Some(format!("{} on trial {} (gap {}) misses the planted matching: \
+                                             ratio {}, exact {}", algo, t, gap, w, e));
+                    }
+                    if matchers::GENERAL_ALGORITHMS.contains(&algo.as_str()) && 2 * matching.len() != opts.plant.n {
+                        return Some(format!("{} on trial {}: {} pairs on {} vertices", algo, t,
+                                            matching.len(), opts.plant.n));
+                    }
+                    if algo == "greedy" {
+                        if let Some((u, v)) = greedy_violation(p, &matching) {
+                            return Some(format!("greedy on trial {} skips ({}, {}) for lighter edges", t, u, v));
+                        }
+                    }
+                    exact += e as usize;
+                    fraction += f;
+                    ratio += w;
+                    least = least.min(w);
+                }
+                let trials = opts.trials as f64;
+                if r.exact != exact || !close(r.pair_fraction, fraction / trials)
+                    || !close(r.weight_ratio, ratio / trials) || !close(r.min_weight_ratio, least) {
+                    return Some(format!("{} at degree {}, gap {}: row ({}, {}, {}, {}), trials give ({}, {}, {}, {})",
+                                        algo, degree, gap, r.exact, r.pair_fraction, r.weight_ratio,
+                                        r.min_weight_ratio, exact, fraction / trials, ratio / trials, least));
+                }
+            }
+        }
+    }
+    let again = recovery::run(opts, &mut |_| {}).unwrap();
+    for (a, b) in rows.iter().zip(&again) {
+        if (a.exact, a.pair_fraction, a.weight_ratio, a.min_weight_ratio)
+            != (b.exact, b.pair_fraction, b.weight_ratio, b.min_weight_ratio) {
+            return Some(format!("{} at degree {}, gap {} differs on a second run", a.algo, a.degree, a.gap));
+        }
+    }
+    None
+}
+
+fn random_options(rng: &mut rng::Rng, max_n: usize) -> recovery::RecoveryOptions {
+    let bipartite = rng.below(2) == 1;
+    let mut algos: Vec<String> = recovery::algorithm_names().into_iter()
+        .filter(|&a| bipartite || a != "hk-tie-break")
+        .filter(|_| rng.below(2) == 0)
+        .map(|a| a.to_string())
+        .collect();
+    if algos.is_empty() {
+        algos.push("weighted".to_string());
+    }
+    rng.shuffle(&mut algos);
+    let degrees = (0..1 + rng.below(3)).map(|_| [0.0, 0.5, 2.0, 5.0, 40.0][rng.below(5) as usize]).collect();
+    let gaps = (0..1 + rng.below(3)).map(|_| [0, 1, 4, 60][rng.below(4) as usize]).collect();
+    recovery::RecoveryOptions {
+        plant: planted::PlantOptions {
+            n: 2 * rng.below(max_n as u64 / 2 + 1) as usize,
+            bipartite,
+            max_potential: [1, 10, 1000][rng.below(3) as usize],
+            spread: [0, 3, 100][rng.below(3) as usize],
+            ..planted::PlantOptions::default()
+        },
+        degrees,
+        gaps,
+        trials: 1 + rng.below(3) as usize,
+        seed: rng.below(1000),
+        algos,
+    }
+}
+
+fn list<T: ToString>(items: &[T]) -> String {
+    items.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn dump_case(dir: &str, name: &str, opts: &recovery::RecoveryOptions, why: &str) -> io::Result<PathBuf> {
+    let command = format!("combi recover {}{} --degrees {} --gaps {} --trials {} --algos {} --max-potential {} --spread {} --seed {}",
+                          opts.plant.n, if opts.plant.bipartite { " --bipartite" } else { "" }, list(&opts.degrees),
+                          list(&opts.gaps), opts.trials, opts.algos.join(","), opts.plant.max_potential,
+                          opts.plant.spread, opts.seed);
+    dump::lines(dir, &format!("{}.txt", name), [command, format!("FAIL {}", why)].iter())
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 500);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("recovery_failures").to_string();
+
+    println!("Recovery experiments vs. independent scoring: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let opts = random_options(&mut rng, max_n);
+        if let Some(why) = check(&opts) {
+            failures += 1;
+            let name = format!("recovery_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &opts, &why));
+            println!("  FAIL  case {:<5} n={} ({}): {}", case, opts.plant.n,
+                     if opts.plant.bipartite { "bipartite" } else { "general" }, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
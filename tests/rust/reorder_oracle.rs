@@ -0,0 +1,145 @@
+/*
+ * Randomized test of the --reorder relabelings
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and
+ * relabels each with every order of ordering.rs. Checks:
+ *   - each relabeling is a permutation,
+ *   - under peripheral, bfs and rcm, every component takes one run of ids
+ *     and is entered once (the BFS orders: every other vertex after a
+ *     neighbor, rcm: before one), bfs from the component's lowest id,
+ *   - degree orders by decreasing degree, ties by id,
+ *   - a maximum matching of the relabeled edges, restored, is a maximum
+ *     matching of the input.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --reorder <order>`.
+ *
+ * Usage: reorder_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/ordering.rs"]
+mod ordering;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+fn check(n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let size = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, edges) {
+        Ok(m) => m.len(),
+        Err(_) => return None, /* rejected cleanly */
+    };
+    let mut adj = vec![Vec::new(); n];
+    for &(u, v) in edges {
+        if u < n && v < n && u != v {
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+    }
+    for a in &mut adj { a.sort_unstable(); a.dedup(); }
+    let mut comp: Vec<usize> = (0..n).collect();
+    loop {
+        let mut changed = false;
+        for u in 0..n {
+            for &v in &adj[u] {
+                if comp[v] < comp[u] { comp[u] = comp[v]; changed = true; }
+            }
+        }
+        if !changed { break; }
+    }
+    let components = (0..n).filter(|&v| comp[v] == v).count();
+
+    for kind in [ordering::Reorder::Peripheral, ordering::Reorder::Bfs, ordering::Reorder::Rcm, ordering::Reorder::Degree] {
+        let name = kind.name();
+        let r = match ordering::relabeling(kind, n, edges) {
+            Some(r) => r,
+            None => return Some(format!("{}: no relabeling", name)),
+        };
+        if r.old_of.len() != n || r.new_of.len() != n || (0..n).any(|v| r.old_of[r.new_of[v]] != v) {
+            return Some(format!("{}: not a permutation: {:?}", name, r.old_of));
+        }
+        let pos = &r.new_of;
+        if kind == ordering::Reorder::Degree {
+            if let Some(w) = r.old_of.windows(2).find(|w| (adj[w[0]].len(), w[1]) < (adj[w[1]].len(), w[0])) {
+                return Some(format!("degree: {} before {}", w[0], w[1]));
+            }
+        } else {
+            let runs = 1 + r.old_of.windows(2).filter(|w| comp[w[0]] != comp[w[1]]).count();
+            /* the vertices a BFS order enters a component by: no
+             * neighbor before them (after them, reversed for rcm) */
+            let entries: Vec<usize> = (0..n).filter(|&v| !adj[v].iter().any(|&w| match kind {
+                ordering::Reorder::Rcm => pos[w] > pos[v],
+                _ => pos[w] < pos[v],
+            })).collect();
+            if n > 0 && runs != components {
+                return Some(format!("{}: {} components in {} runs", name, components, runs));
+            }
+            if entries.len() != components {
+                return Some(format!("{}: {} components entered at {:?}", name, components, entries));
+            }
+            if kind == ordering::Reorder::Bfs && entries.iter().any(|&v| comp[v] != v) {
+                return Some(format!("bfs: components entered at {:?}, not their lowest ids", entries));
+            }
+        }
+        let matching = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, &r.apply(edges)) {
+            Ok(m) => r.restore(&m),
+            Err(e) => return Some(format!("{}: relabeled edges refused: {}", name, e)),
+        };
+        if let Some(w) = edge_lists::matching_error(n, edges, &matching) {
+            return Some(format!("{}: {}", name, w));
+        }
+        if matching.len() != size {
+            return Some(format!("{}: {} pairs, {} without relabeling", name, matching.len(), size));
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("reorder_failures").to_string();
+
+    println!("Relabelings vs. components, degrees and the maximum size: {} cases, seed {}, n <= {}",
+             cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = edge_lists::arbitrary(&mut rng, n);
+        if let Some(why) = check(n, &edges) {
+            failures += 1;
+            let name = format!("reorder_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,217 @@
+/*
+ * Randomized test of the matching sparsifier against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges) and runs sparsifier.rs with first-fit maximal and with
+ * maximum rounds, for 1 to 4 rounds and against the target nu(G):
+ *   - check_sparsifier accepts the rounds (edge-disjoint matchings of
+ *     graph edges, each maximal in the edges left),
+ *   - every edge left out has ends whose kept degrees add up to at least
+ *     the number of rounds,
+ *   - a maximum round is as large as a maximum matching of the edges left,
+ *     and maximum rounds keep nu(G) from the first,
+ *   - maximal rounds keep at least half of nu(G),
+ *   - against the target, nu after each round is right and the rounds stop
+ *     at the first one that reaches nu(G),
+ *   - decompose_into_matchings with no bound takes every edge, in at least
+ *     Delta(G) matchings of non-increasing size, the first of size nu(G),
+ * where every nu is found by a dynamic program over vertex subsets. The
+ * matchers rotate through the general algorithms.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi sparsify`.
+ *
+ * Usage: sparsifier_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/sparsifier.rs"]
+mod sparsifier;
+
+/* Maximum matching size of (n, edges). */
+fn exhaustive_nu(n: usize, edges: &[(usize, usize)]) -> usize {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    /* nu[s]: maximum matching of G[s] */
+    let mut nu = vec![0usize; 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut best = nu[rest];
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            best = best.max(1 + nu[rest & !(1 << w)]);
+        }
+        nu[s] = best;
+    }
+    nu[(1 << n) - 1]
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str) -> Option<String> {
+    let nu = exhaustive_nu(n, edges);
+    let mut graph: Vec<(usize, usize)> = edges.iter()
+        .filter(|&&(u, v)| u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v)))
+        .collect();
+    graph.sort_unstable();
+    graph.dedup();
+    for &maximum in &[false, true] {
+        for &(rounds, target) in &[(1, None), (2, None), (4, None), (usize::MAX, Some(nu))] {
+            let what = format!("{} x {}{}", if maximum { "maximum" } else { "maximal" },
+                               if rounds == usize::MAX { "any".to_string() } else { rounds.to_string() },
+                               if target.is_some() { " to nu" } else { "" });
+            let s = match sparsifier::sparsify(algo, n, edges, maximum, rounds, target) {
+                Ok(s) => s,
+                Err(e) => return Some(format!("{}: {}", what, e)),
+            };
+            let errors = sparsifier::check_sparsifier(n, edges, &s);
+            if !errors.is_empty() {
+                return Some(format!("{}: {:?}", what, errors));
+            }
+            let kept = s.edges();
+            if s.rounds.len() > rounds || (s.rounds.len() < rounds && target.is_none() && kept.len() < graph.len()) {
+                return Some(format!("{}: {} rounds with {} of {} edges kept", what, s.rounds.len(), kept.len(),
+                                    graph.len()));
+            }
+            let mut degree = vec![0usize; n];
+            for &(u, v) in &kept {
+                degree[u] += 1;
+                degree[v] += 1;
+            }
+            if let Some(&(u, v)) = graph.iter()
+                .find(|e| kept.binary_search(e).is_err() && degree[e.0] + degree[e.1] < s.rounds.len())
+            {
+                return Some(format!("{}: dropped ({}, {}) with kept degrees {} + {}", what, u, v, degree[u],
+                                    degree[v]));
+            }
+            let mut taken: Vec<(usize, usize)> = Vec::new();
+            for (i, round) in s.rounds.iter().enumerate() {
+                if maximum {
+                    let left: Vec<(usize, usize)> = graph.iter().cloned()
+                        .filter(|e| taken.binary_search(e).is_err())
+                        .collect();
+                    let best = exhaustive_nu(n, &left);
+                    if round.len() != best {
+                        return Some(format!("{}: round {} has {} edges, the edges left match {}", what, i + 1,
+                                            round.len(), best));
+                    }
+                }
+                taken.extend_from_slice(round);
+                taken.sort_unstable();
+                if target.is_some() {
+                    let reached = exhaustive_nu(n, &taken);
+                    if s.nu_after.get(i) != Some(&reached) {
+                        return Some(format!("{}: nu after round {} is {}, reported {:?}", what, i + 1, reached,
+                                            s.nu_after.get(i)));
+                    }
+                    if reached == nu && i + 1 < s.rounds.len() {
+                        return Some(format!("{}: nu reached in round {} but {} rounds ran", what, i + 1,
+                                            s.rounds.len()));
+                    }
+                }
+            }
+            let kept_nu = exhaustive_nu(n, &kept);
+            if (maximum || target.is_some()) && kept_nu != nu {
+                return Some(format!("{}: nu {} kept of {}", what, kept_nu, nu));
+            }
+            if 2 * kept_nu < nu {
+                return Some(format!("{}: nu {} kept, below half of {}", what, kept_nu, nu));
+            }
+        }
+    }
+    let parts = match sparsifier::decompose_into_matchings(algo, n, edges, usize::MAX) {
+        Ok(p) => p,
+        Err(e) => return Some(format!("decomposition: {}", e)),
+    };
+    let s = sparsifier::Sparsifier { rounds: parts, nu_after: Vec::new() };
+    let errors = sparsifier::check_sparsifier(n, edges, &s);
+    if !errors.is_empty() {
+        return Some(format!("decomposition: {:?}", errors));
+    }
+    let mut degree = vec![0usize; n];
+    for &(u, v) in &graph {
+        degree[u] += 1;
+        degree[v] += 1;
+    }
+    let delta = degree.iter().cloned().max().unwrap_or(0);
+    let sizes: Vec<usize> = s.rounds.iter().map(|r| r.len()).collect();
+    if s.edges() != graph || sizes.len() < delta || sizes.windows(2).any(|w| w[0] < w[1])
+        || sizes.first().cloned().unwrap_or(0) != nu
+    {
+        return Some(format!("decomposition: sizes {:?} of {} edges, Delta {}, nu {}", sizes, graph.len(), delta,
+                            nu));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = rng.next_f64() * 0.7;
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("sparsifier_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Matching sparsifier vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, algo) {
+            failures += 1;
+            let name = format!("sparsify_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}): {}", case, n, edges.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x 8 variants + decomposition)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
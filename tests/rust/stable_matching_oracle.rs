@@ -0,0 +1,208 @@
+/*
+ * Randomized test of deferred acceptance against enumeration of all stable
+ * matchings
+ *
+ * Generates seeded random small instances (incomplete and one-sided lists,
+ * capacities on one side, empty lists) and enumerates every matching of
+ * the mutually acceptable pairs that respects the capacities. Stability is
+ * decided here independently of stable_matching.rs. For both proposing
+ * sides it checks:
+ *   - gale_shapley returns one of the stable matchings,
+ *   - unit-capacity agents get their best stable partner when proposing and
+ *     their worst when receiving,
+ *   - every agent has as many partners as in any other stable matching
+ *     (rural hospitals theorem),
+ *   - check_stable accepts exactly the stable matchings.
+ *
+ * A failing instance is written to the dump directory as a preferences
+ * file, ready for `combi stable`.
+ *
+ * Usage: stable_matching_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/stable_matching.rs"]
+mod stable_matching;
+
+use stable_matching::{Instance, Side};
+
+fn rank(list: &[usize], x: usize) -> Option<usize> {
+    list.iter().position(|&y| y == x)
+}
+
+/* Stable in the textbook sense, by scanning every acceptable pair. */
+fn is_stable(inst: &Instance, pairs: &[(usize, usize)]) -> bool {
+    let worst = |prefs: &[Vec<usize>], cap: &[usize], a: usize, side_left: bool| {
+        let partners: Vec<usize> = pairs.iter()
+            .filter(|&&(l, r)| if side_left { l == a } else { r == a })
+            .map(|&(l, r)| if side_left { r } else { l })
+            .collect();
+        if partners.len() < cap[a] { return usize::MAX; }
+        partners.iter().map(|&b| rank(&prefs[a], b).unwrap()).max().unwrap_or(usize::MAX)
+    };
+    for &(l, r) in &inst.acceptable_pairs() {
+        if pairs.contains(&(l, r)) { continue; }
+        let (a, b) = (rank(&inst.left.prefs[l], r).unwrap(), rank(&inst.right.prefs[r], l).unwrap());
+        if a < worst(&inst.left.prefs, &inst.left.capacity, l, true)
+            && b < worst(&inst.right.prefs, &inst.right.capacity, r, false) {
+            return false;
+        }
+    }
+    true
+}
+
+/* All capacity-respecting subsets of the acceptable pairs. */
+fn enumerate(inst: &Instance, acc: &[(usize, usize)], i: usize, cl: &mut [usize], cr: &mut [usize],
+             cur: &mut Vec<(usize, usize)>, out: &mut Vec<Vec<(usize, usize)>>) {
+    if i == acc.len() {
+        out.push(cur.clone());
+        return;
+    }
+    enumerate(inst, acc, i + 1, cl, cr, cur, out);
+    let (l, r) = acc[i];
+    if cl[l] < inst.left.capacity[l] && cr[r] < inst.right.capacity[r] {
+        cl[l] += 1;
+        cr[r] += 1;
+        cur.push((l, r));
+        enumerate(inst, acc, i + 1, cl, cr, cur, out);
+        cur.pop();
+        cl[l] -= 1;
+        cr[r] -= 1;
+    }
+}
+
+/* Partners of every agent, left agents first, ranks in its own list. */
+fn partner_ranks(inst: &Instance, pairs: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let (nl, nr) = (inst.left.len(), inst.right.len());
+    let mut out = vec![Vec::new(); nl + nr];
+    for &(l, r) in pairs {
+        out[l].push(rank(&inst.left.prefs[l], r).unwrap());
+        out[nl + r].push(rank(&inst.right.prefs[r], l).unwrap());
+    }
+    out
+}
+
+fn check(inst: &Instance) -> Option<String> {
+    let acc = inst.acceptable_pairs();
+    let mut all = Vec::new();
+    let mut cl = vec![0; inst.left.len()];
+    let mut cr = vec![0; inst.right.len()];
+    enumerate(inst, &acc, 0, &mut cl, &mut cr, &mut Vec::new(), &mut all);
+    let mut stable = Vec::new();
+    for m in &all {
+        let ok = is_stable(inst, m);
+        let reported = stable_matching::check_stable(inst, m);
+        if ok != reported.is_empty() {
+            return Some(format!("check_stable on {:?}: {:?}, stable = {}", m, reported, ok));
+        }
+        if ok { stable.push(partner_ranks(inst, m)); }
+    }
+    if stable.is_empty() {
+        return Some("no stable matching found by enumeration".to_string());
+    }
+    let nl = inst.left.len();
+    for &side in &[Side::Left, Side::Right] {
+        let sm = stable_matching::gale_shapley(inst, side);
+        if !all.contains(&sm.pairs) || !is_stable(inst, &sm.pairs) {
+            return Some(format!("{} proposing: {:?} is not a stable matching", side.name(), sm.pairs));
+        }
+        let got = partner_ranks(inst, &sm.pairs);
+        for (a, mine) in got.iter().enumerate() {
+            if stable.iter().any(|s| s[a].len() != mine.len()) {
+                return Some(format!("{} proposing: agent {} breaks the rural hospitals theorem", side.name(), a));
+            }
+            let (cap, proposing) = if a < nl {
+                (inst.left.capacity[a], side == Side::Left)
+            } else {
+                (inst.right.capacity[a - nl], side == Side::Right)
+            };
+            if cap != 1 || mine.is_empty() { continue; }
+            let best = stable.iter().map(|s| s[a][0]).min().unwrap();
+            let worst = stable.iter().map(|s| s[a][0]).max().unwrap();
+            let want = if proposing { best } else { worst };
+            if mine[0] != want {
+                return Some(format!("{} proposing: agent {} gets rank {}, expected {}",
+                                    side.name(), a, mine[0], want));
+            }
+        }
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> Instance {
+    let nl = rng.below(max_n as u64 + 1) as usize;
+    let nr = rng.below(max_n as u64 + 1) as usize;
+    let capped = rng.below(3); /* 0: none, 1: left, 2: right */
+    let mut sides = Vec::new();
+    for (s, count, other) in [(1, nl, nr), (2, nr, nl)].iter().cloned() {
+        let mut capacity = Vec::new();
+        let mut prefs = Vec::new();
+        for _ in 0..count {
+            capacity.push(if capped == s { 1 + rng.below(3) as usize } else { 1 });
+            let mut list: Vec<usize> = (0..other).collect();
+            rng.shuffle(&mut list);
+            let keep = if rng.below(3) == 0 { other } else { rng.below(other as u64 + 1) as usize };
+            list.truncate(keep);
+            prefs.push(list);
+        }
+        sides.push(stable_matching::Agents { capacity, prefs });
+    }
+    let right = sides.pop().unwrap();
+    let left = sides.pop().unwrap();
+    Instance { left, right }
+}
+
+fn dump_case(dir: &str, name: &str, inst: &Instance) -> io::Result<PathBuf> {
+    let mut lines = vec![format!("{} {}", inst.left.len(), inst.right.len())];
+    for agents in &[&inst.left, &inst.right] {
+        for (cap, list) in agents.capacity.iter().zip(&agents.prefs) {
+            let ids: Vec<String> = list.iter().map(|x| x.to_string()).collect();
+            lines.push(format!("{}: {}", cap, if ids.is_empty() { "-".to_string() } else { ids.join(" ") }));
+        }
+    }
+    dump::lines(dir, name, lines)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 5);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("stable_matching_failures").to_string();
+    if max_n > 6 {
+        eprintln!("Error: --max-n is at most 6 (all matchings are enumerated)");
+        std::process::exit(1);
+    }
+
+    println!("Deferred acceptance vs. enumeration: {} cases, seed {}, sides <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let inst = random_instance(&mut rng, max_n);
+        if let Some(why) = check(&inst) {
+            failures += 1;
+            let name = format!("stable_seed{}_case{}.txt", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &inst));
+            println!("  FAIL  case {:<5} {}x{}: {}", case, inst.left.len(), inst.right.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,99 @@
+/*
+ * Randomized test of stopping a solve on request (SIGINT / SIGTERM)
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and solves
+ * each under one of the greedy modes with the flag of matchers::stop_on
+ * raised, as the signal handler of combi solve raises it. Checks:
+ *   - every general matcher, and auto (Hopcroft-Karp when bipartite),
+ *     returns exactly its initial matching, and it is a valid one,
+ *   - once the flag is lowered again, the next solve reaches the maximum.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt` (interrupted by hand).
+ *
+ * Usage: stop_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+
+/* Handed to matchers::stop_on; high only around a stopped solve. */
+static STOP: AtomicBool = AtomicBool::new(false);
+
+fn check(n: usize, edges: &[(usize, usize)], mode: i32, seed: u64) -> Option<String> {
+    let size = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, edges) {
+        Ok(m) => m.len(),
+        Err(_) => return None, /* rejected cleanly */
+    };
+    for algo in matchers::GENERAL_ALGORITHMS.iter().chain([matchers::AUTO_ALGORITHM].iter()) {
+        STOP.store(true, Ordering::Relaxed);
+        let stopped = matchers::solve(algo, n, edges, mode, seed, 0);
+        STOP.store(false, Ordering::Relaxed);
+        let sol = stopped.unwrap();
+        if let Some(e) = edge_lists::matching_error(n, edges, &sol.matching) {
+            return Some(format!("{} stopped, greedy mode {}: {}", algo, mode, e));
+        }
+        if sol.matching.len() != sol.greedy_size {
+            return Some(format!("{} stopped, greedy mode {}: went on from {} to {}",
+                                algo, mode, sol.greedy_size, sol.matching.len()));
+        }
+        let got = matchers::solve(algo, n, edges, mode, seed, 0).unwrap().matching.len();
+        if got != size {
+            return Some(format!("{} after a stop: {}, maximum {}", algo, got, size));
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("stop_failures").to_string();
+
+    println!("Stopped solves vs. their initial matching: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    matchers::stop_on(&STOP);
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = edge_lists::arbitrary(&mut rng, n);
+        let mode = (case % 5) as i32;
+        if let Some(why) = check(n, &edges, mode, case as u64) {
+            failures += 1;
+            let name = format!("stop_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} (greedy mode {}, --seed {}): {}", case, n, edges.len(), mode, case, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Randomized test of the --svg pictures
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them), every
+ * third one a random bipartite graph instead, and draws each over its
+ * maximum matching (Hopcroft-Karp's on the bipartite ones) with
+ * svg::write_svg, the force and circle layouts in turn. Checks:
+ *   - one circle per vertex, one red line per pair and one grey line per
+ *     other distinct edge,
+ *   - every coordinate finite and inside the picture,
+ *   - no two vertices on one spot in the circle layout,
+ *   - the pairs of a Hopcroft-Karp matching horizontal.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * or "left right m" when bipartite, ready for
+ * `combi solve <name>.txt --svg <name>.svg --layout <layout>`, or
+ * `hopcroft_karp <name>.txt --svg <name>.svg`.
+ *
+ * Usage: svg_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/svg.rs"]
+mod svg;
+
+fn check(left: usize, right: usize, edges: &[(usize, usize)], bipartite: bool, layout: svg::Layout)
+         -> Option<String> {
+    let n = left + right;
+    let (drawn, pairs, side) = if bipartite {
+        let pairs = hopcroft_karp::HopcroftKarp::new(left, right, edges).maximum_matching(0);
+        let ids = |p: &[(usize, usize)]| p.iter().map(|&(u, v)| (u, left + v)).collect::<Vec<_>>();
+        (ids(edges), ids(&pairs), Some(left))
+    } else {
+        match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, edges) {
+            Ok(m) => (edges.to_vec(), m, None),
+            Err(_) => return None, /* rejected cleanly */
+        }
+    };
+    let mut text = Vec::new();
+    svg::write_svg(&mut text, n, &drawn, &pairs, side, layout).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    let attr = |line: &str, name: &str| -> Option<f64> {
+        let at = line.find(&format!(" {}=\"", name))? + name.len() + 3;
+        line[at..].split('"').next()?.parse().ok()
+    };
+    let view = text.lines().next().and_then(|l| Some((attr(l, "width")?, attr(l, "height")?)));
+    let (width, height) = match view {
+        Some(v) => v,
+        None => return Some(format!("no width and height:\n{}", text)),
+    };
+    let inside = |x: f64, y: f64| x.is_finite() && y.is_finite() && x >= 0.0 && x <= width && y >= 0.0 && y <= height;
+    let (mut red, mut grey, mut spots) = (Vec::new(), 0, Vec::new());
+    let mut group = "";
+    for line in text.lines() {
+        if line.starts_with("<g ") {
+            group = if line.contains("\"red\"") { "red" } else { "other" };
+        } else if line.starts_with("<line ") {
+            let ends = (attr(line, "x1"), attr(line, "y1"), attr(line, "x2"), attr(line, "y2"));
+            match ends {
+                (Some(x1), Some(y1), Some(x2), Some(y2)) if inside(x1, y1) && inside(x2, y2) => {
+                    if group == "red" {
+                        red.push((y1, y2));
+                    } else {
+                        grey += 1;
+                    }
+                }
+                _ => return Some(format!("line outside the picture: {}", line)),
+            }
+        } else if line.starts_with("<circle ") {
+            match (attr(line, "cx"), attr(line, "cy")) {
+                (Some(x), Some(y)) if inside(x, y) => spots.push((x, y)),
+                _ => return Some(format!("circle outside the picture: {}", line)),
+            }
+        }
+    }
+    let mut distinct: Vec<(usize, usize)> = drawn.iter().filter(|&&(u, v)| u < n && v < n && u != v)
+        .map(|&(u, v)| (u.min(v), u.max(v))).collect();
+    distinct.sort_unstable();
+    distinct.dedup();
+    if spots.len() != n || red.len() != pairs.len() || grey + red.len() != distinct.len() {
+        return Some(format!("{} circles, {} red and {} grey lines for {} vertices, {} pairs, {} edges",
+                            spots.len(), red.len(), grey, n, pairs.len(), distinct.len()));
+    }
+    if layout == svg::Layout::Circle && side.is_none() {
+        let mut sorted = spots.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        if sorted.windows(2).any(|w| w[0] == w[1]) {
+            return Some("two vertices on one spot of the circle".to_string());
+        }
+    }
+    if side.is_some() {
+        if let Some(&(y1, y2)) = red.iter().find(|&&(y1, y2)| y1 != y2) {
+            return Some(format!("a Hopcroft-Karp pair is not horizontal: y {} and {}", y1, y2));
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("svg_failures").to_string();
+
+    println!("SVG pictures vs. their graph and matching: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let bipartite = case % 3 == 2;
+        let layout = if case.is_multiple_of(2) { svg::Layout::Force } else { svg::Layout::Circle };
+        let left = rng.below(max_n as u64 + 1) as usize;
+        let right = if bipartite { rng.below(max_n as u64 + 1) as usize } else { 0 };
+        let edges: Vec<(usize, usize)> = if bipartite {
+            let m = rng.below(3 * (left + right) as u64 + 4);
+            (0..m).map(|_| (rng.below(left.max(1) as u64) as usize, rng.below(right.max(1) as u64) as usize))
+                .filter(|&(u, v)| u < left && v < right)
+                .collect()
+        } else {
+            edge_lists::arbitrary(&mut rng, left)
+        };
+        if let Some(why) = check(left, right, &edges, bipartite, layout) {
+            failures += 1;
+            let name = format!("svg_seed{}_case{}", seed, case);
+            let saved = if bipartite {
+                dump::saved(dump::bipartite_edge_list(&dump_dir, &format!("{}.txt", name), left, right, &edges))
+            } else {
+                dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), left, &edges))
+            };
+            let shape = if bipartite { format!("bipartite {}x{}", left, right) } else { format!("n={}", left) };
+            let layout = if layout == svg::Layout::Force { "force" } else { "circle" };
+            println!("  FAIL  case {:<5} {} m={} (--layout {}): {}", case, shape, edges.len(), layout, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,145 @@
+/*
+ * Randomized test of the minimum T-join (t_join.rs) against exhaustive search
+ *
+ * Generates seeded random small weighted multigraphs (self-loops, parallel
+ * and zero-weight edges included) with a vertex set T: half of the time
+ * the odd-degree vertices of a random edge subset, so a join exists, and
+ * otherwise a random subset. Checks that
+ *   - min_t_join succeeds exactly when some edge subset has odd-degree set T,
+ *   - its join passes check_t_join and weighs as little as the lightest
+ *     such subset.
+ *
+ * A failing instance is written to the dump directory as a weighted "n m"
+ * edge list plus a T-set file, ready for `combi postman <graph> --t-set <T>`.
+ *
+ * Usage: t_join_oracle [--cases N] [--seed S] [--max-m M] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/t_join.rs"]
+mod t_join;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+type Edges = Vec<(usize, usize, i64)>;
+
+/* Weight of the lightest edge subset whose odd-degree vertices are T. */
+fn lightest_join(n: usize, edges: &Edges, t: &[usize]) -> Option<i64> {
+    let mut target = vec![false; n];
+    for &x in t { target[x] = true; }
+    let mut best = None;
+    for mask in 0u32..1 << edges.len() {
+        let mut odd = vec![false; n];
+        let mut weight = 0;
+        for (k, &(u, v, w)) in edges.iter().enumerate() {
+            if mask >> k & 1 == 1 {
+                odd[u] = !odd[u];
+                odd[v] = !odd[v];
+                weight += w;
+            }
+        }
+        if odd == target && best.is_none_or(|b| weight < b) {
+            best = Some(weight);
+        }
+    }
+    best
+}
+
+fn check(n: usize, edges: &Edges, t: &[usize]) -> Option<String> {
+    let best = lightest_join(n, edges, t);
+    match (t_join::min_t_join(n, edges, t), best) {
+        (Ok(join), Some(b)) => {
+            if let Some(e) = t_join::check_t_join(n, edges, t, &join).into_iter().next() {
+                return Some(e);
+            }
+            if join.weight != b {
+                return Some(format!("join weighs {}, the lightest weighs {}", join.weight, b));
+            }
+        }
+        (Ok(join), None) => return Some(format!("join of weight {} but the oracle finds none", join.weight)),
+        (Err(e), Some(b)) => return Some(format!("no join ({}) but the oracle finds one of weight {}", e, b)),
+        (Err(_), None) => {}
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_m: usize) -> (usize, Edges, Vec<usize>) {
+    let n = 1 + rng.below(8) as usize;
+    let m = rng.below(max_m as u64 + 1) as usize;
+    let mut edges = Vec::with_capacity(m);
+    for _ in 0..m {
+        let u = rng.below(n as u64) as usize;
+        let v = rng.below(n as u64) as usize;
+        edges.push((u, v, rng.below(10) as i64));
+    }
+    let mut odd = vec![false; n];
+    if rng.below(2) == 0 {
+        /* planted: the odd-degree vertices of a random edge subset */
+        for &(u, v, _) in &edges {
+            if rng.below(2) == 0 {
+                odd[u] = !odd[u];
+                odd[v] = !odd[v];
+            }
+        }
+    } else {
+        for x in &mut odd { *x = rng.below(3) == 0; }
+    }
+    let t = (0..n).filter(|&v| odd[v]).collect();
+    (n, edges, t)
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, edges: &Edges, t: &[usize]) -> io::Result<PathBuf> {
+    let graph = dump::weighted_edge_list(dir, &format!("{}.txt", name), n, None, edges)?;
+    let ids: Vec<String> = t.iter().map(|v| v.to_string()).collect();
+    dump::lines(dir, &format!("{}.tset", name), Some(ids.join(" ")))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 3000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_m: usize = args::parse_or_exit(&args, "--max-m", 14);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("t_join_failures").to_string();
+    if max_m > 20 {
+        eprintln!("Error: --max-m must be at most 20");
+        std::process::exit(1);
+    }
+
+    println!("Minimum T-join vs. exhaustive search: {} cases, seed {}, m <= {}", cases, seed, max_m);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    let mut feasible = 0;
+    for case in 0..cases {
+        let (n, edges, t) = random_instance(&mut rng, max_m);
+        if lightest_join(n, &edges, &t).is_some() { feasible += 1; }
+        if let Some(why) = check(n, &edges, &t) {
+            failures += 1;
+            let name = format!("t_join_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, n, &edges, &t));
+            println!("  FAIL  case {:<5} n={} m={} |T|={}: {}", case, n, edges.len(), t.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases, {} with a join)", cases, feasible);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,173 @@
+/*
+ * Randomized test of the lexicographic tie-break against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops and
+ * repeated edges, in any order and orientation), takes a maximum matching
+ * from one of the general matchers, and checks tie_break::lexicographic as
+ * `combi solve --tie-break lex` uses it:
+ *   - the result is a matching of the graph, sorted, each pair u < v,
+ *   - it is as large as the matcher's,
+ *   - it is the lexicographically smallest maximum matching, from a dynamic
+ *     program over vertex subsets (the lowest vertex of a subset is either
+ *     left out or matched to a neighbour, the rest solved the same way),
+ *   - refining it again changes nothing, and refining the matching with
+ *     its pairs turned around gives the same result.
+ * Every fourth case is instead a sparse graph of up to 10 x --max-n
+ * vertices (average degree about 3, where nested blossoms show up), checked
+ * for all but the exhaustive search.
+ * The matchers rotate through the general algorithms. (The weighted tie
+ * breaks, --tie-break lighter|heavier, are weighted blossom objectives and
+ * checked by weighted_oracle.rs.)
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --tie-break lex`.
+ *
+ * Usage: tie_break_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/dsu.rs"]
+mod dsu;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/tie_break.rs"]
+mod tie_break;
+
+/* The lexicographically smallest maximum matching of (n, edges). */
+fn exhaustive_lex(n: usize, edges: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    /* best[s]: the smallest of the maximum matchings of G[s]; its pairs
+     * are sorted, as every pair of G[s - v - w] is above (v, w) */
+    let mut best: Vec<Vec<(usize, usize)>> = vec![Vec::new(); 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut pick = best[rest].clone();
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            let sub = &best[rest & !(1 << w)];
+            if sub.len() + 1 < pick.len() { continue; }
+            let mut cand = Vec::with_capacity(sub.len() + 1);
+            cand.push((v, w));
+            cand.extend_from_slice(sub);
+            if cand.len() > pick.len() || cand < pick {
+                pick = cand;
+            }
+        }
+        best[s] = pick;
+    }
+    best[(1usize << n) - 1].clone()
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str, exhaustive: bool) -> Option<String> {
+    let matching = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    let refined = tie_break::lexicographic(n, edges, &matching);
+    let mut used = vec![false; n];
+    for &(u, v) in &refined {
+        let edge = edges.iter().any(|&(a, b)| (a, b) == (u, v) || (a, b) == (v, u));
+        if u >= v || used[u] || used[v] || !edge {
+            return Some(format!("({}, {}) of {:?} is not a free edge (u < v) of the graph", u, v, refined));
+        }
+        used[u] = true;
+        used[v] = true;
+    }
+    let mut sorted = refined.clone();
+    sorted.sort_unstable();
+    if sorted != refined {
+        return Some(format!("pairs not sorted: {:?}", refined));
+    }
+    if refined.len() != matching.len() {
+        return Some(format!("{} pairs, {} found {}", refined.len(), algo, matching.len()));
+    }
+    if exhaustive {
+        let expect = exhaustive_lex(n, edges);
+        if refined != expect {
+            return Some(format!("refined to {:?}, the smallest maximum matching is {:?}", refined, expect));
+        }
+    }
+    let again = tie_break::lexicographic(n, edges, &refined);
+    if again != refined {
+        return Some(format!("refining {:?} again gave {:?}", refined, again));
+    }
+    let turned: Vec<(usize, usize)> = matching.iter().rev().map(|&(u, v)| (v, u)).collect();
+    let other = tie_break::lexicographic(n, edges, &turned);
+    if other != refined {
+        return Some(format!("from the pairs turned around: {:?}, from {}'s: {:?}", other, algo, refined));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, sparse: bool) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = if sparse { 3.0 / n.max(1) as f64 } else { rng.next_f64() * 0.7 };
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push(if rng.below(2) == 0 { (u, v) } else { (v, u) });
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("tie_break_failures").to_string();
+    if max_n > 16 {
+        eprintln!("Error: --max-n is at most 16 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Lexicographic tie-break vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let sparse = case % 4 == 3;
+        let (n, edges) = random_graph(&mut rng, if sparse { 10 * max_n } else { max_n }, sparse);
+        let algo = matchers::GENERAL_ALGORITHMS[case % matchers::GENERAL_ALGORITHMS.len()];
+        if let Some(why) = check(n, &edges, algo, !sparse) {
+            failures += 1;
+            let name = format!("tie_break_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}): {}", case, n, edges.len(), algo, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
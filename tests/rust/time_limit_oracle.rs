@@ -0,0 +1,108 @@
+/*
+ * Randomized test of the --time-limit gap bound
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them) and a
+ * random, usually not maximal, matching of them, as a run stopped by
+ * --time-limit leaves it. Checks that time_limit::gap_bound
+ *   - is at least the number of pairs the matching is short of the
+ *     maximum, so 0 only for a maximum matching,
+ *   - comes from at least two open free vertices per pair.
+ *
+ * A failing instance is written to the dump directory as <name>.txt ("n m")
+ * and the matching as <name>.match, ready for
+ * `combi check <name>.txt <name>.match --maximum`, which compares it with a
+ * maximum matching.
+ *
+ * Usage: time_limit_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/time_limit.rs"]
+mod time_limit;
+
+fn check(n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)]) -> Option<String> {
+    let size = match matchers::maximum_matching(matchers::DEFAULT_ALGORITHM, n, edges) {
+        Ok(m) => m.len(),
+        Err(_) => return None, /* rejected cleanly */
+    };
+    let (bound, open) = time_limit::gap_bound(n, edges, matching);
+    if size - matching.len() > bound {
+        return Some(format!("{} pairs of {}: bound {} below the gap {}",
+                            matching.len(), size, bound, size - matching.len()));
+    }
+    if open < 2 * bound {
+        return Some(format!("bound {} from only {} open free vertices", bound, open));
+    }
+    None
+}
+
+fn dump_case(dir: &str, name: &str, n: usize, edges: &[(usize, usize)], matching: &[(usize, usize)])
+             -> io::Result<PathBuf> {
+    let graph = dump::edge_list(dir, &format!("{}.txt", name), n, edges)?;
+    let pairs = matching.iter().map(|&(u, v)| format!("{} {}", u, v));
+    dump::lines(dir, &format!("{}.match", name), Some(matching.len().to_string()).into_iter().chain(pairs))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("time_limit_failures").to_string();
+
+    println!("Gap bounds vs. the maximum size: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = edge_lists::arbitrary(&mut rng, n);
+        /* first fit over the edges, each free pair taken with probability 2/3 */
+        let mut matched = vec![false; n];
+        let mut matching = Vec::new();
+        for &(u, v) in &edges {
+            if u < n && v < n && u != v && !matched[u] && !matched[v] && rng.below(3) > 0 {
+                matched[u] = true;
+                matched[v] = true;
+                matching.push((u, v));
+            }
+        }
+        if let Some(why) = check(n, &edges, &matching) {
+            failures += 1;
+            let name = format!("time_limit_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, n, &edges, &matching));
+            println!("  FAIL  case {:<5} n={} m={} |matching|={}: {}", case, n, edges.len(), matching.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,113 @@
+/*
+ * Randomized test of the --trace logs
+ *
+ * Generates seeded random edge lists (ids past n, huge ids, self-loops and
+ * repeated edges, as tests/rust/common/edge_lists.rs makes them), every
+ * other one cut to a random bipartite graph so that auto runs
+ * Hopcroft-Karp, and solves each with every general matcher and auto under
+ * one of the greedy modes, a tracer passed to matchers::trace_on. Checks
+ * that trace::replay accepts the log (each path augmenting for the
+ * matching so far, blossoms on graph edges, phases in order) and that it
+ * starts from the greedy pairs and ends at the final matching size.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi solve <name>.txt --trace <name>.trace` and
+ * `combi replay <name>.txt <name>.trace`.
+ *
+ * Usage: trace_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::sync::Mutex;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "common/edge_lists.rs"]
+mod edge_lists;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/trace.rs"]
+mod trace;
+
+/* The events of the current solve, one line each as in a --trace file. */
+static EVENTS: Mutex<String> = Mutex::new(String::new());
+
+fn trace_event(event: &str, vertices: &[usize]) {
+    let mut log = EVENTS.lock().unwrap();
+    log.push_str(event);
+    for v in vertices {
+        log.push_str(&format!(" {}", v));
+    }
+    log.push('\n');
+}
+
+fn check(n: usize, edges: &[(usize, usize)], mode: i32, seed: u64) -> Option<String> {
+    for algo in matchers::GENERAL_ALGORITHMS.iter().chain([matchers::AUTO_ALGORITHM].iter()) {
+        EVENTS.lock().unwrap().clear();
+        let sol = match matchers::solve(algo, n, edges, mode, seed, 0) {
+            Ok(s) => s,
+            Err(_) => return None, /* rejected cleanly */
+        };
+        let log = format!("{}done {}\n", EVENTS.lock().unwrap(), sol.matching.len());
+        let valid: Vec<(usize, usize)> = edges.iter().cloned().filter(|&(u, v)| u < n && v < n).collect();
+        match trace::replay(n, &valid, &log) {
+            Err(e) => return Some(format!("{}, greedy mode {}: replay: {}", algo, mode, e)),
+            Ok(r) if r.start != sol.greedy_size => {
+                return Some(format!("{}, greedy mode {}: trace starts from {} pairs, the greedy matching has {}",
+                                    algo, mode, r.start, sol.greedy_size));
+            }
+            Ok(_) => {}
+        }
+    }
+    None
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 20000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 40);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("trace_failures").to_string();
+
+    println!("Trace logs vs. replay: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    matchers::trace_on(&trace_event);
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let n = rng.below(max_n as u64 + 1) as usize;
+        let edges = if case.is_multiple_of(2) {
+            edge_lists::arbitrary(&mut rng, n)
+        } else {
+            let left = rng.below(n as u64 + 1) as usize;
+            edge_lists::arbitrary(&mut rng, n).into_iter().filter(|&(u, v)| (u < left) != (v < left)).collect()
+        };
+        let mode = (case % 5) as i32;
+        if let Some(why) = check(n, &edges, mode, case as u64) {
+            failures += 1;
+            let name = format!("trace_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} (greedy mode {}, --seed {}): {}", case, n, edges.len(), mode, case, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: general, bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
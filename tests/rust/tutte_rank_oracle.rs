@@ -0,0 +1,178 @@
+/*
+ * Randomized test of the Tutte matrix rank against the matchers
+ *
+ * Generates seeded random graphs (any density, with self-loops and
+ * repeated edges; sparse ones with many components; odd cycles joined by
+ * paths, where the maximum matching needs blossoms) and checks
+ * tutte_rank.rs against a maximum matching nu:
+ *   - rank / 2 summed over the components never exceeds nu, for any
+ *     number of trials,
+ *   - it equals nu (each trial errs with probability at most n / p, about
+ *     3e-8 here, so a miss is a bug),
+ *   - the component count and the largest component match a union-find
+ *     over the edges, and the error bound is (n / p)^trials.
+ * nu comes from the general matchers in rotation.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `tutte_rank <name>.txt --trials K --seed S`.
+ *
+ * Usage: tutte_rank_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/tutte-rank/rust/tutte_rank.rs"]
+#[allow(dead_code)]
+mod tutte_rank;
+
+/* Number of components with an edge, and the size of the largest. */
+fn components(n: usize, edges: &[(usize, usize)]) -> (usize, usize) {
+    fn find(parent: &mut [usize], mut x: usize) -> usize {
+        while parent[x] != x {
+            parent[x] = parent[parent[x]];
+            x = parent[x];
+        }
+        x
+    }
+    let mut parent: Vec<usize> = (0..n).collect();
+    let mut touched = vec![false; n];
+    for &(u, v) in edges {
+        if u == v { continue; }
+        touched[u] = true;
+        touched[v] = true;
+        let (a, b) = (find(&mut parent, u), find(&mut parent, v));
+        parent[a] = b;
+    }
+    let mut size = vec![0usize; n];
+    for v in (0..n).filter(|&v| touched[v]) {
+        let r = find(&mut parent, v);
+        size[r] += 1;
+    }
+    (size.iter().filter(|&&s| s > 0).count(), size.iter().copied().max().unwrap_or(0))
+}
+
+fn check(n: usize, edges: &[(usize, usize)], algo: &str, trials: usize, seed: u64) -> Option<String> {
+    let nu = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m.len(),
+        Err(e) => return Some(e),
+    };
+    let est = match tutte_rank::tutte_matching_size(n, edges, trials, seed) {
+        Ok(est) => est,
+        Err(e) => return Some(e),
+    };
+    if est.size > nu {
+        return Some(format!("rank / 2 = {} above nu = {}", est.size, nu));
+    }
+    if est.size != nu {
+        return Some(format!("rank / 2 = {} after {} trial(s), nu = {}", est.size, trials, nu));
+    }
+    let (count, largest) = components(n, edges);
+    if est.components != count || est.largest != largest {
+        return Some(format!("{} components (largest {}), union-find says {} (largest {})",
+                            est.components, est.largest, count, largest));
+    }
+    let bound = (n as f64 / 2_147_483_647.0).powi(trials.max(1) as i32);
+    if est.trials != trials.max(1) || (est.error_bound - bound).abs() > bound * 1e-9 {
+        return Some(format!("{} trials, error bound {:e}; expected {} and {:e}",
+                            est.trials, est.error_bound, trials.max(1), bound));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, shape: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    match shape {
+        // Any density, with the loops and repeats the loaders accept
+        0 => {
+            let p = rng.next_f64() * 0.5;
+            for u in 0..n {
+                for v in u..n {
+                    if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                        edges.push((u, v));
+                        if rng.below(10) == 0 { edges.push((v, u)); }
+                    }
+                }
+            }
+        }
+        // Average degree below 2: many small components and isolated vertices
+        1 => {
+            for _ in 0..rng.below(n as u64 + 1) {
+                edges.push((rng.below(n as u64) as usize, rng.below(n as u64) as usize));
+            }
+        }
+        // Odd cycles of 3 to 9 vertices chained by single edges
+        _ => {
+            let mut label: Vec<usize> = (0..n).collect();
+            rng.shuffle(&mut label);
+            let mut start = 0;
+            while start < n {
+                let len = (3 + 2 * rng.below(4) as usize).min(n - start);
+                if len == 2 {
+                    edges.push((label[start], label[start + 1]));
+                }
+                if len >= 3 {
+                    for i in 0..len {
+                        edges.push((label[start + i], label[start + (i + 1) % len]));
+                    }
+                }
+                if start > 0 && rng.below(3) != 0 {
+                    edges.push((label[start - 1 - rng.below(start as u64) as usize], label[start]));
+                }
+                start += len;
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 60);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("tutte_rank_failures").to_string();
+
+    println!("Tutte matrix rank vs. maximum matching: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_graph(&mut rng, max_n, case % 3);
+        let algo = matchers::GENERAL_ALGORITHMS[case / 3 % matchers::GENERAL_ALGORITHMS.len()];
+        let trials = 1 + case % 4;
+        let tutte_seed = rng.next_u64();
+        if let Some(why) = check(n, &edges, algo, trials, tutte_seed) {
+            failures += 1;
+            let name = format!("tutte_rank_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={} ({}, --trials {} --seed {}): {}",
+                     case, n, edges.len(), algo, trials, tutte_seed, why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases: dense, sparse, odd-cycle chains)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ * Randomized test of the vertex classification against exhaustive search
+ *
+ * Generates seeded random small graphs (any density, with self-loops,
+ * repeated edges and isolated vertices, in any order and orientation),
+ * takes a maximum matching from one of the general matchers, and checks
+ * vertex_classes.rs as `combi classify` uses it:
+ *   - each vertex's class (matched in every, some or no maximum matching)
+ *     agrees with a dynamic program over vertex subsets that keeps, for the
+ *     maximum matchings of each subset, the vertices all of them cover and
+ *     those some of them cover,
+ *   - check_classification() accepts the matching, and certify() (the
+ *     --certify status) rejects it with one pair taken out (no longer
+ *     maximum),
+ *   - the Gallai-Edmonds labels are the same from the next matcher's
+ *     maximum matching.
+ * Every fourth case is instead a sparse graph of up to 10 x --max-n
+ * vertices (average degree about 3, where nested blossoms show up),
+ * checked for all but the exhaustive search.
+ *
+ * A failing instance is written to the dump directory in the "n m" format,
+ * ready for `combi classify <name>.txt --show`.
+ *
+ * Usage: vertex_classes_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "../../algorithms/common/rust/dot.rs"]
+mod dot;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/error.rs"]
+mod error;
+#[path = "../../algorithms/common/rust/matchers.rs"]
+mod matchers;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/vertex_classes.rs"]
+mod vertex_classes;
+
+use vertex_classes::VertexClass;
+
+/* The class of each vertex of (n, edges), by the maximum matchings of
+ * every vertex subset: (size, vertices all cover, vertices some cover). */
+fn exhaustive_classes(n: usize, edges: &[(usize, usize)]) -> Vec<VertexClass> {
+    let mut adj = vec![0usize; n];
+    for &(u, v) in edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    let mut best = vec![(0usize, 0usize, 0usize); 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut pick = best[rest];
+        let mut nbrs = adj[v] & rest;
+        while nbrs != 0 {
+            let w = nbrs.trailing_zeros() as usize;
+            nbrs &= nbrs - 1;
+            let (size, all, some) = best[rest & !(1 << w)];
+            let cand = (size + 1, all | 1 << v | 1 << w, some | 1 << v | 1 << w);
+            if cand.0 > pick.0 {
+                pick = cand;
+            } else if cand.0 == pick.0 {
+                pick = (pick.0, pick.1 & cand.1, pick.2 | cand.2);
+            }
+        }
+        best[s] = pick;
+    }
+    let (_, all, some) = best[(1usize << n) - 1];
+    (0..n).map(|v| {
+        if all & 1 << v != 0 {
+            VertexClass::Always
+        } else if some & 1 << v != 0 {
+            VertexClass::Sometimes
+        } else {
+            VertexClass::Never
+        }
+    }).collect()
+}
+
+fn check(n: usize, edges: &[(usize, usize)], case: usize, exhaustive: bool) -> Option<String> {
+    let algos = matchers::GENERAL_ALGORITHMS;
+    let (algo, other) = (algos[case % algos.len()], algos[(case + 1) % algos.len()]);
+    let matching = match matchers::maximum_matching(algo, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("{}: {}", algo, e)),
+    };
+    let c = vertex_classes::vertex_classification(n, edges, &matching);
+    if let Err(errors) = vertex_classes::check_classification(n, edges, &matching, &c) {
+        return Some(format!("({}) {}", algo, errors.join("; ")));
+    }
+    if exhaustive {
+        let expect = exhaustive_classes(n, edges);
+        if c.class != expect {
+            let v = (0..n).find(|&v| c.class[v] != expect[v]).unwrap_or(0);
+            return Some(format!("({}) vertex {} is {}, exhaustive search finds it {}", algo, v, c.class[v].name(),
+                                expect[v].name()));
+        }
+    }
+    if !matching.is_empty() && vertex_classes::certify(n, edges, &matching[1..]).is_ok() {
+        return Some(format!("({}) accepted with the pair {:?} taken out", algo, matching[0]));
+    }
+    let second = match matchers::maximum_matching(other, n, edges) {
+        Ok(m) => m,
+        Err(e) => return Some(format!("{}: {}", other, e)),
+    };
+    let d = vertex_classes::vertex_classification(n, edges, &second);
+    if d.label != c.label {
+        let v = (0..n).find(|&v| c.label[v] != d.label[v]).unwrap_or(0);
+        return Some(format!("vertex {}: {:?} from {}'s matching, {:?} from {}'s", v, c.label[v], algo, d.label[v],
+                            other));
+    }
+    None
+}
+
+fn random_graph(rng: &mut rng::Rng, max_n: usize, sparse: bool) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let p = if sparse { 3.0 / n.max(1) as f64 } else { rng.next_f64() * 0.7 };
+    let mut edges = Vec::new();
+    for u in 0..n {
+        for v in u..n {
+            if (u != v || rng.below(20) == 0) && rng.next_f64() < p {
+                edges.push(if rng.below(2) == 0 { (u, v) } else { (v, u) });
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 14);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("vertex_classes_failures").to_string();
+    if max_n > 18 {
+        eprintln!("Error: --max-n is at most 18 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Vertex classification vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let sparse = case % 4 == 3;
+        let (n, edges) = random_graph(&mut rng, if sparse { 10 * max_n } else { max_n }, sparse);
+        if let Some(why) = check(n, &edges, case, !sparse) {
+            failures += 1;
+            let name = format!("vertex_classes_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &format!("{}.txt", name), n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
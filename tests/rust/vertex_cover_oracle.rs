@@ -0,0 +1,205 @@
+/*
+ * Randomized test of the vertex cover kernel and branching against
+ * exhaustive search
+ *
+ * Generates seeded random small graphs (sparse to dense, with self-loops,
+ * parallel edges, isolated vertices and planted small covers) and checks
+ * vertex_cover.rs:
+ *   - min_vertex_cover returns a cover of the optimum size,
+ *   - with k = OPT it still finds one, with k = OPT - 1 it reports none,
+ *   - the LP kernel is safe: some optimum contains every forced vertex and
+ *     no excluded one, and the LP bound does not exceed OPT,
+ *   - approx_cover returns a cover of at most 2 OPT vertices, with a lower
+ *     bound of at most OPT,
+ *   - on a random bipartite graph of the same size, konig_cover returns a
+ *     cover of the optimum size that check_bipartite_cover accepts.
+ *
+ * A failing instance is written to the dump directory as an "n m" edge
+ * list, ready for `combi cover`, or as a "left right m" file, ready for
+ * `combi cover --bipartite`.
+ *
+ * Usage: vertex_cover_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/hopcroft-karp/rust/hopcroft_karp.rs"]
+#[allow(dead_code)]
+mod hopcroft_karp;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/vertex_cover.rs"]
+mod vertex_cover;
+
+/* Smallest cover over all subsets, restricted to those containing `must`
+ * and avoiding `never` (bit masks). */
+fn brute_force(n: usize, edges: &[(usize, usize)], must: u32, never: u32) -> Option<usize> {
+    let mut best = None;
+    for set in 0u32..(1 << n) {
+        if set & must != must || set & never != 0 { continue; }
+        let size = set.count_ones() as usize;
+        if best.is_some_and(|b| size >= b) { continue; }
+        if edges.iter().all(|&(u, v)| set >> u & 1 == 1 || set >> v & 1 == 1) {
+            best = Some(size);
+        }
+    }
+    best
+}
+
+fn mask(vertices: &[usize]) -> u32 {
+    vertices.iter().fold(0, |m, &v| m | 1 << v)
+}
+
+fn check(n: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let opt = brute_force(n, edges, 0, 0).unwrap();
+    let cover = match vertex_cover::min_vertex_cover(n, edges, None) {
+        Some(c) => c,
+        None => return Some("no cover without a budget".to_string()),
+    };
+    if let Some(e) = vertex_cover::check_cover(n, edges, &cover).first() {
+        return Some(e.clone());
+    }
+    if cover.len() != opt {
+        return Some(format!("cover of {} vertices, the search finds {}", cover.len(), opt));
+    }
+    match vertex_cover::min_vertex_cover(n, edges, Some(opt)) {
+        Some(ref c) if c.len() == opt => {}
+        other => return Some(format!("k = {}: got {:?}", opt, other.map(|c| c.len()))),
+    }
+    if opt > 0 {
+        if let Some(c) = vertex_cover::min_vertex_cover(n, edges, Some(opt - 1)) {
+            return Some(format!("k = {}: returned {} vertices", opt - 1, c.len()));
+        }
+    }
+
+    let kernel = vertex_cover::lp_kernel(n, edges);
+    if kernel.lp_bound > opt {
+        return Some(format!("LP bound {} above the optimum {}", kernel.lp_bound, opt));
+    }
+    if 2 * (opt - kernel.forced.len()) < kernel.kernel.len() {
+        return Some(format!("kernel of {} vertices with {} left to cover", kernel.kernel.len(),
+                            opt - kernel.forced.len()));
+    }
+    match brute_force(n, edges, mask(&kernel.forced), mask(&kernel.excluded)) {
+        Some(b) if b == opt => {}
+        b => return Some(format!("kernel is unsafe: best cover respecting it {:?}, optimum {}", b, opt)),
+    }
+
+    let a = vertex_cover::approx_cover(n, edges, &kernel);
+    if let Some(e) = vertex_cover::check_cover(n, edges, &a.cover).first() {
+        return Some(format!("approximation: {}", e));
+    }
+    if a.cover.len() > 2 * opt || a.lower_bound > opt {
+        return Some(format!("approximation of {} vertices with lower bound {}, optimum {}",
+                            a.cover.len(), a.lower_bound, opt));
+    }
+    None
+}
+
+fn check_bipartite(left: usize, right: usize, edges: &[(usize, usize)]) -> Option<String> {
+    let flat: Vec<(usize, usize)> = edges.iter().map(|&(u, v)| (u, left + v)).collect();
+    let opt = brute_force(left + right, &flat, 0, 0).unwrap();
+    let cover = vertex_cover::konig_cover(left, right, edges);
+    if let Some(e) = vertex_cover::check_bipartite_cover(left, right, edges, &cover).first() {
+        return Some(format!("Konig: {}", e));
+    }
+    let size = cover.left.len() + cover.right.len();
+    if size != opt {
+        return Some(format!("Konig cover of {} vertices, the search finds {}", size, opt));
+    }
+    None
+}
+
+fn random_bipartite(rng: &mut rng::Rng, max_n: usize) -> (usize, usize, Vec<(usize, usize)>) {
+    let left = rng.below(max_n as u64 / 2 + 1) as usize;
+    let right = rng.below((max_n - left) as u64 + 1) as usize;
+    let p = rng.next_f64();
+    let mut edges = Vec::new();
+    for u in 0..left {
+        for v in 0..right {
+            if rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((u, v)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (left, right, edges)
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> (usize, Vec<(usize, usize)>) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    if n == 0 { return (n, edges); }
+    let p = rng.next_f64() * if rng.below(2) == 0 { 0.35 } else { 1.0 };
+    /* Sometimes plant a small cover: only edges touching a few hubs. */
+    let hubs = if rng.below(4) == 0 { 1 + rng.below(3) as usize } else { n };
+    for u in 0..n {
+        for v in u + 1..n {
+            if (u < hubs || v < hubs) && rng.next_f64() < p {
+                edges.push((u, v));
+                if rng.below(10) == 0 { edges.push((v, u)); }
+            }
+        }
+    }
+    if rng.below(6) == 0 {
+        let v = rng.below(n as u64) as usize;
+        edges.push((v, v));
+    }
+    let mut perm: Vec<usize> = (0..n).collect();
+    rng.shuffle(&mut perm);
+    for e in &mut edges {
+        *e = (perm[e.0], perm[e.1]);
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 16);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("vertex_cover_failures").to_string();
+    if max_n > 24 {
+        eprintln!("Error: --max-n is at most 24 (the search is exhaustive)");
+        std::process::exit(1);
+    }
+
+    println!("Vertex cover vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_instance(&mut rng, max_n);
+        if let Some(why) = check(n, &edges) {
+            failures += 1;
+            let name = format!("vertex_cover_seed{}_case{}.txt", seed, case);
+            let saved = dump::saved(dump::edge_list(&dump_dir, &name, n, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+        let (left, right, edges) = random_bipartite(&mut rng, max_n);
+        if let Some(why) = check_bipartite(left, right, &edges) {
+            failures += 1;
+            let name = format!("vertex_cover_seed{}_case{}_bipartite.txt", seed, case);
+            let saved = dump::saved(dump::bipartite_edge_list(&dump_dir, &name, left, right, &edges));
+            println!("  FAIL  case {:<5} {}x{} m={}: {}", case, left, right, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases, general and bipartite)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
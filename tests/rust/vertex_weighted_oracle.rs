@@ -0,0 +1,186 @@
+/*
+ * Randomized test of maximum vertex-weight matching against exhaustive
+ * search and the weighted blossom solver
+ *
+ * Generates seeded random small graphs (sparse and dense, odd cycles for
+ * blossoms, many ties, zero and wide-ranging vertex weights) and checks
+ * that vertex_weighted.rs returns a valid matching whose matched weight is:
+ *   - the optimum over all matchings, by a subset dynamic program, for
+ *     graphs of up to --max-n vertices,
+ *   - the weight of weighted_blossom.rs's maximum weight matching with
+ *     edge weights w(u) + w(v), for graphs of up to 4 * --max-n vertices,
+ * and that check_matching agrees with the reported weight.
+ *
+ * A failing instance is written to the dump directory as an "n m" edge
+ * list plus a ".weights" file, ready for `combi vweight --weights`.
+ *
+ * Usage: vertex_weighted_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/common/rust/vertex_weighted.rs"]
+mod vertex_weighted;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+
+struct Instance {
+    n: usize,
+    edges: Vec<(usize, usize)>,
+    weights: Vec<u64>,
+}
+
+/* Best matched weight over all matchings: best[S] for the vertex set S
+ * still free, deciding the lowest vertex of S first. */
+fn exhaustive(inst: &Instance) -> u64 {
+    let n = inst.n;
+    let mut adj = vec![0usize; n];
+    for &(u, v) in &inst.edges {
+        if u != v {
+            adj[u] |= 1 << v;
+            adj[v] |= 1 << u;
+        }
+    }
+    let mut best = vec![0u64; 1 << n];
+    for s in 1..(1usize << n) {
+        let v = s.trailing_zeros() as usize;
+        let rest = s & !(1 << v);
+        let mut b = best[rest];
+        let mut cand = adj[v] & rest;
+        while cand != 0 {
+            let u = cand.trailing_zeros() as usize;
+            cand &= cand - 1;
+            b = b.max(inst.weights[v] + inst.weights[u] + best[rest & !(1 << u)]);
+        }
+        best[s] = b;
+    }
+    best[(1 << n) - 1]
+}
+
+fn blossom_weight(inst: &Instance) -> u64 {
+    let edges: Vec<(usize, usize, i64)> = inst.edges.iter()
+        .filter(|&&(u, v)| u != v)
+        .map(|&(u, v)| (u, v, (inst.weights[u] + inst.weights[v]) as i64))
+        .collect();
+    weighted_blossom::max_weight_matching(inst.n, &edges, false).iter()
+        .map(|&(u, v)| inst.weights[u] + inst.weights[v])
+        .sum()
+}
+
+fn check(inst: &Instance, exact: bool) -> Option<String> {
+    let r = vertex_weighted::max_vertex_weight_matching(inst.n, &inst.edges, &inst.weights);
+    let (errors, weight) = vertex_weighted::check_matching(inst.n, &inst.edges, &inst.weights, &r.matching);
+    if !errors.is_empty() {
+        return Some(format!("{:?}: {:?}", r.matching, errors));
+    }
+    if weight != r.weight {
+        return Some(format!("reported weight {}, the pairs weigh {}", r.weight, weight));
+    }
+    let (best, by) = if exact { (exhaustive(inst), "exhaustive search") } else { (blossom_weight(inst), "weighted blossom") };
+    if weight != best {
+        return Some(format!("matched weight {}, {} finds {}", weight, by, best));
+    }
+    None
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> Instance {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    match rng.below(3) {
+        0 => {
+            /* random density */
+            let p = rng.next_f64();
+            for u in 0..n {
+                for v in u + 1..n {
+                    if rng.next_f64() < p { edges.push((u, v)); }
+                }
+            }
+        }
+        1 => {
+            /* odd cycles sharing vertices, plus pendants */
+            let mut v = 0;
+            while n >= 3 && v + 3 <= n {
+                let mut len = 3 + 2 * rng.below(3) as usize;
+                while v + len > n { len -= 2; }
+                for i in 0..len { edges.push((v + i, v + (i + 1) % len)); }
+                if v > 0 { edges.push((v - 1 - rng.below(v as u64) as usize, v)); }
+                v += len - rng.below(2) as usize;
+            }
+            for u in v..n {
+                if u > 0 { edges.push((rng.below(u as u64) as usize, u)); }
+            }
+        }
+        _ => {
+            /* sparse, average degree about 2 */
+            for _ in 0..n {
+                if n >= 2 {
+                    let u = rng.below(n as u64) as usize;
+                    let v = rng.below(n as u64) as usize;
+                    edges.push((u, v));
+                }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    let weights = match rng.below(4) {
+        0 => (0..n).map(|_| 1 + rng.below(3)).collect(),     /* many ties */
+        1 => (0..n).map(|_| rng.below(3)).collect(),         /* zeros */
+        2 => (0..n).map(|_| rng.below(1 << 30)).collect(),   /* wide range */
+        _ => (0..n).map(|_| 1 << rng.below(10)).collect(),   /* powers of two */
+    };
+    Instance { n, edges, weights }
+}
+
+fn dump_case(dir: &str, name: &str, inst: &Instance) -> io::Result<PathBuf> {
+    let graph = dump::edge_list(dir, &format!("{}.txt", name), inst.n, &inst.edges)?;
+    dump::lines(dir, &format!("{}.weights", name), inst.weights.iter().enumerate().map(|(v, x)| format!("{} {}", v, x)))?;
+    Ok(graph)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 10000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 12);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("vertex_weighted_failures").to_string();
+    if max_n > 20 {
+        eprintln!("Error: --max-n is at most 20 (the exhaustive search is over vertex subsets)");
+        std::process::exit(1);
+    }
+
+    println!("Vertex-weighted matching vs. exhaustive search and weighted blossom: {} cases, seed {}, n <= {} / {}",
+             cases, seed, max_n, 4 * max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let exact = case % 2 == 0;
+        let inst = random_instance(&mut rng, if exact { max_n } else { 4 * max_n });
+        if let Some(why) = check(&inst, exact) {
+            failures += 1;
+            let name = format!("vweight_seed{}_case{}", seed, case);
+            let saved = dump::saved(dump_case(&dump_dir, &name, &inst));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, inst.n, inst.edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}
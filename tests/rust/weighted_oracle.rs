@@ -0,0 +1,214 @@
+/*
+ * Randomized test of the weighted blossom solver against exhaustive search
+ *
+ * Generates seeded random small weighted multigraphs (sparse and dense,
+ * small and wide weight ranges, zero and negative weights) and checks all
+ * four objectives of weighted_blossom.rs:
+ *   maximum weight, maximum weight among maximum-cardinality matchings,
+ *   minimum weight among maximum-cardinality matchings (combi solve
+ *   --minimize), and minimum-weight perfect matching (or its absence),
+ * against a search over all matchings, and checks that the final duals of
+ * each run satisfy complementary slackness (check_duals). The maximum
+ * weight is also checked for Gabow's scaling solver (gabow_scaling.rs).
+ *
+ * A failing instance is written to the dump directory as an "n m" edge
+ * list with weights, ready for weighted_blossom_rust.
+ *
+ * Usage: weighted_oracle [--cases N] [--seed S] [--max-n N] [--dump-dir DIR]
+ * Exit status 1 on any failure.
+ */
+
+#![allow(clippy::duplicate_mod)]
+
+use std::env;
+
+#[path = "../../algorithms/common/rust/args.rs"]
+mod args;
+#[path = "common/dump.rs"]
+mod dump;
+#[path = "../../algorithms/common/rust/rng.rs"]
+mod rng;
+#[path = "../../algorithms/weighted-blossom/rust/weighted_blossom.rs"]
+#[allow(dead_code)]
+mod weighted_blossom;
+#[path = "../../algorithms/gabow-optimized/rust/gabow_scaling.rs"]
+#[allow(dead_code)]
+mod gabow_scaling;
+
+type Edges = Vec<(usize, usize, i64)>;
+
+/* Exhaustive search over all matchings: the best weight, the best
+ * (cardinality, weight), the lightest of the largest matchings and the
+ * lightest perfect matching. */
+struct Search<'a> {
+    n: usize,
+    w: &'a [Vec<Option<i64>>],
+    best_weight: i64,
+    best_card: (usize, i64),
+    lightest_card: (usize, i64),
+    best_perfect: Option<i64>,
+}
+
+impl<'a> Search<'a> {
+    fn go(&mut self, used: &mut Vec<bool>, v: usize, card: usize, weight: i64) {
+        let mut v = v;
+        while v < self.n && used[v] { v += 1; }
+        if v == self.n {
+            self.best_weight = self.best_weight.max(weight);
+            if (card, weight) > self.best_card { self.best_card = (card, weight); }
+            if (card, -weight) > (self.lightest_card.0, -self.lightest_card.1) { self.lightest_card = (card, weight); }
+            if 2 * card == self.n && self.best_perfect.is_none_or(|b| weight < b) {
+                self.best_perfect = Some(weight);
+            }
+            return;
+        }
+        used[v] = true;
+        self.go(used, v + 1, card, weight);
+        for u in v + 1..self.n {
+            if let (false, Some(w)) = (used[u], self.w[v][u]) {
+                used[u] = true;
+                self.go(used, v + 1, card + 1, weight + w);
+                used[u] = false;
+            }
+        }
+        used[v] = false;
+    }
+}
+
+/* Matching weight, or why it is not a matching of (n, edges). */
+fn weigh(n: usize, w: &[Vec<Option<i64>>], m: &[(usize, usize)]) -> Result<(usize, i64), String> {
+    let mut used = vec![false; n];
+    let mut total = 0;
+    for &(u, v) in m {
+        if u >= n || v >= n || used[u] || used[v] {
+            return Err(format!("bad pair ({}, {})", u, v));
+        }
+        used[u] = true;
+        used[v] = true;
+        total += w[u][v].ok_or_else(|| format!("pair ({}, {}) is not an edge", u, v))?;
+    }
+    Ok((m.len(), total))
+}
+
+/* Pair weights: the heaviest parallel edge when maximizing, the lightest
+ * when minimizing. */
+fn pair_weights(n: usize, edges: &[(usize, usize, i64)], heaviest: bool) -> Vec<Vec<Option<i64>>> {
+    let mut w = vec![vec![None; n]; n];
+    for &(u, v, x) in edges {
+        if u == v { continue; }
+        let cur: Option<i64> = w[u][v];
+        let best = Some(cur.map_or(x, |c| if heaviest { c.max(x) } else { c.min(x) }));
+        w[u][v] = best;
+        w[v][u] = best;
+    }
+    w
+}
+
+fn check(n: usize, edges: &[(usize, usize, i64)]) -> Option<String> {
+    let w = pair_weights(n, edges, true);
+    let mut s = Search { n, w: &w, best_weight: 0, best_card: (0, 0), lightest_card: (0, 0), best_perfect: None };
+    s.go(&mut vec![false; n], 0, 0, 0);
+    let light = pair_weights(n, edges, false);
+    let mut sp = Search { n, w: &light, best_weight: 0, best_card: (0, 0), lightest_card: (0, 0), best_perfect: None };
+    sp.go(&mut vec![false; n], 0, 0, 0);
+
+    match weigh(n, &w, &weighted_blossom::max_weight_matching(n, edges, false)) {
+        Err(e) => return Some(format!("max weight: {}", e)),
+        Ok((_, x)) if x != s.best_weight => {
+            return Some(format!("max weight {} but the search finds {}", x, s.best_weight));
+        }
+        _ => {}
+    }
+    match weigh(n, &w, &gabow_scaling::max_weight_matching(n, edges).0) {
+        Err(e) => return Some(format!("gabow scaling: {}", e)),
+        Ok((_, x)) if x != s.best_weight => {
+            return Some(format!("gabow scaling weight {} but the search finds {}", x, s.best_weight));
+        }
+        _ => {}
+    }
+    match weigh(n, &w, &weighted_blossom::max_weight_matching(n, edges, true)) {
+        Err(e) => return Some(format!("max cardinality: {}", e)),
+        Ok(r) if r != s.best_card => {
+            return Some(format!("max cardinality {:?} but the search finds {:?}", r, s.best_card));
+        }
+        _ => {}
+    }
+    match weigh(n, &light, &weighted_blossom::min_weight_max_cardinality_matching(n, edges)) {
+        Err(e) => return Some(format!("min weight max cardinality: {}", e)),
+        Ok(r) if r != sp.lightest_card => {
+            return Some(format!("min weight max cardinality {:?} but the search finds {:?}", r, sp.lightest_card));
+        }
+        _ => {}
+    }
+    let flipped = weighted_blossom::flipped_weights(edges);
+    for &(name, solved, cardinality) in &[("max weight", edges, false), ("max cardinality", edges, true),
+                                          ("min weight max cardinality", &flipped[..], true)] {
+        let result = weighted_blossom::max_weight_matching_with_duals(n, solved, cardinality);
+        if let Err(e) = weighted_blossom::check_duals(n, solved, &result, cardinality) {
+            return Some(format!("{} duals: {}", name, e));
+        }
+    }
+    let perfect = weighted_blossom::min_weight_perfect_matching(n, edges);
+    match (perfect, sp.best_perfect) {
+        (None, None) => None,
+        (Some(m), Some(b)) => match weigh(n, &light, &m) {
+            Err(e) => Some(format!("min perfect: {}", e)),
+            Ok((_, x)) if x != b => Some(format!("min perfect weight {} but the search finds {}", x, b)),
+            _ => None,
+        },
+        (p, b) => Some(format!("min perfect: solver {:?}, search {:?}", p.map(|m| m.len()), b)),
+    }
+}
+
+fn random_instance(rng: &mut rng::Rng, max_n: usize) -> (usize, Edges) {
+    let n = rng.below(max_n as u64 + 1) as usize;
+    let mut edges = Vec::new();
+    if n < 2 { return (n, edges); }
+    let p = rng.next_f64();
+    let (lo, span) = match rng.below(4) {
+        0 => (1, 3),       /* many ties */
+        1 => (0, 100),
+        2 => (-20, 60),    /* negative weights */
+        _ => (1, 1 << 30), /* wide range */
+    };
+    for u in 0..n {
+        for v in u + 1..n {
+            if rng.next_f64() < p {
+                edges.push((u, v, lo + rng.below(span) as i64));
+                if rng.below(8) == 0 { edges.push((v, u, lo + rng.below(span) as i64)); }
+            }
+        }
+    }
+    rng.shuffle(&mut edges);
+    (n, edges)
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let cases: usize = args::parse_or_exit(&args, "--cases", 2000);
+    let seed: u64 = args::parse_or_exit(&args, "--seed", 1);
+    let max_n: usize = args::parse_or_exit(&args, "--max-n", 11);
+    let dump_dir = args::value_of(&args, "--dump-dir").unwrap_or("weighted_failures").to_string();
+
+    println!("Weighted blossom vs. exhaustive search: {} cases, seed {}, n <= {}", cases, seed, max_n);
+
+    let mut rng = rng::Rng::new(seed);
+    let mut failures = 0;
+    for case in 0..cases {
+        let (n, edges) = random_instance(&mut rng, max_n);
+        if let Some(why) = check(n, &edges) {
+            failures += 1;
+            let name = format!("weighted_seed{}_case{}.txt", seed, case);
+            let saved = dump::saved(dump::weighted_edge_list(&dump_dir, &name, n, None, &edges));
+            println!("  FAIL  case {:<5} n={} m={}: {}", case, n, edges.len(), why);
+            println!("        saved: {}", saved);
+        }
+    }
+
+    if failures == 0 {
+        println!("ALL AGREE ({} cases x 4 objectives + duals + gabow scaling)", cases);
+    } else {
+        println!("{} DISAGREEMENT(S)", failures);
+        std::process::exit(1);
+    }
+}